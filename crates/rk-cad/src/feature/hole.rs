@@ -0,0 +1,313 @@
+//! Hole feature geometry and standard fastener sizes
+
+use glam::{Mat4, Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::kernel::{Axis3D, BooleanType, CadKernel, CadResult, Solid, Wire2D};
+use crate::sketch::SketchPlane;
+
+/// Length the hole tool extends above the face, so the cut does not leave a
+/// coplanar skin on the surface
+const HOLE_CLEARANCE: f32 = 0.01;
+
+/// Number of segments approximating hole circles
+const HOLE_SEGMENTS: u32 = 32;
+
+/// Shape of the entry of a hole
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum HoleKind {
+    /// Plain cylindrical hole
+    #[default]
+    Simple,
+    /// Wider cylindrical recess for a socket head
+    Counterbore { diameter: f32, depth: f32 },
+    /// Conical recess for a flat head
+    Countersink {
+        diameter: f32,
+        /// Included angle of the cone in radians
+        angle: f32,
+    },
+}
+
+impl HoleKind {
+    /// Get the display name of the hole kind
+    pub fn name(&self) -> &'static str {
+        match self {
+            HoleKind::Simple => "Simple",
+            HoleKind::Counterbore { .. } => "Counterbore",
+            HoleKind::Countersink { .. } => "Countersink",
+        }
+    }
+}
+
+/// How far a hole goes into the body
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HoleDepth {
+    /// Stop at a fixed depth below the face
+    Blind(f32),
+    /// Cut through the whole body
+    ThroughAll,
+}
+
+/// Millimeters in meters, for writing fastener dimensions readably
+const MM: f32 = 0.001;
+
+/// Standard clearance hole for a metric fastener, in meters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HoleSize {
+    /// Fastener designation, e.g. "M3"
+    pub name: &'static str,
+    /// Clearance hole diameter (ISO 273, normal fit)
+    pub clearance_diameter: f32,
+    /// Counterbore diameter for a socket head cap screw
+    pub counterbore_diameter: f32,
+    /// Counterbore depth for a socket head cap screw
+    pub counterbore_depth: f32,
+    /// Countersink diameter for a 90 degree flat head screw
+    pub countersink_diameter: f32,
+}
+
+impl HoleSize {
+    /// Counterbore fitting this fastener's socket head
+    pub fn counterbore(&self) -> HoleKind {
+        HoleKind::Counterbore {
+            diameter: self.counterbore_diameter,
+            depth: self.counterbore_depth,
+        }
+    }
+
+    /// Countersink fitting this fastener's flat head
+    pub fn countersink(&self) -> HoleKind {
+        HoleKind::Countersink {
+            diameter: self.countersink_diameter,
+            angle: 90f32.to_radians(),
+        }
+    }
+}
+
+/// Clearance holes for common metric fasteners
+pub const STANDARD_HOLE_SIZES: [HoleSize; 9] = [
+    HoleSize {
+        name: "M2",
+        clearance_diameter: 2.4 * MM,
+        counterbore_diameter: 4.4 * MM,
+        counterbore_depth: 2.3 * MM,
+        countersink_diameter: 4.4 * MM,
+    },
+    HoleSize {
+        name: "M2.5",
+        clearance_diameter: 2.9 * MM,
+        counterbore_diameter: 5.4 * MM,
+        counterbore_depth: 2.8 * MM,
+        countersink_diameter: 5.5 * MM,
+    },
+    HoleSize {
+        name: "M3",
+        clearance_diameter: 3.4 * MM,
+        counterbore_diameter: 6.5 * MM,
+        counterbore_depth: 3.3 * MM,
+        countersink_diameter: 6.9 * MM,
+    },
+    HoleSize {
+        name: "M4",
+        clearance_diameter: 4.5 * MM,
+        counterbore_diameter: 8.0 * MM,
+        counterbore_depth: 4.4 * MM,
+        countersink_diameter: 9.2 * MM,
+    },
+    HoleSize {
+        name: "M5",
+        clearance_diameter: 5.5 * MM,
+        counterbore_diameter: 10.0 * MM,
+        counterbore_depth: 5.4 * MM,
+        countersink_diameter: 11.5 * MM,
+    },
+    HoleSize {
+        name: "M6",
+        clearance_diameter: 6.6 * MM,
+        counterbore_diameter: 11.0 * MM,
+        counterbore_depth: 6.5 * MM,
+        countersink_diameter: 13.7 * MM,
+    },
+    HoleSize {
+        name: "M8",
+        clearance_diameter: 9.0 * MM,
+        counterbore_diameter: 15.0 * MM,
+        counterbore_depth: 8.6 * MM,
+        countersink_diameter: 18.3 * MM,
+    },
+    HoleSize {
+        name: "M10",
+        clearance_diameter: 11.0 * MM,
+        counterbore_diameter: 18.0 * MM,
+        counterbore_depth: 10.8 * MM,
+        countersink_diameter: 22.7 * MM,
+    },
+    HoleSize {
+        name: "M12",
+        clearance_diameter: 13.5 * MM,
+        counterbore_diameter: 20.0 * MM,
+        counterbore_depth: 13.0 * MM,
+        countersink_diameter: 27.2 * MM,
+    },
+];
+
+/// Build the solid removed by a hole
+///
+/// The hole starts at `position` on a face and goes `depth` along
+/// `direction`, which points into the material.
+pub(crate) fn hole_tool(
+    kernel: &dyn CadKernel,
+    position: Vec3,
+    direction: Vec3,
+    diameter: f32,
+    depth: f32,
+    kind: HoleKind,
+) -> CadResult<Solid> {
+    // Built drilling down -Z from the origin, then moved onto the face
+    let cylinder = |radius: f32, depth: f32| {
+        let top = SketchPlane {
+            origin: Vec3::Z * HOLE_CLEARANCE,
+            ..SketchPlane::xy()
+        };
+        kernel.extrude(
+            &Wire2D::circle(Vec2::ZERO, radius, HOLE_SEGMENTS),
+            &top,
+            Vec3::NEG_Z,
+            depth + HOLE_CLEARANCE,
+        )
+    };
+
+    let radius = diameter / 2.0;
+    let mut tool = cylinder(radius, depth)?;
+    match kind {
+        HoleKind::Simple => {}
+        HoleKind::Counterbore { diameter, depth } => {
+            let counterbore = cylinder(diameter / 2.0, depth)?;
+            tool = kernel.boolean(&tool, &counterbore, BooleanType::Union)?;
+        }
+        HoleKind::Countersink { diameter, angle } => {
+            // Half cross-section on the XZ plane, whose sketch Y axis points
+            // down -Z, revolved around the hole axis
+            let outer = diameter / 2.0;
+            let slope = (angle / 2.0).tan();
+            let cone_depth = (outer - radius) / slope;
+            let profile = Wire2D::new(
+                vec![
+                    Vec2::new(0.0, -HOLE_CLEARANCE),
+                    Vec2::new(outer + HOLE_CLEARANCE * slope, -HOLE_CLEARANCE),
+                    Vec2::new(radius, cone_depth),
+                    Vec2::new(0.0, cone_depth),
+                ],
+                true,
+            );
+            let countersink = kernel.revolve(
+                &profile,
+                &SketchPlane::xz(),
+                &Axis3D::z(),
+                std::f32::consts::TAU,
+            )?;
+            tool = kernel.boolean(&tool, &countersink, BooleanType::Union)?;
+        }
+    }
+
+    let rotation = Quat::from_rotation_arc(Vec3::NEG_Z, direction.normalize());
+    kernel.transform(&tool, Mat4::from_rotation_translation(rotation, position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::test_kernel::BoundsKernel;
+
+    fn tool_bounds(kernel: &BoundsKernel, tool: &Solid) -> (Vec3, Vec3) {
+        kernel.tessellate(tool, 0.1).unwrap().bounds().unwrap()
+    }
+
+    #[test]
+    fn test_hole_tool_follows_direction() {
+        let kernel = BoundsKernel::default();
+        let tool = hole_tool(
+            &kernel,
+            Vec3::new(5.0, 5.0, 0.0),
+            Vec3::X,
+            3.4 * MM,
+            10.0 * MM,
+            HoleKind::Simple,
+        )
+        .unwrap();
+
+        let (min, max) = tool_bounds(&kernel, &tool);
+        assert!((min.x + HOLE_CLEARANCE - 5.0).abs() < 1e-4);
+        assert!((max.x - 5.01).abs() < 1e-5);
+        assert!((max.y - min.y - 3.4 * MM).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_counterbore_widens_entry() {
+        let kernel = BoundsKernel::default();
+        let size = STANDARD_HOLE_SIZES
+            .iter()
+            .find(|size| size.name == "M3")
+            .unwrap();
+        let tool = hole_tool(
+            &kernel,
+            Vec3::ZERO,
+            Vec3::NEG_Z,
+            size.clearance_diameter,
+            10.0 * MM,
+            size.counterbore(),
+        )
+        .unwrap();
+
+        let (min, max) = tool_bounds(&kernel, &tool);
+        assert!((max.x - min.x - size.counterbore_diameter).abs() < 1e-6);
+        assert!((min.z + 10.0 * MM).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_countersink_widens_entry() {
+        let kernel = BoundsKernel::default();
+        let size = STANDARD_HOLE_SIZES
+            .iter()
+            .find(|size| size.name == "M4")
+            .unwrap();
+        let kind = size.countersink();
+        let tool = hole_tool(
+            &kernel,
+            Vec3::ZERO,
+            Vec3::NEG_Z,
+            size.clearance_diameter,
+            10.0 * MM,
+            kind,
+        )
+        .unwrap();
+
+        // The cone reaches the countersink diameter at the face and keeps
+        // widening through the clearance above it
+        let (min, max) = tool_bounds(&kernel, &tool);
+        let HoleKind::Countersink { angle, .. } = kind else {
+            unreachable!()
+        };
+        let flare = HOLE_CLEARANCE * (angle / 2.0).tan();
+        assert!((max.x - min.x - size.countersink_diameter - 2.0 * flare).abs() < 1e-5);
+        assert!((max.z - HOLE_CLEARANCE).abs() < 1e-6);
+        assert!((min.z + 10.0 * MM).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_standard_sizes_are_consistent() {
+        for size in STANDARD_HOLE_SIZES {
+            assert!(
+                size.counterbore_diameter > size.clearance_diameter,
+                "{}",
+                size.name
+            );
+            assert!(
+                size.countersink_diameter > size.clearance_diameter,
+                "{}",
+                size.name
+            );
+        }
+    }
+}
@@ -3,13 +3,19 @@
 //! Provides parametric feature operations like extrude, revolve, and boolean
 //! that operate on sketches to create 3D solids.
 
-use glam::Vec3;
+mod hole;
+
+use glam::{Mat4, Vec3};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
 
-use crate::kernel::{Axis3D, BooleanType, CadKernel, Solid, TessellatedMesh};
-use crate::sketch::Sketch;
+pub use hole::{HoleDepth, HoleKind, HoleSize, STANDARD_HOLE_SIZES};
+
+use crate::kernel::{
+    Axis3D, BooleanType, CadError, CadKernel, CadResult, Solid, TessellatedMesh, Wire2D,
+};
+use crate::sketch::{Sketch, SketchPlane};
 
 /// Feature-related errors
 #[derive(Debug, Clone, Error)]
@@ -28,11 +34,20 @@ pub enum FeatureError {
 
     #[error("Rebuild failed: {0}")]
     RebuildFailed(String),
+
+    #[error("Invalid feature order: {0}")]
+    InvalidOrder(String),
 }
 
 /// Result type for feature operations
 pub type FeatureResult<T> = Result<T, FeatureError>;
 
+/// Tessellation tolerance used when measuring bodies for shell and hole checks
+const BODY_CHECK_TOLERANCE: f32 = 0.01;
+
+/// Largest extrude draft angle either way, in radians
+const MAX_DRAFT_ANGLE: f32 = std::f32::consts::FRAC_PI_4;
+
 /// Direction for extrusion
 #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 pub enum ExtrudeDirection {
@@ -45,6 +60,60 @@ pub enum ExtrudeDirection {
     Symmetric,
 }
 
+impl ExtrudeDirection {
+    /// Extrude a profile on a sketch plane by `distance` in this direction
+    ///
+    /// A symmetric extrude is one solid starting `distance / 2` behind the
+    /// plane, the same shape as the union of two half extrudes without a
+    /// boolean on the coplanar faces where they would meet.
+    ///
+    /// A non-zero `draft_angle` (radians) tilts the side walls inward going
+    /// away from the sketch plane, or outward if negative. Drafted symmetric
+    /// extrudes taper both halves away from the plane, so they are unioned.
+    pub fn extrude(
+        self,
+        kernel: &dyn CadKernel,
+        profile: &Wire2D,
+        plane: &SketchPlane,
+        distance: f32,
+        draft_angle: f32,
+    ) -> CadResult<Solid> {
+        if draft_angle != 0.0 {
+            let taper = |direction: Vec3, distance: f32| {
+                let top = profile
+                    .offset(distance * draft_angle.tan())
+                    .ok_or_else(|| {
+                        CadError::InvalidProfile(
+                            "Draft closes the profile before the end of the extrude".into(),
+                        )
+                    })?;
+                kernel.extrude_tapered(profile, &top, plane, direction, distance)
+            };
+            return match self {
+                ExtrudeDirection::Positive => taper(plane.normal, distance),
+                ExtrudeDirection::Negative => taper(-plane.normal, distance),
+                ExtrudeDirection::Symmetric => {
+                    let front = taper(plane.normal, distance / 2.0)?;
+                    let back = taper(-plane.normal, distance / 2.0)?;
+                    kernel.boolean(&front, &back, BooleanType::Union)
+                }
+            };
+        }
+
+        match self {
+            ExtrudeDirection::Positive => kernel.extrude(profile, plane, plane.normal, distance),
+            ExtrudeDirection::Negative => kernel.extrude(profile, plane, -plane.normal, distance),
+            ExtrudeDirection::Symmetric => {
+                let start = SketchPlane {
+                    origin: plane.origin - plane.normal * (distance / 2.0),
+                    ..*plane
+                };
+                kernel.extrude(profile, &start, plane.normal, distance)
+            }
+        }
+    }
+}
+
 /// Boolean operation type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum BooleanOp {
@@ -70,6 +139,43 @@ impl From<BooleanOp> for Option<BooleanType> {
     }
 }
 
+/// Combine a newly created solid with its target body
+///
+/// A missing target is an error for join, cut and intersect rather than
+/// silently producing the tool solid as a new body.
+fn combine_with_target(
+    kernel: &dyn CadKernel,
+    solid: Solid,
+    boolean_op: BooleanOp,
+    target_body: Option<Uuid>,
+    existing_bodies: &std::collections::HashMap<Uuid, Solid>,
+) -> FeatureResult<Solid> {
+    let Some(op) = Option::<BooleanType>::from(boolean_op) else {
+        return Ok(solid);
+    };
+
+    let target = target_body
+        .and_then(|id| existing_bodies.get(&id))
+        .ok_or_else(|| {
+            FeatureError::InvalidFeature(format!("{:?} requires a target body", boolean_op))
+        })?;
+
+    kernel.boolean(target, &solid, op).map_err(|e| e.into())
+}
+
+/// Build the matrix reflecting points across the plane through `origin`
+/// with the given normal
+fn reflection_matrix(origin: Vec3, normal: Vec3) -> Mat4 {
+    let n = normal.normalize();
+    let reflect = Mat4::from_cols(
+        (Vec3::X - 2.0 * n.x * n).extend(0.0),
+        (Vec3::Y - 2.0 * n.y * n).extend(0.0),
+        (Vec3::Z - 2.0 * n.z * n).extend(0.0),
+        glam::Vec4::W,
+    );
+    Mat4::from_translation(origin) * reflect * Mat4::from_translation(-origin)
+}
+
 /// A parametric feature that modifies geometry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Feature {
@@ -81,6 +187,11 @@ pub enum Feature {
         name: String,
         /// Reference to the sketch
         sketch_id: Uuid,
+        /// Indices of the sketch profiles to extrude, unioned into one
+        /// solid; `None` in files saved before profile selection, which
+        /// extrude only the first profile
+        #[serde(default)]
+        profiles: Option<Vec<usize>>,
         /// Extrusion distance
         distance: f32,
         /// Extrusion direction
@@ -169,6 +280,65 @@ pub enum Feature {
         #[serde(default)]
         suppressed: bool,
     },
+
+    /// Hollow a body to a wall thickness
+    Shell {
+        /// Unique identifier
+        id: Uuid,
+        /// Name of the feature
+        name: String,
+        /// Body to hollow
+        body_id: Uuid,
+        /// Wall thickness (positive = inward, negative = outward)
+        thickness: f32,
+        /// Face IDs to remove, leaving the shell open
+        removed_faces: Vec<Uuid>,
+        /// Whether the feature is suppressed
+        #[serde(default)]
+        suppressed: bool,
+    },
+
+    /// Drill a hole into a body at a point on one of its faces
+    Hole {
+        /// Unique identifier
+        id: Uuid,
+        /// Name of the feature
+        name: String,
+        /// Body to drill
+        body_id: Uuid,
+        /// Point on the face where the hole starts
+        position: Vec3,
+        /// Direction the hole goes into the material
+        direction: Vec3,
+        /// Hole diameter
+        diameter: f32,
+        /// How deep the hole goes
+        depth: HoleDepth,
+        /// Shape of the hole entry
+        kind: HoleKind,
+        /// Whether the feature is suppressed
+        #[serde(default)]
+        suppressed: bool,
+    },
+
+    /// Reflect a body across a plane
+    Mirror {
+        /// Unique identifier
+        id: Uuid,
+        /// Name of the feature
+        name: String,
+        /// Body to mirror
+        source_body: Uuid,
+        /// A point on the mirror plane
+        plane_origin: Vec3,
+        /// Normal of the mirror plane
+        plane_normal: Vec3,
+        /// How the mirrored copy combines with the source body
+        boolean_op: BooleanOp,
+        /// Whether the feature is suppressed
+        #[serde(default)]
+        suppressed: bool,
+    },
 }
 
 impl Feature {
@@ -180,6 +350,24 @@ impl Feature {
             Feature::Boolean { id, .. } => *id,
             Feature::Fillet { id, .. } => *id,
             Feature::Chamfer { id, .. } => *id,
+            Feature::Shell { id, .. } => *id,
+            Feature::Hole { id, .. } => *id,
+            Feature::Mirror { id, .. } => *id,
+        }
+    }
+
+    /// Give this feature another ID, so it can take the place of an
+    /// existing feature
+    pub fn set_id(&mut self, new_id: Uuid) {
+        match self {
+            Feature::Extrude { id, .. }
+            | Feature::Revolve { id, .. }
+            | Feature::Boolean { id, .. }
+            | Feature::Fillet { id, .. }
+            | Feature::Chamfer { id, .. }
+            | Feature::Shell { id, .. }
+            | Feature::Hole { id, .. }
+            | Feature::Mirror { id, .. } => *id = new_id,
         }
     }
 
@@ -191,6 +379,24 @@ impl Feature {
             Feature::Boolean { name, .. } => name,
             Feature::Fillet { name, .. } => name,
             Feature::Chamfer { name, .. } => name,
+            Feature::Shell { name, .. } => name,
+            Feature::Hole { name, .. } => name,
+            Feature::Mirror { name, .. } => name,
+        }
+    }
+
+    /// Rename this feature
+    pub fn set_name(&mut self, new_name: impl Into<String>) {
+        let new_name = new_name.into();
+        match self {
+            Feature::Extrude { name, .. }
+            | Feature::Revolve { name, .. }
+            | Feature::Boolean { name, .. }
+            | Feature::Fillet { name, .. }
+            | Feature::Chamfer { name, .. }
+            | Feature::Shell { name, .. }
+            | Feature::Hole { name, .. }
+            | Feature::Mirror { name, .. } => *name = new_name,
         }
     }
 
@@ -202,6 +408,9 @@ impl Feature {
             Feature::Boolean { .. } => "Boolean",
             Feature::Fillet { .. } => "Fillet",
             Feature::Chamfer { .. } => "Chamfer",
+            Feature::Shell { .. } => "Shell",
+            Feature::Hole { .. } => "Hole",
+            Feature::Mirror { .. } => "Mirror",
         }
     }
 
@@ -213,6 +422,9 @@ impl Feature {
             Feature::Boolean { suppressed, .. } => *suppressed,
             Feature::Fillet { suppressed, .. } => *suppressed,
             Feature::Chamfer { suppressed, .. } => *suppressed,
+            Feature::Shell { suppressed, .. } => *suppressed,
+            Feature::Hole { suppressed, .. } => *suppressed,
+            Feature::Mirror { suppressed, .. } => *suppressed,
         }
     }
 
@@ -224,6 +436,81 @@ impl Feature {
             Feature::Boolean { suppressed, .. } => *suppressed = value,
             Feature::Fillet { suppressed, .. } => *suppressed = value,
             Feature::Chamfer { suppressed, .. } => *suppressed = value,
+            Feature::Shell { suppressed, .. } => *suppressed = value,
+            Feature::Hole { suppressed, .. } => *suppressed = value,
+            Feature::Mirror { suppressed, .. } => *suppressed = value,
+        }
+    }
+
+    /// Get the sketch this feature builds its profile from, if any
+    pub fn sketch_id(&self) -> Option<Uuid> {
+        match self {
+            Feature::Extrude { sketch_id, .. } | Feature::Revolve { sketch_id, .. } => {
+                Some(*sketch_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the IDs of bodies this feature consumes
+    pub fn referenced_bodies(&self) -> Vec<Uuid> {
+        match self {
+            Feature::Extrude { target_body, .. } | Feature::Revolve { target_body, .. } => {
+                target_body.iter().copied().collect()
+            }
+            Feature::Boolean {
+                target_body,
+                tool_body,
+                ..
+            } => vec![*target_body, *tool_body],
+            Feature::Fillet { body_id, .. }
+            | Feature::Chamfer { body_id, .. }
+            | Feature::Shell { body_id, .. }
+            | Feature::Hole { body_id, .. } => vec![*body_id],
+            Feature::Mirror { source_body, .. } => vec![*source_body],
+        }
+    }
+
+    /// Get the body this feature modifies in place, if any
+    ///
+    /// Join, cut and intersect results replace their target body, as do
+    /// operations on an existing body such as fillet or shell.
+    pub fn modified_body(&self) -> Option<Uuid> {
+        match self {
+            Feature::Extrude {
+                boolean_op,
+                target_body,
+                ..
+            }
+            | Feature::Revolve {
+                boolean_op,
+                target_body,
+                ..
+            } => match boolean_op {
+                BooleanOp::New => None,
+                _ => *target_body,
+            },
+            Feature::Boolean { target_body, .. } => Some(*target_body),
+            Feature::Fillet { body_id, .. }
+            | Feature::Chamfer { body_id, .. }
+            | Feature::Shell { body_id, .. }
+            | Feature::Hole { body_id, .. } => Some(*body_id),
+            Feature::Mirror {
+                source_body,
+                boolean_op,
+                ..
+            } => match boolean_op {
+                BooleanOp::New => None,
+                _ => Some(*source_body),
+            },
+        }
+    }
+
+    /// Get bodies that no longer exist once this feature has run
+    pub fn consumed_bodies(&self) -> Vec<Uuid> {
+        match self {
+            Feature::Boolean { tool_body, .. } => vec![*tool_body],
+            _ => Vec::new(),
         }
     }
 
@@ -238,6 +525,7 @@ impl Feature {
             id: Uuid::new_v4(),
             name: name.into(),
             sketch_id,
+            profiles: Some(vec![0]),
             distance,
             direction,
             boolean_op: BooleanOp::New,
@@ -262,6 +550,63 @@ impl Feature {
         }
     }
 
+    /// Create a new shell feature
+    pub fn shell(
+        name: impl Into<String>,
+        body_id: Uuid,
+        thickness: f32,
+        removed_faces: Vec<Uuid>,
+    ) -> Self {
+        Feature::Shell {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            body_id,
+            thickness,
+            removed_faces,
+            suppressed: false,
+        }
+    }
+
+    /// Create a new simple hole feature
+    pub fn hole(
+        name: impl Into<String>,
+        body_id: Uuid,
+        position: Vec3,
+        direction: Vec3,
+        diameter: f32,
+        depth: HoleDepth,
+    ) -> Self {
+        Feature::Hole {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            body_id,
+            position,
+            direction,
+            diameter,
+            depth,
+            kind: HoleKind::Simple,
+            suppressed: false,
+        }
+    }
+
+    /// Create a new mirror feature that joins the reflection to its source
+    pub fn mirror(
+        name: impl Into<String>,
+        source_body: Uuid,
+        plane_origin: Vec3,
+        plane_normal: Vec3,
+    ) -> Self {
+        Feature::Mirror {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            source_body,
+            plane_origin,
+            plane_normal,
+            boolean_op: BooleanOp::Join,
+            suppressed: false,
+        }
+    }
+
     /// Execute this feature to produce a solid
     pub fn execute(
         &self,
@@ -276,12 +621,21 @@ impl Feature {
         match self {
             Feature::Extrude {
                 sketch_id,
+                profiles: selected,
                 distance,
                 direction,
                 boolean_op,
                 target_body,
+                draft_angle,
                 ..
             } => {
+                if draft_angle.is_nan() || draft_angle.abs() >= MAX_DRAFT_ANGLE {
+                    return Err(FeatureError::InvalidFeature(format!(
+                        "Draft angle must be less than {}° either way",
+                        MAX_DRAFT_ANGLE.to_degrees()
+                    )));
+                }
+
                 let sketch =
                     sketches
                         .get(sketch_id)
@@ -299,44 +653,37 @@ impl Feature {
                     ));
                 }
 
-                // Calculate extrusion direction and distance
-                let (extrude_dir, extrude_dist) = match direction {
-                    ExtrudeDirection::Positive => (sketch.plane.normal, *distance),
-                    ExtrudeDirection::Negative => (-sketch.plane.normal, *distance),
-                    ExtrudeDirection::Symmetric => (sketch.plane.normal, *distance / 2.0),
-                };
-
-                // Extrude the first profile (for now)
-                let profile = &profiles[0];
-                let mut solid = kernel.extrude(
-                    profile,
-                    sketch.plane.origin,
-                    sketch.plane.normal,
-                    extrude_dir,
-                    extrude_dist,
-                )?;
-
-                // For symmetric, extrude in the other direction and union
-                if matches!(direction, ExtrudeDirection::Symmetric) {
-                    let solid2 = kernel.extrude(
+                // Extrude every selected profile into one solid; the dialog
+                // preview runs this same code, so it matches the committed body
+                let indices = selected.as_deref().unwrap_or(&[0]);
+                let mut solid: Option<Solid> = None;
+                for &index in indices {
+                    let profile = profiles.get(index).ok_or_else(|| {
+                        FeatureError::InvalidFeature(format!(
+                            "Profile {} not found in sketch",
+                            index
+                        ))
+                    })?;
+                    let extruded = direction.extrude(
+                        kernel,
                         profile,
-                        sketch.plane.origin,
-                        sketch.plane.normal,
-                        -extrude_dir,
-                        extrude_dist,
+                        &sketch.plane,
+                        *distance,
+                        *draft_angle,
                     )?;
-                    solid = kernel.boolean(&solid, &solid2, BooleanType::Union)?;
+                    solid = Some(match solid {
+                        Some(previous) => {
+                            kernel.boolean(&previous, &extruded, BooleanType::Union)?
+                        }
+                        None => extruded,
+                    });
                 }
+                let Some(solid) = solid else {
+                    return Err(FeatureError::InvalidFeature("No profiles selected".into()));
+                };
 
                 // Apply boolean operation with target body
-                if let (Some(op), Some(target_id)) =
-                    (Option::<BooleanType>::from(*boolean_op), target_body)
-                    && let Some(target) = existing_bodies.get(target_id)
-                {
-                    solid = kernel.boolean(target, &solid, op)?;
-                }
-
-                Ok(solid)
+                combine_with_target(kernel, solid, *boolean_op, *target_body, existing_bodies)
             }
 
             Feature::Revolve {
@@ -367,23 +714,10 @@ impl Feature {
                 let axis = Axis3D::new(*axis_origin, *axis_direction);
                 let profile = &profiles[0];
 
-                let mut solid = kernel.revolve(
-                    profile,
-                    sketch.plane.origin,
-                    sketch.plane.normal,
-                    &axis,
-                    *angle,
-                )?;
+                let solid = kernel.revolve(profile, &sketch.plane, &axis, *angle)?;
 
                 // Apply boolean operation
-                if let (Some(op), Some(target_id)) =
-                    (Option::<BooleanType>::from(*boolean_op), target_body)
-                    && let Some(target) = existing_bodies.get(target_id)
-                {
-                    solid = kernel.boolean(target, &solid, op)?;
-                }
-
-                Ok(solid)
+                combine_with_target(kernel, solid, *boolean_op, *target_body, existing_bodies)
             }
 
             Feature::Boolean {
@@ -407,6 +741,145 @@ impl Feature {
                 kernel.boolean(target, tool, op).map_err(|e| e.into())
             }
 
+            Feature::Shell {
+                body_id,
+                thickness,
+                removed_faces,
+                ..
+            } => {
+                let body = existing_bodies
+                    .get(body_id)
+                    .ok_or(FeatureError::InvalidFeature(
+                        "Body to shell not found".into(),
+                    ))?;
+
+                if *thickness == 0.0 {
+                    return Err(FeatureError::InvalidFeature(
+                        "Shell thickness must be non-zero".into(),
+                    ));
+                }
+
+                // Shelling inward needs room for walls on both sides
+                if *thickness > 0.0 {
+                    let mesh = kernel.tessellate(body, BODY_CHECK_TOLERANCE)?;
+                    if let Some((min, max)) = mesh.bounds() {
+                        let max_thickness = (max - min).min_element() / 2.0;
+                        if *thickness >= max_thickness {
+                            return Err(FeatureError::InvalidFeature(format!(
+                                "Shell thickness {} must be less than {} for this body",
+                                thickness, max_thickness
+                            )));
+                        }
+                    }
+                }
+
+                kernel
+                    .shell(body, *thickness, removed_faces)
+                    .map_err(|e| e.into())
+            }
+
+            Feature::Mirror {
+                source_body,
+                plane_origin,
+                plane_normal,
+                boolean_op,
+                ..
+            } => {
+                let source =
+                    existing_bodies
+                        .get(source_body)
+                        .ok_or(FeatureError::InvalidFeature(
+                            "Body to mirror not found".into(),
+                        ))?;
+
+                if plane_normal.length_squared() < f32::EPSILON {
+                    return Err(FeatureError::InvalidFeature(
+                        "Mirror plane normal must be non-zero".into(),
+                    ));
+                }
+
+                let reflection = reflection_matrix(*plane_origin, *plane_normal);
+                let mirrored = kernel.transform(source, reflection)?;
+
+                match Option::<BooleanType>::from(*boolean_op) {
+                    Some(op) => kernel.boolean(source, &mirrored, op).map_err(|e| e.into()),
+                    None => Ok(mirrored),
+                }
+            }
+
+            Feature::Hole {
+                body_id,
+                position,
+                direction,
+                diameter,
+                depth,
+                kind,
+                ..
+            } => {
+                let body = existing_bodies
+                    .get(body_id)
+                    .ok_or(FeatureError::InvalidFeature(
+                        "Body to drill not found".into(),
+                    ))?;
+
+                if direction.length_squared() < f32::EPSILON {
+                    return Err(FeatureError::InvalidFeature(
+                        "Hole direction must be non-zero".into(),
+                    ));
+                }
+                if *diameter <= 0.0 {
+                    return Err(FeatureError::InvalidFeature(
+                        "Hole diameter must be positive".into(),
+                    ));
+                }
+                match kind {
+                    HoleKind::Simple => {}
+                    HoleKind::Counterbore {
+                        diameter: outer,
+                        depth,
+                    } => {
+                        if *outer <= *diameter || *depth <= 0.0 {
+                            return Err(FeatureError::InvalidFeature(
+                                "Counterbore must be wider than the hole and have a depth".into(),
+                            ));
+                        }
+                    }
+                    HoleKind::Countersink {
+                        diameter: outer,
+                        angle,
+                    } => {
+                        if *outer <= *diameter || *angle <= 0.0 || *angle >= std::f32::consts::PI {
+                            return Err(FeatureError::InvalidFeature(
+                                "Countersink must be wider than the hole with an angle below 180°"
+                                    .into(),
+                            ));
+                        }
+                    }
+                }
+
+                let depth = match depth {
+                    HoleDepth::Blind(depth) if *depth > 0.0 => *depth,
+                    HoleDepth::Blind(_) => {
+                        return Err(FeatureError::InvalidFeature(
+                            "Hole depth must be positive".into(),
+                        ));
+                    }
+                    // Deep enough to reach the far corner of the body
+                    HoleDepth::ThroughAll => {
+                        let mesh = kernel.tessellate(body, BODY_CHECK_TOLERANCE)?;
+                        let (min, max) = mesh.bounds().ok_or(FeatureError::InvalidFeature(
+                            "Body to drill is empty".into(),
+                        ))?;
+                        position.distance((min + max) / 2.0) + (max - min).length() / 2.0
+                    }
+                };
+
+                let tool = hole::hole_tool(kernel, *position, *direction, *diameter, depth, *kind)?;
+                kernel
+                    .boolean(body, &tool, BooleanType::Subtract)
+                    .map_err(|e| e.into())
+            }
+
             Feature::Fillet { .. } | Feature::Chamfer { .. } => Err(FeatureError::InvalidFeature(
                 "Fillet/Chamfer not yet implemented".into(),
             )),
@@ -427,10 +900,34 @@ pub struct CadBody {
     /// Cached tessellation
     #[serde(skip)]
     pub mesh_cache: Option<TessellatedMesh>,
+    /// Tolerance the cached tessellation was generated with
+    #[serde(skip)]
+    mesh_tolerance: Option<f32>,
+    /// Hash of the features and sketches the solid was built from
+    #[serde(skip)]
+    pub(crate) fingerprint: u64,
+    /// Tessellation tolerance overriding the global setting
+    #[serde(default)]
+    pub tessellation_tolerance: Option<f32>,
+    /// Display color (RGBA) chosen by the user, or `None` for a palette color
+    #[serde(default)]
+    pub color: Option<[f32; 4]>,
     /// Feature that created this body
     pub source_feature: Option<Uuid>,
 }
 
+/// Palette bodies are colored from when the user has not picked a color
+const BODY_PALETTE: [[f32; 4]; 8] = [
+    [0.55, 0.65, 0.80, 1.0],
+    [0.85, 0.60, 0.45, 1.0],
+    [0.55, 0.75, 0.55, 1.0],
+    [0.80, 0.55, 0.70, 1.0],
+    [0.85, 0.78, 0.45, 1.0],
+    [0.50, 0.75, 0.78, 1.0],
+    [0.70, 0.62, 0.85, 1.0],
+    [0.78, 0.50, 0.50, 1.0],
+];
+
 impl Default for CadBody {
     fn default() -> Self {
         Self {
@@ -438,6 +935,10 @@ impl Default for CadBody {
             name: String::from("Body"),
             solid: None,
             mesh_cache: None,
+            mesh_tolerance: None,
+            fingerprint: 0,
+            tessellation_tolerance: None,
+            color: None,
             source_feature: None,
         }
     }
@@ -451,17 +952,71 @@ impl CadBody {
             name: name.into(),
             solid: None,
             mesh_cache: None,
+            mesh_tolerance: None,
+            fingerprint: 0,
+            tessellation_tolerance: None,
+            color: None,
             source_feature: None,
         }
     }
 
+    /// Get the color this body is displayed with
+    ///
+    /// Bodies without a user color get a palette color derived from their ID,
+    /// so the color stays the same across rebuilds and sessions.
+    pub fn display_color(&self) -> [f32; 4] {
+        self.color.unwrap_or_else(|| {
+            let hash = self.id.as_bytes().iter().fold(0usize, |acc, &b| {
+                acc.wrapping_mul(31).wrapping_add(b as usize)
+            });
+            BODY_PALETTE[hash % BODY_PALETTE.len()]
+        })
+    }
+
+    /// Get the tolerance this body tessellates with, given the global default
+    pub fn tolerance(&self, default_tolerance: f32) -> f32 {
+        self.tessellation_tolerance.unwrap_or(default_tolerance)
+    }
+
+    /// Set the per-body tolerance override, invalidating the cached mesh
+    pub fn set_tessellation_tolerance(&mut self, tolerance: Option<f32>) {
+        if self.tessellation_tolerance != tolerance {
+            self.tessellation_tolerance = tolerance;
+            self.invalidate_cache();
+        }
+    }
+
+    /// Whether the cached mesh is up to date for `default_tolerance`
+    pub fn has_current_mesh(&self, default_tolerance: f32) -> bool {
+        self.mesh_cache.is_some() && self.mesh_tolerance == Some(self.tolerance(default_tolerance))
+    }
+
+    /// Take over the cached mesh of an earlier body with the same geometry
+    pub(crate) fn reuse_mesh(&mut self, previous: CadBody) {
+        self.mesh_cache = previous.mesh_cache;
+        self.mesh_tolerance = previous.mesh_tolerance;
+    }
+
     /// Get the tessellated mesh, tessellating if needed
-    pub fn get_mesh(&mut self, kernel: &dyn CadKernel, tolerance: f32) -> Option<&TessellatedMesh> {
+    ///
+    /// The body's own tolerance takes precedence over `default_tolerance`.
+    /// The cached mesh is regenerated when the effective tolerance changes.
+    pub fn get_mesh(
+        &mut self,
+        kernel: &dyn CadKernel,
+        default_tolerance: f32,
+    ) -> Option<&TessellatedMesh> {
+        let tolerance = self.tolerance(default_tolerance);
+        if self.mesh_tolerance != Some(tolerance) {
+            self.invalidate_cache();
+        }
+
         if self.mesh_cache.is_none()
             && let Some(ref solid) = self.solid
             && let Ok(mesh) = kernel.tessellate(solid, tolerance)
         {
             self.mesh_cache = Some(mesh);
+            self.mesh_tolerance = Some(tolerance);
         }
         self.mesh_cache.as_ref()
     }
@@ -469,12 +1024,16 @@ impl CadBody {
     /// Invalidate the mesh cache
     pub fn invalidate_cache(&mut self) {
         self.mesh_cache = None;
+        self.mesh_tolerance = None;
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
+    use crate::kernel::test_kernel::{BoundsKernel, BoxKernel};
 
     #[test]
     fn test_feature_id() {
@@ -492,4 +1051,270 @@ mod tests {
         feature.set_suppressed(true);
         assert!(feature.is_suppressed());
     }
+
+    fn shell_with_thickness(thickness: f32) -> FeatureResult<Solid> {
+        let body_id = Uuid::new_v4();
+        let bodies = HashMap::from([(body_id, Solid::new(body_id))]);
+        Feature::shell("Shell", body_id, thickness, Vec::new()).execute(
+            &BoxKernel,
+            &HashMap::new(),
+            &bodies,
+        )
+    }
+
+    #[test]
+    fn test_shell_thickness_validation() {
+        // The thinnest dimension is 2, so inward walls must be thinner than 1
+        assert!(shell_with_thickness(0.5).is_ok());
+        assert!(matches!(
+            shell_with_thickness(1.0),
+            Err(FeatureError::InvalidFeature(_))
+        ));
+        assert!(matches!(
+            shell_with_thickness(0.0),
+            Err(FeatureError::InvalidFeature(_))
+        ));
+        // Outward shells are not limited by the body size
+        assert!(shell_with_thickness(-5.0).is_ok());
+    }
+
+    #[test]
+    fn test_hole_validation() {
+        let body = Solid::new(Uuid::new_v4());
+        let bodies = HashMap::from([(body.id, body.clone())]);
+        let drill = |diameter: f32, depth: HoleDepth, kind: HoleKind| {
+            let mut feature =
+                Feature::hole("Hole", body.id, Vec3::ZERO, Vec3::NEG_Z, diameter, depth);
+            if let Feature::Hole { kind: k, .. } = &mut feature {
+                *k = kind;
+            }
+            feature.execute(&BoxKernel, &HashMap::new(), &bodies)
+        };
+        let m3 = STANDARD_HOLE_SIZES[2];
+
+        let d = m3.clearance_diameter;
+
+        assert!(drill(d, HoleDepth::Blind(0.005), HoleKind::Simple).is_ok());
+        assert!(drill(d, HoleDepth::ThroughAll, m3.counterbore()).is_ok());
+        assert!(drill(d, HoleDepth::Blind(0.0), HoleKind::Simple).is_err());
+        assert!(drill(0.0, HoleDepth::ThroughAll, HoleKind::Simple).is_err());
+        // The recess must be wider than the hole itself
+        assert!(drill(0.008, HoleDepth::ThroughAll, m3.counterbore()).is_err());
+        assert!(drill(0.008, HoleDepth::ThroughAll, m3.countersink()).is_err());
+    }
+
+    #[test]
+    fn test_symmetric_extrude_matches_two_halves() {
+        let kernel = BoundsKernel::default();
+        let mut sketch = Sketch::default();
+        sketch.add_rectangle(glam::Vec2::ZERO, glam::Vec2::new(2.0, 3.0));
+        let sketches = HashMap::from([(sketch.id, sketch.clone())]);
+        let profile = &sketch.extract_profiles().unwrap()[0];
+        let volume = |solid: &Solid| {
+            let mesh = kernel.tessellate(solid, 0.1).unwrap();
+            let (min, max) = mesh.bounds().unwrap();
+            (max - min).x * (max - min).y * (max - min).z
+        };
+
+        let feature = Feature::extrude("Sym", sketch.id, 4.0, ExtrudeDirection::Symmetric);
+        let symmetric = feature
+            .execute(&kernel, &sketches, &HashMap::new())
+            .unwrap();
+        let halves = kernel
+            .boolean(
+                &ExtrudeDirection::Positive
+                    .extrude(&kernel, profile, &sketch.plane, 2.0, 0.0)
+                    .unwrap(),
+                &ExtrudeDirection::Negative
+                    .extrude(&kernel, profile, &sketch.plane, 2.0, 0.0)
+                    .unwrap(),
+                BooleanType::Union,
+            )
+            .unwrap();
+
+        assert!((volume(&symmetric) - 24.0).abs() < 1e-5);
+        assert!((volume(&symmetric) - volume(&halves)).abs() < 1e-5);
+        let mesh = kernel.tessellate(&symmetric, 0.1).unwrap();
+        let (min, max) = mesh.bounds().unwrap();
+        assert_eq!((min.z, max.z), (-2.0, 2.0));
+    }
+
+    #[test]
+    fn test_drafted_extrude_tapers() {
+        let kernel = BoundsKernel::default();
+        let mut sketch = Sketch::default();
+        sketch.add_rectangle(glam::Vec2::ZERO, glam::Vec2::new(4.0, 4.0));
+        let sketches = HashMap::from([(sketch.id, sketch.clone())]);
+        let drafted = |angle: f32| {
+            let mut feature = Feature::extrude("Draft", sketch.id, 1.0, ExtrudeDirection::Positive);
+            if let Feature::Extrude { draft_angle, .. } = &mut feature {
+                *draft_angle = angle.to_radians();
+            }
+            feature
+                .execute(&kernel, &sketches, &HashMap::new())
+                .map(|solid| kernel.tessellate(&solid, 0.1).unwrap().bounds().unwrap())
+        };
+
+        // The top shrinks by tan(angle) per unit of height on every side
+        let profile = &sketch.extract_profiles().unwrap()[0];
+        let top = profile.offset(45f32.to_radians().tan() * 0.5).unwrap();
+        assert!((top.signed_area().abs() - 9.0).abs() < 1e-4);
+        assert!(top.signed_area().abs() < profile.signed_area().abs());
+
+        // A positive draft stays within the base footprint
+        let (min, max) = drafted(10.0).unwrap();
+        assert!((min - Vec3::ZERO).length() < 1e-5);
+        assert!((max - Vec3::new(4.0, 4.0, 1.0)).length() < 1e-5);
+
+        // A negative draft flares out past it
+        let (min, max) = drafted(-10.0).unwrap();
+        let flare = 10f32.to_radians().tan();
+        assert!((min.x + flare).abs() < 1e-5);
+        assert!((max.y - 4.0 - flare).abs() < 1e-5);
+
+        assert!(drafted(60.0).is_err());
+        assert!(drafted(f32::NAN).is_err());
+    }
+
+    #[test]
+    fn test_extrude_unions_selected_profiles() {
+        let kernel = BoundsKernel::default();
+        let mut sketch = Sketch::default();
+        sketch.add_rectangle(glam::Vec2::ZERO, glam::Vec2::new(1.0, 1.0));
+        sketch.add_rectangle(glam::Vec2::new(4.0, 0.0), glam::Vec2::new(5.0, 2.0));
+        let sketches = HashMap::from([(sketch.id, sketch.clone())]);
+        let bounds = |feature: &Feature| {
+            let solid = feature
+                .execute(&kernel, &sketches, &HashMap::new())
+                .unwrap();
+            kernel.tessellate(&solid, 0.1).unwrap().bounds().unwrap()
+        };
+
+        let mut feature = Feature::extrude("Both", sketch.id, 1.0, ExtrudeDirection::Positive);
+        if let Feature::Extrude { profiles, .. } = &mut feature {
+            *profiles = Some(vec![0, 1]);
+        }
+        let (min, max) = bounds(&feature);
+        assert_eq!(min, Vec3::ZERO);
+        assert_eq!(max, Vec3::new(5.0, 2.0, 1.0));
+
+        // Files without a selection extrude only the first profile
+        if let Feature::Extrude { profiles, .. } = &mut feature {
+            *profiles = None;
+        }
+        let (min, max) = bounds(&feature);
+        assert!((max - min).x <= 1.0);
+
+        // An explicitly empty selection or a missing profile is an error
+        for selection in [vec![], vec![2]] {
+            if let Feature::Extrude { profiles, .. } = &mut feature {
+                *profiles = Some(selection);
+            }
+            assert!(
+                feature
+                    .execute(&kernel, &sketches, &HashMap::new())
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_cut_requires_target_body() {
+        let mut sketch = Sketch::default();
+        sketch.add_rectangle(glam::Vec2::ZERO, glam::Vec2::new(1.0, 1.0));
+        let sketches = HashMap::from([(sketch.id, sketch.clone())]);
+
+        let cut = Feature::Extrude {
+            id: Uuid::new_v4(),
+            name: "Hole".into(),
+            sketch_id: sketch.id,
+            profiles: None,
+            distance: 5.0,
+            direction: ExtrudeDirection::Positive,
+            boolean_op: BooleanOp::Cut,
+            target_body: None,
+            draft_angle: 0.0,
+            suppressed: false,
+        };
+
+        let result = cut.execute(&BoxKernel, &sketches, &HashMap::new());
+        assert!(matches!(result, Err(FeatureError::InvalidFeature(_))));
+    }
+
+    #[test]
+    fn test_cut_removes_material_from_target() {
+        let kernel = BoundsKernel::default();
+        let mut sketch = Sketch::default();
+        sketch.add_rectangle(glam::Vec2::new(-1.0, -1.0), glam::Vec2::new(3.0, 3.0));
+        let sketches = HashMap::from([(sketch.id, sketch.clone())]);
+
+        let target_id = Uuid::new_v4();
+        let target = kernel.create_box(Vec3::ONE, Vec3::splat(2.0)).unwrap();
+        let bodies = HashMap::from([(target_id, target.clone())]);
+        let volume = |solid: &Solid| {
+            let (min, max) = kernel.tessellate(solid, 0.1).unwrap().bounds().unwrap();
+            let size = max - min;
+            size.x * size.y * size.z
+        };
+
+        let cut = Feature::Extrude {
+            id: Uuid::new_v4(),
+            name: "Pocket".into(),
+            sketch_id: sketch.id,
+            profiles: Some(vec![0]),
+            distance: 1.0,
+            direction: ExtrudeDirection::Positive,
+            boolean_op: BooleanOp::Cut,
+            target_body: Some(target_id),
+            draft_angle: 0.0,
+            suppressed: false,
+        };
+
+        let result = cut.execute(&kernel, &sketches, &bodies).unwrap();
+        assert!((volume(&target) - 8.0).abs() < 1e-5);
+        assert!((volume(&result) - 4.0).abs() < 1e-5);
+
+        // The tool is subtracted from the target, not the other way round
+        let (min, max) = kernel.tessellate(&result, 0.1).unwrap().bounds().unwrap();
+        assert_eq!(min, Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(max, Vec3::splat(2.0));
+    }
+
+    #[test]
+    fn test_body_mesh_follows_tolerance() {
+        let mut body = CadBody::new("Body");
+        body.solid = Some(Solid::new(body.id));
+
+        assert!(body.get_mesh(&BoxKernel, 0.1).is_some());
+        assert_eq!(body.mesh_tolerance, Some(0.1));
+
+        // A changed global tolerance regenerates the mesh
+        body.get_mesh(&BoxKernel, 0.01);
+        assert_eq!(body.mesh_tolerance, Some(0.01));
+
+        // The per-body override wins and invalidates the cache
+        body.set_tessellation_tolerance(Some(0.5));
+        assert!(body.mesh_cache.is_none());
+        body.get_mesh(&BoxKernel, 0.01);
+        assert_eq!(body.mesh_tolerance, Some(0.5));
+    }
+
+    #[test]
+    fn test_body_display_color() {
+        let mut body = CadBody::new("Body");
+        let palette_color = body.display_color();
+        assert!(BODY_PALETTE.contains(&palette_color));
+        assert_eq!(body.display_color(), palette_color);
+
+        body.color = Some([1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(body.display_color(), [1.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_reflection_matrix() {
+        let reflection = reflection_matrix(Vec3::new(1.0, 0.0, 0.0), Vec3::X);
+        let mirrored = reflection.transform_point3(Vec3::new(3.0, 2.0, 1.0));
+        assert!((mirrored - Vec3::new(-1.0, 2.0, 1.0)).length() < 1e-6);
+        assert!(reflection.determinant() < 0.0);
+    }
 }
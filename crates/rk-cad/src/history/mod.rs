@@ -4,13 +4,44 @@
 //! supporting rollback, rebuild, and editing of historical features.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::hash::{Hash, Hasher};
 use uuid::Uuid;
 
 use crate::feature::{CadBody, Feature, FeatureError, FeatureResult};
 use crate::kernel::{CadKernel, Solid};
+use crate::reference::{RefAxis, RefPoint};
 use crate::sketch::Sketch;
 
+/// Bodies that differ from the ones before a rebuild
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BodyChanges {
+    /// Bodies that did not exist before
+    pub added: Vec<Uuid>,
+    /// Bodies whose geometry changed
+    pub modified: Vec<Uuid>,
+    /// Bodies that no longer exist
+    pub removed: Vec<Uuid>,
+}
+
+impl BodyChanges {
+    /// Whether no body changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+
+    /// All changed bodies, whether added, modified or removed
+    pub fn all(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.added
+            .iter()
+            .chain(&self.modified)
+            .chain(&self.removed)
+            .copied()
+    }
+}
+
 /// An entry in the feature history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -24,6 +55,9 @@ pub struct HistoryEntry {
     pub modified_bodies: Vec<Uuid>,
     /// Bodies deleted by this feature
     pub deleted_bodies: Vec<Uuid>,
+    /// Why the feature failed in the last rebuild, if it did
+    #[serde(skip)]
+    pub error: Option<String>,
 }
 
 impl HistoryEntry {
@@ -35,6 +69,7 @@ impl HistoryEntry {
             created_bodies: Vec::new(),
             modified_bodies: Vec::new(),
             deleted_bodies: Vec::new(),
+            error: None,
         }
     }
 }
@@ -51,6 +86,12 @@ pub struct FeatureHistory {
     /// All bodies in the model
     #[serde(skip)]
     bodies: HashMap<Uuid, CadBody>,
+    /// User-picked body colors, kept so they survive saving and reloading
+    #[serde(default)]
+    body_colors: HashMap<Uuid, [f32; 4]>,
+    /// Per-body tessellation tolerance overrides, kept like the colors
+    #[serde(default)]
+    body_tolerances: HashMap<Uuid, f32>,
 }
 
 impl FeatureHistory {
@@ -101,14 +142,24 @@ impl FeatureHistory {
     }
 
     /// Add a feature to the history
+    ///
+    /// When rolled back, the feature is inserted at the rollback position and
+    /// the rollback marker moves past it; later features are kept.
     pub fn add_feature(&mut self, feature: Feature) {
-        // If we're rolled back, remove features after the rollback point
         if let Some(pos) = self.rollback_position {
-            self.entries.truncate(pos);
-            self.rollback_position = None;
+            self.entries.insert(pos, HistoryEntry::new(feature));
+            self.rollback_position = Some(pos + 1);
+        } else {
+            self.entries.push(HistoryEntry::new(feature));
         }
+    }
 
-        self.entries.push(HistoryEntry::new(feature));
+    /// Replace a feature with an edited version, keeping its ID and position
+    pub fn replace_feature(&mut self, id: Uuid, mut feature: Feature) -> Result<(), FeatureError> {
+        let index = self.index_of(id).ok_or(FeatureError::FeatureNotFound(id))?;
+        feature.set_id(id);
+        self.entries[index] = HistoryEntry::new(feature);
+        Ok(())
     }
 
     /// Remove a feature from the history
@@ -119,6 +170,9 @@ impl FeatureHistory {
     }
 
     /// Move a feature to a new position
+    ///
+    /// The move is rejected if it would place a feature before the feature
+    /// that creates a body it depends on. Bodies are known after a rebuild.
     pub fn move_feature(&mut self, id: Uuid, new_index: usize) -> Result<(), FeatureError> {
         let old_index = self.index_of(id).ok_or(FeatureError::FeatureNotFound(id))?;
 
@@ -129,6 +183,35 @@ impl FeatureHistory {
         let entry = self.entries.remove(old_index);
         self.entries.insert(new_index, entry);
 
+        if let Err(e) = self.validate_order() {
+            // Restore the original order
+            let entry = self.entries.remove(new_index);
+            self.entries.insert(old_index, entry);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Check that every feature comes after the features creating the bodies it uses
+    pub fn validate_order(&self) -> Result<(), FeatureError> {
+        for (index, entry) in self.entries.iter().enumerate() {
+            for body_id in entry.feature.referenced_bodies() {
+                let producer = self
+                    .entries
+                    .iter()
+                    .position(|e| e.created_bodies.contains(&body_id));
+                if let Some(producer) = producer
+                    && producer >= index
+                {
+                    return Err(FeatureError::InvalidOrder(format!(
+                        "'{}' uses a body created by '{}'",
+                        entry.feature.name(),
+                        self.entries[producer].feature.name()
+                    )));
+                }
+            }
+        }
         Ok(())
     }
 
@@ -183,6 +266,33 @@ impl FeatureHistory {
         self.bodies.get_mut(&id)
     }
 
+    /// Get mutable references to all bodies
+    pub fn bodies_mut(&mut self) -> impl Iterator<Item = &mut CadBody> {
+        self.bodies.values_mut()
+    }
+
+    /// Set a body's display color, or clear it to use the palette color
+    pub fn set_body_color(&mut self, id: Uuid, color: Option<[f32; 4]>) {
+        match color {
+            Some(color) => self.body_colors.insert(id, color),
+            None => self.body_colors.remove(&id),
+        };
+        if let Some(body) = self.bodies.get_mut(&id) {
+            body.color = color;
+        }
+    }
+
+    /// Set a body's tessellation tolerance, or clear it to use the global one
+    pub fn set_body_tolerance(&mut self, id: Uuid, tolerance: Option<f32>) {
+        match tolerance {
+            Some(tolerance) => self.body_tolerances.insert(id, tolerance),
+            None => self.body_tolerances.remove(&id),
+        };
+        if let Some(body) = self.bodies.get_mut(&id) {
+            body.set_tessellation_tolerance(tolerance);
+        }
+    }
+
     /// Get all bodies
     pub fn bodies(&self) -> &HashMap<Uuid, CadBody> {
         &self.bodies
@@ -203,6 +313,17 @@ impl FeatureHistory {
         self.rollback_position = None;
     }
 
+    /// Set the rollback marker so only the first `index` features are active
+    ///
+    /// An index at or past the end rolls forward to the end.
+    pub fn set_rollback_index(&mut self, index: usize) {
+        self.rollback_position = if index >= self.entries.len() {
+            None
+        } else {
+            Some(index)
+        };
+    }
+
     /// Get the current rollback position
     pub fn rollback_position(&self) -> Option<usize> {
         self.rollback_position
@@ -222,25 +343,60 @@ impl FeatureHistory {
     // ============== Rebuild ==============
 
     /// Rebuild all geometry from features
-    pub fn rebuild(&mut self, kernel: &dyn CadKernel) -> FeatureResult<()> {
-        // Clear existing bodies
-        self.bodies.clear();
+    ///
+    /// Bodies built from the same features and sketches as before keep their
+    /// cached mesh and are left out of the returned changes.
+    pub fn rebuild(&mut self, kernel: &dyn CadKernel) -> FeatureResult<BodyChanges> {
+        // Clear existing bodies, remembering settings the user may have changed
+        let previous_bodies: HashMap<Uuid, CadBody> = self.bodies.drain().collect();
 
         // Convert bodies to solids for feature execution
         let mut solids: HashMap<Uuid, Solid> = HashMap::new();
 
         // Execute each feature in order
         let end = self.effective_len();
+        for entry in &mut self.entries {
+            entry.error = None;
+        }
         for entry in &mut self.entries[..end] {
             if entry.feature.is_suppressed() {
                 continue;
             }
 
+            let fingerprint = input_fingerprint(&entry.feature, &self.sketches, &self.bodies);
             match entry.feature.execute(kernel, &self.sketches, &solids) {
                 Ok(solid) => {
-                    // Create a new body for the result
+                    for consumed in entry.feature.consumed_bodies() {
+                        self.bodies.remove(&consumed);
+                        solids.remove(&consumed);
+                    }
+
+                    // Join, cut and body operations replace their target
+                    // body rather than adding an overlapping one
+                    if let Some(body_id) = entry.feature.modified_body()
+                        && let Some(body) = self.bodies.get_mut(&body_id)
+                    {
+                        solids.insert(body_id, solid.clone());
+                        body.solid = Some(solid);
+                        body.fingerprint = fingerprint;
+                        body.invalidate_cache();
+                        entry.created_bodies.clear();
+                        continue;
+                    }
+
+                    // Create a body for the result, keeping the ID from the
+                    // previous rebuild so later features can reference it
                     let mut body = CadBody::new(entry.feature.name());
+                    if let Some(&body_id) = entry.created_bodies.first() {
+                        body.id = body_id;
+                        if let Some(previous) = previous_bodies.get(&body_id) {
+                            body.name = previous.name.clone();
+                        }
+                    }
                     body.source_feature = Some(entry.feature.id());
+                    body.fingerprint = fingerprint;
+                    body.color = self.body_colors.get(&body.id).copied();
+                    body.tessellation_tolerance = self.body_tolerances.get(&body.id).copied();
                     let body_id = body.id;
 
                     // Store the solid
@@ -251,17 +407,57 @@ impl FeatureHistory {
                     entry.created_bodies = vec![body_id];
                 }
                 Err(e) => {
-                    // Log error but continue with other features
+                    // Record the error but continue with other features
                     tracing::warn!("Feature {} failed: {}", entry.feature.name(), e);
+                    entry.error = Some(e.to_string());
                 }
             }
         }
 
-        Ok(())
+        let mut changes = BodyChanges::default();
+        let mut previous_bodies = previous_bodies;
+        for (id, body) in &mut self.bodies {
+            match previous_bodies.remove(id) {
+                Some(previous) if previous.fingerprint == body.fingerprint => {
+                    body.reuse_mesh(previous);
+                }
+                Some(_) => changes.modified.push(*id),
+                None => changes.added.push(*id),
+            }
+        }
+        changes.removed = previous_bodies.into_keys().collect();
+
+        // Drop settings of bodies no feature creates any more. Bodies hidden
+        // by rollback or suppression keep theirs, as they come back.
+        let known: HashSet<Uuid> = self
+            .entries
+            .iter()
+            .flat_map(|entry| entry.created_bodies.iter().copied())
+            .collect();
+        self.body_colors.retain(|id, _| known.contains(id));
+        self.body_tolerances.retain(|id, _| known.contains(id));
+        Ok(changes)
+    }
+
+    /// Features that failed in the last rebuild, with their errors
+    pub fn failed_features(&self) -> impl Iterator<Item = (&Feature, &str)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| Some((&entry.feature, entry.error.as_deref()?)))
+    }
+
+    /// Roll back to `index` and rebuild, executing only the features before it
+    pub fn rebuild_to(
+        &mut self,
+        index: usize,
+        kernel: &dyn CadKernel,
+    ) -> FeatureResult<BodyChanges> {
+        self.set_rollback_index(index);
+        self.rebuild(kernel)
     }
 
     /// Rebuild a single feature and all dependent features
-    pub fn rebuild_from(&mut self, id: Uuid, kernel: &dyn CadKernel) -> FeatureResult<()> {
+    pub fn rebuild_from(&mut self, id: Uuid, kernel: &dyn CadKernel) -> FeatureResult<BodyChanges> {
         // Verify the feature exists
         let _start_index = self.index_of(id).ok_or(FeatureError::FeatureNotFound(id))?;
 
@@ -271,11 +467,46 @@ impl FeatureHistory {
     }
 }
 
+/// Hash everything a feature's result depends on
+///
+/// The feature and its sketch are hashed through their `Debug` output, which
+/// covers every parameter; input bodies contribute their own fingerprints,
+/// so a change propagates to every body built on top of it.
+fn input_fingerprint(
+    feature: &Feature,
+    sketches: &HashMap<Uuid, Sketch>,
+    bodies: &HashMap<Uuid, CadBody>,
+) -> u64 {
+    struct HashWriter<'a>(&'a mut DefaultHasher);
+    impl std::fmt::Write for HashWriter<'_> {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0.write(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    let mut hasher = DefaultHasher::new();
+    let _ = write!(HashWriter(&mut hasher), "{:?}", feature);
+    if let Some(sketch) = feature.sketch_id().and_then(|id| sketches.get(&id)) {
+        let _ = write!(HashWriter(&mut hasher), "{:?}", sketch);
+    }
+    for body in feature.referenced_bodies() {
+        bodies.get(&body).map(|b| b.fingerprint).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
 /// CAD data that can be stored in a project
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CadData {
     /// Feature history
     pub history: FeatureHistory,
+    /// Reference axes
+    #[serde(default)]
+    pub ref_axes: Vec<RefAxis>,
+    /// Reference points
+    #[serde(default)]
+    pub ref_points: Vec<RefPoint>,
 }
 
 impl CadData {
@@ -286,14 +517,30 @@ impl CadData {
 
     /// Check if there's any CAD data
     pub fn is_empty(&self) -> bool {
-        self.history.is_empty() && self.history.sketches().is_empty()
+        self.history.is_empty()
+            && self.history.sketches().is_empty()
+            && self.ref_axes.is_empty()
+            && self.ref_points.is_empty()
+    }
+
+    /// Get a reference axis by ID
+    pub fn get_ref_axis(&self, id: Uuid) -> Option<&RefAxis> {
+        self.ref_axes.iter().find(|axis| axis.id == id)
+    }
+
+    /// Get a reference point by ID
+    pub fn get_ref_point(&self, id: Uuid) -> Option<&RefPoint> {
+        self.ref_points.iter().find(|point| point.id == id)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use glam::Vec2;
+
     use super::*;
-    use crate::feature::ExtrudeDirection;
+    use crate::feature::{BooleanOp, ExtrudeDirection};
+    use crate::kernel::test_kernel::{BoundsKernel, BoxKernel};
 
     #[test]
     fn test_add_feature() {
@@ -307,6 +554,31 @@ mod tests {
         assert!(history.get_by_id(id).is_some());
     }
 
+    #[test]
+    fn test_replace_feature() {
+        let mut history = FeatureHistory::new();
+        let f1 = Feature::extrude("F1", Uuid::new_v4(), 10.0, ExtrudeDirection::Positive);
+        let f2 = Feature::extrude("F2", Uuid::new_v4(), 20.0, ExtrudeDirection::Positive);
+        let f1_id = f1.id();
+        history.add_feature(f1);
+        history.add_feature(f2);
+
+        let edited = Feature::extrude("F1", Uuid::new_v4(), 15.0, ExtrudeDirection::Negative);
+        history.replace_feature(f1_id, edited).unwrap();
+        assert_eq!(history.index_of(f1_id), Some(0));
+        assert!(matches!(
+            history.get_by_id(f1_id),
+            Some(Feature::Extrude { distance, .. }) if *distance == 15.0
+        ));
+
+        let missing = Uuid::new_v4();
+        assert!(
+            history
+                .replace_feature(missing, history.get(1).unwrap().clone())
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_rollback() {
         let mut history = FeatureHistory::new();
@@ -333,4 +605,315 @@ mod tests {
         history.rollback_to_end();
         assert_eq!(history.effective_len(), 3);
     }
+
+    #[test]
+    fn test_add_feature_at_rollback_position() {
+        let mut history = FeatureHistory::new();
+
+        let f1 = Feature::extrude("F1", Uuid::new_v4(), 10.0, ExtrudeDirection::Positive);
+        let f2 = Feature::extrude("F2", Uuid::new_v4(), 20.0, ExtrudeDirection::Positive);
+        let f3 = Feature::extrude("F3", Uuid::new_v4(), 30.0, ExtrudeDirection::Positive);
+        let f2_id = f2.id();
+        let f3_id = f3.id();
+
+        history.add_feature(f1);
+        history.add_feature(f2);
+        history.set_rollback_index(1);
+        history.add_feature(f3);
+
+        // Inserted before F2, which is kept but still rolled back
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.index_of(f3_id), Some(1));
+        assert_eq!(history.index_of(f2_id), Some(2));
+        assert_eq!(history.effective_len(), 2);
+
+        history.set_rollback_index(10);
+        assert_eq!(history.rollback_position(), None);
+    }
+
+    #[test]
+    fn test_rebuild_to() {
+        let mut history = FeatureHistory::new();
+        history.add_feature(Feature::extrude(
+            "F1",
+            Uuid::new_v4(),
+            10.0,
+            ExtrudeDirection::Positive,
+        ));
+        history.add_feature(Feature::extrude(
+            "F2",
+            Uuid::new_v4(),
+            20.0,
+            ExtrudeDirection::Positive,
+        ));
+
+        history.rebuild_to(0, &crate::kernel::NullKernel).unwrap();
+        assert_eq!(history.effective_len(), 0);
+        assert!(history.bodies().is_empty());
+    }
+
+    #[test]
+    fn test_move_feature() {
+        let mut history = FeatureHistory::new();
+
+        let f1 = Feature::extrude("F1", Uuid::new_v4(), 10.0, ExtrudeDirection::Positive);
+        let f2 = Feature::extrude("F2", Uuid::new_v4(), 20.0, ExtrudeDirection::Positive);
+        let f2_id = f2.id();
+
+        history.add_feature(f1);
+        history.add_feature(f2);
+
+        history.move_feature(f2_id, 0).unwrap();
+        assert_eq!(history.index_of(f2_id), Some(0));
+        assert!(history.move_feature(f2_id, 2).is_err());
+    }
+
+    #[test]
+    fn test_move_feature_rejects_broken_dependency() {
+        let mut history = FeatureHistory::new();
+
+        let extrude = Feature::extrude("Extrude", Uuid::new_v4(), 10.0, ExtrudeDirection::Positive);
+        let extrude_id = extrude.id();
+        history.add_feature(extrude);
+        let body_id = Uuid::new_v4();
+        history.entries[0].created_bodies = vec![body_id];
+
+        let fillet = Feature::Fillet {
+            id: Uuid::new_v4(),
+            name: "Fillet".into(),
+            body_id,
+            radius: 1.0,
+            edges: Vec::new(),
+            suppressed: false,
+        };
+        let fillet_id = fillet.id();
+        history.add_feature(fillet);
+
+        let result = history.move_feature(fillet_id, 0);
+        assert!(matches!(result, Err(FeatureError::InvalidOrder(_))));
+        // Order is unchanged after a rejected move
+        assert_eq!(history.index_of(extrude_id), Some(0));
+        assert_eq!(history.index_of(fillet_id), Some(1));
+    }
+
+    #[test]
+    fn test_bodies_rebuild_after_reload() {
+        let mut data = CadData::new();
+        let mut sketch = Sketch::default();
+        sketch.add_rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+        let sketch_id = data.history.add_sketch(sketch);
+        data.history.add_feature(Feature::extrude(
+            "Extrude",
+            sketch_id,
+            5.0,
+            ExtrudeDirection::Positive,
+        ));
+        data.history.rebuild(&BoxKernel).unwrap();
+        assert_eq!(data.history.bodies().len(), 1);
+
+        // Solids are not serialized, so a reloaded history starts without bodies
+        let saved = ron::to_string(&data).unwrap();
+        let mut loaded: CadData = ron::from_str(&saved).unwrap();
+        assert!(loaded.history.bodies().is_empty());
+
+        loaded.history.rebuild(&BoxKernel).unwrap();
+        assert_eq!(loaded.history.bodies().len(), 1);
+        assert!(loaded.history.bodies().values().all(|b| b.solid.is_some()));
+    }
+
+    #[test]
+    fn test_body_color_and_tolerance_survive_reload() {
+        let mut data = CadData::new();
+        let mut sketch = Sketch::default();
+        sketch.add_rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+        let sketch_id = data.history.add_sketch(sketch);
+        data.history.add_feature(Feature::extrude(
+            "Extrude",
+            sketch_id,
+            5.0,
+            ExtrudeDirection::Positive,
+        ));
+        data.history.rebuild(&BoxKernel).unwrap();
+        let body_id = *data.history.bodies().keys().next().unwrap();
+
+        let red = [1.0, 0.0, 0.0, 1.0];
+        data.history.set_body_color(body_id, Some(red));
+        assert_eq!(data.history.get_body(body_id).unwrap().color, Some(red));
+        data.history.set_body_tolerance(body_id, Some(0.5));
+
+        let saved = ron::to_string(&data).unwrap();
+        let mut loaded: CadData = ron::from_str(&saved).unwrap();
+        loaded.history.rebuild(&BoxKernel).unwrap();
+        assert_eq!(
+            loaded.history.get_body(body_id).unwrap().display_color(),
+            red
+        );
+        assert_eq!(
+            loaded.history.get_body(body_id).unwrap().tolerance(0.01),
+            0.5
+        );
+
+        // Rolled back bodies keep their settings, removed ones drop them
+        let history = &mut loaded.history;
+        history.set_rollback_index(0);
+        history.rebuild(&BoxKernel).unwrap();
+        assert!(history.body_colors.contains_key(&body_id));
+        history.rollback_to_end();
+        let feature_id = history.features().next().unwrap().id();
+        history.remove_feature(feature_id);
+        history.rebuild(&BoxKernel).unwrap();
+        assert!(history.body_colors.is_empty());
+        assert!(history.body_tolerances.is_empty());
+    }
+
+    #[test]
+    fn test_rebuild_reports_changed_bodies() {
+        let mut history = FeatureHistory::new();
+        let mut sketch = Sketch::default();
+        sketch.add_rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+        let sketch_id = history.add_sketch(sketch);
+        let first = Feature::extrude("First", sketch_id, 5.0, ExtrudeDirection::Positive);
+        let first_id = first.id();
+        history.add_feature(first);
+        history.add_feature(Feature::extrude(
+            "Second",
+            sketch_id,
+            8.0,
+            ExtrudeDirection::Positive,
+        ));
+
+        let changes = history.rebuild(&BoxKernel).unwrap();
+        assert_eq!(changes.added.len(), 2);
+        let first_body = history.entries()[0].created_bodies[0];
+        history
+            .get_body_mut(first_body)
+            .unwrap()
+            .get_mesh(&BoxKernel, 0.1);
+
+        // Nothing changed: bodies keep their meshes
+        assert!(history.rebuild(&BoxKernel).unwrap().is_empty());
+        assert!(history.get_body(first_body).unwrap().has_current_mesh(0.1));
+
+        // Editing one feature only touches its own body
+        if let Some(Feature::Extrude { distance, .. }) = history.get_by_id_mut(first_id) {
+            *distance = 6.0;
+        }
+        let changes = history.rebuild(&BoxKernel).unwrap();
+        assert_eq!(changes.modified, vec![first_body]);
+        assert!(changes.added.is_empty() && changes.removed.is_empty());
+        assert!(!history.get_body(first_body).unwrap().has_current_mesh(0.1));
+
+        history
+            .get_by_id_mut(first_id)
+            .unwrap()
+            .set_suppressed(true);
+        let changes = history.rebuild(&BoxKernel).unwrap();
+        assert_eq!(changes.removed, vec![first_body]);
+    }
+
+    #[test]
+    fn test_rebuild_records_failed_features() {
+        let mut history = FeatureHistory::new();
+        let broken = Feature::extrude("Broken", Uuid::new_v4(), 5.0, ExtrudeDirection::Positive);
+        let broken_id = broken.id();
+        history.add_feature(broken);
+
+        history.rebuild(&BoxKernel).unwrap();
+        let failed: Vec<Uuid> = history.failed_features().map(|(f, _)| f.id()).collect();
+        assert_eq!(failed, vec![broken_id]);
+
+        // Suppressing the feature clears its error
+        history
+            .get_by_id_mut(broken_id)
+            .unwrap()
+            .set_suppressed(true);
+        history.rebuild(&BoxKernel).unwrap();
+        assert_eq!(history.failed_features().count(), 0);
+    }
+
+    #[test]
+    fn test_cut_replaces_target_body() {
+        let mut history = FeatureHistory::new();
+
+        let mut base_sketch = Sketch::default();
+        base_sketch.add_rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+        let base_sketch_id = history.add_sketch(base_sketch);
+        let mut hole_sketch = Sketch::default();
+        hole_sketch.add_rectangle(Vec2::new(4.0, 4.0), Vec2::new(6.0, 6.0));
+        let hole_sketch_id = history.add_sketch(hole_sketch);
+
+        history.add_feature(Feature::extrude(
+            "Base",
+            base_sketch_id,
+            10.0,
+            ExtrudeDirection::Positive,
+        ));
+        history.rebuild(&BoxKernel).unwrap();
+        let base_id = *history.bodies().keys().next().unwrap();
+
+        history.add_feature(Feature::Extrude {
+            id: Uuid::new_v4(),
+            name: "Hole".into(),
+            sketch_id: hole_sketch_id,
+            profiles: None,
+            distance: 10.0,
+            direction: ExtrudeDirection::Positive,
+            boolean_op: BooleanOp::Cut,
+            target_body: Some(base_id),
+            draft_angle: 0.0,
+            suppressed: false,
+        });
+        history.rebuild(&BoxKernel).unwrap();
+
+        // The cut result replaces the base instead of adding a second body
+        assert_eq!(history.bodies().len(), 1);
+        assert_eq!(history.get_body(base_id).unwrap().name, "Base");
+        assert!(history.entries()[1].created_bodies.is_empty());
+    }
+
+    #[test]
+    fn test_mirror_joins_symmetric_copy() {
+        let kernel = BoundsKernel::default();
+        let mut history = FeatureHistory::new();
+
+        // Asymmetric L-shaped profile, entirely on the positive x side
+        let mut sketch = Sketch::default();
+        let corners = [
+            Vec2::new(1.0, 0.0),
+            Vec2::new(3.0, 0.0),
+            Vec2::new(3.0, 1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, 3.0),
+            Vec2::new(1.0, 3.0),
+        ];
+        let points: Vec<Uuid> = corners.iter().map(|c| sketch.add_point(*c)).collect();
+        for i in 0..points.len() {
+            sketch.add_line(points[i], points[(i + 1) % points.len()]);
+        }
+        let sketch_id = history.add_sketch(sketch);
+
+        history.add_feature(Feature::extrude(
+            "L",
+            sketch_id,
+            2.0,
+            ExtrudeDirection::Positive,
+        ));
+        history.rebuild(&kernel).unwrap();
+        let body_id = *history.bodies().keys().next().unwrap();
+
+        history.add_feature(Feature::mirror(
+            "Mirror",
+            body_id,
+            glam::Vec3::ZERO,
+            glam::Vec3::X,
+        ));
+        history.rebuild(&kernel).unwrap();
+
+        // The mirrored copy is joined into the source body
+        assert_eq!(history.bodies().len(), 1);
+        let solid = history.get_body(body_id).unwrap().solid.as_ref().unwrap();
+        let (min, max) = kernel.tessellate(solid, 0.1).unwrap().bounds().unwrap();
+        assert!((min.x + 3.0).abs() < 1e-5);
+        assert!((max.x - 3.0).abs() < 1e-5);
+    }
 }
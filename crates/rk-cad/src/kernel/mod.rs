@@ -5,6 +5,9 @@
 
 mod traits;
 
+#[cfg(test)]
+pub(crate) mod test_kernel;
+
 // TODO: Implement kernel backends
 // #[cfg(feature = "opencascade")]
 // mod opencascade;
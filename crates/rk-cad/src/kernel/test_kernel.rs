@@ -0,0 +1,266 @@
+//! Stub kernel for unit tests
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use glam::{Mat4, Quat, Vec3};
+use uuid::Uuid;
+
+use crate::sketch::SketchPlane;
+
+use super::{
+    Axis3D, BooleanType, CadError, CadKernel, CadResult, FaceInfo, Solid, TessellatedMesh, Wire2D,
+};
+
+/// Kernel whose solids tessellate to a fixed 10 x 4 x 2 box and shell successfully
+pub(crate) struct BoxKernel;
+
+impl CadKernel for BoxKernel {
+    fn name(&self) -> &str {
+        "box"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn extrude(
+        &self,
+        _profile: &Wire2D,
+        _plane: &SketchPlane,
+        _direction: Vec3,
+        _distance: f32,
+    ) -> CadResult<Solid> {
+        Ok(Solid::new(Uuid::new_v4()))
+    }
+
+    fn extrude_tapered(
+        &self,
+        _profile: &Wire2D,
+        _top: &Wire2D,
+        _plane: &SketchPlane,
+        _direction: Vec3,
+        _distance: f32,
+    ) -> CadResult<Solid> {
+        Ok(Solid::new(Uuid::new_v4()))
+    }
+
+    fn revolve(
+        &self,
+        _profile: &Wire2D,
+        _plane: &SketchPlane,
+        _axis: &Axis3D,
+        _angle: f32,
+    ) -> CadResult<Solid> {
+        Ok(Solid::new(Uuid::new_v4()))
+    }
+
+    fn boolean(&self, a: &Solid, _b: &Solid, _op: BooleanType) -> CadResult<Solid> {
+        Ok(a.clone())
+    }
+
+    fn faces(&self, _solid: &Solid) -> CadResult<Vec<FaceInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn shell(&self, solid: &Solid, _thickness: f32, _open_faces: &[Uuid]) -> CadResult<Solid> {
+        Ok(solid.clone())
+    }
+
+    fn transform(&self, solid: &Solid, _matrix: Mat4) -> CadResult<Solid> {
+        Ok(solid.clone())
+    }
+
+    fn tessellate(&self, _solid: &Solid, _tolerance: f32) -> CadResult<TessellatedMesh> {
+        Ok(TessellatedMesh {
+            vertices: vec![[0.0, 0.0, 0.0], [10.0, 4.0, 2.0]],
+            normals: vec![[0.0, 0.0, 1.0]; 2],
+            indices: Vec::new(),
+            ..Default::default()
+        })
+    }
+
+    fn create_box(&self, _center: Vec3, _size: Vec3) -> CadResult<Solid> {
+        Ok(Solid::new(Uuid::new_v4()))
+    }
+
+    fn create_cylinder(
+        &self,
+        _center: Vec3,
+        _radius: f32,
+        _height: f32,
+        _axis: Vec3,
+    ) -> CadResult<Solid> {
+        Ok(Solid::new(Uuid::new_v4()))
+    }
+
+    fn create_sphere(&self, _center: Vec3, _radius: f32) -> CadResult<Solid> {
+        Ok(Solid::new(Uuid::new_v4()))
+    }
+}
+
+/// Kernel that tracks only the axis-aligned bounds of each solid
+///
+/// Unions merge bounds, which is enough to check where features place
+/// geometry. Subtraction only handles tools that slice straight through
+/// one side of the target, so the remainder is still a box.
+#[derive(Default)]
+pub(crate) struct BoundsKernel {
+    bounds: Mutex<HashMap<Uuid, (Vec3, Vec3)>>,
+}
+
+impl BoundsKernel {
+    fn insert(&self, points: impl IntoIterator<Item = Vec3>) -> CadResult<Solid> {
+        let mut points = points.into_iter();
+        let first = points
+            .next()
+            .ok_or_else(|| CadError::OperationFailed("Solid has no points".into()))?;
+        let bounds = points.fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+
+        let solid = Solid::new(Uuid::new_v4());
+        self.bounds.lock().unwrap().insert(solid.id, bounds);
+        Ok(solid)
+    }
+
+    fn get(&self, solid: &Solid) -> CadResult<(Vec3, Vec3)> {
+        self.bounds
+            .lock()
+            .unwrap()
+            .get(&solid.id)
+            .copied()
+            .ok_or_else(|| CadError::OperationFailed("Unknown solid".into()))
+    }
+}
+
+impl CadKernel for BoundsKernel {
+    fn name(&self) -> &str {
+        "bounds"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn extrude(
+        &self,
+        profile: &Wire2D,
+        plane: &SketchPlane,
+        direction: Vec3,
+        distance: f32,
+    ) -> CadResult<Solid> {
+        let offset = direction.normalize() * distance;
+        self.insert(profile.points.iter().flat_map(|p| {
+            let base = plane.to_world(*p);
+            [base, base + offset]
+        }))
+    }
+
+    fn extrude_tapered(
+        &self,
+        profile: &Wire2D,
+        top: &Wire2D,
+        plane: &SketchPlane,
+        direction: Vec3,
+        distance: f32,
+    ) -> CadResult<Solid> {
+        let offset = direction.normalize() * distance;
+        let bottom = profile.points.iter().map(|p| plane.to_world(*p));
+        let top = top.points.iter().map(|p| plane.to_world(*p) + offset);
+        self.insert(bottom.chain(top))
+    }
+
+    fn revolve(
+        &self,
+        profile: &Wire2D,
+        plane: &SketchPlane,
+        axis: &Axis3D,
+        angle: f32,
+    ) -> CadResult<Solid> {
+        // Sample the sweep finely enough that the bounds are exact at the
+        // quarter turns
+        const STEPS: u32 = 64;
+        let points: Vec<Vec3> = profile.points.iter().map(|p| plane.to_world(*p)).collect();
+        self.insert((0..=STEPS).flat_map(|step| {
+            let rotation =
+                Quat::from_axis_angle(axis.direction, angle * step as f32 / STEPS as f32);
+            points
+                .iter()
+                .map(move |p| axis.origin + rotation * (*p - axis.origin))
+        }))
+    }
+
+    fn boolean(&self, a: &Solid, b: &Solid, op: BooleanType) -> CadResult<Solid> {
+        let (a_min, a_max) = self.get(a)?;
+        let (b_min, b_max) = self.get(b)?;
+        match op {
+            BooleanType::Union => self.insert([a_min, a_max, b_min, b_max]),
+            BooleanType::Subtract => {
+                for axis in 0..3 {
+                    let covers_rest = (0..3)
+                        .filter(|&other| other != axis)
+                        .all(|other| b_min[other] <= a_min[other] && b_max[other] >= a_max[other]);
+                    if !covers_rest {
+                        continue;
+                    }
+                    let (mut min, mut max) = (a_min, a_max);
+                    if b_min[axis] <= a_min[axis] && b_max[axis] < a_max[axis] {
+                        min[axis] = min[axis].max(b_max[axis]);
+                    } else if b_max[axis] >= a_max[axis] && b_min[axis] > a_min[axis] {
+                        max[axis] = max[axis].min(b_min[axis]);
+                    } else {
+                        continue;
+                    }
+                    return self.insert([min, max]);
+                }
+                Err(CadError::OperationFailed("Not supported".into()))
+            }
+            _ => Err(CadError::OperationFailed("Not supported".into())),
+        }
+    }
+
+    fn faces(&self, _solid: &Solid) -> CadResult<Vec<FaceInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn shell(&self, solid: &Solid, _thickness: f32, _open_faces: &[Uuid]) -> CadResult<Solid> {
+        Ok(solid.clone())
+    }
+
+    fn transform(&self, solid: &Solid, matrix: Mat4) -> CadResult<Solid> {
+        let (min, max) = self.get(solid)?;
+        self.insert((0..8).map(|corner| {
+            let point = Vec3::new(
+                if corner & 1 == 0 { min.x } else { max.x },
+                if corner & 2 == 0 { min.y } else { max.y },
+                if corner & 4 == 0 { min.z } else { max.z },
+            );
+            matrix.transform_point3(point)
+        }))
+    }
+
+    fn tessellate(&self, solid: &Solid, _tolerance: f32) -> CadResult<TessellatedMesh> {
+        let (min, max) = self.get(solid)?;
+        Ok(TessellatedMesh {
+            vertices: vec![min.to_array(), max.to_array()],
+            ..Default::default()
+        })
+    }
+
+    fn create_box(&self, center: Vec3, size: Vec3) -> CadResult<Solid> {
+        self.insert([center - size / 2.0, center + size / 2.0])
+    }
+
+    fn create_cylinder(
+        &self,
+        _center: Vec3,
+        _radius: f32,
+        _height: f32,
+        _axis: Vec3,
+    ) -> CadResult<Solid> {
+        Err(CadError::OperationFailed("Not supported".into()))
+    }
+
+    fn create_sphere(&self, center: Vec3, radius: f32) -> CadResult<Solid> {
+        self.insert([center - Vec3::splat(radius), center + Vec3::splat(radius)])
+    }
+}
@@ -2,11 +2,13 @@
 //!
 //! These traits define the interface that all CAD kernels must implement.
 
-use glam::{Vec2, Vec3};
+use glam::{Mat4, Vec2, Vec3};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::sketch::SketchPlane;
+
 /// Error type for CAD kernel operations
 #[derive(Debug, Clone, Error)]
 pub enum CadError {
@@ -38,6 +40,21 @@ pub struct TessellatedMesh {
     pub normals: Vec<[f32; 3]>,
     /// Triangle indices (3 indices per triangle)
     pub indices: Vec<u32>,
+    /// Source face of each triangle, matching the ids from [`CadKernel::faces`]
+    ///
+    /// Empty if the kernel does not tag triangles.
+    pub face_ids: Vec<Uuid>,
+    /// Polylines of the solid's edges
+    pub edges: Vec<MeshEdge>,
+}
+
+/// An edge of a solid, discretized for display and picking
+#[derive(Debug, Clone, Default)]
+pub struct MeshEdge {
+    /// Edge identifier, stable for a given solid
+    pub id: Uuid,
+    /// Points along the edge
+    pub points: Vec<[f32; 3]>,
 }
 
 impl TessellatedMesh {
@@ -55,6 +72,34 @@ impl TessellatedMesh {
     pub fn triangle_count(&self) -> usize {
         self.indices.len() / 3
     }
+
+    /// Get the source face of a triangle, if the kernel tagged it
+    pub fn face_of_triangle(&self, triangle: usize) -> Option<Uuid> {
+        self.face_ids.get(triangle).copied()
+    }
+
+    /// Get the axis-aligned bounds as (min, max), or `None` if empty
+    pub fn bounds(&self) -> Option<(Vec3, Vec3)> {
+        let mut vertices = self.vertices.iter().map(|v| Vec3::from(*v));
+        let first = vertices.next()?;
+        Some(vertices.fold((first, first), |(min, max), v| (min.min(v), max.max(v))))
+    }
+}
+
+/// A face of a solid, as enumerated by the kernel
+#[derive(Debug, Clone, Copy)]
+pub struct FaceInfo {
+    /// Face identifier, stable for a given solid
+    pub id: Uuid,
+    /// Centroid of the face
+    pub centroid: Vec3,
+    /// Outward normal at the centroid
+    pub normal: Vec3,
+    /// Surface area
+    pub area: f32,
+    /// Whether the face lies in a single plane; only planar faces can
+    /// carry a sketch
+    pub planar: bool,
 }
 
 /// A 2D wire (closed loop of edges) for extrusion profiles
@@ -103,6 +148,53 @@ impl Wire2D {
             .collect();
         Self::new(points, true)
     }
+
+    /// Signed area enclosed by the wire, positive if counter-clockwise
+    pub fn signed_area(&self) -> f32 {
+        let n = self.points.len();
+        (0..n)
+            .map(|i| self.points[i].perp_dot(self.points[(i + 1) % n]))
+            .sum::<f32>()
+            / 2.0
+    }
+
+    /// Move every edge of a closed wire by `distance`, inward if positive
+    ///
+    /// Corners are mitered so each edge ends up exactly `distance` from the
+    /// original. Returns `None` if an edge collapses or flips, which happens
+    /// when the distance is too large for the wire.
+    pub fn offset(&self, distance: f32) -> Option<Self> {
+        let n = self.points.len();
+        let area = self.signed_area();
+        if n < 3 || area == 0.0 {
+            return None;
+        }
+        // Left of each edge is inside for a counter-clockwise wire
+        let inward = |i: usize| {
+            let edge = self.points[(i + 1) % n] - self.points[i];
+            edge.normalize_or_zero().perp() * area.signum()
+        };
+
+        let mut points = Vec::with_capacity(n);
+        for i in 0..n {
+            let (a, b) = (inward((i + n - 1) % n), inward(i));
+            let denominator = 1.0 + a.dot(b);
+            if denominator < 1e-6 {
+                return None;
+            }
+            points.push(self.points[i] + (a + b) * (distance / denominator));
+        }
+
+        let flipped = (0..n).any(|i| {
+            let before = self.points[(i + 1) % n] - self.points[i];
+            let after = points[(i + 1) % n] - points[i];
+            before.dot(after) <= 0.0
+        });
+        if flipped {
+            return None;
+        }
+        Some(Self::new(points, self.closed))
+    }
 }
 
 /// A 3D solid body
@@ -205,15 +297,28 @@ pub trait CadKernel: Send + Sync {
     ///
     /// # Arguments
     /// * `profile` - The 2D wire profile to extrude
-    /// * `plane_origin` - The origin of the sketch plane in 3D
-    /// * `plane_normal` - The normal of the sketch plane
-    /// * `direction` - The extrusion direction (local to plane)
+    /// * `plane` - The sketch plane; profile points map to 3D through
+    ///   [`SketchPlane::to_world`]
+    /// * `direction` - The extrusion direction in world space
     /// * `distance` - The extrusion distance
     fn extrude(
         &self,
         profile: &Wire2D,
-        plane_origin: Vec3,
-        plane_normal: Vec3,
+        plane: &SketchPlane,
+        direction: Vec3,
+        distance: f32,
+    ) -> CadResult<Solid>;
+
+    /// Extrude a 2D profile that blends into a second profile at its far end
+    ///
+    /// Like [`CadKernel::extrude`], but the end of the solid `distance` along
+    /// `direction` is `top`, which has as many points as `profile`; matching
+    /// points are joined by ruled side faces. Used for drafted extrudes.
+    fn extrude_tapered(
+        &self,
+        profile: &Wire2D,
+        top: &Wire2D,
+        plane: &SketchPlane,
         direction: Vec3,
         distance: f32,
     ) -> CadResult<Solid>;
@@ -222,15 +327,14 @@ pub trait CadKernel: Send + Sync {
     ///
     /// # Arguments
     /// * `profile` - The 2D wire profile to revolve
-    /// * `plane_origin` - The origin of the sketch plane in 3D
-    /// * `plane_normal` - The normal of the sketch plane
+    /// * `plane` - The sketch plane; profile points map to 3D through
+    ///   [`SketchPlane::to_world`]
     /// * `axis` - The rotation axis
     /// * `angle` - The rotation angle in radians
     fn revolve(
         &self,
         profile: &Wire2D,
-        plane_origin: Vec3,
-        plane_normal: Vec3,
+        plane: &SketchPlane,
         axis: &Axis3D,
         angle: f32,
     ) -> CadResult<Solid>;
@@ -243,11 +347,31 @@ pub trait CadKernel: Send + Sync {
     /// * `op` - The boolean operation type
     fn boolean(&self, a: &Solid, b: &Solid, op: BooleanType) -> CadResult<Solid>;
 
+    /// Enumerate the faces of a solid
+    fn faces(&self, solid: &Solid) -> CadResult<Vec<FaceInfo>>;
+
+    /// Hollow a solid to a wall thickness
+    ///
+    /// # Arguments
+    /// * `solid` - The solid to shell
+    /// * `thickness` - Wall thickness; positive shells inward, negative outward
+    /// * `open_faces` - IDs of faces to remove, leaving the shell open there
+    fn shell(&self, solid: &Solid, thickness: f32, open_faces: &[Uuid]) -> CadResult<Solid>;
+
+    /// Apply an affine transform to a solid
+    ///
+    /// Reflections invert orientation, so implementations must flip face
+    /// winding to keep the result's normals pointing outward.
+    fn transform(&self, solid: &Solid, matrix: Mat4) -> CadResult<Solid>;
+
     /// Tessellate a solid into triangles
     ///
     /// # Arguments
     /// * `solid` - The solid to tessellate
     /// * `tolerance` - The tessellation tolerance (lower = more triangles)
+    ///
+    /// Kernels should fill `face_ids` and `edges` so faces and edges can be
+    /// picked in the viewport.
     fn tessellate(&self, solid: &Solid, tolerance: f32) -> CadResult<TessellatedMesh>;
 
     /// Create a box primitive
@@ -282,8 +406,20 @@ impl CadKernel for NullKernel {
     fn extrude(
         &self,
         _profile: &Wire2D,
-        _plane_origin: Vec3,
-        _plane_normal: Vec3,
+        _plane: &SketchPlane,
+        _direction: Vec3,
+        _distance: f32,
+    ) -> CadResult<Solid> {
+        Err(CadError::KernelNotAvailable(
+            "No CAD kernel available".into(),
+        ))
+    }
+
+    fn extrude_tapered(
+        &self,
+        _profile: &Wire2D,
+        _top: &Wire2D,
+        _plane: &SketchPlane,
         _direction: Vec3,
         _distance: f32,
     ) -> CadResult<Solid> {
@@ -295,8 +431,7 @@ impl CadKernel for NullKernel {
     fn revolve(
         &self,
         _profile: &Wire2D,
-        _plane_origin: Vec3,
-        _plane_normal: Vec3,
+        _plane: &SketchPlane,
         _axis: &Axis3D,
         _angle: f32,
     ) -> CadResult<Solid> {
@@ -311,6 +446,24 @@ impl CadKernel for NullKernel {
         ))
     }
 
+    fn faces(&self, _solid: &Solid) -> CadResult<Vec<FaceInfo>> {
+        Err(CadError::KernelNotAvailable(
+            "No CAD kernel available".into(),
+        ))
+    }
+
+    fn shell(&self, _solid: &Solid, _thickness: f32, _open_faces: &[Uuid]) -> CadResult<Solid> {
+        Err(CadError::KernelNotAvailable(
+            "No CAD kernel available".into(),
+        ))
+    }
+
+    fn transform(&self, _solid: &Solid, _matrix: Mat4) -> CadResult<Solid> {
+        Err(CadError::KernelNotAvailable(
+            "No CAD kernel available".into(),
+        ))
+    }
+
     fn tessellate(&self, _solid: &Solid, _tolerance: f32) -> CadResult<TessellatedMesh> {
         Err(CadError::KernelNotAvailable(
             "No CAD kernel available".into(),
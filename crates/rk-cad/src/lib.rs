@@ -4,21 +4,29 @@
 //! - Abstract CAD kernel traits for geometry operations
 //! - 2D sketch system with entities and constraints
 //! - Constraint solver using Newton-Raphson iteration
-//! - Feature operations (extrude, revolve, boolean)
+//! - Feature operations (extrude, revolve, boolean, hole)
 //! - Parametric history for design changes
+//! - Reference axes and points
 
 pub mod feature;
 pub mod history;
 pub mod kernel;
+pub mod reference;
 pub mod sketch;
 
 // Re-exports for convenience
-pub use feature::{BooleanOp, CadBody, ExtrudeDirection, Feature, FeatureError, FeatureResult};
-pub use history::{CadData, FeatureHistory, HistoryEntry};
+pub use feature::{
+    BooleanOp, CadBody, ExtrudeDirection, Feature, FeatureError, FeatureResult, HoleDepth,
+    HoleKind, HoleSize, STANDARD_HOLE_SIZES,
+};
+pub use history::{BodyChanges, CadData, FeatureHistory, HistoryEntry};
 pub use kernel::{
-    Axis3D, BooleanType, CadError, CadKernel, CadResult, NullKernel, Solid, TessellatedMesh,
-    Wire2D, default_kernel,
+    Axis3D, BooleanType, CadError, CadKernel, CadResult, FaceInfo, MeshEdge, NullKernel, Solid,
+    TessellatedMesh, Wire2D, default_kernel,
 };
+pub use reference::{RefAxis, RefPoint};
 pub use sketch::{
-    ConstraintSolver, Sketch, SketchConstraint, SketchEntity, SketchError, SketchPlane, SolveResult,
+    ConstraintSolver, ELLIPSE_SEGMENTS, SPLINE_SEGMENTS, Sketch, SketchClipboard, SketchConstraint,
+    SketchEntity, SketchError, SketchPlane, SolveDiagnostics, SolveResult, SolveStop,
+    sample_ellipse, sample_spline,
 };
@@ -0,0 +1,122 @@
+//! Reference geometry
+//!
+//! Datum axes and points that do not belong to any sketch or body. They can
+//! be used as revolve axes and projected into sketches to dimension against.
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::kernel::Axis3D;
+use crate::sketch::{Sketch, SketchError};
+
+/// A reference axis (infinite line) in world space
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefAxis {
+    /// Unique identifier
+    pub id: Uuid,
+    /// Name of the axis
+    pub name: String,
+    /// A point on the axis
+    pub origin: Vec3,
+    /// Direction of the axis (normalized)
+    pub direction: Vec3,
+}
+
+impl RefAxis {
+    /// Create a new reference axis
+    pub fn new(name: impl Into<String>, origin: Vec3, direction: Vec3) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            origin,
+            direction: direction.normalize_or(Vec3::Z),
+        }
+    }
+
+    /// Get the axis for kernel operations
+    pub fn axis(&self) -> Axis3D {
+        Axis3D::new(self.origin, self.direction)
+    }
+
+    /// Project the axis into a sketch as a fixed construction line
+    ///
+    /// The projected segment runs `half_length` along the axis to either
+    /// side of its origin. Fails with [`SketchError::DegenerateProjection`]
+    /// if the axis is perpendicular to the sketch plane.
+    pub fn project_into(&self, sketch: &mut Sketch, half_length: f32) -> Result<Uuid, SketchError> {
+        let offset = self.direction * half_length;
+        let lines = sketch.project_polyline(&[self.origin - offset, self.origin + offset], true)?;
+        Ok(lines[0])
+    }
+}
+
+/// A reference point in world space
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefPoint {
+    /// Unique identifier
+    pub id: Uuid,
+    /// Name of the point
+    pub name: String,
+    /// Position of the point
+    pub position: Vec3,
+}
+
+impl RefPoint {
+    /// Create a new reference point
+    pub fn new(name: impl Into<String>, position: Vec3) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            position,
+        }
+    }
+
+    /// Project the point into a sketch as a fixed point
+    pub fn project_into(&self, sketch: &mut Sketch) -> Result<Uuid, SketchError> {
+        let position = sketch.plane.to_local(self.position);
+        sketch.add_fixed_point(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sketch::{SketchEntity, SketchPlane};
+    use glam::Vec2;
+
+    #[test]
+    fn test_project_point() {
+        let mut sketch = Sketch::new("Sketch", SketchPlane::xy());
+        let point = RefPoint::new("Point", Vec3::new(1.0, 2.0, 5.0));
+        let id = point.project_into(&mut sketch).unwrap();
+
+        let position = sketch.get_entity(id).unwrap().position().unwrap();
+        assert!((position - Vec2::new(1.0, 2.0)).length() < 1e-6);
+        assert_eq!(sketch.constraints_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_project_axis() {
+        let mut sketch = Sketch::new("Sketch", SketchPlane::xy());
+
+        // An axis along the sketch normal projects to a point
+        let normal_axis = RefAxis::new("Normal", Vec3::ZERO, Vec3::Z);
+        assert!(matches!(
+            normal_axis.project_into(&mut sketch, 1.0),
+            Err(SketchError::DegenerateProjection)
+        ));
+
+        let axis = RefAxis::new("Axis", Vec3::new(0.0, 1.0, 3.0), Vec3::X);
+        let line = axis.project_into(&mut sketch, 2.0).unwrap();
+        assert!(sketch.is_construction(line));
+
+        let Some(SketchEntity::Line { start, end, .. }) = sketch.get_entity(line) else {
+            panic!("projected axis is not a line");
+        };
+        let start = sketch.get_entity(*start).unwrap().position().unwrap();
+        let end = sketch.get_entity(*end).unwrap().position().unwrap();
+        assert!((start - Vec2::new(-2.0, 1.0)).length() < 1e-5);
+        assert!((end - Vec2::new(2.0, 1.0)).length() < 1e-5);
+    }
+}
@@ -0,0 +1,245 @@
+//! Copy and paste of sketch geometry
+//!
+//! A [`SketchClipboard`] holds a self-contained set of entities, the points
+//! they reference and the constraints among them. Pasting assigns fresh IDs
+//! and maps positions onto the destination plane, so geometry can be copied
+//! within a sketch or between sketches on parallel planes.
+
+use std::collections::{HashMap, HashSet};
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{Sketch, SketchConstraint, SketchEntity, SketchError, SketchPlane};
+
+/// Copied sketch entities and the constraints among them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SketchClipboard {
+    /// Plane of the sketch the entities were copied from
+    plane: SketchPlane,
+    /// Copied entities, points first
+    entities: Vec<SketchEntity>,
+    /// Constraints whose referenced entities were all copied
+    constraints: Vec<SketchConstraint>,
+    /// IDs of copied construction geometry
+    construction: HashSet<Uuid>,
+}
+
+impl SketchClipboard {
+    /// Plane the entities were copied from
+    pub fn plane(&self) -> &SketchPlane {
+        &self.plane
+    }
+
+    /// Number of copied entities, including referenced points
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Check if nothing was copied
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+impl SketchPlane {
+    /// Check if another plane has a parallel normal
+    pub fn is_parallel_to(&self, other: &SketchPlane) -> bool {
+        self.normal.cross(other.normal).length_squared() < 1e-8
+    }
+}
+
+impl Sketch {
+    /// Copy entities and the points they reference to a clipboard
+    ///
+    /// Constraints are included when every entity they reference is copied.
+    /// Unknown IDs are ignored.
+    pub fn copy_entities(&self, ids: &[Uuid]) -> SketchClipboard {
+        let mut copied: HashSet<Uuid> = HashSet::new();
+        for id in ids {
+            if let Some(entity) = self.get_entity(*id) {
+                copied.insert(*id);
+                copied.extend(entity.referenced_points());
+            }
+        }
+
+        let mut entities: Vec<SketchEntity> = copied
+            .iter()
+            .filter_map(|id| self.get_entity(*id).cloned())
+            .collect();
+        entities.sort_by_key(|e| !e.is_point());
+
+        let constraints = self
+            .constraints_iter()
+            .filter(|c| c.referenced_entities().iter().all(|id| copied.contains(id)))
+            .cloned()
+            .collect();
+
+        let construction = copied
+            .iter()
+            .copied()
+            .filter(|id| self.is_construction(*id))
+            .collect();
+
+        SketchClipboard {
+            plane: self.plane,
+            entities,
+            constraints,
+            construction,
+        }
+    }
+
+    /// Paste clipboard contents into this sketch with new IDs
+    ///
+    /// Positions are carried over through world space onto this sketch's
+    /// plane and then shifted by `offset`. Returns the IDs of the pasted
+    /// entities in clipboard order.
+    pub fn paste(
+        &mut self,
+        clipboard: &SketchClipboard,
+        offset: Vec2,
+    ) -> Result<Vec<Uuid>, SketchError> {
+        let (source, target) = (clipboard.plane, self.plane);
+        if !source.is_parallel_to(&target) {
+            return Err(SketchError::IncompatiblePlane);
+        }
+        let flipped = source.normal.dot(target.normal) < 0.0;
+        let map_point = |p: Vec2| target.to_local(source.to_world(p)) + offset;
+        let map_direction = |d: Vec2| {
+            let world = source.x_axis * d.x + source.y_axis() * d.y;
+            Vec2::new(world.dot(target.x_axis), world.dot(target.y_axis()))
+        };
+
+        let id_map: HashMap<Uuid, Uuid> = clipboard
+            .entities
+            .iter()
+            .map(|e| e.id())
+            .chain(clipboard.constraints.iter().map(|c| c.id()))
+            .map(|id| (id, Uuid::new_v4()))
+            .collect();
+
+        let mut pasted = Vec::with_capacity(clipboard.entities.len());
+        for entity in &clipboard.entities {
+            let mut entity = entity.clone();
+            entity.remap_ids(&id_map);
+            match &mut entity {
+                SketchEntity::Point { position, .. } => *position = map_point(*position),
+                SketchEntity::Ellipse { rotation, .. } => {
+                    *rotation = map_direction(Vec2::from_angle(*rotation)).to_angle();
+                }
+                // Mirrored planes reverse the arc's winding
+                SketchEntity::Arc { start, end, .. } if flipped => std::mem::swap(start, end),
+                _ => {}
+            }
+            if clipboard.construction.contains(&entity.id()) {
+                self.set_construction(entity.id(), true);
+            }
+            pasted.push(self.add_entity(entity));
+        }
+
+        for constraint in &clipboard.constraints {
+            let mut constraint = constraint.clone();
+            constraint.remap_ids(&id_map);
+            if let SketchConstraint::Fixed { x, y, .. } = &mut constraint {
+                let position = map_point(Vec2::new(*x, *y));
+                (*x, *y) = (position.x, position.y);
+            }
+            self.add_constraint(constraint)?;
+        }
+
+        Ok(pasted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+
+    fn point_position(sketch: &Sketch, id: Uuid) -> Vec2 {
+        match sketch.get_entity(id) {
+            Some(SketchEntity::Point { position, .. }) => *position,
+            other => panic!("expected point, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_copy_includes_referenced_points_and_constraints() {
+        let mut sketch = Sketch::default();
+        let (_, lines) = sketch.add_rectangle(Vec2::ZERO, Vec2::new(10.0, 5.0));
+        sketch
+            .add_constraint(SketchConstraint::horizontal(lines[0]))
+            .unwrap();
+        sketch
+            .add_constraint(SketchConstraint::parallel(lines[0], lines[2]))
+            .unwrap();
+
+        let clipboard = sketch.copy_entities(&[lines[0]]);
+
+        // One line and its two points; the parallel constraint needs lines[2]
+        assert_eq!(clipboard.len(), 3);
+        assert!(clipboard.entities[0].is_point());
+        assert_eq!(clipboard.constraints.len(), 1);
+    }
+
+    #[test]
+    fn test_paste_remaps_ids_and_offsets() {
+        let mut sketch = Sketch::default();
+        let (points, lines) = sketch.add_rectangle(Vec2::ZERO, Vec2::new(10.0, 5.0));
+        sketch
+            .add_constraint(SketchConstraint::horizontal(lines[0]))
+            .unwrap();
+        let clipboard = sketch.copy_entities(&lines);
+
+        let pasted = sketch.paste(&clipboard, Vec2::new(1.0, 2.0)).unwrap();
+
+        assert_eq!(pasted.len(), 8);
+        assert_eq!(sketch.entities().len(), 16);
+        assert_eq!(sketch.constraints().len(), 2);
+        assert!(
+            pasted
+                .iter()
+                .all(|id| !points.contains(id) && !lines.contains(id))
+        );
+
+        let new_constraint = sketch
+            .constraints_iter()
+            .find(|c| !c.references_entity(lines[0]))
+            .unwrap();
+        assert!(
+            new_constraint
+                .referenced_entities()
+                .iter()
+                .all(|id| pasted.contains(id))
+        );
+
+        let origin_copy = pasted
+            .iter()
+            .copied()
+            .filter(|id| sketch.get_entity(*id).is_some_and(|e| e.is_point()))
+            .map(|id| point_position(&sketch, id))
+            .fold(Vec2::splat(f32::MAX), Vec2::min);
+        assert!((origin_copy - Vec2::new(1.0, 2.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_paste_onto_offset_parallel_plane() {
+        let mut source = Sketch::default();
+        let point = source.add_point(Vec2::new(3.0, 4.0));
+        let clipboard = source.copy_entities(&[point]);
+
+        let plane = SketchPlane::new(Vec3::new(1.0, 0.0, 5.0), Vec3::Z, Vec3::X);
+        let mut target = Sketch::new("Target", plane);
+        let pasted = target.paste(&clipboard, Vec2::ZERO).unwrap();
+
+        assert!((point_position(&target, pasted[0]) - Vec2::new(2.0, 4.0)).length() < 1e-5);
+
+        let mut side = Sketch::new("Side", SketchPlane::xz());
+        assert!(matches!(
+            side.paste(&clipboard, Vec2::ZERO),
+            Err(SketchError::IncompatiblePlane)
+        ));
+    }
+}
@@ -3,6 +3,8 @@
 //! Defines geometric and dimensional constraints that can be applied
 //! to sketch entities.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -295,6 +297,58 @@ impl SketchConstraint {
         }
     }
 
+    /// Replace this constraint's ID and the entity IDs it references
+    ///
+    /// IDs missing from `map` are left unchanged.
+    pub fn remap_ids(&mut self, map: &HashMap<Uuid, Uuid>) {
+        let ids: Vec<&mut Uuid> = match self {
+            SketchConstraint::Coincident { id, point1, point2 }
+            | SketchConstraint::HorizontalDistance {
+                id, point1, point2, ..
+            }
+            | SketchConstraint::VerticalDistance {
+                id, point1, point2, ..
+            } => vec![id, point1, point2],
+            SketchConstraint::Horizontal { id, line }
+            | SketchConstraint::Vertical { id, line }
+            | SketchConstraint::Length { id, line, .. } => vec![id, line],
+            SketchConstraint::Parallel { id, line1, line2 }
+            | SketchConstraint::Perpendicular { id, line1, line2 }
+            | SketchConstraint::EqualLength { id, line1, line2 }
+            | SketchConstraint::Angle {
+                id, line1, line2, ..
+            } => vec![id, line1, line2],
+            SketchConstraint::Tangent { id, curve1, curve2 } => vec![id, curve1, curve2],
+            SketchConstraint::EqualRadius {
+                id,
+                circle1,
+                circle2,
+            } => vec![id, circle1, circle2],
+            SketchConstraint::PointOnCurve { id, point, curve } => vec![id, point, curve],
+            SketchConstraint::Midpoint { id, point, line } => vec![id, point, line],
+            SketchConstraint::Symmetric {
+                id,
+                entity1,
+                entity2,
+                axis,
+            } => vec![id, entity1, entity2, axis],
+            SketchConstraint::Fixed { id, point, .. } => vec![id, point],
+            SketchConstraint::Distance {
+                id,
+                entity1,
+                entity2,
+                ..
+            } => vec![id, entity1, entity2],
+            SketchConstraint::Radius { id, circle, .. }
+            | SketchConstraint::Diameter { id, circle, .. } => vec![id, circle],
+        };
+        for id in ids {
+            if let Some(new_id) = map.get(id) {
+                *id = *new_id;
+            }
+        }
+    }
+
     /// Check if this constraint references a specific entity
     pub fn references_entity(&self, entity_id: Uuid) -> bool {
         self.referenced_entities().contains(&entity_id)
@@ -462,6 +516,15 @@ impl SketchConstraint {
         }
     }
 
+    /// Create a diameter constraint
+    pub fn diameter(circle: Uuid, value: f32) -> Self {
+        SketchConstraint::Diameter {
+            id: Uuid::new_v4(),
+            circle,
+            value,
+        }
+    }
+
     /// Create a fixed constraint
     pub fn fixed(point: Uuid, x: f32, y: f32) -> Self {
         SketchConstraint::Fixed {
@@ -472,6 +535,25 @@ impl SketchConstraint {
         }
     }
 
+    /// Create a tangent constraint between two curves
+    pub fn tangent(curve1: Uuid, curve2: Uuid) -> Self {
+        SketchConstraint::Tangent {
+            id: Uuid::new_v4(),
+            curve1,
+            curve2,
+        }
+    }
+
+    /// Create a symmetric constraint about an axis line
+    pub fn symmetric(entity1: Uuid, entity2: Uuid, axis: Uuid) -> Self {
+        SketchConstraint::Symmetric {
+            id: Uuid::new_v4(),
+            entity1,
+            entity2,
+            axis,
+        }
+    }
+
     /// Create an angle constraint
     pub fn angle(line1: Uuid, line2: Uuid, value: f32) -> Self {
         SketchConstraint::Angle {
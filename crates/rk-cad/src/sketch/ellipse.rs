@@ -0,0 +1,127 @@
+//! Ellipses in sketches
+//!
+//! An ellipse is placed by its center point; its radii and the rotation of
+//! its major axis are stored on the entity, as the solver does not constrain
+//! them.
+
+use std::f32::consts::TAU;
+
+use glam::Vec2;
+use uuid::Uuid;
+
+use super::{Sketch, SketchEntity};
+
+/// Line segments a full ellipse is sampled with
+pub const ELLIPSE_SEGMENTS: u32 = 64;
+
+/// Sample an ellipse as a closed polyline
+///
+/// The returned points start on the positive major axis, run
+/// counter-clockwise and repeat the first point at the end.
+pub fn sample_ellipse(
+    center: Vec2,
+    major_radius: f32,
+    minor_radius: f32,
+    rotation: f32,
+    segments: u32,
+) -> Vec<Vec2> {
+    let segments = segments.max(3);
+    let axis = Vec2::from_angle(rotation);
+    (0..=segments)
+        .map(|i| {
+            let t = Vec2::from_angle(TAU * (i % segments) as f32 / segments as f32);
+            center + axis.rotate(Vec2::new(t.x * major_radius, t.y * minor_radius))
+        })
+        .collect()
+}
+
+impl Sketch {
+    /// Add an ellipse around an existing center point
+    ///
+    /// The larger radius always becomes the major axis; if `minor_radius`
+    /// is the larger one, the radii are swapped and the major axis turned a
+    /// quarter turn so the shape is unchanged.
+    pub fn add_ellipse(
+        &mut self,
+        center: Uuid,
+        major_radius: f32,
+        minor_radius: f32,
+        rotation: f32,
+    ) -> Uuid {
+        let (major_radius, minor_radius, rotation) = if minor_radius > major_radius {
+            (minor_radius, major_radius, rotation + TAU / 4.0)
+        } else {
+            (major_radius, minor_radius, rotation)
+        };
+        self.add_entity(SketchEntity::Ellipse {
+            id: Uuid::new_v4(),
+            center,
+            major_radius,
+            minor_radius,
+            rotation,
+        })
+    }
+
+    /// Sample an ellipse entity as a closed polyline
+    ///
+    /// Returns `None` if the entity is not an ellipse or its center is
+    /// missing. See [`sample_ellipse`] for the layout.
+    pub fn ellipse_polyline(&self, id: Uuid) -> Option<Vec<Vec2>> {
+        let Some(SketchEntity::Ellipse {
+            center,
+            major_radius,
+            minor_radius,
+            rotation,
+            ..
+        }) = self.get_entity(id)
+        else {
+            return None;
+        };
+        let center = self.get_entity(*center)?.position()?;
+        Some(sample_ellipse(
+            center,
+            *major_radius,
+            *minor_radius,
+            *rotation,
+            ELLIPSE_SEGMENTS,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_rotated_ellipse() {
+        let samples = sample_ellipse(Vec2::new(1.0, 1.0), 2.0, 1.0, TAU / 4.0, 4);
+
+        assert_eq!(samples.len(), 5);
+        assert_eq!(samples.first(), samples.last());
+        // Major axis turned to point along +Y
+        assert!((samples[0] - Vec2::new(1.0, 3.0)).length() < 1e-5);
+        assert!((samples[1] - Vec2::new(0.0, 1.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_add_ellipse_keeps_major_axis_largest() {
+        let mut sketch = Sketch::default();
+        let center = sketch.add_point(Vec2::ZERO);
+        let ellipse = sketch.add_ellipse(center, 1.0, 3.0, 0.0);
+
+        let Some(SketchEntity::Ellipse {
+            major_radius,
+            minor_radius,
+            rotation,
+            ..
+        }) = sketch.get_entity(ellipse)
+        else {
+            panic!("not an ellipse");
+        };
+        assert_eq!((*major_radius, *minor_radius), (3.0, 1.0));
+        assert!((rotation - TAU / 4.0).abs() < 1e-6);
+
+        let samples = sketch.ellipse_polyline(ellipse).unwrap();
+        assert!((samples[0] - Vec2::new(0.0, 3.0)).length() < 1e-5);
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! Defines the basic geometric elements that can be used in sketches.
 
+use std::collections::HashMap;
+
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -125,6 +127,35 @@ impl SketchEntity {
         }
     }
 
+    /// Replace this entity's ID and the point IDs it references
+    ///
+    /// IDs missing from `map` are left unchanged.
+    pub fn remap_ids(&mut self, map: &HashMap<Uuid, Uuid>) {
+        let ids: Vec<&mut Uuid> = match self {
+            SketchEntity::Point { id, .. } => vec![id],
+            SketchEntity::Line { id, start, end } => vec![id, start, end],
+            SketchEntity::Arc {
+                id,
+                center,
+                start,
+                end,
+                ..
+            } => vec![id, center, start, end],
+            SketchEntity::Circle { id, center, .. } => vec![id, center],
+            SketchEntity::Ellipse { id, center, .. } => vec![id, center],
+            SketchEntity::Spline {
+                id, control_points, ..
+            } => std::iter::once(id)
+                .chain(control_points.iter_mut())
+                .collect(),
+        };
+        for id in ids {
+            if let Some(new_id) = map.get(id) {
+                *id = *new_id;
+            }
+        }
+    }
+
     /// Get the degrees of freedom for this entity type
     ///
     /// This is the number of independent parameters needed to fully define
@@ -136,7 +167,7 @@ impl SketchEntity {
             SketchEntity::Arc { .. } => 1,     // radius (points define the rest)
             SketchEntity::Circle { .. } => 1,  // radius (center is a point)
             SketchEntity::Ellipse { .. } => 3, // major radius, minor radius, rotation
-            SketchEntity::Spline { control_points, .. } => control_points.len() as u32 * 2,
+            SketchEntity::Spline { .. } => 0,  // defined by its control points
         }
     }
 
@@ -0,0 +1,153 @@
+//! Mirroring sketch geometry across a line
+//!
+//! Mirrored copies get their own points, each tied to its original by a
+//! [`SketchConstraint::Symmetric`] about the mirror line, so the copy follows
+//! when the original half is edited and re-solved.
+
+use std::collections::HashMap;
+
+use glam::Vec2;
+use uuid::Uuid;
+
+use super::{Sketch, SketchConstraint, SketchEntity, SketchError};
+
+impl Sketch {
+    /// Create mirrored copies of entities across a line
+    ///
+    /// Points referenced by the entities are mirrored too, and every copied
+    /// point is constrained symmetric to its original. The axis itself is
+    /// skipped if it is part of `ids`. Returns the IDs of the new entities.
+    pub fn mirror_entities(&mut self, ids: &[Uuid], axis: Uuid) -> Result<Vec<Uuid>, SketchError> {
+        let Some(SketchEntity::Line { start, end, .. }) = self.get_entity(axis) else {
+            return Err(SketchError::InvalidConstraint(format!(
+                "Mirror axis {} is not a line",
+                axis
+            )));
+        };
+        let origin = self.get_point_position(*start)?;
+        let direction = (self.get_point_position(*end)? - origin).normalize_or_zero();
+        if direction == Vec2::ZERO {
+            return Err(SketchError::InvalidConstraint(
+                "Mirror axis has zero length".into(),
+            ));
+        }
+        let reflect = |p: Vec2| {
+            let along = origin + direction * (p - origin).dot(direction);
+            2.0 * along - p
+        };
+
+        let mut sources = Vec::new();
+        let mut points = Vec::new();
+        for id in ids.iter().copied().filter(|id| *id != axis) {
+            let entity = self.get_entity(id).ok_or(SketchError::EntityNotFound(id))?;
+            if entity.is_point() {
+                points.push(id);
+            } else {
+                points.extend(entity.referenced_points());
+                sources.push(entity.clone());
+            }
+        }
+
+        let mut created = Vec::new();
+        let mut point_map: HashMap<Uuid, Uuid> = HashMap::new();
+        for point in points {
+            if point_map.contains_key(&point) {
+                continue;
+            }
+            let copy = self.add_point(reflect(self.get_point_position(point)?));
+            self.set_construction(copy, self.is_construction(point));
+            point_map.insert(point, copy);
+            created.push(copy);
+            self.add_constraint(SketchConstraint::symmetric(point, copy, axis))?;
+        }
+
+        for source in sources {
+            let is_construction = self.is_construction(source.id());
+            let mut entity = source;
+            let new_id = Uuid::new_v4();
+            let mut id_map = point_map.clone();
+            id_map.insert(entity.id(), new_id);
+            entity.remap_ids(&id_map);
+            match &mut entity {
+                // Reflection reverses the winding
+                SketchEntity::Arc { start, end, .. } => std::mem::swap(start, end),
+                SketchEntity::Ellipse { rotation, .. } => {
+                    let major = Vec2::from_angle(*rotation);
+                    *rotation = (2.0 * direction * major.dot(direction) - major).to_angle();
+                }
+                _ => {}
+            }
+            self.set_construction(new_id, is_construction);
+            created.push(self.add_entity(entity));
+        }
+
+        Ok(created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sketch::SolveResult;
+
+    #[test]
+    fn test_mirror_line_across_vertical_axis() {
+        let mut sketch = Sketch::default();
+        let a0 = sketch.add_point(Vec2::new(0.0, -5.0));
+        let a1 = sketch.add_point(Vec2::new(0.0, 5.0));
+        let axis = sketch.add_line(a0, a1);
+        let p0 = sketch.add_point(Vec2::new(1.0, 0.0));
+        let p1 = sketch.add_point(Vec2::new(3.0, 2.0));
+        let line = sketch.add_line(p0, p1);
+
+        let created = sketch.mirror_entities(&[line, axis], axis).unwrap();
+
+        // Two mirrored points and one line; the axis is not copied
+        assert_eq!(created.len(), 3);
+        assert_eq!(sketch.constraints().len(), 2);
+        let Some(SketchEntity::Line { start, end, .. }) = sketch.get_entity(created[2]) else {
+            panic!("expected mirrored line");
+        };
+        assert!(![p0, p1].contains(start) && ![p0, p1].contains(end));
+        assert_eq!(
+            sketch.get_point_position(*start).unwrap(),
+            Vec2::new(-1.0, 0.0)
+        );
+        assert_eq!(
+            sketch.get_point_position(*end).unwrap(),
+            Vec2::new(-3.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn test_mirrored_copy_follows_original() {
+        let mut sketch = Sketch::default();
+        let a0 = sketch.add_point(Vec2::new(0.0, 0.0));
+        let a1 = sketch.add_point(Vec2::new(0.0, 1.0));
+        let axis = sketch.add_line(a0, a1);
+        let p = sketch.add_point(Vec2::new(2.0, 1.0));
+        let created = sketch.mirror_entities(&[p], axis).unwrap();
+
+        for id in [a0, a1] {
+            let position = sketch.get_point_position(id).unwrap();
+            sketch
+                .add_constraint(SketchConstraint::fixed(id, position.x, position.y))
+                .unwrap();
+        }
+        sketch
+            .add_constraint(SketchConstraint::fixed(p, 4.0, 3.0))
+            .unwrap();
+
+        let result = sketch.solve();
+        assert!(!matches!(result, SolveResult::Failed { .. }), "{result:?}");
+        let copy = sketch.get_point_position(created[0]).unwrap();
+        assert!((copy - Vec2::new(-4.0, 3.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_mirror_requires_line_axis() {
+        let mut sketch = Sketch::default();
+        let p = sketch.add_point(Vec2::ZERO);
+        assert!(sketch.mirror_entities(&[p], p).is_err());
+    }
+}
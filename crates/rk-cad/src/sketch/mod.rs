@@ -5,13 +5,22 @@
 //! - Constraints (coincident, parallel, perpendicular, dimensions)
 //! - Constraint solver using Newton-Raphson iteration
 
+mod clipboard;
 mod constraint;
+mod ellipse;
 mod entity;
+mod mirror;
+mod offset;
+mod project;
 mod solver;
+mod spline;
 
+pub use clipboard::*;
 pub use constraint::*;
+pub use ellipse::*;
 pub use entity::*;
 pub use solver::*;
+pub use spline::*;
 
 use glam::{Mat4, Quat, Vec2, Vec3};
 use serde::{Deserialize, Serialize};
@@ -19,6 +28,10 @@ use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 use uuid::Uuid;
 
+/// Distance under which separate points still close a profile loop, about
+/// the accuracy the constraint solver reaches
+const PROFILE_POINT_TOLERANCE: f32 = 1e-4;
+
 /// Sketch-related errors
 #[derive(Debug, Clone, Error)]
 pub enum SketchError {
@@ -36,6 +49,12 @@ pub enum SketchError {
 
     #[error("Profile extraction failed: {0}")]
     ProfileExtractionFailed(String),
+
+    #[error("Sketch plane is not parallel to the copied geometry")]
+    IncompatiblePlane,
+
+    #[error("Geometry projects to a single point on the sketch plane")]
+    DegenerateProjection,
 }
 
 /// A plane on which sketches are drawn
@@ -92,6 +111,23 @@ impl SketchPlane {
         }
     }
 
+    /// Create a plane through a point with the given normal
+    ///
+    /// The X axis is the world X axis projected onto the plane, or the world
+    /// Y axis when the normal is close to X, so faces parallel to the
+    /// reference planes get the same axes as [`Self::xy`], [`Self::xz`] and
+    /// [`Self::yz`].
+    pub fn from_origin_normal(origin: Vec3, normal: Vec3) -> Self {
+        let normal = normal.normalize();
+        let reference = if normal.x.abs() > 0.9 {
+            Vec3::Y
+        } else {
+            Vec3::X
+        };
+        let x_axis = reference - normal * reference.dot(normal);
+        Self::new(origin, normal, x_axis)
+    }
+
     /// Get the Y axis of the plane
     pub fn y_axis(&self) -> Vec3 {
         self.normal.cross(self.x_axis).normalize()
@@ -147,6 +183,15 @@ pub struct Sketch {
     /// Degrees of freedom remaining
     #[serde(default)]
     dof: u32,
+    /// Entities that can still move after the last solve
+    #[serde(default)]
+    free_entities: HashSet<Uuid>,
+    /// Solver internals of the last solve
+    #[serde(skip)]
+    diagnostics: Option<SolveDiagnostics>,
+    /// Number of edits made since the sketch was created or loaded
+    #[serde(skip)]
+    revision: u64,
 }
 
 impl Default for Sketch {
@@ -167,6 +212,9 @@ impl Sketch {
             construction: HashSet::new(),
             is_solved: true,
             dof: 0,
+            free_entities: HashSet::new(),
+            diagnostics: None,
+            revision: 0,
         }
     }
 
@@ -181,16 +229,33 @@ impl Sketch {
             construction: HashSet::new(),
             is_solved: true,
             dof: 0,
+            free_entities: HashSet::new(),
+            diagnostics: None,
+            revision: 0,
         }
     }
 
     // ============== Entity Management ==============
 
+    /// Record an edit that invalidates the last solve
+    fn mark_edited(&mut self) {
+        self.is_solved = false;
+        self.revision += 1;
+    }
+
+    /// Get the number of edits made since the sketch was created or loaded
+    ///
+    /// Solving moves entities and counts as an edit too, so compare against
+    /// the revision read after the last solve to detect user changes.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
     /// Add an entity to the sketch
     pub fn add_entity(&mut self, entity: SketchEntity) -> Uuid {
         let id = entity.id();
         self.entities.insert(id, entity);
-        self.is_solved = false;
+        self.mark_edited();
         id
     }
 
@@ -201,7 +266,7 @@ impl Sketch {
 
     /// Get a mutable entity by ID
     pub fn get_entity_mut(&mut self, id: Uuid) -> Option<&mut SketchEntity> {
-        self.is_solved = false;
+        self.mark_edited();
         self.entities.get_mut(&id)
     }
 
@@ -220,7 +285,7 @@ impl Sketch {
         }
 
         self.construction.remove(&id);
-        self.is_solved = false;
+        self.mark_edited();
         self.entities.remove(&id)
     }
 
@@ -247,7 +312,7 @@ impl Sketch {
 
         let id = constraint.id();
         self.constraints.insert(id, constraint);
-        self.is_solved = false;
+        self.mark_edited();
         Ok(id)
     }
 
@@ -258,7 +323,7 @@ impl Sketch {
 
     /// Remove a constraint
     pub fn remove_constraint(&mut self, id: Uuid) -> Option<SketchConstraint> {
-        self.is_solved = false;
+        self.mark_edited();
         self.constraints.remove(&id)
     }
 
@@ -300,19 +365,35 @@ impl Sketch {
         self.dof
     }
 
+    /// Check if an entity still has freedom after the last solve
+    ///
+    /// Only meaningful while [`is_solved`](Self::is_solved) is true.
+    pub fn is_entity_free(&self, id: Uuid) -> bool {
+        self.free_entities.contains(&id)
+    }
+
+    /// Iteration count and residuals of the last solve, if solved since
+    /// the sketch was created or loaded
+    pub fn solve_diagnostics(&self) -> Option<SolveDiagnostics> {
+        self.diagnostics
+    }
+
     /// Solve the sketch constraints
     pub fn solve(&mut self) -> SolveResult {
         let mut solver = ConstraintSolver::new();
         let result = solver.solve(self);
+        self.diagnostics = Some(solver.diagnostics());
 
         match &result {
             SolveResult::FullyConstrained => {
                 self.is_solved = true;
                 self.dof = 0;
+                self.free_entities.clear();
             }
             SolveResult::UnderConstrained { dof } => {
                 self.is_solved = true;
                 self.dof = *dof;
+                self.free_entities = self.find_free_entities(&solver.free_points(self));
             }
             SolveResult::OverConstrained { .. } => {
                 self.is_solved = false;
@@ -325,33 +406,68 @@ impl Sketch {
         result
     }
 
+    /// Entities that move with a free point or have an unconstrained size
+    fn find_free_entities(&self, free_points: &HashSet<Uuid>) -> HashSet<Uuid> {
+        // Circle radii are not solver variables, only dimensioned ones count as fixed
+        let sized: HashSet<Uuid> = self
+            .constraints
+            .values()
+            .filter_map(|c| match c {
+                SketchConstraint::Radius { circle, .. }
+                | SketchConstraint::Diameter { circle, .. } => Some(*circle),
+                _ => None,
+            })
+            .collect();
+
+        self.entities
+            .values()
+            .filter(|entity| match entity {
+                SketchEntity::Point { id, .. } => free_points.contains(id),
+                SketchEntity::Circle { id, center, .. } => {
+                    free_points.contains(center) || !sized.contains(id)
+                }
+                // Axes and rotation are never constrained
+                SketchEntity::Ellipse { .. } => true,
+                _ => entity
+                    .referenced_points()
+                    .iter()
+                    .any(|p| free_points.contains(p)),
+            })
+            .map(|entity| entity.id())
+            .collect()
+    }
+
     // ============== Profile Extraction ==============
 
     /// Extract closed profiles from the sketch for extrusion
     ///
-    /// Returns a list of closed wire profiles (excluding construction geometry)
+    /// Returns a list of closed wire profiles (excluding construction geometry).
+    /// Loops are traced through lines and open splines sharing end points;
+    /// circles, ellipses and closed splines form profiles on their own.
+    /// Entities are visited in ID order, so a profile keeps its index across
+    /// saves and reloads and features can refer to it by index.
     pub fn extract_profiles(&self) -> Result<Vec<crate::kernel::Wire2D>, SketchError> {
-        // For now, implement a simple profile extraction
-        // that works with lines forming closed loops
-
         let mut profiles = Vec::new();
         let mut used_entities: HashSet<Uuid> = HashSet::new();
 
-        // Find all line entities that are not construction
-        let lines: Vec<&SketchEntity> = self
-            .entities
-            .values()
-            .filter(|e| matches!(e, SketchEntity::Line { .. }) && !self.is_construction(e.id()))
+        let mut entities: Vec<&SketchEntity> = self.entities.values().collect();
+        entities.sort_by_key(|e| e.id());
+
+        // Find all chainable entities that are not construction
+        let edges: Vec<Uuid> = entities
+            .iter()
+            .filter(|e| self.profile_edge_ends(e).is_some())
+            .map(|e| e.id())
             .collect();
 
         // Try to form closed loops
-        for start_line in &lines {
-            if used_entities.contains(&start_line.id()) {
+        for start_edge in edges {
+            if used_entities.contains(&start_edge) {
                 continue;
             }
 
-            if let Some(profile) = self.trace_closed_loop(start_line.id(), &used_entities) {
-                for id in &profile {
+            if let Some(profile) = self.trace_closed_loop(start_edge, &used_entities) {
+                for (id, _) in &profile {
                     used_entities.insert(*id);
                 }
 
@@ -363,15 +479,36 @@ impl Sketch {
             }
         }
 
-        // Also check for circles (single entity profiles)
-        for entity in self.entities.values() {
+        // Also check for single entity profiles
+        for entity in entities {
             if self.is_construction(entity.id()) {
                 continue;
             }
 
-            if let SketchEntity::Circle { center, radius, .. } = entity {
-                let center_pos = self.get_point_position(*center)?;
-                profiles.push(crate::kernel::Wire2D::circle(center_pos, *radius, 32));
+            match entity {
+                SketchEntity::Circle { center, radius, .. } => {
+                    let center_pos = self.get_point_position(*center)?;
+                    profiles.push(crate::kernel::Wire2D::circle(center_pos, *radius, 32));
+                }
+                SketchEntity::Ellipse { id, .. } => {
+                    let mut points = self
+                        .ellipse_polyline(*id)
+                        .ok_or(SketchError::EntityNotFound(*id))?;
+                    points.pop();
+                    profiles.push(crate::kernel::Wire2D::new(points, true));
+                }
+                SketchEntity::Spline {
+                    id, closed: true, ..
+                } => {
+                    let mut points = self
+                        .spline_polyline(*id)
+                        .ok_or(SketchError::EntityNotFound(*id))?;
+                    points.pop();
+                    if points.len() >= 3 {
+                        profiles.push(crate::kernel::Wire2D::new(points, true));
+                    }
+                }
+                _ => {}
             }
         }
 
@@ -384,66 +521,106 @@ impl Sketch {
         Ok(profiles)
     }
 
-    /// Trace a closed loop starting from a line
-    fn trace_closed_loop(&self, start_id: Uuid, used: &HashSet<Uuid>) -> Option<Vec<Uuid>> {
-        let start = self.entities.get(&start_id)?;
-        let SketchEntity::Line {
-            start: start_point,
-            end: first_end,
-            ..
-        } = start
-        else {
+    /// End points of an entity that can be chained into a profile loop
+    fn profile_edge_ends(&self, entity: &SketchEntity) -> Option<(Uuid, Uuid)> {
+        if self.is_construction(entity.id()) {
             return None;
-        };
+        }
+        match entity {
+            SketchEntity::Line { start, end, .. } => Some((*start, *end)),
+            SketchEntity::Spline {
+                control_points,
+                closed: false,
+                ..
+            } if control_points.len() >= 2 => {
+                Some((control_points[0], control_points[control_points.len() - 1]))
+            }
+            _ => None,
+        }
+    }
 
-        let mut loop_entities = vec![start_id];
-        let mut current_end = *first_end;
-        let target = *start_point;
+    /// Check if two points are the same point or lie on top of each other
+    fn points_coincide(&self, a: Uuid, b: Uuid) -> bool {
+        a == b
+            || matches!(
+                (self.get_point_position(a), self.get_point_position(b)),
+                (Ok(a), Ok(b)) if a.distance(b) <= PROFILE_POINT_TOLERANCE
+            )
+    }
 
-        // Follow connected lines
+    /// Trace a closed loop starting from a line or open spline
+    ///
+    /// Edges join where their end points coincide, whether or not they share
+    /// the point entity. Returns the entities in loop order, each with
+    /// whether it is traversed
+    /// from its end to its start.
+    fn trace_closed_loop(&self, start_id: Uuid, used: &HashSet<Uuid>) -> Option<Vec<(Uuid, bool)>> {
+        let (start_point, first_end) = self.profile_edge_ends(self.entities.get(&start_id)?)?;
+
+        let mut loop_entities = vec![(start_id, false)];
+        let mut current_end = first_end;
+        let target = start_point;
+
+        // Follow connected edges
         for _ in 0..100 {
             // Limit iterations
-            if current_end == target {
+            if self.points_coincide(current_end, target) {
                 return Some(loop_entities);
             }
 
-            // Find next connected line
-            let next = self.entities.values().find(|e| {
-                if used.contains(&e.id()) || loop_entities.contains(&e.id()) {
-                    return false;
+            // Find next connected edge
+            let next = self.entities.values().find_map(|e| {
+                let id = e.id();
+                if used.contains(&id) || loop_entities.iter().any(|(used, _)| *used == id) {
+                    return None;
                 }
-                if let SketchEntity::Line { start, end, .. } = e {
-                    *start == current_end || *end == current_end
+                let (start, end) = self.profile_edge_ends(e)?;
+                if self.points_coincide(start, current_end) {
+                    Some((id, false, end))
+                } else if self.points_coincide(end, current_end) {
+                    Some((id, true, start))
                 } else {
-                    false
+                    None
                 }
             });
 
-            match next {
-                Some(SketchEntity::Line { id, start, end, .. }) => {
-                    loop_entities.push(*id);
-                    current_end = if *start == current_end { *end } else { *start };
-                }
-                _ => return None,
-            }
+            let (id, reversed, far_end) = next?;
+            loop_entities.push((id, reversed));
+            current_end = far_end;
         }
 
         None
     }
 
-    /// Convert entity IDs to a list of 2D points
-    fn entities_to_points(&self, entity_ids: &[Uuid]) -> Result<Vec<Vec2>, SketchError> {
+    /// Convert a traced loop to a list of 2D points
+    ///
+    /// Each entity contributes the points from where the loop enters it up
+    /// to, but not including, where it leaves.
+    fn entities_to_points(&self, entities: &[(Uuid, bool)]) -> Result<Vec<Vec2>, SketchError> {
         let mut points = Vec::new();
 
-        for id in entity_ids {
+        for &(id, reversed) in entities {
             let entity = self
                 .entities
-                .get(id)
-                .ok_or(SketchError::EntityNotFound(*id))?;
+                .get(&id)
+                .ok_or(SketchError::EntityNotFound(id))?;
 
-            if let SketchEntity::Line { start, .. } = entity {
-                let pos = self.get_point_position(*start)?;
-                points.push(pos);
+            match entity {
+                SketchEntity::Line { start, end, .. } => {
+                    let first = if reversed { end } else { start };
+                    points.push(self.get_point_position(*first)?);
+                }
+                SketchEntity::Spline { .. } => {
+                    let mut samples = self
+                        .spline_polyline(id)
+                        .ok_or(SketchError::EntityNotFound(id))?;
+                    if reversed {
+                        samples.reverse();
+                    }
+                    samples.pop();
+                    points.extend(samples);
+                }
+                _ => {}
             }
         }
 
@@ -505,6 +682,178 @@ impl Sketch {
         })
     }
 
+    /// Add an arc continuing tangentially from a line or arc
+    ///
+    /// The arc starts at `at`, an endpoint of `from`, leaves in the direction
+    /// `from` was travelling and ends at a new point at `end`. A tangent
+    /// constraint between the two curves is added. Returns the arc and its
+    /// end point.
+    pub fn add_tangent_arc(
+        &mut self,
+        from: Uuid,
+        at: Uuid,
+        end: Vec2,
+    ) -> Result<(Uuid, Uuid), SketchError> {
+        let start = self.get_point_position(at)?;
+        let direction = match self.entities.get(&from) {
+            Some(SketchEntity::Line {
+                start: s, end: e, ..
+            }) if *s == at || *e == at => {
+                let other = if *e == at { *s } else { *e };
+                start - self.get_point_position(other)?
+            }
+            Some(SketchEntity::Arc {
+                center,
+                start: s,
+                end: e,
+                ..
+            }) if *s == at || *e == at => {
+                // Arcs run counter-clockwise from start to end
+                let radial = start - self.get_point_position(*center)?;
+                if *e == at {
+                    radial.perp()
+                } else {
+                    -radial.perp()
+                }
+            }
+            Some(_) => {
+                return Err(SketchError::InvalidConstraint(format!(
+                    "Entity {} is not a line or arc ending at {}",
+                    from, at
+                )));
+            }
+            None => return Err(SketchError::EntityNotFound(from)),
+        }
+        .normalize_or_zero();
+
+        // The center lies on the normal at the start and on the chord's bisector
+        let normal = direction.perp();
+        let chord = end - start;
+        let along_normal = chord.dot(normal);
+        if direction == Vec2::ZERO || along_normal.abs() < 1e-6 * chord.length().max(1.0) {
+            return Err(SketchError::InvalidConstraint(
+                "Tangent arc end must not lie on the tangent line".into(),
+            ));
+        }
+        let offset = chord.length_squared() / (2.0 * along_normal);
+        let center = self.add_point(start + normal * offset);
+        let end_point = self.add_point(end);
+
+        // Centers to the left turn counter-clockwise
+        let arc = if offset > 0.0 {
+            self.add_arc(center, at, end_point, offset.abs())
+        } else {
+            self.add_arc(center, end_point, at, offset.abs())
+        };
+        self.add_constraint(SketchConstraint::tangent(from, arc))?;
+
+        Ok((arc, end_point))
+    }
+
+    /// Current radius of a circle or arc
+    pub fn curve_radius(&self, curve: Uuid) -> Option<f32> {
+        match self.entities.get(&curve)? {
+            SketchEntity::Circle { radius, .. } => Some(*radius),
+            SketchEntity::Arc { center, start, .. } => Some(
+                self.get_point_position(*center)
+                    .ok()?
+                    .distance(self.get_point_position(*start).ok()?),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Distance from `point` to an arc, measured radially
+    ///
+    /// Arcs sweep counter-clockwise from start to end. Returns `None` when
+    /// `point` lies outside that sweep, so the rest of the circle does not
+    /// count as near the arc, or when `arc` is not an arc.
+    pub fn arc_distance(&self, arc: Uuid, point: Vec2) -> Option<f32> {
+        let SketchEntity::Arc {
+            center, start, end, ..
+        } = self.entities.get(&arc)?
+        else {
+            return None;
+        };
+        let center = self.get_point_position(*center).ok()?;
+        let start = self.get_point_position(*start).ok()? - center;
+        let end = self.get_point_position(*end).ok()? - center;
+        let offset = point - center;
+        let sweep = start.angle_to(end).rem_euclid(std::f32::consts::TAU);
+        if start.angle_to(offset).rem_euclid(std::f32::consts::TAU) > sweep {
+            return None;
+        }
+        Some((offset.length() - start.length()).abs())
+    }
+
+    /// Dimension the radius (or diameter) of a circle or arc
+    ///
+    /// The geometry is resized to match right away, keeping arc endpoints on
+    /// their rays from the center, and any earlier radius or diameter
+    /// dimension of the curve is replaced. Returns the new constraint's ID.
+    pub fn dimension_radius(
+        &mut self,
+        curve: Uuid,
+        value: f32,
+        diameter: bool,
+    ) -> Result<Uuid, SketchError> {
+        let new_radius = if diameter { value / 2.0 } else { value };
+        if new_radius <= 0.0 || !new_radius.is_finite() {
+            return Err(SketchError::InvalidConstraint(format!(
+                "Radius must be positive, got {}",
+                new_radius
+            )));
+        }
+
+        match self.entities.get(&curve).cloned() {
+            Some(SketchEntity::Circle { .. }) => {}
+            Some(SketchEntity::Arc {
+                center, start, end, ..
+            }) => {
+                let c = self.get_point_position(center)?;
+                for point in [start, end] {
+                    let ray = (self.get_point_position(point)? - c).normalize_or(Vec2::X);
+                    if let Some(SketchEntity::Point { position, .. }) =
+                        self.entities.get_mut(&point)
+                    {
+                        *position = c + ray * new_radius;
+                    }
+                }
+            }
+            Some(_) => {
+                return Err(SketchError::InvalidConstraint(format!(
+                    "Entity {} is not a circle or arc",
+                    curve
+                )));
+            }
+            None => return Err(SketchError::EntityNotFound(curve)),
+        }
+        if let Some(SketchEntity::Circle { radius, .. } | SketchEntity::Arc { radius, .. }) =
+            self.entities.get_mut(&curve)
+        {
+            *radius = new_radius;
+        }
+
+        let previous: Vec<Uuid> = self
+            .constraints
+            .values()
+            .filter(|c| {
+                matches!(c, SketchConstraint::Radius { circle, .. }
+                    | SketchConstraint::Diameter { circle, .. } if *circle == curve)
+            })
+            .map(|c| c.id())
+            .collect();
+        for id in previous {
+            self.remove_constraint(id);
+        }
+
+        self.add_constraint(if diameter {
+            SketchConstraint::diameter(curve, value)
+        } else {
+            SketchConstraint::radius(curve, value)
+        })
+    }
+
     /// Create a rectangle and return the corner point IDs and line IDs
     pub fn add_rectangle(&mut self, corner1: Vec2, corner2: Vec2) -> (Vec<Uuid>, Vec<Uuid>) {
         let corners = [
@@ -543,6 +892,106 @@ mod tests {
         assert!((back - point_2d).length() < 0.001);
     }
 
+    #[test]
+    fn test_plane_from_origin_normal() {
+        let plane = SketchPlane::from_origin_normal(Vec3::new(0.0, 0.0, 2.0), Vec3::Z * 3.0);
+        assert_eq!(plane.normal, Vec3::Z);
+        assert_eq!(plane.x_axis, Vec3::X);
+        assert_eq!(
+            plane.to_world(Vec2::new(1.0, 1.0)),
+            Vec3::new(1.0, 1.0, 2.0)
+        );
+
+        // A face pointing along -X falls back to the Y axis
+        let side = SketchPlane::from_origin_normal(Vec3::ZERO, Vec3::NEG_X);
+        assert_eq!(side.x_axis, Vec3::Y);
+
+        // A tilted face keeps an in-plane X axis
+        let tilted = SketchPlane::from_origin_normal(Vec3::ZERO, Vec3::new(1.0, 0.0, 1.0));
+        assert!(tilted.x_axis.dot(tilted.normal).abs() < 1e-6);
+        assert!((tilted.x_axis.length() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_revision_counts_edits() {
+        let mut sketch = Sketch::default();
+        let start = sketch.revision();
+        let p0 = sketch.add_point(Vec2::ZERO);
+        let p1 = sketch.add_point(Vec2::new(1.0, 0.0));
+        sketch.add_line(p0, p1);
+        assert_eq!(sketch.revision(), start + 3);
+
+        sketch.solve();
+        let solved = sketch.revision();
+        sketch.get_constraint(Uuid::nil());
+        assert_eq!(sketch.revision(), solved);
+        sketch.remove_entity(p1);
+        assert!(sketch.revision() > solved);
+    }
+
+    #[test]
+    fn test_extract_profile_from_coincident_points() {
+        // A square drawn without snapping: every line has its own end points
+        let mut sketch = Sketch::default();
+        let corners =
+            [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].map(|(x, y)| Vec2::new(x, y));
+        for i in 0..4 {
+            let start = sketch.add_point(corners[i]);
+            let end = sketch.add_point(corners[(i + 1) % 4] + Vec2::splat(2e-5));
+            sketch.add_line(start, end);
+        }
+
+        let profiles = sketch.extract_profiles().unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].points.len(), 4);
+        assert!((profiles[0].signed_area().abs() - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_extract_spline_profiles() {
+        let mut sketch = Sketch::default();
+        // An arch: open spline over a straight base line, entered reversed
+        let a = sketch.add_point(Vec2::new(0.0, 0.0));
+        let b = sketch.add_point(Vec2::new(2.0, 0.0));
+        let top = sketch.add_point(Vec2::new(1.0, 1.0));
+        sketch.add_line(a, b);
+        sketch.add_spline(vec![a, top, b], false);
+
+        let profiles = sketch.extract_profiles().unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].points.len(), 1 + 2 * SPLINE_SEGMENTS as usize);
+        assert!(
+            profiles[0]
+                .points
+                .iter()
+                .any(|p| (*p - Vec2::new(1.0, 1.0)).length() < 1e-5)
+        );
+
+        // A closed spline is a profile on its own
+        let mut sketch = Sketch::default();
+        let points = [(3.0, 0.0), (4.0, 1.0), (5.0, 0.0)]
+            .map(|(x, y)| sketch.add_point(Vec2::new(x, y)))
+            .to_vec();
+        sketch.add_spline(points, true);
+        let profiles = sketch.extract_profiles().unwrap();
+        assert_eq!(profiles[0].points.len(), 3 * SPLINE_SEGMENTS as usize);
+    }
+
+    #[test]
+    fn test_extract_ellipse_profile() {
+        let mut sketch = Sketch::default();
+        let center = sketch.add_point(Vec2::new(1.0, 2.0));
+        let ellipse = sketch.add_ellipse(center, 3.0, 1.0, 0.0);
+
+        let profiles = sketch.extract_profiles().unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].points.len(), ELLIPSE_SEGMENTS as usize);
+        assert!((profiles[0].points[0] - Vec2::new(4.0, 2.0)).length() < 1e-5);
+
+        sketch.set_construction(ellipse, true);
+        assert!(sketch.extract_profiles().is_err());
+    }
+
     #[test]
     fn test_add_rectangle() {
         let mut sketch = Sketch::default();
@@ -552,4 +1001,96 @@ mod tests {
         assert_eq!(lines.len(), 4);
         assert_eq!(sketch.entities().len(), 8); // 4 points + 4 lines
     }
+
+    #[test]
+    fn test_add_tangent_arc_after_line() {
+        let mut sketch = Sketch::default();
+        let p0 = sketch.add_point(Vec2::new(0.0, 0.0));
+        let p1 = sketch.add_point(Vec2::new(2.0, 0.0));
+        let line = sketch.add_line(p0, p1);
+
+        // Turning left: the center is above the shared endpoint
+        let (arc, end) = sketch
+            .add_tangent_arc(line, p1, Vec2::new(3.0, 1.0))
+            .unwrap();
+        let Some(SketchEntity::Arc {
+            center,
+            start,
+            radius,
+            ..
+        }) = sketch.get_entity(arc).cloned()
+        else {
+            panic!("expected arc");
+        };
+        assert_eq!(start, p1);
+        assert!((radius - 1.0).abs() < 1e-5);
+        assert!((sketch.get_point_position(center).unwrap() - Vec2::new(2.0, 1.0)).length() < 1e-5);
+        assert_eq!(sketch.constraints().len(), 1);
+
+        // Turning right from the arc's end reverses the stored direction
+        let (arc2, _) = sketch
+            .add_tangent_arc(arc, end, Vec2::new(5.0, 1.0))
+            .unwrap();
+        let Some(SketchEntity::Arc { end: arc2_end, .. }) = sketch.get_entity(arc2) else {
+            panic!("expected arc");
+        };
+        assert_eq!(*arc2_end, end);
+        assert!(!matches!(sketch.solve(), SolveResult::Failed { .. }));
+
+        // Straight ahead has no tangent arc
+        assert!(
+            sketch
+                .add_tangent_arc(line, p1, Vec2::new(4.0, 0.0))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_dimension_radius_resizes_curve() {
+        let mut sketch = Sketch::default();
+        let c = sketch.add_point(Vec2::ZERO);
+        let circle = sketch.add_circle(c, 1.0);
+        sketch.dimension_radius(circle, 5.0, true).unwrap();
+        assert_eq!(sketch.curve_radius(circle), Some(2.5));
+
+        // Re-dimensioning replaces the earlier constraint
+        sketch.dimension_radius(circle, 3.0, false).unwrap();
+        assert_eq!(sketch.constraints().len(), 1);
+        assert_eq!(sketch.curve_radius(circle), Some(3.0));
+
+        let s = sketch.add_point(Vec2::new(1.0, 0.0));
+        let e = sketch.add_point(Vec2::new(0.0, 1.0));
+        let arc = sketch.add_arc(c, s, e, 1.0);
+        sketch.dimension_radius(arc, 2.0, false).unwrap();
+        assert_eq!(sketch.get_point_position(e).unwrap(), Vec2::new(0.0, 2.0));
+        assert!(!matches!(sketch.solve(), SolveResult::Failed { .. }));
+        assert!((sketch.curve_radius(arc).unwrap() - 2.0).abs() < 1e-4);
+
+        let p = sketch.add_point(Vec2::ONE);
+        assert!(sketch.dimension_radius(p, 1.0, false).is_err());
+        assert!(sketch.dimension_radius(circle, -1.0, false).is_err());
+    }
+
+    #[test]
+    fn test_arc_distance_respects_sweep() {
+        let mut sketch = Sketch::default();
+        let c = sketch.add_point(Vec2::ZERO);
+        let s = sketch.add_point(Vec2::new(1.0, 0.0));
+        let e = sketch.add_point(Vec2::new(0.0, 1.0));
+        let quarter = sketch.add_arc(c, s, e, 1.0);
+
+        let inside = Vec2::from_angle(std::f32::consts::FRAC_PI_4) * 1.1;
+        assert!((sketch.arc_distance(quarter, inside).unwrap() - 0.1).abs() < 1e-5);
+        // Points on the rest of the circle are not near the arc
+        assert_eq!(sketch.arc_distance(quarter, Vec2::new(-1.0, 0.0)), None);
+        assert_eq!(sketch.arc_distance(quarter, Vec2::new(0.7, -0.7)), None);
+
+        // Sweeps that wrap past the positive X axis
+        let wrapped = sketch.add_arc(c, e, s, 1.0);
+        assert_eq!(sketch.arc_distance(wrapped, inside), None);
+        assert!(sketch.arc_distance(wrapped, Vec2::new(0.7, -0.7)).is_some());
+        assert!(sketch.arc_distance(wrapped, Vec2::new(-1.0, 0.0)).is_some());
+
+        assert_eq!(sketch.arc_distance(c, Vec2::ZERO), None);
+    }
 }
@@ -0,0 +1,484 @@
+//! Offsetting chains of sketch curves
+//!
+//! A connected chain of lines and arcs is copied at a constant distance.
+//! Closed chains are oriented counter-clockwise first, so a positive distance
+//! grows the profile and a negative one shrinks it. Corners that open up are
+//! closed with arcs around the original vertex; corners that overlap are
+//! trimmed at the intersection of the neighbouring offset curves.
+
+use std::collections::HashMap;
+
+use glam::Vec2;
+use uuid::Uuid;
+
+use super::{Sketch, SketchEntity, SketchError};
+
+/// Distances below this are treated as coincident
+const EPSILON: f32 = 1e-5;
+
+/// A line or arc with resolved positions, oriented along the chain
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    start: Vec2,
+    end: Vec2,
+    /// Center, radius and direction for arcs; `None` for lines
+    arc: Option<(Vec2, f32, bool)>,
+}
+
+impl Segment {
+    fn line(start: Vec2, end: Vec2) -> Self {
+        Self {
+            start,
+            end,
+            arc: None,
+        }
+    }
+
+    fn arc(center: Vec2, start: Vec2, end: Vec2, ccw: bool) -> Self {
+        Self {
+            start,
+            end,
+            arc: Some((center, start.distance(center), ccw)),
+        }
+    }
+
+    fn reversed(self) -> Self {
+        Self {
+            start: self.end,
+            end: self.start,
+            arc: self.arc.map(|(center, radius, ccw)| (center, radius, !ccw)),
+        }
+    }
+
+    /// Unit direction of travel at a point on the segment
+    fn tangent_at(&self, point: Vec2) -> Vec2 {
+        match self.arc {
+            None => (self.end - self.start).normalize_or_zero(),
+            Some((center, _, ccw)) => {
+                let t = (point - center).perp().normalize_or_zero();
+                if ccw { t } else { -t }
+            }
+        }
+    }
+
+    /// Point halfway along the segment
+    fn midpoint(&self) -> Vec2 {
+        match self.arc {
+            None => (self.start + self.end) * 0.5,
+            Some((center, radius, ccw)) => {
+                let a0 = (self.start - center).to_angle();
+                let mut sweep = (self.end - center).to_angle() - a0;
+                if ccw && sweep <= 0.0 {
+                    sweep += std::f32::consts::TAU;
+                } else if !ccw && sweep >= 0.0 {
+                    sweep -= std::f32::consts::TAU;
+                }
+                center + Vec2::from_angle(a0 + sweep * 0.5) * radius
+            }
+        }
+    }
+
+    /// Copy of the segment moved `distance` to the right of its direction
+    fn offset(&self, distance: f32) -> Result<Self, SketchError> {
+        match self.arc {
+            None => {
+                let shift = -self.tangent_at(self.start).perp() * distance;
+                Ok(Self::line(self.start + shift, self.end + shift))
+            }
+            Some((center, radius, ccw)) => {
+                // Travelling counter-clockwise, the right side is away from the center
+                let new_radius = if ccw {
+                    radius + distance
+                } else {
+                    radius - distance
+                };
+                if new_radius <= EPSILON {
+                    return Err(SketchError::InvalidConstraint(
+                        "Offset distance collapses an arc".into(),
+                    ));
+                }
+                let scale = |p: Vec2| center + (p - center).normalize() * new_radius;
+                Ok(Self::arc(center, scale(self.start), scale(self.end), ccw))
+            }
+        }
+    }
+
+    /// Intersections of the infinite line or full circle through two segments
+    fn intersect(&self, other: &Self) -> Vec<Vec2> {
+        match (self.arc, other.arc) {
+            (None, None) => {
+                let d1 = self.end - self.start;
+                let d2 = other.end - other.start;
+                let denom = d1.perp_dot(d2);
+                if denom.abs() < EPSILON {
+                    return Vec::new();
+                }
+                let t = (other.start - self.start).perp_dot(d2) / denom;
+                vec![self.start + d1 * t]
+            }
+            (None, Some((center, radius, _))) => line_circle(self.start, self.end, center, radius),
+            (Some((center, radius, _)), None) => {
+                line_circle(other.start, other.end, center, radius)
+            }
+            (Some((c1, r1, _)), Some((c2, r2, _))) => circle_circle(c1, r1, c2, r2),
+        }
+    }
+}
+
+fn line_circle(a: Vec2, b: Vec2, center: Vec2, radius: f32) -> Vec<Vec2> {
+    let d = (b - a).normalize_or_zero();
+    let foot = a + d * (center - a).dot(d);
+    let h2 = radius * radius - foot.distance_squared(center);
+    if h2 < 0.0 {
+        return Vec::new();
+    }
+    let h = h2.sqrt();
+    vec![foot - d * h, foot + d * h]
+}
+
+fn circle_circle(c1: Vec2, r1: f32, c2: Vec2, r2: f32) -> Vec<Vec2> {
+    let d = c1.distance(c2);
+    if d < EPSILON || d > r1 + r2 || d < (r1 - r2).abs() {
+        return Vec::new();
+    }
+    let a = (r1 * r1 - r2 * r2 + d * d) / (2.0 * d);
+    let h = (r1 * r1 - a * a).max(0.0).sqrt();
+    let dir = (c2 - c1) / d;
+    let base = c1 + dir * a;
+    vec![base + dir.perp() * h, base - dir.perp() * h]
+}
+
+impl Sketch {
+    /// Create an offset copy of a connected chain of lines and arcs
+    ///
+    /// The copy lies `distance` to the right of the chain direction; for
+    /// closed chains that is outside the profile. Open chains are traversed
+    /// from the free end of the first listed end segment. Returns the IDs of
+    /// all created points and curves.
+    pub fn offset_profile(
+        &mut self,
+        entities: &[Uuid],
+        distance: f32,
+    ) -> Result<Vec<Uuid>, SketchError> {
+        if distance.abs() < EPSILON {
+            return Err(SketchError::InvalidConstraint(
+                "Offset distance must not be zero".into(),
+            ));
+        }
+        let (chain, closed) = self.ordered_chain(entities)?;
+
+        // Offset every segment, then resolve the corners between neighbours
+        let mut offsets = chain
+            .iter()
+            .map(|s| s.offset(distance))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut joints: Vec<Option<Segment>> = vec![None; chain.len()];
+        let corners = if closed { chain.len() } else { chain.len() - 1 };
+        for i in 0..corners {
+            let j = (i + 1) % chain.len();
+            let vertex = chain[i].end;
+            let (end, start) = (offsets[i].end, offsets[j].start);
+            if end.distance(start) < EPSILON {
+                offsets[j].start = end;
+                continue;
+            }
+            let turn = chain[i]
+                .tangent_at(vertex)
+                .perp_dot(chain[j].tangent_at(vertex));
+            if turn * distance > 0.0 {
+                // Convex corner: round it around the original vertex
+                joints[i] = Some(Segment::arc(vertex, end, start, turn > 0.0));
+            } else if let Some(corner) =
+                offsets[i]
+                    .intersect(&offsets[j])
+                    .into_iter()
+                    .min_by(|a, b| {
+                        let ideal = (end + start) * 0.5;
+                        a.distance(ideal).total_cmp(&b.distance(ideal))
+                    })
+            {
+                // Concave corner: trim both curves to where they meet
+                offsets[i].end = corner;
+                offsets[j].start = corner;
+            } else {
+                joints[i] = Some(Segment::line(end, start));
+            }
+        }
+
+        // Trimming past a segment's far end means the offset swallowed it
+        if offsets.iter().zip(&chain).any(|(offset, original)| {
+            original.arc.is_none()
+                && (offset.end - offset.start).dot(original.end - original.start) <= 0.0
+        }) {
+            return Err(SketchError::InvalidConstraint(
+                "Offset distance collapses the profile".into(),
+            ));
+        }
+
+        let segments: Vec<Segment> = offsets
+            .into_iter()
+            .zip(joints)
+            .flat_map(|(segment, joint)| std::iter::once(segment).chain(joint))
+            .filter(|s| s.start.distance(s.end) > EPSILON)
+            .collect();
+        if segments.is_empty() {
+            return Err(SketchError::InvalidConstraint(
+                "Offset collapses the profile".into(),
+            ));
+        }
+
+        // Materialize, sharing the point between consecutive segments
+        let mut created = Vec::new();
+        let first = self.add_point(segments[0].start);
+        created.push(first);
+        let mut previous = first;
+        for (i, segment) in segments.iter().enumerate() {
+            let end = if closed && i == segments.len() - 1 {
+                first
+            } else {
+                let id = self.add_point(segment.end);
+                created.push(id);
+                id
+            };
+            let curve = match segment.arc {
+                None => self.add_line(previous, end),
+                Some((center, radius, ccw)) => {
+                    let center = self.add_point(center);
+                    created.push(center);
+                    let (start, end) = if ccw {
+                        (previous, end)
+                    } else {
+                        (end, previous)
+                    };
+                    self.add_arc(center, start, end, radius)
+                }
+            };
+            created.push(curve);
+            previous = end;
+        }
+
+        Ok(created)
+    }
+
+    /// Order lines and arcs into a single chain connected through shared points
+    ///
+    /// Returns the oriented segments and whether the chain is closed. Closed
+    /// chains are oriented counter-clockwise.
+    fn ordered_chain(&self, entities: &[Uuid]) -> Result<(Vec<Segment>, bool), SketchError> {
+        // (entity, start point, end point, segment in stored direction)
+        let mut pieces = Vec::new();
+        for id in entities {
+            let piece = match self.get_entity(*id) {
+                Some(SketchEntity::Line { start, end, .. }) => (
+                    *start,
+                    *end,
+                    Segment::line(
+                        self.get_point_position(*start)?,
+                        self.get_point_position(*end)?,
+                    ),
+                ),
+                Some(SketchEntity::Arc {
+                    center, start, end, ..
+                }) => (
+                    *start,
+                    *end,
+                    Segment::arc(
+                        self.get_point_position(*center)?,
+                        self.get_point_position(*start)?,
+                        self.get_point_position(*end)?,
+                        true,
+                    ),
+                ),
+                Some(_) => {
+                    return Err(SketchError::InvalidConstraint(
+                        "Only lines and arcs can be offset".into(),
+                    ));
+                }
+                None => return Err(SketchError::EntityNotFound(*id)),
+            };
+            pieces.push(piece);
+        }
+        if pieces.is_empty() {
+            return Err(SketchError::InvalidConstraint(
+                "Nothing selected to offset".into(),
+            ));
+        }
+
+        let mut by_point: HashMap<Uuid, Vec<usize>> = HashMap::new();
+        for (i, (start, end, _)) in pieces.iter().enumerate() {
+            by_point.entry(*start).or_default().push(i);
+            by_point.entry(*end).or_default().push(i);
+        }
+        if by_point.values().any(|uses| uses.len() > 2) {
+            return Err(SketchError::InvalidConstraint(
+                "Offset chain must not branch".into(),
+            ));
+        }
+        let is_free = |point: &Uuid| by_point[point].len() == 1;
+
+        // Start at a free end if there is one
+        let (mut current, mut forward) = pieces
+            .iter()
+            .enumerate()
+            .find_map(|(i, (start, end, _))| {
+                if is_free(start) {
+                    Some((i, true))
+                } else if is_free(end) {
+                    Some((i, false))
+                } else {
+                    None
+                }
+            })
+            .unwrap_or((0, true));
+        let closed = !pieces.iter().any(|(s, e, _)| is_free(s) || is_free(e));
+
+        let mut used = vec![false; pieces.len()];
+        let mut chain = Vec::with_capacity(pieces.len());
+        loop {
+            used[current] = true;
+            let (start, end, segment) = pieces[current];
+            chain.push(if forward { segment } else { segment.reversed() });
+            let tail = if forward { end } else { start };
+            let Some(&next) = by_point[&tail].iter().find(|&&i| !used[i]) else {
+                break;
+            };
+            forward = pieces[next].0 == tail;
+            current = next;
+        }
+        if chain.len() != pieces.len() {
+            return Err(SketchError::InvalidConstraint(
+                "Offset selection must be one connected chain".into(),
+            ));
+        }
+
+        if closed {
+            let area: f32 = chain
+                .iter()
+                .flat_map(|s| [(s.start, s.midpoint()), (s.midpoint(), s.end)])
+                .map(|(a, b)| a.perp_dot(b))
+                .sum();
+            if area < 0.0 {
+                chain = chain.into_iter().rev().map(Segment::reversed).collect();
+            }
+        }
+
+        Ok((chain, closed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curves(sketch: &Sketch, created: &[Uuid]) -> (usize, usize) {
+        let count = |f: fn(&SketchEntity) -> bool| {
+            created
+                .iter()
+                .filter(|id| sketch.get_entity(**id).is_some_and(f))
+                .count()
+        };
+        (
+            count(|e| matches!(e, SketchEntity::Line { .. })),
+            count(|e| matches!(e, SketchEntity::Arc { .. })),
+        )
+    }
+
+    fn point_positions(sketch: &Sketch, created: &[Uuid]) -> Vec<Vec2> {
+        created
+            .iter()
+            .filter_map(|id| match sketch.get_entity(*id) {
+                Some(SketchEntity::Point { position, .. }) => Some(*position),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_outward_offset_rounds_corners() {
+        let mut sketch = Sketch::default();
+        let (_, lines) = sketch.add_rectangle(Vec2::ZERO, Vec2::new(10.0, 10.0));
+
+        let created = sketch.offset_profile(&lines, 1.0).unwrap();
+
+        assert_eq!(curves(&sketch, &created), (4, 4));
+        let points = point_positions(&sketch, &created);
+        assert!(
+            points
+                .iter()
+                .any(|p| p.distance(Vec2::new(0.0, -1.0)) < 1e-5)
+        );
+        assert!(
+            points
+                .iter()
+                .any(|p| p.distance(Vec2::new(11.0, 10.0)) < 1e-5)
+        );
+        // Arc centers sit on the original corners
+        assert!(
+            points
+                .iter()
+                .any(|p| p.distance(Vec2::new(10.0, 10.0)) < 1e-5)
+        );
+    }
+
+    #[test]
+    fn test_inward_offset_trims_corners() {
+        for (a, b) in [
+            (Vec2::ZERO, Vec2::new(10.0, 10.0)),
+            // Clockwise winding gives the same result
+            (Vec2::new(10.0, 0.0), Vec2::new(0.0, 10.0)),
+        ] {
+            let mut sketch = Sketch::default();
+            let (_, lines) = sketch.add_rectangle(a, b);
+
+            let created = sketch.offset_profile(&lines, -1.0).unwrap();
+
+            assert_eq!(curves(&sketch, &created), (4, 0));
+            let points = point_positions(&sketch, &created);
+            assert_eq!(points.len(), 4);
+            for corner in [(1.0, 1.0), (9.0, 1.0), (9.0, 9.0), (1.0, 9.0)] {
+                let corner = Vec2::from(corner);
+                assert!(points.iter().any(|p| p.distance(corner) < 1e-4));
+            }
+        }
+    }
+
+    #[test]
+    fn test_open_chain_and_arc_offset() {
+        let mut sketch = Sketch::default();
+        let p0 = sketch.add_point(Vec2::new(0.0, 0.0));
+        let p1 = sketch.add_point(Vec2::new(4.0, 0.0));
+        let center = sketch.add_point(Vec2::new(4.0, 2.0));
+        let p2 = sketch.add_point(Vec2::new(6.0, 2.0));
+        let line = sketch.add_line(p0, p1);
+        let arc = sketch.add_arc(center, p1, p2, 2.0);
+
+        // Traversal starts at the arc's free end, so its right side is inside the arc
+        let created = sketch.offset_profile(&[arc, line], 0.5).unwrap();
+
+        assert_eq!(curves(&sketch, &created), (1, 1));
+        let points = point_positions(&sketch, &created);
+        assert!(
+            points
+                .iter()
+                .any(|p| p.distance(Vec2::new(0.0, 0.5)) < 1e-5)
+        );
+        assert!(
+            points
+                .iter()
+                .any(|p| p.distance(Vec2::new(5.5, 2.0)) < 1e-5)
+        );
+    }
+
+    #[test]
+    fn test_offset_rejects_branches_and_collapse() {
+        let mut sketch = Sketch::default();
+        let (points, lines) = sketch.add_rectangle(Vec2::ZERO, Vec2::new(2.0, 2.0));
+        assert!(sketch.offset_profile(&lines, -1.5).is_err());
+
+        let extra = sketch.add_point(Vec2::new(5.0, 5.0));
+        let branch = sketch.add_line(points[0], extra);
+        let mut selection = lines.clone();
+        selection.push(branch);
+        assert!(sketch.offset_profile(&selection, 0.5).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Projecting 3D geometry onto the sketch plane
+//!
+//! Projected geometry is static: every projected point is fixed where it
+//! lands, so re-solving the sketch keeps it in place but does not follow
+//! later changes to the source geometry.
+
+use glam::{Vec2, Vec3};
+use uuid::Uuid;
+
+use super::{Sketch, SketchConstraint, SketchError};
+
+/// Distances below this are treated as coincident
+const EPSILON: f32 = 1e-5;
+
+impl Sketch {
+    /// Add a point fixed at its position
+    pub(crate) fn add_fixed_point(&mut self, position: Vec2) -> Result<Uuid, SketchError> {
+        let point = self.add_point(position);
+        self.add_constraint(SketchConstraint::fixed(point, position.x, position.y))?;
+        Ok(point)
+    }
+
+    /// Project a 3D polyline onto the sketch plane as fixed lines
+    ///
+    /// Consecutive points that coincide or are collinear after projection
+    /// are merged, and a polyline whose ends meet is closed on a shared
+    /// point. Returns the IDs of the new lines, or
+    /// [`SketchError::DegenerateProjection`] if the polyline projects to a
+    /// single point.
+    pub fn project_polyline(
+        &mut self,
+        points: &[Vec3],
+        construction: bool,
+    ) -> Result<Vec<Uuid>, SketchError> {
+        let projected: Vec<Vec2> = points.iter().map(|p| self.plane.to_local(*p)).collect();
+        let mut path = simplify(&projected);
+        if path.len() < 2 {
+            return Err(SketchError::DegenerateProjection);
+        }
+        let closed = path.len() > 2 && path[0].distance(path[path.len() - 1]) < EPSILON;
+        if closed {
+            path.pop();
+        }
+
+        let point_ids = path
+            .iter()
+            .map(|p| self.add_fixed_point(*p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let segments = if closed {
+            point_ids.len()
+        } else {
+            point_ids.len() - 1
+        };
+        let lines = (0..segments)
+            .map(|i| {
+                let line = self.add_line(point_ids[i], point_ids[(i + 1) % point_ids.len()]);
+                self.set_construction(line, construction);
+                line
+            })
+            .collect();
+        Ok(lines)
+    }
+}
+
+/// Drop coincident points and interior points on a straight run
+fn simplify(points: &[Vec2]) -> Vec<Vec2> {
+    let mut path: Vec<Vec2> = Vec::with_capacity(points.len());
+    for &point in points {
+        if path
+            .last()
+            .is_some_and(|last| last.distance(point) < EPSILON)
+        {
+            continue;
+        }
+        if let [.., a, b] = path[..]
+            && (b - a).perp_dot(point - b).abs() < EPSILON * (b - a).length()
+            && (b - a).dot(point - b) > 0.0
+        {
+            path.pop();
+        }
+        path.push(point);
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sketch::{SketchEntity, SketchPlane};
+
+    fn line_ends(sketch: &Sketch, line: Uuid) -> (Vec2, Vec2) {
+        let Some(SketchEntity::Line { start, end, .. }) = sketch.get_entity(line) else {
+            panic!("not a line");
+        };
+        (
+            sketch.get_entity(*start).unwrap().position().unwrap(),
+            sketch.get_entity(*end).unwrap().position().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_project_edge_onto_plane() {
+        let mut sketch = Sketch::new("Sketch", SketchPlane::xy());
+        let edge = [
+            Vec3::new(0.0, 0.0, 5.0),
+            Vec3::new(1.0, 0.0, 4.0),
+            Vec3::new(2.0, 0.0, 3.0),
+            Vec3::new(2.0, 2.0, 3.0),
+        ];
+        let lines = sketch.project_polyline(&edge, true).unwrap();
+
+        // The collinear middle point is merged away
+        assert_eq!(lines.len(), 2);
+        let (start, end) = line_ends(&sketch, lines[0]);
+        assert!((start - Vec2::ZERO).length() < 1e-6);
+        assert!((end - Vec2::new(2.0, 0.0)).length() < 1e-6);
+        assert!(sketch.is_construction(lines[0]));
+        assert_eq!(sketch.constraints_iter().count(), 3);
+    }
+
+    #[test]
+    fn test_project_closed_loop_shares_point() {
+        let mut sketch = Sketch::new("Sketch", SketchPlane::xy());
+        let square = [
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(1.0, 0.0, 1.0),
+            Vec3::new(1.0, 1.0, 1.0),
+            Vec3::new(0.0, 1.0, 1.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ];
+        let lines = sketch.project_polyline(&square, false).unwrap();
+
+        assert_eq!(lines.len(), 4);
+        assert!(!sketch.is_construction(lines[0]));
+        let (first_start, _) = line_ends(&sketch, lines[0]);
+        let (_, last_end) = line_ends(&sketch, lines[3]);
+        assert_eq!(first_start, last_end);
+        assert_eq!(sketch.extract_profiles().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_project_perpendicular_edge_fails() {
+        let mut sketch = Sketch::new("Sketch", SketchPlane::xy());
+        let edge = [Vec3::new(1.0, 1.0, 0.0), Vec3::new(1.0, 1.0, 3.0)];
+        assert!(matches!(
+            sketch.project_polyline(&edge, false),
+            Err(SketchError::DegenerateProjection)
+        ));
+        assert!(sketch.entities().is_empty());
+    }
+}
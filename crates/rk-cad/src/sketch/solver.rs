@@ -5,7 +5,7 @@
 
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 use super::{Sketch, SketchConstraint, SketchEntity};
@@ -35,6 +35,37 @@ pub enum SolveResult {
     },
 }
 
+/// Why the solver stopped iterating
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SolveStop {
+    /// The residual dropped below the tolerance
+    #[default]
+    Converged,
+    /// The Jacobian had no usable solution, usually from conflicting constraints
+    SingularJacobian,
+    /// The iteration limit was reached first
+    IterationLimit,
+}
+
+/// Internals of the last solve, for debugging constraints
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SolveDiagnostics {
+    /// Newton-Raphson iterations run
+    pub iterations: usize,
+    /// Norm of the constraint errors before the first step
+    pub initial_residual: f32,
+    /// Norm of the constraint errors when the solver stopped
+    pub residual: f32,
+    pub stop: SolveStop,
+}
+
+impl SolveDiagnostics {
+    /// Whether the solver ended further from a solution than it started
+    pub fn diverged(&self) -> bool {
+        self.stop != SolveStop::Converged && self.residual > self.initial_residual
+    }
+}
+
 /// Constraint solver using Newton-Raphson iteration
 pub struct ConstraintSolver {
     /// Tolerance for convergence
@@ -43,6 +74,8 @@ pub struct ConstraintSolver {
     max_iterations: usize,
     /// Damping factor for Newton steps
     damping: f32,
+    /// Internals of the last solve
+    diagnostics: SolveDiagnostics,
 }
 
 impl Default for ConstraintSolver {
@@ -58,6 +91,7 @@ impl ConstraintSolver {
             tolerance: 1e-4, // Relaxed for f32 precision
             max_iterations: 200,
             damping: 0.8, // Slight damping for stability
+            diagnostics: SolveDiagnostics::default(),
         }
     }
 
@@ -79,8 +113,15 @@ impl ConstraintSolver {
         self
     }
 
+    /// Iteration count and residuals of the last [`solve`](Self::solve)
+    pub fn diagnostics(&self) -> SolveDiagnostics {
+        self.diagnostics
+    }
+
     /// Solve the constraints in the given sketch
     pub fn solve(&mut self, sketch: &mut Sketch) -> SolveResult {
+        self.diagnostics = SolveDiagnostics::default();
+
         // Build variable vector (point positions)
         let mut var_map = VariableMap::new();
         var_map.build_from_sketch(sketch);
@@ -96,9 +137,6 @@ impl ConstraintSolver {
         // Count constraint equations
         let n_equations: usize = sketch.constraints_iter().map(|c| c.equation_count()).sum();
 
-        // Check for over/under constrained
-        let dof = n_vars as i32 - n_equations as i32;
-
         if n_equations == 0 {
             return SolveResult::UnderConstrained { dof: n_vars as u32 };
         }
@@ -113,15 +151,18 @@ impl ConstraintSolver {
 
             // Check for convergence
             let error = f.iter().map(|e| e * e).sum::<f32>().sqrt();
+            if iteration == 0 {
+                self.diagnostics.initial_residual = error;
+            }
+            self.diagnostics.iterations = iteration;
+            self.diagnostics.residual = error;
             if error < self.tolerance {
-                if dof > 0 {
-                    return SolveResult::UnderConstrained { dof: dof as u32 };
-                } else if dof == 0 {
-                    return SolveResult::FullyConstrained;
-                } else {
-                    // Over-constrained but still converged
-                    return SolveResult::FullyConstrained;
-                }
+                // Redundant constraints do not remove freedom, so count by rank
+                let rank = row_space_basis(&self.compute_jacobian(sketch, &var_map, &x)).len();
+                return match n_vars - rank {
+                    0 => SolveResult::FullyConstrained,
+                    dof => SolveResult::UnderConstrained { dof: dof as u32 },
+                };
             }
 
             // Compute Jacobian
@@ -137,6 +178,7 @@ impl ConstraintSolver {
                 }
                 None => {
                     // Singular Jacobian - check for conflicts
+                    self.diagnostics.stop = SolveStop::SingularJacobian;
                     return SolveResult::Failed {
                         reason: format!(
                             "Singular Jacobian at iteration {} (possibly over-constrained)",
@@ -148,6 +190,8 @@ impl ConstraintSolver {
         }
 
         // Failed to converge
+        self.diagnostics.iterations = self.max_iterations;
+        self.diagnostics.stop = SolveStop::IterationLimit;
         SolveResult::Failed {
             reason: format!(
                 "Failed to converge after {} iterations",
@@ -156,6 +200,31 @@ impl ConstraintSolver {
         }
     }
 
+    /// Find points that can still move without violating any constraint
+    ///
+    /// A point coordinate is determined when it is fixed by the linearized
+    /// constraints at the current positions; points with an undetermined x or
+    /// y are returned.
+    pub fn free_points(&self, sketch: &Sketch) -> HashSet<Uuid> {
+        let mut var_map = VariableMap::new();
+        var_map.build_from_sketch(sketch);
+        let x = var_map.get_values(sketch);
+        let basis = row_space_basis(&self.compute_jacobian(sketch, &var_map, &x));
+
+        // A variable is determined if its unit vector lies in the row space
+        let determined = |index: usize| {
+            let projection: f64 = basis.iter().map(|row| row[index] * row[index]).sum();
+            projection > 1.0 - RANK_TOLERANCE
+        };
+
+        var_map
+            .point_indices
+            .iter()
+            .filter(|(_, index)| !determined(**index) || !determined(**index + 1))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
     /// Evaluate all constraint equations
     fn evaluate_constraints(&self, sketch: &Sketch, var_map: &VariableMap) -> Vec<f32> {
         let mut errors = Vec::new();
@@ -278,8 +347,14 @@ impl ConstraintSolver {
                 }
 
                 SketchConstraint::Radius { circle, value, .. } => {
-                    if let Some(SketchEntity::Circle { radius, .. }) = sketch.get_entity(*circle) {
-                        errors.push(*radius - *value);
+                    if let Some(radius) = self.curve_radius(sketch, var_map, *circle) {
+                        errors.push(radius - *value);
+                    }
+                }
+
+                SketchConstraint::Diameter { circle, value, .. } => {
+                    if let Some(radius) = self.curve_radius(sketch, var_map, *circle) {
+                        errors.push(2.0 * radius - *value);
                     }
                 }
 
@@ -313,6 +388,68 @@ impl ConstraintSolver {
                     }
                 }
 
+                SketchConstraint::Tangent { curve1, curve2, .. } => {
+                    let circle = |id: Uuid| match sketch.get_entity(id) {
+                        Some(SketchEntity::Circle { center, radius, .. }) => {
+                            Some((var_map.get_point_position(sketch, *center), *radius))
+                        }
+                        Some(SketchEntity::Arc { center, start, .. }) => {
+                            let c = var_map.get_point_position(sketch, *center);
+                            Some((c, c.distance(var_map.get_point_position(sketch, *start))))
+                        }
+                        _ => None,
+                    };
+                    let line = |id: Uuid| {
+                        self.get_line_endpoints(sketch, id).map(|(s, e)| {
+                            let a = var_map.get_point_position(sketch, s);
+                            (a, var_map.get_point_position(sketch, e) - a)
+                        })
+                    };
+
+                    match (
+                        line(*curve1),
+                        line(*curve2),
+                        circle(*curve1),
+                        circle(*curve2),
+                    ) {
+                        // Line-circle: distance from center to line equals radius
+                        (Some((a, d)), _, _, Some((c, r))) | (_, Some((a, d)), Some((c, r)), _) => {
+                            let d = d.normalize_or_zero();
+                            errors.push(d.perp_dot(c - a).abs() - r);
+                        }
+                        // Circle-circle: touching outside or inside, whichever is closer
+                        (_, _, Some((c1, r1)), Some((c2, r2))) => {
+                            let distance = c1.distance(c2);
+                            let outside = distance - (r1 + r2);
+                            let inside = distance - (r1 - r2).abs();
+                            errors.push(if outside.abs() < inside.abs() {
+                                outside
+                            } else {
+                                inside
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+
+                SketchConstraint::Symmetric {
+                    entity1,
+                    entity2,
+                    axis,
+                    ..
+                } => {
+                    if let Some((start, end)) = self.get_line_endpoints(sketch, *axis) {
+                        let p1 = var_map.get_point_position(sketch, *entity1);
+                        let p2 = var_map.get_point_position(sketch, *entity2);
+                        let a = var_map.get_point_position(sketch, start);
+                        let d = var_map.get_point_position(sketch, end) - a;
+                        let mid = (p1 + p2) * 0.5 - a;
+                        // Midpoint lies on the axis, and the pair is perpendicular to it
+                        errors.push(d.x * mid.y - d.y * mid.x);
+                        errors.push(d.dot(p2 - p1));
+                    }
+                }
+
                 // TODO: Implement remaining constraint types
                 _ => {}
             }
@@ -445,6 +582,19 @@ impl ConstraintSolver {
         Some(x)
     }
 
+    /// Radius of a circle, or of an arc measured to its start point
+    fn curve_radius(&self, sketch: &Sketch, var_map: &VariableMap, curve: Uuid) -> Option<f32> {
+        match sketch.get_entity(curve) {
+            Some(SketchEntity::Circle { radius, .. }) => Some(*radius),
+            Some(SketchEntity::Arc { center, start, .. }) => Some(
+                var_map
+                    .get_point_position(sketch, *center)
+                    .distance(var_map.get_point_position(sketch, *start)),
+            ),
+            _ => None,
+        }
+    }
+
     /// Get the start and end point IDs of a line entity
     fn get_line_endpoints(&self, sketch: &Sketch, line_id: Uuid) -> Option<(Uuid, Uuid)> {
         match sketch.get_entity(line_id) {
@@ -454,6 +604,35 @@ impl ConstraintSolver {
     }
 }
 
+/// Relative size below which a Jacobian row adds nothing to the rank
+const RANK_TOLERANCE: f64 = 1e-3;
+
+/// Orthonormal basis of the Jacobian row space (modified Gram-Schmidt)
+///
+/// Rows are scaled to unit length first, so the tolerance does not depend
+/// on sketch size.
+fn row_space_basis(jacobian: &[Vec<f32>]) -> Vec<Vec<f64>> {
+    let mut basis: Vec<Vec<f64>> = Vec::new();
+    for row in jacobian {
+        let mut v: Vec<f64> = row.iter().map(|&x| x as f64).collect();
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            continue;
+        }
+        v.iter_mut().for_each(|x| *x /= norm);
+        for q in &basis {
+            let dot: f64 = v.iter().zip(q).map(|(a, b)| a * b).sum();
+            v.iter_mut().zip(q).for_each(|(a, b)| *a -= dot * b);
+        }
+        let residual = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if residual > RANK_TOLERANCE.sqrt() {
+            v.iter_mut().for_each(|x| *x /= residual);
+            basis.push(v);
+        }
+    }
+    basis
+}
+
 /// Maps point IDs to variable indices
 struct VariableMap {
     /// Map from point ID to variable index (x = index, y = index + 1)
@@ -570,6 +749,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_solve_diagnostics() {
+        let mut sketch = Sketch::new("test", SketchPlane::xy());
+        let p = sketch.add_point(Vec2::new(5.0, 5.0));
+        sketch
+            .add_constraint(SketchConstraint::fixed(p, 0.0, 0.0))
+            .unwrap();
+        assert!(sketch.solve_diagnostics().is_none());
+
+        sketch.solve();
+        let diagnostics = sketch.solve_diagnostics().unwrap();
+        assert_eq!(diagnostics.stop, SolveStop::Converged);
+        assert!(diagnostics.iterations > 0);
+        assert!((diagnostics.initial_residual - 50f32.sqrt()).abs() < 1e-4);
+        assert!(diagnostics.residual < 1e-4);
+        assert!(!diagnostics.diverged());
+
+        // Conflicting constraints never converge
+        sketch
+            .add_constraint(SketchConstraint::fixed(p, 1.0, 0.0))
+            .unwrap();
+        assert!(matches!(sketch.solve(), SolveResult::Failed { .. }));
+        let diagnostics = sketch.solve_diagnostics().unwrap();
+        assert_ne!(diagnostics.stop, SolveStop::Converged);
+        assert!(diagnostics.residual > 0.1);
+    }
+
     #[test]
     fn test_fixed_constraint() {
         let mut sketch = Sketch::new("test", SketchPlane::xy());
@@ -640,4 +846,33 @@ mod tests {
             pos1
         );
     }
+
+    #[test]
+    fn test_free_entities_after_solve() {
+        let mut sketch = Sketch::new("test", SketchPlane::xy());
+        let p1 = sketch.add_point(Vec2::new(0.0, 0.0));
+        let p2 = sketch.add_point(Vec2::new(5.0, 1.0));
+        let line = sketch.add_line(p1, p2);
+        sketch
+            .add_constraint(SketchConstraint::fixed(p1, 0.0, 0.0))
+            .unwrap();
+        sketch
+            .add_constraint(SketchConstraint::horizontal(line))
+            .unwrap();
+
+        // The free end can still slide along the line
+        let result = sketch.solve();
+        assert!(matches!(result, SolveResult::UnderConstrained { dof: 1 }));
+        assert!(!sketch.is_entity_free(p1));
+        assert!(sketch.is_entity_free(p2));
+        assert!(sketch.is_entity_free(line));
+
+        sketch
+            .add_constraint(SketchConstraint::length(line, 4.0))
+            .unwrap();
+        let result = sketch.solve();
+        assert!(matches!(result, SolveResult::FullyConstrained));
+        assert!(!sketch.is_entity_free(line));
+        assert_eq!(sketch.degrees_of_freedom(), 0);
+    }
 }
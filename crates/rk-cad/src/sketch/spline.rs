@@ -0,0 +1,151 @@
+//! Splines through sketch points
+//!
+//! A [`SketchEntity::Spline`] passes through each of its control points, with
+//! every span between neighbouring points drawn as a uniform Catmull-Rom
+//! curve. The control points are regular point entities, so the solver moves
+//! them like any other geometry and the curve follows.
+
+use glam::Vec2;
+use uuid::Uuid;
+
+use super::{Sketch, SketchEntity};
+
+/// Line segments each span of a spline is sampled with
+pub const SPLINE_SEGMENTS: u32 = 16;
+
+/// Sample a Catmull-Rom spline through `points`
+///
+/// Each span between neighbouring points is split into `segments` pieces.
+/// The ends of an open spline continue straight out of their last span;
+/// a closed spline also joins the last point back to the first, and the
+/// returned polyline repeats the first point at the end.
+pub fn sample_spline(points: &[Vec2], closed: bool, segments: u32) -> Vec<Vec2> {
+    let n = points.len();
+    if n < 2 {
+        return points.to_vec();
+    }
+    let segments = segments.max(1);
+
+    // Neighbours around the control polygon, mirrored past the open ends
+    let point = |i: isize| -> Vec2 {
+        if closed {
+            points[i.rem_euclid(n as isize) as usize]
+        } else if i < 0 {
+            2.0 * points[0] - points[1]
+        } else if i as usize >= n {
+            2.0 * points[n - 1] - points[n - 2]
+        } else {
+            points[i as usize]
+        }
+    };
+
+    let spans = if closed { n } else { n - 1 };
+    let mut samples = Vec::with_capacity(spans * segments as usize + 1);
+    for span in 0..spans as isize {
+        let (p0, p1, p2, p3) = (
+            point(span - 1),
+            point(span),
+            point(span + 1),
+            point(span + 2),
+        );
+        for step in 0..segments {
+            let t = step as f32 / segments as f32;
+            let t2 = t * t;
+            let t3 = t2 * t;
+            samples.push(
+                0.5 * (2.0 * p1
+                    + (p2 - p0) * t
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                    + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3),
+            );
+        }
+    }
+    samples.push(if closed { points[0] } else { points[n - 1] });
+    samples
+}
+
+impl Sketch {
+    /// Add a spline through existing points
+    pub fn add_spline(&mut self, control_points: Vec<Uuid>, closed: bool) -> Uuid {
+        self.add_entity(SketchEntity::Spline {
+            id: Uuid::new_v4(),
+            control_points,
+            closed,
+        })
+    }
+
+    /// Sample a spline entity as a polyline
+    ///
+    /// Returns `None` if the entity is not a spline or one of its control
+    /// points is missing. See [`sample_spline`] for the layout.
+    pub fn spline_polyline(&self, id: Uuid) -> Option<Vec<Vec2>> {
+        let Some(SketchEntity::Spline {
+            control_points,
+            closed,
+            ..
+        }) = self.get_entity(id)
+        else {
+            return None;
+        };
+        let points = control_points
+            .iter()
+            .map(|p| self.get_entity(*p)?.position())
+            .collect::<Option<Vec<_>>>()?;
+        Some(sample_spline(&points, *closed, SPLINE_SEGMENTS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spline_passes_through_points() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(3.0, 1.0),
+        ];
+        let samples = sample_spline(&points, false, 8);
+
+        assert_eq!(samples.len(), 3 * 8 + 1);
+        for (i, point) in points.iter().enumerate() {
+            assert!((samples[i * 8] - *point).length() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_closed_spline_wraps() {
+        let points = [
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(-1.0, 0.0),
+            Vec2::new(0.0, -1.0),
+        ];
+        let samples = sample_spline(&points, true, 4);
+
+        assert_eq!(samples.len(), 4 * 4 + 1);
+        assert_eq!(samples.first(), samples.last());
+        // The curve bulges outside the control polygon towards a circle
+        let mid = samples[2];
+        assert!(mid.length() > std::f32::consts::FRAC_1_SQRT_2);
+    }
+
+    #[test]
+    fn test_spline_follows_moved_point() {
+        let mut sketch = Sketch::default();
+        let ids: Vec<Uuid> = [Vec2::ZERO, Vec2::new(1.0, 1.0), Vec2::new(2.0, 0.0)]
+            .into_iter()
+            .map(|p| sketch.add_point(p))
+            .collect();
+        let spline = sketch.add_spline(ids.clone(), false);
+
+        if let Some(SketchEntity::Point { position, .. }) = sketch.get_entity_mut(ids[1]) {
+            *position = Vec2::new(1.0, 3.0);
+        }
+        let samples = sketch.spline_polyline(spline).unwrap();
+        assert!((samples[SPLINE_SEGMENTS as usize] - Vec2::new(1.0, 3.0)).length() < 1e-5);
+        assert!(sketch.spline_polyline(ids[0]).is_none());
+    }
+}
@@ -0,0 +1,379 @@
+//! Headless URDF validation and re-export
+//!
+//! Imports a URDF, checks joints and inertias, and optionally writes a
+//! normalized URDF with its meshes re-exported as STL. Exits with status 1
+//! when validation fails and 2 on usage or I/O errors, so it can gate CI.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use rk_core::{ExportOptions, ImportOptions, InertiaMatrix, PrimitiveQuality, Project, StlUnit};
+
+const USAGE: &str = "\
+Usage: rk-cli <URDF> [OPTIONS]
+
+Options:
+  -o, --output <DIR>         Write the normalized URDF and meshes to DIR
+  -n, --name <NAME>          Robot name for the output (default: from the URDF)
+  -p, --package <NAME=PATH>  Map package://NAME to PATH (repeatable)
+      --ros-packages         Discover packages from the ROS environment
+      --recompute-inertia    Recompute link inertias and centers of mass from
+                             watertight meshes; links with open meshes keep
+                             their inertia
+      --bbox-inertia-fallback
+                             With --recompute-inertia, give links with open
+                             meshes a bounding-box estimate instead
+      --package-uri          Reference meshes with package:// URIs
+      --mesh-unit <UNIT>     Unit for exported meshes: m, mm, cm, in (default: m)
+      --precision <DIGITS>   Significant digits of exported numbers, 1 to 9, or
+                             0 for full precision (default: 6)
+      --primitive-quality <LEVEL>
+                             Tessellation of URDF primitives: low, medium, high
+                             (default: medium)
+  -h, --help                 Print this help";
+
+/// Parsed command line
+#[derive(Debug, Default)]
+struct Args {
+    urdf: PathBuf,
+    output: Option<PathBuf>,
+    name: Option<String>,
+    packages: Vec<(String, PathBuf)>,
+    ros_packages: bool,
+    recompute_inertia: bool,
+    bbox_inertia_fallback: bool,
+    package_uri: bool,
+    mesh_unit: Option<StlUnit>,
+    precision: Option<u32>,
+    primitive_quality: PrimitiveQuality,
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Parse arguments; `Ok(None)` means help was requested
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut parsed = Args::default();
+    let mut urdf = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or(format!("{flag} requires a value"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--output" => parsed.output = Some(value(&arg)?.into()),
+            "-n" | "--name" => parsed.name = Some(value(&arg)?),
+            "-p" | "--package" => {
+                let mapping = value(&arg)?;
+                let (name, path) = mapping
+                    .split_once('=')
+                    .ok_or(format!("expected NAME=PATH, got '{mapping}'"))?;
+                parsed.packages.push((name.to_string(), path.into()));
+            }
+            "--ros-packages" => parsed.ros_packages = true,
+            "--recompute-inertia" => parsed.recompute_inertia = true,
+            "--bbox-inertia-fallback" => parsed.bbox_inertia_fallback = true,
+            "--package-uri" => parsed.package_uri = true,
+            "--mesh-unit" => parsed.mesh_unit = Some(parse_unit(&value(&arg)?)?),
+            "--precision" => {
+                let digits = value(&arg)?;
+                let digits = digits
+                    .parse()
+                    .ok()
+                    .filter(|digits| (0..=9).contains(digits))
+                    .ok_or(format!("invalid precision '{digits}', expected 0 to 9"))?;
+                parsed.precision = Some(digits);
+            }
+            "--primitive-quality" => parsed.primitive_quality = parse_quality(&value(&arg)?)?,
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{flag}'")),
+            path => {
+                if urdf.replace(PathBuf::from(path)).is_some() {
+                    return Err("only one URDF path may be given".to_string());
+                }
+            }
+        }
+    }
+
+    if parsed.bbox_inertia_fallback && !parsed.recompute_inertia {
+        return Err("--bbox-inertia-fallback requires --recompute-inertia".to_string());
+    }
+    parsed.urdf = urdf.ok_or("missing URDF path")?;
+    Ok(Some(parsed))
+}
+
+fn parse_unit(unit: &str) -> Result<StlUnit, String> {
+    match unit {
+        "m" => Ok(StlUnit::Meters),
+        "mm" => Ok(StlUnit::Millimeters),
+        "cm" => Ok(StlUnit::Centimeters),
+        "in" => Ok(StlUnit::Inches),
+        _ => Err(format!(
+            "unknown mesh unit '{unit}' (expected m, mm, cm or in)"
+        )),
+    }
+}
+
+fn parse_quality(quality: &str) -> Result<PrimitiveQuality, String> {
+    match quality {
+        "low" => Ok(PrimitiveQuality::Low),
+        "medium" => Ok(PrimitiveQuality::Medium),
+        "high" => Ok(PrimitiveQuality::High),
+        _ => Err(format!(
+            "unknown primitive quality '{quality}' (expected low, medium or high)"
+        )),
+    }
+}
+
+/// Import, validate and optionally export; returns whether validation passed
+fn run(args: &Args) -> Result<bool, String> {
+    let mut options = if args.ros_packages {
+        ImportOptions::with_ros_packages()
+    } else {
+        ImportOptions::default()
+    };
+    for (name, path) in &args.packages {
+        options.add_package_path(name, path);
+    }
+    options.primitive_quality = args.primitive_quality;
+
+    let mut project = rk_core::import_urdf(&args.urdf, &options)
+        .map_err(|e| format!("failed to import {}: {e}", args.urdf.display()))?;
+    println!(
+        "Imported '{}': {} links, {} joints",
+        project.name,
+        project.assembly.links.len(),
+        project.assembly.joints.len()
+    );
+
+    if args.recompute_inertia {
+        recompute_inertia(&mut project, args.bbox_inertia_fallback);
+    }
+
+    let valid = validate(&project);
+
+    if let Some(output_dir) = &args.output {
+        if !valid {
+            eprintln!("Skipping export because validation failed");
+            return Ok(false);
+        }
+
+        let export_options = ExportOptions {
+            output_dir: output_dir.clone(),
+            robot_name: args.name.clone().unwrap_or_else(|| project.name.clone()),
+            use_package_uri: args.package_uri,
+            mesh_unit: args.mesh_unit.unwrap_or(StlUnit::Meters),
+            precision: match args.precision {
+                Some(0) => None,
+                Some(digits) => Some(digits),
+                None => Some(rk_core::DEFAULT_PRECISION),
+            },
+            ..ExportOptions::default()
+        };
+        rk_core::export_project_urdf(&project, &export_options)
+            .map_err(|e| format!("failed to export: {e}"))?;
+        println!(
+            "Wrote {}",
+            output_dir
+                .join(format!("{}.urdf", export_options.robot_name))
+                .display()
+        );
+    }
+
+    Ok(valid)
+}
+
+/// Recompute link inertias from their meshes, optionally falling back to
+/// bounding-box estimates for links whose mesh is not watertight
+fn recompute_inertia(project: &mut Project, bbox_fallback: bool) {
+    let report = project.recompute_mesh_inertias();
+    println!(
+        "Recomputed mesh inertia for {} links{}",
+        report.updated.len(),
+        name_list(&report.updated)
+    );
+    if report.skipped.is_empty() {
+        return;
+    }
+
+    if bbox_fallback {
+        bbox_inertia(project, &report.skipped);
+        println!(
+            "Used bounding-box inertia for {} links with open meshes{}",
+            report.skipped.len(),
+            name_list(&report.skipped)
+        );
+    } else {
+        eprintln!(
+            "warning: kept the inertia of {} links with open meshes{}",
+            report.skipped.len(),
+            name_list(&report.skipped)
+        );
+    }
+}
+
+/// Replace the inertia of the named links by the bounding-box estimate of
+/// their parts, centered on the box
+fn bbox_inertia(project: &mut Project, link_names: &[String]) {
+    let updates: Vec<_> = project
+        .assembly
+        .links
+        .iter()
+        .filter(|(_, link)| link_names.contains(&link.name))
+        .filter_map(|(link_id, link)| {
+            let part = project.parts().get(&link.part_id?)?;
+            let inertia =
+                InertiaMatrix::from_bounding_box(link.inertial.mass, part.bbox_min, part.bbox_max);
+            // Same placement of the mesh in the link frame as the mesh estimate
+            let placement = link
+                .visuals
+                .first()
+                .map_or(part.origin_transform, |visual| {
+                    visual.origin.to_mat4() * part.origin_transform
+                });
+            let center: [f32; 3] =
+                std::array::from_fn(|i| (part.bbox_min[i] + part.bbox_max[i]) / 2.0);
+            let center = placement.transform_point3(center.into()).to_array();
+            Some((*link_id, part.id, inertia, center))
+        })
+        .collect();
+
+    for (link_id, part_id, inertia, center) in &updates {
+        if let Some(link) = project.assembly.links.get_mut(link_id) {
+            link.inertial.inertia = *inertia;
+            link.inertial.origin.xyz = *center;
+        }
+        if let Some(part) = project.parts_mut().get_mut(part_id) {
+            part.inertia = *inertia;
+        }
+    }
+}
+
+/// `": a, b"` for a non-empty list of names
+fn name_list(names: &[String]) -> String {
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", names.join(", "))
+    }
+}
+
+/// Print validation problems; returns false if any of them is an error
+fn validate(project: &Project) -> bool {
+    let assembly = &project.assembly;
+    let mut errors = 0;
+
+    if let Err(assembly_errors) = assembly.validate() {
+        for error in &assembly_errors {
+            eprintln!("error: {error}");
+        }
+        errors += assembly_errors.len();
+    }
+
+    for (joint_id, issue) in assembly.joint_issues() {
+        if !issue.is_error() {
+            eprintln!(
+                "warning: joint '{}': {issue}",
+                assembly.joints[&joint_id].name
+            );
+        }
+    }
+
+    let mut links: Vec<_> = assembly.links.values().collect();
+    links.sort_by(|a, b| a.name.cmp(&b.name));
+    for link in links {
+        let inertial = &link.inertial;
+        if inertial.mass < 0.0 {
+            eprintln!(
+                "error: link '{}': negative mass {}",
+                link.name, inertial.mass
+            );
+            errors += 1;
+        } else if inertial.mass > 0.0 && !inertial.inertia.is_valid() {
+            eprintln!(
+                "error: link '{}': inertia is not physically valid",
+                link.name
+            );
+            errors += 1;
+        }
+    }
+
+    if errors == 0 {
+        println!("Validation passed");
+    } else {
+        eprintln!("Validation failed with {errors} error(s)");
+    }
+    errors == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Result<Option<Args>, String> {
+        parse_args(list.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = args(&[
+            "robot.urdf",
+            "-o",
+            "out",
+            "--package",
+            "robot_description=/ws/src/robot_description",
+            "--mesh-unit",
+            "mm",
+            "--precision",
+            "4",
+            "--recompute-inertia",
+            "--bbox-inertia-fallback",
+            "--primitive-quality",
+            "high",
+        ])
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(parsed.urdf, PathBuf::from("robot.urdf"));
+        assert_eq!(parsed.output, Some(PathBuf::from("out")));
+        assert_eq!(parsed.packages[0].0, "robot_description");
+        assert_eq!(parsed.mesh_unit, Some(StlUnit::Millimeters));
+        assert_eq!(parsed.precision, Some(4));
+        assert!(parsed.recompute_inertia);
+        assert!(parsed.bbox_inertia_fallback);
+        assert_eq!(parsed.primitive_quality, PrimitiveQuality::High);
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(args(&["--help"]).unwrap().is_none());
+        assert!(args(&[]).is_err());
+        assert!(args(&["a.urdf", "b.urdf"]).is_err());
+        assert!(args(&["a.urdf", "--output"]).is_err());
+        assert!(args(&["a.urdf", "--mesh-unit", "ft"]).is_err());
+        assert!(args(&["a.urdf", "--precision", "six"]).is_err());
+        assert!(args(&["a.urdf", "--precision", "400"]).is_err());
+        assert!(args(&["a.urdf", "--primitive-quality", "ultra"]).is_err());
+        assert!(args(&["a.urdf", "--frobnicate"]).is_err());
+        assert!(args(&["a.urdf", "--bbox-inertia-fallback"]).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+//! Time URDF import with and without the `parallel` feature
+//!
+//! Writes a chain of 20 links, each with its own dense sphere STL, into a
+//! temporary directory and times `import_urdf` on it. Compare the two builds
+//! on the same machine:
+//!
+//! ```text
+//! cargo run --release -p rk-core --example import_timing
+//! cargo run --release -p rk-core --no-default-features --example import_timing
+//! ```
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use rk_core::{ImportOptions, Part, generate_sphere_mesh_with_segments, import_urdf, save_stl};
+
+const LINKS: usize = 20;
+const RUNS: usize = 5;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let urdf_path = write_fixture(dir.path())?;
+
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    println!(
+        "parallel feature: {}, available threads: {threads}",
+        if cfg!(feature = "parallel") {
+            "on"
+        } else {
+            "off"
+        }
+    );
+
+    let options = ImportOptions::default();
+    // Warm up the file cache and the thread pool
+    import_urdf(&urdf_path, &options)?;
+
+    let mut times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            import_urdf(&urdf_path, &options).map(|_| start.elapsed())
+        })
+        .collect::<Result<_, _>>()?;
+    times.sort();
+    println!(
+        "import_urdf, {LINKS} links, {RUNS} runs: min {:.1} ms, median {:.1} ms",
+        times[0].as_secs_f64() * 1000.0,
+        times[RUNS / 2].as_secs_f64() * 1000.0
+    );
+    Ok(())
+}
+
+/// Write the meshes and the URDF chaining them; returns the URDF path
+fn write_fixture(dir: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut urdf = String::from("<robot name=\"timing\">\n");
+    for i in 0..LINKS {
+        let (vertices, normals, indices) = generate_sphere_mesh_with_segments(0.05, 128, 256);
+        let mut part = Part::new(format!("link{i}"));
+        part.vertices = vertices;
+        part.normals = normals;
+        part.indices = indices;
+        save_stl(&part, dir.join(format!("link{i}.stl")))?;
+
+        writeln!(
+            urdf,
+            "  <link name=\"link{i}\"><visual><geometry>\
+             <mesh filename=\"link{i}.stl\"/></geometry></visual></link>"
+        )?;
+        if i > 0 {
+            writeln!(
+                urdf,
+                "  <joint name=\"joint{i}\" type=\"revolute\">\
+                 <parent link=\"link{}\"/><child link=\"link{i}\"/>\
+                 <origin xyz=\"0 0 0.1\"/><axis xyz=\"0 0 1\"/>\
+                 <limit lower=\"-1\" upper=\"1\" effort=\"1\" velocity=\"1\"/></joint>",
+                i - 1
+            )?;
+        }
+    }
+    urdf.push_str("</robot>\n");
+
+    let path = dir.join("timing.urdf");
+    std::fs::write(&path, urdf)?;
+    Ok(path)
+}
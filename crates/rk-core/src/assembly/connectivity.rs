@@ -0,0 +1,189 @@
+//! Reachability check of the link tree
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use super::Assembly;
+
+/// Links that keep the assembly from forming a single tree
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectivityReport {
+    /// Root link the traversal started from
+    pub root: Option<Uuid>,
+    /// Links that cannot be reached from the root through joints
+    pub unreachable: HashSet<Uuid>,
+    /// Links that are the child of more than one joint
+    pub multiple_parents: HashSet<Uuid>,
+}
+
+impl ConnectivityReport {
+    /// Whether every link hangs off the root exactly once
+    pub fn is_connected(&self) -> bool {
+        self.unreachable.is_empty() && self.multiple_parents.is_empty()
+    }
+
+    /// All links with a connectivity problem
+    pub fn problem_links(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.unreachable
+            .iter()
+            .chain(self.multiple_parents.difference(&self.unreachable))
+            .copied()
+    }
+}
+
+impl Assembly {
+    /// Get the link URDF export uses as the robot root
+    ///
+    /// Of the links without a parent, this is the one with the largest
+    /// subtree; ties go to the first by name.
+    pub fn root_link(&self) -> Option<Uuid> {
+        self.get_root_links().into_iter().min_by_key(|id| {
+            (
+                Reverse(self.get_subtree_size(*id)),
+                self.links.get(id).map(|link| link.name.as_str()),
+            )
+        })
+    }
+
+    /// Find links that are not reachable from [`Self::root_link`] through
+    /// joints, and links with more than one parent joint
+    pub fn connectivity_report(&self) -> ConnectivityReport {
+        let root = self.root_link();
+
+        let mut reached = HashSet::new();
+        let mut stack: Vec<Uuid> = root.into_iter().collect();
+        while let Some(link_id) = stack.pop() {
+            if !reached.insert(link_id) {
+                continue;
+            }
+            if let Some(children) = self.children.get(&link_id) {
+                stack.extend(children.iter().map(|(_, child)| *child));
+            }
+        }
+
+        let mut parent_joints: HashMap<Uuid, usize> = HashMap::new();
+        for joint in self.joints.values() {
+            *parent_joints.entry(joint.child_link).or_default() += 1;
+        }
+
+        ConnectivityReport {
+            root,
+            unreachable: self
+                .links
+                .keys()
+                .filter(|id| !reached.contains(id))
+                .copied()
+                .collect(),
+            multiple_parents: parent_joints
+                .into_iter()
+                .filter(|(_, count)| *count > 1)
+                .map(|(id, _)| id)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembly::{AssemblyError, Joint, Link};
+    use crate::types::Pose;
+
+    fn connect(assembly: &mut Assembly, parent: Uuid, child: Uuid) -> Uuid {
+        let joint = Joint::fixed("joint", parent, child, Pose::default());
+        assembly.connect(parent, child, joint).unwrap()
+    }
+
+    #[test]
+    fn test_connected_tree() {
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(Link::empty("base"));
+        let arm = assembly.add_link(Link::empty("arm"));
+        connect(&mut assembly, base, arm);
+
+        let report = assembly.connectivity_report();
+        assert_eq!(report.root, Some(base));
+        assert!(report.is_connected());
+        assert!(assembly.validate().is_ok());
+    }
+
+    #[test]
+    fn test_unreachable_links() {
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(Link::empty("base"));
+        let arm = assembly.add_link(Link::empty("arm"));
+        let loose = assembly.add_link(Link::empty("loose"));
+        let loose_child = assembly.add_link(Link::empty("loose_child"));
+        connect(&mut assembly, base, arm);
+        connect(&mut assembly, loose, loose_child);
+
+        // The larger subtree wins the root; ties go to the first name
+        let report = assembly.connectivity_report();
+        assert_eq!(report.root, Some(base));
+        assert_eq!(report.unreachable, HashSet::from([loose, loose_child]));
+        assert!(report.multiple_parents.is_empty());
+        assert!(matches!(
+            assembly.validate().unwrap_err().as_slice(),
+            [
+                AssemblyError::OrphanedLink(_),
+                AssemblyError::OrphanedLink(_)
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_multiple_parents() {
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(Link::empty("base"));
+        let left = assembly.add_link(Link::empty("left"));
+        let right = assembly.add_link(Link::empty("right"));
+        connect(&mut assembly, base, left);
+        connect(&mut assembly, base, right);
+
+        // A second joint into the same child, as a hand-edited file could contain
+        let joint = Joint::fixed("extra", right, left, Pose::default());
+        assembly.joints.insert(joint.id, joint);
+
+        let report = assembly.connectivity_report();
+        assert!(report.unreachable.is_empty());
+        assert_eq!(report.multiple_parents, HashSet::from([left]));
+        assert!(!report.is_connected());
+    }
+
+    #[test]
+    fn test_validate_with_missing_child() {
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(Link::empty("base"));
+        let left = assembly.add_link(Link::empty("left"));
+        let right = assembly.add_link(Link::empty("right"));
+        connect(&mut assembly, base, left);
+        connect(&mut assembly, base, right);
+        let joint = Joint::fixed("extra", right, left, Pose::default());
+        assembly.joints.insert(joint.id, joint);
+
+        // Two joints into a link that was never added
+        let missing = Uuid::new_v4();
+        for parent in [left, right] {
+            let joint = Joint::fixed("dangling", parent, missing, Pose::default());
+            assembly.joints.insert(joint.id, joint);
+        }
+
+        let report = assembly.connectivity_report();
+        assert!(report.multiple_parents.contains(&missing));
+
+        let errors = assembly.validate().unwrap_err();
+        let invalid = errors
+            .iter()
+            .filter(|error| {
+                matches!(error, AssemblyError::InvalidJointReference(_, id) if *id == missing)
+            })
+            .count();
+        assert_eq!(invalid, 2);
+        assert!(matches!(
+            errors.as_slice(),
+            [.., AssemblyError::MultipleParents(id)] if *id == left
+        ));
+    }
+}
@@ -2,11 +2,13 @@
 
 use uuid::Uuid;
 
-use super::{Assembly, AssemblyError, Joint};
+use crate::types::Pose;
+
+use super::{Assembly, AssemblyError, Joint, Link};
 
 impl Assembly {
     /// Add a link to the assembly (does not automatically set as root)
-    pub fn add_link(&mut self, link: super::Link) -> Uuid {
+    pub fn add_link(&mut self, link: Link) -> Uuid {
         let id = link.id;
         self.link_name_index.insert(link.name.clone(), id);
         self.links.insert(id, link);
@@ -14,45 +16,58 @@ impl Assembly {
         id
     }
 
-    /// Remove a link and all its children
-    pub fn remove_link(&mut self, id: Uuid) -> Result<(), AssemblyError> {
+    /// Remove a link along with its parent joint
+    ///
+    /// With `reparent`, the link's children are attached to its parent by
+    /// their own joints, with the removed joint's origin folded into theirs
+    /// so they stay where they are at rest. Otherwise, or if the link is a
+    /// root, the children's joints are removed too and they become roots.
+    /// Returns the removed link.
+    pub fn remove_link(&mut self, id: Uuid, reparent: bool) -> Result<Link, AssemblyError> {
         if !self.links.contains_key(&id) {
             return Err(AssemblyError::LinkNotFound(id));
         }
 
-        // Collect all descendants
-        let mut to_remove = vec![id];
-        let mut i = 0;
-        while i < to_remove.len() {
-            let link_id = to_remove[i];
-            if let Some(children) = self.children.get(&link_id) {
-                for (_, child_id) in children {
-                    to_remove.push(*child_id);
+        let parent_joint = self.parent.contains_key(&id).then(|| self.disconnect(id));
+        let parent_joint = parent_joint.transpose()?;
+        for (joint_id, child_id) in self.children.remove(&id).unwrap_or_default() {
+            self.parent.remove(&child_id);
+            let Some(mut joint) = self.joints.remove(&joint_id) else {
+                continue;
+            };
+            match &parent_joint {
+                Some(parent_joint) if reparent => {
+                    joint.parent_link = parent_joint.parent_link;
+                    joint.origin =
+                        Pose::from_mat4(parent_joint.origin.to_mat4() * joint.origin.to_mat4());
+                    self.children
+                        .entry(parent_joint.parent_link)
+                        .or_default()
+                        .push((joint_id, child_id));
+                    self.parent
+                        .insert(child_id, (joint_id, parent_joint.parent_link));
+                    self.joints.insert(joint_id, joint);
+                }
+                _ => {
+                    self.joint_name_index.remove(&joint.name);
+                    self.joint_positions.remove(&joint_id);
+                    self.joint_dof_positions.remove(&joint_id);
+                    self.clear_mimics_of(joint_id);
                 }
-            }
-            i += 1;
-        }
-
-        // Remove all collected links and their joints
-        for link_id in &to_remove {
-            if let Some(link) = self.links.remove(link_id) {
-                self.link_name_index.remove(&link.name);
-            }
-            self.children.remove(link_id);
-            if let Some((joint_id, _)) = self.parent.remove(link_id)
-                && let Some(joint) = self.joints.remove(&joint_id)
-            {
-                self.joint_name_index.remove(&joint.name);
             }
         }
-
-        // Clean up children references
-        for children in self.children.values_mut() {
-            children.retain(|(_, child_id)| !to_remove.contains(child_id));
+        if let Some(parent_joint) = &parent_joint {
+            self.joint_positions.remove(&parent_joint.id);
+            self.joint_dof_positions.remove(&parent_joint.id);
         }
 
+        let link = self
+            .links
+            .remove(&id)
+            .ok_or(AssemblyError::LinkNotFound(id))?;
+        self.link_name_index.remove(&link.name);
         self.invalidate_cache();
-        Ok(())
+        Ok(link)
     }
 
     /// Connect two links with a joint
@@ -115,11 +130,25 @@ impl Assembly {
             .remove(&joint_id)
             .ok_or(AssemblyError::JointNotFound(joint_id))?;
         self.joint_name_index.remove(&joint.name);
+        self.clear_mimics_of(joint_id);
 
         self.invalidate_cache();
         Ok(joint)
     }
 
+    /// Stop joints from mimicking a removed joint
+    fn clear_mimics_of(&mut self, joint_id: Uuid) {
+        for joint in self.joints.values_mut() {
+            if joint
+                .mimic
+                .as_ref()
+                .is_some_and(|mimic| mimic.joint_id == joint_id)
+            {
+                joint.mimic = None;
+            }
+        }
+    }
+
     /// Check if connecting parent to child would create a cycle
     pub(crate) fn would_create_cycle(&self, parent_id: Uuid, child_id: Uuid) -> bool {
         // Check if child is an ancestor of parent
@@ -133,3 +162,58 @@ impl Assembly {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_remove_link_keeps_tree_consistent() {
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(Link::empty("base"));
+        let arm = assembly.add_link(Link::empty("arm"));
+        let hand = assembly.add_link(Link::empty("hand"));
+        let shoulder = Joint::fixed("shoulder", base, arm, Pose::from_position([0.0, 0.0, 1.0]));
+        let shoulder = assembly.connect(base, arm, shoulder).unwrap();
+        let wrist = Joint::fixed("wrist", arm, hand, Pose::from_position([0.5, 0.0, 0.0]));
+        let wrist = assembly.connect(arm, hand, wrist).unwrap();
+        // A joint elsewhere mimicking the shoulder
+        let gripper = assembly.add_link(Link::empty("gripper"));
+        let finger = Joint::builder("finger", base, gripper)
+            .prismatic()
+            .mimic(shoulder)
+            .build();
+        let finger = assembly.connect(base, gripper, finger).unwrap();
+        assembly.update_world_transforms();
+        let hand_world = assembly.links[&hand].world_transform;
+
+        // Removing a mid-chain link hands its child to the grandparent in place
+        let mut reparented = assembly.clone();
+        reparented.remove_link(arm, true).unwrap();
+        assert_eq!(reparented.get_parent_link_id(hand), Some(base));
+        assert_eq!(reparented.joints[&wrist].parent_link, base);
+        assert_eq!(reparented.joints.len(), 2);
+        // The removed joint is no longer mimicked
+        assert!(reparented.joints[&finger].mimic.is_none());
+        assert!(reparented.validate().is_ok());
+        reparented.update_world_transforms();
+        assert!(
+            reparented.links[&hand]
+                .world_transform
+                .abs_diff_eq(hand_world, 1e-5)
+        );
+
+        // Orphaning leaves the child as a separate root
+        assembly.remove_link(arm, false).unwrap();
+        assert_eq!(assembly.joints.len(), 1);
+        assert!(assembly.joints[&finger].mimic.is_none());
+        assert!(!assembly.children.contains_key(&arm));
+        assert_eq!(assembly.get_parent_link_id(hand), None);
+        assert_eq!(
+            assembly.connectivity_report().unreachable,
+            HashSet::from([hand])
+        );
+    }
+}
@@ -1,10 +1,15 @@
 //! Joint types and builder for robot assembly
 
+use std::f32::consts::PI;
+
 use glam::Vec3;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::types::{JointDynamics, JointLimits, JointMimic, JointType, Pose};
+use crate::types::{
+    JointDynamics, JointLimits, JointMimic, JointSafetyController, JointTransmission, JointType,
+    Pose, normalize_angle,
+};
 
 /// A joint connecting two links
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +31,21 @@ pub struct Joint {
     pub dynamics: Option<JointDynamics>,
     /// Joint mimic configuration (follows another joint)
     pub mimic: Option<JointMimic>,
+    /// Soft limits enforced by the robot's controllers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safety_controller: Option<JointSafetyController>,
+    /// ros_control transmission driving this joint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transmission: Option<JointTransmission>,
+    /// Position in the home pose (rad or m)
+    #[serde(default)]
+    pub default_value: f32,
+    /// Joint point on the parent part this joint was snapped from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_joint_point: Option<Uuid>,
+    /// Joint point on the child part this joint was snapped from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub child_joint_point: Option<Uuid>,
 }
 
 impl Joint {
@@ -42,6 +62,11 @@ impl Joint {
             limits: None,
             dynamics: None,
             mimic: None,
+            safety_controller: None,
+            transmission: None,
+            default_value: 0.0,
+            parent_joint_point: None,
+            child_joint_point: None,
         }
     }
 
@@ -65,15 +90,137 @@ impl Joint {
             limits: Some(limits),
             dynamics: None,
             mimic: None,
+            safety_controller: None,
+            transmission: None,
+            default_value: 0.0,
+            parent_joint_point: None,
+            child_joint_point: None,
+        }
+    }
+
+    /// Check the joint axis and limits for values that would produce a
+    /// broken URDF
+    ///
+    /// Continuous joints ignore lower/upper, since they rotate without bounds.
+    pub fn validate(&self) -> Vec<JointIssue> {
+        let mut issues = Vec::new();
+        if self.joint_type.has_axis() && self.axis.try_normalize().is_none() {
+            issues.push(JointIssue::DegenerateAxis(self.axis.into()));
+        }
+        if let Some(dynamics) = &self.dynamics {
+            if dynamics.damping < 0.0 {
+                issues.push(JointIssue::NegativeDamping(dynamics.damping));
+            }
+            if dynamics.friction < 0.0 {
+                issues.push(JointIssue::NegativeFriction(dynamics.friction));
+            }
+        }
+        let Some(limits) = &self.limits else {
+            return issues;
+        };
+
+        if self.joint_type.has_limits() {
+            if limits.lower > limits.upper {
+                issues.push(JointIssue::InvertedLimits {
+                    lower: limits.lower,
+                    upper: limits.upper,
+                });
+            }
+            if self.joint_type == JointType::Revolute {
+                for value in [limits.lower, limits.upper] {
+                    if value.abs() > PI + f32::EPSILON {
+                        issues.push(JointIssue::RevoluteLimitOutOfRange(value));
+                    }
+                }
+            }
+        }
+        if limits.effort < 0.0 {
+            issues.push(JointIssue::NegativeEffort(limits.effort));
+        }
+        if limits.velocity < 0.0 {
+            issues.push(JointIssue::NegativeVelocity(limits.velocity));
+        }
+
+        issues
+    }
+
+    /// Clamp a joint position to the limits, if they apply and are well-formed
+    pub fn clamp_position(&self, position: f32) -> f32 {
+        match &self.limits {
+            Some(limits) if self.joint_type.has_limits() && limits.lower <= limits.upper => {
+                position.clamp(limits.lower, limits.upper)
+            }
+            _ => position,
+        }
+    }
+
+    /// Axis as written to a URDF: normalized, or +X (the URDF default) if
+    /// it is zero or not finite
+    pub fn export_axis(&self) -> Vec3 {
+        self.axis.try_normalize().unwrap_or(Vec3::X)
+    }
+
+    /// Bring a joint position into its valid range
+    ///
+    /// Continuous joints rotate freely, so their angle wraps into (-π, π]
+    /// and any limits are ignored. Revolute and prismatic joints clamp to
+    /// their limits.
+    pub fn constrain_position(&self, position: f32) -> f32 {
+        match self.joint_type {
+            JointType::Continuous => normalize_angle(position),
+            _ => self.clamp_position(position),
         }
     }
 
+    /// Seed the home position: zero, or the middle of the limits when zero
+    /// lies outside them
+    pub fn seed_default_value(&mut self) {
+        self.default_value = match self.limits {
+            Some(limits)
+                if self.joint_type.has_limits()
+                    && !(limits.lower..=limits.upper).contains(&0.0) =>
+            {
+                (limits.lower + limits.upper) / 2.0
+            }
+            _ => 0.0,
+        };
+    }
+
     /// Create a builder for constructing joints with fluent API
     pub fn builder(name: impl Into<String>, parent: Uuid, child: Uuid) -> JointBuilder {
         JointBuilder::new(name, parent, child)
     }
 }
 
+/// Problem found in a joint's axis or limits
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+pub enum JointIssue {
+    #[error("Lower limit {lower} is greater than upper limit {upper}")]
+    InvertedLimits { lower: f32, upper: f32 },
+    #[error("Revolute limit {0} is outside ±π")]
+    RevoluteLimitOutOfRange(f32),
+    #[error("Effort must be non-negative (got {0})")]
+    NegativeEffort(f32),
+    #[error("Velocity must be non-negative (got {0})")]
+    NegativeVelocity(f32),
+    #[error("Damping must be non-negative (got {0})")]
+    NegativeDamping(f32),
+    #[error("Friction must be non-negative (got {0})")]
+    NegativeFriction(f32),
+    #[error("Axis {0:?} has no direction; exporting +X instead")]
+    DegenerateAxis([f32; 3]),
+}
+
+impl JointIssue {
+    /// Whether the issue makes the joint unexportable (as opposed to a warning)
+    pub fn is_error(&self) -> bool {
+        !matches!(
+            self,
+            JointIssue::RevoluteLimitOutOfRange(_) | JointIssue::DegenerateAxis(_)
+        )
+    }
+}
+
 /// Builder for creating joints with fluent API
 #[derive(Debug, Clone)]
 pub struct JointBuilder {
@@ -86,6 +233,9 @@ pub struct JointBuilder {
     limits: Option<JointLimits>,
     dynamics: Option<JointDynamics>,
     mimic: Option<JointMimic>,
+    safety_controller: Option<JointSafetyController>,
+    transmission: Option<JointTransmission>,
+    joint_points: Option<(Uuid, Uuid)>,
 }
 
 impl JointBuilder {
@@ -101,6 +251,9 @@ impl JointBuilder {
             limits: None,
             dynamics: None,
             mimic: None,
+            safety_controller: None,
+            transmission: None,
+            joint_points: None,
         }
     }
 
@@ -188,6 +341,18 @@ impl JointBuilder {
         self
     }
 
+    /// Set the safety controller soft limits and gains
+    pub fn safety_controller(mut self, safety_controller: JointSafetyController) -> Self {
+        self.safety_controller = Some(safety_controller);
+        self
+    }
+
+    /// Set the ros_control transmission
+    pub fn transmission(mut self, transmission: JointTransmission) -> Self {
+        self.transmission = Some(transmission);
+        self
+    }
+
     /// Set mimic configuration to follow another joint
     pub fn mimic(mut self, joint_id: Uuid) -> Self {
         self.mimic = Some(JointMimic::new(joint_id));
@@ -200,6 +365,12 @@ impl JointBuilder {
         self
     }
 
+    /// Record the parent and child joint points the joint was snapped from
+    pub fn joint_points(mut self, parent: Uuid, child: Uuid) -> Self {
+        self.joint_points = Some((parent, child));
+        self
+    }
+
     /// Build the joint
     pub fn build(self) -> Joint {
         Joint {
@@ -213,6 +384,185 @@ impl JointBuilder {
             limits: self.limits,
             dynamics: self.dynamics,
             mimic: self.mimic,
+            safety_controller: self.safety_controller,
+            transmission: self.transmission,
+            default_value: 0.0,
+            parent_joint_point: self.joint_points.map(|(parent, _)| parent),
+            child_joint_point: self.joint_points.map(|(_, child)| child),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembly::{Assembly, Link};
+    use crate::types::JointDof;
+
+    #[test]
+    fn test_validate_flags_inverted_limits() {
+        let joint = Joint::builder("j", Uuid::new_v4(), Uuid::new_v4())
+            .revolute()
+            .limits_range(1.0, -1.0)
+            .build();
+        assert_eq!(
+            joint.validate(),
+            vec![JointIssue::InvertedLimits {
+                lower: 1.0,
+                upper: -1.0
+            }]
+        );
+        assert_eq!(joint.clamp_position(5.0), 5.0);
+    }
+
+    #[test]
+    fn test_validate_revolute_range_is_warning() {
+        let joint = Joint::builder("j", Uuid::new_v4(), Uuid::new_v4())
+            .revolute()
+            .limits_range(-4.0, 1.0)
+            .build();
+        let issues = joint.validate();
+        assert_eq!(issues, vec![JointIssue::RevoluteLimitOutOfRange(-4.0)]);
+        assert!(!issues[0].is_error());
+    }
+
+    #[test]
+    fn test_validate_continuous_ignores_range() {
+        let mut limits = JointLimits::with_range(2.0, -2.0);
+        limits.velocity = -1.0;
+        let joint = Joint::builder("j", Uuid::new_v4(), Uuid::new_v4())
+            .continuous()
+            .limits(limits)
+            .build();
+        assert_eq!(joint.validate(), vec![JointIssue::NegativeVelocity(-1.0)]);
+        assert_eq!(joint.clamp_position(10.0), 10.0);
+    }
+
+    #[test]
+    fn test_validate_dynamics() {
+        let mut joint = Joint::builder("j", Uuid::new_v4(), Uuid::new_v4())
+            .continuous()
+            .dynamics(-0.5, 0.2)
+            .build();
+        let issues = joint.validate();
+        assert_eq!(issues, vec![JointIssue::NegativeDamping(-0.5)]);
+        assert!(issues[0].is_error());
+
+        joint.dynamics = Some(JointDynamics::default_damped());
+        assert!(joint.validate().is_empty());
+    }
+
+    #[test]
+    fn test_degenerate_axis_is_warning() {
+        let mut joint = Joint::builder("j", Uuid::new_v4(), Uuid::new_v4())
+            .prismatic()
+            .build();
+        joint.axis = Vec3::new(0.0, 0.0, 2.0);
+        assert!(joint.validate().is_empty());
+        assert_eq!(joint.export_axis(), Vec3::Z);
+
+        joint.axis = Vec3::ZERO;
+        let issues = joint.validate();
+        assert_eq!(issues, vec![JointIssue::DegenerateAxis([0.0; 3])]);
+        assert!(!issues[0].is_error());
+        assert_eq!(joint.export_axis(), Vec3::X);
+
+        // Fixed joints write no axis, so theirs is not checked
+        joint.joint_type = JointType::Fixed;
+        assert!(joint.validate().is_empty());
+    }
+
+    #[test]
+    fn test_constrain_position_by_joint_type() {
+        let continuous = Joint::builder("c", Uuid::new_v4(), Uuid::new_v4())
+            .continuous()
+            .limits_range(-1.0, 1.0)
+            .build();
+        assert!((continuous.constrain_position(2.0 * PI + 0.5) - 0.5).abs() < 1e-5);
+
+        let revolute = Joint::builder("r", Uuid::new_v4(), Uuid::new_v4())
+            .revolute()
+            .limits_range(-1.0, 1.0)
+            .build();
+        assert_eq!(revolute.constrain_position(2.0), 1.0);
+
+        let prismatic = Joint::builder("p", Uuid::new_v4(), Uuid::new_v4())
+            .prismatic()
+            .limits_range(0.0, 0.2)
+            .build();
+        assert_eq!(prismatic.constrain_position(-0.5), 0.0);
+    }
+
+    #[test]
+    fn test_home_pose() {
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(Link::empty("base"));
+        let arm = assembly.add_link(Link::empty("arm"));
+        let mut joint = Joint::builder("lift", base, arm)
+            .prismatic()
+            .limits_range(0.2, 0.6)
+            .build();
+        joint.seed_default_value();
+        assert!((joint.default_value - 0.4).abs() < 1e-6);
+        let lift = assembly.connect(base, arm, joint).unwrap();
+
+        assembly.set_joint_position(lift, 0.5);
+        assembly.set_home_to_current();
+        assembly.reset_all_joint_positions();
+        assembly.go_home();
+        assert_eq!(assembly.get_joint_position(lift), 0.5);
+
+        // A zero home outside the limits is clamped like any other
+        assembly.joints.get_mut(&lift).unwrap().default_value = 0.0;
+        assembly.go_home();
+        assert_eq!(assembly.get_joint_position(lift), 0.2);
+    }
+
+    #[test]
+    fn test_multi_dof_joint_poses() {
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(Link::empty("base"));
+        let slider = assembly.add_link(Link::empty("slider"));
+        let mut joint = Joint::builder("plane", base, slider).build();
+        joint.joint_type = JointType::Planar;
+        joint.axis = Vec3::Z;
+        let plane = assembly.connect(base, slider, joint).unwrap();
+
+        // Planar joints move within the plane normal to the axis
+        assembly.set_joint_dof(plane, JointDof::X, 0.3);
+        assembly.set_joint_dof(plane, JointDof::Y, 0.4);
+        assembly.set_joint_dof(plane, JointDof::Yaw, PI / 2.0);
+        assembly.update_world_transforms_with_current_positions();
+        let (_, rotation, translation) = assembly.links[&slider]
+            .world_transform
+            .to_scale_rotation_translation();
+        assert!(translation.z.abs() < 1e-6);
+        assert!((translation.length() - 0.5).abs() < 1e-5);
+        assert!((rotation * Vec3::X).abs_diff_eq(Vec3::Y, 1e-5));
+
+        // Floating joints take all six DOFs, spherical ones only rotate
+        let joint = assembly.joints.get_mut(&plane).unwrap();
+        joint.joint_type = JointType::Floating;
+        assembly.set_joint_dof(plane, JointDof::Z, 1.0);
+        assembly.update_world_transforms_with_current_positions();
+        let translation = assembly.links[&slider].world_transform.w_axis.truncate();
+        assert!(translation.abs_diff_eq(Vec3::new(0.3, 0.4, 1.0), 1e-5));
+
+        assembly.joints.get_mut(&plane).unwrap().joint_type = JointType::Spherical;
+        assembly.update_world_transforms_with_current_positions();
+        let transform = assembly.links[&slider].world_transform;
+        assert!(transform.w_axis.truncate().abs_diff_eq(Vec3::ZERO, 1e-6));
+        assert!(
+            transform
+                .transform_vector3(Vec3::X)
+                .abs_diff_eq(Vec3::Y, 1e-5)
+        );
+
+        assembly.reset_all_joint_positions();
+        assert_eq!(assembly.get_joint_dof(plane, JointDof::Yaw), 0.0);
+
+        assembly.set_joint_dof(plane, JointDof::Roll, 1.0);
+        assembly.go_home();
+        assert_eq!(assembly.get_joint_dof(plane, JointDof::Roll), 0.0);
+    }
+}
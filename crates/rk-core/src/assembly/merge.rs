@@ -0,0 +1,122 @@
+//! Merging the assembled robot into a single mesh
+
+use std::collections::HashMap;
+
+use glam::{Mat4, Vec3};
+use uuid::Uuid;
+
+use super::{Assembly, Link};
+use crate::part::Part;
+
+impl Assembly {
+    /// World transform of a link's part in the current pose
+    ///
+    /// The part is placed by the link's `world_transform` relative to the
+    /// link's rest frame, so at zero joint positions it sits where its
+    /// `origin_transform` puts it.
+    pub fn posed_part_transform(&self, link: &Link, part: &Part) -> Mat4 {
+        let rest = self.get_world_transform(link.id);
+        link.world_transform * rest.inverse() * part.origin_transform
+    }
+
+    /// Merge the meshes of all links into one world-space mesh
+    ///
+    /// Each link's part is placed by its `world_transform` relative to the
+    /// link's rest frame, so at zero joint positions parts sit where their
+    /// `origin_transform` puts them and otherwise follow the current pose.
+    /// Call one of the `update_world_transforms*` methods first.
+    ///
+    /// Returns `(vertices, normals, indices)` with one normal per triangle.
+    /// Links are merged in name order so the output is deterministic.
+    pub fn to_merged_mesh(
+        &self,
+        parts: &HashMap<Uuid, Part>,
+    ) -> (Vec<[f32; 3]>, Vec<[f32; 3]>, Vec<u32>) {
+        let mut links: Vec<_> = self.links.values().collect();
+        links.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut indices = Vec::new();
+        for link in links {
+            let Some(part) = link.part_id.and_then(|id| parts.get(&id)) else {
+                continue;
+            };
+            let transform = self.posed_part_transform(link, part);
+            let normal_matrix = transform.inverse().transpose();
+
+            // Indices of this part follow the vertices already merged
+            let offset = vertices.len() as u32;
+            vertices.extend(
+                part.vertices
+                    .iter()
+                    .map(|v| transform.transform_point3(Vec3::from(*v)).to_array()),
+            );
+            for (triangle, chunk) in part.indices.chunks_exact(3).enumerate() {
+                indices.extend(chunk.iter().map(|index| index + offset));
+
+                let normal = match part.normals.get(triangle) {
+                    Some(n) => normal_matrix.transform_vector3(Vec3::from(*n)),
+                    None => {
+                        let [a, b, c] = [0, 1, 2].map(|i| vertices[(chunk[i] + offset) as usize]);
+                        (Vec3::from(b) - Vec3::from(a)).cross(Vec3::from(c) - Vec3::from(a))
+                    }
+                };
+                normals.push(normal.normalize_or(Vec3::Z).to_array());
+            }
+        }
+
+        (vertices, normals, indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembly::Joint;
+    use crate::types::Pose;
+
+    fn triangle_part(name: &str, transform: Mat4) -> Part {
+        let mut part = Part::new(name);
+        part.vertices = vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        part.normals = vec![[0.0, 0.0, 1.0]];
+        part.indices = vec![0, 1, 2];
+        part.origin_transform = transform;
+        part
+    }
+
+    #[test]
+    fn test_merged_mesh_offsets_indices_and_applies_pose() {
+        let base = triangle_part("a_base", Mat4::IDENTITY);
+        let arm = triangle_part("b_arm", Mat4::from_translation(Vec3::new(0.0, 0.0, 1.0)));
+
+        let mut assembly = Assembly::new("robot");
+        let base_link = assembly.add_link(Link::from_part(&base));
+        let arm_link = assembly.add_link(Link::from_part(&arm));
+        let joint = Joint::revolute(
+            "shoulder",
+            base_link,
+            arm_link,
+            Pose::from_position([0.0, 0.0, 1.0]),
+            Vec3::Z,
+            Default::default(),
+        );
+        let joint_id = assembly.connect(base_link, arm_link, joint).unwrap();
+        let parts: HashMap<Uuid, Part> = [base, arm].into_iter().map(|p| (p.id, p)).collect();
+
+        // At rest, parts sit at their origin transforms
+        assembly.update_world_transforms();
+        let (vertices, normals, indices) = assembly.to_merged_mesh(&parts);
+        assert_eq!(vertices.len(), 6);
+        assert_eq!(indices, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(normals.len(), 2);
+        assert_eq!(vertices[4], [1.0, 0.0, 1.0]);
+
+        // Rotating the joint swings the arm about its axis
+        let positions = HashMap::from([(joint_id, std::f32::consts::FRAC_PI_2)]);
+        assembly.update_world_transforms_with_positions(&positions);
+        let (vertices, normals, _) = assembly.to_merged_mesh(&parts);
+        assert!(Vec3::from(vertices[4]).abs_diff_eq(Vec3::new(0.0, 1.0, 1.0), 1e-5));
+        assert!(Vec3::from(normals[1]).abs_diff_eq(Vec3::Z, 1e-5));
+    }
+}
@@ -1,20 +1,28 @@
 //! Assembly (scene graph) for robot structure
 
+mod connectivity;
 mod graph;
 mod joint;
+mod merge;
 mod queries;
+mod self_collision;
 mod transforms;
 mod tree_cache;
 mod types;
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-pub use joint::{Joint, JointBuilder};
-pub use types::{CollisionElement, InertialProperties, Link, VisualElement};
+use crate::types::JointDof;
+
+pub use connectivity::ConnectivityReport;
+pub use joint::{Joint, JointBuilder, JointIssue};
+pub use types::{
+    CollisionElement, CollisionMesh, CollisionSource, InertialProperties, Link, VisualElement,
+};
 
 use tree_cache::TreeCache;
 
@@ -50,6 +58,10 @@ pub struct Assembly {
     /// Current joint positions (joint_id -> position in radians or meters)
     /// Runtime state only - not serialized
     pub joint_positions: HashMap<Uuid, f32>,
+    /// Current multi-DOF joint positions (joint_id -> positions indexed by
+    /// `JointDof::index`)
+    /// Runtime state only - not serialized
+    pub joint_dof_positions: HashMap<Uuid, [f32; 6]>,
 }
 
 impl From<Assembly> for AssemblyData {
@@ -76,6 +88,7 @@ impl From<AssemblyData> for Assembly {
             joint_name_index: HashMap::new(),
             cache: RefCell::new(TreeCache::default()),
             joint_positions: HashMap::new(),
+            joint_dof_positions: HashMap::new(),
         };
         assembly.rebuild_indices();
         assembly.update_world_transforms();
@@ -112,6 +125,7 @@ impl Assembly {
             joint_name_index: HashMap::new(),
             cache: RefCell::new(TreeCache::default()),
             joint_positions: HashMap::new(),
+            joint_dof_positions: HashMap::new(),
         }
     }
 
@@ -167,6 +181,36 @@ impl Assembly {
             }
         }
 
+        // Check every link hangs off the root exactly once, in name order.
+        // Missing links were reported as invalid joint references above.
+        let connectivity = self.connectivity_report();
+        let by_name = |ids: &HashSet<Uuid>| {
+            let mut ids: Vec<Uuid> = ids
+                .iter()
+                .copied()
+                .filter(|id| self.links.contains_key(id))
+                .collect();
+            ids.sort_by(|a, b| self.links[a].name.cmp(&self.links[b].name));
+            ids
+        };
+        errors.extend(
+            by_name(&connectivity.unreachable)
+                .into_iter()
+                .map(AssemblyError::OrphanedLink),
+        );
+        errors.extend(
+            by_name(&connectivity.multiple_parents)
+                .into_iter()
+                .map(AssemblyError::MultipleParents),
+        );
+
+        // Check joint limits
+        for (joint_id, issue) in self.joint_issues() {
+            if issue.is_error() {
+                errors.push(AssemblyError::InvalidJoint(joint_id, issue));
+            }
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -174,6 +218,17 @@ impl Assembly {
         }
     }
 
+    /// Collect limit issues (errors and warnings) for every joint
+    pub fn joint_issues(&self) -> Vec<(Uuid, JointIssue)> {
+        let mut issues: Vec<_> = self
+            .joints
+            .values()
+            .flat_map(|joint| joint.validate().into_iter().map(|issue| (joint.id, issue)))
+            .collect();
+        issues.sort_by(|(a, _), (b, _)| self.joints[a].name.cmp(&self.joints[b].name));
+        issues
+    }
+
     /// Get all links in depth-first order from all roots
     pub fn links_depth_first(&self) -> Vec<Uuid> {
         let mut result = Vec::new();
@@ -204,14 +259,76 @@ impl Assembly {
         self.joint_positions.get(&joint_id).copied().unwrap_or(0.0)
     }
 
+    /// Set one DOF of a multi-DOF joint (radians or meters)
+    pub fn set_joint_dof(&mut self, joint_id: Uuid, dof: JointDof, value: f32) {
+        self.joint_dof_positions.entry(joint_id).or_default()[dof.index()] = value;
+    }
+
+    /// Get one DOF of a multi-DOF joint (defaults to 0.0)
+    pub fn get_joint_dof(&self, joint_id: Uuid, dof: JointDof) -> f32 {
+        self.joint_dof_positions
+            .get(&joint_id)
+            .map_or(0.0, |dofs| dofs[dof.index()])
+    }
+
     /// Reset a joint position to 0
     pub fn reset_joint_position(&mut self, joint_id: Uuid) {
         self.joint_positions.remove(&joint_id);
+        self.joint_dof_positions.remove(&joint_id);
     }
 
     /// Reset all joint positions to 0
     pub fn reset_all_joint_positions(&mut self) {
         self.joint_positions.clear();
+        self.joint_dof_positions.clear();
+    }
+
+    /// Move every actuated joint to its default value and update world
+    /// transforms
+    ///
+    /// Multi-DOF joints have no default value and go back to zero.
+    pub fn go_home(&mut self) {
+        self.joint_dof_positions.clear();
+        self.joint_positions = self
+            .joints
+            .values()
+            .filter(|joint| joint.joint_type.has_axis())
+            .map(|joint| (joint.id, joint.constrain_position(joint.default_value)))
+            .collect();
+        self.update_world_transforms_with_current_positions();
+    }
+
+    /// Store the current joint positions as the actuated joints' default
+    /// values
+    pub fn set_home_to_current(&mut self) {
+        for joint in self.joints.values_mut() {
+            if joint.joint_type.has_axis() {
+                joint.default_value = self.joint_positions.get(&joint.id).copied().unwrap_or(0.0);
+            }
+        }
+    }
+
+    /// Set joint positions by joint name and update world transforms
+    ///
+    /// Positions are clamped to the joint limits, or wrapped for continuous
+    /// joints. Returns the names that don't match any joint in the assembly;
+    /// those entries are skipped.
+    pub fn apply_joint_state(&mut self, positions: &HashMap<String, f32>) -> Vec<String> {
+        let mut unknown = Vec::new();
+        for (name, &position) in positions {
+            match self.find_joint_by_name(name) {
+                Some(joint) => {
+                    let id = joint.id;
+                    let position = joint.constrain_position(position);
+                    self.set_joint_position(id, position);
+                }
+                None => unknown.push(name.clone()),
+            }
+        }
+        unknown.sort();
+
+        self.update_world_transforms_with_current_positions();
+        unknown
     }
 }
 
@@ -224,6 +341,8 @@ pub enum AssemblyError {
     JointNotFound(Uuid),
     #[error("Part not found: {0}")]
     PartNotFound(Uuid),
+    #[error("Joint point not found: {0}")]
+    JointPointNotFound(Uuid),
     #[error("Connection would create a cycle")]
     WouldCreateCycle,
     #[error("Link already has a parent: {0}")]
@@ -232,8 +351,12 @@ pub enum AssemblyError {
     NoParent(Uuid),
     #[error("No root link defined")]
     NoRoot,
-    #[error("Orphaned link: {0}")]
+    #[error("Link not connected to the root link: {0}")]
     OrphanedLink(Uuid),
+    #[error("Link has more than one parent joint: {0}")]
+    MultipleParents(Uuid),
     #[error("Invalid joint reference: joint {0} references non-existent link {1}")]
     InvalidJointReference(Uuid, Uuid),
+    #[error("Invalid joint {0}: {1}")]
+    InvalidJoint(Uuid, JointIssue),
 }
@@ -0,0 +1,248 @@
+//! Broad-phase self-collision check for Assembly
+
+use std::collections::HashMap;
+
+use glam::{Mat4, Vec3};
+use uuid::Uuid;
+
+use super::Assembly;
+use super::types::Link;
+use crate::part::Part;
+use crate::types::GeometryType;
+
+/// Penetration depth below which touching shapes are not reported
+const CONTACT_TOLERANCE: f32 = 1e-4;
+
+/// World-space bounds of one collision shape
+#[derive(Debug, Clone, Copy)]
+struct ShapeBounds {
+    min: Vec3,
+    max: Vec3,
+    /// Exact bounding sphere for sphere geometry
+    sphere: Option<(Vec3, f32)>,
+}
+
+impl ShapeBounds {
+    fn from_box(transform: Mat4, half_extents: Vec3) -> Self {
+        let center = transform.transform_point3(Vec3::ZERO);
+        // Extent of a transformed box along each world axis
+        let extent = transform.x_axis.truncate().abs() * half_extents.x
+            + transform.y_axis.truncate().abs() * half_extents.y
+            + transform.z_axis.truncate().abs() * half_extents.z;
+        Self {
+            min: center - extent,
+            max: center + extent,
+            sphere: None,
+        }
+    }
+
+    fn from_sphere(center: Vec3, radius: f32) -> Self {
+        Self {
+            min: center - Vec3::splat(radius),
+            max: center + Vec3::splat(radius),
+            sphere: Some((center, radius)),
+        }
+    }
+
+    fn overlaps(&self, other: &ShapeBounds) -> bool {
+        let aabb = (self.max - other.min).min_element() > CONTACT_TOLERANCE
+            && (other.max - self.min).min_element() > CONTACT_TOLERANCE;
+        match (self.sphere, other.sphere) {
+            (Some((a, ra)), Some((b, rb))) if aabb => a.distance(b) < ra + rb - CONTACT_TOLERANCE,
+            _ => aabb,
+        }
+    }
+}
+
+impl Assembly {
+    /// Find pairs of links whose collision geometry overlaps at the current pose
+    ///
+    /// Uses the links' world transforms, so call one of the
+    /// `update_world_transforms*` methods first. Links without collision
+    /// elements fall back to their part's bounding box. Links connected
+    /// directly by a joint are never reported. This is a broad-phase check
+    /// only: oriented shapes are approximated by their world AABBs.
+    pub fn self_collisions(&self, parts: &HashMap<Uuid, Part>) -> Vec<(Uuid, Uuid)> {
+        let mut shapes: Vec<(Uuid, Vec<ShapeBounds>)> = self
+            .links
+            .values()
+            .map(|link| (link.id, link_shape_bounds(link, parts)))
+            .filter(|(_, bounds)| !bounds.is_empty())
+            .collect();
+        shapes.sort_by_key(|(id, _)| *id);
+
+        let mut pairs = Vec::new();
+        for (i, (a, a_shapes)) in shapes.iter().enumerate() {
+            for (b, b_shapes) in &shapes[i + 1..] {
+                if self.are_adjacent(*a, *b) {
+                    continue;
+                }
+                let hit = a_shapes
+                    .iter()
+                    .any(|sa| b_shapes.iter().any(|sb| sa.overlaps(sb)));
+                if hit {
+                    pairs.push((*a, *b));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Find the link whose collision geometry reaches lowest along world Z
+    ///
+    /// Returns that link and the world Z of the bottom of its lowest shape,
+    /// using the same bounds as [`Assembly::self_collisions`]. Compare the
+    /// height against a floor to check for penetration.
+    pub fn lowest_collision_point(&self, parts: &HashMap<Uuid, Part>) -> Option<(Uuid, f32)> {
+        self.links
+            .values()
+            .flat_map(|link| {
+                link_shape_bounds(link, parts)
+                    .into_iter()
+                    .map(move |bounds| (link.id, bounds.min.z))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Check whether two links are connected directly by a joint
+    fn are_adjacent(&self, a: Uuid, b: Uuid) -> bool {
+        self.get_parent_link_id(a) == Some(b) || self.get_parent_link_id(b) == Some(a)
+    }
+}
+
+/// Compute world-space bounds for every collision shape of a link
+fn link_shape_bounds(link: &Link, parts: &HashMap<Uuid, Part>) -> Vec<ShapeBounds> {
+    let part_bounds = link
+        .part_id
+        .and_then(|id| parts.get(&id))
+        .map(|part| (Vec3::from(part.bbox_min), Vec3::from(part.bbox_max)));
+    let mesh_box = |transform: Mat4, (min, max): (Vec3, Vec3), scale: Vec3| {
+        let (min, max) = (min * scale, max * scale);
+        let center = transform * Mat4::from_translation((min + max) / 2.0);
+        ShapeBounds::from_box(center, ((max - min) / 2.0).abs())
+    };
+
+    if link.collisions.is_empty() {
+        return part_bounds
+            .map(|bounds| mesh_box(link.world_transform, bounds, Vec3::ONE))
+            .into_iter()
+            .collect();
+    }
+
+    link.collisions
+        .iter()
+        .filter_map(|collision| {
+            let transform = link.world_transform * collision.origin.to_mat4();
+            match &collision.geometry {
+                GeometryType::Mesh { scale, .. } => {
+                    // An imported collision mesh replaces the visual mesh
+                    let bounds = match &collision.mesh {
+                        Some(mesh) => mesh.bounds(),
+                        None => part_bounds,
+                    };
+                    bounds.map(|bounds| {
+                        mesh_box(transform, bounds, scale.map_or(Vec3::ONE, Vec3::from))
+                    })
+                }
+                GeometryType::Box { size } => {
+                    Some(ShapeBounds::from_box(transform, Vec3::from(*size) / 2.0))
+                }
+                GeometryType::Cylinder { radius, length } => Some(ShapeBounds::from_box(
+                    transform,
+                    Vec3::new(*radius, *radius, length / 2.0),
+                )),
+                GeometryType::Capsule { radius, length } => Some(ShapeBounds::from_box(
+                    transform,
+                    Vec3::new(*radius, *radius, length / 2.0 + radius),
+                )),
+                GeometryType::Sphere { radius } => {
+                    let (scale, _, center) = transform.to_scale_rotation_translation();
+                    Some(ShapeBounds::from_sphere(
+                        center,
+                        radius * scale.max_element(),
+                    ))
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembly::{CollisionElement, Joint};
+    use crate::types::Pose;
+
+    fn sphere_link(name: &str, radius: f32) -> Link {
+        let mut link = Link::empty(name);
+        link.collisions.push(CollisionElement {
+            name: None,
+            origin: Pose::default(),
+            geometry: GeometryType::Sphere { radius },
+            mesh: None,
+        });
+        link
+    }
+
+    #[test]
+    fn test_self_collisions_skip_adjacent_links() {
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(sphere_link("base", 0.5));
+        let arm = assembly.add_link(sphere_link("arm", 0.5));
+        let tool = assembly.add_link(sphere_link("tool", 0.5));
+        let offset = Pose::new([0.6, 0.0, 0.0], [0.0; 3]);
+        assembly
+            .connect(base, arm, Joint::fixed("j1", base, arm, offset))
+            .unwrap();
+        assembly
+            .connect(arm, tool, Joint::fixed("j2", arm, tool, offset))
+            .unwrap();
+        assembly.update_world_transforms();
+
+        // base and tool are 1.2 apart, so nothing overlaps
+        assert!(assembly.self_collisions(&HashMap::new()).is_empty());
+
+        // Fold the tool back onto the base
+        let j2 = assembly.find_joint_id_by_name("j2").unwrap();
+        assembly.joints.get_mut(&j2).unwrap().origin = Pose::new([-0.6, 0.0, 0.0], [0.0; 3]);
+        assembly.update_world_transforms();
+
+        let mut expected = (base, tool);
+        if expected.0 > expected.1 {
+            expected = (tool, base);
+        }
+        assert_eq!(assembly.self_collisions(&HashMap::new()), vec![expected]);
+    }
+
+    #[test]
+    fn test_lowest_collision_point() {
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(sphere_link("base", 0.5));
+        let foot = assembly.add_link(sphere_link("foot", 0.1));
+        let offset = Pose::new([0.0, 0.0, -0.6], [0.0; 3]);
+        assembly
+            .connect(base, foot, Joint::fixed("ankle", base, foot, offset))
+            .unwrap();
+        assembly.update_world_transforms();
+
+        let (link, z) = assembly.lowest_collision_point(&HashMap::new()).unwrap();
+        assert_eq!(link, foot);
+        assert!((z + 0.7).abs() < 1e-5);
+        assert!(
+            Assembly::new("empty")
+                .lowest_collision_point(&HashMap::new())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_spheres_in_aabb_corners_do_not_collide() {
+        let a = ShapeBounds::from_sphere(Vec3::ZERO, 1.0);
+        let b = ShapeBounds::from_sphere(Vec3::splat(1.5), 1.0);
+        assert!(!a.overlaps(&b));
+        assert!(a.overlaps(&ShapeBounds::from_box(
+            Mat4::from_translation(Vec3::splat(1.5)),
+            Vec3::ONE
+        )));
+    }
+}
@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 
-use glam::{Mat4, Quat};
+use glam::{EulerRot, Mat4, Quat, Vec3};
 use uuid::Uuid;
 
 use crate::types::JointType;
@@ -24,15 +24,22 @@ impl JointTransformStrategy for NoJointTransform {
     }
 }
 
-/// Joint transform with positions from a HashMap
+/// Joint transform with positions from HashMaps
 struct WithPositions<'a> {
     positions: &'a HashMap<Uuid, f32>,
+    dof_positions: &'a HashMap<Uuid, [f32; 6]>,
 }
 
 impl JointTransformStrategy for WithPositions<'_> {
     fn compute(&self, joint_id: Uuid, joint: &Joint) -> Mat4 {
         let position = self.positions.get(&joint_id).copied().unwrap_or(0.0);
+        let dofs = self
+            .dof_positions
+            .get(&joint_id)
+            .copied()
+            .unwrap_or_default();
         Assembly::compute_joint_transform(&joint.joint_type, joint.axis, position)
+            * Assembly::compute_multi_dof_transform(&joint.joint_type, joint.axis, &dofs)
     }
 }
 
@@ -70,10 +77,14 @@ impl Assembly {
     }
 
     /// Update all world transforms with joint positions applied
+    ///
+    /// Multi-DOF joints keep the assembly's current DOF positions.
     pub fn update_world_transforms_with_positions(&mut self, joint_positions: &HashMap<Uuid, f32>) {
         let roots = self.get_root_links();
+        let dof_positions = self.joint_dof_positions.clone();
         let strategy = WithPositions {
             positions: joint_positions,
+            dof_positions: &dof_positions,
         };
         for root_id in roots {
             self.update_transform_recursive_impl(root_id, Mat4::IDENTITY, &strategy);
@@ -84,8 +95,10 @@ impl Assembly {
     pub fn update_world_transforms_with_current_positions(&mut self) {
         let roots = self.get_root_links();
         let positions = self.joint_positions.clone();
+        let dof_positions = self.joint_dof_positions.clone();
         let strategy = WithPositions {
             positions: &positions,
+            dof_positions: &dof_positions,
         };
         for root_id in roots {
             self.update_transform_recursive_impl(root_id, Mat4::IDENTITY, &strategy);
@@ -143,10 +156,40 @@ impl Assembly {
                 let translation = axis * position;
                 Mat4::from_translation(translation)
             }
-            JointType::Fixed | JointType::Floating | JointType::Planar => {
-                // No transform for fixed joints, floating/planar would need more DOFs
+            JointType::Fixed | JointType::Floating | JointType::Planar | JointType::Spherical => {
+                // No transform for fixed joints, multi-DOF joints use
+                // compute_multi_dof_transform
                 Mat4::IDENTITY
             }
         }
     }
+
+    /// Compute the transform for a multi-DOF joint at the given DOF positions
+    ///
+    /// `dofs` is indexed by [`JointDof::index`](crate::types::JointDof::index). Rotations use the same
+    /// roll-pitch-yaw convention as [`Pose`](crate::Pose). Single-DOF and
+    /// fixed joints return the identity.
+    pub fn compute_multi_dof_transform(
+        joint_type: &JointType,
+        axis: Vec3,
+        dofs: &[f32; 6],
+    ) -> Mat4 {
+        let [x, y, z, roll, pitch, yaw] = *dofs;
+        match joint_type {
+            JointType::Planar => {
+                // Translation in the plane normal to the axis, rotation about it
+                let normal = axis.normalize_or(Vec3::Z);
+                let (u, v) = normal.any_orthonormal_pair();
+                Mat4::from_translation(u * x + v * y) * Mat4::from_axis_angle(normal, yaw)
+            }
+            JointType::Floating => Mat4::from_rotation_translation(
+                Quat::from_euler(EulerRot::XYZ, roll, pitch, yaw),
+                Vec3::new(x, y, z),
+            ),
+            JointType::Spherical => {
+                Mat4::from_quat(Quat::from_euler(EulerRot::XYZ, roll, pitch, yaw))
+            }
+            _ => Mat4::IDENTITY,
+        }
+    }
 }
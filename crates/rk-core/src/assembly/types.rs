@@ -1,11 +1,13 @@
 //! Link and element types for robot assembly
 
-use glam::Mat4;
+use glam::{Mat4, Vec3};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::constants::{DEFAULT_ROUGHNESS, SIMPLIFIED_COLLISION_TRIANGLES};
 use crate::inertia::InertiaMatrix;
-use crate::part::Part;
+use crate::mesh::{convex_hull, simplify_mesh};
+use crate::part::{Part, default_roughness};
 use crate::types::{GeometryType, Pose};
 
 /// A link in the robot assembly
@@ -55,8 +57,10 @@ impl Link {
                 name: None,
                 origin: Pose::default(),
                 color: part.color,
+                metallic: part.metallic,
+                roughness: part.roughness,
                 material_name: part.material_name.clone(),
-                texture: None,
+                texture: part.texture.clone(),
                 geometry: GeometryType::Mesh {
                     path: None,
                     scale: None,
@@ -70,6 +74,94 @@ impl Link {
             },
         }
     }
+
+    /// Replace the link's collisions with one built from its visual mesh
+    ///
+    /// `part` is the link's part. [`CollisionSource::Exact`] references the
+    /// visual mesh file; the other sources get a mesh of their own. The
+    /// collision takes the origin of the first visual. Returns `false`,
+    /// leaving the collisions alone, if no collision mesh can be built.
+    pub fn copy_visual_to_collision(&mut self, part: &Part, source: CollisionSource) -> bool {
+        if part.indices.is_empty() {
+            return false;
+        }
+        // Like the exported visual mesh, the collision mesh carries the
+        // part's origin transform
+        let vertices = || -> Vec<[f32; 3]> {
+            part.vertices
+                .iter()
+                .map(|v| {
+                    part.origin_transform
+                        .transform_point3(Vec3::from(*v))
+                        .to_array()
+                })
+                .collect()
+        };
+        let mesh = match source {
+            CollisionSource::Exact => None,
+            CollisionSource::ConvexHull => match convex_hull(&vertices()) {
+                Some(hull) => Some(hull),
+                None => return false,
+            },
+            CollisionSource::Simplified => Some(simplify_mesh(
+                &vertices(),
+                &part.indices,
+                SIMPLIFIED_COLLISION_TRIANGLES,
+            )),
+        };
+
+        let mut collision = match mesh {
+            Some((vertices, indices)) => CollisionElement::from_mesh(CollisionMesh {
+                id: Uuid::new_v4(),
+                name: format!("{}_{}", part.name, source.suffix()),
+                vertices,
+                indices,
+            }),
+            None => CollisionElement::default(),
+        };
+        if let Some(visual) = self.visuals.first() {
+            collision.origin = visual.origin;
+        }
+        self.collisions = vec![collision];
+        true
+    }
+}
+
+/// How [`Link::copy_visual_to_collision`] derives collision geometry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionSource {
+    /// The visual mesh itself
+    Exact,
+    /// Convex hull of the visual mesh
+    ConvexHull,
+    /// Visual mesh decimated to a small triangle budget
+    Simplified,
+}
+
+impl CollisionSource {
+    pub const ALL: [CollisionSource; 3] = [
+        CollisionSource::Exact,
+        CollisionSource::ConvexHull,
+        CollisionSource::Simplified,
+    ];
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            CollisionSource::Exact => "Exact",
+            CollisionSource::ConvexHull => "Convex Hull",
+            CollisionSource::Simplified => "Simplified",
+        }
+    }
+
+    /// Suffix of the collision mesh name
+    fn suffix(&self) -> &'static str {
+        match self {
+            CollisionSource::Exact => "visual",
+            CollisionSource::ConvexHull => "hull",
+            CollisionSource::Simplified => "simplified",
+        }
+    }
 }
 
 /// Single visual element for a link
@@ -79,6 +171,12 @@ pub struct VisualElement {
     pub name: Option<String>,
     pub origin: Pose,
     pub color: [f32; 4],
+    /// PBR metallic factor (not exported to URDF)
+    #[serde(default)]
+    pub metallic: f32,
+    /// PBR roughness factor (not exported to URDF)
+    #[serde(default = "default_roughness")]
+    pub roughness: f32,
     pub material_name: Option<String>,
     /// Texture filename (optional)
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -93,6 +191,8 @@ impl Default for VisualElement {
             name: None,
             origin: Pose::default(),
             color: [0.5, 0.5, 0.5, 1.0],
+            metallic: 0.0,
+            roughness: DEFAULT_ROUGHNESS,
             material_name: None,
             texture: None,
             geometry: GeometryType::Mesh {
@@ -111,6 +211,21 @@ pub struct CollisionElement {
     pub origin: Pose,
     /// Geometry type
     pub geometry: GeometryType,
+    /// Mesh of its own for a mesh geometry; without one the link's visual
+    /// mesh is used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mesh: Option<CollisionMesh>,
+}
+
+impl CollisionElement {
+    /// Collision element for an imported collision mesh, in the link frame
+    pub fn from_mesh(mesh: CollisionMesh) -> Self {
+        Self {
+            name: Some(mesh.name.clone()),
+            mesh: Some(mesh),
+            ..Self::default()
+        }
+    }
 }
 
 impl Default for CollisionElement {
@@ -122,8 +237,61 @@ impl Default for CollisionElement {
                 path: None,
                 scale: None,
             },
+            mesh: None,
+        }
+    }
+}
+
+/// Triangle mesh imported as collision geometry, kept apart from the parts
+/// so it is neither rendered nor exported as a visual
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollisionMesh {
+    /// Identifies the mesh's exported file and its GPU buffers
+    pub id: Uuid,
+    pub name: String,
+    pub vertices: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+}
+
+impl CollisionMesh {
+    /// Take the triangles of a loaded mesh, with its origin transform applied
+    pub fn from_part(part: &Part) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: part.name.clone(),
+            vertices: part
+                .vertices
+                .iter()
+                .map(|v| {
+                    part.origin_transform
+                        .transform_point3(Vec3::from(*v))
+                        .to_array()
+                })
+                .collect(),
+            indices: part.indices.clone(),
         }
     }
+
+    /// Part holding the mesh's triangles, for writing it as STL
+    pub fn to_part(&self) -> Part {
+        let mut part = Part::new(self.name.clone());
+        part.vertices = self.vertices.clone();
+        part.indices = self.indices.clone();
+        part.calculate_bounding_box();
+        part
+    }
+
+    /// Number of triangles
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Axis-aligned bounds as (min, max), or `None` for an empty mesh
+    pub fn bounds(&self) -> Option<(Vec3, Vec3)> {
+        let mut points = self.vertices.iter().map(|v| Vec3::from(*v));
+        let first = points.next()?;
+        Some(points.fold((first, first), |(min, max), p| (min.min(p), max.max(p))))
+    }
 }
 
 /// Inertial properties for a link
@@ -133,3 +301,26 @@ pub struct InertialProperties {
     pub mass: f32,
     pub inertia: InertiaMatrix,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copied_collision_follows_origin_transform() {
+        let (vertices, _, indices) = crate::primitive::generate_box_mesh([0.2, 0.2, 0.2]);
+        let mut part = Part::new("box");
+        part.vertices = vertices;
+        part.indices = indices;
+        part.origin_transform = Mat4::from_translation(Vec3::new(0.0, 0.0, 1.0));
+        let mut link = Link::from_part(&part);
+
+        for source in [CollisionSource::ConvexHull, CollisionSource::Simplified] {
+            assert!(link.copy_visual_to_collision(&part, source));
+            let mesh = link.collisions[0].mesh.as_ref().unwrap();
+            let (min, max) = mesh.bounds().unwrap();
+            assert!(min.abs_diff_eq(Vec3::new(-0.1, -0.1, 0.9), 1e-5));
+            assert!(max.abs_diff_eq(Vec3::new(0.1, 0.1, 1.1), 1e-5));
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Bounding volume hierarchy for ray queries
+//!
+//! A [`Bvh`] is built once over the bounding boxes of a set of primitives
+//! (usually the triangles of a mesh) and answers "which primitive does this
+//! ray hit first" by visiting only the boxes along the ray, nearest first.
+
+use glam::Vec3;
+
+/// Primitives stored in a leaf before it is split
+const MAX_LEAF_SIZE: usize = 4;
+
+/// Node of a [`Bvh`]
+#[derive(Debug, Clone)]
+struct BvhNode {
+    min: Vec3,
+    max: Vec3,
+    /// Index of the left child (the right one follows it), or of the first
+    /// primitive in `Bvh::primitives` for a leaf
+    start: u32,
+    /// Number of primitives in a leaf, 0 for an interior node
+    count: u32,
+}
+
+/// Bounding volume hierarchy over axis-aligned boxes
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    /// Primitive indices, grouped by leaf
+    primitives: Vec<u32>,
+}
+
+impl Bvh {
+    /// Build a hierarchy over primitive bounding boxes given as (min, max)
+    ///
+    /// Primitives are referred to by their index in `bounds`.
+    pub fn build(bounds: &[(Vec3, Vec3)]) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::with_capacity(bounds.len().div_ceil(MAX_LEAF_SIZE) * 2),
+            primitives: (0..bounds.len() as u32).collect(),
+        };
+        if !bounds.is_empty() {
+            bvh.nodes.push(BvhNode {
+                min: Vec3::ZERO,
+                max: Vec3::ZERO,
+                start: 0,
+                count: 0,
+            });
+            bvh.split(0, 0, bounds.len(), bounds);
+        }
+        bvh
+    }
+
+    /// Build a hierarchy over the triangles of an indexed mesh
+    ///
+    /// Primitive `i` is the triangle `indices[3 * i..3 * i + 3]`.
+    pub fn from_triangles(vertices: &[[f32; 3]], indices: &[u32]) -> Self {
+        let bounds: Vec<(Vec3, Vec3)> = indices
+            .chunks_exact(3)
+            .map(|chunk| {
+                let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(vertices[chunk[i] as usize]));
+                (a.min(b).min(c), a.max(b).max(c))
+            })
+            .collect();
+        Self::build(&bounds)
+    }
+
+    /// Number of primitives the hierarchy was built over
+    pub fn len(&self) -> usize {
+        self.primitives.len()
+    }
+
+    /// Whether the hierarchy has no primitives
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    /// Fill in node `node` over `primitives[start..end]`, splitting it into
+    /// children at the median along the longest axis of the box centers
+    fn split(&mut self, node: usize, start: usize, end: usize, bounds: &[(Vec3, Vec3)]) {
+        let range = &mut self.primitives[start..end];
+        let (mut min, mut max) = (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN));
+        let (mut center_min, mut center_max) = (min, max);
+        for &primitive in range.iter() {
+            let (lo, hi) = bounds[primitive as usize];
+            min = min.min(lo);
+            max = max.max(hi);
+            let center = (lo + hi) * 0.5;
+            center_min = center_min.min(center);
+            center_max = center_max.max(center);
+        }
+        self.nodes[node].min = min;
+        self.nodes[node].max = max;
+
+        let extent = center_max - center_min;
+        if range.len() <= MAX_LEAF_SIZE || extent.max_element() <= 0.0 {
+            self.nodes[node].start = start as u32;
+            self.nodes[node].count = range.len() as u32;
+            return;
+        }
+
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+        let mid = range.len() / 2;
+        range.select_nth_unstable_by(mid, |&a, &b| {
+            let center = |p: u32| {
+                let (lo, hi) = bounds[p as usize];
+                lo[axis] + hi[axis]
+            };
+            center(a).total_cmp(&center(b))
+        });
+
+        let left = self.nodes.len();
+        for _ in 0..2 {
+            self.nodes.push(BvhNode {
+                min: Vec3::ZERO,
+                max: Vec3::ZERO,
+                start: 0,
+                count: 0,
+            });
+        }
+        self.nodes[node].start = left as u32;
+        self.split(left, start, start + mid, bounds);
+        self.split(left + 1, start + mid, end, bounds);
+    }
+
+    /// Find the closest primitive hit by a ray
+    ///
+    /// `hit` tests a single primitive and returns the ray parameter of the
+    /// hit, if any. Boxes are visited nearest first and skipped once they
+    /// start beyond the closest hit so far. Returns the primitive index and
+    /// ray parameter of the closest hit.
+    pub fn ray_cast(
+        &self,
+        ray_origin: Vec3,
+        ray_dir: Vec3,
+        mut hit: impl FnMut(usize) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        let inv_dir = ray_dir.recip();
+        let entry = |node: &BvhNode| ray_box_entry(ray_origin, inv_dir, node.min, node.max);
+
+        let mut closest: Option<(usize, f32)> = None;
+        let mut stack: Vec<(u32, f32)> = Vec::new();
+        if let Some(root) = self.nodes.first()
+            && let Some(t) = entry(root)
+        {
+            stack.push((0, t));
+        }
+
+        while let Some((index, t_entry)) = stack.pop() {
+            if closest.is_some_and(|(_, t)| t_entry > t) {
+                continue;
+            }
+            let node = &self.nodes[index as usize];
+            if node.count > 0 {
+                let start = node.start as usize;
+                for &primitive in &self.primitives[start..start + node.count as usize] {
+                    if let Some(t) = hit(primitive as usize)
+                        && closest.is_none_or(|(_, best)| t < best)
+                    {
+                        closest = Some((primitive as usize, t));
+                    }
+                }
+                continue;
+            }
+
+            // Push the far child first so the near one is visited next
+            let children = [node.start, node.start + 1].map(|child| {
+                let t = entry(&self.nodes[child as usize]);
+                (child, t)
+            });
+            let [near, far] = match (children[0].1, children[1].1) {
+                (Some(a), Some(b)) if b < a => [children[1], children[0]],
+                _ => children,
+            };
+            for (child, t) in [far, near] {
+                if let Some(t) = t {
+                    stack.push((child, t));
+                }
+            }
+        }
+
+        closest
+    }
+}
+
+/// Ray parameter at which a ray enters a box (0 if it starts inside), or
+/// `None` if it misses
+fn ray_box_entry(ray_origin: Vec3, inv_dir: Vec3, min: Vec3, max: Vec3) -> Option<f32> {
+    let t1 = (min - ray_origin) * inv_dir;
+    let t2 = (max - ray_origin) * inv_dir;
+    let t_min = t1.min(t2).max_element();
+    let t_max = t1.max(t2).min_element();
+    (t_max >= 0.0 && t_min <= t_max).then_some(t_min.max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit boxes spaced along X
+    fn boxes(count: usize) -> Vec<(Vec3, Vec3)> {
+        (0..count)
+            .map(|i| {
+                let min = Vec3::new(i as f32 * 2.0, 0.0, 0.0);
+                (min, min + Vec3::ONE)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ray_cast_finds_nearest() {
+        let bounds = boxes(50);
+        let bvh = Bvh::build(&bounds);
+        assert_eq!(bvh.len(), 50);
+
+        // Ray along -X from beyond the last box hits it first
+        let origin = Vec3::new(200.0, 0.5, 0.5);
+        let mut tested = 0;
+        let hit = bvh.ray_cast(origin, Vec3::NEG_X, |i| {
+            tested += 1;
+            ray_box_entry(origin, Vec3::NEG_X.recip(), bounds[i].0, bounds[i].1)
+        });
+        assert_eq!(hit.map(|(i, _)| i), Some(49));
+        // Far boxes are culled once the closest hit is known
+        assert!(tested < 50);
+
+        // Ray passing beside the row misses everything
+        assert!(
+            bvh.ray_cast(Vec3::new(0.0, 5.0, 0.5), Vec3::X, |_| Some(0.0))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_triangle_bvh_matches_primitives() {
+        // Two triangles in the planes z = 0 and z = 1
+        let vertices = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0],
+            [0.0, 1.0, 1.0],
+        ];
+        let bvh = Bvh::from_triangles(&vertices, &[0, 1, 2, 3, 4, 5]);
+        let hit = bvh.ray_cast(Vec3::new(0.2, 0.2, 5.0), Vec3::NEG_Z, |i| {
+            Some(5.0 - vertices[i * 3][2])
+        });
+        assert_eq!(hit, Some((1, 4.0)));
+        assert!(Bvh::build(&[]).is_empty());
+    }
+}
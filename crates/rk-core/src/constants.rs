@@ -12,5 +12,11 @@ pub const SPHERE_LAT_SEGMENTS: u32 = 16;
 /// Default number of longitude segments for sphere mesh generation
 pub const SPHERE_LON_SEGMENTS: u32 = 32;
 
+/// Triangle budget of a simplified collision mesh copied from a visual
+pub const SIMPLIFIED_COLLISION_TRIANGLES: usize = 1000;
+
 /// Default color for parts and visuals (gray, RGBA)
 pub const DEFAULT_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+
+/// Default material roughness for parts and visuals (medium, non-metallic look)
+pub const DEFAULT_ROUGHNESS: f32 = 0.5;
@@ -0,0 +1,141 @@
+//! Bill of materials export as CSV
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+
+use uuid::Uuid;
+
+use crate::part::Part;
+
+use super::ExportError;
+
+/// One line of a bill of materials: identical parts counted together
+#[derive(Debug, Clone, PartialEq)]
+pub struct BomEntry {
+    /// Name of the first part in the group, by name
+    pub name: String,
+    /// Number of identical parts
+    pub quantity: usize,
+    /// Bounding box size of one part (meters)
+    pub size: [f32; 3],
+    /// Mass of one part (kg)
+    pub mass: f32,
+    /// Material name, if assigned
+    pub material: Option<String>,
+}
+
+/// Group parts into bill of materials entries
+///
+/// Parts count as identical when they have the same mesh geometry, mass
+/// and material, so copies of a part are tallied even after renaming.
+/// Entries are sorted by name.
+pub fn bill_of_materials(parts: &HashMap<Uuid, Part>) -> Vec<BomEntry> {
+    let mut parts: Vec<&Part> = parts.values().collect();
+    parts.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut entries: Vec<BomEntry> = Vec::new();
+    let mut groups: HashMap<(u64, u32, Option<&str>), usize> = HashMap::new();
+    for part in parts {
+        let key = (
+            geometry_hash(part),
+            part.mass.to_bits(),
+            part.material_name.as_deref(),
+        );
+        if let Some(&index) = groups.get(&key) {
+            entries[index].quantity += 1;
+            continue;
+        }
+        groups.insert(key, entries.len());
+        entries.push(BomEntry {
+            name: part.name.clone(),
+            quantity: 1,
+            size: std::array::from_fn(|i| part.bbox_max[i] - part.bbox_min[i]),
+            mass: part.mass,
+            material: part.material_name.clone(),
+        });
+    }
+    entries
+}
+
+/// Hash of a part's vertex positions and triangles
+fn geometry_hash(part: &Part) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for vertex in &part.vertices {
+        vertex.map(f32::to_bits).hash(&mut hasher);
+    }
+    part.indices.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Export the bill of materials as CSV (writes the file to disk)
+pub fn export_bom(parts: &HashMap<Uuid, Part>, path: impl AsRef<Path>) -> Result<(), ExportError> {
+    std::fs::write(path, export_bom_to_string(parts)).map_err(|e| ExportError::Io(e.to_string()))
+}
+
+/// Export the bill of materials as a CSV string
+///
+/// Columns are quantity, name, bounding box size in meters, unit mass in
+/// kilograms and material, one row per [`BomEntry`].
+pub fn export_bom_to_string(parts: &HashMap<Uuid, Part>) -> String {
+    let mut csv =
+        String::from("Quantity,Name,Size X (m),Size Y (m),Size Z (m),Mass (kg),Material\n");
+    for entry in bill_of_materials(parts) {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            entry.quantity,
+            csv_field(&entry.name),
+            entry.size[0],
+            entry.size[1],
+            entry.size[2],
+            entry.mass,
+            csv_field(entry.material.as_deref().unwrap_or("")),
+        ));
+    }
+    csv
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bom_counts_identical_parts() {
+        let mut wheel = Part::new("wheel");
+        wheel.vertices = vec![[0.0, 0.0, 0.0], [0.1, 0.0, 0.0], [0.0, 0.2, 0.05]];
+        wheel.indices = vec![0, 1, 2];
+        wheel.bbox_max = [0.1, 0.2, 0.05];
+        wheel.mass = 0.5;
+        wheel.material_name = Some("rubber".to_string());
+        let mut copy = wheel.clone();
+        copy.id = Uuid::new_v4();
+        copy.name = "wheel, rear".to_string();
+        let mut base = Part::new("base");
+        base.mass = 2.0;
+
+        let parts: HashMap<Uuid, Part> =
+            [wheel, copy, base].into_iter().map(|p| (p.id, p)).collect();
+
+        let entries = bill_of_materials(&parts);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "base");
+        assert_eq!(entries[1].name, "wheel");
+        assert_eq!(entries[1].quantity, 2);
+
+        let csv = export_bom_to_string(&parts);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("Quantity,Name"));
+        assert_eq!(lines[2], "2,wheel,0.1,0.2,0.05,0.5,rubber");
+        assert_eq!(csv_field("wheel, rear"), "\"wheel, rear\"");
+    }
+}
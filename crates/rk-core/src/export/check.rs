@@ -0,0 +1,310 @@
+//! Pre-export checks of the assembly
+//!
+//! [`check_export`] collects everything that would make URDF export fail or
+//! produce a robot that misbehaves in simulation, so it can be reviewed (and
+//! partly fixed with [`ExportFix`]) before any file is written.
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use glam::Vec3;
+use uuid::Uuid;
+
+use crate::assembly::{Assembly, JointIssue};
+use crate::inertia::InertiaMatrix;
+use crate::part::Part;
+use crate::types::JointType;
+
+/// How serious an export issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueSeverity {
+    /// The robot exports but may misbehave
+    Warning,
+    /// Export fails until the issue is fixed
+    Error,
+}
+
+/// Automatic fix for an export issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFix {
+    /// Point a joint's degenerate axis along +X
+    ResetAxis(Uuid),
+    /// Swap a joint's inverted lower and upper limits
+    SwapLimits(Uuid),
+    /// Clamp a revolute joint's limits into ±π
+    ClampRevoluteLimits(Uuid),
+    /// Make a joint's negative effort and velocity limits positive
+    FlipNegativeLimits(Uuid),
+    /// Make a joint's negative damping and friction positive
+    FlipNegativeDynamics(Uuid),
+    /// Recompute a link's inertia from its mass and its part's bounding box
+    RecomputeInertia(Uuid),
+}
+
+impl ExportFix {
+    /// Apply the fix; does nothing if its link or joint no longer exists
+    pub fn apply(&self, assembly: &mut Assembly, parts: &HashMap<Uuid, Part>) {
+        match *self {
+            ExportFix::ResetAxis(joint_id) => {
+                if let Some(joint) = assembly.get_joint_mut(joint_id) {
+                    joint.axis = Vec3::X;
+                }
+            }
+            ExportFix::SwapLimits(joint_id) => {
+                if let Some(limits) = assembly
+                    .get_joint_mut(joint_id)
+                    .and_then(|joint| joint.limits.as_mut())
+                    && limits.lower > limits.upper
+                {
+                    std::mem::swap(&mut limits.lower, &mut limits.upper);
+                }
+            }
+            ExportFix::ClampRevoluteLimits(joint_id) => {
+                if let Some(limits) = assembly
+                    .get_joint_mut(joint_id)
+                    .and_then(|joint| joint.limits.as_mut())
+                {
+                    limits.lower = limits.lower.clamp(-PI, PI);
+                    limits.upper = limits.upper.clamp(-PI, PI);
+                }
+            }
+            ExportFix::FlipNegativeLimits(joint_id) => {
+                if let Some(limits) = assembly
+                    .get_joint_mut(joint_id)
+                    .and_then(|joint| joint.limits.as_mut())
+                {
+                    limits.effort = limits.effort.abs();
+                    limits.velocity = limits.velocity.abs();
+                }
+            }
+            ExportFix::FlipNegativeDynamics(joint_id) => {
+                if let Some(dynamics) = assembly
+                    .get_joint_mut(joint_id)
+                    .and_then(|joint| joint.dynamics.as_mut())
+                {
+                    dynamics.damping = dynamics.damping.abs();
+                    dynamics.friction = dynamics.friction.abs();
+                }
+            }
+            ExportFix::RecomputeInertia(link_id) => {
+                if let Some(link) = assembly.get_link_mut(link_id)
+                    && let Some(part) = link.part_id.and_then(|id| parts.get(&id))
+                {
+                    link.inertial.inertia = InertiaMatrix::from_bounding_box(
+                        link.inertial.mass,
+                        part.bbox_min,
+                        part.bbox_max,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Problem found by [`check_export`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportIssue {
+    pub severity: IssueSeverity,
+    /// Name of the link or joint the issue is about
+    pub subject: String,
+    pub message: String,
+    /// Fix that resolves the issue, if it can be fixed automatically
+    pub fix: Option<ExportFix>,
+}
+
+/// Check the assembly for problems before URDF export
+///
+/// Covers links not connected to the root, joints with broken axes or
+/// limits, spherical joints URDF cannot express, links whose part is missing or has no triangles, and links with
+/// geometry but no mass or a non-physical inertia. Issues are ordered
+/// errors first, then by subject name.
+pub fn check_export(assembly: &Assembly, parts: &HashMap<Uuid, Part>) -> Vec<ExportIssue> {
+    let mut issues = Vec::new();
+    let link_name = |id: &Uuid| {
+        assembly
+            .links
+            .get(id)
+            .map_or_else(|| id.to_string(), |link| link.name.clone())
+    };
+
+    let connectivity = assembly.connectivity_report();
+    for id in &connectivity.unreachable {
+        issues.push(ExportIssue {
+            severity: IssueSeverity::Error,
+            subject: link_name(id),
+            message: "Not connected to the root link".to_string(),
+            fix: None,
+        });
+    }
+    for id in &connectivity.multiple_parents {
+        issues.push(ExportIssue {
+            severity: IssueSeverity::Error,
+            subject: link_name(id),
+            message: "Child of more than one joint".to_string(),
+            fix: None,
+        });
+    }
+
+    for (joint_id, issue) in assembly.joint_issues() {
+        let fix = match issue {
+            JointIssue::InvertedLimits { .. } => ExportFix::SwapLimits(joint_id),
+            JointIssue::RevoluteLimitOutOfRange(_) => ExportFix::ClampRevoluteLimits(joint_id),
+            JointIssue::NegativeEffort(_) | JointIssue::NegativeVelocity(_) => {
+                ExportFix::FlipNegativeLimits(joint_id)
+            }
+            JointIssue::NegativeDamping(_) | JointIssue::NegativeFriction(_) => {
+                ExportFix::FlipNegativeDynamics(joint_id)
+            }
+            JointIssue::DegenerateAxis(_) => ExportFix::ResetAxis(joint_id),
+        };
+        issues.push(ExportIssue {
+            severity: if issue.is_error() {
+                IssueSeverity::Error
+            } else {
+                IssueSeverity::Warning
+            },
+            subject: assembly.joints[&joint_id].name.clone(),
+            message: issue.to_string(),
+            fix: Some(fix),
+        });
+    }
+
+    for joint in assembly.joints.values() {
+        if joint.joint_type == JointType::Spherical {
+            issues.push(ExportIssue {
+                severity: IssueSeverity::Warning,
+                subject: joint.name.clone(),
+                message: "URDF has no spherical joint; exported as floating".to_string(),
+                fix: None,
+            });
+        }
+    }
+
+    for link in assembly.links.values() {
+        let Some(part_id) = link.part_id else {
+            continue;
+        };
+        let mut issue = |severity, message: &str, fix| {
+            issues.push(ExportIssue {
+                severity,
+                subject: link.name.clone(),
+                message: message.to_string(),
+                fix,
+            });
+        };
+        let Some(part) = parts.get(&part_id) else {
+            issue(IssueSeverity::Error, "Part is missing", None);
+            continue;
+        };
+        if part.indices.is_empty() {
+            issue(
+                IssueSeverity::Warning,
+                "Part has no triangles; its mesh file will be empty",
+                None,
+            );
+        }
+        if link.inertial.mass <= 0.0 {
+            issue(
+                IssueSeverity::Warning,
+                "Link with geometry has no mass",
+                None,
+            );
+        } else if !link.inertial.inertia.is_valid() {
+            issue(
+                IssueSeverity::Warning,
+                "Inertia is not physical (non-positive or violates the triangle inequality)",
+                Some(ExportFix::RecomputeInertia(link.id)),
+            );
+        }
+    }
+
+    issues.sort_by(|a, b| {
+        (b.severity == IssueSeverity::Error)
+            .cmp(&(a.severity == IssueSeverity::Error))
+            .then_with(|| a.subject.cmp(&b.subject))
+    });
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembly::{Joint, Link};
+    use crate::types::{JointLimits, Pose};
+
+    #[test]
+    fn test_check_and_fix() {
+        let mut part = Part::new("body");
+        part.mass = 1.0;
+        part.bbox_min = [-0.1; 3];
+        part.bbox_max = [0.1; 3];
+        let mut assembly = Assembly::new("robot");
+        let mut base_link = Link::from_part(&part);
+        base_link.inertial.inertia.ixx = 1.0;
+        let base = assembly.add_link(base_link);
+        let arm = assembly.add_link(Link::empty("arm"));
+        assembly.add_link(Link::empty("loose"));
+        let mut joint = Joint::fixed("shoulder", base, arm, Pose::default());
+        joint.joint_type = JointType::Revolute;
+        joint.axis = Vec3::ZERO;
+        joint.limits = Some(JointLimits {
+            lower: 1.0,
+            upper: -1.0,
+            ..JointLimits::default_revolute()
+        });
+        assembly.connect(base, arm, joint).unwrap();
+        let parts: HashMap<Uuid, Part> = [(part.id, part)].into();
+
+        let issues = check_export(&assembly, &parts);
+        let messages: Vec<(&str, IssueSeverity)> = issues
+            .iter()
+            .map(|i| (i.subject.as_str(), i.severity))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("loose", IssueSeverity::Error),
+                ("shoulder", IssueSeverity::Error),
+                ("body", IssueSeverity::Warning),
+                ("body", IssueSeverity::Warning),
+                ("shoulder", IssueSeverity::Warning),
+            ]
+        );
+
+        for fix in issues.iter().filter_map(|issue| issue.fix) {
+            fix.apply(&mut assembly, &parts);
+        }
+        assert!(assembly.links[&base].inertial.inertia.is_valid());
+        // Only the problems that need a decision are left
+        let remaining: Vec<String> = check_export(&assembly, &parts)
+            .into_iter()
+            .map(|i| i.message)
+            .collect();
+        assert_eq!(
+            remaining,
+            [
+                "Not connected to the root link",
+                "Part has no triangles; its mesh file will be empty"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spherical_joint_warns() {
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(Link::empty("base"));
+        let head = assembly.add_link(Link::empty("head"));
+        let mut joint = Joint::fixed("neck", base, head, Pose::default());
+        joint.joint_type = JointType::Spherical;
+        assembly.connect(base, head, joint).unwrap();
+
+        let issues = check_export(&assembly, &HashMap::new());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].subject, "neck");
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+
+        let urdf =
+            crate::export::export_urdf_to_string(&assembly, &HashMap::new(), "robot").unwrap();
+        assert!(urdf.contains("type=\"floating\""));
+    }
+}
@@ -0,0 +1,249 @@
+//! COLLADA (.dae) export of the posed assembly for DCC tools
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use glam::Vec3;
+use uuid::Uuid;
+
+use crate::assembly::Assembly;
+use crate::part::Part;
+
+use super::{ExportError, xml_escape};
+
+/// Export the assembly as a COLLADA scene (writes the file to disk)
+///
+/// See [`export_dae_to_string`] for what the scene contains.
+pub fn export_dae(
+    assembly: &Assembly,
+    parts: &HashMap<Uuid, Part>,
+    path: impl AsRef<Path>,
+) -> Result<(), ExportError> {
+    let dae = export_dae_to_string(assembly, parts);
+    std::fs::write(path, dae).map_err(|e| ExportError::Io(e.to_string()))
+}
+
+/// Export the assembly as a COLLADA scene string
+///
+/// Every part with geometry becomes one geometry and one scene node, placed
+/// in meters by its world transform in the current pose (call one of the
+/// `update_world_transforms*` methods first). Parts not attached to a link
+/// are placed by their `origin_transform`. Parts sharing a material name
+/// share a material; the others get one of their own with their color.
+pub fn export_dae_to_string(assembly: &Assembly, parts: &HashMap<Uuid, Part>) -> String {
+    let mut parts: Vec<&Part> = parts.values().filter(|p| !p.indices.is_empty()).collect();
+    parts.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Material of each part: (name, color), deduplicated by name
+    let part_materials: Vec<(String, [f32; 4])> = parts
+        .iter()
+        .map(|part| {
+            let name = part
+                .material_name
+                .clone()
+                .unwrap_or_else(|| format!("{}_material", part.name));
+            (name, part.color)
+        })
+        .collect();
+    let mut materials: Vec<&(String, [f32; 4])> = Vec::new();
+    for material in &part_materials {
+        if !materials.iter().any(|m| m.0 == material.0) {
+            materials.push(material);
+        }
+    }
+    let material_index = |name: &str| materials.iter().position(|m| m.0 == name).unwrap_or(0);
+
+    let mut dae = String::new();
+    dae.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    dae.push_str(
+        "<COLLADA xmlns=\"http://www.collada.org/2005/11/COLLADASchema\" version=\"1.4.1\">\n",
+    );
+    dae.push_str("  <asset>\n    <unit name=\"meter\" meter=\"1\"/>\n    <up_axis>Z_UP</up_axis>\n  </asset>\n");
+
+    dae.push_str("  <library_effects>\n");
+    for (index, (_, color)) in materials.iter().enumerate() {
+        dae.push_str(&format!(
+            "    <effect id=\"material{}-effect\">\n      <profile_COMMON>\n        <technique sid=\"common\">\n          <lambert>\n",
+            index
+        ));
+        dae.push_str(&format!(
+            "            <diffuse><color>{} {} {} {}</color></diffuse>\n",
+            color[0], color[1], color[2], color[3]
+        ));
+        dae.push_str(&format!(
+            "            <transparency><float>{}</float></transparency>\n",
+            color[3]
+        ));
+        dae.push_str(
+            "          </lambert>\n        </technique>\n      </profile_COMMON>\n    </effect>\n",
+        );
+    }
+    dae.push_str("  </library_effects>\n");
+
+    dae.push_str("  <library_materials>\n");
+    for (index, (name, _)) in materials.iter().enumerate() {
+        dae.push_str(&format!(
+            "    <material id=\"material{}\" name=\"{}\"><instance_effect url=\"#material{}-effect\"/></material>\n",
+            index,
+            xml_escape(name),
+            index
+        ));
+    }
+    dae.push_str("  </library_materials>\n");
+
+    dae.push_str("  <library_geometries>\n");
+    for (index, part) in parts.iter().enumerate() {
+        write_geometry(&mut dae, &format!("geometry{}", index), part);
+    }
+    dae.push_str("  </library_geometries>\n");
+
+    dae.push_str("  <library_visual_scenes>\n");
+    dae.push_str(&format!(
+        "    <visual_scene id=\"scene\" name=\"{}\">\n",
+        xml_escape(&assembly.name)
+    ));
+    for (index, (part, (material, _))) in parts.iter().zip(&part_materials).enumerate() {
+        let transform = match assembly.find_link_by_part(part.id) {
+            Some(link) => assembly.posed_part_transform(link, part),
+            None => part.origin_transform,
+        };
+        // COLLADA matrices are row-major
+        let matrix: Vec<String> = transform
+            .transpose()
+            .to_cols_array()
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        let name = xml_escape(&part.name);
+        dae.push_str(&format!(
+            "      <node id=\"node{}\" name=\"{}\" type=\"NODE\">\n",
+            index, name
+        ));
+        dae.push_str(&format!(
+            "        <matrix sid=\"transform\">{}</matrix>\n",
+            matrix.join(" ")
+        ));
+        dae.push_str(&format!(
+            "        <instance_geometry url=\"#geometry{}\" name=\"{}\">\n",
+            index, name
+        ));
+        dae.push_str(&format!(
+            "          <bind_material><technique_common><instance_material symbol=\"material\" target=\"#material{}\"/></technique_common></bind_material>\n",
+            material_index(material)
+        ));
+        dae.push_str("        </instance_geometry>\n      </node>\n");
+    }
+    dae.push_str("    </visual_scene>\n  </library_visual_scenes>\n");
+
+    dae.push_str("  <scene>\n    <instance_visual_scene url=\"#scene\"/>\n  </scene>\n");
+    dae.push_str("</COLLADA>\n");
+    dae
+}
+
+/// Write a part's mesh in its own coordinates, with one normal per triangle
+fn write_geometry(dae: &mut String, id: &str, part: &Part) {
+    let triangles: Vec<&[u32]> = part.indices.chunks_exact(3).collect();
+    let normals: Vec<[f32; 3]> = triangles
+        .iter()
+        .enumerate()
+        .map(|(triangle, chunk)| match part.normals.get(triangle) {
+            Some(normal) => *normal,
+            None => {
+                let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(part.vertices[chunk[i] as usize]));
+                (b - a).cross(c - a).normalize_or(Vec3::Z).to_array()
+            }
+        })
+        .collect();
+
+    dae.push_str(&format!(
+        "    <geometry id=\"{}\" name=\"{}\">\n      <mesh>\n",
+        id,
+        xml_escape(&part.name)
+    ));
+    write_source(dae, &format!("{}-positions", id), &part.vertices);
+    write_source(dae, &format!("{}-normals", id), &normals);
+    dae.push_str(&format!(
+        "        <vertices id=\"{}-vertices\"><input semantic=\"POSITION\" source=\"#{}-positions\"/></vertices>\n",
+        id, id
+    ));
+    dae.push_str(&format!(
+        "        <triangles material=\"material\" count=\"{}\">\n",
+        triangles.len()
+    ));
+    dae.push_str(&format!(
+        "          <input semantic=\"VERTEX\" source=\"#{}-vertices\" offset=\"0\"/>\n",
+        id
+    ));
+    dae.push_str(&format!(
+        "          <input semantic=\"NORMAL\" source=\"#{}-normals\" offset=\"1\"/>\n",
+        id
+    ));
+    // Each corner indexes a position and its triangle's normal
+    let corners: Vec<String> = triangles
+        .iter()
+        .enumerate()
+        .flat_map(|(triangle, chunk)| chunk.iter().map(move |v| format!("{} {}", v, triangle)))
+        .collect();
+    dae.push_str(&format!("          <p>{}</p>\n", corners.join(" ")));
+    dae.push_str("        </triangles>\n      </mesh>\n    </geometry>\n");
+}
+
+/// Write a source of XYZ triples
+fn write_source(dae: &mut String, id: &str, values: &[[f32; 3]]) {
+    let floats: Vec<String> = values.iter().flatten().map(|v| v.to_string()).collect();
+    dae.push_str(&format!("        <source id=\"{}\">\n", id));
+    dae.push_str(&format!(
+        "          <float_array id=\"{}-array\" count=\"{}\">{}</float_array>\n",
+        id,
+        floats.len(),
+        floats.join(" ")
+    ));
+    dae.push_str(&format!(
+        "          <technique_common>\n            <accessor source=\"#{}-array\" count=\"{}\" stride=\"3\">\n",
+        id,
+        values.len()
+    ));
+    for axis in ["X", "Y", "Z"] {
+        dae.push_str(&format!(
+            "              <param name=\"{}\" type=\"float\"/>\n",
+            axis
+        ));
+    }
+    dae.push_str("            </accessor>\n          </technique_common>\n        </source>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Mat4;
+
+    use super::*;
+    use crate::assembly::Link;
+
+    #[test]
+    fn test_dae_places_parts_and_shares_materials() {
+        let mut base = Part::new("base");
+        base.vertices = vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        base.indices = vec![0, 1, 2];
+        base.material_name = Some("steel".to_string());
+        base.origin_transform = Mat4::from_translation(Vec3::new(0.0, 0.0, 2.0));
+        let mut arm = base.clone();
+        arm.id = Uuid::new_v4();
+        arm.name = "arm".to_string();
+        let empty = Part::new("empty");
+
+        let mut assembly = Assembly::new("robot");
+        assembly.add_link(Link::from_part(&base));
+        assembly.update_world_transforms();
+        let parts: HashMap<Uuid, Part> =
+            [base, arm, empty].into_iter().map(|p| (p.id, p)).collect();
+
+        let dae = export_dae_to_string(&assembly, &parts);
+        assert_eq!(dae.matches("<geometry id=").count(), 2);
+        assert_eq!(dae.matches("<material id=").count(), 1);
+        assert!(dae.contains("name=\"steel\""));
+        // Computed normal, and both parts lifted by their origin transform
+        assert!(dae.contains("count=\"3\">0 0 1</float_array>"));
+        assert_eq!(dae.matches("1 0 0 0 0 1 0 0 0 0 1 2 0 0 0 1").count(), 2);
+        assert!(dae.contains("<p>0 0 1 0 2 0</p>"));
+    }
+}
@@ -1,26 +1,61 @@
-//! URDF export functionality
+//! URDF, COLLADA and bill of materials export functionality
 
+mod bom;
+mod check;
+mod dae;
 mod options;
 mod xml;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use uuid::Uuid;
 
-use crate::assembly::Assembly;
-use crate::mesh::save_stl;
+use crate::assembly::{Assembly, CollisionMesh};
+use crate::mesh::{StlUnit, save_stl_with_unit};
 use crate::part::Part;
+use crate::project::{MaterialDef, Project, UrdfExtension};
 
-pub use options::ExportOptions;
+pub use bom::{BomEntry, bill_of_materials, export_bom, export_bom_to_string};
+pub use check::{ExportFix, ExportIssue, IssueSeverity, check_export};
+pub use dae::{export_dae, export_dae_to_string};
+pub use options::{DEFAULT_PRECISION, ExportOptions};
 pub use xml::{sanitize_filename, xml_escape};
 
-use xml::generate_urdf_string;
+use xml::{XmlFormat, generate_urdf_string};
 
 /// Export assembly to URDF (writes files to disk)
+///
+/// Library materials and preserved extension elements belong to the
+/// project; use [`export_project_urdf`] to write them too.
 pub fn export_urdf(
     assembly: &Assembly,
     parts: &HashMap<Uuid, Part>,
     options: &ExportOptions,
+) -> Result<String, ExportError> {
+    write_urdf(assembly, parts, &[], &[], options)
+}
+
+/// Export a project to URDF (writes files to disk), with its library
+/// materials and preserved extension elements
+pub fn export_project_urdf(
+    project: &Project,
+    options: &ExportOptions,
+) -> Result<String, ExportError> {
+    write_urdf(
+        &project.assembly,
+        project.parts(),
+        &project.materials,
+        &project.extensions,
+        options,
+    )
+}
+
+fn write_urdf(
+    assembly: &Assembly,
+    parts: &HashMap<Uuid, Part>,
+    materials: &[MaterialDef],
+    extensions: &[UrdfExtension],
+    options: &ExportOptions,
 ) -> Result<String, ExportError> {
     // Validate assembly
     assembly
@@ -32,22 +67,40 @@ pub fn export_urdf(
     std::fs::create_dir_all(&mesh_dir).map_err(|e| ExportError::Io(e.to_string()))?;
 
     // Export meshes and collect paths
+    let mesh_uri = |filename: &str| {
+        if options.use_package_uri {
+            format!("package://{}/{}", options.robot_name, options.mesh_prefix) + "/" + filename
+        } else {
+            format!("{}/{}", options.mesh_prefix, filename)
+        }
+    };
     let mut mesh_paths = HashMap::new();
     for (part_id, part) in parts {
         let filename = sanitize_filename(&part.name) + ".stl";
         let mesh_path = mesh_dir.join(&filename);
-        save_stl(part, &mesh_path).map_err(|e| ExportError::MeshExport(e.to_string()))?;
-
-        let uri = if options.use_package_uri {
-            format!("package://{}/{}", options.robot_name, options.mesh_prefix) + "/" + &filename
-        } else {
-            format!("{}/{}", options.mesh_prefix, filename)
-        };
-        mesh_paths.insert(*part_id, uri);
+        save_stl_with_unit(part, &mesh_path, options.mesh_unit)
+            .map_err(|e| ExportError::MeshExport(e.to_string()))?;
+        mesh_paths.insert(*part_id, mesh_uri(&filename));
+    }
+    for (mesh, filename) in collision_mesh_files(assembly, parts) {
+        save_stl_with_unit(&mesh.to_part(), mesh_dir.join(&filename), options.mesh_unit)
+            .map_err(|e| ExportError::MeshExport(e.to_string()))?;
+        mesh_paths.insert(mesh.id, mesh_uri(&filename));
     }
 
     // Generate URDF string
-    let urdf = generate_urdf_string(assembly, parts, &mesh_paths, &options.robot_name)?;
+    let urdf = generate_urdf_string(
+        assembly,
+        parts,
+        materials,
+        &mesh_paths,
+        extensions,
+        XmlFormat {
+            mesh_unit: options.mesh_unit,
+            precision: options.precision,
+        },
+        &options.robot_name,
+    )?;
 
     // Write URDF file
     let urdf_path = options
@@ -59,11 +112,37 @@ pub fn export_urdf(
 }
 
 /// Export assembly to URDF string only (no file I/O, for WASM support)
-/// Note: Mesh URIs will be placeholder paths like "meshes/part_name.stl"
+/// Note: Mesh URIs will be placeholder paths like "meshes/part_name.stl",
+/// and numbers are written with [`DEFAULT_PRECISION`] significant digits
 pub fn export_urdf_to_string(
     assembly: &Assembly,
     parts: &HashMap<Uuid, Part>,
     robot_name: &str,
+) -> Result<String, ExportError> {
+    urdf_string(assembly, parts, &[], &[], robot_name)
+}
+
+/// Export a project to a URDF string, with its library materials and
+/// preserved extension elements (see [`export_urdf_to_string`])
+pub fn export_project_urdf_to_string(
+    project: &Project,
+    robot_name: &str,
+) -> Result<String, ExportError> {
+    urdf_string(
+        &project.assembly,
+        project.parts(),
+        &project.materials,
+        &project.extensions,
+        robot_name,
+    )
+}
+
+fn urdf_string(
+    assembly: &Assembly,
+    parts: &HashMap<Uuid, Part>,
+    materials: &[MaterialDef],
+    extensions: &[UrdfExtension],
+    robot_name: &str,
 ) -> Result<String, ExportError> {
     // Validate assembly
     assembly
@@ -77,8 +156,53 @@ pub fn export_urdf_to_string(
         let uri = format!("meshes/{}", filename);
         mesh_paths.insert(*part_id, uri);
     }
+    for (mesh, filename) in collision_mesh_files(assembly, parts) {
+        mesh_paths.insert(mesh.id, format!("meshes/{}", filename));
+    }
 
-    generate_urdf_string(assembly, parts, &mesh_paths, robot_name)
+    generate_urdf_string(
+        assembly,
+        parts,
+        materials,
+        &mesh_paths,
+        extensions,
+        XmlFormat {
+            mesh_unit: StlUnit::Meters,
+            precision: Some(DEFAULT_PRECISION),
+        },
+        robot_name,
+    )
+}
+
+/// Imported collision meshes of the assembly with the file names they are
+/// exported to, next to the visual meshes
+///
+/// A name already taken by a visual mesh or another collision mesh gets a
+/// numeric suffix, so no file overwrites another.
+fn collision_mesh_files<'a>(
+    assembly: &'a Assembly,
+    parts: &HashMap<Uuid, Part>,
+) -> Vec<(&'a CollisionMesh, String)> {
+    let mut taken: HashSet<String> = parts
+        .values()
+        .map(|part| sanitize_filename(&part.name) + ".stl")
+        .collect();
+    let mut files = Vec::new();
+    for link in assembly.links.values() {
+        for (index, collision) in link.collisions.iter().enumerate() {
+            if let Some(mesh) = &collision.mesh {
+                let stem = format!("{}_collision_{}", sanitize_filename(&link.name), index);
+                let mut filename = format!("{stem}.stl");
+                let mut suffix = 1;
+                while !taken.insert(filename.clone()) {
+                    filename = format!("{stem}_{suffix}.stl");
+                    suffix += 1;
+                }
+                files.push((mesh, filename));
+            }
+        }
+    }
+    files
 }
 
 /// Export-related errors
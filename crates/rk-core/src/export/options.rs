@@ -2,6 +2,11 @@
 
 use std::path::PathBuf;
 
+use crate::mesh::StlUnit;
+
+/// Significant digits exported numbers are rounded to by default
+pub const DEFAULT_PRECISION: u32 = 6;
+
 /// Export options for URDF generation
 #[derive(Debug, Clone)]
 pub struct ExportOptions {
@@ -13,6 +18,15 @@ pub struct ExportOptions {
     pub mesh_prefix: String,
     /// Whether to use package:// URIs or relative paths
     pub use_package_uri: bool,
+    /// Unit the exported STL meshes are written in (the URDF scales them back to meters)
+    pub mesh_unit: StlUnit,
+    /// Significant digits numbers are written with, or `None` to write them
+    /// at full precision
+    ///
+    /// With a precision, positions and angles within its last digit of zero
+    /// are written as 0, and angles that close to a multiple of 90° as that
+    /// exact multiple.
+    pub precision: Option<u32>,
 }
 
 impl Default for ExportOptions {
@@ -22,6 +36,8 @@ impl Default for ExportOptions {
             robot_name: "robot".to_string(),
             mesh_prefix: "meshes".to_string(),
             use_package_uri: false,
+            mesh_unit: StlUnit::Meters,
+            precision: Some(DEFAULT_PRECISION),
         }
     }
 }
@@ -1,30 +1,125 @@
 //! XML generation utilities for URDF export
 
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::f32::consts::FRAC_PI_2;
 
 use uuid::Uuid;
 
-use crate::assembly::{Assembly, Joint, Link};
+use crate::assembly::{Assembly, Joint, Link, VisualElement};
+use crate::mesh::StlUnit;
 use crate::part::Part;
-use crate::types::{JointType, Pose};
+use crate::project::{ExtensionScope, MaterialDef, UrdfExtension};
+use crate::types::{GeometryType, JointTransmission, JointType, Pose};
 
 use super::ExportError;
 
+/// How numbers and mesh scales are written to the URDF
+#[derive(Debug, Clone, Copy)]
+pub struct XmlFormat {
+    /// Unit the exported meshes are written in
+    pub mesh_unit: StlUnit,
+    /// Significant digits numbers are rounded to, or `None` for full precision
+    pub precision: Option<u32>,
+}
+
+impl XmlFormat {
+    /// Smallest magnitude a position or angle keeps before it is snapped to
+    /// zero (or to the nearest quarter turn)
+    fn tolerance(&self) -> Option<f32> {
+        self.precision
+            .map(|digits| 10f32.powi(-(digits.max(1) as i32)))
+    }
+
+    /// Round a value to the configured number of significant digits
+    fn value(&self, v: f32) -> f32 {
+        let Some(digits) = self.precision else {
+            return v;
+        };
+        if v == 0.0 || !v.is_finite() {
+            // Also turns -0 into 0
+            return v + 0.0;
+        }
+        let v = v as f64;
+        let scale = 10f64.powi(digits.max(1) as i32 - 1 - v.abs().log10().floor() as i32);
+        ((v * scale).round() / scale) as f32 + 0.0
+    }
+
+    /// Round a position or direction component, snapping near-zero values
+    fn coord(&self, v: f32) -> f32 {
+        match self.tolerance() {
+            Some(tolerance) if v.abs() < tolerance => 0.0,
+            _ => self.value(v),
+        }
+    }
+
+    /// Round an angle, snapping values close to a multiple of a quarter turn
+    /// to that multiple at full precision
+    fn angle(&self, v: f32) -> f32 {
+        if let Some(tolerance) = self.tolerance() {
+            let quarters = (v / FRAC_PI_2).round();
+            if quarters != 0.0 && (v - quarters * FRAC_PI_2).abs() < tolerance {
+                return quarters * FRAC_PI_2;
+            }
+        }
+        self.coord(v)
+    }
+
+    /// Three space-separated components
+    fn triple(&self, v: [f32; 3], f: impl Fn(&Self, f32) -> f32) -> String {
+        format!("{} {} {}", f(self, v[0]), f(self, v[1]), f(self, v[2]))
+    }
+
+    /// An RGBA color
+    fn rgba(&self, color: &[f32; 4]) -> String {
+        color.map(|c| self.value(c).to_string()).join(" ")
+    }
+
+    /// Geometry with its sizes rounded, and mesh scales converted back to
+    /// meters when meshes are exported in another unit
+    fn geometry(&self, geometry: &GeometryType) -> GeometryType {
+        match geometry {
+            GeometryType::Mesh { path, scale } => {
+                let scale = if self.mesh_unit != StlUnit::Meters {
+                    let factor = self.mesh_unit.scale_factor();
+                    Some(scale.unwrap_or([1.0; 3]).map(|s| s * factor))
+                } else {
+                    *scale
+                };
+                GeometryType::Mesh {
+                    path: path.clone(),
+                    scale: scale.map(|s| s.map(|s| self.value(s))),
+                }
+            }
+            GeometryType::Box { size } => GeometryType::Box {
+                size: size.map(|s| self.value(s)),
+            },
+            GeometryType::Cylinder { radius, length } => GeometryType::Cylinder {
+                radius: self.value(*radius),
+                length: self.value(*length),
+            },
+            GeometryType::Sphere { radius } => GeometryType::Sphere {
+                radius: self.value(*radius),
+            },
+            GeometryType::Capsule { radius, length } => GeometryType::Capsule {
+                radius: self.value(*radius),
+                length: self.value(*length),
+            },
+        }
+    }
+}
+
 /// Internal function to generate URDF XML string
 pub fn generate_urdf_string(
     assembly: &Assembly,
     parts: &HashMap<Uuid, Part>,
+    materials: &[MaterialDef],
     mesh_paths: &HashMap<Uuid, String>,
+    extensions: &[UrdfExtension],
+    format: XmlFormat,
     robot_name: &str,
 ) -> Result<String, ExportError> {
-    let roots = assembly.get_root_links();
-    let root_id = roots.first().copied().ok_or(ExportError::NoRootLink)?;
-    if roots.len() > 1 {
-        eprintln!(
-            "Warning: Multiple root links found ({}), using first one for URDF export",
-            roots.len()
-        );
-    }
+    let root_id = assembly.root_link().ok_or(ExportError::NoRootLink)?;
 
     // Build URDF string
     let mut urdf = String::new();
@@ -33,36 +128,59 @@ pub fn generate_urdf_string(
         xml_escape(robot_name)
     ));
 
-    // Collect unique materials
-    let mut materials: HashMap<String, [f32; 4]> = HashMap::new();
-    for part in parts.values() {
-        if let Some(ref name) = part.material_name {
-            materials.insert(name.clone(), part.color);
-        }
+    // Write the library's materials, then any other named material a part
+    // carries, with that part's color
+    for material in materials {
+        write_material(
+            &mut urdf,
+            &material.name,
+            &material.color,
+            material.texture.as_deref(),
+            format,
+        );
     }
-
-    // Write material definitions
-    for (name, color) in &materials {
-        urdf.push_str(&format!(
-            "  <material name=\"{}\">\n    <color rgba=\"{} {} {} {}\"/>\n  </material>\n\n",
-            xml_escape(name),
-            color[0],
-            color[1],
-            color[2],
-            color[3]
-        ));
+    let mut part_materials: Vec<(&str, &[f32; 4])> = parts
+        .values()
+        .filter_map(|part| Some((part.material_name.as_deref()?, &part.color)))
+        .filter(|(name, _)| !materials.iter().any(|m| m.name == *name))
+        .collect();
+    part_materials.sort_by(|a, b| a.0.cmp(b.0));
+    part_materials.dedup_by(|a, b| a.0 == b.0);
+    for (name, color) in part_materials {
+        write_material(&mut urdf, name, color, None, format);
     }
 
     // Write links and joints recursively
+    let mut visited = HashSet::new();
     write_link_recursive(
         &mut urdf,
         assembly,
         parts,
         mesh_paths,
+        format,
         root_id,
-        &mut HashSet::new(),
+        &mut visited,
     )?;
 
+    // Write transmissions for exported joints
+    let mut transmissions: Vec<(&Joint, &JointTransmission)> = assembly
+        .joints
+        .values()
+        .filter(|joint| visited.contains(&joint.child_link))
+        .filter_map(|joint| joint.transmission.as_ref().map(|t| (joint, t)))
+        .collect();
+    transmissions.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+    for (joint, transmission) in transmissions {
+        write_transmission(&mut urdf, &joint.name, transmission, format);
+    }
+
+    // Write extension elements kept from import
+    for extension in extensions {
+        if let Some(xml) = extension_xml(extension, assembly, &visited) {
+            urdf.push_str(&format!("  {}\n\n", xml));
+        }
+    }
+
     urdf.push_str("</robot>\n");
 
     Ok(urdf)
@@ -73,6 +191,7 @@ pub fn write_link_recursive(
     assembly: &Assembly,
     parts: &HashMap<Uuid, Part>,
     mesh_paths: &HashMap<Uuid, String>,
+    format: XmlFormat,
     link_id: Uuid,
     visited: &mut HashSet<Uuid>,
 ) -> Result<(), ExportError> {
@@ -96,10 +215,10 @@ pub fn write_link_recursive(
             .ok_or(ExportError::MeshNotFound(part_id))?;
 
         // Write link with mesh
-        write_link(urdf, link, Some(part), Some(mesh_uri));
+        write_link(urdf, link, Some(part), Some(mesh_uri), mesh_paths, format);
     } else {
         // Write empty link (no geometry)
-        write_link(urdf, link, None, None);
+        write_link(urdf, link, None, None, mesh_paths, format);
     }
 
     // Write joints and children
@@ -111,8 +230,10 @@ pub fn write_link_recursive(
                     .get(child_id)
                     .ok_or(ExportError::LinkNotFound(*child_id))?;
 
-                write_joint(urdf, joint, &link.name, &child_link.name, assembly);
-                write_link_recursive(urdf, assembly, parts, mesh_paths, *child_id, visited)?;
+                write_joint(urdf, joint, &link.name, &child_link.name, assembly, format);
+                write_link_recursive(
+                    urdf, assembly, parts, mesh_paths, format, *child_id, visited,
+                )?;
             }
         }
     }
@@ -120,40 +241,63 @@ pub fn write_link_recursive(
     Ok(())
 }
 
-pub fn write_link(urdf: &mut String, link: &Link, part: Option<&Part>, mesh_uri: Option<&str>) {
+pub fn write_link(
+    urdf: &mut String,
+    link: &Link,
+    part: Option<&Part>,
+    mesh_uri: Option<&str>,
+    mesh_paths: &HashMap<Uuid, String>,
+    format: XmlFormat,
+) {
     urdf.push_str(&format!("  <link name=\"{}\">\n", xml_escape(&link.name)));
 
     // Only write full link content if we have a part/mesh
     if let Some(_part) = part {
         // Inertial
         urdf.push_str("    <inertial>\n");
-        write_origin(urdf, &link.inertial.origin, 6);
-        urdf.push_str(&format!("      <mass value=\"{}\"/>\n", link.inertial.mass));
+        write_origin(urdf, &link.inertial.origin, 6, format);
+        urdf.push_str(&format!(
+            "      <mass value=\"{}\"/>\n",
+            format.value(link.inertial.mass)
+        ));
         let inertia = &link.inertial.inertia;
+        // Products of inertia that are negligible next to the moments are
+        // numerical noise from the mesh integration
+        let noise = format
+            .tolerance()
+            .map_or(0.0, |t| t * inertia.ixx.max(inertia.iyy).max(inertia.izz));
+        let product = |v: f32| {
+            if v.abs() < noise {
+                0.0
+            } else {
+                format.value(v)
+            }
+        };
         urdf.push_str(&format!(
             "      <inertia ixx=\"{}\" ixy=\"{}\" ixz=\"{}\" iyy=\"{}\" iyz=\"{}\" izz=\"{}\"/>\n",
-            inertia.ixx, inertia.ixy, inertia.ixz, inertia.iyy, inertia.iyz, inertia.izz
+            format.value(inertia.ixx),
+            product(inertia.ixy),
+            product(inertia.ixz),
+            format.value(inertia.iyy),
+            product(inertia.iyz),
+            format.value(inertia.izz)
         ));
         urdf.push_str("    </inertial>\n");
 
         // Visual elements
         for elem in &link.visuals {
-            let geom_str = elem.geometry.to_urdf_xml(mesh_uri);
-            write_visual_element(
-                urdf,
-                elem.name.as_deref(),
-                &elem.origin,
-                elem.material_name.as_deref(),
-                &elem.color,
-                elem.texture.as_deref(),
-                &geom_str,
-            );
+            let geom_str = format.geometry(&elem.geometry).to_urdf_xml(mesh_uri);
+            write_visual_element(urdf, elem, &geom_str, format);
         }
 
-        // Collision elements
+        // Collision elements, pointing at their own mesh file if they have one
         for elem in &link.collisions {
-            let geom_str = elem.geometry.to_urdf_xml(mesh_uri);
-            write_collision_element(urdf, elem.name.as_deref(), &elem.origin, &geom_str);
+            let uri = match &elem.mesh {
+                Some(mesh) => mesh_paths.get(&mesh.id).map(String::as_str),
+                None => mesh_uri,
+            };
+            let geom_str = format.geometry(&elem.geometry).to_urdf_xml(uri);
+            write_collision_element(urdf, elem.name.as_deref(), &elem.origin, &geom_str, format);
         }
     }
     // Empty links have no visual/collision/inertial
@@ -161,42 +305,53 @@ pub fn write_link(urdf: &mut String, link: &Link, part: Option<&Part>, mesh_uri:
     urdf.push_str("  </link>\n\n");
 }
 
-pub fn write_origin(urdf: &mut String, origin: &Pose, indent: usize) {
+pub fn write_origin(urdf: &mut String, origin: &Pose, indent: usize, format: XmlFormat) {
     let indent_str = " ".repeat(indent);
     urdf.push_str(&format!(
-        "{}<origin xyz=\"{} {} {}\" rpy=\"{} {} {}\"/>\n",
+        "{}<origin xyz=\"{}\" rpy=\"{}\"/>\n",
         indent_str,
-        origin.xyz[0],
-        origin.xyz[1],
-        origin.xyz[2],
-        origin.rpy[0],
-        origin.rpy[1],
-        origin.rpy[2]
+        format.triple(origin.xyz, XmlFormat::coord),
+        format.triple(origin.rpy, XmlFormat::angle)
     ));
 }
 
-pub fn write_visual_element(
+pub fn write_material(
     urdf: &mut String,
-    name: Option<&str>,
-    origin: &Pose,
-    material_name: Option<&str>,
+    name: &str,
     color: &[f32; 4],
     texture: Option<&str>,
+    format: XmlFormat,
+) {
+    urdf.push_str(&format!("  <material name=\"{}\">\n", xml_escape(name)));
+    urdf.push_str(&format!("    <color rgba=\"{}\"/>\n", format.rgba(color)));
+    if let Some(tex) = texture {
+        urdf.push_str(&format!(
+            "    <texture filename=\"{}\"/>\n",
+            xml_escape(tex)
+        ));
+    }
+    urdf.push_str("  </material>\n\n");
+}
+
+pub fn write_visual_element(
+    urdf: &mut String,
+    visual: &VisualElement,
     geometry_xml: &str,
+    format: XmlFormat,
 ) {
-    if let Some(n) = name {
+    if let Some(n) = &visual.name {
         urdf.push_str(&format!("    <visual name=\"{}\">\n", xml_escape(n)));
     } else {
         urdf.push_str("    <visual>\n");
     }
 
-    write_origin(urdf, origin, 6);
+    write_origin(urdf, &visual.origin, 6, format);
     urdf.push_str(&format!(
         "      <geometry>\n        {}\n      </geometry>\n",
         geometry_xml
     ));
 
-    if let Some(mat_name) = material_name {
+    if let Some(mat_name) = &visual.material_name {
         urdf.push_str(&format!(
             "      <material name=\"{}\"/>\n",
             xml_escape(mat_name)
@@ -204,10 +359,10 @@ pub fn write_visual_element(
     } else {
         urdf.push_str("      <material name=\"\">\n");
         urdf.push_str(&format!(
-            "        <color rgba=\"{} {} {} {}\"/>\n",
-            color[0], color[1], color[2], color[3]
+            "        <color rgba=\"{}\"/>\n",
+            format.rgba(&visual.color)
         ));
-        if let Some(tex) = texture {
+        if let Some(tex) = &visual.texture {
             urdf.push_str(&format!(
                 "        <texture filename=\"{}\"/>\n",
                 xml_escape(tex)
@@ -223,6 +378,7 @@ pub fn write_collision_element(
     name: Option<&str>,
     origin: &Pose,
     geometry_xml: &str,
+    format: XmlFormat,
 ) {
     if let Some(n) = name {
         urdf.push_str(&format!("    <collision name=\"{}\">\n", xml_escape(n)));
@@ -230,7 +386,7 @@ pub fn write_collision_element(
         urdf.push_str("    <collision>\n");
     }
 
-    write_origin(urdf, origin, 6);
+    write_origin(urdf, origin, 6, format);
     urdf.push_str(&format!(
         "      <geometry>\n        {}\n      </geometry>\n",
         geometry_xml
@@ -244,13 +400,16 @@ pub fn write_joint(
     parent_name: &str,
     child_name: &str,
     assembly: &Assembly,
+    format: XmlFormat,
 ) {
     let type_str = match joint.joint_type {
         JointType::Fixed => "fixed",
         JointType::Revolute => "revolute",
         JointType::Continuous => "continuous",
         JointType::Prismatic => "prismatic",
-        JointType::Floating => "floating",
+        // URDF has no ball joint; check_export warns about the lost
+        // translation lock
+        JointType::Floating | JointType::Spherical => "floating",
         JointType::Planar => "planar",
     };
 
@@ -267,34 +426,49 @@ pub fn write_joint(
         "    <child link=\"{}\"/>\n",
         xml_escape(child_name)
     ));
-    urdf.push_str(&format!(
-        "    <origin xyz=\"{} {} {}\" rpy=\"{} {} {}\"/>\n",
-        joint.origin.xyz[0],
-        joint.origin.xyz[1],
-        joint.origin.xyz[2],
-        joint.origin.rpy[0],
-        joint.origin.rpy[1],
-        joint.origin.rpy[2]
-    ));
+    write_origin(urdf, &joint.origin, 4, format);
 
     if joint.joint_type.has_axis() {
         urdf.push_str(&format!(
-            "    <axis xyz=\"{} {} {}\"/>\n",
-            joint.axis.x, joint.axis.y, joint.axis.z
+            "    <axis xyz=\"{}\"/>\n",
+            format.triple(joint.export_axis().into(), XmlFormat::coord)
         ));
     }
 
+    // Positions along a revolute joint are angles, along a prismatic one lengths
+    let position = |v: f32| {
+        if joint.joint_type == JointType::Prismatic {
+            format.coord(v)
+        } else {
+            format.angle(v)
+        }
+    };
+
     if let Some(ref limits) = joint.limits {
         urdf.push_str(&format!(
             "    <limit lower=\"{}\" upper=\"{}\" effort=\"{}\" velocity=\"{}\"/>\n",
-            limits.lower, limits.upper, limits.effort, limits.velocity
+            position(limits.lower),
+            position(limits.upper),
+            format.value(limits.effort),
+            format.value(limits.velocity)
         ));
     }
 
     if let Some(ref dynamics) = joint.dynamics {
         urdf.push_str(&format!(
             "    <dynamics damping=\"{}\" friction=\"{}\"/>\n",
-            dynamics.damping, dynamics.friction
+            format.value(dynamics.damping),
+            format.value(dynamics.friction)
+        ));
+    }
+
+    if let Some(ref sc) = joint.safety_controller {
+        urdf.push_str(&format!(
+            "    <safety_controller soft_lower_limit=\"{}\" soft_upper_limit=\"{}\" k_position=\"{}\" k_velocity=\"{}\"/>\n",
+            position(sc.soft_lower_limit),
+            position(sc.soft_upper_limit),
+            format.value(sc.k_position),
+            format.value(sc.k_velocity)
         ));
     }
 
@@ -304,8 +478,8 @@ pub fn write_joint(
             urdf.push_str(&format!(
                 "    <mimic joint=\"{}\" multiplier=\"{}\" offset=\"{}\"/>\n",
                 xml_escape(&mimic_joint.name),
-                mimic.multiplier,
-                mimic.offset
+                format.value(mimic.multiplier),
+                position(mimic.offset)
             ));
         }
     }
@@ -313,6 +487,68 @@ pub fn write_joint(
     urdf.push_str("  </joint>\n\n");
 }
 
+pub fn write_transmission(
+    urdf: &mut String,
+    joint_name: &str,
+    transmission: &JointTransmission,
+    format: XmlFormat,
+) {
+    let interface = xml_escape(&transmission.hardware_interface);
+    urdf.push_str(&format!(
+        "  <transmission name=\"{}\">\n",
+        xml_escape(&transmission.name)
+    ));
+    urdf.push_str(&format!(
+        "    <type>{}</type>\n",
+        xml_escape(&transmission.transmission_type)
+    ));
+    urdf.push_str(&format!(
+        "    <joint name=\"{}\">\n      <hardwareInterface>{}</hardwareInterface>\n    </joint>\n",
+        xml_escape(joint_name),
+        interface
+    ));
+    urdf.push_str(&format!(
+        "    <actuator name=\"{}\">\n      <hardwareInterface>{}</hardwareInterface>\n      <mechanicalReduction>{}</mechanicalReduction>\n    </actuator>\n",
+        xml_escape(&transmission.actuator_name),
+        interface,
+        format.value(transmission.mechanical_reduction)
+    ));
+    urdf.push_str("  </transmission>\n\n");
+}
+
+/// Resolve an extension's XML against the current assembly
+///
+/// Returns `None` if its link or joint was removed or is not exported, and
+/// rewrites the `reference` attribute if the target was renamed.
+fn extension_xml<'a>(
+    extension: &'a UrdfExtension,
+    assembly: &Assembly,
+    exported_links: &HashSet<Uuid>,
+) -> Option<Cow<'a, str>> {
+    let (old_name, new_name) = match &extension.scope {
+        ExtensionScope::Robot => return Some(Cow::Borrowed(&extension.xml)),
+        ExtensionScope::Link { id, name } => {
+            let link = assembly.links.get(id)?;
+            exported_links.contains(id).then_some((name, &link.name))?
+        }
+        ExtensionScope::Joint { id, name } => {
+            let joint = assembly.joints.get(id)?;
+            exported_links
+                .contains(&joint.child_link)
+                .then_some((name, &joint.name))?
+        }
+    };
+
+    if old_name == new_name {
+        return Some(Cow::Borrowed(&extension.xml));
+    }
+    Some(Cow::Owned(extension.xml.replacen(
+        &format!("reference=\"{}\"", xml_escape(old_name)),
+        &format!("reference=\"{}\"", xml_escape(new_name)),
+        1,
+    )))
+}
+
 pub fn xml_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -332,3 +568,90 @@ pub fn sanitize_filename(name: &str) -> String {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rounded_numbers() {
+        let format = XmlFormat {
+            mesh_unit: StlUnit::Meters,
+            precision: Some(6),
+        };
+        assert_eq!(format.value(0.123_456_79).to_string(), "0.123457");
+        assert_eq!(format.value(-1234.5678).to_string(), "-1234.57");
+        assert_eq!(format.value(1.5e-9).to_string(), "0.0000000015");
+        assert_eq!(format.coord(-3e-8).to_string(), "0");
+        assert_eq!(format.angle(1.5707961), FRAC_PI_2);
+        assert_eq!(format.angle(-3.1415925), -2.0 * FRAC_PI_2);
+        assert_eq!(format.angle(0.5).to_string(), "0.5");
+
+        let full = XmlFormat {
+            precision: None,
+            ..format
+        };
+        assert_eq!(full.coord(-3e-8), -3e-8);
+        assert_eq!(full.angle(1.5707961), 1.5707961);
+    }
+
+    #[test]
+    fn test_joint_dynamics_round_trip() {
+        use crate::assembly::Joint;
+        use crate::export::export_urdf_to_string;
+        use crate::types::JointDynamics;
+
+        let mut assembly = Assembly::new("robot");
+        let base = assembly.add_link(Link::empty("base"));
+        let arm = assembly.add_link(Link::empty("arm"));
+        let joint = Joint::builder("shoulder", base, arm)
+            .revolute()
+            .dynamics(0.25, 0.05)
+            .build();
+        assembly.connect(base, arm, joint).unwrap();
+
+        let urdf = export_urdf_to_string(&assembly, &HashMap::new(), "robot").unwrap();
+        let robot = urdf_rs::read_from_string(&urdf).unwrap();
+        let dynamics = robot.joints[0].dynamics.as_ref().unwrap();
+        let imported = JointDynamics {
+            damping: dynamics.damping as f32,
+            friction: dynamics.friction as f32,
+        };
+        assert_eq!(
+            imported,
+            JointDynamics {
+                damping: 0.25,
+                friction: 0.05
+            }
+        );
+    }
+
+    #[test]
+    fn test_collision_mesh_uri() {
+        use crate::assembly::{CollisionElement, CollisionMesh};
+        use crate::export::export_urdf_to_string;
+
+        let part = Part::new("body");
+        let mut assembly = Assembly::new("robot");
+        let mut link = Link::from_part(&part);
+        link.name = "base".to_string();
+        link.collisions = vec![CollisionElement::from_mesh(CollisionMesh::from_part(
+            &Part::new("hull"),
+        ))];
+        assembly.add_link(link);
+        let mut parts: HashMap<Uuid, Part> = [(part.id, part)].into();
+
+        let urdf = export_urdf_to_string(&assembly, &parts, "robot").unwrap();
+        let visual = urdf.find("<visual>").unwrap();
+        let collision = urdf.find("<collision").unwrap();
+        assert!(urdf[visual..collision].contains("meshes/body.stl"));
+        assert!(urdf[collision..].contains("meshes/base_collision_0.stl"));
+
+        // A visual mesh with the same file name keeps its file
+        let part = parts.values_mut().next().unwrap();
+        part.name = "base_collision_0".to_string();
+        let urdf = export_urdf_to_string(&assembly, &parts, "robot").unwrap();
+        let collision = urdf.find("<collision").unwrap();
+        assert!(urdf[collision..].contains("meshes/base_collision_0_1.stl"));
+    }
+}
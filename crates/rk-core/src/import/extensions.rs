@@ -0,0 +1,101 @@
+//! Passthrough of URDF elements the editor does not model
+//!
+//! `<gazebo>` blocks (and any other unknown children of `<robot>`) are kept
+//! as their original XML text so export can write them back unchanged.
+
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use quick_xml::name::QName;
+
+use super::ImportError;
+use super::transmission::attribute;
+
+/// Children of `<robot>` that import understands
+const MODELED_ELEMENTS: [&[u8]; 4] = [b"link", b"joint", b"material", b"transmission"];
+
+/// Collect unmodeled top-level elements as (reference attribute, verbatim XML) pairs
+pub fn parse_extensions(urdf: &str) -> Result<Vec<(Option<String>, String)>, ImportError> {
+    let mut reader = Reader::from_str(urdf);
+    let xml_err = |e: quick_xml::Error| ImportError::UrdfParse(e.to_string());
+
+    let mut extensions = Vec::new();
+    let mut depth = 0usize;
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event().map_err(xml_err)? {
+            Event::Start(e) => {
+                if depth == 1 && !MODELED_ELEMENTS.contains(&e.local_name().as_ref()) {
+                    let reference = attribute(&e, b"reference");
+                    let name = e.name().as_ref().to_vec();
+                    reader.read_to_end(QName(&name)).map_err(xml_err)?;
+                    let end = reader.buffer_position() as usize;
+                    extensions.push((reference, urdf[start..end].to_string()));
+                } else {
+                    depth += 1;
+                }
+            }
+            Event::Empty(e)
+                if depth == 1 && !MODELED_ELEMENTS.contains(&e.local_name().as_ref()) =>
+            {
+                let end = reader.buffer_position() as usize;
+                extensions.push((attribute(&e, b"reference"), urdf[start..end].to_string()));
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(extensions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAZEBO_BLOCK: &str = r#"<gazebo reference="base_link">
+    <material>Gazebo/Orange</material>
+    <sensor name="imu" type="imu">
+      <always_on>true</always_on>
+    </sensor>
+  </gazebo>"#;
+
+    fn robot_urdf() -> String {
+        format!(
+            r#"<?xml version="1.0"?>
+<robot name="bot">
+  <link name="base_link"/>
+  {GAZEBO_BLOCK}
+  <gazebo>
+    <plugin name="control" filename="libgazebo_ros_control.so"/>
+  </gazebo>
+</robot>
+"#
+        )
+    }
+
+    #[test]
+    fn test_parse_extensions_keeps_xml_verbatim() {
+        let extensions = parse_extensions(&robot_urdf()).unwrap();
+
+        assert_eq!(extensions.len(), 2);
+        assert_eq!(extensions[0].0.as_deref(), Some("base_link"));
+        assert_eq!(extensions[0].1, GAZEBO_BLOCK);
+        assert_eq!(extensions[1].0, None);
+        assert!(extensions[1].1.contains("libgazebo_ros_control.so"));
+    }
+
+    #[test]
+    fn test_gazebo_block_survives_import_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bot.urdf");
+        std::fs::write(&path, robot_urdf()).unwrap();
+
+        let project = crate::import_urdf(&path, &crate::ImportOptions::default()).unwrap();
+        let urdf = crate::export_project_urdf_to_string(&project, "bot").unwrap();
+
+        assert!(urdf.contains(GAZEBO_BLOCK));
+        assert!(urdf.contains(r#"<plugin name="control" filename="libgazebo_ros_control.so"/>"#));
+    }
+}
@@ -4,10 +4,14 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use crate::assembly::{CollisionElement, VisualElement};
+use crate::constants::DEFAULT_ROUGHNESS;
 use crate::inertia::InertiaMatrix;
 use crate::mesh::{MeshFormat, load_mesh};
 use crate::part::Part;
-use crate::primitive::{generate_box_mesh, generate_cylinder_mesh, generate_sphere_mesh};
+use crate::primitive::{
+    generate_box_mesh, generate_capsule_mesh_with_segments, generate_cylinder_mesh_with_segments,
+    generate_sphere_mesh_with_segments,
+};
 use crate::types::GeometryType;
 use crate::types::Pose;
 
@@ -71,6 +75,8 @@ pub fn process_visual_geometry(
                 .or_else(|| Some(format!("visual_{}", i))),
             origin: elem_origin,
             color: elem_color,
+            metallic: 0.0,
+            roughness: DEFAULT_ROUGHNESS,
             material_name: elem_material,
             texture: elem_texture,
             geometry: elem_geometry,
@@ -124,6 +130,7 @@ pub fn process_geometry(
     color: [f32; 4],
     material_name: Option<String>,
 ) -> Result<Option<Part>, ImportError> {
+    let quality = ctx.options.primitive_quality;
     let part = match geometry {
         urdf_rs::Geometry::Mesh { filename, scale } => {
             let mesh_path = resolve_mesh_path(filename, ctx.base_dir, ctx.package_paths)?;
@@ -157,22 +164,30 @@ pub fn process_geometry(
 
         urdf_rs::Geometry::Cylinder { radius, length } => Some(create_primitive_part(
             link_name,
-            generate_cylinder_mesh(*radius as f32, *length as f32),
+            generate_cylinder_mesh_with_segments(
+                *radius as f32,
+                *length as f32,
+                quality.segments(),
+            ),
             color,
             material_name,
         )),
 
         urdf_rs::Geometry::Sphere { radius } => Some(create_primitive_part(
             link_name,
-            generate_sphere_mesh(*radius as f32),
+            generate_sphere_mesh_with_segments(*radius as f32, quality.rings(), quality.segments()),
             color,
             material_name,
         )),
 
-        // Approximate capsule as cylinder (capsule mesh generation would be more complex)
         urdf_rs::Geometry::Capsule { radius, length } => Some(create_primitive_part(
             link_name,
-            generate_cylinder_mesh(*radius as f32, *length as f32),
+            generate_capsule_mesh_with_segments(
+                *radius as f32,
+                *length as f32,
+                quality.rings() / 2,
+                quality.segments(),
+            ),
             color,
             material_name,
         )),
@@ -193,6 +208,7 @@ pub fn process_collision_geometry(collisions: &[urdf_rs::Collision]) -> Vec<Coll
                 .or_else(|| Some(format!("collision_{}", i))),
             origin: Pose::from(&collision.origin),
             geometry: GeometryType::from(&collision.geometry),
+            mesh: None,
         })
         .collect()
 }
@@ -0,0 +1,102 @@
+//! Joint state import from YAML
+//!
+//! Accepts either a plain `joint_name: position` map or a ROS
+//! `sensor_msgs/JointState` message as printed by `rostopic echo`
+//! (parallel `name` and `position` lists).
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// Errors that can occur while reading a joint state file
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum JointStateError {
+    #[error("IO error: {0}")]
+    Io(String),
+
+    #[error("Failed to parse joint state: {0}")]
+    Parse(String),
+
+    #[error("Joint state has {names} names but {positions} positions")]
+    LengthMismatch { names: usize, positions: usize },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JointStateFile {
+    Message {
+        name: Vec<String>,
+        position: Vec<f32>,
+    },
+    Map(HashMap<String, f32>),
+}
+
+/// Parse a joint state YAML document into a joint name -> position map
+pub fn parse_joint_state(yaml: &str) -> Result<HashMap<String, f32>, JointStateError> {
+    let file: JointStateFile =
+        serde_yaml::from_str(yaml).map_err(|e| JointStateError::Parse(e.to_string()))?;
+
+    match file {
+        JointStateFile::Message { name, position } => {
+            if name.len() != position.len() {
+                return Err(JointStateError::LengthMismatch {
+                    names: name.len(),
+                    positions: position.len(),
+                });
+            }
+            Ok(name.into_iter().zip(position).collect())
+        }
+        JointStateFile::Map(positions) => Ok(positions),
+    }
+}
+
+/// Read a joint state YAML file into a joint name -> position map
+pub fn load_joint_state(path: &Path) -> Result<HashMap<String, f32>, JointStateError> {
+    let yaml = std::fs::read_to_string(path).map_err(|e| JointStateError::Io(e.to_string()))?;
+    parse_joint_state(&yaml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name_position_map() {
+        let positions = parse_joint_state("shoulder: 0.5\nelbow: -1.25\n").unwrap();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions["shoulder"], 0.5);
+        assert_eq!(positions["elbow"], -1.25);
+    }
+
+    #[test]
+    fn test_parse_joint_state_message() {
+        let yaml = "\
+header:
+  seq: 12
+  stamp:
+    secs: 0
+    nsecs: 0
+  frame_id: ''
+name: [shoulder, elbow]
+position: [0.5, -1.25]
+velocity: []
+effort: []
+";
+        let positions = parse_joint_state(yaml).unwrap();
+        assert_eq!(positions["shoulder"], 0.5);
+        assert_eq!(positions["elbow"], -1.25);
+    }
+
+    #[test]
+    fn test_parse_joint_state_length_mismatch() {
+        let result = parse_joint_state("name: [a, b]\nposition: [0.1]\n");
+        assert!(matches!(
+            result,
+            Err(JointStateError::LengthMismatch {
+                names: 2,
+                positions: 1
+            })
+        ));
+    }
+}
@@ -2,8 +2,11 @@
 //!
 //! Imports URDF files and converts them to the internal Project format.
 
+mod extensions;
 mod geometry;
+mod joint_state;
 mod options;
+mod transmission;
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -13,15 +16,21 @@ use uuid::Uuid;
 
 use crate::assembly::{Assembly, InertialProperties, Joint, Link};
 use crate::inertia::InertiaMatrix;
+use crate::parallel::par_map;
 use crate::part::Part;
-use crate::project::{MaterialDef, Project};
-use crate::types::{JointDynamics, JointLimits, JointMimic, JointType, Pose};
+use crate::project::{ExtensionScope, MaterialDef, Project, UrdfExtension};
+use crate::types::{
+    JointDynamics, JointLimits, JointMimic, JointSafetyController, JointType, Pose,
+};
 
+pub use extensions::parse_extensions;
 pub use geometry::{
     GeometryContext, create_part_from_mesh, process_collision_geometry, process_geometry,
     process_visual_geometry, resolve_mesh_path,
 };
+pub use joint_state::{JointStateError, load_joint_state, parse_joint_state};
 pub use options::ImportOptions;
+pub use transmission::parse_transmissions;
 
 /// Result of processing URDF links: (parts, links, link_name_to_id mapping)
 type ProcessedLinks = (
@@ -91,12 +100,55 @@ pub fn import_urdf(urdf_path: &Path, options: &ImportOptions) -> Result<Project,
 
     process_urdf_joints(&robot.joints, &link_name_to_id, &mut assembly)?;
 
+    // urdf-rs drops transmissions and extension elements, so read them from the raw text
+    let urdf_text =
+        std::fs::read_to_string(urdf_path).map_err(|e| ImportError::Io(e.to_string()))?;
+    apply_transmissions(&urdf_text, &mut assembly)?;
+    let extensions = collect_extensions(&urdf_text, &assembly)?;
+
     assembly.rebuild_indices();
     assembly.update_world_transforms();
 
     apply_world_transforms_to_parts(&assembly, &mut parts);
 
-    Ok(Project::with_parts(robot.name, parts, assembly, materials))
+    let mut project = Project::with_parts(robot.name, parts, assembly, materials);
+    project.extensions = extensions;
+    Ok(project)
+}
+
+/// Attach `<transmission>` elements from the URDF file to their joints
+fn apply_transmissions(urdf: &str, assembly: &mut Assembly) -> Result<(), ImportError> {
+    for (joint_name, transmission) in parse_transmissions(urdf)? {
+        if let Some(joint_id) = assembly.find_joint_id_by_name(&joint_name)
+            && let Some(joint) = assembly.joints.get_mut(&joint_id)
+        {
+            joint.transmission = Some(transmission);
+        }
+    }
+    Ok(())
+}
+
+/// Capture unmodeled elements, tying `reference` attributes to links or joints
+fn collect_extensions(urdf: &str, assembly: &Assembly) -> Result<Vec<UrdfExtension>, ImportError> {
+    let extensions = parse_extensions(urdf)?
+        .into_iter()
+        .map(|(reference, xml)| {
+            let scope = match reference {
+                Some(name) => {
+                    if let Some(id) = assembly.find_link_id_by_name(&name) {
+                        ExtensionScope::Link { id, name }
+                    } else if let Some(id) = assembly.find_joint_id_by_name(&name) {
+                        ExtensionScope::Joint { id, name }
+                    } else {
+                        ExtensionScope::Robot
+                    }
+                }
+                None => ExtensionScope::Robot,
+            };
+            UrdfExtension { scope, xml }
+        })
+        .collect();
+    Ok(extensions)
 }
 
 /// Resolve the base directory for mesh path resolution
@@ -152,11 +204,17 @@ fn process_urdf_links(
     let mut links: HashMap<Uuid, Link> = HashMap::new();
     let mut link_name_to_id: HashMap<String, Uuid> = HashMap::new();
 
-    for urdf_link in urdf_links {
+    // Mesh loading dominates import time, so load every link's meshes up
+    // front; results come back in link order
+    let geometries = par_map(urdf_links, |urdf_link| {
+        process_visual_geometry(&urdf_link.visual, &urdf_link.name, ctx)
+    });
+
+    for (urdf_link, geometry) in urdf_links.iter().zip(geometries) {
         let link_id = Uuid::new_v4();
         link_name_to_id.insert(urdf_link.name.clone(), link_id);
 
-        let (part_opt, visuals) = process_visual_geometry(&urdf_link.visual, &urdf_link.name, ctx)?;
+        let (part_opt, visuals) = geometry?;
 
         let inertial_props = InertialProperties {
             origin: Pose::from(&urdf_link.inertial.origin),
@@ -210,7 +268,7 @@ fn process_urdf_joints(
             .get(&urdf_joint.child.link)
             .ok_or_else(|| ImportError::LinkNotFound(urdf_joint.child.link.clone()))?;
 
-        let joint = Joint {
+        let mut joint = Joint {
             id: Uuid::new_v4(),
             name: urdf_joint.name.clone(),
             joint_type: JointType::from(&urdf_joint.joint_type),
@@ -233,7 +291,16 @@ fn process_urdf_joints(
                 friction: d.friction as f32,
             }),
             mimic: None,
+            safety_controller: urdf_joint
+                .safety_controller
+                .as_ref()
+                .map(JointSafetyController::from),
+            transmission: None,
+            default_value: 0.0,
+            parent_joint_point: None,
+            child_joint_point: None,
         };
+        joint.seed_default_value();
 
         let joint_id = joint.id;
         joint_name_to_id.insert(urdf_joint.name.clone(), joint_id);
@@ -296,6 +363,22 @@ mod tests {
         assert_eq!(pose.rpy, [0.1, 0.2, 0.3]);
     }
 
+    #[test]
+    fn test_safety_controller_from() {
+        let urdf_sc = urdf_rs::SafetyController {
+            soft_lower_limit: -1.5,
+            soft_upper_limit: 1.5,
+            k_position: 100.0,
+            k_velocity: 15.0,
+        };
+
+        let sc = JointSafetyController::from(&urdf_sc);
+        assert_eq!(sc.soft_lower_limit, -1.5);
+        assert_eq!(sc.soft_upper_limit, 1.5);
+        assert_eq!(sc.k_position, 100.0);
+        assert_eq!(sc.k_velocity, 15.0);
+    }
+
     #[test]
     fn test_joint_type_from() {
         assert!(matches!(
@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use crate::mesh::StlUnit;
+use crate::primitive::PrimitiveQuality;
 
 /// Import options for URDF loading
 #[derive(Debug, Clone)]
@@ -17,6 +18,8 @@ pub struct ImportOptions {
     /// Package path mappings for resolving package:// URIs
     /// Maps package name to its root directory
     pub package_paths: HashMap<String, PathBuf>,
+    /// Tessellation level for cylinder, sphere and capsule geometry
+    pub primitive_quality: PrimitiveQuality,
 }
 
 impl Default for ImportOptions {
@@ -26,6 +29,7 @@ impl Default for ImportOptions {
             stl_unit: StlUnit::Meters,
             default_color: [0.7, 0.7, 0.7, 1.0],
             package_paths: HashMap::new(),
+            primitive_quality: PrimitiveQuality::default(),
         }
     }
 }
@@ -0,0 +1,207 @@
+//! ros_control `<transmission>` parsing
+//!
+//! urdf-rs does not model transmissions, so they are read from the raw URDF
+//! text. Both the current `<type>` element and the legacy `type` attribute
+//! are accepted. Transmissions driving several joints are split into one
+//! transmission per joint, each paired with the first actuator.
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::types::JointTransmission;
+
+use super::ImportError;
+
+/// Section of a `<transmission>` element the reader is currently in
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Transmission,
+    Joint,
+    Actuator,
+}
+
+/// A transmission as written in the file, before it is split per joint
+#[derive(Default)]
+struct RawTransmission {
+    name: String,
+    transmission_type: String,
+    joints: Vec<(String, Option<String>)>,
+    actuator_name: Option<String>,
+    actuator_interface: Option<String>,
+    mechanical_reduction: Option<f32>,
+}
+
+/// Parse all `<transmission>` elements into (joint name, transmission) pairs
+pub fn parse_transmissions(urdf: &str) -> Result<Vec<(String, JointTransmission)>, ImportError> {
+    let mut reader = Reader::from_str(urdf);
+    let xml_err = |e: quick_xml::Error| ImportError::UrdfParse(e.to_string());
+
+    let mut result = Vec::new();
+    let mut current: Option<RawTransmission> = None;
+    let mut section = Section::Transmission;
+
+    loop {
+        match reader.read_event().map_err(xml_err)? {
+            Event::Start(e) => match (e.local_name().as_ref(), current.as_mut()) {
+                (b"transmission", None) => {
+                    current = Some(RawTransmission {
+                        name: attribute(&e, b"name").unwrap_or_default(),
+                        transmission_type: attribute(&e, b"type").unwrap_or_default(),
+                        ..Default::default()
+                    });
+                    section = Section::Transmission;
+                }
+                (b"joint", Some(t)) => {
+                    t.joints
+                        .push((attribute(&e, b"name").unwrap_or_default(), None));
+                    section = Section::Joint;
+                }
+                (b"actuator", Some(t)) => {
+                    if t.actuator_name.is_none() {
+                        t.actuator_name = attribute(&e, b"name");
+                    }
+                    section = Section::Actuator;
+                }
+                (b"type", Some(t)) => {
+                    t.transmission_type = read_text(&mut reader, &e)?;
+                }
+                (b"hardwareInterface", Some(t)) => {
+                    let interface = read_text(&mut reader, &e)?;
+                    match section {
+                        Section::Joint => {
+                            if let Some((_, slot)) = t.joints.last_mut() {
+                                slot.get_or_insert(interface);
+                            }
+                        }
+                        _ => {
+                            t.actuator_interface.get_or_insert(interface);
+                        }
+                    }
+                }
+                (b"mechanicalReduction", Some(t)) => {
+                    let text = read_text(&mut reader, &e)?;
+                    t.mechanical_reduction = text.trim().parse().ok();
+                }
+                _ => {}
+            },
+            Event::Empty(e) => {
+                if let (b"joint", Some(t)) = (e.local_name().as_ref(), current.as_mut()) {
+                    t.joints
+                        .push((attribute(&e, b"name").unwrap_or_default(), None));
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"transmission" => {
+                    if let Some(t) = current.take() {
+                        result.extend(split_per_joint(t));
+                    }
+                }
+                b"joint" | b"actuator" => section = Section::Transmission,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(result)
+}
+
+/// Turn a parsed transmission into one entry per driven joint
+fn split_per_joint(t: RawTransmission) -> Vec<(String, JointTransmission)> {
+    let default_interface = JointTransmission::HARDWARE_INTERFACES[0].to_string();
+    t.joints
+        .into_iter()
+        .filter(|(joint, _)| !joint.is_empty())
+        .map(|(joint, interface)| {
+            let transmission = JointTransmission {
+                name: t.name.clone(),
+                transmission_type: t.transmission_type.clone(),
+                hardware_interface: interface
+                    .or_else(|| t.actuator_interface.clone())
+                    .unwrap_or_else(|| default_interface.clone()),
+                actuator_name: t
+                    .actuator_name
+                    .clone()
+                    .unwrap_or_else(|| format!("{}_motor", joint)),
+                mechanical_reduction: t.mechanical_reduction.unwrap_or(1.0),
+            };
+            (joint, transmission)
+        })
+        .collect()
+}
+
+/// Read an attribute value as a string
+pub(super) fn attribute(e: &BytesStart, name: &[u8]) -> Option<String> {
+    e.try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.into_owned())
+}
+
+/// Read the trimmed text content of a simple element
+fn read_text(reader: &mut Reader<&[u8]>, e: &BytesStart) -> Result<String, ImportError> {
+    reader
+        .read_text(e.name())
+        .map(|text| text.trim().to_string())
+        .map_err(|e| ImportError::UrdfParse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_transmission() {
+        let urdf = r#"<robot name="arm">
+  <joint name="shoulder" type="revolute"/>
+  <transmission name="shoulder_trans">
+    <type>transmission_interface/SimpleTransmission</type>
+    <joint name="shoulder">
+      <hardwareInterface>hardware_interface/PositionJointInterface</hardwareInterface>
+    </joint>
+    <actuator name="shoulder_motor">
+      <mechanicalReduction>50</mechanicalReduction>
+    </actuator>
+  </transmission>
+</robot>"#;
+
+        let transmissions = parse_transmissions(urdf).unwrap();
+        assert_eq!(transmissions.len(), 1);
+
+        let (joint, t) = &transmissions[0];
+        assert_eq!(joint, "shoulder");
+        assert_eq!(t.name, "shoulder_trans");
+        assert_eq!(t.transmission_type, JointTransmission::SIMPLE_TRANSMISSION);
+        assert_eq!(
+            t.hardware_interface,
+            "hardware_interface/PositionJointInterface"
+        );
+        assert_eq!(t.actuator_name, "shoulder_motor");
+        assert_eq!(t.mechanical_reduction, 50.0);
+    }
+
+    #[test]
+    fn test_parse_legacy_transmission() {
+        let urdf = r#"<robot name="arm">
+  <transmission name="t" type="pr2_mechanism_model/SimpleTransmission">
+    <joint name="elbow"/>
+    <actuator name="elbow_motor">
+      <hardwareInterface>EffortJointInterface</hardwareInterface>
+    </actuator>
+    <mechanicalReduction>2</mechanicalReduction>
+  </transmission>
+</robot>"#;
+
+        let transmissions = parse_transmissions(urdf).unwrap();
+        let (joint, t) = &transmissions[0];
+        assert_eq!(joint, "elbow");
+        assert_eq!(
+            t.transmission_type,
+            "pr2_mechanism_model/SimpleTransmission"
+        );
+        assert_eq!(t.hardware_interface, "EffortJointInterface");
+        assert_eq!(t.mechanical_reduction, 2.0);
+    }
+}
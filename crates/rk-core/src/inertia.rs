@@ -1,5 +1,8 @@
 //! Inertia tensor calculations
 
+use std::collections::HashMap;
+
+use glam::{DMat3, DVec3};
 use serde::{Deserialize, Serialize};
 
 /// Inertia tensor (symmetric 3x3 matrix)
@@ -149,6 +152,98 @@ fn signed_tetrahedron_volume(v0: [f32; 3], v1: [f32; 3], v2: [f32; 3]) -> f32 {
     (v0[0] * cross[0] + v0[1] * cross[1] + v0[2] * cross[2]) / 6.0
 }
 
+/// Whether a triangle mesh is closed and consistently wound
+///
+/// Vertices at the same position are welded first, so meshes split per
+/// face still count as closed. Every edge must be used exactly once in each
+/// direction.
+pub fn is_watertight(vertices: &[[f32; 3]], indices: &[u32]) -> bool {
+    if indices.is_empty() || !indices.len().is_multiple_of(3) {
+        return false;
+    }
+    let mut welded: HashMap<[u32; 3], u32> = HashMap::new();
+    let mut directed: HashMap<(u32, u32), u32> = HashMap::new();
+    for triangle in indices.chunks_exact(3) {
+        let mut ids = [0; 3];
+        for (id, &index) in ids.iter_mut().zip(triangle) {
+            let Some(position) = vertices.get(index as usize) else {
+                return false;
+            };
+            let next = welded.len() as u32;
+            *id = *welded.entry(position.map(f32::to_bits)).or_insert(next);
+        }
+        for (start, end) in [(0, 1), (1, 2), (2, 0)] {
+            *directed.entry((ids[start], ids[end])).or_default() += 1;
+        }
+    }
+    directed
+        .iter()
+        .all(|(&(start, end), &count)| count == 1 && directed.get(&(end, start)) == Some(&1))
+}
+
+/// Mass properties of a closed mesh of uniform density
+#[derive(Debug, Clone, Copy)]
+pub struct MassProperties {
+    /// Enclosed volume
+    pub volume: f32,
+    /// Center of mass in mesh coordinates
+    pub center_of_mass: [f32; 3],
+    /// Inertia tensor about the center of mass
+    pub inertia: InertiaMatrix,
+}
+
+/// Volume, center of mass and inertia of a closed mesh with the given mass
+///
+/// Integrates over signed tetrahedra from the origin to each triangle, so
+/// the result is only meaningful for watertight meshes (see
+/// [`is_watertight`]); inverted winding is handled. Returns `None` for
+/// meshes without volume.
+pub fn calculate_mass_properties(
+    vertices: &[[f32; 3]],
+    indices: &[u32],
+    mass: f32,
+) -> Option<MassProperties> {
+    let mut volume = 0.0;
+    let mut first_moment = DVec3::ZERO;
+    // Second moments ∫ r rᵀ dV
+    let mut covariance = DMat3::ZERO;
+
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| vertices[triangle[i] as usize].map(f64::from).into());
+        let det = DVec3::dot(a, b.cross(c));
+        let sum: DVec3 = a + b + c;
+        volume += det / 6.0;
+        first_moment += sum * det / 24.0;
+        let outer = |v: DVec3| DMat3::from_cols(v * v.x, v * v.y, v * v.z);
+        covariance += (outer(sum) + outer(a) + outer(b) + outer(c)) * (det / 120.0);
+    }
+    if volume.abs() < f64::EPSILON {
+        return None;
+    }
+
+    let center = first_moment / volume;
+    let density = f64::from(mass) / volume;
+    // Move to the center of mass, then I = tr(C)·E - C
+    let central = (covariance
+        - DMat3::from_cols(center * center.x, center * center.y, center * center.z) * volume)
+        * density;
+    let trace = central.x_axis.x + central.y_axis.y + central.z_axis.z;
+    let tensor = DMat3::from_diagonal(DVec3::splat(trace)) - central;
+
+    Some(MassProperties {
+        volume: volume.abs() as f32,
+        center_of_mass: center.as_vec3().to_array(),
+        inertia: InertiaMatrix {
+            ixx: tensor.x_axis.x as f32,
+            ixy: tensor.y_axis.x as f32,
+            ixz: tensor.z_axis.x as f32,
+            iyy: tensor.y_axis.y as f32,
+            iyz: tensor.z_axis.y as f32,
+            izz: tensor.z_axis.z as f32,
+        },
+    })
+}
+
 /// Estimate mass from volume and density
 pub fn mass_from_volume(volume: f32, density: f32) -> f32 {
     volume * density
@@ -6,22 +6,26 @@
 //! - Project: Serializable project file
 
 pub mod assembly;
+pub mod bvh;
 pub mod constants;
 pub mod export;
 pub mod import;
 pub mod inertia;
 pub mod mesh;
+pub mod parallel;
 pub mod part;
 pub mod primitive;
 pub mod project;
 pub mod types;
 
 pub use assembly::*;
+pub use bvh::*;
 pub use constants::*;
 pub use export::*;
 pub use import::*;
 pub use inertia::*;
 pub use mesh::*;
+pub use parallel::{par_for_each_mut, par_map};
 pub use part::*;
 pub use primitive::*;
 pub use project::*;
@@ -2,15 +2,22 @@
 
 use std::path::Path;
 
-use dae_parser::{Document, Geometry, Primitive, Semantic, Source};
+use dae_parser::{
+    ColorParam, Document, Effect, Geometry, Instance, LocalMap, Material, Node, Primitive, Profile,
+    Semantic, Shader, Source, Transform,
+};
+use glam::{Mat3, Mat4, Vec3};
 
 use crate::part::Part;
 
-use super::MeshError;
 use super::normals::{calculate_face_normals, calculate_triangle_normal};
 use super::stl::StlUnit;
+use super::{MeshError, RawMeshData};
 
 /// Load a DAE (COLLADA) file and create a Part
+///
+/// All geometry in the visual scene is merged into one part, with each
+/// node's transform applied. Material colors become vertex colors.
 pub fn load_dae(path: impl AsRef<Path>) -> Result<Part, MeshError> {
     load_dae_with_unit(path, StlUnit::Meters)
 }
@@ -18,15 +25,85 @@ pub fn load_dae(path: impl AsRef<Path>) -> Result<Part, MeshError> {
 /// Load a DAE (COLLADA) file with specified unit
 pub fn load_dae_with_unit(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part, MeshError> {
     let path = path.as_ref();
+    let meshes = load_dae_meshes(path, unit)?;
+    let (name, mesh_path) = super::extract_name_and_path(path);
+    let mut part = Part::new(name);
 
-    let document = Document::from_file(path)
-        .map_err(|e| MeshError::Parse(format!("DAE parse error: {:?}", e)))?;
+    let colored = meshes.iter().any(|m| m.color.is_some());
+    let mut merged = RawMeshData {
+        vertices: Vec::new(),
+        normals: Vec::new(),
+        indices: Vec::new(),
+        colors: Vec::new(),
+    };
+    for SceneMesh { mesh, color, .. } in meshes {
+        let offset = merged.vertices.len() as u32;
+        if colored {
+            let color = color.unwrap_or(part.color);
+            merged.colors.extend(mesh.vertices.iter().map(|_| color));
+        }
+        merged.vertices.extend(mesh.vertices);
+        merged.normals.extend(mesh.normals);
+        merged
+            .indices
+            .extend(mesh.indices.iter().map(|i| i + offset));
+    }
 
-    let scale = unit.scale_factor();
+    super::finalize_part(&mut part, mesh_path, merged);
+    Ok(part)
+}
 
-    let mut all_vertices: Vec<[f32; 3]> = Vec::new();
-    let mut all_normals: Vec<[f32; 3]> = Vec::new();
-    let mut all_indices: Vec<u32> = Vec::new();
+/// Load a DAE (COLLADA) file as one part per geometry instance in the scene
+///
+/// Node transforms are baked into the vertices, so the parts keep their
+/// relative placement with an identity origin transform. Each part takes the
+/// diffuse color of its geometry's material, if it has one.
+pub fn load_dae_scene(path: impl AsRef<Path>) -> Result<Vec<Part>, MeshError> {
+    load_dae_scene_with_unit(path, StlUnit::Meters)
+}
+
+/// Load a DAE (COLLADA) scene with specified unit
+pub fn load_dae_scene_with_unit(
+    path: impl AsRef<Path>,
+    unit: StlUnit,
+) -> Result<Vec<Part>, MeshError> {
+    let path = path.as_ref();
+    let (file_name, mesh_path) = super::extract_name_and_path(path);
+
+    let meshes = load_dae_meshes(path, unit)?;
+    let count = meshes.len();
+    Ok(meshes
+        .into_iter()
+        .map(|SceneMesh { name, mesh, color }| {
+            let name = match name {
+                Some(name) if count > 1 => format!("{}_{}", file_name, name),
+                _ => file_name.clone(),
+            };
+            let mut part = Part::new(name);
+            if let Some(color) = color {
+                part.color = color;
+            }
+            super::finalize_part(&mut part, mesh_path.clone(), mesh);
+            part
+        })
+        .collect())
+}
+
+/// Geometry instance of a DAE scene in world coordinates
+struct SceneMesh {
+    /// Node name, or the geometry ID
+    name: Option<String>,
+    mesh: RawMeshData,
+    /// Diffuse color of the bound material
+    color: Option<[f32; 4]>,
+}
+
+/// Load every geometry instance of a DAE file in scene (world) coordinates
+///
+/// Falls back to the raw geometry library when the file has no visual scene.
+fn load_dae_meshes(path: &Path, unit: StlUnit) -> Result<Vec<SceneMesh>, MeshError> {
+    let document = Document::from_file(path)
+        .map_err(|e| MeshError::Parse(format!("DAE parse error: {:?}", e)))?;
 
     // Get the local map to access geometry elements
     let geom_map = document
@@ -37,118 +114,222 @@ pub fn load_dae_with_unit(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part,
         .local_map::<Source>()
         .map_err(|_| MeshError::EmptyMesh)?;
 
-    // Process each geometry
-    for geometry in geom_map.0.values() {
-        let mesh = match &geometry.element {
-            dae_parser::GeometryElement::Mesh(m) => m,
-            _ => continue,
-        };
+    // Materials only add color, so files with broken ones still load
+    let material_map = document.local_map::<Material>().unwrap_or_default();
+    let effect_map = document.local_map::<Effect>().unwrap_or_default();
 
-        // Find position source from vertices
-        let vertices = mesh.vertices.as_ref().ok_or(MeshError::EmptyMesh)?;
+    // Positions are scaled after the node transforms, so translations are
+    // converted to the target unit as well
+    let unit_scale = Mat4::from_scale(Vec3::splat(unit.scale_factor()));
 
-        let position_input = vertices
-            .inputs
-            .iter()
-            .find(|i| i.semantic == Semantic::Position)
-            .ok_or(MeshError::EmptyMesh)?;
+    let mut meshes = Vec::new();
+    if let Some(scene) = document.get_visual_scene() {
+        let mut instances = Vec::new();
+        for node in &scene.nodes {
+            collect_geometry_instances(node, Mat4::IDENTITY, &mut instances);
+        }
+        for (node_name, transform, instance) in instances {
+            let Some(geometry) = geom_map.get(&instance.url) else {
+                continue;
+            };
+            if let Some(mesh) = process_geometry(geometry, &source_map)? {
+                meshes.push(SceneMesh {
+                    name: node_name.or_else(|| geometry.id.clone()),
+                    mesh: transform_mesh(mesh, unit_scale * transform),
+                    color: material_color(instance, &material_map, &effect_map),
+                });
+            }
+        }
+    }
 
-        // Get position source array
-        let position_source = source_map
-            .get(position_input.source_as_source())
-            .ok_or_else(|| MeshError::Parse("Position source not found".to_string()))?;
+    // No scene (or an empty one): take the geometry library as-is
+    if meshes.is_empty() {
+        for geometry in geom_map.0.values() {
+            if let Some(mesh) = process_geometry(geometry, &source_map)? {
+                meshes.push(SceneMesh {
+                    name: geometry.id.clone(),
+                    mesh: transform_mesh(mesh, unit_scale),
+                    color: None,
+                });
+            }
+        }
+    }
 
-        let positions: Vec<[f32; 3]> = extract_vec3_from_source(position_source, scale)?;
+    if meshes.is_empty() {
+        return Err(MeshError::EmptyMesh);
+    }
+    Ok(meshes)
+}
 
-        // Find normal source if exists
-        let normal_input = vertices
-            .inputs
-            .iter()
-            .find(|i| i.semantic == Semantic::Normal);
-        let normals_from_vertices: Option<Vec<[f32; 3]>> = if let Some(ni) = normal_input {
-            source_map
-                .get(ni.source_as_source())
-                .and_then(|s| extract_vec3_from_source(s, 1.0).ok())
-        } else {
-            None
-        };
-
-        // Process primitives (triangles, polylist, etc.)
-        for primitive in &mesh.elements {
-            let vertex_offset = all_vertices.len() as u32;
-
-            match primitive {
-                Primitive::Triangles(tris) => {
-                    // Get the stride and vertex offset from inputs
-                    let stride = tris.inputs.stride;
-                    let vtx_off = tris
-                        .inputs
-                        .iter()
-                        .find(|i| i.semantic == Semantic::Vertex)
-                        .map(|i| i.offset as usize)
-                        .unwrap_or(0);
+/// Walk the node hierarchy, collecting geometry instances with their world transforms
+fn collect_geometry_instances<'a>(
+    node: &'a Node,
+    parent_transform: Mat4,
+    out: &mut Vec<(Option<String>, Mat4, &'a Instance<Geometry>)>,
+) {
+    let transform = node
+        .transforms
+        .iter()
+        .fold(parent_transform, |acc, t| acc * transform_matrix(t));
+
+    let name = node.name.clone().or_else(|| node.id.clone());
+    for instance in &node.instance_geometry {
+        out.push((name.clone(), transform, instance));
+    }
+    for child in &node.children {
+        collect_geometry_instances(child, transform, out);
+    }
+}
 
-                    // Get normal offset if exists
-                    let normal_off = tris
-                        .inputs
-                        .iter()
-                        .find(|i| i.semantic == Semantic::Normal)
-                        .map(|i| i.offset as usize);
-
-                    let normal_source = if normal_off.is_some() {
-                        tris.inputs
-                            .iter()
-                            .find(|i| i.semantic == Semantic::Normal)
-                            .and_then(|i| source_map.get(i.source_as_source()))
-                            .and_then(|s| extract_vec3_from_source(s, 1.0).ok())
-                    } else {
-                        None
-                    };
-
-                    if let Some(ref prim_data) = tris.data.prim {
-                        process_triangles(
-                            prim_data,
-                            stride,
-                            vtx_off,
-                            normal_off,
-                            &positions,
-                            normal_source.as_ref().or(normals_from_vertices.as_ref()),
-                            &mut all_vertices,
-                            &mut all_normals,
-                            &mut all_indices,
-                            vertex_offset,
-                        );
-                    }
-                }
-                Primitive::PolyList(polylist) => {
-                    let stride = polylist.inputs.stride;
-                    let vtx_off = polylist
-                        .inputs
-                        .iter()
-                        .find(|i| i.semantic == Semantic::Vertex)
-                        .map(|i| i.offset as usize)
-                        .unwrap_or(0);
+/// Diffuse color of the first material bound to a geometry instance
+///
+/// Only literal colors in the common profile are read; textures and
+/// parameter references are ignored.
+fn material_color(
+    instance: &Instance<Geometry>,
+    materials: &LocalMap<'_, Material>,
+    effects: &LocalMap<'_, Effect>,
+) -> Option<[f32; 4]> {
+    let binding = instance
+        .data
+        .bind_material
+        .as_ref()?
+        .instance_material
+        .first()?;
+    let material = materials.get(&binding.target)?;
+    let effect = effects.get(&material.instance_effect.url)?;
+    let common = effect.profile.iter().find_map(|profile| match profile {
+        Profile::Common(common) => Some(common),
+        _ => None,
+    })?;
+    common.technique.data.shaders.iter().find_map(|shader| {
+        let param = match shader {
+            Shader::Blinn(s) => s.diffuse.as_ref(),
+            Shader::Lambert(s) => s.diffuse.as_ref(),
+            Shader::Phong(s) => s.diffuse.as_ref(),
+            Shader::Constant(s) => s.emission.as_ref(),
+        }?;
+        match &**param {
+            ColorParam::Color(rgba) => Some(**rgba),
+            _ => None,
+        }
+    })
+}
 
-                    let normal_off = polylist
-                        .inputs
+/// Convert a COLLADA node transform element to a matrix
+fn transform_matrix(transform: &Transform) -> Mat4 {
+    match transform {
+        // COLLADA matrices are written in row-major order
+        Transform::Matrix(m) => Mat4::from_cols_array(&m.0).transpose(),
+        Transform::Translate(t) => Mat4::from_translation(Vec3::from(*t.0)),
+        Transform::Rotate(r) => {
+            let [x, y, z, angle] = *r.0;
+            Mat4::from_axis_angle(Vec3::new(x, y, z).normalize_or_zero(), angle.to_radians())
+        }
+        Transform::Scale(s) => Mat4::from_scale(Vec3::from(*s.0)),
+        // Camera-style transforms don't appear on mesh nodes in practice
+        Transform::LookAt(_) | Transform::Skew(_) => Mat4::IDENTITY,
+    }
+}
+
+/// Apply a transform to mesh positions and normals
+fn transform_mesh(mut mesh: RawMeshData, transform: Mat4) -> RawMeshData {
+    if transform == Mat4::IDENTITY {
+        return mesh;
+    }
+
+    let normal_matrix = Mat3::from_mat4(transform).inverse().transpose();
+    for v in &mut mesh.vertices {
+        *v = transform.transform_point3(Vec3::from(*v)).to_array();
+    }
+    for n in &mut mesh.normals {
+        *n = (normal_matrix * Vec3::from(*n))
+            .normalize_or_zero()
+            .to_array();
+    }
+    mesh
+}
+
+/// Triangulate a single geometry element (in its local coordinates)
+///
+/// Returns `None` for non-mesh geometry or meshes without triangles.
+fn process_geometry(
+    geometry: &Geometry,
+    source_map: &LocalMap<'_, Source>,
+) -> Result<Option<RawMeshData>, MeshError> {
+    let mut all_vertices: Vec<[f32; 3]> = Vec::new();
+    let mut all_normals: Vec<[f32; 3]> = Vec::new();
+    let mut all_indices: Vec<u32> = Vec::new();
+
+    let mesh = match &geometry.element {
+        dae_parser::GeometryElement::Mesh(m) => m,
+        _ => return Ok(None),
+    };
+
+    // Find position source from vertices
+    let vertices = mesh.vertices.as_ref().ok_or(MeshError::EmptyMesh)?;
+
+    let position_input = vertices
+        .inputs
+        .iter()
+        .find(|i| i.semantic == Semantic::Position)
+        .ok_or(MeshError::EmptyMesh)?;
+
+    // Get position source array
+    let position_source = source_map
+        .get(position_input.source_as_source())
+        .ok_or_else(|| MeshError::Parse("Position source not found".to_string()))?;
+
+    let positions: Vec<[f32; 3]> = extract_vec3_from_source(position_source, 1.0)?;
+
+    // Find normal source if exists
+    let normal_input = vertices
+        .inputs
+        .iter()
+        .find(|i| i.semantic == Semantic::Normal);
+    let normals_from_vertices: Option<Vec<[f32; 3]>> = if let Some(ni) = normal_input {
+        source_map
+            .get(ni.source_as_source())
+            .and_then(|s| extract_vec3_from_source(s, 1.0).ok())
+    } else {
+        None
+    };
+
+    // Process primitives (triangles, polylist, etc.)
+    for primitive in &mesh.elements {
+        let vertex_offset = all_vertices.len() as u32;
+
+        match primitive {
+            Primitive::Triangles(tris) => {
+                // Get the stride and vertex offset from inputs
+                let stride = tris.inputs.stride;
+                let vtx_off = tris
+                    .inputs
+                    .iter()
+                    .find(|i| i.semantic == Semantic::Vertex)
+                    .map(|i| i.offset as usize)
+                    .unwrap_or(0);
+
+                // Get normal offset if exists
+                let normal_off = tris
+                    .inputs
+                    .iter()
+                    .find(|i| i.semantic == Semantic::Normal)
+                    .map(|i| i.offset as usize);
+
+                let normal_source = if normal_off.is_some() {
+                    tris.inputs
                         .iter()
                         .find(|i| i.semantic == Semantic::Normal)
-                        .map(|i| i.offset as usize);
-
-                    let normal_source = if normal_off.is_some() {
-                        polylist
-                            .inputs
-                            .iter()
-                            .find(|i| i.semantic == Semantic::Normal)
-                            .and_then(|i| source_map.get(i.source_as_source()))
-                            .and_then(|s| extract_vec3_from_source(s, 1.0).ok())
-                    } else {
-                        None
-                    };
-
-                    process_polylist(
-                        &polylist.data.prim,
-                        &polylist.data.vcount,
+                        .and_then(|i| source_map.get(i.source_as_source()))
+                        .and_then(|s| extract_vec3_from_source(s, 1.0).ok())
+                } else {
+                    None
+                };
+
+                if let Some(ref prim_data) = tris.data.prim {
+                    process_triangles(
+                        prim_data,
                         stride,
                         vtx_off,
                         normal_off,
@@ -160,15 +341,54 @@ pub fn load_dae_with_unit(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part,
                         vertex_offset,
                     );
                 }
-                _ => {
-                    // Skip other primitive types (lines, etc.)
-                }
+            }
+            Primitive::PolyList(polylist) => {
+                let stride = polylist.inputs.stride;
+                let vtx_off = polylist
+                    .inputs
+                    .iter()
+                    .find(|i| i.semantic == Semantic::Vertex)
+                    .map(|i| i.offset as usize)
+                    .unwrap_or(0);
+
+                let normal_off = polylist
+                    .inputs
+                    .iter()
+                    .find(|i| i.semantic == Semantic::Normal)
+                    .map(|i| i.offset as usize);
+
+                let normal_source = if normal_off.is_some() {
+                    polylist
+                        .inputs
+                        .iter()
+                        .find(|i| i.semantic == Semantic::Normal)
+                        .and_then(|i| source_map.get(i.source_as_source()))
+                        .and_then(|s| extract_vec3_from_source(s, 1.0).ok())
+                } else {
+                    None
+                };
+
+                process_polylist(
+                    &polylist.data.prim,
+                    &polylist.data.vcount,
+                    stride,
+                    vtx_off,
+                    normal_off,
+                    &positions,
+                    normal_source.as_ref().or(normals_from_vertices.as_ref()),
+                    &mut all_vertices,
+                    &mut all_normals,
+                    &mut all_indices,
+                );
+            }
+            _ => {
+                // Skip other primitive types (lines, etc.)
             }
         }
     }
 
     if all_vertices.is_empty() {
-        return Err(MeshError::EmptyMesh);
+        return Ok(None);
     }
 
     // Calculate face normals if not provided
@@ -176,19 +396,12 @@ pub fn load_dae_with_unit(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part,
         all_normals = calculate_face_normals(&all_vertices, &all_indices);
     }
 
-    let (name, mesh_path) = super::extract_name_and_path(path);
-    let mut part = Part::new(name);
-    super::finalize_part(
-        &mut part,
-        mesh_path,
-        super::RawMeshData {
-            vertices: all_vertices,
-            normals: all_normals,
-            indices: all_indices,
-        },
-    );
-
-    Ok(part)
+    Ok(Some(RawMeshData {
+        vertices: all_vertices,
+        normals: all_normals,
+        indices: all_indices,
+        colors: Vec::new(),
+    }))
 }
 
 /// Extract Vec3 data from a COLLADA source
@@ -287,7 +500,6 @@ fn process_polylist(
     all_vertices: &mut Vec<[f32; 3]>,
     all_normals: &mut Vec<[f32; 3]>,
     all_indices: &mut Vec<u32>,
-    vertex_offset: u32,
 ) {
     let mut prim_offset = 0;
 
@@ -305,7 +517,7 @@ fn process_polylist(
 
                     if pos_idx < positions.len() {
                         all_vertices.push(positions[pos_idx]);
-                        all_indices.push(vertex_offset + all_vertices.len() as u32 - 1);
+                        all_indices.push(all_vertices.len() as u32 - 1);
 
                         if let (Some(n_off), Some(norms)) = (normal_off, normals) {
                             let norm_idx = prim_data[idx_base + n_off] as usize;
@@ -335,3 +547,128 @@ fn process_polylist(
         prim_offset += vert_count * stride;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A unit box geometry instanced by two nodes, translated along X
+    const TWO_BOXES: &str = r##"<?xml version="1.0" encoding="utf-8"?>
+<COLLADA xmlns="http://www.collada.org/2005/11/COLLADASchema" version="1.4.1">
+  <asset>
+    <created>2024-01-01T00:00:00</created>
+    <modified>2024-01-01T00:00:00</modified>
+    <unit name="meter" meter="1"/>
+    <up_axis>Z_UP</up_axis>
+  </asset>
+  <library_effects>
+    <effect id="red-effect">
+      <profile_COMMON>
+        <technique sid="common">
+          <lambert>
+            <diffuse><color>1 0 0 1</color></diffuse>
+          </lambert>
+        </technique>
+      </profile_COMMON>
+    </effect>
+  </library_effects>
+  <library_materials>
+    <material id="red" name="red">
+      <instance_effect url="#red-effect"/>
+    </material>
+  </library_materials>
+  <library_geometries>
+    <geometry id="box-mesh" name="box">
+      <mesh>
+        <source id="box-positions">
+          <float_array id="box-positions-array" count="24">0 0 0 1 0 0 1 1 0 0 1 0 0 0 1 1 0 1 1 1 1 0 1 1</float_array>
+          <technique_common>
+            <accessor source="#box-positions-array" count="8" stride="3">
+              <param name="X" type="float"/>
+              <param name="Y" type="float"/>
+              <param name="Z" type="float"/>
+            </accessor>
+          </technique_common>
+        </source>
+        <vertices id="box-vertices">
+          <input semantic="POSITION" source="#box-positions"/>
+        </vertices>
+        <triangles count="12">
+          <input semantic="VERTEX" source="#box-vertices" offset="0"/>
+          <p>0 2 1 0 3 2 4 5 6 4 6 7 0 1 5 0 5 4 1 2 6 1 6 5 2 3 7 2 7 6 3 0 4 3 4 7</p>
+        </triangles>
+      </mesh>
+    </geometry>
+  </library_geometries>
+  <library_visual_scenes>
+    <visual_scene id="scene">
+      <node id="left" name="left">
+        <translate>-3 0 0</translate>
+        <instance_geometry url="#box-mesh">
+          <bind_material>
+            <technique_common>
+              <instance_material symbol="box-material" target="#red"/>
+            </technique_common>
+          </bind_material>
+        </instance_geometry>
+      </node>
+      <node id="right" name="right">
+        <translate>2 0 0</translate>
+        <instance_geometry url="#box-mesh"/>
+      </node>
+    </visual_scene>
+  </library_visual_scenes>
+  <scene>
+    <instance_visual_scene url="#scene"/>
+  </scene>
+</COLLADA>
+"##;
+
+    fn write_fixture() -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("boxes.dae");
+        std::fs::write(&path, TWO_BOXES).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn test_load_dae_scene_applies_node_transforms() {
+        let (_dir, path) = write_fixture();
+        let mut parts = load_dae_scene(&path).unwrap();
+        parts.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].name, "boxes_left");
+        assert_eq!(parts[0].bbox_min, [-3.0, 0.0, 0.0]);
+        assert_eq!(parts[0].bbox_max, [-2.0, 1.0, 1.0]);
+        assert_eq!(parts[0].color, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(parts[1].name, "boxes_right");
+        assert_eq!(parts[1].bbox_min, [2.0, 0.0, 0.0]);
+        assert_eq!(parts[1].bbox_max, [3.0, 1.0, 1.0]);
+        assert_eq!(parts[1].color, Part::new("").color);
+    }
+
+    #[test]
+    fn test_load_dae_merges_scene_with_unit() {
+        let (_dir, path) = write_fixture();
+        let part = load_dae_with_unit(&path, StlUnit::Millimeters).unwrap();
+
+        assert_eq!(part.vertices.len(), 2 * 36);
+        assert_eq!(part.vertex_colors.len(), part.vertices.len());
+        let red = part
+            .vertex_colors
+            .iter()
+            .filter(|c| **c == [1.0, 0.0, 0.0, 1.0])
+            .count();
+        assert_eq!(red, 36);
+        let bounds = [
+            (part.bbox_min, [-0.003, 0.0, 0.0]),
+            (part.bbox_max, [0.003, 0.001, 0.001]),
+        ];
+        for (actual, expected) in bounds {
+            for axis in 0..3 {
+                assert!((actual[axis] - expected[axis]).abs() < 1e-6);
+            }
+        }
+    }
+}
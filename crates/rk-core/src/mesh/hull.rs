@@ -0,0 +1,119 @@
+//! Convex hull of a point cloud
+
+use std::collections::{HashMap, HashSet};
+
+use glam::DVec3;
+
+/// Convex hull of a set of points as (vertices, indices)
+///
+/// Triangles are wound counter-clockwise seen from outside. Returns `None`
+/// if the points are all on one plane, so the hull has no volume.
+pub fn convex_hull(points: &[[f32; 3]]) -> Option<(Vec<[f32; 3]>, Vec<u32>)> {
+    let mut seen = HashSet::new();
+    let points: Vec<DVec3> = points
+        .iter()
+        .filter(|p| seen.insert(p.map(f32::to_bits)))
+        .map(|p| DVec3::from(p.map(f64::from)))
+        .collect();
+    if points.len() < 4 {
+        return None;
+    }
+
+    let (min, max) = points.iter().fold((points[0], points[0]), |(lo, hi), p| {
+        (lo.min(*p), hi.max(*p))
+    });
+    let epsilon = (max - min).max_element() * 1e-9;
+
+    // Initial tetrahedron from the most spread out points
+    let farthest = |score: &dyn Fn(DVec3) -> f64| {
+        (0..points.len())
+            .max_by(|&a, &b| score(points[a]).total_cmp(&score(points[b])))
+            .unwrap()
+    };
+    let i0 = farthest(&|p| -p.x);
+    let i1 = farthest(&|p| p.distance_squared(points[i0]));
+    let line = points[i1] - points[i0];
+    let i2 = farthest(&|p| (p - points[i0]).cross(line).length_squared());
+    let plane = line.cross(points[i2] - points[i0]);
+    let i3 = farthest(&|p| (p - points[i0]).dot(plane).abs());
+    if (points[i3] - points[i0]).dot(plane).abs() <= epsilon * plane.length() {
+        return None;
+    }
+
+    let normal = |face: &[usize; 3]| {
+        let [a, b, c] = face.map(|i| points[i]);
+        (b - a).cross(c - a)
+    };
+    let mut faces: Vec<[usize; 3]> = Vec::new();
+    let inside = (points[i0] + points[i1] + points[i2] + points[i3]) / 4.0;
+    for face in [[i0, i1, i2], [i0, i1, i3], [i0, i2, i3], [i1, i2, i3]] {
+        let outward = normal(&face).dot(points[face[0]] - inside) > 0.0;
+        faces.push(if outward {
+            face
+        } else {
+            [face[0], face[2], face[1]]
+        });
+    }
+
+    let sees = |face: &[usize; 3], p: DVec3| {
+        let n = normal(face);
+        n.dot(p - points[face[0]]) > epsilon * n.length()
+    };
+    for (index, &point) in points.iter().enumerate() {
+        let (visible, kept): (Vec<[usize; 3]>, Vec<[usize; 3]>) =
+            faces.iter().partition(|face| sees(face, point));
+        if visible.is_empty() {
+            continue;
+        }
+        // Edges of the visible region not shared with another visible face
+        let edges: HashSet<(usize, usize)> = visible
+            .iter()
+            .flat_map(|f| [(f[0], f[1]), (f[1], f[2]), (f[2], f[0])])
+            .collect();
+        faces = kept;
+        for &(a, b) in &edges {
+            if !edges.contains(&(b, a)) {
+                faces.push([a, b, index]);
+            }
+        }
+    }
+
+    let mut remap: HashMap<usize, u32> = HashMap::new();
+    let mut vertices = Vec::new();
+    let mut indices = Vec::with_capacity(faces.len() * 3);
+    for index in faces.into_iter().flatten() {
+        let next = remap.len() as u32;
+        let id = *remap.entry(index).or_insert_with(|| {
+            vertices.push(points[index].as_vec3().to_array());
+            next
+        });
+        indices.push(id);
+    }
+    Some((vertices, indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inertia::{calculate_mesh_volume, is_watertight};
+
+    #[test]
+    fn test_convex_hull() {
+        // Cube corners plus points inside and on its faces
+        let mut points = Vec::new();
+        for i in 0..8 {
+            points.push([(i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32]);
+        }
+        points.extend([[0.5, 0.5, 0.5], [0.2, 0.7, 0.4], [0.5, 0.5, 1.0]]);
+
+        let (vertices, indices) = convex_hull(&points).unwrap();
+        assert!(is_watertight(&vertices, &indices));
+        assert!((calculate_mesh_volume(&vertices, &indices) - 1.0).abs() < 1e-5);
+        assert!(vertices.len() <= 9);
+
+        // Coplanar points have no hull
+        assert!(
+            convex_hull(&[[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0]]).is_none()
+        );
+    }
+}
@@ -1,24 +1,36 @@
-//! Mesh file loading (STL, OBJ, DAE formats)
+//! Mesh file loading (STL, OBJ, DAE, PLY formats), convex hulls and
+//! decimation
 
 mod dae;
+mod hull;
 mod normals;
 mod obj;
+mod ply;
+mod simplify;
 mod stl;
 
 use std::path::Path;
 
 use crate::part::Part;
 
-pub use dae::{load_dae, load_dae_with_unit};
+pub use dae::{load_dae, load_dae_scene, load_dae_scene_with_unit, load_dae_with_unit};
+pub use hull::convex_hull;
 pub use normals::{calculate_face_normals, calculate_triangle_normal};
 pub use obj::{load_obj, load_obj_with_unit};
-pub use stl::{StlError, StlUnit, load_stl, load_stl_from_bytes, load_stl_with_unit, save_stl};
+pub use ply::{load_ply, load_ply_with_unit};
+pub use simplify::simplify_mesh;
+pub use stl::{
+    StlError, StlUnit, load_stl, load_stl_from_bytes, load_stl_with_unit, save_stl,
+    save_stl_with_unit,
+};
 
 /// Raw mesh data extracted from a file (before Part creation)
 pub(crate) struct RawMeshData {
     pub vertices: Vec<[f32; 3]>,
     pub normals: Vec<[f32; 3]>,
     pub indices: Vec<u32>,
+    /// Per-vertex colors, either empty or one per vertex
+    pub colors: Vec<[f32; 4]>,
 }
 
 /// Finalize a Part from raw mesh data
@@ -32,9 +44,13 @@ pub(crate) fn finalize_part(part: &mut Part, mesh_path: Option<String>, mesh_dat
     part.vertices = mesh_data.vertices;
     part.normals = mesh_data.normals;
     part.indices = mesh_data.indices;
+    part.vertex_colors = if mesh_data.colors.len() == part.vertices.len() {
+        mesh_data.colors
+    } else {
+        Vec::new()
+    };
     part.calculate_bounding_box();
-    part.inertia =
-        crate::inertia::InertiaMatrix::from_bounding_box(part.mass, part.bbox_min, part.bbox_max);
+    part.recompute_inertia();
 }
 
 /// Extract name and path from a file path for Part creation
@@ -54,6 +70,7 @@ pub enum MeshFormat {
     Stl,
     Obj,
     Dae,
+    Ply,
     Unknown,
 }
 
@@ -69,13 +86,17 @@ impl MeshFormat {
             Some("stl") => MeshFormat::Stl,
             Some("obj") => MeshFormat::Obj,
             Some("dae") => MeshFormat::Dae,
+            Some("ply") => MeshFormat::Ply,
             _ => MeshFormat::Unknown,
         }
     }
 
     /// Check if the format is supported
     pub fn is_supported(&self) -> bool {
-        matches!(self, MeshFormat::Stl | MeshFormat::Obj | MeshFormat::Dae)
+        matches!(
+            self,
+            MeshFormat::Stl | MeshFormat::Obj | MeshFormat::Dae | MeshFormat::Ply
+        )
     }
 
     /// Get format name
@@ -84,6 +105,7 @@ impl MeshFormat {
             MeshFormat::Stl => "STL",
             MeshFormat::Obj => "OBJ",
             MeshFormat::Dae => "DAE (COLLADA)",
+            MeshFormat::Ply => "PLY",
             MeshFormat::Unknown => "Unknown",
         }
     }
@@ -100,6 +122,7 @@ pub fn load_mesh(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part, MeshErro
         }
         MeshFormat::Obj => load_obj_with_unit(path, unit),
         MeshFormat::Dae => load_dae_with_unit(path, unit),
+        MeshFormat::Ply => load_ply_with_unit(path, unit),
         MeshFormat::Unknown => Err(MeshError::UnsupportedFormat(
             path.extension()
                 .and_then(|e| e.to_str())
@@ -17,7 +17,8 @@ pub fn load_obj(path: impl AsRef<Path>) -> Result<Part, MeshError> {
 pub fn load_obj_with_unit(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part, MeshError> {
     let path = path.as_ref();
 
-    let (models, _materials) = tobj::load_obj(
+    // tobj resolves `mtllib` relative to the OBJ file
+    let (models, materials) = tobj::load_obj(
         path,
         &tobj::LoadOptions {
             triangulate: true,
@@ -37,6 +38,7 @@ pub fn load_obj_with_unit(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part,
     let mut all_vertices: Vec<[f32; 3]> = Vec::new();
     let mut all_normals: Vec<[f32; 3]> = Vec::new();
     let mut all_indices: Vec<u32> = Vec::new();
+    let mut all_colors: Vec<Option<[f32; 4]>> = Vec::new();
 
     for model in &models {
         let mesh = &model.mesh;
@@ -60,6 +62,18 @@ pub fn load_obj_with_unit(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part,
             }
         }
 
+        // Extract vertex colors (`v x y z r g b`), if this model has them
+        let vertex_count = all_vertices.len() - vertex_offset as usize;
+        if mesh.vertex_color.len() == vertex_count * 3 {
+            all_colors.extend(
+                mesh.vertex_color
+                    .chunks(3)
+                    .map(|c| Some([c[0], c[1], c[2], 1.0])),
+            );
+        } else {
+            all_colors.resize(all_vertices.len(), None);
+        }
+
         // Extract indices
         for &idx in &mesh.indices {
             all_indices.push(vertex_offset + idx);
@@ -73,6 +87,24 @@ pub fn load_obj_with_unit(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part,
 
     let (name, mesh_path) = super::extract_name_and_path(path);
     let mut part = Part::new(name);
+
+    // A missing or broken .mtl file is not fatal, the part keeps the default color
+    if let Ok(materials) = materials
+        && let Some(material) = dominant_material(&models, &materials)
+    {
+        apply_material(&mut part, material, path);
+    }
+
+    // Models without vertex colors fall back to the material color
+    let all_colors = if all_colors.iter().any(Option::is_some) {
+        all_colors
+            .into_iter()
+            .map(|c| c.unwrap_or(part.color))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
     super::finalize_part(
         &mut part,
         mesh_path,
@@ -80,8 +112,131 @@ pub fn load_obj_with_unit(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part,
             vertices: all_vertices,
             normals: all_normals,
             indices: all_indices,
+            colors: all_colors,
         },
     );
 
     Ok(part)
 }
+
+/// Pick the material used by the most triangles
+///
+/// Parts have a single color, so multi-material OBJs take their dominant material.
+fn dominant_material<'a>(
+    models: &[tobj::Model],
+    materials: &'a [tobj::Material],
+) -> Option<&'a tobj::Material> {
+    let mut triangle_counts = vec![0usize; materials.len()];
+    for model in models {
+        if let Some(id) = model.mesh.material_id
+            && let Some(count) = triangle_counts.get_mut(id)
+        {
+            *count += model.mesh.indices.len() / 3;
+        }
+    }
+
+    triangle_counts
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .max_by_key(|(_, count)| **count)
+        .map(|(id, _)| &materials[id])
+}
+
+/// Copy diffuse color, name and texture from an OBJ material to a part
+fn apply_material(part: &mut Part, material: &tobj::Material, obj_path: &Path) {
+    if let Some([r, g, b]) = material.diffuse {
+        let alpha = material.dissolve.unwrap_or(1.0);
+        part.color = [r, g, b, alpha];
+    }
+    if !material.name.is_empty() {
+        part.material_name = Some(material.name.clone());
+    }
+    // Texture paths in the .mtl are relative to the OBJ's directory
+    part.texture = material.diffuse_texture.as_ref().map(|texture| {
+        obj_path
+            .parent()
+            .map(|dir| dir.join(texture))
+            .unwrap_or_else(|| texture.into())
+            .to_string_lossy()
+            .to_string()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUBE_OBJ: &str = "\
+mtllib cube.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+usemtl red
+f 1 2 3
+f 1 3 4
+usemtl blue
+f 1 3 2
+";
+
+    const CUBE_MTL: &str = "\
+newmtl red
+Kd 0.8 0.1 0.1
+d 0.5
+map_Kd textures/red.png
+
+newmtl blue
+Kd 0.1 0.1 0.8
+";
+
+    #[test]
+    fn test_load_obj_reads_dominant_material() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("cube.obj"), CUBE_OBJ).unwrap();
+        std::fs::write(dir.path().join("cube.mtl"), CUBE_MTL).unwrap();
+
+        let part = load_obj(dir.path().join("cube.obj")).unwrap();
+
+        assert_eq!(part.color, [0.8, 0.1, 0.1, 0.5]);
+        assert_eq!(part.material_name.as_deref(), Some("red"));
+        let texture = part.texture.unwrap();
+        assert!(texture.ends_with("red.png"));
+        assert!(texture.starts_with(dir.path().to_str().unwrap()));
+    }
+
+    #[test]
+    fn test_load_obj_without_mtl_keeps_default_color() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("cube.obj"), CUBE_OBJ).unwrap();
+
+        let part = load_obj(dir.path().join("cube.obj")).unwrap();
+
+        assert_eq!(part.color, Part::new("default").color);
+        assert!(part.material_name.is_none());
+        assert!(part.vertex_colors.is_empty());
+    }
+
+    #[test]
+    fn test_load_obj_vertex_colors() {
+        let dir = tempfile::tempdir().unwrap();
+        let obj = "\
+v 0 0 0 1 0 0
+v 1 0 0 0 1 0
+v 1 1 0 0 0 1
+f 1 2 3
+";
+        std::fs::write(dir.path().join("tri.obj"), obj).unwrap();
+
+        let part = load_obj(dir.path().join("tri.obj")).unwrap();
+
+        assert_eq!(
+            part.vertex_colors,
+            vec![
+                [1.0, 0.0, 0.0, 1.0],
+                [0.0, 1.0, 0.0, 1.0],
+                [0.0, 0.0, 1.0, 1.0]
+            ]
+        );
+    }
+}
@@ -0,0 +1,505 @@
+//! PLY (Stanford polygon) mesh file loading
+//!
+//! Supports `ascii` and `binary_little_endian` files. Reads vertex
+//! positions, faces (polygons are fan-triangulated) and optional per-vertex
+//! colors; any other elements and properties are skipped.
+
+use std::path::Path;
+
+use crate::part::Part;
+
+use super::normals::calculate_face_normals;
+use super::stl::StlUnit;
+use super::{MeshError, RawMeshData};
+
+/// Load a PLY file and create a Part
+pub fn load_ply(path: impl AsRef<Path>) -> Result<Part, MeshError> {
+    load_ply_with_unit(path, StlUnit::Meters)
+}
+
+/// Load a PLY file with specified unit
+pub fn load_ply_with_unit(path: impl AsRef<Path>, unit: StlUnit) -> Result<Part, MeshError> {
+    let path = path.as_ref();
+    let data = std::fs::read(path).map_err(|e| MeshError::Io(e.to_string()))?;
+    let mesh = parse_ply(&data)?;
+
+    let scale = unit.scale_factor();
+    let vertices: Vec<[f32; 3]> = mesh
+        .positions
+        .iter()
+        .map(|p| [p[0] * scale, p[1] * scale, p[2] * scale])
+        .collect();
+    let normals = calculate_face_normals(&vertices, &mesh.indices);
+
+    let (name, mesh_path) = super::extract_name_and_path(path);
+    let mut part = Part::new(name);
+    // The average stands in for the vertex colors in URDF materials and the color picker
+    if let Some(color) = mesh.average_color() {
+        part.color = color;
+    }
+    super::finalize_part(
+        &mut part,
+        mesh_path,
+        RawMeshData {
+            vertices,
+            normals,
+            indices: mesh.indices,
+            colors: mesh.colors,
+        },
+    );
+
+    Ok(part)
+}
+
+/// Geometry read from a PLY file
+struct PlyMesh {
+    positions: Vec<[f32; 3]>,
+    /// Per-vertex RGBA colors in 0..=1 (empty if the file has none)
+    colors: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+}
+
+impl PlyMesh {
+    fn average_color(&self) -> Option<[f32; 4]> {
+        if self.colors.is_empty() {
+            return None;
+        }
+        let sum = self.colors.iter().fold([0.0f32; 4], |mut acc, c| {
+            for i in 0..4 {
+                acc[i] += c[i];
+            }
+            acc
+        });
+        let n = self.colors.len() as f32;
+        Some(sum.map(|c| c / n))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+/// Scalar property types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Result<Self, MeshError> {
+        Ok(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return Err(MeshError::Parse(format!("Unknown PLY type: {}", name))),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+
+    /// Scale factor that maps an integer color channel to 0..=1
+    fn color_range(self) -> f64 {
+        match self {
+            Scalar::U8 | Scalar::I8 => 255.0,
+            Scalar::U16 | Scalar::I16 => 65535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PropertyType {
+    Scalar(Scalar),
+    List { count: Scalar, item: Scalar },
+}
+
+#[derive(Debug, Clone)]
+struct Property {
+    name: String,
+    ty: PropertyType,
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Sequential reader over the element data of a PLY body
+trait ValueReader {
+    fn read(&mut self, ty: Scalar) -> Result<f64, MeshError>;
+}
+
+struct AsciiReader<'a> {
+    tokens: std::str::SplitAsciiWhitespace<'a>,
+}
+
+impl ValueReader for AsciiReader<'_> {
+    fn read(&mut self, _ty: Scalar) -> Result<f64, MeshError> {
+        let token = self
+            .tokens
+            .next()
+            .ok_or_else(|| MeshError::Parse("Unexpected end of PLY data".to_string()))?;
+        token
+            .parse()
+            .map_err(|_| MeshError::Parse(format!("Invalid PLY value: {}", token)))
+    }
+}
+
+struct BinaryReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl ValueReader for BinaryReader<'_> {
+    fn read(&mut self, ty: Scalar) -> Result<f64, MeshError> {
+        let end = self.pos + ty.size();
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or_else(|| MeshError::Parse("Unexpected end of PLY data".to_string()))?;
+        self.pos = end;
+        Ok(match ty {
+            Scalar::I8 => bytes[0] as i8 as f64,
+            Scalar::U8 => bytes[0] as f64,
+            Scalar::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            Scalar::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            Scalar::I32 => i32::from_le_bytes(bytes.try_into().unwrap()) as f64,
+            Scalar::U32 => u32::from_le_bytes(bytes.try_into().unwrap()) as f64,
+            Scalar::F32 => f32::from_le_bytes(bytes.try_into().unwrap()) as f64,
+            Scalar::F64 => f64::from_le_bytes(bytes.try_into().unwrap()),
+        })
+    }
+}
+
+/// Parse PLY file contents
+fn parse_ply(data: &[u8]) -> Result<PlyMesh, MeshError> {
+    let (encoding, elements, body_start) = parse_header(data)?;
+    let body = &data[body_start..];
+
+    match encoding {
+        Encoding::Ascii => {
+            let text = std::str::from_utf8(body)
+                .map_err(|_| MeshError::Parse("PLY body is not valid text".to_string()))?;
+            let mut reader = AsciiReader {
+                tokens: text.split_ascii_whitespace(),
+            };
+            read_elements(&elements, &mut reader)
+        }
+        Encoding::BinaryLittleEndian => {
+            let mut reader = BinaryReader { data: body, pos: 0 };
+            read_elements(&elements, &mut reader)
+        }
+    }
+}
+
+/// Parse the header, returning the encoding, element layout and body offset
+fn parse_header(data: &[u8]) -> Result<(Encoding, Vec<Element>, usize), MeshError> {
+    // The header ends at the first line that is exactly end_header; the
+    // body starts after its newline
+    let mut line_start = 0;
+    let (end, body_start) = loop {
+        let line_end = data[line_start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(data.len(), |i| line_start + i);
+        if data[line_start..line_end].trim_ascii() == b"end_header" {
+            break (line_start, (line_end + 1).min(data.len()));
+        }
+        if line_end == data.len() {
+            return Err(MeshError::Parse("Missing PLY end_header".to_string()));
+        }
+        line_start = line_end + 1;
+    };
+
+    let header = std::str::from_utf8(&data[..end])
+        .map_err(|_| MeshError::Parse("PLY header is not valid text".to_string()))?;
+    let mut lines = header.lines().map(str::trim);
+    if lines.next() != Some("ply") {
+        return Err(MeshError::Parse("Not a PLY file".to_string()));
+    }
+
+    let mut encoding = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for line in lines {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["format", "ascii", _] => encoding = Some(Encoding::Ascii),
+            ["format", "binary_little_endian", _] => encoding = Some(Encoding::BinaryLittleEndian),
+            ["format", other, _] => {
+                return Err(MeshError::Parse(format!(
+                    "Unsupported PLY format: {}",
+                    other
+                )));
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| MeshError::Parse(format!("Invalid element count: {}", count)))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| MeshError::Parse("Property before element".to_string()))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    ty: PropertyType::List {
+                        count: Scalar::parse(count)?,
+                        item: Scalar::parse(item)?,
+                    },
+                });
+            }
+            ["property", ty, name] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| MeshError::Parse("Property before element".to_string()))?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    ty: PropertyType::Scalar(Scalar::parse(ty)?),
+                });
+            }
+            // comment, obj_info and blank lines
+            _ => {}
+        }
+    }
+
+    let encoding = encoding.ok_or_else(|| MeshError::Parse("Missing PLY format".to_string()))?;
+    Ok((encoding, elements, body_start))
+}
+
+/// Read all element data, keeping vertices and faces
+fn read_elements(
+    elements: &[Element],
+    reader: &mut impl ValueReader,
+) -> Result<PlyMesh, MeshError> {
+    let mut mesh = PlyMesh {
+        positions: Vec::new(),
+        colors: Vec::new(),
+        indices: Vec::new(),
+    };
+
+    for element in elements {
+        let find = |name: &str| element.properties.iter().position(|p| p.name == name);
+        let position_props = [find("x"), find("y"), find("z")];
+        let color_props = [
+            find("red").or_else(|| find("r")),
+            find("green").or_else(|| find("g")),
+            find("blue").or_else(|| find("b")),
+            find("alpha").or_else(|| find("a")),
+        ];
+        let face_prop = find("vertex_indices").or_else(|| find("vertex_index"));
+
+        let mut values = vec![0.0f64; element.properties.len()];
+        let mut list = Vec::new();
+        for _ in 0..element.count {
+            list.clear();
+            for (i, property) in element.properties.iter().enumerate() {
+                match property.ty {
+                    PropertyType::Scalar(ty) => values[i] = reader.read(ty)?,
+                    PropertyType::List { count, item } => {
+                        let n = reader.read(count)? as usize;
+                        let keep = Some(i) == face_prop;
+                        for _ in 0..n {
+                            let value = reader.read(item)?;
+                            if keep {
+                                if !(0.0..=u32::MAX as f64).contains(&value) {
+                                    return Err(MeshError::Parse(format!(
+                                        "Invalid PLY vertex index: {}",
+                                        value
+                                    )));
+                                }
+                                list.push(value as u32);
+                            }
+                        }
+                    }
+                }
+            }
+
+            match element.name.as_str() {
+                "vertex" => {
+                    let [Some(x), Some(y), Some(z)] = position_props else {
+                        return Err(MeshError::Parse("PLY vertex has no x/y/z".to_string()));
+                    };
+                    mesh.positions
+                        .push([values[x] as f32, values[y] as f32, values[z] as f32]);
+
+                    if let [Some(r), Some(g), Some(b), a] = color_props {
+                        let channel = |i: usize| match element.properties[i].ty {
+                            PropertyType::Scalar(ty) => (values[i] / ty.color_range()) as f32,
+                            PropertyType::List { .. } => 1.0,
+                        };
+                        mesh.colors.push([
+                            channel(r),
+                            channel(g),
+                            channel(b),
+                            a.map_or(1.0, channel),
+                        ]);
+                    }
+                }
+                "face" if list.len() >= 3 => {
+                    // Fan triangulation
+                    for i in 1..list.len() - 1 {
+                        mesh.indices.extend([list[0], list[i], list[i + 1]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let vertex_count = mesh.positions.len() as u32;
+    if mesh.indices.is_empty() {
+        return Err(MeshError::EmptyMesh);
+    }
+    if mesh.indices.iter().any(|&i| i >= vertex_count) {
+        return Err(MeshError::Parse(
+            "PLY face references a missing vertex".to_string(),
+        ));
+    }
+    Ok(mesh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit cube with quad faces and red vertex colors
+    const ASCII_CUBE: &str = "\
+ply
+format ascii 1.0
+comment unit cube
+element vertex 8
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 6
+property list uchar int vertex_indices
+end_header
+0 0 0 255 0 0
+1 0 0 255 0 0
+1 1 0 255 0 0
+0 1 0 255 0 0
+0 0 1 255 0 0
+1 0 1 255 0 0
+1 1 1 255 0 0
+0 1 1 255 0 0
+4 0 3 2 1
+4 4 5 6 7
+4 0 1 5 4
+4 1 2 6 5
+4 2 3 7 6
+4 3 0 4 7
+";
+
+    fn write_cube(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("cube.ply");
+        std::fs::write(&path, ASCII_CUBE).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_ascii_ply_cube() {
+        let dir = tempfile::tempdir().unwrap();
+        let part = load_ply_with_unit(write_cube(dir.path()), StlUnit::Millimeters).unwrap();
+
+        assert_eq!(part.name, "cube");
+        assert_eq!(part.vertices.len(), 8);
+        assert_eq!(part.indices.len(), 6 * 2 * 3);
+        assert_eq!(part.bbox_min, [0.0, 0.0, 0.0]);
+        assert_eq!(part.bbox_max, [0.001, 0.001, 0.001]);
+        assert_eq!(part.color, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(part.vertex_colors, vec![[1.0, 0.0, 0.0, 1.0]; 8]);
+    }
+
+    #[test]
+    fn test_binary_ply_matches_ascii() {
+        let ascii = parse_ply(ASCII_CUBE.as_bytes()).unwrap();
+
+        // Re-encode the same cube as binary_little_endian
+        let mut data = ASCII_CUBE
+            .split("end_header\n")
+            .next()
+            .unwrap()
+            .replace("format ascii", "format binary_little_endian")
+            .into_bytes();
+        data.extend_from_slice(b"end_header\n");
+        for p in &ascii.positions {
+            for c in p {
+                data.extend_from_slice(&c.to_le_bytes());
+            }
+            data.extend_from_slice(&[255, 0, 0]);
+        }
+        for quad in ASCII_CUBE.lines().filter(|l| l.starts_with("4 ")) {
+            data.push(4);
+            for index in quad.split_whitespace().skip(1) {
+                data.extend_from_slice(&index.parse::<i32>().unwrap().to_le_bytes());
+            }
+        }
+
+        let binary = parse_ply(&data).unwrap();
+        assert_eq!(binary.positions, ascii.positions);
+        assert_eq!(binary.indices, ascii.indices);
+        assert_eq!(binary.average_color(), ascii.average_color());
+    }
+
+    #[test]
+    fn test_ply_header_ends_at_exact_line() {
+        let data = "ply\nformat ascii 1.0\ncomment no end_header here\nelement vertex 3\n\
+                    property float x\nproperty float y\nproperty float z\nelement face 1\n\
+                    property list uchar int vertex_indices\nend_header\n\
+                    0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n";
+        let mesh = parse_ply(data.as_bytes()).unwrap();
+        assert_eq!(mesh.positions.len(), 3);
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_ply_rejects_negative_index() {
+        let data = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\n\
+                    property float y\nproperty float z\nelement face 1\n\
+                    property list uchar int vertex_indices\nend_header\n\
+                    0 0 0\n1 0 0\n0 1 0\n3 0 1 -1\n";
+        assert!(matches!(
+            parse_ply(data.as_bytes()),
+            Err(MeshError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_ply_rejects_out_of_range_face() {
+        let data = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\n\
+                    property float y\nproperty float z\nelement face 1\n\
+                    property list uchar int vertex_indices\nend_header\n0 0 0\n3 0 1 2\n";
+        assert!(matches!(
+            parse_ply(data.as_bytes()),
+            Err(MeshError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+//! Mesh decimation by vertex clustering
+
+use std::collections::{HashMap, HashSet};
+
+use glam::Vec3;
+
+/// Grid cells along the longest side of the mesh for the first attempt
+const START_RESOLUTION: u32 = 256;
+
+/// Reduce a triangle mesh to at most `target_triangles` triangles
+///
+/// Vertices are merged per cell of a uniform grid, which is made coarser
+/// until the target is met; triangles collapsing to an edge or point are
+/// dropped. Returns (vertices, indices), unchanged if the mesh is already
+/// small enough.
+pub fn simplify_mesh(
+    vertices: &[[f32; 3]],
+    indices: &[u32],
+    target_triangles: usize,
+) -> (Vec<[f32; 3]>, Vec<u32>) {
+    if indices.len() / 3 <= target_triangles || vertices.is_empty() {
+        return (vertices.to_vec(), indices.to_vec());
+    }
+    let (min, max) = vertices.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(lo, hi), v| (lo.min(Vec3::from(*v)), hi.max(Vec3::from(*v))),
+    );
+    let extent = (max - min).max_element().max(f32::EPSILON);
+
+    let mut resolution = START_RESOLUTION;
+    loop {
+        let result = cluster(vertices, indices, min, extent / resolution as f32);
+        if result.1.len() / 3 <= target_triangles || resolution <= 1 {
+            return result;
+        }
+        resolution = resolution * 3 / 4;
+    }
+}
+
+/// Merge the vertices in each grid cell into their mean
+fn cluster(
+    vertices: &[[f32; 3]],
+    indices: &[u32],
+    origin: Vec3,
+    cell_size: f32,
+) -> (Vec<[f32; 3]>, Vec<u32>) {
+    let mut cells: HashMap<[i32; 3], u32> = HashMap::new();
+    let mut sums: Vec<(Vec3, u32)> = Vec::new();
+    let cell_of: Vec<u32> = vertices
+        .iter()
+        .map(|v| {
+            let cell = ((Vec3::from(*v) - origin) / cell_size).floor().as_ivec3();
+            let id = *cells.entry(cell.to_array()).or_insert_with(|| {
+                sums.push((Vec3::ZERO, 0));
+                sums.len() as u32 - 1
+            });
+            sums[id as usize].0 += Vec3::from(*v);
+            sums[id as usize].1 += 1;
+            id
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut new_indices = Vec::new();
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| cell_of[triangle[i] as usize]);
+        if a == b || b == c || c == a {
+            continue;
+        }
+        // Same triangle with the same winding, starting from its lowest index
+        let key = if a < b && a < c {
+            [a, b, c]
+        } else if b < c {
+            [b, c, a]
+        } else {
+            [c, a, b]
+        };
+        if seen.insert(key) {
+            new_indices.extend(key);
+        }
+    }
+
+    let new_vertices = sums
+        .into_iter()
+        .map(|(sum, count)| (sum / count as f32).to_array())
+        .collect();
+    (new_vertices, new_indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitive::generate_sphere_mesh_with_segments;
+
+    #[test]
+    fn test_simplify_mesh() {
+        let (vertices, _, indices) = generate_sphere_mesh_with_segments(1.0, 32, 64);
+        let (small_vertices, small_indices) = simplify_mesh(&vertices, &indices, 500);
+        assert!(small_indices.len() / 3 <= 500);
+        assert!(small_indices.len() / 3 > 50);
+        assert!(
+            small_indices
+                .iter()
+                .all(|&i| (i as usize) < small_vertices.len())
+        );
+        // Vertices stay near the surface
+        for v in &small_vertices {
+            assert!((Vec3::from(*v).length() - 1.0).abs() < 0.2);
+        }
+
+        // Small meshes are returned as they are
+        let (same, _) = simplify_mesh(&vertices, &indices, indices.len());
+        assert_eq!(same, vertices);
+    }
+}
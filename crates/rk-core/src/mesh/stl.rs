@@ -1,7 +1,7 @@
 //! STL file loading
 
 use std::collections::HashMap;
-use std::io::BufReader;
+use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
@@ -76,88 +76,167 @@ pub fn load_stl_from_bytes(name: &str, data: &[u8], unit: StlUnit) -> Result<Par
     load_stl_from_reader(&mut cursor, name, None, unit)
 }
 
+/// Size of the binary STL header plus the triangle count
+const BINARY_HEADER_SIZE: u64 = 84;
+/// Size of one binary STL triangle record (normal, 3 vertices, attribute)
+const BINARY_TRIANGLE_SIZE: u64 = 50;
+
 /// Internal function to load STL from any reader
-fn load_stl_from_reader<R: std::io::Read + std::io::Seek>(
+fn load_stl_from_reader<R: Read + Seek>(
     reader: &mut R,
     name: &str,
     stl_path: Option<String>,
     unit: StlUnit,
 ) -> Result<Part, StlError> {
-    let mesh = stl_io::read_stl(reader).map_err(|e| StlError::Parse(e.to_string()))?;
-
-    let scale = unit.scale_factor();
+    let mut indexer = MeshIndexer::new(unit.scale_factor());
+
+    if let Some(triangle_count) = binary_triangle_count(reader)? {
+        read_binary_triangles(reader, triangle_count, &mut indexer)?;
+    } else {
+        reader
+            .seek(SeekFrom::Start(0))
+            .map_err(|e| StlError::Io(e.to_string()))?;
+        let triangles =
+            stl_io::create_stl_reader(reader).map_err(|e| StlError::Parse(e.to_string()))?;
+        for triangle in triangles {
+            let t = triangle.map_err(|e| StlError::Parse(e.to_string()))?;
+            indexer.push(
+                [t.normal[0], t.normal[1], t.normal[2]],
+                [0, 1, 2].map(|i| [t.vertices[i][0], t.vertices[i][1], t.vertices[i][2]]),
+            );
+        }
+    }
 
-    // Convert to indexed mesh with scale
-    let (vertices, normals, indices) = index_mesh_with_scale(&mesh, scale);
+    if indexer.indices.is_empty() {
+        return Err(StlError::Parse("STL contains no triangles".to_string()));
+    }
 
     let mut part = Part::new(name.to_string());
     super::finalize_part(
         &mut part,
         stl_path,
         super::RawMeshData {
-            vertices,
-            normals,
-            indices,
+            vertices: indexer.vertices,
+            normals: indexer.normals,
+            indices: indexer.indices,
+            colors: Vec::new(),
         },
     );
 
     Ok(part)
 }
 
-/// Convert triangle soup to indexed mesh with scale factor
-fn index_mesh_with_scale(
-    mesh: &stl_io::IndexedMesh,
+/// Detect a binary STL by checking its declared triangle count against the stream size
+///
+/// A leading "solid" is not enough to tell ASCII files apart, since many
+/// exporters write it into the binary header too. Returns the triangle count
+/// with the reader positioned at the first triangle, or `None` for ASCII.
+fn binary_triangle_count<R: Read + Seek>(reader: &mut R) -> Result<Option<u32>, StlError> {
+    let io_err = |e: std::io::Error| StlError::Io(e.to_string());
+
+    let len = reader.seek(SeekFrom::End(0)).map_err(io_err)?;
+    if len < BINARY_HEADER_SIZE {
+        return Ok(None);
+    }
+
+    let mut header = [0u8; BINARY_HEADER_SIZE as usize];
+    reader.seek(SeekFrom::Start(0)).map_err(io_err)?;
+    reader.read_exact(&mut header).map_err(io_err)?;
+    let count = u32::from_le_bytes([header[80], header[81], header[82], header[83]]);
+
+    let expected = BINARY_HEADER_SIZE + u64::from(count) * BINARY_TRIANGLE_SIZE;
+    Ok((expected == len).then_some(count))
+}
+
+/// Stream binary STL triangles into the indexer without buffering the whole file
+fn read_binary_triangles<R: Read>(
+    reader: &mut R,
+    count: u32,
+    indexer: &mut MeshIndexer,
+) -> Result<(), StlError> {
+    let mut reader = BufReader::new(reader);
+    let mut record = [0u8; BINARY_TRIANGLE_SIZE as usize];
+    let read_vec3 = |bytes: &[u8]| {
+        [0, 4, 8].map(|o| f32::from_le_bytes([bytes[o], bytes[o + 1], bytes[o + 2], bytes[o + 3]]))
+    };
+
+    for _ in 0..count {
+        reader
+            .read_exact(&mut record)
+            .map_err(|e| StlError::Parse(e.to_string()))?;
+        indexer.push(
+            read_vec3(&record[0..12]),
+            [
+                read_vec3(&record[12..24]),
+                read_vec3(&record[24..36]),
+                read_vec3(&record[36..48]),
+            ],
+        );
+    }
+    Ok(())
+}
+
+/// Builds an indexed mesh from a triangle stream, merging coincident vertices
+struct MeshIndexer {
     scale: f32,
-) -> (Vec<[f32; 3]>, Vec<[f32; 3]>, Vec<u32>) {
-    let mut unique_vertices: Vec<[f32; 3]> = Vec::new();
-    let mut vertex_map: HashMap<[i32; 3], u32> = HashMap::new();
-    let mut indices: Vec<u32> = Vec::new();
-    let mut normals: Vec<[f32; 3]> = Vec::new();
+    vertices: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    indices: Vec<u32>,
+    vertex_map: HashMap<[i32; 3], u32>,
+}
 
-    use crate::constants::STL_VERTEX_PRECISION;
+impl MeshIndexer {
+    fn new(scale: f32) -> Self {
+        Self {
+            scale,
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            indices: Vec::new(),
+            vertex_map: HashMap::new(),
+        }
+    }
+
+    fn push(&mut self, normal: [f32; 3], triangle: [[f32; 3]; 3]) {
+        use crate::constants::STL_VERTEX_PRECISION;
 
-    for face in &mesh.faces {
-        let normal = face.normal;
-        normals.push([normal[0], normal[1], normal[2]]);
+        self.normals.push(normal);
 
-        for &vertex_idx in &face.vertices {
-            let vertex = mesh.vertices[vertex_idx];
+        for vertex in triangle {
             // Apply scale factor
-            let v = [vertex[0] * scale, vertex[1] * scale, vertex[2] * scale];
+            let v = vertex.map(|c| c * self.scale);
 
             // Quantize for comparison (use scaled precision)
-            let key = [
-                (v[0] * STL_VERTEX_PRECISION) as i32,
-                (v[1] * STL_VERTEX_PRECISION) as i32,
-                (v[2] * STL_VERTEX_PRECISION) as i32,
-            ];
-
-            let index = if let Some(&existing_idx) = vertex_map.get(&key) {
-                existing_idx
-            } else {
-                let new_idx = unique_vertices.len() as u32;
-                unique_vertices.push(v);
-                vertex_map.insert(key, new_idx);
-                new_idx
-            };
+            let key = v.map(|c| (c * STL_VERTEX_PRECISION) as i32);
 
-            indices.push(index);
+            let index = *self.vertex_map.entry(key).or_insert_with(|| {
+                self.vertices.push(v);
+                self.vertices.len() as u32 - 1
+            });
+            self.indices.push(index);
         }
     }
-
-    (unique_vertices, normals, indices)
 }
 
 /// Save a Part as an STL file (with origin transform applied)
 pub fn save_stl(part: &Part, path: impl AsRef<Path>) -> Result<(), StlError> {
+    save_stl_with_unit(part, path, StlUnit::Meters)
+}
+
+/// Save a Part as an STL file with vertices written in the given unit
+pub fn save_stl_with_unit(
+    part: &Part,
+    path: impl AsRef<Path>,
+    unit: StlUnit,
+) -> Result<(), StlError> {
     let path = path.as_ref();
+    let scale = unit.scale_factor();
 
-    // Apply origin transform to vertices
+    // Apply origin transform to vertices, then convert from meters
     let transformed_vertices: Vec<[f32; 3]> = part
         .vertices
         .iter()
         .map(|v| {
-            let p = part.origin_transform.transform_point3(glam::Vec3::from(*v));
+            let p = part.origin_transform.transform_point3(glam::Vec3::from(*v)) / scale;
             [p.x, p.y, p.z]
         })
         .collect();
@@ -225,3 +304,80 @@ pub enum StlError {
     #[error("Write error: {0}")]
     Write(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a binary STL from triangles, with the given 80-byte header text
+    fn binary_stl(header: &str, triangles: &[[[f32; 3]; 3]]) -> Vec<u8> {
+        let mut data = vec![b' '; 80];
+        data[..header.len()].copy_from_slice(header.as_bytes());
+        data.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+        for triangle in triangles {
+            for v in [[0.0, 0.0, 1.0]].iter().chain(triangle) {
+                for c in v {
+                    data.extend_from_slice(&c.to_le_bytes());
+                }
+            }
+            data.extend_from_slice(&[0, 0]);
+        }
+        data
+    }
+
+    const TRIANGLES: [[[f32; 3]; 3]; 2] = [
+        [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]],
+        [[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+    ];
+
+    #[test]
+    fn test_binary_stl_with_solid_header() {
+        let data = binary_stl("solid exported by some CAD tool", &TRIANGLES);
+        let part = load_stl_from_bytes("adversarial", &data, StlUnit::Meters).unwrap();
+
+        assert_eq!(part.indices.len(), 6);
+        assert_eq!(part.vertices.len(), 4);
+        assert_eq!(part.bbox_max, [1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_ascii_stl() {
+        let data = b"solid square
+facet normal 0 0 1
+  outer loop
+    vertex 0 0 0
+    vertex 1000 0 0
+    vertex 1000 1000 0
+  endloop
+endfacet
+endsolid square
+";
+        let part = load_stl_from_bytes("square", data, StlUnit::Millimeters).unwrap();
+
+        assert_eq!(part.indices.len(), 3);
+        assert_eq!(part.bbox_max, [1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_save_stl_in_millimeters() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("square.stl");
+        let part =
+            load_stl_from_bytes("square", &binary_stl("square", &TRIANGLES), StlUnit::Meters)
+                .unwrap();
+
+        save_stl_with_unit(&part, &path, StlUnit::Millimeters).unwrap();
+
+        let raw = load_stl_with_unit(&path, StlUnit::Meters).unwrap();
+        assert!((raw.bbox_max[0] - 1000.0).abs() < 1e-3);
+        let scaled = load_stl_with_unit(&path, StlUnit::Millimeters).unwrap();
+        assert!((scaled.bbox_max[1] - part.bbox_max[1]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_truncated_binary_stl_is_rejected() {
+        let mut data = binary_stl("solid truncated", &TRIANGLES);
+        data.truncate(data.len() - 10);
+        assert!(load_stl_from_bytes("truncated", &data, StlUnit::Meters).is_err());
+    }
+}
@@ -4,9 +4,18 @@ use glam::{Mat4, Quat, Vec3};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::inertia::InertiaMatrix;
+use crate::constants::DEFAULT_ROUGHNESS;
+use crate::inertia::{InertiaMatrix, MassProperties, calculate_mass_properties, is_watertight};
 use crate::types::{JointLimits, JointType};
 
+pub(crate) fn default_roughness() -> f32 {
+    DEFAULT_ROUGHNESS
+}
+
+fn default_visible() -> bool {
+    true
+}
+
 /// A part loaded from an STL file with metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Part {
@@ -20,6 +29,9 @@ pub struct Part {
     pub normals: Vec<[f32; 3]>,
     /// Indices for indexed rendering
     pub indices: Vec<u32>,
+    /// Per-vertex RGBA colors, parallel to `vertices` (empty if the mesh has none)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vertex_colors: Vec<[f32; 4]>,
     /// Transform applied to original mesh (origin adjustment)
     pub origin_transform: Mat4,
     /// Mass in kg
@@ -32,10 +44,28 @@ pub struct Part {
     pub bbox_max: [f32; 3],
     /// Material color (RGBA)
     pub color: [f32; 4],
+    /// Material metallic factor (0.0 = dielectric, 1.0 = metal)
+    #[serde(default)]
+    pub metallic: f32,
+    /// Material roughness factor (0.0 = mirror-like, 1.0 = fully rough)
+    #[serde(default = "default_roughness")]
+    pub roughness: f32,
     /// Material name for URDF
     pub material_name: Option<String>,
+    /// Diffuse texture file, if the source mesh had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub texture: Option<String>,
     /// Mirror pair information
     pub mirror_pair: Option<MirrorPair>,
+    /// Whether the part is shown in the viewport (editor-only, not exported)
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    /// Color shown in the viewport instead of `color` (editor-only, not exported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_override: Option<[f32; 4]>,
+    /// Connection points other parts can be snapped to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub joint_points: Vec<JointPoint>,
 }
 
 impl Part {
@@ -48,17 +78,66 @@ impl Part {
             vertices: Vec::new(),
             normals: Vec::new(),
             indices: Vec::new(),
+            vertex_colors: Vec::new(),
             origin_transform: Mat4::IDENTITY,
             mass: 1.0,
             inertia: InertiaMatrix::default(),
             bbox_min: [0.0; 3],
             bbox_max: [0.0; 3],
             color: [0.7, 0.7, 0.7, 1.0],
+            metallic: 0.0,
+            roughness: DEFAULT_ROUGHNESS,
             material_name: None,
+            texture: None,
             mirror_pair: None,
+            visible: true,
+            color_override: None,
+            joint_points: Vec::new(),
         }
     }
 
+    /// Create a copy of this part with a new ID and name
+    ///
+    /// The copy keeps the mesh, material, mesh file reference and joint
+    /// points (with new IDs) but is not part of any mirror pair.
+    pub fn duplicate(&self, name: impl Into<String>) -> Self {
+        let id = Uuid::new_v4();
+        let joint_points = self
+            .joint_points
+            .iter()
+            .map(|point| JointPoint {
+                id: Uuid::new_v4(),
+                part_id: id,
+                ..point.clone()
+            })
+            .collect();
+        Self {
+            id,
+            name: name.into(),
+            mirror_pair: None,
+            joint_points,
+            ..self.clone()
+        }
+    }
+
+    /// Add a joint point to this part, returning its ID
+    pub fn add_joint_point(&mut self, mut point: JointPoint) -> Uuid {
+        point.part_id = self.id;
+        let id = point.id;
+        self.joint_points.push(point);
+        id
+    }
+
+    /// Get a joint point of this part by ID
+    pub fn get_joint_point(&self, id: Uuid) -> Option<&JointPoint> {
+        self.joint_points.iter().find(|point| point.id == id)
+    }
+
+    /// Get the world-space frame of one of this part's joint points
+    pub fn joint_point_frame(&self, point: &JointPoint) -> Mat4 {
+        self.origin_transform * point.local_transform()
+    }
+
     /// Calculate bounding box from vertices
     pub fn calculate_bounding_box(&mut self) {
         if self.vertices.is_empty() {
@@ -81,6 +160,28 @@ impl Part {
         self.bbox_max = max;
     }
 
+    /// Recompute the inertia tensor from the mass and bounding box
+    pub fn recompute_inertia(&mut self) {
+        self.inertia = InertiaMatrix::from_bounding_box(self.mass, self.bbox_min, self.bbox_max);
+    }
+
+    /// Center of mass and inertia of the mesh as a uniform solid of `mass`,
+    /// or `None` if the mesh is not watertight
+    ///
+    /// The mesh is first moved by `placement`, so the results are in the
+    /// frame the mesh is placed into.
+    pub fn mesh_mass_properties(&self, mass: f32, placement: Mat4) -> Option<MassProperties> {
+        if !is_watertight(&self.vertices, &self.indices) {
+            return None;
+        }
+        let vertices: Vec<[f32; 3]> = self
+            .vertices
+            .iter()
+            .map(|v| placement.transform_point3(Vec3::from(*v)).to_array())
+            .collect();
+        calculate_mass_properties(&vertices, &self.indices, mass)
+    }
+
     /// Get the center of the bounding box
     pub fn center(&self) -> Vec3 {
         Vec3::new(
@@ -98,6 +199,37 @@ impl Part {
             self.bbox_max[2] - self.bbox_min[2],
         )
     }
+
+    /// Radius of the sphere around the bounding box center that encloses all vertices
+    pub fn bounding_radius(&self) -> f32 {
+        let center = self.center();
+        self.vertices
+            .iter()
+            .map(|v| Vec3::from(*v).distance(center))
+            .fold(0.0, f32::max)
+    }
+
+    /// Color the viewport shows: the override if set, else the material color
+    pub fn display_color(&self) -> [f32; 4] {
+        self.color_override.unwrap_or(self.color)
+    }
+
+    /// Get the world-space axis-aligned bounding box as (min, max)
+    pub fn world_bounds(&self) -> (Vec3, Vec3) {
+        let (min, max) = (Vec3::from(self.bbox_min), Vec3::from(self.bbox_max));
+        (0..8)
+            .map(|corner| {
+                Vec3::new(
+                    if corner & 1 == 0 { min.x } else { max.x },
+                    if corner & 2 == 0 { min.y } else { max.y },
+                    if corner & 4 == 0 { min.z } else { max.z },
+                )
+            })
+            .map(|corner| self.origin_transform.transform_point3(corner))
+            .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(lo, hi), p| {
+                (lo.min(p), hi.max(p))
+            })
+    }
 }
 
 /// Joint connection point on a part
@@ -134,6 +266,11 @@ impl JointPoint {
         }
     }
 
+    /// Get the frame of this joint point relative to the part origin
+    pub fn local_transform(&self) -> Mat4 {
+        Mat4::from_rotation_translation(self.orientation, self.position)
+    }
+
     /// Create a revolute joint point
     pub fn revolute(name: impl Into<String>, part_id: Uuid, position: Vec3, axis: Vec3) -> Self {
         Self {
@@ -149,6 +286,158 @@ impl JointPoint {
     }
 }
 
+/// Arrangement of copies created by patterning a part
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PatternKind {
+    /// Copies offset along a direction
+    Linear {
+        direction: Vec3,
+        /// Distance between neighbouring instances
+        spacing: f32,
+        /// Number of instances, including the original
+        count: usize,
+    },
+    /// Copies rotated about an axis
+    Circular {
+        axis_origin: Vec3,
+        axis: Vec3,
+        /// Angle between neighbouring instances in radians
+        angle_step: f32,
+        /// Number of instances, including the original
+        count: usize,
+    },
+}
+
+impl PatternKind {
+    /// Get the number of instances, including the original
+    pub fn count(&self) -> usize {
+        match self {
+            PatternKind::Linear { count, .. } | PatternKind::Circular { count, .. } => *count,
+        }
+    }
+
+    /// Get the world-space transforms that place each copy relative to the
+    /// original, excluding the original itself
+    pub fn copy_transforms(&self) -> Vec<Mat4> {
+        (1..self.count())
+            .map(|i| match *self {
+                PatternKind::Linear {
+                    direction, spacing, ..
+                } => Mat4::from_translation(direction.normalize_or_zero() * spacing * i as f32),
+                PatternKind::Circular {
+                    axis_origin,
+                    axis,
+                    angle_step,
+                    ..
+                } => {
+                    Mat4::from_translation(axis_origin)
+                        * Mat4::from_axis_angle(axis.normalize_or(Vec3::Z), angle_step * i as f32)
+                        * Mat4::from_translation(-axis_origin)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Side of a bounding box that alignment lines up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignEdge {
+    Min,
+    Center,
+    Max,
+}
+
+impl AlignEdge {
+    pub const ALL: &'static [AlignEdge] = &[AlignEdge::Min, AlignEdge::Center, AlignEdge::Max];
+
+    /// Get the display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlignEdge::Min => "Min",
+            AlignEdge::Center => "Center",
+            AlignEdge::Max => "Max",
+        }
+    }
+
+    /// Position of this edge for a bounding box extent along one axis
+    fn of(&self, min: f32, max: f32) -> f32 {
+        match self {
+            AlignEdge::Min => min,
+            AlignEdge::Center => (min + max) / 2.0,
+            AlignEdge::Max => max,
+        }
+    }
+}
+
+/// What selected parts are aligned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlignReference {
+    /// The first part in the selection stays put
+    #[default]
+    FirstSelected,
+    /// The combined bounding box of the whole selection
+    SelectionBounds,
+}
+
+/// Compute world translations that align the given bounding boxes along
+/// `axis` (0 = X, 1 = Y, 2 = Z)
+pub fn align_offsets(
+    bounds: &[(Vec3, Vec3)],
+    axis: usize,
+    edge: AlignEdge,
+    reference: AlignReference,
+) -> Vec<Vec3> {
+    let Some(&(first_min, first_max)) = bounds.first() else {
+        return Vec::new();
+    };
+
+    let target = match reference {
+        AlignReference::FirstSelected => edge.of(first_min[axis], first_max[axis]),
+        AlignReference::SelectionBounds => {
+            let min = bounds
+                .iter()
+                .map(|(min, _)| min[axis])
+                .fold(f32::MAX, f32::min);
+            let max = bounds
+                .iter()
+                .map(|(_, max)| max[axis])
+                .fold(f32::MIN, f32::max);
+            edge.of(min, max)
+        }
+    };
+
+    bounds
+        .iter()
+        .map(|(min, max)| axis_offset(axis, target - edge.of(min[axis], max[axis])))
+        .collect()
+}
+
+/// Compute world translations that space the bounding box centers evenly
+/// along `axis`, keeping the outermost two in place
+pub fn distribute_offsets(bounds: &[(Vec3, Vec3)], axis: usize) -> Vec<Vec3> {
+    let mut offsets = vec![Vec3::ZERO; bounds.len()];
+    if bounds.len() < 3 {
+        return offsets;
+    }
+
+    let center = |i: usize| AlignEdge::Center.of(bounds[i].0[axis], bounds[i].1[axis]);
+    let mut order: Vec<usize> = (0..bounds.len()).collect();
+    order.sort_by(|&a, &b| center(a).total_cmp(&center(b)));
+
+    let start = center(order[0]);
+    let step = (center(order[order.len() - 1]) - start) / (order.len() - 1) as f32;
+    for (rank, &i) in order.iter().enumerate() {
+        offsets[i] = axis_offset(axis, start + step * rank as f32 - center(i));
+    }
+    offsets
+}
+
+fn axis_offset(axis: usize, distance: f32) -> Vec3 {
+    let mut offset = Vec3::ZERO;
+    offset[axis] = distance;
+    offset
+}
+
 /// Mirror pair information for symmetric parts
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MirrorPair {
@@ -164,3 +453,111 @@ pub enum MirrorSide {
     Left,
     Right,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(min: f32, max: f32) -> (Vec3, Vec3) {
+        (Vec3::splat(min), Vec3::splat(max))
+    }
+
+    #[test]
+    fn test_world_bounds() {
+        let mut part = Part::new("box");
+        part.bbox_min = [-1.0, -2.0, -3.0];
+        part.bbox_max = [1.0, 2.0, 3.0];
+        part.origin_transform =
+            Mat4::from_translation(Vec3::X * 10.0) * Mat4::from_rotation_z(90f32.to_radians());
+
+        let (min, max) = part.world_bounds();
+        assert!(min.abs_diff_eq(Vec3::new(8.0, -1.0, -3.0), 1e-5));
+        assert!(max.abs_diff_eq(Vec3::new(12.0, 1.0, 3.0), 1e-5));
+    }
+
+    #[test]
+    fn test_color_override() {
+        let mut part = Part::new("link");
+        part.color = [1.0, 0.0, 0.0, 1.0];
+        assert!(!ron::to_string(&part).unwrap().contains("color_override"));
+
+        part.color_override = Some([0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(part.display_color(), [0.0, 0.0, 1.0, 1.0]);
+        // The material color is left alone
+        assert_eq!(part.color, [1.0, 0.0, 0.0, 1.0]);
+
+        let loaded: Part = ron::from_str(&ron::to_string(&part).unwrap()).unwrap();
+        assert_eq!(loaded.color_override, part.color_override);
+    }
+
+    #[test]
+    fn test_recompute_inertia() {
+        let mut part = Part::new("box");
+        part.bbox_min = [-0.5, -1.0, -1.5];
+        part.bbox_max = [0.5, 1.0, 1.5];
+        part.mass = 2.0;
+        part.recompute_inertia();
+
+        let expected = InertiaMatrix::from_bounding_box(2.0, part.bbox_min, part.bbox_max);
+        assert_eq!(part.inertia.ixx, expected.ixx);
+        assert_eq!(part.inertia.iyy, expected.iyy);
+        assert_eq!(part.inertia.izz, expected.izz);
+        assert!(part.inertia.ixx > part.inertia.iyy && part.inertia.iyy > part.inertia.izz);
+    }
+
+    #[test]
+    fn test_mesh_mass_properties() {
+        let (vertices, _, indices) = crate::primitive::generate_box_mesh([1.0, 2.0, 3.0]);
+        let mut part = Part::new("box");
+        part.vertices = vertices.iter().map(|v| [v[0] + 1.0, v[1], v[2]]).collect();
+        part.indices = indices;
+
+        let props = part.mesh_mass_properties(2.0, Mat4::IDENTITY).unwrap();
+        assert!((props.volume - 6.0).abs() < 1e-4);
+        assert!(Vec3::from(props.center_of_mass).abs_diff_eq(Vec3::X, 1e-5));
+        let expected = InertiaMatrix::box_inertia(2.0, 1.0, 2.0, 3.0);
+        assert!((props.inertia.ixx - expected.ixx).abs() < 1e-4);
+        assert!((props.inertia.iyy - expected.iyy).abs() < 1e-4);
+        assert!((props.inertia.izz - expected.izz).abs() < 1e-4);
+        assert!(props.inertia.ixy.abs() < 1e-4 && props.inertia.iyz.abs() < 1e-4);
+
+        // One missing triangle opens the mesh
+        part.indices.truncate(part.indices.len() - 3);
+        assert!(part.mesh_mass_properties(2.0, Mat4::IDENTITY).is_none());
+    }
+
+    #[test]
+    fn test_align_offsets() {
+        let boxes = [bounds(1.0, 2.0), bounds(-4.0, 0.0), bounds(3.0, 7.0)];
+
+        let offsets = align_offsets(&boxes, 0, AlignEdge::Min, AlignReference::FirstSelected);
+        assert_eq!(
+            offsets.iter().map(|o| o.x).collect::<Vec<_>>(),
+            [0.0, 5.0, -2.0]
+        );
+        assert_eq!(offsets[1].y, 0.0);
+
+        let offsets = align_offsets(&boxes, 2, AlignEdge::Max, AlignReference::SelectionBounds);
+        assert_eq!(
+            offsets.iter().map(|o| o.z).collect::<Vec<_>>(),
+            [5.0, 7.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_distribute_offsets() {
+        // Centers at 0, 9 and 2, in selection order
+        let boxes = [bounds(-1.0, 1.0), bounds(8.0, 10.0), bounds(1.0, 3.0)];
+
+        let offsets = distribute_offsets(&boxes, 1);
+        assert_eq!(
+            offsets.iter().map(|o| o.y).collect::<Vec<_>>(),
+            [0.0, 0.0, 2.5]
+        );
+        assert!(
+            distribute_offsets(&boxes[..2], 1)
+                .iter()
+                .all(|o| *o == Vec3::ZERO)
+        );
+    }
+}
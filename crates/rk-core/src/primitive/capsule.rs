@@ -0,0 +1,87 @@
+//! Capsule mesh generation (cylinder with hemispherical caps)
+
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use super::MeshData;
+
+/// Generate a capsule mesh along the Z axis
+///
+/// # Arguments
+/// * `radius` - Radius of the cylinder and end caps
+/// * `length` - Length of the cylindrical section (excluding the caps)
+///
+/// # Returns
+/// (vertices, normals, indices)
+pub fn generate_capsule_mesh(radius: f32, length: f32) -> MeshData {
+    use crate::constants::{SPHERE_LAT_SEGMENTS, SPHERE_LON_SEGMENTS};
+    generate_capsule_mesh_with_segments(
+        radius,
+        length,
+        SPHERE_LAT_SEGMENTS / 2,
+        SPHERE_LON_SEGMENTS,
+    )
+}
+
+/// Generate a capsule mesh with custom resolution
+///
+/// # Arguments
+/// * `radius` - Radius of the cylinder and end caps
+/// * `length` - Length of the cylindrical section (excluding the caps)
+/// * `cap_segments` - Number of latitude bands per hemisphere (default: 8)
+/// * `lon_segments` - Number of longitude segments (default: 32)
+pub fn generate_capsule_mesh_with_segments(
+    radius: f32,
+    length: f32,
+    cap_segments: u32,
+    lon_segments: u32,
+) -> MeshData {
+    let half_length = length / 2.0;
+    let mut vertices = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+
+    // Rings from the top pole to the bottom pole. The equator appears twice,
+    // once for each cap, and the band between the copies is the cylinder.
+    let top = (0..=cap_segments).map(|i| (FRAC_PI_2 * i as f32 / cap_segments as f32, half_length));
+    let bottom = (0..=cap_segments).map(|i| {
+        (
+            FRAC_PI_2 + FRAC_PI_2 * i as f32 / cap_segments as f32,
+            -half_length,
+        )
+    });
+    let rings: Vec<(f32, f32)> = top.chain(bottom).collect();
+
+    for &(theta, z_offset) in &rings {
+        let sin_theta = theta.sin();
+        let cos_theta = theta.cos();
+
+        for lon in 0..=lon_segments {
+            let phi = (lon as f32 / lon_segments as f32) * 2.0 * PI;
+            let x = sin_theta * phi.cos();
+            let y = sin_theta * phi.sin();
+            let z = cos_theta;
+
+            vertices.push([radius * x, radius * y, radius * z + z_offset]);
+            normals.push([x, y, z]);
+        }
+    }
+
+    for ring in 0..rings.len() as u32 - 1 {
+        for lon in 0..lon_segments {
+            let current = ring * (lon_segments + 1) + lon;
+            let next = current + lon_segments + 1;
+
+            // Triangle 1
+            indices.push(current);
+            indices.push(next);
+            indices.push(current + 1);
+
+            // Triangle 2
+            indices.push(current + 1);
+            indices.push(next);
+            indices.push(next + 1);
+        }
+    }
+
+    (vertices, normals, indices)
+}
@@ -4,18 +4,71 @@
 //! - Box (rectangular prism)
 //! - Cylinder (with end caps)
 //! - Sphere (UV sphere)
+//! - Capsule (cylinder with hemispherical caps)
 
 mod box_mesh;
+mod capsule;
 mod cylinder;
 mod sphere;
 
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{CYLINDER_SEGMENTS, SPHERE_LAT_SEGMENTS};
+
 pub use box_mesh::generate_box_mesh;
+pub use capsule::{generate_capsule_mesh, generate_capsule_mesh_with_segments};
 pub use cylinder::{generate_cylinder_mesh, generate_cylinder_mesh_with_segments};
 pub use sphere::{generate_sphere_mesh, generate_sphere_mesh_with_segments};
 
 /// Mesh data: vertices, normals, and triangle indices
 pub type MeshData = (Vec<[f32; 3]>, Vec<[f32; 3]>, Vec<u32>);
 
+/// Tessellation level for curved primitives (cylinder, sphere, capsule)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PrimitiveQuality {
+    /// 16 segments around, 8 rings
+    Low,
+    /// 32 segments around, 16 rings
+    #[default]
+    Medium,
+    /// 64 segments around, 32 rings
+    High,
+}
+
+impl PrimitiveQuality {
+    /// Number of segments around the axis of revolution
+    pub fn segments(&self) -> u32 {
+        match self {
+            PrimitiveQuality::Low => CYLINDER_SEGMENTS / 2,
+            PrimitiveQuality::Medium => CYLINDER_SEGMENTS,
+            PrimitiveQuality::High => CYLINDER_SEGMENTS * 2,
+        }
+    }
+
+    /// Number of latitude rings from pole to pole
+    pub fn rings(&self) -> u32 {
+        match self {
+            PrimitiveQuality::Low => SPHERE_LAT_SEGMENTS / 2,
+            PrimitiveQuality::Medium => SPHERE_LAT_SEGMENTS,
+            PrimitiveQuality::High => SPHERE_LAT_SEGMENTS * 2,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrimitiveQuality::Low => "Low",
+            PrimitiveQuality::Medium => "Medium",
+            PrimitiveQuality::High => "High",
+        }
+    }
+
+    pub const ALL: &'static [PrimitiveQuality] = &[
+        PrimitiveQuality::Low,
+        PrimitiveQuality::Medium,
+        PrimitiveQuality::High,
+    ];
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,6 +99,34 @@ mod tests {
         assert!(indices.len() % 3 == 0);
     }
 
+    #[test]
+    fn test_capsule_dimensions() {
+        let (vertices, normals, indices) = generate_capsule_mesh(0.1, 0.5);
+        assert_eq!(vertices.len(), normals.len());
+        assert!(indices.len() % 3 == 0);
+
+        let min_z = vertices.iter().map(|v| v[2]).fold(f32::MAX, f32::min);
+        let max_z = vertices.iter().map(|v| v[2]).fold(f32::MIN, f32::max);
+        let max_x = vertices.iter().map(|v| v[0]).fold(f32::MIN, f32::max);
+        assert!((max_z - min_z - (0.5 + 2.0 * 0.1)).abs() < 0.001);
+        assert!((max_x - 0.1).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_primitive_quality_resolution() {
+        let count = |quality: PrimitiveQuality| {
+            generate_sphere_mesh_with_segments(1.0, quality.rings(), quality.segments())
+                .0
+                .len()
+        };
+        assert_eq!(
+            count(PrimitiveQuality::Medium),
+            generate_sphere_mesh(1.0).0.len()
+        );
+        assert!(count(PrimitiveQuality::Low) < count(PrimitiveQuality::Medium));
+        assert!(count(PrimitiveQuality::Medium) < count(PrimitiveQuality::High));
+    }
+
     #[test]
     fn test_box_dimensions() {
         let (vertices, _, _) = generate_box_mesh([2.0, 4.0, 6.0]);
@@ -1,22 +1,29 @@
 //! Project file serialization
 
+use std::cell::OnceCell;
 use std::collections::HashMap;
 use std::path::Path;
 
+use glam::{EulerRot, Mat4, Vec3};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::assembly::Assembly;
-use crate::part::Part;
+use crate::assembly::{Assembly, AssemblyError, Joint, Link};
+use crate::part::{JointPoint, Part};
+use crate::types::{JointLimits, JointType, Pose};
 
 /// Serialization format for backward compatibility
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ProjectData {
+    /// Missing in files written before versioning, which count as version 0
+    #[serde(default)]
     version: u32,
     name: String,
     parts: Vec<Part>,
     assembly: Assembly,
     materials: Vec<MaterialDef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extensions: Vec<UrdfExtension>,
 }
 
 /// Project file containing all editor state
@@ -32,8 +39,16 @@ pub struct Project {
     pub assembly: Assembly,
     /// Material definitions
     pub materials: Vec<MaterialDef>,
+    /// URDF elements kept verbatim from import (e.g. `<gazebo>`)
+    pub extensions: Vec<UrdfExtension>,
+    /// Triangle count and bounds of the parts for [`Self::statistics`],
+    /// cleared whenever parts are added, removed or borrowed mutably
+    part_stats: OnceCell<PartStats>,
 }
 
+/// Triangle count and world bounds of all parts
+type PartStats = (usize, Option<(Vec3, Vec3)>);
+
 impl From<Project> for ProjectData {
     fn from(project: Project) -> Self {
         Self {
@@ -42,6 +57,7 @@ impl From<Project> for ProjectData {
             parts: project.parts.into_values().collect(),
             assembly: project.assembly,
             materials: project.materials,
+            extensions: project.extensions,
         }
     }
 }
@@ -55,6 +71,8 @@ impl From<ProjectData> for Project {
             parts,
             assembly: data.assembly,
             materials: data.materials,
+            extensions: data.extensions,
+            part_stats: OnceCell::new(),
         }
     }
 }
@@ -70,6 +88,7 @@ impl Serialize for Project {
             parts: self.parts.values().cloned().collect(),
             assembly: self.assembly.clone(),
             materials: self.materials.clone(),
+            extensions: self.extensions.clone(),
         };
         data.serialize(serializer)
     }
@@ -92,14 +111,19 @@ impl Default for Project {
 }
 
 impl Project {
+    /// Current project file format version
+    pub const CURRENT_VERSION: u32 = 1;
+
     /// Create a new empty project
     pub fn new(name: impl Into<String>) -> Self {
         Self {
-            version: 1,
+            version: Self::CURRENT_VERSION,
             name: name.into(),
             parts: HashMap::new(),
             assembly: Assembly::default(),
             materials: Vec::new(),
+            extensions: Vec::new(),
+            part_stats: OnceCell::new(),
         }
     }
 
@@ -111,11 +135,13 @@ impl Project {
         materials: Vec<MaterialDef>,
     ) -> Self {
         Self {
-            version: 1,
+            version: Self::CURRENT_VERSION,
             name: name.into(),
             parts,
             assembly,
             materials,
+            extensions: Vec::new(),
+            part_stats: OnceCell::new(),
         }
     }
 
@@ -138,20 +164,44 @@ impl Project {
     pub fn load(path: impl AsRef<Path>) -> Result<Self, ProjectError> {
         let path = path.as_ref();
         let content = std::fs::read_to_string(path).map_err(|e| ProjectError::Io(e.to_string()))?;
-        let project: Project =
-            ron::from_str(&content).map_err(|e| ProjectError::Deserialize(e.to_string()))?;
-        Ok(project)
+        Self::from_ron(&content)
     }
 
     /// Load project from bytes (for WASM support)
     pub fn load_from_bytes(data: &[u8]) -> Result<Self, ProjectError> {
         let content =
             std::str::from_utf8(data).map_err(|e| ProjectError::Deserialize(e.to_string()))?;
-        let project: Project =
+        Self::from_ron(content)
+    }
+
+    /// Parse a project and migrate it to [`Self::CURRENT_VERSION`]
+    fn from_ron(content: &str) -> Result<Self, ProjectError> {
+        let mut project: Project =
             ron::from_str(content).map_err(|e| ProjectError::Deserialize(e.to_string()))?;
+        project.migrate()?;
         Ok(project)
     }
 
+    /// Upgrade a loaded project file one format version at a time
+    ///
+    /// Projects are documents, so a file from a newer format is rejected
+    /// rather than loaded with data silently dropped. File formats that
+    /// embed a project must call this after deserializing it.
+    pub fn migrate(&mut self) -> Result<(), ProjectError> {
+        if self.version > Self::CURRENT_VERSION {
+            return Err(ProjectError::UnsupportedVersion {
+                found: self.version,
+                supported: Self::CURRENT_VERSION,
+            });
+        }
+        while self.version < Self::CURRENT_VERSION {
+            // Version 0 files were saved before the version field existed;
+            // their parts and assembly already match version 1
+            self.version += 1;
+        }
+        Ok(())
+    }
+
     // ============== Part Accessors ==============
 
     /// Get a reference to the parts map
@@ -161,6 +211,7 @@ impl Project {
 
     /// Get a mutable reference to the parts map
     pub fn parts_mut(&mut self) -> &mut HashMap<Uuid, Part> {
+        self.part_stats.take();
         &mut self.parts
     }
 
@@ -172,6 +223,7 @@ impl Project {
     /// Add a part to the project, returns the part ID
     pub fn add_part(&mut self, part: Part) -> Uuid {
         let id = part.id;
+        self.part_stats.take();
         self.parts.insert(id, part);
         id
     }
@@ -183,17 +235,563 @@ impl Project {
 
     /// Get a mutable part by ID
     pub fn get_part_mut(&mut self, id: Uuid) -> Option<&mut Part> {
+        self.part_stats.take();
         self.parts.get_mut(&id)
     }
 
     /// Remove a part by ID
     pub fn remove_part(&mut self, id: Uuid) -> Option<Part> {
+        self.part_stats.take();
         self.parts.remove(&id)
     }
+
+    /// Find a joint point and the part it belongs to
+    pub fn find_joint_point(&self, id: Uuid) -> Option<(&Part, &JointPoint)> {
+        self.parts
+            .values()
+            .find_map(|part| Some((part, part.get_joint_point(id)?)))
+    }
+
+    // ============== Assembly ==============
+
+    /// Joint two parts by snapping a joint point of the child onto one of the parent
+    ///
+    /// The child part, along with everything jointed below it, is moved so the
+    /// two joint point frames coincide. A joint of the parent point's type is
+    /// then created at that frame, replacing any existing parent joint of the
+    /// child. Returns the new joint's ID.
+    pub fn snap_joint_points(
+        &mut self,
+        parent_point: Uuid,
+        child_point: Uuid,
+    ) -> Result<Uuid, AssemblyError> {
+        let (parent_part, parent_jp) = self
+            .find_joint_point(parent_point)
+            .ok_or(AssemblyError::JointPointNotFound(parent_point))?;
+        let (child_part, child_jp) = self
+            .find_joint_point(child_point)
+            .ok_or(AssemblyError::JointPointNotFound(child_point))?;
+        if parent_part.id == child_part.id {
+            return Err(AssemblyError::WouldCreateCycle);
+        }
+
+        let (parent_id, child_id) = (parent_part.id, child_part.id);
+        let target = parent_part.joint_point_frame(parent_jp);
+        let old_child = child_part.origin_transform;
+        let new_child = target * child_jp.local_transform().inverse();
+        // The axis is given in the parent part's frame
+        let world_axis = parent_part
+            .origin_transform
+            .transform_vector3(parent_jp.axis);
+        let joint_type = parent_jp.joint_type;
+        let limits = parent_jp.limits.or_else(|| default_limits(joint_type));
+
+        let parent_link = self.link_for_part(parent_id);
+        let child_link = self.link_for_part(child_id);
+        if self.assembly.is_ancestor(child_link, parent_link) {
+            return Err(AssemblyError::WouldCreateCycle);
+        }
+        if self.assembly.parent.contains_key(&child_link) {
+            self.assembly.disconnect(child_link)?;
+        }
+
+        // Move the child's subtree so its joint point lands on the parent's
+        let delta = new_child * old_child.inverse();
+        let moved: Vec<Uuid> = std::iter::once(child_link)
+            .chain(self.assembly.get_all_descendants(child_link))
+            .filter_map(|link_id| self.assembly.get_link(link_id)?.part_id)
+            .collect();
+        self.part_stats.take();
+        for part_id in moved {
+            if let Some(part) = self.parts.get_mut(&part_id) {
+                part.origin_transform = delta * part.origin_transform;
+            }
+        }
+
+        // Joint frame relative to the parent link at zero joint positions
+        let origin = self.assembly.get_world_transform(parent_link).inverse() * target;
+        let (_, rotation, translation) = origin.to_scale_rotation_translation();
+        let (roll, pitch, yaw) = rotation.to_euler(EulerRot::XYZ);
+        let (_, target_rotation, _) = target.to_scale_rotation_translation();
+        let axis = (target_rotation.inverse() * world_axis).normalize_or(Vec3::Z);
+
+        let name = |link_id| {
+            self.assembly
+                .get_link(link_id)
+                .map(|link| link.name.clone())
+                .unwrap_or_default()
+        };
+        let mut builder = Joint::builder(
+            format!("{}_to_{}", name(parent_link), name(child_link)),
+            parent_link,
+            child_link,
+        )
+        .joint_type(joint_type)
+        .origin(Pose::new(translation.to_array(), [roll, pitch, yaw]))
+        .axis(axis)
+        .joint_points(parent_point, child_point);
+        if let Some(limits) = limits {
+            builder = builder.limits(limits);
+        }
+
+        let joint_id = self
+            .assembly
+            .connect(parent_link, child_link, builder.build())?;
+        self.assembly
+            .update_world_transforms_with_current_positions();
+        Ok(joint_id)
+    }
+
+    /// Joint two existing links where they currently are
+    ///
+    /// The joint origin is the child's current pose (the child part's
+    /// placement if it has one) relative to the parent link's current pose,
+    /// both taken with the joint positions applied, so nothing moves. The
+    /// child part's rest placement is updated to match. Any existing parent
+    /// joint of the child is replaced. The axis is the child's Z axis, with
+    /// default limits for revolute and prismatic joints. Returns the new
+    /// joint's ID.
+    pub fn create_joint(
+        &mut self,
+        parent_link: Uuid,
+        child_link: Uuid,
+        joint_type: JointType,
+    ) -> Result<Uuid, AssemblyError> {
+        for link_id in [parent_link, child_link] {
+            if self.assembly.get_link(link_id).is_none() {
+                return Err(AssemblyError::LinkNotFound(link_id));
+            }
+        }
+        if parent_link == child_link || self.assembly.is_ancestor(child_link, parent_link) {
+            return Err(AssemblyError::WouldCreateCycle);
+        }
+
+        // Both poses are taken with the joint positions applied. A posed link
+        // moves its part by the link's offset from its rest pose.
+        self.assembly
+            .update_world_transforms_with_current_positions();
+        let posed = |link_id| self.assembly.links[&link_id].world_transform;
+        let child_part_id = self.assembly.links[&child_link].part_id;
+        let child_world = match child_part_id.and_then(|id| self.parts.get(&id)) {
+            Some(part) => {
+                posed(child_link)
+                    * self.assembly.get_world_transform(child_link).inverse()
+                    * part.origin_transform
+            }
+            None => posed(child_link),
+        };
+        let parent_world = posed(parent_link);
+        if self.assembly.parent.contains_key(&child_link) {
+            self.assembly.disconnect(child_link)?;
+        }
+
+        let origin = parent_world.inverse() * child_world;
+        self.part_stats.take();
+        if let Some(part) = child_part_id.and_then(|id| self.parts.get_mut(&id)) {
+            part.origin_transform = self.assembly.get_world_transform(parent_link) * origin;
+        }
+
+        let name = |link_id| {
+            self.assembly
+                .get_link(link_id)
+                .map(|link| link.name.clone())
+                .unwrap_or_default()
+        };
+        let mut builder = Joint::builder(
+            format!("{}_to_{}", name(parent_link), name(child_link)),
+            parent_link,
+            child_link,
+        )
+        .joint_type(joint_type)
+        .origin(Pose::from_mat4(origin))
+        .axis(Vec3::Z);
+        if let Some(limits) = default_limits(joint_type) {
+            builder = builder.limits(limits);
+        }
+
+        let joint_id = self
+            .assembly
+            .connect(parent_link, child_link, builder.build())?;
+        self.assembly
+            .update_world_transforms_with_current_positions();
+        Ok(joint_id)
+    }
+
+    // ============== Materials ==============
+
+    /// Get a library material by name
+    pub fn get_material(&self, name: &str) -> Option<&MaterialDef> {
+        self.materials.iter().find(|m| m.name == name)
+    }
+
+    /// Add a material to the library
+    pub fn add_material(&mut self, material: MaterialDef) -> Result<(), ProjectError> {
+        if self.get_material(&material.name).is_some() {
+            return Err(ProjectError::DuplicateMaterial(material.name));
+        }
+        self.materials.push(material);
+        Ok(())
+    }
+
+    /// Replace the library material named `name`
+    ///
+    /// Parts and link visuals using it follow a rename and take the new
+    /// color. Returns the IDs of the parts that changed.
+    pub fn update_material(
+        &mut self,
+        name: &str,
+        material: MaterialDef,
+    ) -> Result<Vec<Uuid>, ProjectError> {
+        let index = self
+            .materials
+            .iter()
+            .position(|m| m.name == name)
+            .ok_or_else(|| ProjectError::MaterialNotFound(name.to_string()))?;
+        if material.name != name && self.get_material(&material.name).is_some() {
+            return Err(ProjectError::DuplicateMaterial(material.name));
+        }
+
+        let users: Vec<Uuid> = self
+            .parts
+            .values()
+            .filter(|part| part.material_name.as_deref() == Some(name))
+            .map(|part| part.id)
+            .collect();
+        for link in self.assembly.links.values_mut() {
+            for visual in &mut link.visuals {
+                if visual.material_name.as_deref() == Some(name) {
+                    visual.material_name = Some(material.name.clone());
+                    visual.color = material.color;
+                }
+            }
+        }
+        self.materials[index] = material;
+        let material = &self.materials[index];
+        for id in &users {
+            if let Some(part) = self.parts.get_mut(id) {
+                part.material_name = Some(material.name.clone());
+                part.color = material.color;
+            }
+        }
+        Ok(users)
+    }
+
+    /// Remove a library material; parts and visuals using it keep their color
+    pub fn remove_material(&mut self, name: &str) -> Option<MaterialDef> {
+        let index = self.materials.iter().position(|m| m.name == name)?;
+        for part in self.parts.values_mut() {
+            if part.material_name.as_deref() == Some(name) {
+                part.material_name = None;
+            }
+        }
+        for link in self.assembly.links.values_mut() {
+            for visual in &mut link.visuals {
+                if visual.material_name.as_deref() == Some(name) {
+                    visual.material_name = None;
+                }
+            }
+        }
+        Some(self.materials.remove(index))
+    }
+
+    /// Assign a library material to parts, or clear their material with `None`
+    ///
+    /// The parts and the visuals of their links take the material's color, so
+    /// the viewport and the exported URDF agree. Clearing keeps the color.
+    pub fn assign_material(
+        &mut self,
+        part_ids: &[Uuid],
+        name: Option<&str>,
+    ) -> Result<(), ProjectError> {
+        let material = match name {
+            Some(name) => Some(
+                self.get_material(name)
+                    .cloned()
+                    .ok_or_else(|| ProjectError::MaterialNotFound(name.to_string()))?,
+            ),
+            None => None,
+        };
+        let material_name = material.as_ref().map(|m| m.name.clone());
+
+        for id in part_ids {
+            if let Some(part) = self.parts.get_mut(id) {
+                part.material_name = material_name.clone();
+                if let Some(material) = &material {
+                    part.color = material.color;
+                }
+            }
+        }
+        for link in self.assembly.links.values_mut() {
+            if !link.part_id.is_some_and(|id| part_ids.contains(&id)) {
+                continue;
+            }
+            for visual in &mut link.visuals {
+                visual.material_name = material_name.clone();
+                if let Some(material) = &material {
+                    visual.color = material.color;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // ============== Merging ==============
+
+    /// Insert the parts, links and joints of another project into this one
+    ///
+    /// Every ID from `other` is replaced by a new one, and link, joint,
+    /// part and material names already in use get a numeric suffix. With a
+    /// `parent_link`, each root link of `other` is attached to it by a fixed
+    /// joint at `origin`; without one, the merged roots stay roots placed at
+    /// `origin`. The merged parts move along with their links. CAD history
+    /// and URDF extensions of `other` are not merged.
+    ///
+    /// `other` is checked to form a valid tree before anything is inserted,
+    /// so a failed merge leaves this project untouched. Mimic references to
+    /// joints outside `other` are dropped.
+    ///
+    /// Returns the IDs of the inserted parts.
+    pub fn merge(
+        &mut self,
+        other: Project,
+        parent_link: Option<Uuid>,
+        origin: Pose,
+    ) -> Result<Vec<Uuid>, ProjectError> {
+        let offset = match parent_link {
+            Some(id) if !self.assembly.links.contains_key(&id) => {
+                return Err(AssemblyError::LinkNotFound(id).into());
+            }
+            Some(id) => self.assembly.get_world_transform(id) * origin.to_mat4(),
+            None => origin.to_mat4(),
+        };
+        check_merge_tree(&other.assembly)?;
+        let roots = other.assembly.get_root_links();
+
+        // Materials with the same name and definition are shared
+        let mut material_names: HashMap<String, String> = HashMap::new();
+        for material in other.materials {
+            match self.get_material(&material.name) {
+                Some(existing) if *existing == material => {}
+                Some(_) => {
+                    let name =
+                        unique_name(&material.name, |name| self.get_material(name).is_some());
+                    material_names.insert(material.name.clone(), name.clone());
+                    self.materials.push(MaterialDef { name, ..material });
+                }
+                None => self.materials.push(material),
+            }
+        }
+        let rename_material = |name: &mut Option<String>| {
+            if let Some(name) = name
+                && let Some(renamed) = material_names.get(name)
+            {
+                *name = renamed.clone();
+            }
+        };
+
+        let part_ids = fresh_ids(other.parts.keys().copied());
+        let point_ids = fresh_ids(
+            other
+                .parts
+                .values()
+                .flat_map(|part| part.joint_points.iter().map(|point| point.id)),
+        );
+        let link_ids = fresh_ids(other.assembly.links.keys().copied());
+        let joint_ids = fresh_ids(other.assembly.joints.keys().copied());
+
+        let mut added = Vec::with_capacity(part_ids.len());
+        for mut part in other.parts.into_values() {
+            part.id = part_ids[&part.id];
+            part.name = unique_name(&part.name, |name| {
+                self.parts.values().any(|existing| existing.name == name)
+            });
+            part.origin_transform = offset * part.origin_transform;
+            for point in &mut part.joint_points {
+                point.id = point_ids[&point.id];
+                point.part_id = part.id;
+            }
+            if let Some(pair) = &mut part.mirror_pair {
+                pair.partner_id = pair.partner_id.and_then(|id| part_ids.get(&id).copied());
+            }
+            rename_material(&mut part.material_name);
+            added.push(self.add_part(part));
+        }
+
+        for mut link in other.assembly.links.into_values() {
+            link.id = link_ids[&link.id];
+            link.name = unique_name(&link.name, |name| {
+                self.assembly.find_link_id_by_name(name).is_some()
+            });
+            link.part_id = link.part_id.and_then(|id| part_ids.get(&id).copied());
+            for visual in &mut link.visuals {
+                rename_material(&mut visual.material_name);
+            }
+            self.assembly.add_link(link);
+        }
+
+        let mut joints: Vec<Joint> = other.assembly.joints.into_values().collect();
+        for joint in &mut joints {
+            joint.id = joint_ids[&joint.id];
+            joint.parent_link = link_ids[&joint.parent_link];
+            joint.child_link = link_ids[&joint.child_link];
+            joint.mimic = joint.mimic.take().and_then(|mut mimic| {
+                mimic.joint_id = *joint_ids.get(&mimic.joint_id)?;
+                Some(mimic)
+            });
+            joint.parent_joint_point = joint
+                .parent_joint_point
+                .and_then(|id| point_ids.get(&id).copied());
+            joint.child_joint_point = joint
+                .child_joint_point
+                .and_then(|id| point_ids.get(&id).copied());
+        }
+        for mut joint in joints {
+            joint.name = unique_name(&joint.name, |name| {
+                self.assembly.find_joint_id_by_name(name).is_some()
+            });
+            self.assembly
+                .connect(joint.parent_link, joint.child_link, joint)?;
+        }
+
+        if let Some(parent_id) = parent_link {
+            for root in roots {
+                let root = link_ids[&root];
+                let name = unique_name(
+                    &format!("{}_mount", self.assembly.links[&root].name),
+                    |name| self.assembly.find_joint_id_by_name(name).is_some(),
+                );
+                let joint = Joint::fixed(name, parent_id, root, origin);
+                self.assembly.connect(parent_id, root, joint)?;
+            }
+        }
+        self.assembly
+            .update_world_transforms_with_current_positions();
+
+        Ok(added)
+    }
+
+    // ============== Statistics ==============
+
+    /// Aggregate counts, mass and extent of the whole robot
+    ///
+    /// Mass and center of mass come from the links' inertial properties, as
+    /// written on export; triangle count and bounds come from the parts and
+    /// are cached until the parts change.
+    pub fn statistics(&self) -> ProjectStats {
+        let links = self.assembly.links.values();
+        let total_mass: f32 = links.clone().map(|link| link.inertial.mass).sum();
+        let center_of_mass = (total_mass > 0.0).then(|| {
+            links
+                .map(|link| {
+                    let center = link
+                        .world_transform
+                        .transform_point3(Vec3::from(link.inertial.origin.xyz));
+                    center * link.inertial.mass
+                })
+                .sum::<Vec3>()
+                / total_mass
+        });
+        let (triangle_count, bounds) = *self.part_stats.get_or_init(|| {
+            let triangle_count = self.parts.values().map(|p| p.indices.len() / 3).sum();
+            let bounds = self
+                .parts
+                .values()
+                .map(Part::world_bounds)
+                .reduce(|(lo, hi), (min, max)| (lo.min(min), hi.max(max)));
+            (triangle_count, bounds)
+        });
+
+        ProjectStats {
+            link_count: self.assembly.links.len(),
+            joint_count: self.assembly.joints.len(),
+            part_count: self.parts.len(),
+            triangle_count,
+            total_mass,
+            center_of_mass,
+            bounds,
+        }
+    }
+
+    // ============== Inertia ==============
+
+    /// Recompute the inertia of every link from its part's mesh
+    ///
+    /// Each link with a watertight mesh gets the inertia tensor of the mesh
+    /// as a uniform solid of the link's mass, with `inertial.origin` moved to
+    /// the mesh's center of mass; the part's inertia is updated to match.
+    /// Links whose mesh is open are left alone and reported as skipped.
+    pub fn recompute_mesh_inertias(&mut self) -> InertiaRecomputeReport {
+        let mut report = InertiaRecomputeReport::default();
+        for link in self.assembly.links.values_mut() {
+            let Some(part) = link.part_id.and_then(|id| self.parts.get_mut(&id)) else {
+                continue;
+            };
+            // Exported meshes carry the part's origin transform and sit at
+            // the visual origin in the link frame
+            let placement = link
+                .visuals
+                .first()
+                .map_or(Mat4::IDENTITY, |visual| visual.origin.to_mat4())
+                * part.origin_transform;
+            let Some(props) = part.mesh_mass_properties(link.inertial.mass, placement) else {
+                report.skipped.push(link.name.clone());
+                continue;
+            };
+            part.inertia = props.inertia;
+            link.inertial.inertia = props.inertia;
+            link.inertial.origin.xyz = props.center_of_mass;
+            report.updated.push(link.name.clone());
+        }
+        report.updated.sort();
+        report.skipped.sort();
+        report
+    }
+
+    /// Get the link using a part, creating one if the part has none
+    fn link_for_part(&mut self, part_id: Uuid) -> Uuid {
+        if let Some(link) = self.assembly.find_link_by_part(part_id) {
+            return link.id;
+        }
+        let link = Link::from_part(&self.parts[&part_id]);
+        self.assembly.add_link(link)
+    }
+}
+
+/// Default limits of a newly created joint of the given type
+fn default_limits(joint_type: JointType) -> Option<JointLimits> {
+    match joint_type {
+        JointType::Revolute => Some(JointLimits::default_revolute()),
+        JointType::Prismatic => Some(JointLimits::default_prismatic()),
+        _ => None,
+    }
+}
+
+/// Aggregate statistics of a project, see [`Project::statistics`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectStats {
+    pub link_count: usize,
+    pub joint_count: usize,
+    pub part_count: usize,
+    pub triangle_count: usize,
+    /// Sum of link masses (kg)
+    pub total_mass: f32,
+    /// World-space center of mass, if any link has mass
+    pub center_of_mass: Option<Vec3>,
+    /// World-space bounding box of all parts as (min, max), if there are any
+    pub bounds: Option<(Vec3, Vec3)>,
+}
+
+/// Links touched by [`Project::recompute_mesh_inertias`], by name
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InertiaRecomputeReport {
+    /// Links whose inertia and center of mass were recomputed
+    pub updated: Vec<String>,
+    /// Links skipped because their mesh is not watertight
+    pub skipped: Vec<String>,
 }
 
 /// Material definition for URDF
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MaterialDef {
     pub name: String,
     pub color: [f32; 4],
@@ -210,6 +808,26 @@ impl MaterialDef {
     }
 }
 
+/// A URDF element the editor does not model, kept verbatim for export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrdfExtension {
+    /// Element the extension's `reference` attribute points at
+    pub scope: ExtensionScope,
+    /// Original XML text of the element
+    pub xml: String,
+}
+
+/// What a [`UrdfExtension`] is attached to
+///
+/// Links and joints are tracked by ID so renames can be carried into the
+/// `reference` attribute on export; `name` is the reference as written.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ExtensionScope {
+    Robot,
+    Link { id: Uuid, name: String },
+    Joint { id: Uuid, name: String },
+}
+
 /// Project-related errors
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ProjectError {
@@ -219,4 +837,516 @@ pub enum ProjectError {
     Serialize(String),
     #[error("Deserialization error: {0}")]
     Deserialize(String),
+    #[error("Project file version {found} is newer than supported version {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("Material already exists: {0}")]
+    DuplicateMaterial(String),
+    #[error("Material not found: {0}")]
+    MaterialNotFound(String),
+    #[error(transparent)]
+    Assembly(#[from] AssemblyError),
+}
+
+/// Check that the joints of an assembly about to be merged form a forest
+///
+/// Every joint must join two of its links, and no link may have more than
+/// one parent or be its own ancestor, so that connecting the joints one by
+/// one cannot fail halfway through a merge.
+fn check_merge_tree(assembly: &Assembly) -> Result<(), AssemblyError> {
+    let mut parents: HashMap<Uuid, Uuid> = HashMap::new();
+    for joint in assembly.joints.values() {
+        for link in [joint.parent_link, joint.child_link] {
+            if !assembly.links.contains_key(&link) {
+                return Err(AssemblyError::InvalidJointReference(joint.id, link));
+            }
+        }
+        if parents
+            .insert(joint.child_link, joint.parent_link)
+            .is_some()
+        {
+            return Err(AssemblyError::MultipleParents(joint.child_link));
+        }
+    }
+    for &start in parents.keys() {
+        let mut current = start;
+        for _ in 0..parents.len() {
+            match parents.get(&current) {
+                Some(&parent) if parent == start => return Err(AssemblyError::WouldCreateCycle),
+                Some(&parent) => current = parent,
+                None => break,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Map each ID to a new random one
+fn fresh_ids(ids: impl IntoIterator<Item = Uuid>) -> HashMap<Uuid, Uuid> {
+    ids.into_iter().map(|id| (id, Uuid::new_v4())).collect()
+}
+
+/// `name`, or `name_2`, `name_3`, ... for the first one not `taken`
+fn unique_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|i| format!("{}_{}", name, i))
+        .find(|candidate| !taken(candidate))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unversioned_project_is_migrated() {
+        // Files written before versioning have no version field
+        let current = ron::to_string(&Project::new("Old")).unwrap();
+        let old = current.replace(&format!("version:{},", Project::CURRENT_VERSION), "");
+        assert!(!old.contains("version"));
+
+        let project = Project::load_from_bytes(old.as_bytes()).unwrap();
+        assert_eq!(project.version, Project::CURRENT_VERSION);
+        assert_eq!(project.name, "Old");
+    }
+
+    #[test]
+    fn test_current_project_round_trips() {
+        let mut project = Project::new("Robot");
+        project
+            .materials
+            .push(MaterialDef::new("red", [1.0, 0.0, 0.0, 1.0]));
+
+        let bytes = project.to_bytes().unwrap();
+        let loaded = Project::load_from_bytes(&bytes).unwrap();
+
+        assert_eq!(loaded.version, Project::CURRENT_VERSION);
+        assert_eq!(loaded.name, "Robot");
+        assert_eq!(loaded.materials[0].name, "red");
+        assert_eq!(loaded.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_snap_joint_points() {
+        use glam::{Mat4, Quat};
+
+        let mut base = Part::new("base");
+        base.origin_transform = Mat4::from_translation(Vec3::new(0.0, 0.0, 1.0));
+        let shoulder = base.add_joint_point(crate::part::JointPoint::revolute(
+            "shoulder",
+            base.id,
+            Vec3::new(0.5, 0.0, 0.0),
+            Vec3::Y,
+        ));
+
+        let mut arm = Part::new("arm");
+        arm.origin_transform = Mat4::from_translation(Vec3::new(5.0, 5.0, 5.0));
+        let mut mount = crate::part::JointPoint::new("mount", arm.id, Vec3::new(0.0, 0.0, -0.2));
+        mount.orientation = Quat::from_rotation_z(90f32.to_radians());
+        let mount = arm.add_joint_point(mount);
+
+        let mut project = Project::new("Snap");
+        let (base_id, arm_id) = (project.add_part(base), project.add_part(arm));
+        let joint_id = project.snap_joint_points(shoulder, mount).unwrap();
+
+        // The two joint point frames now coincide
+        let (base, shoulder_point) = project.find_joint_point(shoulder).unwrap();
+        let (arm, mount_point) = project.find_joint_point(mount).unwrap();
+        let parent_frame = base.joint_point_frame(shoulder_point);
+        let child_frame = arm.joint_point_frame(mount_point);
+        assert!(parent_frame.abs_diff_eq(child_frame, 1e-5));
+        assert!(
+            child_frame
+                .w_axis
+                .truncate()
+                .abs_diff_eq(Vec3::new(0.5, 0.0, 1.0), 1e-5)
+        );
+
+        let assembly = &project.assembly;
+        let joint = assembly.get_joint(joint_id).unwrap();
+        assert_eq!(joint.joint_type, JointType::Revolute);
+        assert!(joint.limits.is_some());
+        assert!(joint.axis.abs_diff_eq(Vec3::Y, 1e-5));
+        assert_eq!(joint.parent_joint_point, Some(shoulder));
+        assert_eq!(joint.child_joint_point, Some(mount));
+
+        let base_link = assembly.find_link_by_part(base_id).unwrap().id;
+        let arm_link = assembly.find_link_by_part(arm_id).unwrap();
+        assert_eq!(assembly.get_parent_link_id(arm_link.id), Some(base_link));
+        assert!(arm_link.world_transform.abs_diff_eq(parent_frame, 1e-5));
+
+        // Snapping a part onto itself is rejected
+        assert!(matches!(
+            project.snap_joint_points(mount, mount),
+            Err(AssemblyError::WouldCreateCycle)
+        ));
+    }
+
+    #[test]
+    fn test_create_joint_keeps_child_in_place() {
+        use glam::{Mat4, Quat};
+
+        let mut base = Part::new("base");
+        base.origin_transform = Mat4::from_translation(Vec3::new(0.0, 0.0, 1.0));
+        let mut arm = Part::new("arm");
+        arm.origin_transform =
+            Mat4::from_rotation_translation(Quat::from_rotation_z(0.5), Vec3::new(0.3, 0.0, 1.2));
+        let arm_placement = arm.origin_transform;
+
+        let mut project = Project::new("Joints");
+        let (base_id, arm_id) = (project.add_part(base), project.add_part(arm));
+        let base_link = project.link_for_part(base_id);
+        let arm_link = project.link_for_part(arm_id);
+
+        let joint_id = project
+            .create_joint(base_link, arm_link, JointType::Revolute)
+            .unwrap();
+        let assembly = &project.assembly;
+        let joint = assembly.get_joint(joint_id).unwrap();
+        assert_eq!(joint.joint_type, JointType::Revolute);
+        assert!(joint.limits.is_some());
+        assert_eq!(assembly.get_parent_link_id(arm_link), Some(base_link));
+        assert!(
+            assembly.links[&arm_link]
+                .world_transform
+                .abs_diff_eq(arm_placement, 1e-5)
+        );
+
+        // Reversing the joint would close a loop
+        assert!(matches!(
+            project.create_joint(arm_link, base_link, JointType::Fixed),
+            Err(AssemblyError::WouldCreateCycle)
+        ));
+
+        // A posed parent doesn't move the child either
+        project.assembly.set_joint_position(joint_id, 0.7);
+        let mut tool = Part::new("tool");
+        tool.origin_transform = Mat4::from_translation(Vec3::new(0.5, 0.2, 1.4));
+        let tool_placement = tool.origin_transform;
+        let tool_id = project.add_part(tool);
+        let tool_link = project.link_for_part(tool_id);
+        project
+            .create_joint(arm_link, tool_link, JointType::Fixed)
+            .unwrap();
+        let assembly = &project.assembly;
+        assert!(
+            assembly.links[&tool_link]
+                .world_transform
+                .abs_diff_eq(tool_placement, 1e-5)
+        );
+        // The part's rest placement follows the arm back to its rest pose
+        let rest = project.parts[&tool_id].origin_transform;
+        assert!(
+            assembly
+                .get_world_transform(tool_link)
+                .abs_diff_eq(rest, 1e-5)
+        );
+        assert!(!rest.abs_diff_eq(tool_placement, 1e-3));
+    }
+
+    #[test]
+    fn test_merge_project() {
+        use glam::Mat4;
+
+        // Robot with a single base link
+        let mut robot = Project::new("Robot");
+        robot
+            .materials
+            .push(MaterialDef::new("red", [1.0, 0.0, 0.0, 1.0]));
+        let base = Part::new("base");
+        let base_link = robot.assembly.add_link(Link::from_part(&base));
+        robot.add_part(base);
+
+        // Gripper whose names collide with the robot's
+        let mut gripper = Project::new("Gripper");
+        gripper
+            .materials
+            .push(MaterialDef::new("red", [0.8, 0.0, 0.0, 1.0]));
+        let mut palm = Part::new("base");
+        palm.material_name = Some("red".to_string());
+        let mut finger = Part::new("finger");
+        finger.origin_transform = Mat4::from_translation(Vec3::new(0.0, 0.0, 0.1));
+        let palm_link = gripper.assembly.add_link(Link::from_part(&palm));
+        let finger_link = gripper.assembly.add_link(Link::from_part(&finger));
+        let joint = Joint::fixed(
+            "joint",
+            palm_link,
+            finger_link,
+            Pose::from_position([0.0, 0.0, 0.1]),
+        );
+        gripper
+            .assembly
+            .connect(palm_link, finger_link, joint)
+            .unwrap();
+        gripper.add_part(palm);
+        gripper.add_part(finger);
+        let old_ids: Vec<Uuid> = gripper.parts().keys().copied().collect();
+
+        let added = robot
+            .merge(
+                gripper,
+                Some(base_link),
+                Pose::from_position([0.0, 0.0, 1.0]),
+            )
+            .unwrap();
+        assert_eq!(added.len(), 2);
+        assert!(added.iter().all(|id| !old_ids.contains(id)));
+
+        let assembly = &robot.assembly;
+        assert_eq!(assembly.links.len(), 3);
+        assert_eq!(assembly.joints.len(), 2);
+        assert!(assembly.validate().is_ok());
+
+        // The gripper root hangs off the base link under a suffixed name
+        let palm_link = assembly.find_link_by_name("base_2").unwrap();
+        assert_eq!(assembly.get_parent_link_id(palm_link.id), Some(base_link));
+        assert!(assembly.find_joint_by_name("base_2_mount").is_some());
+
+        // Parts follow the attachment offset
+        let finger = robot
+            .parts_iter()
+            .find(|part| part.name == "finger")
+            .unwrap();
+        assert!(
+            finger
+                .origin_transform
+                .w_axis
+                .truncate()
+                .abs_diff_eq(Vec3::new(0.0, 0.0, 1.1), 1e-5)
+        );
+        let finger_link = assembly.find_link_by_part(finger.id).unwrap();
+        assert!(
+            finger_link
+                .world_transform
+                .abs_diff_eq(finger.origin_transform, 1e-5)
+        );
+
+        // The differing material is renamed along with its users
+        let palm = robot.get_part(palm_link.part_id.unwrap()).unwrap();
+        assert_eq!(palm.material_name.as_deref(), Some("red_2"));
+        assert!(robot.get_material("red_2").is_some());
+
+        // An unknown attachment link is rejected
+        assert!(matches!(
+            robot.merge(Project::new("Empty"), Some(Uuid::new_v4()), Pose::default()),
+            Err(ProjectError::Assembly(AssemblyError::LinkNotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn test_merge_is_atomic() {
+        use crate::types::JointMimic;
+
+        let mut robot = Project::new("Robot");
+        let base = Part::new("base");
+        robot.assembly.add_link(Link::from_part(&base));
+        robot.add_part(base);
+
+        // A linkage whose second finger mimics the first, and also a joint
+        // from elsewhere that the merge cannot know about
+        let mut hand = Project::new("Hand");
+        let [palm, left, right] = ["palm", "left", "right"].map(|name| {
+            let part = Part::new(name);
+            let link = hand.assembly.add_link(Link::from_part(&part));
+            hand.add_part(part);
+            link
+        });
+        let left_joint = hand
+            .assembly
+            .connect(
+                palm,
+                left,
+                Joint::fixed("left", palm, left, Pose::default()),
+            )
+            .unwrap();
+        let mut right_joint = Joint::fixed("right", palm, right, Pose::default());
+        right_joint.mimic = Some(JointMimic::new(left_joint));
+        hand.assembly.connect(palm, right, right_joint).unwrap();
+        hand.assembly.joints.get_mut(&left_joint).unwrap().mimic =
+            Some(JointMimic::new(Uuid::new_v4()));
+
+        // A second parent for a link fails before anything is inserted
+        let mut broken = hand.clone();
+        let extra = Joint::fixed("extra", left, right, Pose::default());
+        broken.assembly.joints.insert(extra.id, extra);
+        assert!(matches!(
+            robot.merge(broken, None, Pose::default()),
+            Err(ProjectError::Assembly(AssemblyError::MultipleParents(_)))
+        ));
+        assert_eq!(robot.parts().len(), 1);
+        assert_eq!(robot.assembly.links.len(), 1);
+
+        robot.merge(hand, None, Pose::default()).unwrap();
+        let assembly = &robot.assembly;
+        let left = assembly.find_joint_by_name("left").unwrap();
+        let right = assembly.find_joint_by_name("right").unwrap();
+        assert_eq!(right.mimic.as_ref().unwrap().joint_id, left.id);
+        assert!(left.mimic.is_none());
+    }
+
+    #[test]
+    fn test_statistics() {
+        use glam::Mat4;
+
+        let mut project = Project::new("Stats");
+        assert_eq!(project.statistics().center_of_mass, None);
+        assert_eq!(project.statistics().bounds, None);
+
+        let mut base = Part::new("base");
+        base.indices = vec![0, 1, 2, 0, 2, 3];
+        base.bbox_min = [-1.0, -1.0, 0.0];
+        base.bbox_max = [1.0, 1.0, 1.0];
+        let mut arm = Part::new("arm");
+        arm.indices = vec![0, 1, 2];
+        arm.bbox_max = [1.0, 1.0, 1.0];
+        arm.origin_transform = Mat4::from_translation(Vec3::new(2.0, 0.0, 0.0));
+
+        let mut base_link = Link::from_part(&base);
+        base_link.inertial.mass = 3.0;
+        let mut arm_link = Link::from_part(&arm);
+        arm_link.inertial.mass = 1.0;
+        arm_link.world_transform = arm.origin_transform;
+        arm_link.inertial.origin = Pose::new([0.0, 0.0, 4.0], [0.0; 3]);
+        project.add_part(base);
+        let arm_id = project.add_part(arm);
+        project.assembly.add_link(base_link);
+        project.assembly.add_link(arm_link);
+
+        let stats = project.statistics();
+        assert_eq!((stats.link_count, stats.joint_count), (2, 0));
+        assert_eq!((stats.part_count, stats.triangle_count), (2, 3));
+        assert_eq!(stats.total_mass, 4.0);
+        let com = stats.center_of_mass.unwrap();
+        assert!(com.abs_diff_eq(Vec3::new(0.5, 0.0, 1.0), 1e-6));
+        let (min, max) = stats.bounds.unwrap();
+        assert_eq!(min, Vec3::new(-1.0, -1.0, 0.0));
+        assert_eq!(max, Vec3::new(3.0, 1.0, 1.0));
+
+        // Cached part statistics follow edits to the parts
+        project.get_part_mut(arm_id).unwrap().origin_transform =
+            Mat4::from_translation(Vec3::new(4.0, 0.0, 0.0));
+        assert_eq!(
+            project.statistics().bounds.unwrap().1,
+            Vec3::new(5.0, 1.0, 1.0)
+        );
+        project.remove_part(arm_id);
+        assert_eq!(project.statistics().triangle_count, 2);
+    }
+
+    #[test]
+    fn test_recompute_mesh_inertias() {
+        let mut project = Project::new("Inertia");
+        let (vertices, _, indices) = crate::primitive::generate_box_mesh([0.2, 0.2, 0.4]);
+        let mut closed = Part::new("closed");
+        closed.vertices = vertices.iter().map(|v| [v[0], v[1], v[2] + 0.2]).collect();
+        closed.indices = indices.clone();
+        let mut open = Part::new("open");
+        open.vertices = vertices;
+        open.indices = indices[..30].to_vec();
+
+        let mut closed_link = Link::from_part(&closed);
+        closed_link.inertial.mass = 3.0;
+        let open_link = Link::from_part(&open);
+        let open_inertia = open_link.inertial.inertia.ixx;
+        project.add_part(closed);
+        project.add_part(open);
+        let closed_id = project.assembly.add_link(closed_link);
+        let open_id = project.assembly.add_link(open_link);
+        project.assembly.add_link(Link::empty("empty"));
+
+        let report = project.recompute_mesh_inertias();
+        assert_eq!(report.updated, ["closed"]);
+        assert_eq!(report.skipped, ["open"]);
+
+        let inertial = &project.assembly.links[&closed_id].inertial;
+        assert!(Vec3::from(inertial.origin.xyz).abs_diff_eq(Vec3::new(0.0, 0.0, 0.2), 1e-5));
+        assert!((inertial.inertia.ixx - 3.0 * (0.04 + 0.16) / 12.0).abs() < 1e-5);
+        assert_eq!(
+            project.assembly.links[&open_id].inertial.inertia.ixx,
+            open_inertia
+        );
+
+        // A part turned a quarter about X and lifted puts its long side
+        // along Y in the link frame
+        let mut turned = Part::new("turned");
+        let (vertices, _, indices) = crate::primitive::generate_box_mesh([0.2, 0.2, 0.4]);
+        turned.vertices = vertices;
+        turned.indices = indices;
+        turned.origin_transform = Mat4::from_rotation_translation(
+            glam::Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+            Vec3::new(0.0, 0.0, 0.5),
+        );
+        let mut turned_link = Link::from_part(&turned);
+        turned_link.inertial.mass = 3.0;
+        turned_link.visuals[0].origin = Pose::from_position([0.1, 0.0, 0.0]);
+        project.add_part(turned);
+        let turned_id = project.assembly.add_link(turned_link);
+
+        project.recompute_mesh_inertias();
+        let inertial = &project.assembly.links[&turned_id].inertial;
+        assert!(Vec3::from(inertial.origin.xyz).abs_diff_eq(Vec3::new(0.1, 0.0, 0.5), 1e-5));
+        assert!((inertial.inertia.ixx - 3.0 * (0.16 + 0.04) / 12.0).abs() < 1e-5);
+        assert!((inertial.inertia.iyy - 3.0 * (0.04 + 0.04) / 12.0).abs() < 1e-5);
+        assert!((inertial.inertia.izz - 3.0 * (0.04 + 0.16) / 12.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_material_library() {
+        let mut project = Project::new("Materials");
+        project
+            .add_material(MaterialDef::new("red", [1.0, 0.0, 0.0, 1.0]))
+            .unwrap();
+        assert!(matches!(
+            project.add_material(MaterialDef::new("red", [0.0; 4])),
+            Err(ProjectError::DuplicateMaterial(_))
+        ));
+
+        let base = Part::new("base");
+        let arm = Part::new("arm");
+        let link = Link::from_part(&base);
+        let (base_id, arm_id) = (project.add_part(base), project.add_part(arm));
+        let link_id = project.assembly.add_link(link);
+        project
+            .assign_material(&[base_id, arm_id], Some("red"))
+            .unwrap();
+        let visual = &project.assembly.get_link(link_id).unwrap().visuals[0];
+        assert_eq!(visual.material_name.as_deref(), Some("red"));
+        assert_eq!(visual.color, [1.0, 0.0, 0.0, 1.0]);
+
+        // Editing the material updates every part and visual using it
+        let dark_red = MaterialDef::new("dark_red", [0.5, 0.0, 0.0, 1.0]);
+        let mut changed = project.update_material("red", dark_red).unwrap();
+        changed.sort();
+        let mut expected = vec![base_id, arm_id];
+        expected.sort();
+        assert_eq!(changed, expected);
+        let arm = project.get_part(arm_id).unwrap();
+        assert_eq!(arm.material_name.as_deref(), Some("dark_red"));
+        assert_eq!(arm.color, [0.5, 0.0, 0.0, 1.0]);
+        let visual = &project.assembly.get_link(link_id).unwrap().visuals[0];
+        assert_eq!(visual.material_name.as_deref(), Some("dark_red"));
+
+        // Removing it keeps the color but drops the reference
+        project.remove_material("dark_red").unwrap();
+        let base = project.get_part(base_id).unwrap();
+        assert_eq!(base.material_name, None);
+        assert_eq!(base.color, [0.5, 0.0, 0.0, 1.0]);
+        assert!(matches!(
+            project.assign_material(&[base_id], Some("dark_red")),
+            Err(ProjectError::MaterialNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_newer_project_is_rejected() {
+        let mut project = Project::new("Future");
+        project.version = Project::CURRENT_VERSION + 1;
+        let bytes = project.to_bytes().unwrap();
+        assert!(matches!(
+            Project::load_from_bytes(&bytes),
+            Err(ProjectError::UnsupportedVersion { .. })
+        ));
+    }
 }
@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Geometry type for visual/collision elements
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GeometryType {
     /// Mesh geometry with optional path and scale
     Mesh {
@@ -53,8 +53,7 @@ impl GeometryType {
                 format!("<sphere radius=\"{}\"/>", radius)
             }
             GeometryType::Capsule { radius, length } => {
-                // Capsule is not standard URDF, approximate as cylinder
-                format!("<cylinder radius=\"{}\" length=\"{}\"/>", radius, length)
+                format!("<capsule radius=\"{}\" length=\"{}\"/>", radius, length)
             }
         }
     }
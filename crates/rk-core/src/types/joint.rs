@@ -13,17 +13,75 @@ pub enum JointType {
     Prismatic,
     Floating,
     Planar,
+    Spherical,
+}
+
+/// One degree of freedom of a multi-DOF joint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JointDof {
+    X,
+    Y,
+    Z,
+    Roll,
+    Pitch,
+    Yaw,
+}
+
+impl JointDof {
+    /// Index into a multi-DOF position array
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Check if this DOF is a rotation (radians) rather than a translation
+    /// (meters)
+    pub fn is_angular(self) -> bool {
+        matches!(self, JointDof::Roll | JointDof::Pitch | JointDof::Yaw)
+    }
+
+    /// Get display name
+    pub fn name(self) -> &'static str {
+        match self {
+            JointDof::X => "X",
+            JointDof::Y => "Y",
+            JointDof::Z => "Z",
+            JointDof::Roll => "Roll",
+            JointDof::Pitch => "Pitch",
+            JointDof::Yaw => "Yaw",
+        }
+    }
 }
 
 impl JointType {
-    /// Check if this joint type has an axis
+    /// Check if this joint type has an axis (the plane normal for planar
+    /// joints)
     pub fn has_axis(&self) -> bool {
         matches!(
             self,
-            JointType::Revolute | JointType::Continuous | JointType::Prismatic
+            JointType::Revolute | JointType::Continuous | JointType::Prismatic | JointType::Planar
         )
     }
 
+    /// Degrees of freedom of a multi-DOF joint, empty for the others
+    ///
+    /// Planar X and Y move in the plane normal to the axis and Yaw turns
+    /// about it.
+    pub fn multi_dofs(&self) -> &'static [JointDof] {
+        match self {
+            JointType::Planar => &[JointDof::X, JointDof::Y, JointDof::Yaw],
+            JointType::Floating => &[
+                JointDof::X,
+                JointDof::Y,
+                JointDof::Z,
+                JointDof::Roll,
+                JointDof::Pitch,
+                JointDof::Yaw,
+            ],
+            JointType::Spherical => &[JointDof::Roll, JointDof::Pitch, JointDof::Yaw],
+            _ => &[],
+        }
+    }
+
     /// Check if this joint type has limits
     pub fn has_limits(&self) -> bool {
         matches!(self, JointType::Revolute | JointType::Prismatic)
@@ -38,6 +96,7 @@ impl JointType {
             JointType::Prismatic => "Prismatic",
             JointType::Floating => "Floating",
             JointType::Planar => "Planar",
+            JointType::Spherical => "Spherical",
         }
     }
 
@@ -50,10 +109,27 @@ impl JointType {
             JointType::Prismatic,
             JointType::Floating,
             JointType::Planar,
+            JointType::Spherical,
         ]
     }
 }
 
+/// Wrap an angle into (-π, π]
+pub fn normalize_angle(angle: f32) -> f32 {
+    use std::f32::consts::{PI, TAU};
+    let wrapped = (angle + PI).rem_euclid(TAU) - PI;
+    if wrapped <= -PI {
+        wrapped + TAU
+    } else {
+        wrapped
+    }
+}
+
+/// Shortest signed rotation from one angle to another, in (-π, π]
+pub fn angle_difference(from: f32, to: f32) -> f32 {
+    normalize_angle(to - from)
+}
+
 impl From<&urdf_rs::JointType> for JointType {
     fn from(urdf_type: &urdf_rs::JointType) -> Self {
         match urdf_type {
@@ -63,7 +139,7 @@ impl From<&urdf_rs::JointType> for JointType {
             urdf_rs::JointType::Prismatic => JointType::Prismatic,
             urdf_rs::JointType::Floating => JointType::Floating,
             urdf_rs::JointType::Planar => JointType::Planar,
-            urdf_rs::JointType::Spherical => JointType::Floating, // Approximate as floating
+            urdf_rs::JointType::Spherical => JointType::Spherical,
         }
     }
 }
@@ -119,12 +195,24 @@ impl JointLimits {
 }
 
 /// Joint dynamics
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct JointDynamics {
+    /// Viscous damping (N·m·s/rad, or N·s/m for prismatic joints)
     pub damping: f32,
+    /// Static friction (N·m, or N for prismatic joints)
     pub friction: f32,
 }
 
+impl JointDynamics {
+    /// Light damping so simulated joints settle, without friction
+    pub fn default_damped() -> Self {
+        Self {
+            damping: 0.1,
+            friction: 0.0,
+        }
+    }
+}
+
 impl Default for JointDynamics {
     fn default() -> Self {
         Self {
@@ -134,6 +222,83 @@ impl Default for JointDynamics {
     }
 }
 
+/// Joint safety controller (URDF `<safety_controller>`)
+///
+/// Soft limits sit inside the hard `<limit>` range; controllers such as
+/// ros_control push the joint back once it crosses them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JointSafetyController {
+    /// Lower soft limit (rad or m)
+    pub soft_lower_limit: f32,
+    /// Upper soft limit (rad or m)
+    pub soft_upper_limit: f32,
+    /// Gain relating position error past a soft limit to the velocity bound
+    pub k_position: f32,
+    /// Gain relating velocity to the effort bound
+    pub k_velocity: f32,
+}
+
+impl JointSafetyController {
+    /// Create a safety controller with soft limits matching the given hard limits
+    pub fn from_limits(limits: &JointLimits) -> Self {
+        Self {
+            soft_lower_limit: limits.lower,
+            soft_upper_limit: limits.upper,
+            k_position: 100.0,
+            k_velocity: 10.0,
+        }
+    }
+}
+
+impl From<&urdf_rs::SafetyController> for JointSafetyController {
+    fn from(sc: &urdf_rs::SafetyController) -> Self {
+        Self {
+            soft_lower_limit: sc.soft_lower_limit as f32,
+            soft_upper_limit: sc.soft_upper_limit as f32,
+            k_position: sc.k_position as f32,
+            k_velocity: sc.k_velocity as f32,
+        }
+    }
+}
+
+/// ros_control transmission for an actuated joint (URDF `<transmission>`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JointTransmission {
+    /// Transmission element name
+    pub name: String,
+    /// Transmission plugin type
+    pub transmission_type: String,
+    /// Hardware interface the joint is commanded through
+    pub hardware_interface: String,
+    /// Actuator name
+    pub actuator_name: String,
+    /// Gear ratio between actuator and joint
+    pub mechanical_reduction: f32,
+}
+
+impl JointTransmission {
+    /// Single actuator driving a single joint
+    pub const SIMPLE_TRANSMISSION: &'static str = "transmission_interface/SimpleTransmission";
+
+    /// Standard ros_control joint interfaces
+    pub const HARDWARE_INTERFACES: [&'static str; 3] = [
+        "hardware_interface/EffortJointInterface",
+        "hardware_interface/VelocityJointInterface",
+        "hardware_interface/PositionJointInterface",
+    ];
+
+    /// Create a simple effort-controlled transmission for a joint
+    pub fn simple(joint_name: &str) -> Self {
+        Self {
+            name: format!("{}_trans", joint_name),
+            transmission_type: Self::SIMPLE_TRANSMISSION.to_string(),
+            hardware_interface: Self::HARDWARE_INTERFACES[0].to_string(),
+            actuator_name: format!("{}_motor", joint_name),
+            mechanical_reduction: 1.0,
+        }
+    }
+}
+
 /// Joint mimic configuration
 /// Makes this joint follow another joint's position: value = multiplier * other_joint + offset
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -170,3 +335,19 @@ impl JointMimic {
         self.multiplier * source_position + self.offset
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_angle() {
+        assert!((normalize_angle(3.0 * PI / 2.0) + PI / 2.0).abs() < 1e-5);
+        assert!((normalize_angle(-3.0 * PI / 2.0) - PI / 2.0).abs() < 1e-5);
+        assert_eq!(normalize_angle(0.5), 0.5);
+        assert_eq!(normalize_angle(-PI), PI);
+        assert!((angle_difference(PI - 0.1, -PI + 0.1) - 0.2).abs() < 1e-5);
+    }
+}
@@ -19,6 +19,13 @@ impl Pose {
         Self { xyz, rpy: [0.0; 3] }
     }
 
+    /// Pose of a rigid transform (scale is dropped)
+    pub fn from_mat4(transform: Mat4) -> Self {
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let (roll, pitch, yaw) = rotation.to_euler(glam::EulerRot::XYZ);
+        Self::new(translation.to_array(), [roll, pitch, yaw])
+    }
+
     pub fn to_mat4(&self) -> Mat4 {
         let translation = Vec3::from(self.xyz);
         let rotation = Quat::from_euler(glam::EulerRot::XYZ, self.rpy[0], self.rpy[1], self.rpy[2]);
@@ -1,9 +1,15 @@
 //! Assembly-related action handlers
 
+use std::collections::HashSet;
+use std::path::PathBuf;
+
 use uuid::Uuid;
 
 use glam::Vec3;
-use rk_core::{CollisionElement, GeometryType, Joint, JointLimits, JointType, Link, Pose};
+use rk_core::{
+    CollisionElement, CollisionSource, GeometryType, Joint, JointDof, JointDynamics, JointLimits,
+    JointSafetyController, JointTransmission, JointType, Link, Pose,
+};
 
 use crate::state::{AppAction, AppState};
 
@@ -14,15 +20,35 @@ pub fn handle_assembly_action(action: AppAction, ctx: &ActionContext) {
     match action {
         AppAction::ConnectParts { parent, child } => handle_connect_parts(parent, child, ctx),
         AppAction::DisconnectPart { child } => handle_disconnect_part(child, ctx),
+        AppAction::SnapJointPoints {
+            parent_point,
+            child_point,
+        } => handle_snap_joint_points(parent_point, child_point, ctx),
+        AppAction::CreateJoint {
+            parent,
+            child,
+            joint_type,
+        } => handle_create_joint(parent, child, joint_type, ctx),
         AppAction::UpdateJointPosition { joint_id, position } => {
             handle_update_joint_position(joint_id, position, ctx)
         }
+        AppAction::UpdateJointDof {
+            joint_id,
+            dof,
+            value,
+        } => handle_update_joint_dof(joint_id, dof, value, ctx),
         AppAction::ResetJointPosition { joint_id } => handle_reset_joint_position(joint_id, ctx),
         AppAction::ResetAllJointPositions => handle_reset_all_joint_positions(ctx),
+        AppAction::GoHome => handle_go_home(ctx),
+        AppAction::SetHomePose => handle_set_home_pose(ctx),
+        AppAction::LoadJointState { path } => handle_load_joint_state(path, ctx),
         AppAction::SelectCollision(selection) => handle_select_collision(selection, ctx),
         AppAction::AddCollision { link_id, geometry } => {
             handle_add_collision(link_id, geometry, ctx)
         }
+        AppAction::CopyVisualToCollision { link_id, source } => {
+            handle_copy_visual_to_collision(link_id, source, ctx)
+        }
         AppAction::RemoveCollision { link_id, index } => {
             handle_remove_collision(link_id, index, ctx)
         }
@@ -44,12 +70,38 @@ pub fn handle_assembly_action(action: AppAction, ctx: &ActionContext) {
         AppAction::UpdateJointOrigin { joint_id, origin } => {
             handle_update_joint_origin(joint_id, origin, ctx)
         }
+        AppAction::SetJointOriginPick(joint_id) => {
+            ctx.app_state.lock().joint_origin_pick = joint_id;
+        }
+        AppAction::SetJointOriginDrag(joint_id) => {
+            ctx.app_state.lock().joint_origin_drag = joint_id;
+        }
+        AppAction::SnapJointOrigin { joint_id, position } => {
+            handle_snap_joint_origin(joint_id, position, ctx)
+        }
+        AppAction::DragJointOrigin { joint_id, origin } => {
+            set_joint_origin(joint_id, origin, ctx);
+        }
         AppAction::UpdateJointAxis { joint_id, axis } => {
             handle_update_joint_axis(joint_id, axis, ctx)
         }
         AppAction::UpdateJointLimits { joint_id, limits } => {
             handle_update_joint_limits(joint_id, limits, ctx)
         }
+        AppAction::UpdateJointDefault { joint_id, value } => {
+            handle_update_joint_default(joint_id, value, ctx)
+        }
+        AppAction::UpdateJointDynamics { joint_id, dynamics } => {
+            handle_update_joint_dynamics(joint_id, dynamics, ctx)
+        }
+        AppAction::UpdateJointSafetyController {
+            joint_id,
+            safety_controller,
+        } => handle_update_joint_safety_controller(joint_id, safety_controller, ctx),
+        AppAction::UpdateJointTransmission {
+            joint_id,
+            transmission,
+        } => handle_update_joint_transmission(joint_id, transmission, ctx),
         _ => {}
     }
 }
@@ -58,67 +110,68 @@ fn handle_connect_parts(parent: Uuid, child: Uuid, ctx: &ActionContext) {
     let mut state = ctx.app_state.lock();
 
     // Get or create links
-    let parent_link_id = find_or_create_link(&mut state, parent);
-    let child_link_id = find_or_create_link(&mut state, child);
-
-    if let (Some(parent_link_id), Some(child_link_id)) = (parent_link_id, child_link_id) {
-        // Disconnect child from existing parent first
-        if state.project.assembly.parent.contains_key(&child_link_id)
-            && let Err(e) = state.project.assembly.disconnect(child_link_id)
-        {
-            tracing::warn!("Failed to disconnect existing parent: {}", e);
+    let (Some(parent_link_id), Some(child_link_id)) = (
+        find_or_create_link(&mut state, parent),
+        find_or_create_link(&mut state, child),
+    ) else {
+        return;
+    };
+
+    // Fixed joint where the child currently is
+    match state
+        .project
+        .create_joint(parent_link_id, child_link_id, JointType::Fixed)
+    {
+        Ok(joint_id) => {
+            tracing::info!("Connected {} to {} via joint {}", parent, child, joint_id);
+            state.modified = true;
+
+            // Update renderer transforms
+            sync_renderer_transforms(&state, ctx);
+        }
+        Err(e) => {
+            tracing::error!("Failed to connect parts: {}", e);
         }
+    }
+}
 
-        // Get names for joint
-        let parent_name = state
-            .project
-            .assembly
-            .links
-            .get(&parent_link_id)
-            .map(|l| l.name.clone())
-            .unwrap_or_default();
-        let child_name = state
-            .project
-            .assembly
-            .links
-            .get(&child_link_id)
-            .map(|l| l.name.clone())
-            .unwrap_or_default();
-
-        // Create fixed joint
-        let joint = Joint::fixed(
-            format!("{}_to_{}", parent_name, child_name),
-            parent_link_id,
-            child_link_id,
-            Pose::default(),
-        );
+fn handle_snap_joint_points(parent_point: Uuid, child_point: Uuid, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
 
-        match state
-            .project
-            .assembly
-            .connect(parent_link_id, child_link_id, joint)
-        {
-            Ok(joint_id) => {
-                tracing::info!(
-                    "Connected {} to {} via joint {}",
-                    parent_name,
-                    child_name,
-                    joint_id
-                );
-                state.modified = true;
+    match state.project.snap_joint_points(parent_point, child_point) {
+        Ok(joint_id) => {
+            tracing::info!("Snapped joint points into joint {}", joint_id);
+            state.modified = true;
+            sync_renderer_transforms(&state, ctx);
+        }
+        Err(e) => {
+            tracing::error!("Failed to snap joint points: {}", e);
+        }
+    }
+}
 
-                // Update world transforms after connection
-                state
-                    .project
-                    .assembly
-                    .update_world_transforms_with_current_positions();
+fn handle_create_joint(parent: Uuid, child: Uuid, joint_type: JointType, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
 
-                // Update renderer transforms
-                sync_renderer_transforms(&state, ctx);
-            }
-            Err(e) => {
-                tracing::error!("Failed to connect parts: {}", e);
-            }
+    let (Some(parent_link_id), Some(child_link_id)) = (
+        find_or_create_link(&mut state, parent),
+        find_or_create_link(&mut state, child),
+    ) else {
+        return;
+    };
+
+    match state
+        .project
+        .create_joint(parent_link_id, child_link_id, joint_type)
+    {
+        Ok(joint_id) => {
+            tracing::info!("Created joint {}", joint_id);
+            state.modified = true;
+            state.notify_success(format!("Created {} joint", joint_type.display_name()));
+            sync_renderer_transforms(&state, ctx);
+        }
+        Err(e) => {
+            state.notify_error(format!("Failed to create joint: {}", e));
         }
     }
 }
@@ -182,16 +235,13 @@ fn find_or_create_link(state: &mut AppState, part_id: Uuid) -> Option<Uuid> {
 fn handle_update_joint_position(joint_id: Uuid, position: f32, ctx: &ActionContext) {
     let mut state = ctx.app_state.lock();
 
-    // Clamp to limits if applicable
-    let clamped_position = if let Some(joint) = state.project.assembly.joints.get(&joint_id) {
-        if let Some(limits) = &joint.limits {
-            position.clamp(limits.lower, limits.upper)
-        } else {
-            position
-        }
-    } else {
-        position
-    };
+    // Clamp to limits, or wrap continuous joints
+    let clamped_position = state
+        .project
+        .assembly
+        .joints
+        .get(&joint_id)
+        .map_or(position, |joint| joint.constrain_position(position));
 
     state
         .project
@@ -208,6 +258,20 @@ fn handle_update_joint_position(joint_id: Uuid, position: f32, ctx: &ActionConte
     sync_renderer_transforms(&state, ctx);
 }
 
+fn handle_update_joint_dof(joint_id: Uuid, dof: JointDof, value: f32, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    state.project.assembly.set_joint_dof(joint_id, dof, value);
+
+    // Update world transforms with new joint positions
+    state
+        .project
+        .assembly
+        .update_world_transforms_with_current_positions();
+
+    // Update renderer transforms
+    sync_renderer_transforms(&state, ctx);
+}
+
 fn handle_reset_joint_position(joint_id: Uuid, ctx: &ActionContext) {
     let mut state = ctx.app_state.lock();
     state.project.assembly.reset_joint_position(joint_id);
@@ -233,8 +297,46 @@ fn handle_reset_all_joint_positions(ctx: &ActionContext) {
     sync_renderer_transforms(&state, ctx);
 }
 
+fn handle_go_home(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    state.project.assembly.go_home();
+
+    // Update renderer transforms
+    sync_renderer_transforms(&state, ctx);
+}
+
+fn handle_set_home_pose(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    state.project.assembly.set_home_to_current();
+    state.modified = true;
+    state.notify_success("Home pose set to the current joint positions");
+}
+
+fn handle_load_joint_state(path: PathBuf, ctx: &ActionContext) {
+    let positions = match rk_core::load_joint_state(&path) {
+        Ok(positions) => positions,
+        Err(e) => {
+            tracing::error!("Failed to load joint state {:?}: {}", path, e);
+            return;
+        }
+    };
+
+    let mut state = ctx.app_state.lock();
+    let unknown = state.project.assembly.apply_joint_state(&positions);
+    for name in &unknown {
+        tracing::warn!("Joint state references unknown joint '{}'", name);
+    }
+    tracing::info!(
+        "Applied {} joint positions from {:?}",
+        positions.len() - unknown.len(),
+        path
+    );
+
+    sync_renderer_transforms(&state, ctx);
+}
+
 /// Sync renderer transforms with assembly world transforms
-fn sync_renderer_transforms(state: &AppState, ctx: &ActionContext) {
+pub(super) fn sync_renderer_transforms(state: &AppState, ctx: &ActionContext) {
     use glam::{Mat4, Quat, Vec3};
 
     if let Some(viewport_state) = ctx.viewport_state {
@@ -247,8 +349,10 @@ fn sync_renderer_transforms(state: &AppState, ctx: &ActionContext) {
                 && let Some(part) = state.get_part(part_id)
             {
                 // Collect ancestor joints from this link to root
-                // Store: (original_pivot, original_axis, joint_type, joint_value)
-                let mut joint_chain: Vec<(Vec3, Vec3, rk_core::JointType, f32)> = Vec::new();
+                // Store: (original_pivot, original_axis, joint_type, joint_value, dof_values,
+                // rest orientation of the joint frame)
+                let mut joint_chain: Vec<(Vec3, Vec3, rk_core::JointType, f32, [f32; 6], Quat)> =
+                    Vec::new();
                 let mut current_link_id = *link_id;
 
                 while let Some((joint_id, parent_link_id)) =
@@ -260,7 +364,27 @@ fn sync_renderer_transforms(state: &AppState, ctx: &ActionContext) {
                         // Get parent part's center as original joint pivot point
                         let original_pivot = get_part_center(state, *parent_link_id);
 
-                        joint_chain.push((original_pivot, joint.axis, joint.joint_type, joint_pos));
+                        let dofs = state
+                            .project
+                            .assembly
+                            .joint_dof_positions
+                            .get(joint_id)
+                            .copied()
+                            .unwrap_or_default();
+                        let (_, rest_frame, _) = state
+                            .project
+                            .assembly
+                            .get_world_transform(current_link_id)
+                            .to_scale_rotation_translation();
+
+                        joint_chain.push((
+                            original_pivot,
+                            joint.axis,
+                            joint.joint_type,
+                            joint_pos,
+                            dofs,
+                            rest_frame,
+                        ));
                     }
                     current_link_id = *parent_link_id;
                 }
@@ -273,17 +397,30 @@ fn sync_renderer_transforms(state: &AppState, ctx: &ActionContext) {
                 let mut accumulated_rotation = Quat::IDENTITY;
 
                 // Apply each joint's rotation around its transformed pivot with transformed axis
-                for (original_pivot, original_axis, joint_type, joint_value) in &joint_chain {
+                for (original_pivot, original_axis, joint_type, joint_value, dofs, rest_frame) in
+                    &joint_chain
+                {
                     // Transform the pivot and axis by all previous joint transforms
                     let current_pivot = accumulated_transform.transform_point3(*original_pivot);
                     let current_axis = accumulated_rotation * *original_axis;
 
+                    // Multi-DOF joints move along and about the axes of their
+                    // own frame, as posed by the joints above
+                    let frame = Mat4::from_quat(accumulated_rotation * *rest_frame);
+                    let multi_dof = frame
+                        * rk_core::Assembly::compute_multi_dof_transform(
+                            joint_type,
+                            *original_axis,
+                            dofs,
+                        )
+                        * frame.inverse();
+
                     // Compute joint rotation with transformed axis
                     let joint_rotation = rk_core::Assembly::compute_joint_transform(
                         joint_type,
                         current_axis,
                         *joint_value,
-                    );
+                    ) * multi_dof;
 
                     // Extract rotation part for axis transformation
                     let (_, rot, _) = joint_rotation.to_scale_rotation_translation();
@@ -303,6 +440,16 @@ fn sync_renderer_transforms(state: &AppState, ctx: &ActionContext) {
                 vp.update_part_transform(part_id, result);
             }
         }
+
+        // Highlight links that overlap at the current pose
+        let assembly = &state.project.assembly;
+        let colliding: HashSet<Uuid> = assembly
+            .self_collisions(state.project.parts())
+            .into_iter()
+            .flat_map(|(a, b)| [a, b])
+            .filter_map(|link_id| assembly.links.get(&link_id)?.part_id)
+            .collect();
+        vp.set_colliding_parts(&colliding);
     }
 }
 
@@ -335,9 +482,8 @@ fn handle_add_collision(link_id: Uuid, geometry: GeometryType, ctx: &ActionConte
 
     if let Some(link) = state.project.assembly.get_link_mut(link_id) {
         let collision = CollisionElement {
-            name: None,
-            origin: Pose::default(),
             geometry,
+            ..CollisionElement::default()
         };
         link.collisions.push(collision);
         state.modified = true;
@@ -347,6 +493,37 @@ fn handle_add_collision(link_id: Uuid, geometry: GeometryType, ctx: &ActionConte
     }
 }
 
+fn handle_copy_visual_to_collision(link_id: Uuid, source: CollisionSource, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let Some(mut link) = state.project.assembly.get_link(link_id).cloned() else {
+        tracing::warn!("Link {} not found for copying visual to collision", link_id);
+        return;
+    };
+    let Some(part) = link.part_id.and_then(|id| state.get_part(id)) else {
+        return;
+    };
+    if !link.copy_visual_to_collision(part, source) {
+        let message = format!("Cannot build a collision from the mesh of {}", link.name);
+        state.notify_error(message);
+        return;
+    }
+    let geometry = match source {
+        CollisionSource::Exact => "its visual mesh",
+        CollisionSource::ConvexHull => "the convex hull of its visual mesh",
+        CollisionSource::Simplified => "a simplified copy of its visual mesh",
+    };
+    let mut message = format!("Collision of {} set to {}", link.name, geometry);
+    if let Some(mesh) = &link.collisions[0].mesh {
+        message.push_str(&format!(" ({} triangles)", mesh.triangle_count()));
+    }
+
+    tracing::info!("{}", message);
+    state.project.assembly.links.insert(link_id, link);
+    state.selected_collision = Some((link_id, 0));
+    state.modified = true;
+    state.notify_success(message);
+}
+
 fn handle_remove_collision(link_id: Uuid, index: usize, ctx: &ActionContext) {
     let mut state = ctx.app_state.lock();
 
@@ -402,6 +579,10 @@ fn handle_update_collision_geometry(
 
     if let Some(link) = state.project.assembly.get_link_mut(link_id) {
         if let Some(collision) = link.collisions.get_mut(index) {
+            // A primitive replaces an imported collision mesh
+            if !geometry.is_mesh() {
+                collision.mesh = None;
+            }
             collision.geometry = geometry;
             state.modified = true;
             tracing::debug!("Updated collision {} geometry for link {}", index, link_id);
@@ -439,6 +620,7 @@ fn handle_update_joint_type(joint_id: Uuid, joint_type: JointType, ctx: &ActionC
         if !joint_type.has_limits() {
             joint.limits = None;
         }
+        log_joint_issues(joint);
 
         state.modified = true;
         tracing::info!(
@@ -448,6 +630,12 @@ fn handle_update_joint_type(joint_id: Uuid, joint_type: JointType, ctx: &ActionC
             joint_type
         );
 
+        // Keep the current position valid for the new type
+        let assembly = &mut state.project.assembly;
+        let position = assembly.get_joint_position(joint_id);
+        let constrained = assembly.joints[&joint_id].constrain_position(position);
+        assembly.set_joint_position(joint_id, constrained);
+
         // Update world transforms
         state
             .project
@@ -462,24 +650,51 @@ fn handle_update_joint_type(joint_id: Uuid, joint_type: JointType, ctx: &ActionC
 }
 
 fn handle_update_joint_origin(joint_id: Uuid, origin: Pose, ctx: &ActionContext) {
+    if set_joint_origin(joint_id, origin, ctx) {
+        ctx.app_state.lock().modified = true;
+    }
+}
+
+/// Move a joint origin and re-pose its children, without marking the project modified
+///
+/// Returns false if the joint does not exist.
+pub(super) fn set_joint_origin(joint_id: Uuid, origin: Pose, ctx: &ActionContext) -> bool {
     let mut state = ctx.app_state.lock();
 
-    if let Some(joint) = state.project.assembly.get_joint_mut(joint_id) {
-        joint.origin = origin;
-        state.modified = true;
-        tracing::debug!("Updated joint {} origin", joint_id);
+    let Some(joint) = state.project.assembly.get_joint_mut(joint_id) else {
+        tracing::warn!("Joint {} not found for updating origin", joint_id);
+        return false;
+    };
+    joint.origin = origin;
+    tracing::debug!("Updated joint {} origin", joint_id);
 
-        // Update world transforms
-        state
-            .project
-            .assembly
-            .update_world_transforms_with_current_positions();
+    // Update world transforms
+    state
+        .project
+        .assembly
+        .update_world_transforms_with_current_positions();
 
-        // Update renderer transforms
-        sync_renderer_transforms(&state, ctx);
-    } else {
-        tracing::warn!("Joint {} not found for updating origin", joint_id);
-    }
+    // Update renderer transforms
+    sync_renderer_transforms(&state, ctx);
+    true
+}
+
+fn handle_snap_joint_origin(joint_id: Uuid, position: Vec3, ctx: &ActionContext) {
+    let origin = {
+        let state = ctx.app_state.lock();
+        let assembly = &state.project.assembly;
+        let Some(joint) = assembly.get_joint(joint_id) else {
+            tracing::warn!("Joint {} not found for snapping origin", joint_id);
+            return;
+        };
+        let Some(parent) = assembly.get_link(joint.parent_link) else {
+            return;
+        };
+        // The origin is expressed in the parent link's frame
+        let local = parent.world_transform.inverse().transform_point3(position);
+        Pose::new(local.to_array(), joint.origin.rpy)
+    };
+    handle_update_joint_origin(joint_id, origin, ctx);
 }
 
 fn handle_update_joint_axis(joint_id: Uuid, axis: Vec3, ctx: &ActionContext) {
@@ -516,6 +731,7 @@ fn handle_update_joint_limits(joint_id: Uuid, limits: Option<JointLimits>, ctx:
         if let Some(joint) = state.project.assembly.get_joint_mut(joint_id) {
             joint.limits = limits;
             tracing::debug!("Updated joint {} limits", joint_id);
+            log_joint_issues(joint);
             true
         } else {
             tracing::warn!("Joint {} not found for updating limits", joint_id);
@@ -530,20 +746,88 @@ fn handle_update_joint_limits(joint_id: Uuid, limits: Option<JointLimits>, ctx:
     state.modified = true;
 
     // Clamp current joint position to new limits if necessary
-    if let Some(limits) = limits {
-        let current_pos = state.project.assembly.get_joint_position(joint_id);
-        let clamped = current_pos.clamp(limits.lower, limits.upper);
-        if clamped != current_pos {
-            state.project.assembly.set_joint_position(joint_id, clamped);
-
-            // Update world transforms
-            state
-                .project
-                .assembly
-                .update_world_transforms_with_current_positions();
+    let current_pos = state.project.assembly.get_joint_position(joint_id);
+    let clamped = state.project.assembly.joints[&joint_id].constrain_position(current_pos);
+    if clamped != current_pos {
+        state.project.assembly.set_joint_position(joint_id, clamped);
 
-            // Update renderer transforms
-            sync_renderer_transforms(&state, ctx);
-        }
+        // Update world transforms
+        state
+            .project
+            .assembly
+            .update_world_transforms_with_current_positions();
+
+        // Update renderer transforms
+        sync_renderer_transforms(&state, ctx);
+    }
+}
+
+fn handle_update_joint_default(joint_id: Uuid, value: f32, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+
+    if let Some(joint) = state.project.assembly.get_joint_mut(joint_id) {
+        joint.default_value = joint.constrain_position(value);
+        tracing::debug!("Updated joint {} default value", joint_id);
+        state.modified = true;
+    } else {
+        tracing::warn!("Joint {} not found for updating default value", joint_id);
+    }
+}
+
+fn handle_update_joint_dynamics(
+    joint_id: Uuid,
+    dynamics: Option<JointDynamics>,
+    ctx: &ActionContext,
+) {
+    let mut state = ctx.app_state.lock();
+
+    if let Some(joint) = state.project.assembly.get_joint_mut(joint_id) {
+        joint.dynamics = dynamics;
+        tracing::debug!("Updated joint {} dynamics", joint_id);
+        state.modified = true;
+    } else {
+        tracing::warn!("Joint {} not found for updating dynamics", joint_id);
+    }
+}
+
+fn handle_update_joint_safety_controller(
+    joint_id: Uuid,
+    safety_controller: Option<JointSafetyController>,
+    ctx: &ActionContext,
+) {
+    let mut state = ctx.app_state.lock();
+
+    if let Some(joint) = state.project.assembly.get_joint_mut(joint_id) {
+        joint.safety_controller = safety_controller;
+        tracing::debug!("Updated joint {} safety controller", joint_id);
+        state.modified = true;
+    } else {
+        tracing::warn!(
+            "Joint {} not found for updating safety controller",
+            joint_id
+        );
+    }
+}
+
+fn handle_update_joint_transmission(
+    joint_id: Uuid,
+    transmission: Option<JointTransmission>,
+    ctx: &ActionContext,
+) {
+    let mut state = ctx.app_state.lock();
+
+    if let Some(joint) = state.project.assembly.get_joint_mut(joint_id) {
+        joint.transmission = transmission;
+        tracing::debug!("Updated joint {} transmission", joint_id);
+        state.modified = true;
+    } else {
+        tracing::warn!("Joint {} not found for updating transmission", joint_id);
+    }
+}
+
+/// Log limit problems on a joint that was just edited
+fn log_joint_issues(joint: &Joint) {
+    for issue in joint.validate() {
+        tracing::warn!("Joint '{}': {}", joint.name, issue);
     }
 }
@@ -0,0 +1,126 @@
+//! Document action handlers
+//!
+//! Switching documents swaps the active project in the app state, so the
+//! viewport is refilled from the newly active project afterwards.
+
+use std::collections::HashSet;
+
+use tracing::info;
+use uuid::Uuid;
+
+use rk_core::{Part, Pose, Project};
+
+use crate::state::{AppAction, ViewportState};
+
+use super::ActionContext;
+
+/// Handle document actions
+pub fn handle_document_action(action: AppAction, ctx: &ActionContext) {
+    match action {
+        AppAction::NewDocument => handle_new_document(ctx),
+        AppAction::SwitchDocument(index) => handle_switch_document(index, ctx),
+        AppAction::CloseDocument(index) => handle_close_document(index, ctx),
+        AppAction::CopySelectedParts => handle_copy_selected_parts(ctx),
+        AppAction::PasteParts => handle_paste_parts(ctx),
+        _ => {}
+    }
+}
+
+fn handle_new_document(ctx: &ActionContext) {
+    ctx.app_state.lock().open_document();
+    show_active_document(ctx);
+}
+
+fn handle_switch_document(index: usize, ctx: &ActionContext) {
+    if ctx.app_state.lock().switch_document(index) {
+        show_active_document(ctx);
+    }
+}
+
+fn handle_close_document(index: usize, ctx: &ActionContext) {
+    if ctx.app_state.lock().close_document(index) {
+        show_active_document(ctx);
+    }
+}
+
+/// Replace the viewport contents with the active document's parts
+fn show_active_document(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    // CAD bodies are sent to the viewport again by `sync_cad_bodies`
+    state.cad.rendered_bodies.clear();
+    state.cad.bodies_dirty = true;
+
+    let Some(viewport_state) = ctx.viewport_state else {
+        return;
+    };
+    let mut vp = viewport_state.lock();
+    vp.clear_parts();
+    vp.clear_overlays();
+    for part in state.project.parts_iter() {
+        add_part_to_viewport(&mut vp, part);
+    }
+    let selected: HashSet<Uuid> = state.selected_parts.iter().copied().collect();
+    vp.set_selected_parts(state.selected_part, &selected);
+}
+
+fn handle_copy_selected_parts(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let mut clipboard = Project::new("Clipboard");
+    for part in state
+        .selected_parts
+        .iter()
+        .filter_map(|id| state.project.get_part(*id))
+    {
+        if let Some(material) = part
+            .material_name
+            .as_deref()
+            .and_then(|name| state.project.get_material(name))
+            && clipboard.get_material(&material.name).is_none()
+        {
+            clipboard.materials.push(material.clone());
+        }
+        clipboard.add_part(part.clone());
+    }
+    if clipboard.parts().is_empty() {
+        return;
+    }
+
+    info!("Copied {} parts", clipboard.parts().len());
+    state.part_clipboard = Some(clipboard);
+}
+
+/// Paste copied parts in place with new IDs, bringing their materials along
+fn handle_paste_parts(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let Some(clipboard) = state.part_clipboard.clone() else {
+        return;
+    };
+    let part_ids = match state.project.merge(clipboard, None, Pose::default()) {
+        Ok(part_ids) => part_ids,
+        Err(e) => {
+            tracing::error!("Failed to paste parts: {}", e);
+            return;
+        }
+    };
+    state.modified = true;
+    state.select_parts(&part_ids, false);
+    info!("Pasted {} parts", part_ids.len());
+
+    if let Some(viewport_state) = ctx.viewport_state {
+        let mut vp = viewport_state.lock();
+        for part in part_ids.iter().filter_map(|id| state.project.get_part(*id)) {
+            add_part_to_viewport(&mut vp, part);
+        }
+        let selected: HashSet<Uuid> = part_ids.iter().copied().collect();
+        vp.set_selected_parts(state.selected_part, &selected);
+    }
+    super::assembly::sync_renderer_transforms(&state, ctx);
+}
+
+/// Add a part to the viewport, keeping it hidden if it was hidden
+fn add_part_to_viewport(vp: &mut ViewportState, part: &Part) {
+    vp.add_part(part);
+    if !part.visible {
+        vp.set_part_visible(part.id, false);
+    }
+}
@@ -2,9 +2,11 @@
 
 use std::collections::HashMap;
 
-use rk_core::{ImportOptions, Project, import_urdf, load_mesh};
+use rk_cad::CadData;
+use rk_core::{CollisionElement, CollisionMesh, ImportOptions, Part, Pose, import_urdf, load_mesh};
+use uuid::Uuid;
 
-use crate::state::AppAction;
+use crate::state::{AppAction, ExportCheckDialogState, project_file};
 
 use super::ActionContext;
 
@@ -12,10 +14,28 @@ use super::ActionContext;
 pub fn handle_file_action(action: AppAction, ctx: &ActionContext) {
     match action {
         AppAction::ImportMesh(path) => handle_import_mesh(path, ctx),
+        AppAction::ImportCollisionMesh { path, link_id } => {
+            handle_import_collision_mesh(path, link_id, ctx)
+        }
         AppAction::ImportUrdf(path) => handle_import_urdf(path, ctx),
         AppAction::SaveProject(path) => handle_save_project(path, ctx),
         AppAction::LoadProject(path) => handle_load_project(path, ctx),
+        AppAction::MergeProject { path, parent_link } => {
+            handle_merge_project(path, parent_link, ctx)
+        }
+        AppAction::CheckExportUrdf { path, robot_name } => {
+            handle_check_export_urdf(path, robot_name, ctx)
+        }
+        AppAction::FixExportIssues => handle_fix_export_issues(ctx),
         AppAction::ExportUrdf { path, robot_name } => handle_export_urdf(path, robot_name, ctx),
+        AppAction::ExportMergedStl(path) => handle_export_merged_stl(path, ctx),
+        AppAction::ExportDae(path) => handle_export_dae(path, ctx),
+        AppAction::ExportBom { path } => handle_export_bom(path, ctx),
+        AppAction::ExportTurntable {
+            output_dir,
+            frames,
+            duration_secs,
+        } => handle_export_turntable(output_dir, frames, duration_secs, ctx),
         AppAction::NewProject => handle_new_project(ctx),
         _ => {}
     }
@@ -66,21 +86,61 @@ fn handle_import_mesh(path: std::path::PathBuf, ctx: &ActionContext) {
         }
         Err(e) => {
             tracing::error!("Failed to load mesh: {}", e);
+            ctx.app_state.lock().notify_error(format!(
+                "Failed to import {}: {}",
+                file_name(&path),
+                e
+            ));
         }
     }
 }
 
-fn handle_import_urdf(path: std::path::PathBuf, ctx: &ActionContext) {
-    let stl_unit = ctx.app_state.lock().stl_import_unit;
-    let options = ImportOptions {
+/// Load a mesh and add it to a link as collision geometry of its own
+fn handle_import_collision_mesh(path: std::path::PathBuf, link_id: Uuid, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let mesh = match load_mesh(&path, state.stl_import_unit) {
+        Ok(part) => CollisionMesh::from_part(&part),
+        Err(e) => {
+            tracing::error!("Failed to load collision mesh: {}", e);
+            state.notify_error(format!("Failed to import {}: {}", file_name(&path), e));
+            return;
+        }
+    };
+    let triangles = mesh.triangle_count();
+    let Some(link) = state.project.assembly.get_link_mut(link_id) else {
+        tracing::warn!("Link {} not found for collision mesh import", link_id);
+        return;
+    };
+    link.collisions.push(CollisionElement::from_mesh(mesh));
+    let selection = (link_id, link.collisions.len() - 1);
+    let message = format!(
+        "Imported {} as collision of {} ({} triangles)",
+        file_name(&path),
+        link.name,
+        triangles
+    );
+    state.selected_collision = Some(selection);
+    state.modified = true;
+    state.notify_success(message);
+}
+
+/// URDF import options for a file, using the import settings of the app
+fn urdf_import_options(path: &std::path::Path, ctx: &ActionContext) -> ImportOptions {
+    let state = ctx.app_state.lock();
+    ImportOptions {
         base_dir: path
             .parent()
             .map(|p| p.to_path_buf())
             .unwrap_or_else(|| std::path::PathBuf::from(".")),
-        stl_unit,
+        stl_unit: state.stl_import_unit,
         default_color: [0.7, 0.7, 0.7, 1.0],
         package_paths: HashMap::new(),
-    };
+        primitive_quality: state.primitive_quality,
+    }
+}
+
+fn handle_import_urdf(path: std::path::PathBuf, ctx: &ActionContext) {
+    let options = urdf_import_options(&path, ctx);
 
     match import_urdf(&path, &options) {
         Ok(project) => {
@@ -134,10 +194,17 @@ fn handle_import_urdf(path: std::path::PathBuf, ctx: &ActionContext) {
             }
 
             // Load into app state
-            ctx.app_state.lock().load_project(project, path);
+            ctx.app_state
+                .lock()
+                .load_project(project, CadData::default(), Some(path));
         }
         Err(e) => {
             tracing::error!("Failed to import URDF: {}", e);
+            ctx.app_state.lock().notify_error(format!(
+                "Failed to import {}: {}",
+                file_name(&path),
+                e
+            ));
         }
     }
 }
@@ -147,24 +214,24 @@ fn handle_save_project(path: Option<std::path::PathBuf>, ctx: &ActionContext) {
     let save_path = path.or(state.project_path.clone());
 
     if let Some(ref path) = save_path {
-        // No sync needed - parts are stored directly in project
-
-        match state.project.save(path) {
+        match project_file::save(&state.project, &state.cad.data, path) {
             Ok(()) => {
                 tracing::info!("Saved project to {:?}", path);
                 state.project_path = Some(path.clone());
                 state.modified = false;
+                state.notify_success(format!("Saved {}", file_name(path)));
             }
             Err(e) => {
                 tracing::error!("Failed to save project: {}", e);
+                state.notify_error(format!("Failed to save project: {}", e));
             }
         }
     }
 }
 
 fn handle_load_project(path: std::path::PathBuf, ctx: &ActionContext) {
-    match Project::load(&path) {
-        Ok(project) => {
+    match project_file::load(&path) {
+        Ok((project, cad)) => {
             tracing::info!("Loaded project: {}", project.name);
 
             // Clear viewport
@@ -181,33 +248,304 @@ fn handle_load_project(path: std::path::PathBuf, ctx: &ActionContext) {
             }
 
             // Load into app state
-            ctx.app_state.lock().load_project(project, path);
+            ctx.app_state.lock().load_project(project, cad, Some(path));
         }
         Err(e) => {
             tracing::error!("Failed to load project: {}", e);
+            ctx.app_state.lock().notify_error(format!(
+                "Failed to open {}: {}",
+                file_name(&path),
+                e
+            ));
         }
     }
 }
 
+fn handle_merge_project(path: std::path::PathBuf, parent_link: Option<Uuid>, ctx: &ActionContext) {
+    // Anything that is not a project file is read as URDF. CAD history is
+    // not merged, only parts and links.
+    let other = if path.extension().is_some_and(|ext| ext == "rk") {
+        project_file::load(&path)
+            .map(|(project, _)| project)
+            .map_err(|e| e.to_string())
+    } else {
+        import_urdf(&path, &urdf_import_options(&path, ctx)).map_err(|e| e.to_string())
+    };
+    let other = match other {
+        Ok(other) => other,
+        Err(e) => {
+            tracing::error!("Failed to read {:?} for merging: {}", path, e);
+            ctx.app_state.lock().notify_error(format!(
+                "Failed to read {}: {}",
+                file_name(&path),
+                e
+            ));
+            return;
+        }
+    };
+
+    let mut state = ctx.app_state.lock();
+    let name = other.name.clone();
+    match state.project.merge(other, parent_link, Pose::default()) {
+        Ok(part_ids) => {
+            state.modified = true;
+            if let Some(viewport_state) = ctx.viewport_state {
+                let mut viewport = viewport_state.lock();
+                for part in part_ids.iter().filter_map(|id| state.project.get_part(*id)) {
+                    viewport.add_part(part);
+                }
+            }
+            // Parts attached under a posed link follow its joints
+            super::assembly::sync_renderer_transforms(&state, ctx);
+            tracing::info!("Merged '{}' ({} parts)", name, part_ids.len());
+        }
+        Err(e) => {
+            tracing::error!("Failed to merge '{}': {}", name, e);
+            state.notify_error(format!("Failed to merge '{}': {}", name, e));
+        }
+    }
+}
+
+fn handle_check_export_urdf(path: std::path::PathBuf, robot_name: String, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let issues = rk_core::check_export(&state.project.assembly, state.project.parts());
+    if issues.is_empty() {
+        drop(state);
+        handle_export_urdf(path, robot_name, ctx);
+        return;
+    }
+    state.export_check_dialog = Some(ExportCheckDialogState {
+        path,
+        robot_name,
+        issues,
+    });
+}
+
+fn handle_fix_export_issues(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let Some(dialog) = state.export_check_dialog.as_ref() else {
+        return;
+    };
+    let fixes: Vec<_> = dialog.issues.iter().filter_map(|issue| issue.fix).collect();
+
+    // The fixes read the parts while editing the assembly
+    let mut assembly = std::mem::take(&mut state.project.assembly);
+    for fix in &fixes {
+        fix.apply(&mut assembly, state.project.parts());
+    }
+    let issues = rk_core::check_export(&assembly, state.project.parts());
+    state.project.assembly = assembly;
+    state.modified = true;
+    if let Some(dialog) = state.export_check_dialog.as_mut() {
+        dialog.issues = issues;
+    }
+    tracing::info!("Applied {} export fixes", fixes.len());
+}
+
 fn handle_export_urdf(path: std::path::PathBuf, robot_name: String, ctx: &ActionContext) {
-    let state = ctx.app_state.lock();
+    let mut state = ctx.app_state.lock();
     let options = rk_core::ExportOptions {
         output_dir: path,
         robot_name,
         mesh_prefix: "meshes".to_string(),
         use_package_uri: false,
+        mesh_unit: state.stl_export_unit,
+        precision: state.export_precision,
     };
 
-    match rk_core::export_urdf(&state.project.assembly, state.project.parts(), &options) {
+    for (joint_id, issue) in state.project.assembly.joint_issues() {
+        if !issue.is_error() {
+            let name = &state.project.assembly.joints[&joint_id].name;
+            tracing::warn!("Joint '{}': {}", name, issue);
+        }
+    }
+
+    let assembly = &state.project.assembly;
+    let mut disconnected: Vec<&str> = assembly
+        .connectivity_report()
+        .problem_links()
+        .map(|link_id| assembly.links[&link_id].name.as_str())
+        .collect();
+    if !disconnected.is_empty() {
+        disconnected.sort_unstable();
+        tracing::error!(
+            "Links not connected to the root link: {}",
+            disconnected.join(", ")
+        );
+    }
+
+    let result = rk_core::export_project_urdf(&state.project, &options);
+    match result {
         Ok(_urdf) => {
             tracing::info!("Exported URDF to {:?}", options.output_dir);
+            state.notify_success(format!("Exported URDF to {}", options.output_dir.display()));
         }
         Err(e) => {
             tracing::error!("Failed to export URDF: {}", e);
+            state.notify_error(format!("Failed to export URDF: {}", e));
+        }
+    }
+}
+
+fn handle_export_merged_stl(path: std::path::PathBuf, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let (vertices, normals, indices) = state.project.assembly.to_merged_mesh(state.project.parts());
+    if indices.is_empty() {
+        tracing::warn!("No linked parts to export");
+        state.notify_error("No linked parts to export");
+        return;
+    }
+
+    let mut merged = Part::new(state.project.name.clone());
+    merged.vertices = vertices;
+    merged.normals = normals;
+    merged.indices = indices;
+
+    match rk_core::save_stl_with_unit(&merged, &path, state.stl_export_unit) {
+        Ok(()) => {
+            tracing::info!("Exported merged STL to {:?}", path);
+            state.notify_success(format!("Exported {}", file_name(&path)));
+        }
+        Err(e) => {
+            tracing::error!("Failed to export merged STL: {}", e);
+            state.notify_error(format!("Failed to export merged STL: {}", e));
+        }
+    }
+}
+
+fn handle_export_dae(path: std::path::PathBuf, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    match rk_core::export_dae(&state.project.assembly, state.project.parts(), &path) {
+        Ok(()) => {
+            tracing::info!("Exported COLLADA scene to {:?}", path);
+            state.notify_success(format!("Exported {}", file_name(&path)));
+        }
+        Err(e) => {
+            tracing::error!("Failed to export COLLADA scene: {}", e);
+            state.notify_error(format!("Failed to export COLLADA scene: {}", e));
+        }
+    }
+}
+
+fn handle_export_bom(path: std::path::PathBuf, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    match rk_core::export_bom(state.project.parts(), &path) {
+        Ok(()) => {
+            tracing::info!("Exported bill of materials to {:?}", path);
+            state.notify_success(format!("Exported {}", file_name(&path)));
+        }
+        Err(e) => {
+            tracing::error!("Failed to export bill of materials: {}", e);
+            state.notify_error(format!("Failed to export bill of materials: {}", e));
+        }
+    }
+}
+
+fn handle_export_turntable(
+    output_dir: std::path::PathBuf,
+    frames: u32,
+    duration_secs: f32,
+    ctx: &ActionContext,
+) {
+    let Some(viewport_state) = ctx.viewport_state else {
+        return;
+    };
+
+    // Orbit around the center of all visible parts
+    let center = {
+        let state = ctx.app_state.lock();
+        state
+            .project
+            .parts()
+            .values()
+            .filter(|part| part.visible)
+            .map(|part| part.world_bounds())
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+            .map(|(min, max)| (min + max) / 2.0)
+            .unwrap_or(glam::Vec3::ZERO)
+    };
+
+    if let Err(e) = std::fs::create_dir_all(&output_dir) {
+        tracing::error!("Failed to create {:?}: {}", output_dir, e);
+        ctx.app_state.lock().notify_error(format!(
+            "Failed to create {}: {}",
+            output_dir.display(),
+            e
+        ));
+        return;
+    }
+
+    let mut vp = viewport_state.lock();
+    let (saved_target, saved_yaw) = (vp.renderer.camera().target, vp.renderer.camera().yaw);
+    vp.renderer.camera_mut().target = center;
+
+    let step = std::f32::consts::TAU / frames.max(1) as f32;
+    let mut written = 0;
+    let mut error = None;
+    for frame in 0..frames {
+        vp.renderer
+            .camera_mut()
+            .orbit(if frame == 0 { 0.0 } else { step }, 0.0);
+
+        let Some((width, height, pixels)) = vp.capture_frame() else {
+            tracing::error!("Failed to capture turntable frame {}", frame);
+            error = Some(format!("Failed to capture turntable frame {}", frame));
+            break;
+        };
+        let path = output_dir.join(format!("frame_{:04}.png", frame));
+        if let Err(e) = write_png(&path, width, height, &pixels) {
+            tracing::error!("Failed to write {:?}: {}", path, e);
+            error = Some(format!("Failed to write {}: {}", file_name(&path), e));
+            break;
         }
+        written += 1;
+    }
+
+    let camera = vp.renderer.camera_mut();
+    camera.target = saved_target;
+    camera.orbit(saved_yaw - camera.yaw, 0.0);
+
+    tracing::info!(
+        "Wrote {} turntable frames to {:?} ({:.1} fps for {:.1} s)",
+        written,
+        output_dir,
+        frames as f32 / duration_secs.max(0.01),
+        duration_secs
+    );
+    drop(vp);
+    let mut state = ctx.app_state.lock();
+    match error {
+        Some(error) => state.notify_error(error),
+        None => state.notify_success(format!(
+            "Wrote {} turntable frames to {}",
+            written,
+            output_dir.display()
+        )),
     }
 }
 
+/// File name of a path for messages, or the whole path if it has none
+fn file_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Write an RGBA8 image as a PNG file
+fn write_png(
+    path: &std::path::Path,
+    width: u32,
+    height: u32,
+    pixels: &[u8],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(pixels)?;
+    Ok(())
+}
+
 fn handle_new_project(ctx: &ActionContext) {
     ctx.app_state.lock().new_project();
     if let Some(viewport_state) = ctx.viewport_state {
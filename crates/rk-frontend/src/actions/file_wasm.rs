@@ -1,8 +1,8 @@
 //! WASM file I/O action handlers
 
-use rk_core::{Project, load_stl_from_bytes};
+use rk_core::load_stl_from_bytes;
 
-use crate::state::AppAction;
+use crate::state::{AppAction, project_file};
 
 use super::ActionContext;
 
@@ -51,13 +51,16 @@ fn handle_import_mesh_bytes(name: &str, data: &[u8], ctx: &ActionContext) {
         }
         Err(e) => {
             tracing::error!("Failed to load mesh from bytes: {}", e);
+            ctx.app_state
+                .lock()
+                .notify_error(format!("Failed to import {}: {}", name, e));
         }
     }
 }
 
-fn handle_load_project_bytes(_name: &str, data: &[u8], ctx: &ActionContext) {
-    match Project::load_from_bytes(data) {
-        Ok(project) => {
+fn handle_load_project_bytes(name: &str, data: &[u8], ctx: &ActionContext) {
+    match project_file::load_from_bytes(data) {
+        Ok((project, cad)) => {
             tracing::info!("Loaded project from bytes: {}", project.name);
 
             // Clear viewport
@@ -74,15 +77,13 @@ fn handle_load_project_bytes(_name: &str, data: &[u8], ctx: &ActionContext) {
             }
 
             // Load into app state (without file path for WASM)
-            // No manual sync needed - parts are stored directly in project
-            let mut state = ctx.app_state.lock();
-            state.project = project;
-            state.project_path = None;
-            state.selected_part = None;
-            state.modified = false;
+            ctx.app_state.lock().load_project(project, cad, None);
         }
         Err(e) => {
             tracing::error!("Failed to load project from bytes: {}", e);
+            ctx.app_state
+                .lock()
+                .notify_error(format!("Failed to open {}: {}", name, e));
         }
     }
 }
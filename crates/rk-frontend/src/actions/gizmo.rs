@@ -0,0 +1,91 @@
+//! Gizmo drag action handlers
+//!
+//! A gizmo drag updates its target live every frame without marking the
+//! project modified; the drag is committed as a whole when it ends, or
+//! reverted to the value captured when it started.
+
+use rk_core::Pose;
+
+use crate::state::{AppAction, GizmoDragStart};
+
+use super::ActionContext;
+use super::assembly::set_joint_origin;
+
+/// Handle gizmo drag actions
+pub fn handle_gizmo_action(action: AppAction, ctx: &ActionContext) {
+    match action {
+        AppAction::CommitGizmoDrag(start) => handle_commit_gizmo_drag(start, ctx),
+        AppAction::RevertGizmoDrag(start) => handle_revert_gizmo_drag(start, ctx),
+        _ => {}
+    }
+}
+
+fn handle_commit_gizmo_drag(start: GizmoDragStart, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let changed = match start {
+        GizmoDragStart::Part { part_id, transform } => state
+            .get_part(part_id)
+            .is_some_and(|part| part.origin_transform != transform),
+        GizmoDragStart::Collision {
+            link_id,
+            index,
+            origin,
+        } => state
+            .project
+            .assembly
+            .get_link(link_id)
+            .and_then(|link| link.collisions.get(index))
+            .is_some_and(|collision| !same_pose(&collision.origin, &origin)),
+        GizmoDragStart::Joint { joint_id, origin } => state
+            .project
+            .assembly
+            .get_joint(joint_id)
+            .is_some_and(|joint| !same_pose(&joint.origin, &origin)),
+    };
+
+    if changed {
+        state.modified = true;
+        tracing::debug!("Committed gizmo drag: {:?}", start);
+    }
+}
+
+fn handle_revert_gizmo_drag(start: GizmoDragStart, ctx: &ActionContext) {
+    match start {
+        GizmoDragStart::Part { part_id, transform } => {
+            let mut state = ctx.app_state.lock();
+            if let Some(part) = state.project.get_part_mut(part_id) {
+                part.origin_transform = transform;
+            }
+            drop(state);
+            if let Some(viewport_state) = ctx.viewport_state {
+                viewport_state
+                    .lock()
+                    .update_part_transform(part_id, transform);
+            }
+        }
+        GizmoDragStart::Collision {
+            link_id,
+            index,
+            origin,
+        } => {
+            let mut state = ctx.app_state.lock();
+            if let Some(collision) = state
+                .project
+                .assembly
+                .get_link_mut(link_id)
+                .and_then(|link| link.collisions.get_mut(index))
+            {
+                collision.origin = origin;
+            }
+        }
+        GizmoDragStart::Joint { joint_id, origin } => {
+            set_joint_origin(joint_id, origin, ctx);
+        }
+    }
+    tracing::debug!("Reverted gizmo drag: {:?}", start);
+}
+
+/// Whether two poses are exactly equal
+fn same_pose(a: &Pose, b: &Pose) -> bool {
+    a.xyz == b.xyz && a.rpy == b.rpy
+}
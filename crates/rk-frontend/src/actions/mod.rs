@@ -4,22 +4,26 @@
 //! Actions are queued in AppState and processed each frame.
 
 mod assembly;
+mod document;
 #[cfg(not(target_arch = "wasm32"))]
 mod file;
 #[cfg(target_arch = "wasm32")]
 mod file_wasm;
+mod gizmo;
 mod part;
 mod sketch;
 
 use crate::state::{AppAction, SharedAppState, SharedViewportState};
 
 pub use assembly::handle_assembly_action;
+pub use document::handle_document_action;
 #[cfg(not(target_arch = "wasm32"))]
 pub use file::handle_file_action;
 #[cfg(target_arch = "wasm32")]
 pub use file_wasm::handle_file_action_wasm;
+pub use gizmo::handle_gizmo_action;
 pub use part::handle_part_action;
-pub use sketch::handle_sketch_action;
+pub use sketch::{handle_sketch_action, sync_cad_bodies};
 
 /// Context for action handlers
 pub struct ActionContext<'a> {
@@ -45,10 +49,18 @@ pub fn dispatch_action(action: AppAction, ctx: &ActionContext) {
         // File actions (native only)
         #[cfg(not(target_arch = "wasm32"))]
         AppAction::ImportMesh(_)
+        | AppAction::ImportCollisionMesh { .. }
         | AppAction::ImportUrdf(_)
         | AppAction::SaveProject(_)
         | AppAction::LoadProject(_)
+        | AppAction::MergeProject { .. }
+        | AppAction::CheckExportUrdf { .. }
+        | AppAction::FixExportIssues
         | AppAction::ExportUrdf { .. }
+        | AppAction::ExportMergedStl(_)
+        | AppAction::ExportDae(_)
+        | AppAction::ExportBom { .. }
+        | AppAction::ExportTurntable { .. }
         | AppAction::NewProject => {
             handle_file_action(action, ctx);
         }
@@ -56,10 +68,17 @@ pub fn dispatch_action(action: AppAction, ctx: &ActionContext) {
         // File actions (WASM - ignore)
         #[cfg(target_arch = "wasm32")]
         AppAction::ImportMesh(_)
+        | AppAction::ImportCollisionMesh { .. }
         | AppAction::ImportUrdf(_)
         | AppAction::SaveProject(_)
         | AppAction::LoadProject(_)
-        | AppAction::ExportUrdf { .. } => {
+        | AppAction::MergeProject { .. }
+        | AppAction::CheckExportUrdf { .. }
+        | AppAction::FixExportIssues
+        | AppAction::ExportUrdf { .. }
+        | AppAction::ExportMergedStl(_)
+        | AppAction::ExportDae(_)
+        | AppAction::ExportBom { .. } => {
             tracing::warn!("File actions are not supported in WASM");
         }
 
@@ -85,31 +104,81 @@ pub fn dispatch_action(action: AppAction, ctx: &ActionContext) {
             tracing::warn!("Bytes-based file actions are primarily for WASM");
         }
 
+        // Document actions
+        AppAction::NewDocument
+        | AppAction::SwitchDocument(_)
+        | AppAction::CloseDocument(_)
+        | AppAction::CopySelectedParts
+        | AppAction::PasteParts => {
+            handle_document_action(action, ctx);
+        }
+
         // Part actions
         AppAction::CreatePrimitive { .. }
         | AppAction::CreateEmpty { .. }
         | AppAction::SelectPart(_)
-        | AppAction::DeleteSelectedPart
-        | AppAction::UpdatePartTransform { .. } => {
+        | AppAction::TogglePartSelection(_)
+        | AppAction::SelectParts { .. }
+        | AppAction::FramePart(_)
+        | AppAction::DeleteSelectedPart { .. }
+        | AppAction::RenamePart { .. }
+        | AppAction::DuplicatePart(_)
+        | AppAction::RecomputeInertia(_)
+        | AppAction::RecomputeAllInertias
+        | AppAction::UpdatePartTransform { .. }
+        | AppAction::SetPartVisibility { .. }
+        | AppAction::IsolateSelection
+        | AppAction::ShowAllParts
+        | AppAction::PatternPart { .. }
+        | AppAction::AlignSelectedParts { .. }
+        | AppAction::DistributeSelectedParts { .. } => {
+            handle_part_action(action, ctx);
+        }
+
+        // Gizmo actions
+        AppAction::CommitGizmoDrag(_) | AppAction::RevertGizmoDrag(_) => {
+            handle_gizmo_action(action, ctx);
+        }
+
+        // Material actions
+        AppAction::AddMaterial
+        | AppAction::UpdateMaterial { .. }
+        | AppAction::RemoveMaterial { .. }
+        | AppAction::AssignMaterial { .. } => {
             handle_part_action(action, ctx);
         }
 
         // Assembly actions
         AppAction::ConnectParts { .. }
         | AppAction::DisconnectPart { .. }
+        | AppAction::SnapJointPoints { .. }
+        | AppAction::CreateJoint { .. }
         | AppAction::UpdateJointPosition { .. }
+        | AppAction::UpdateJointDof { .. }
         | AppAction::ResetJointPosition { .. }
         | AppAction::ResetAllJointPositions
+        | AppAction::GoHome
+        | AppAction::SetHomePose
+        | AppAction::LoadJointState { .. }
         | AppAction::UpdateJointType { .. }
         | AppAction::UpdateJointOrigin { .. }
+        | AppAction::SetJointOriginPick(_)
+        | AppAction::SetJointOriginDrag(_)
+        | AppAction::SnapJointOrigin { .. }
+        | AppAction::DragJointOrigin { .. }
         | AppAction::UpdateJointAxis { .. }
-        | AppAction::UpdateJointLimits { .. } => {
+        | AppAction::UpdateJointLimits { .. }
+        | AppAction::UpdateJointDefault { .. }
+        | AppAction::UpdateJointDynamics { .. }
+        | AppAction::UpdateJointSafetyController { .. }
+        | AppAction::UpdateJointTransmission { .. } => {
             handle_assembly_action(action, ctx);
         }
 
         // Collision actions
         AppAction::SelectCollision(_)
         | AppAction::AddCollision { .. }
+        | AppAction::CopyVisualToCollision { .. }
         | AppAction::RemoveCollision { .. }
         | AppAction::UpdateCollisionOrigin { .. }
         | AppAction::UpdateCollisionGeometry { .. } => {
@@ -1,13 +1,19 @@
 //! Part-related action handlers
 
-use glam::Mat4;
+use std::collections::HashSet;
+
+use glam::{Mat4, Vec3};
 use uuid::Uuid;
 
-use rk_core::{Part, generate_box_mesh, generate_cylinder_mesh, generate_sphere_mesh};
+use rk_core::{
+    AlignEdge, AlignReference, MaterialDef, Part, PatternKind, align_offsets, distribute_offsets,
+    generate_box_mesh, generate_cylinder_mesh, generate_sphere_mesh,
+};
 
 use crate::state::{AppAction, PrimitiveType};
 
 use super::ActionContext;
+use super::assembly::sync_renderer_transforms;
 
 /// Handle part-related actions
 pub fn handle_part_action(action: AppAction, ctx: &ActionContext) {
@@ -18,10 +24,41 @@ pub fn handle_part_action(action: AppAction, ctx: &ActionContext) {
         } => handle_create_primitive(primitive_type, name, ctx),
         AppAction::CreateEmpty { name } => handle_create_empty(name, ctx),
         AppAction::SelectPart(part_id) => handle_select_part(part_id, ctx),
-        AppAction::DeleteSelectedPart => handle_delete_selected_part(ctx),
+        AppAction::TogglePartSelection(part_id) => handle_toggle_part_selection(part_id, ctx),
+        AppAction::SelectParts { part_ids, extend } => handle_select_parts(&part_ids, extend, ctx),
+        AppAction::FramePart(part_id) => handle_frame_part(part_id, ctx),
+        AppAction::DeleteSelectedPart { reparent_children } => {
+            handle_delete_selected_part(reparent_children, ctx)
+        }
+        AppAction::RenamePart { part_id, name } => handle_rename_part(part_id, name, ctx),
+        AppAction::DuplicatePart(part_id) => handle_duplicate_part(part_id, ctx),
+        AppAction::RecomputeInertia(part_id) => handle_recompute_inertia(part_id, ctx),
+        AppAction::RecomputeAllInertias => handle_recompute_all_inertias(ctx),
         AppAction::UpdatePartTransform { part_id, transform } => {
             handle_update_part_transform(part_id, transform, ctx)
         }
+        AppAction::SetPartVisibility { part_id, visible } => {
+            handle_set_part_visibility(part_id, visible, ctx)
+        }
+        AppAction::IsolateSelection => handle_isolate_selection(ctx),
+        AppAction::ShowAllParts => handle_show_all_parts(ctx),
+        AppAction::PatternPart {
+            part_id,
+            kind,
+            attach_to_parent,
+        } => handle_pattern_part(part_id, kind, attach_to_parent, ctx),
+        AppAction::AlignSelectedParts {
+            axis,
+            edge,
+            reference,
+        } => handle_align_selected_parts(axis, edge, reference, ctx),
+        AppAction::DistributeSelectedParts { axis } => handle_distribute_selected_parts(axis, ctx),
+        AppAction::AddMaterial => handle_add_material(ctx),
+        AppAction::UpdateMaterial { name, material } => {
+            handle_update_material(&name, material, ctx)
+        }
+        AppAction::RemoveMaterial { name } => handle_remove_material(&name, ctx),
+        AppAction::AssignMaterial { name } => handle_assign_material(name.as_deref(), ctx),
         _ => {}
     }
 }
@@ -88,6 +125,125 @@ fn handle_create_empty(name: Option<String>, ctx: &ActionContext) {
     ctx.app_state.lock().add_part(part);
 }
 
+fn handle_pattern_part(
+    part_id: Uuid,
+    kind: PatternKind,
+    attach_to_parent: bool,
+    ctx: &ActionContext,
+) {
+    let mut state = ctx.app_state.lock();
+    let Some(source) = state.get_part(part_id).cloned() else {
+        return;
+    };
+
+    // Part owning the parent link of the original, if it is jointed to one
+    let assembly = &state.project.assembly;
+    let parent_part = assembly
+        .links
+        .iter()
+        .find(|(_, link)| link.part_id == Some(part_id))
+        .and_then(|(link_id, _)| assembly.parent.get(link_id))
+        .and_then(|(_, parent_link_id)| assembly.links.get(parent_link_id))
+        .and_then(|link| link.part_id)
+        .filter(|_| attach_to_parent);
+
+    let copies: Vec<Part> = kind
+        .copy_transforms()
+        .into_iter()
+        .enumerate()
+        .map(|(i, offset)| {
+            let mut copy = source.duplicate(format!("{}_{}", source.name, i + 2));
+            copy.origin_transform = offset * source.origin_transform;
+            copy
+        })
+        .collect();
+
+    tracing::info!("Patterned {} into {} copies", source.name, copies.len());
+
+    for copy in &copies {
+        state.add_part(copy.clone());
+        if let Some(parent) = parent_part {
+            state.queue_action(AppAction::ConnectParts {
+                parent,
+                child: copy.id,
+            });
+        }
+    }
+    drop(state);
+
+    // Copies reuse the original's GPU mesh
+    if let Some(viewport_state) = ctx.viewport_state {
+        let mut vp = viewport_state.lock();
+        for copy in &copies {
+            vp.add_part_instance(part_id, copy);
+        }
+    }
+}
+
+fn handle_rename_part(part_id: Uuid, name: String, ctx: &ActionContext) {
+    if let Some(part) = ctx.app_state.lock().get_part_mut(part_id) {
+        tracing::info!("Renamed part {} to {}", part.name, name);
+        part.name = name;
+    }
+}
+
+fn handle_duplicate_part(part_id: Uuid, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let Some(source) = state.get_part(part_id) else {
+        return;
+    };
+    let copy = source.duplicate(format!("{}_copy", source.name));
+    let copy_id = copy.id;
+
+    tracing::info!("Duplicated {} as {}", source.name, copy.name);
+    state.add_part(copy.clone());
+    state.select_part(Some(copy_id));
+    drop(state);
+
+    // The copy reuses the original's GPU mesh
+    if let Some(viewport_state) = ctx.viewport_state {
+        let mut vp = viewport_state.lock();
+        vp.add_part_instance(part_id, &copy);
+        vp.set_selected_part(Some(copy_id));
+    }
+}
+
+fn handle_recompute_inertia(part_id: Uuid, ctx: &ActionContext) {
+    if let Some(part) = ctx.app_state.lock().get_part_mut(part_id) {
+        part.recompute_inertia();
+        tracing::info!("Recomputed inertia of {}", part.name);
+    }
+}
+
+fn handle_recompute_all_inertias(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let report = state.project.recompute_mesh_inertias();
+    tracing::info!(
+        "Recomputed inertia of {:?}, skipped {:?}",
+        report.updated,
+        report.skipped
+    );
+
+    if report.updated.is_empty() && report.skipped.is_empty() {
+        state.notify_error("No links with meshes to recompute");
+        return;
+    }
+    state.modified |= !report.updated.is_empty();
+    let mut message = format!("Recomputed inertia of {} links", report.updated.len());
+    if !report.skipped.is_empty() {
+        message.push_str(&format!(
+            "; skipped {} without a watertight mesh: {}",
+            report.skipped.len(),
+            report.skipped.join(", ")
+        ));
+    }
+    if report.updated.is_empty() {
+        state.notify_error(message);
+    } else {
+        state.notify_success(message);
+    }
+}
+
 fn handle_select_part(part_id: Option<Uuid>, ctx: &ActionContext) {
     ctx.app_state.lock().select_part(part_id);
 
@@ -98,25 +254,245 @@ fn handle_select_part(part_id: Option<Uuid>, ctx: &ActionContext) {
     // Overlays are updated in update_overlays() called after process_actions
 }
 
-fn handle_delete_selected_part(ctx: &ActionContext) {
-    let selected = ctx.app_state.lock().selected_part;
-    if let Some(id) = selected {
-        ctx.app_state.lock().remove_part(id);
+fn handle_toggle_part_selection(part_id: Uuid, ctx: &ActionContext) {
+    let (primary, selected) = {
+        let mut state = ctx.app_state.lock();
+        state.toggle_part_selection(part_id);
+        let selected: HashSet<Uuid> = state.selected_parts.iter().copied().collect();
+        (state.selected_part, selected)
+    };
+
+    if let Some(viewport_state) = ctx.viewport_state {
+        viewport_state.lock().set_selected_parts(primary, &selected);
+    }
+}
+
+fn handle_select_parts(part_ids: &[Uuid], extend: bool, ctx: &ActionContext) {
+    let (primary, selected) = {
+        let mut state = ctx.app_state.lock();
+        state.select_parts(part_ids, extend);
+        let selected: HashSet<Uuid> = state.selected_parts.iter().copied().collect();
+        (state.selected_part, selected)
+    };
+
+    if let Some(viewport_state) = ctx.viewport_state {
+        viewport_state.lock().set_selected_parts(primary, &selected);
+    }
+}
+
+fn handle_frame_part(part_id: Uuid, ctx: &ActionContext) {
+    let Some((min, max)) = ctx
+        .app_state
+        .lock()
+        .project
+        .get_part(part_id)
+        .map(|part| part.world_bounds())
+    else {
+        return;
+    };
+    if let Some(viewport_state) = ctx.viewport_state {
+        let radius = (max - min).length() / 2.0;
+        viewport_state
+            .lock()
+            .renderer
+            .camera_mut()
+            .fit_all((min + max) / 2.0, radius.max(0.05));
+    }
+}
+
+fn handle_align_selected_parts(
+    axis: usize,
+    edge: AlignEdge,
+    reference: AlignReference,
+    ctx: &ActionContext,
+) {
+    translate_selected_parts(ctx, |bounds| align_offsets(bounds, axis, edge, reference));
+}
+
+fn handle_distribute_selected_parts(axis: usize, ctx: &ActionContext) {
+    translate_selected_parts(ctx, |bounds| distribute_offsets(bounds, axis));
+}
+
+/// Move the selected parts by offsets computed from their world bounding boxes
+fn translate_selected_parts(
+    ctx: &ActionContext,
+    offsets: impl FnOnce(&[(Vec3, Vec3)]) -> Vec<Vec3>,
+) {
+    let moves: Vec<(Uuid, Mat4)> = {
+        let state = ctx.app_state.lock();
+        let parts: Vec<&Part> = state
+            .selected_parts
+            .iter()
+            .filter_map(|id| state.get_part(*id))
+            .collect();
+        let bounds: Vec<_> = parts.iter().map(|part| part.world_bounds()).collect();
+
+        parts
+            .iter()
+            .zip(offsets(&bounds))
+            .filter(|(_, offset)| *offset != Vec3::ZERO)
+            .map(|(part, offset)| {
+                (
+                    part.id,
+                    Mat4::from_translation(offset) * part.origin_transform,
+                )
+            })
+            .collect()
+    };
 
-        if let Some(viewport_state) = ctx.viewport_state {
-            viewport_state.lock().remove_part(id);
-            viewport_state.lock().clear_overlays();
+    for (part_id, transform) in moves {
+        handle_update_part_transform(part_id, transform, ctx);
+    }
+}
+
+fn handle_delete_selected_part(reparent_children: bool, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let Some(id) = state.selected_part else {
+        return;
+    };
+
+    // Remove the link too, so its joints don't point at a missing part
+    let link_id = state.project.assembly.find_link_by_part(id).map(|l| l.id);
+    if let Some(link_id) = link_id {
+        if let Err(e) = state
+            .project
+            .assembly
+            .remove_link(link_id, reparent_children)
+        {
+            tracing::warn!("Failed to remove link of deleted part: {}", e);
         }
+        state
+            .project
+            .assembly
+            .update_world_transforms_with_current_positions();
+    }
+    state.remove_part(id);
+
+    if let Some(viewport_state) = ctx.viewport_state {
+        viewport_state.lock().remove_part(id);
+        viewport_state.lock().clear_overlays();
     }
+    sync_renderer_transforms(&state, ctx);
 }
 
 fn handle_update_part_transform(part_id: Uuid, transform: Mat4, ctx: &ActionContext) {
-    if let Some(part) = ctx.app_state.lock().get_part_mut(part_id) {
+    let mut state = ctx.app_state.lock();
+    if let Some(part) = state.get_part_mut(part_id) {
         part.origin_transform = transform;
     }
+    drop(state);
     if let Some(viewport_state) = ctx.viewport_state {
         viewport_state
             .lock()
             .update_part_transform(part_id, transform);
     }
 }
+
+fn handle_set_part_visibility(part_id: Uuid, visible: bool, ctx: &ActionContext) {
+    if let Some(part) = ctx.app_state.lock().get_part_mut(part_id) {
+        part.visible = visible;
+    }
+    if let Some(viewport_state) = ctx.viewport_state {
+        viewport_state.lock().set_part_visible(part_id, visible);
+    }
+}
+
+fn handle_isolate_selection(ctx: &ActionContext) {
+    let Some(selected) = ctx.app_state.lock().selected_part else {
+        return;
+    };
+    apply_visibility(ctx, |part_id| part_id == selected);
+}
+
+fn handle_show_all_parts(ctx: &ActionContext) {
+    apply_visibility(ctx, |_| true);
+}
+
+/// Set the visibility of every part from a predicate, in state and viewport
+fn apply_visibility(ctx: &ActionContext, is_visible: impl Fn(Uuid) -> bool) {
+    let changes: Vec<(Uuid, bool)> = {
+        let mut state = ctx.app_state.lock();
+        let part_ids: Vec<Uuid> = state.project.parts().keys().copied().collect();
+        let mut changes = Vec::new();
+        for part_id in part_ids {
+            let visible = is_visible(part_id);
+            if let Some(part) = state.project.get_part_mut(part_id)
+                && part.visible != visible
+            {
+                part.visible = visible;
+                changes.push((part_id, visible));
+            }
+        }
+        if !changes.is_empty() {
+            state.modified = true;
+        }
+        changes
+    };
+
+    if let Some(viewport_state) = ctx.viewport_state {
+        let mut vp = viewport_state.lock();
+        for (part_id, visible) in changes {
+            vp.set_part_visible(part_id, visible);
+        }
+    }
+}
+
+fn handle_add_material(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let name = (1..)
+        .map(|i| format!("material_{}", i))
+        .find(|name| state.project.get_material(name).is_none())
+        .unwrap();
+    match state
+        .project
+        .add_material(MaterialDef::new(name, [0.7, 0.7, 0.7, 1.0]))
+    {
+        Ok(()) => state.modified = true,
+        Err(e) => tracing::warn!("Failed to add material: {}", e),
+    }
+}
+
+fn handle_update_material(name: &str, material: MaterialDef, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    match state.project.update_material(name, material) {
+        Ok(part_ids) => {
+            state.modified = true;
+            if let Some(viewport_state) = ctx.viewport_state {
+                let mut vp = viewport_state.lock();
+                for part in part_ids.iter().filter_map(|id| state.get_part(*id)) {
+                    vp.update_part_color(part.id, part.display_color());
+                }
+            }
+        }
+        Err(e) => tracing::warn!("Failed to update material: {}", e),
+    }
+}
+
+fn handle_remove_material(name: &str, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    if state.project.remove_material(name).is_some() {
+        state.modified = true;
+    }
+}
+
+fn handle_assign_material(name: Option<&str>, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let part_ids = state.selected_parts.clone();
+    if part_ids.is_empty() {
+        return;
+    }
+    if let Err(e) = state.project.assign_material(&part_ids, name) {
+        tracing::warn!("Failed to assign material: {}", e);
+        return;
+    }
+    state.modified = true;
+
+    if let Some(viewport_state) = ctx.viewport_state {
+        let mut vp = viewport_state.lock();
+        for part_id in part_ids {
+            if let Some(part) = state.get_part(part_id) {
+                vp.update_part_color(part_id, part.display_color());
+            }
+        }
+    }
+}
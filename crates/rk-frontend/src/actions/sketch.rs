@@ -2,12 +2,28 @@
 //!
 //! Handles actions related to sketch editing and CAD operations.
 
+use std::collections::HashMap;
+
+use glam::{Vec2, Vec3};
 use tracing::info;
+use uuid::Uuid;
+
+use rk_cad::{
+    CadBody, CadKernel, Feature, FeatureHistory, FeatureResult, RefAxis, RefPoint, SketchEntity,
+    SketchPlane, Solid, TessellatedMesh, default_kernel,
+};
+use rk_core::{Part, par_for_each_mut};
 
-use crate::state::{AppAction, SketchAction};
+use crate::state::{
+    AppAction, CadState, DimensionDialogState, ExtrudeDialogState, HoleDialogState,
+    InProgressEntity, RevolveDialogState, ShellDialogState, SketchAction,
+};
 
 use super::ActionContext;
 
+/// Half length of the construction line a reference axis projects to
+const REF_AXIS_PROJECTION_HALF_LENGTH: f32 = 10.0;
+
 /// Handle sketch-related actions
 pub fn handle_sketch_action(action: AppAction, ctx: &ActionContext) {
     let sketch_action = match action {
@@ -21,13 +37,52 @@ pub fn handle_sketch_action(action: AppAction, ctx: &ActionContext) {
             let sketch_id = state.cad.create_sketch("Sketch", plane);
             info!("Created sketch: {}", sketch_id);
             // Automatically enter sketch mode for the new sketch
-            state.cad.enter_sketch_mode(sketch_id);
+            state.enter_sketch_mode(sketch_id);
+        }
+
+        SketchAction::SetFaceSketchPick { active } => {
+            ctx.app_state.lock().cad.face_sketch_pick = active;
+        }
+
+        SketchAction::CreateSketchOnFace { body_id, face_id } => {
+            let mut state = ctx.app_state.lock();
+            state.cad.face_sketch_pick = false;
+            let faces = state
+                .cad
+                .data
+                .history
+                .get_body(body_id)
+                .and_then(|body| body.solid.as_ref())
+                .map(|solid| default_kernel().faces(solid));
+            let face = match faces {
+                Some(Ok(faces)) => faces.into_iter().find(|face| face.id == face_id),
+                Some(Err(e)) => {
+                    state.cad.last_error = Some(e.to_string());
+                    return;
+                }
+                None => None,
+            };
+            let Some(face) = face else {
+                state.cad.last_error = Some("Picked face not found on the body".into());
+                return;
+            };
+            if !face.planar {
+                // Stay in face picking so another face can be chosen
+                state.cad.face_sketch_pick = true;
+                state.notify_error("Sketches can only be placed on flat faces");
+                return;
+            }
+
+            let plane = SketchPlane::from_origin_normal(face.centroid, face.normal);
+            let sketch_id = state.cad.create_sketch("Sketch", plane);
+            info!("Created sketch {} on face {}", sketch_id, face_id);
+            state.enter_sketch_mode(sketch_id);
         }
 
         SketchAction::EditSketch { sketch_id } => {
             let mut state = ctx.app_state.lock();
             if state.cad.get_sketch(sketch_id).is_some() {
-                state.cad.enter_sketch_mode(sketch_id);
+                state.enter_sketch_mode(sketch_id);
                 info!("Entered sketch mode for: {}", sketch_id);
             } else {
                 tracing::warn!("Sketch not found: {}", sketch_id);
@@ -60,6 +115,294 @@ pub fn handle_sketch_action(action: AppAction, ctx: &ActionContext) {
             }
         }
 
+        SketchAction::LineToolClick {
+            position,
+            point,
+            tangent_arc,
+        } => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch() else {
+                return;
+            };
+            let sketch_id = sketch_state.active_sketch;
+            let chain_end = match sketch_state.in_progress {
+                Some(InProgressEntity::Line { start_point, .. }) => Some(start_point),
+                _ => None,
+            };
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            let next = match chain_end {
+                None => Ok(point.unwrap_or_else(|| sketch.add_point(position))),
+                Some(start) if point == Some(start) => return,
+                Some(start)
+                    if matches!(sketch.get_entity(start),
+                        Some(SketchEntity::Point { position: p, .. }) if *p == position) =>
+                {
+                    return;
+                }
+                Some(start) if tangent_arc => match chain_end_curve(sketch, start) {
+                    Some(previous) => sketch
+                        .add_tangent_arc(previous, start, position)
+                        .map(|(_, end)| end),
+                    None => Err(rk_cad::SketchError::InvalidConstraint(
+                        "A tangent arc needs a preceding line or arc".into(),
+                    )),
+                },
+                Some(start) => {
+                    let end = point.unwrap_or_else(|| sketch.add_point(position));
+                    sketch.add_line(start, end);
+                    Ok(end)
+                }
+            };
+
+            match next {
+                Ok(start_point) => {
+                    if let Some(sketch_state) = state.cad.editor_mode.sketch_mut() {
+                        sketch_state.in_progress = Some(InProgressEntity::Line {
+                            start_point,
+                            preview_end: position,
+                        });
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to add tangent arc: {}", e);
+                    state.cad.last_error = Some(format!("Tangent arc failed: {e}"));
+                }
+            }
+        }
+
+        SketchAction::SplineToolClick { position } => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch() else {
+                return;
+            };
+            let sketch_id = sketch_state.active_sketch;
+            let mut control_points = match &sketch_state.in_progress {
+                Some(InProgressEntity::Spline { control_points, .. }) => control_points.clone(),
+                _ => Vec::new(),
+            };
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            // Clicking the last point again does not add a zero-length span
+            let repeated = control_points.last().is_some_and(|last| {
+                matches!(sketch.get_entity(*last),
+                    Some(SketchEntity::Point { position: p, .. }) if *p == position)
+            });
+            if repeated {
+                return;
+            }
+            control_points.push(sketch.add_point(position));
+
+            if let Some(sketch_state) = state.cad.editor_mode.sketch_mut() {
+                sketch_state.in_progress = Some(InProgressEntity::Spline {
+                    control_points,
+                    preview_end: position,
+                });
+            }
+        }
+
+        SketchAction::FinishSpline { closed } => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch_mut() else {
+                return;
+            };
+            let sketch_id = sketch_state.active_sketch;
+            let Some(InProgressEntity::Spline { control_points, .. }) =
+                sketch_state.in_progress.take()
+            else {
+                return;
+            };
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            let min_points = if closed { 3 } else { 2 };
+            if control_points.len() < min_points {
+                for point in control_points {
+                    sketch.remove_entity(point);
+                }
+                return;
+            }
+            let spline = sketch.add_spline(control_points, closed);
+            info!("Added spline: {}", spline);
+        }
+
+        SketchAction::EllipseToolClick { position } => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch() else {
+                return;
+            };
+            let sketch_id = sketch_state.active_sketch;
+            let in_progress = sketch_state.in_progress.clone();
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            let next = match in_progress {
+                // Second click places the end of the major axis
+                Some(InProgressEntity::Ellipse {
+                    center_point,
+                    major_end: None,
+                    ..
+                }) => {
+                    let Some(center) = sketch.get_entity(center_point).and_then(|e| e.position())
+                    else {
+                        return;
+                    };
+                    if center == position {
+                        return;
+                    }
+                    Some(InProgressEntity::Ellipse {
+                        center_point,
+                        major_end: Some(position),
+                        preview_end: position,
+                    })
+                }
+                // Third click sets the minor radius from the major axis
+                Some(InProgressEntity::Ellipse {
+                    center_point,
+                    major_end: Some(major_end),
+                    ..
+                }) => {
+                    let Some(center) = sketch.get_entity(center_point).and_then(|e| e.position())
+                    else {
+                        return;
+                    };
+                    let major = major_end - center;
+                    let minor_radius = major.normalize().perp_dot(position - center).abs();
+                    if minor_radius < f32::EPSILON {
+                        return;
+                    }
+                    let ellipse = sketch.add_ellipse(
+                        center_point,
+                        major.length(),
+                        minor_radius,
+                        major.to_angle(),
+                    );
+                    info!("Added ellipse: {}", ellipse);
+                    None
+                }
+                // First click places the center
+                _ => Some(InProgressEntity::Ellipse {
+                    center_point: sketch.add_point(position),
+                    major_end: None,
+                    preview_end: position,
+                }),
+            };
+
+            if let Some(sketch_state) = state.cad.editor_mode.sketch_mut() {
+                sketch_state.in_progress = next;
+            }
+        }
+
+        SketchAction::CancelDrawing => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch_mut() else {
+                return;
+            };
+            let sketch_id = sketch_state.active_sketch;
+            let placed = match sketch_state.in_progress.take() {
+                Some(InProgressEntity::Spline { control_points, .. }) => control_points,
+                Some(InProgressEntity::Ellipse { center_point, .. }) => vec![center_point],
+                _ => return,
+            };
+            if let Some(sketch) = state.cad.get_sketch_mut(sketch_id) {
+                for point in placed {
+                    sketch.remove_entity(point);
+                }
+            }
+        }
+
+        SketchAction::SelectEntity { entity, toggle } => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch_mut() else {
+                return;
+            };
+            match (entity, toggle) {
+                (Some(id), true) => sketch_state.toggle_selection(id),
+                (Some(id), false) => {
+                    sketch_state.clear_selection();
+                    sketch_state.select_entity(id);
+                }
+                (None, true) => {}
+                (None, false) => sketch_state.clear_selection(),
+            }
+        }
+
+        SketchAction::SelectEntities { entities, extend } => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch_mut() else {
+                return;
+            };
+            if !extend {
+                sketch_state.clear_selection();
+            }
+            for id in entities {
+                sketch_state.select_entity(id);
+            }
+        }
+
+        SketchAction::OpenDimensionDialog { entity } => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch() else {
+                return;
+            };
+            let Some(radius) = state
+                .cad
+                .get_sketch(sketch_state.active_sketch)
+                .and_then(|sketch| sketch.curve_radius(entity))
+            else {
+                return;
+            };
+            state.cad.dimension_dialog = Some(DimensionDialogState {
+                entity,
+                value: radius,
+                diameter: false,
+            });
+        }
+
+        SketchAction::ConfirmDimension => {
+            let mut state = ctx.app_state.lock();
+            let Some(dialog) = state.cad.dimension_dialog.take() else {
+                return;
+            };
+            let Some(sketch_state) = state.cad.editor_mode.sketch() else {
+                return;
+            };
+            let sketch_id = sketch_state.active_sketch;
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            match sketch.dimension_radius(dialog.entity, dialog.value, dialog.diameter) {
+                Ok(_) => {
+                    sketch.solve();
+                    info!(
+                        "Dimensioned {} {} = {}",
+                        dialog.entity,
+                        if dialog.diameter {
+                            "diameter"
+                        } else {
+                            "radius"
+                        },
+                        dialog.value
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to add dimension: {}", e);
+                    state.cad.last_error = Some(format!("Dimension failed: {e}"));
+                }
+            }
+        }
+
+        SketchAction::CancelDimension => {
+            ctx.app_state.lock().cad.dimension_dialog = None;
+        }
+
         SketchAction::DeleteSelected => {
             let mut state = ctx.app_state.lock();
             let (sketch_id, selected) = {
@@ -86,6 +429,135 @@ pub fn handle_sketch_action(action: AppAction, ctx: &ActionContext) {
             }
         }
 
+        SketchAction::ToggleConstruction => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch() else {
+                return;
+            };
+            let sketch_id = sketch_state.active_sketch;
+            let selected = sketch_state.selected_entities.clone();
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            // Mixed selections become construction; all-construction ones are restored
+            let construction = !selected.iter().all(|id| sketch.is_construction(*id));
+            for id in &selected {
+                sketch.set_construction(*id, construction);
+            }
+            info!(
+                "Set {} entities to {}",
+                selected.len(),
+                if construction {
+                    "construction"
+                } else {
+                    "regular"
+                }
+            );
+        }
+
+        SketchAction::MirrorSelected { axis } => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch() else {
+                return;
+            };
+            let sketch_id = sketch_state.active_sketch;
+            let selected = sketch_state.selected_entities.clone();
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            match sketch.mirror_entities(&selected, axis) {
+                Ok(created) => {
+                    info!("Mirrored {} entities", created.len());
+                    if let Some(sketch_state) = state.cad.editor_mode.sketch_mut() {
+                        sketch_state.selected_entities = created;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to mirror sketch entities: {}", e);
+                    state.cad.last_error = Some(format!("Mirror failed: {e}"));
+                }
+            }
+        }
+
+        SketchAction::OffsetSelected { distance } => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch() else {
+                return;
+            };
+            let sketch_id = sketch_state.active_sketch;
+            let selected = sketch_state.selected_entities.clone();
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            match sketch.offset_profile(&selected, distance) {
+                Ok(created) => {
+                    info!(
+                        "Offset profile by {} ({} entities)",
+                        distance,
+                        created.len()
+                    );
+                    if let Some(sketch_state) = state.cad.editor_mode.sketch_mut() {
+                        sketch_state.selected_entities = created;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to offset sketch profile: {}", e);
+                    state.cad.last_error = Some(format!("Offset failed: {e}"));
+                }
+            }
+        }
+
+        SketchAction::CopySelected => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_state) = state.cad.editor_mode.sketch() else {
+                return;
+            };
+            let selected = sketch_state.selected_entities.clone();
+            let Some(sketch) = state.cad.get_sketch(sketch_state.active_sketch) else {
+                return;
+            };
+            let clipboard = sketch.copy_entities(&selected);
+            if clipboard.is_empty() {
+                return;
+            }
+            info!("Copied {} sketch entities", clipboard.len());
+            state.cad.sketch_clipboard = Some(clipboard);
+            state.cad.paste_count = 0;
+        }
+
+        SketchAction::Paste { clipboard } => {
+            let mut state = ctx.app_state.lock();
+            let Some(clipboard) = clipboard.or_else(|| state.cad.sketch_clipboard.clone()) else {
+                return;
+            };
+            let Some(sketch_state) = state.cad.editor_mode.sketch() else {
+                return;
+            };
+            let sketch_id = sketch_state.active_sketch;
+            let offset =
+                Vec2::splat(sketch_state.grid_spacing * (state.cad.paste_count + 1) as f32);
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            match sketch.paste(&clipboard, offset) {
+                Ok(pasted) => {
+                    info!("Pasted {} sketch entities", pasted.len());
+                    state.cad.paste_count += 1;
+                    if let Some(sketch_state) = state.cad.editor_mode.sketch_mut() {
+                        sketch_state.selected_entities = pasted;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to paste sketch entities: {}", e);
+                    state.cad.last_error = Some(format!("Paste failed: {e}"));
+                }
+            }
+        }
+
         SketchAction::AddConstraint { constraint } => {
             let mut state = ctx.app_state.lock();
             if let Some(sketch_state) = state.cad.editor_mode.sketch() {
@@ -127,6 +599,8 @@ pub fn handle_sketch_action(action: AppAction, ctx: &ActionContext) {
             let mut state = ctx.app_state.lock();
             if let Some(sketch_state) = state.cad.editor_mode.sketch_mut() {
                 sketch_state.snap_to_grid = !sketch_state.snap_to_grid;
+                let snap_to_grid = sketch_state.snap_to_grid;
+                state.sketch_snap_to_grid = snap_to_grid;
             }
         }
 
@@ -135,6 +609,759 @@ pub fn handle_sketch_action(action: AppAction, ctx: &ActionContext) {
             if let Some(sketch_state) = state.cad.editor_mode.sketch_mut() {
                 sketch_state.grid_spacing = spacing;
             }
+            state.sketch_grid_spacing = spacing;
+        }
+
+        SketchAction::ToggleProjectConstruction => {
+            let mut state = ctx.app_state.lock();
+            if let Some(sketch_state) = state.cad.editor_mode.sketch_mut() {
+                sketch_state.project_construction = !sketch_state.project_construction;
+            }
+        }
+
+        SketchAction::ProjectEdge { body_id, edge_id } => {
+            let mut state = ctx.app_state.lock();
+            let Some((sketch_id, construction)) = state
+                .cad
+                .editor_mode
+                .sketch()
+                .map(|s| (s.active_sketch, s.project_construction))
+            else {
+                return;
+            };
+
+            let tolerance = state.cad_tessellation_tolerance;
+            let kernel = default_kernel();
+            let points: Option<Vec<Vec3>> = state
+                .cad
+                .data
+                .history
+                .get_body_mut(body_id)
+                .and_then(|body| body.get_mesh(kernel.as_ref(), tolerance))
+                .and_then(|mesh| mesh.edges.iter().find(|edge| edge.id == edge_id))
+                .map(|edge| edge.points.iter().copied().map(Vec3::from).collect());
+            let Some(points) = points else {
+                tracing::warn!("Edge not found: {}", edge_id);
+                return;
+            };
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            match sketch.project_polyline(&points, construction) {
+                Ok(lines) => {
+                    sketch.solve();
+                    state.modified = true;
+                    info!("Projected edge into {} sketch lines", lines.len());
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to project edge: {}", e);
+                    state.cad.last_error = Some(format!("Projection failed: {e}"));
+                }
+            }
+        }
+
+        SketchAction::OpenExtrudeDialog { sketch_id } => {
+            let mut state = ctx.app_state.lock();
+            if state.cad.get_sketch(sketch_id).is_none() {
+                tracing::warn!("Sketch not found: {}", sketch_id);
+                return;
+            }
+            let name = format!("Extrude{}", state.cad.data.history.len() + 1);
+            let previous = state
+                .cad
+                .extrude_dialog
+                .replace(ExtrudeDialogState::new(sketch_id, name));
+            drop(state);
+
+            if let Some(previous) = previous {
+                remove_preview(previous.preview_part, ctx);
+            }
+            update_extrude_preview(ctx);
+        }
+
+        SketchAction::UpdateExtrudePreview => update_extrude_preview(ctx),
+
+        SketchAction::ConfirmExtrude => {
+            let mut state = ctx.app_state.lock();
+            let Some(dialog) = state.cad.extrude_dialog.take() else {
+                return;
+            };
+            let feature = dialog.build_feature();
+
+            if let Some(feature_id) = dialog.editing {
+                info!("Edited extrude feature: {}", feature.name());
+                if let Err(e) = state.cad.data.history.replace_feature(feature_id, feature) {
+                    state.cad.last_error = Some(e.to_string());
+                }
+                // Editing rolled back to the feature; bring the later ones back
+                state.cad.data.history.rollback_to_end();
+            } else {
+                info!("Created extrude feature: {}", feature.name());
+                state.cad.data.history.add_feature(feature);
+            }
+            state.cad.rebuild();
+            state.modified = true;
+            drop(state);
+            remove_preview(dialog.preview_part, ctx);
+        }
+
+        SketchAction::CancelExtrude => {
+            let mut state = ctx.app_state.lock();
+            let Some(dialog) = state.cad.extrude_dialog.take() else {
+                return;
+            };
+            if dialog.editing.is_some() {
+                // Editing rolled back to the feature; put the marker back
+                let history = &mut state.cad.data.history;
+                let index = dialog.previous_rollback.unwrap_or(history.len());
+                history.set_rollback_index(index);
+                state.cad.rebuild();
+            }
+            drop(state);
+            remove_preview(dialog.preview_part, ctx);
+        }
+
+        SketchAction::OpenRevolveDialog { sketch_id } => {
+            let mut state = ctx.app_state.lock();
+            if state.cad.get_sketch(sketch_id).is_none() {
+                tracing::warn!("Sketch not found: {}", sketch_id);
+                return;
+            }
+            let name = format!("Revolve{}", state.cad.data.history.len() + 1);
+            let previous = state
+                .cad
+                .revolve_dialog
+                .replace(RevolveDialogState::new(sketch_id, name));
+            drop(state);
+
+            if let Some(previous) = previous {
+                remove_preview(previous.preview_part, ctx);
+            }
+            update_revolve_preview(ctx);
+        }
+
+        SketchAction::UpdateRevolvePreview => update_revolve_preview(ctx),
+
+        SketchAction::ConfirmRevolve => {
+            let mut state = ctx.app_state.lock();
+            let Some(dialog) = state.cad.revolve_dialog.as_ref() else {
+                return;
+            };
+            let feature = match state.cad.get_sketch(dialog.sketch_id) {
+                Some(sketch) => dialog.build_feature(sketch, &state.cad.data.ref_axes),
+                None => {
+                    tracing::warn!("Sketch not found: {}", dialog.sketch_id);
+                    return;
+                }
+            };
+            let feature = match feature {
+                Ok(feature) => feature,
+                Err(e) => {
+                    if let Some(dialog) = state.cad.revolve_dialog.as_mut() {
+                        dialog.preview_error = Some(e.to_string());
+                    }
+                    return;
+                }
+            };
+
+            info!("Created revolve feature: {}", feature.name());
+            state.cad.data.history.add_feature(feature);
+            state.cad.rebuild();
+
+            let preview_part = state
+                .cad
+                .revolve_dialog
+                .take()
+                .and_then(|dialog| dialog.preview_part);
+            state.modified = true;
+            drop(state);
+            remove_preview(preview_part, ctx);
+        }
+
+        SketchAction::OpenShellDialog { body_id } => {
+            let mut state = ctx.app_state.lock();
+            let body_id = body_id.or_else(|| latest_body(&state.cad.data.history));
+            let name = format!("Shell{}", state.cad.data.history.len() + 1);
+            let mut dialog = ShellDialogState::new(name, body_id);
+            refresh_shell_faces(&mut dialog, &state.cad.data.history);
+            state.cad.shell_dialog = Some(dialog);
+        }
+
+        SketchAction::UpdateShellFaces => {
+            let mut state = ctx.app_state.lock();
+            let cad = &mut state.cad;
+            if let Some(dialog) = cad.shell_dialog.as_mut() {
+                dialog.removed_faces.clear();
+                refresh_shell_faces(dialog, &cad.data.history);
+            }
+        }
+
+        SketchAction::OpenHoleDialog { body_id } => {
+            let mut state = ctx.app_state.lock();
+            let body_id = body_id.or_else(|| latest_body(&state.cad.data.history));
+            let name = format!("Hole{}", state.cad.data.history.len() + 1);
+            state.cad.hole_dialog = Some(HoleDialogState::new(name, body_id));
+        }
+
+        SketchAction::PickHolePosition { face_id, position } => {
+            let mut state = ctx.app_state.lock();
+            let cad = &mut state.cad;
+            let Some(dialog) = cad.hole_dialog.as_mut() else {
+                return;
+            };
+            let faces = dialog
+                .body_id
+                .and_then(|id| cad.data.history.get_body(id))
+                .and_then(|body| body.solid.as_ref())
+                .map(|solid| default_kernel().faces(solid));
+            match faces {
+                Some(Ok(faces)) => match faces.iter().find(|face| face.id == face_id) {
+                    Some(face) => {
+                        dialog.position = Some(position);
+                        dialog.direction = -face.normal;
+                        dialog.error = None;
+                    }
+                    None => dialog.error = Some("Picked face not found on the body".into()),
+                },
+                Some(Err(e)) => dialog.error = Some(e.to_string()),
+                None => {}
+            }
+        }
+
+        SketchAction::ConfirmHole => {
+            let mut state = ctx.app_state.lock();
+            let Some(dialog) = state.cad.hole_dialog.as_ref() else {
+                return;
+            };
+
+            // Execute once up front so invalid dimensions are reported in the dialog
+            let kernel = default_kernel();
+            let feature = dialog.build_feature().and_then(|feature| {
+                preview_feature(&feature, &state.cad, kernel.as_ref()).map(|_| feature)
+            });
+            match feature {
+                Ok(feature) => {
+                    info!("Created hole feature: {}", feature.name());
+                    state.cad.data.history.add_feature(feature);
+                    state.cad.rebuild();
+                    state.cad.hole_dialog = None;
+                    state.modified = true;
+                }
+                Err(e) => {
+                    if let Some(dialog) = state.cad.hole_dialog.as_mut() {
+                        dialog.error = Some(e.to_string());
+                    }
+                }
+            }
+        }
+
+        SketchAction::CancelHole => {
+            ctx.app_state.lock().cad.hole_dialog = None;
+        }
+
+        SketchAction::MirrorBody {
+            body_id,
+            plane_normal,
+        } => {
+            let mut state = ctx.app_state.lock();
+            let name = format!("Mirror{}", state.cad.data.history.len() + 1);
+            let feature = Feature::mirror(name, body_id, Vec3::ZERO, plane_normal);
+            info!("Created mirror feature: {}", feature.name());
+            state.cad.data.history.add_feature(feature);
+            state.cad.rebuild();
+            state.modified = true;
+        }
+
+        SketchAction::ToggleShellFace { face_id } => {
+            let mut state = ctx.app_state.lock();
+            if let Some(dialog) = state.cad.shell_dialog.as_mut() {
+                if dialog.removed_faces.contains(&face_id) {
+                    dialog.removed_faces.retain(|id| *id != face_id);
+                } else {
+                    dialog.removed_faces.push(face_id);
+                }
+            }
+        }
+
+        SketchAction::ConfirmShell => {
+            let mut state = ctx.app_state.lock();
+            let Some(dialog) = state.cad.shell_dialog.as_ref() else {
+                return;
+            };
+
+            // Execute once up front so invalid thicknesses are reported in the dialog
+            let kernel = default_kernel();
+            let feature = dialog.build_feature().and_then(|feature| {
+                preview_feature(&feature, &state.cad, kernel.as_ref()).map(|_| feature)
+            });
+            match feature {
+                Ok(feature) => {
+                    info!("Created shell feature: {}", feature.name());
+                    state.cad.data.history.add_feature(feature);
+                    state.cad.rebuild();
+                    state.cad.shell_dialog = None;
+                    state.modified = true;
+                }
+                Err(e) => {
+                    if let Some(dialog) = state.cad.shell_dialog.as_mut() {
+                        dialog.error = Some(e.to_string());
+                    }
+                }
+            }
+        }
+
+        SketchAction::CancelShell => {
+            ctx.app_state.lock().cad.shell_dialog = None;
+        }
+
+        SketchAction::MoveFeature {
+            feature_id,
+            new_index,
+        } => {
+            let mut state = ctx.app_state.lock();
+            match state.cad.data.history.move_feature(feature_id, new_index) {
+                Ok(()) => {
+                    info!("Moved feature {} to {}", feature_id, new_index);
+                    state.cad.last_error = None;
+                    state.cad.rebuild();
+                    state.modified = true;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to move feature: {}", e);
+                    state.cad.last_error = Some(e.to_string());
+                }
+            }
+        }
+
+        SketchAction::RenameFeature { feature_id, name } => {
+            let mut state = ctx.app_state.lock();
+            if let Some(feature) = state.cad.data.history.get_by_id_mut(feature_id) {
+                feature.set_name(name);
+                state.modified = true;
+            }
+        }
+
+        SketchAction::RenameBody { body_id, name } => {
+            let mut state = ctx.app_state.lock();
+            if let Some(body) = state.cad.data.history.get_body_mut(body_id) {
+                body.name = name;
+                state.modified = true;
+            }
+        }
+
+        SketchAction::SetBodyTolerance { body_id, tolerance } => {
+            let mut state = ctx.app_state.lock();
+            state
+                .cad
+                .data
+                .history
+                .set_body_tolerance(body_id, tolerance);
+            state.cad.bodies_dirty = true;
+            state.modified = true;
+        }
+
+        SketchAction::SetBodyColor { body_id, color } => {
+            let mut state = ctx.app_state.lock();
+            state.cad.data.history.set_body_color(body_id, color);
+            let Some(display_color) = state
+                .cad
+                .data
+                .history
+                .get_body(body_id)
+                .map(|body| body.display_color())
+            else {
+                return;
+            };
+            state.modified = true;
+            let rendered = state.cad.rendered_bodies.contains(&body_id);
+            drop(state);
+
+            if rendered && let Some(viewport_state) = ctx.viewport_state {
+                viewport_state
+                    .lock()
+                    .update_part_color(body_id, display_color);
+            }
+        }
+
+        SketchAction::AddRefAxis { origin, direction } => {
+            let mut state = ctx.app_state.lock();
+            let name = format!("Axis{}", state.cad.data.ref_axes.len() + 1);
+            let axis = RefAxis::new(name, origin, direction);
+            info!("Added reference axis: {}", axis.name);
+            state.cad.data.ref_axes.push(axis);
+            state.modified = true;
+        }
+
+        SketchAction::AddRefPoint { position } => {
+            let mut state = ctx.app_state.lock();
+            let name = format!("Point{}", state.cad.data.ref_points.len() + 1);
+            let point = RefPoint::new(name, position);
+            info!("Added reference point: {}", point.name);
+            state.cad.data.ref_points.push(point);
+            state.modified = true;
+        }
+
+        SketchAction::UpdateRefAxis { mut axis } => {
+            let mut state = ctx.app_state.lock();
+            if let Some(existing) = state
+                .cad
+                .data
+                .ref_axes
+                .iter_mut()
+                .find(|existing| existing.id == axis.id)
+            {
+                axis.direction = axis.direction.normalize_or(existing.direction);
+                *existing = axis;
+                state.modified = true;
+            }
+        }
+
+        SketchAction::UpdateRefPoint { point } => {
+            let mut state = ctx.app_state.lock();
+            if let Some(existing) = state
+                .cad
+                .data
+                .ref_points
+                .iter_mut()
+                .find(|existing| existing.id == point.id)
+            {
+                *existing = point;
+                state.modified = true;
+            }
+        }
+
+        SketchAction::DeleteReference { id } => {
+            let mut state = ctx.app_state.lock();
+            let data = &mut state.cad.data;
+            let count = data.ref_axes.len() + data.ref_points.len();
+            data.ref_axes.retain(|axis| axis.id != id);
+            data.ref_points.retain(|point| point.id != id);
+            if data.ref_axes.len() + data.ref_points.len() != count {
+                state.modified = true;
+            }
+        }
+
+        SketchAction::ProjectReference { id } => {
+            let mut state = ctx.app_state.lock();
+            let Some(sketch_id) = state.cad.editor_mode.sketch().map(|s| s.active_sketch) else {
+                return;
+            };
+            let axis = state.cad.data.get_ref_axis(id).cloned();
+            let point = state.cad.data.get_ref_point(id).cloned();
+            let Some(sketch) = state.cad.get_sketch_mut(sketch_id) else {
+                return;
+            };
+
+            let result = match (axis, point) {
+                (Some(axis), _) => axis.project_into(sketch, REF_AXIS_PROJECTION_HALF_LENGTH),
+                (None, Some(point)) => point.project_into(sketch),
+                (None, None) => return,
+            };
+            match result {
+                Ok(_) => {
+                    sketch.solve();
+                    state.modified = true;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to project reference: {}", e);
+                    state.cad.last_error = Some(format!("Projection failed: {e}"));
+                }
+            }
+        }
+
+        SketchAction::SetRollback { index } => {
+            let mut state = ctx.app_state.lock();
+            state.cad.data.history.set_rollback_index(index);
+            state.cad.rebuild();
+            state.modified = true;
+            info!(
+                "Rolled back to {} of {} features",
+                state.cad.data.history.effective_len(),
+                state.cad.data.history.len()
+            );
+        }
+
+        SketchAction::EditFeature { feature_id } => handle_edit_feature(feature_id, ctx),
+
+        SketchAction::ClearError => {
+            ctx.app_state.lock().cad.last_error = None;
+        }
+
+        SketchAction::CancelRevolve => {
+            let dialog = ctx.app_state.lock().cad.revolve_dialog.take();
+            if let Some(dialog) = dialog {
+                remove_preview(dialog.preview_part, ctx);
+            }
         }
     }
 }
+
+/// Tessellation tolerance for feature previews, in meters
+const PREVIEW_TOLERANCE: f32 = 0.001;
+
+/// Color of feature previews, translucent so the existing bodies show through
+const PREVIEW_COLOR: [f32; 4] = [0.4, 0.7, 1.0, 0.5];
+
+/// Roll back to just before a feature and open its dialog for editing
+///
+/// Only extrudes have an edit dialog; other features are left rolled back
+/// so the cause of their failure can be inspected.
+fn handle_edit_feature(feature_id: Uuid, ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let history = &mut state.cad.data.history;
+    let Some(index) = history.index_of(feature_id) else {
+        tracing::warn!("Feature to edit not found: {}", feature_id);
+        return;
+    };
+    let previous_rollback = history.rollback_position();
+    history.set_rollback_index(index);
+    let dialog = history
+        .get(index)
+        .and_then(ExtrudeDialogState::edit)
+        .map(|dialog| ExtrudeDialogState {
+            previous_rollback,
+            ..dialog
+        });
+    state.cad.rebuild();
+    state.modified = true;
+
+    let Some(dialog) = dialog else {
+        info!("Rolled back to before feature {}", feature_id);
+        return;
+    };
+    let previous = state.cad.extrude_dialog.replace(dialog);
+    drop(state);
+
+    if let Some(previous) = previous {
+        remove_preview(previous.preview_part, ctx);
+    }
+    update_extrude_preview(ctx);
+}
+
+/// Recompute the extrude preview mesh from the dialog state
+fn update_extrude_preview(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let Some(dialog) = state.cad.extrude_dialog.as_ref() else {
+        return;
+    };
+    let previous = dialog.preview_part;
+
+    let kernel = default_kernel();
+    let result = preview_feature(&dialog.build_feature(), &state.cad, kernel.as_ref());
+    let (preview, error) = build_preview_part("Extrude Preview", result);
+
+    if let Some(dialog) = state.cad.extrude_dialog.as_mut() {
+        dialog.preview_part = preview.as_ref().map(|part| part.id);
+        dialog.preview_error = error;
+    }
+    drop(state);
+
+    replace_preview(previous, preview, ctx);
+}
+
+/// Recompute the revolve preview mesh from the dialog state
+fn update_revolve_preview(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    let Some(dialog) = state.cad.revolve_dialog.as_ref() else {
+        return;
+    };
+    let previous = dialog.preview_part;
+
+    let kernel = default_kernel();
+    let result = match state.cad.get_sketch(dialog.sketch_id) {
+        Some(sketch) => dialog
+            .build_feature(sketch, &state.cad.data.ref_axes)
+            .and_then(|feature| preview_feature(&feature, &state.cad, kernel.as_ref())),
+        None => return,
+    };
+    let (preview, error) = build_preview_part("Revolve Preview", result);
+
+    if let Some(dialog) = state.cad.revolve_dialog.as_mut() {
+        dialog.preview_part = preview.as_ref().map(|part| part.id);
+        dialog.preview_error = error;
+    }
+    drop(state);
+
+    replace_preview(previous, preview, ctx);
+}
+
+/// Build the translucent preview part for a feature, or the error to show
+fn build_preview_part(
+    name: &str,
+    result: FeatureResult<TessellatedMesh>,
+) -> (Option<Part>, Option<String>) {
+    match result {
+        Ok(mesh) => {
+            let mut part = Part::new(name);
+            part.vertices = mesh.vertices;
+            part.normals = mesh.normals;
+            part.indices = mesh.indices;
+            part.color = PREVIEW_COLOR;
+            part.calculate_bounding_box();
+            (Some(part), None)
+        }
+        Err(e) => (None, Some(e.to_string())),
+    }
+}
+
+/// Swap the previous preview mesh in the viewport for a new one
+fn replace_preview(previous: Option<Uuid>, preview: Option<Part>, ctx: &ActionContext) {
+    remove_preview(previous, ctx);
+    if let Some(part) = preview
+        && let Some(viewport_state) = ctx.viewport_state
+    {
+        viewport_state.lock().add_part(&part);
+    }
+}
+
+/// Get the most recently created body, the default target of body dialogs
+fn latest_body(history: &FeatureHistory) -> Option<Uuid> {
+    history
+        .bodies()
+        .values()
+        .max_by_key(|b| b.source_feature.and_then(|id| history.index_of(id)))
+        .map(|b| b.id)
+}
+
+/// Enumerate the faces of the shell dialog's body
+fn refresh_shell_faces(dialog: &mut ShellDialogState, history: &FeatureHistory) {
+    dialog.faces.clear();
+    dialog.error = None;
+    let Some(solid) = dialog
+        .body_id
+        .and_then(|id| history.get_body(id))
+        .and_then(|body| body.solid.as_ref())
+    else {
+        return;
+    };
+    match default_kernel().faces(solid) {
+        Ok(faces) => dialog.faces = faces,
+        Err(e) => dialog.error = Some(e.to_string()),
+    }
+}
+
+/// Execute a feature against the current bodies and tessellate the result
+fn preview_feature(
+    feature: &Feature,
+    cad: &CadState,
+    kernel: &dyn CadKernel,
+) -> FeatureResult<TessellatedMesh> {
+    let bodies: HashMap<Uuid, Solid> = cad
+        .data
+        .history
+        .bodies()
+        .iter()
+        .filter_map(|(id, body)| body.solid.clone().map(|solid| (*id, solid)))
+        .collect();
+    let solid = feature.execute(kernel, cad.data.history.sketches(), &bodies)?;
+    Ok(kernel.tessellate(&solid, PREVIEW_TOLERANCE)?)
+}
+
+/// Update the viewport meshes of CAD bodies after a rebuild
+///
+/// Bodies are added as renderer parts under their own IDs. Only bodies that
+/// changed, are not shown yet, or need tessellating again are uploaded;
+/// the GPU meshes of all other bodies are kept as they are.
+pub fn sync_cad_bodies(ctx: &ActionContext) {
+    let mut state = ctx.app_state.lock();
+    if !state.cad.bodies_dirty {
+        return;
+    }
+    state.cad.bodies_dirty = false;
+    let Some(viewport_state) = ctx.viewport_state else {
+        return;
+    };
+
+    let tolerance = state.cad_tessellation_tolerance;
+    let kernel = default_kernel();
+    let cad = &mut state.cad;
+    let changed = std::mem::take(&mut cad.changed_bodies);
+    let history = &mut cad.data.history;
+
+    // Bodies that are gone since the last sync
+    let mut removed: Vec<Uuid> = cad
+        .rendered_bodies
+        .iter()
+        .copied()
+        .filter(|id| history.get_body(*id).is_none())
+        .collect();
+    let mut rendered: Vec<Uuid> = cad
+        .rendered_bodies
+        .iter()
+        .copied()
+        .filter(|id| !removed.contains(id))
+        .collect();
+
+    let mut stale: Vec<&mut CadBody> = history
+        .bodies_mut()
+        .filter(|body| {
+            changed.contains(&body.id)
+                || !rendered.contains(&body.id)
+                || !body.has_current_mesh(tolerance)
+        })
+        .collect();
+    // Tessellate on worker threads; uploading to the GPU stays on this thread
+    par_for_each_mut(&mut stale, |body| {
+        body.get_mesh(kernel.as_ref(), tolerance);
+    });
+
+    let mut parts = Vec::with_capacity(stale.len());
+    for body in stale {
+        let name = body.name.clone();
+        let color = body.display_color();
+        let id = body.id;
+        let Some(mesh) = body.get_mesh(kernel.as_ref(), tolerance) else {
+            // A body that can no longer be tessellated is hidden
+            if rendered.contains(&id) {
+                rendered.retain(|rendered_id| *rendered_id != id);
+                removed.push(id);
+            }
+            continue;
+        };
+        let mut part = Part::new(name);
+        part.id = id;
+        part.vertices = mesh.vertices.clone();
+        part.normals = mesh.normals.clone();
+        part.indices = mesh.indices.clone();
+        part.color = color;
+        part.calculate_bounding_box();
+        if !rendered.contains(&id) {
+            rendered.push(id);
+        }
+        parts.push(part);
+    }
+    cad.rendered_bodies = rendered;
+    drop(state);
+
+    let mut viewport = viewport_state.lock();
+    for id in removed {
+        viewport.remove_part(id);
+    }
+    // Adding a part under an existing ID replaces its mesh
+    for part in &parts {
+        viewport.add_part(part);
+    }
+}
+
+/// Remove a preview mesh from the viewport
+fn remove_preview(preview_part: Option<Uuid>, ctx: &ActionContext) {
+    if let Some(part_id) = preview_part
+        && let Some(viewport_state) = ctx.viewport_state
+    {
+        viewport_state.lock().remove_part(part_id);
+    }
+}
+
+/// Find the line or arc that a drawn chain ends in at `point`
+fn chain_end_curve(sketch: &rk_cad::Sketch, point: Uuid) -> Option<Uuid> {
+    sketch
+        .entities_iter()
+        .find(|entity| match entity {
+            SketchEntity::Line { start, end, .. } | SketchEntity::Arc { start, end, .. } => {
+                *start == point || *end == point
+            }
+            _ => false,
+        })
+        .map(|entity| entity.id())
+}
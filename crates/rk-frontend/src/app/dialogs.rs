@@ -0,0 +1,1258 @@
+//! Modal dialogs for CAD features and assembly edits
+
+use uuid::Uuid;
+
+use rk_cad::{BooleanOp, ExtrudeDirection, HoleKind, STANDARD_HOLE_SIZES, SketchEntity};
+use rk_core::JointType;
+
+use crate::panels::length_drag;
+use crate::state::{AppAction, RevolveAxis, SharedAppState, SketchAction};
+
+/// Smallest length, in meters, the feature and dimension dialogs accept
+const MIN_DIALOG_LENGTH: f32 = 0.0001;
+
+/// Boolean operations offered by feature dialogs
+const BOOLEAN_OPS: [BooleanOp; 4] = [
+    BooleanOp::New,
+    BooleanOp::Join,
+    BooleanOp::Cut,
+    BooleanOp::Intersect,
+];
+
+/// Get the display name of a boolean operation
+fn boolean_op_name(op: BooleanOp) -> &'static str {
+    match op {
+        BooleanOp::New => "New Body",
+        BooleanOp::Join => "Join",
+        BooleanOp::Cut => "Cut",
+        BooleanOp::Intersect => "Intersect",
+    }
+}
+
+/// Show the sketch dimension dialog if it is open
+pub fn show_dimension_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    let unit = state.length_unit;
+    let Some(dialog) = state.cad.dimension_dialog.as_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut confirmed = false;
+    let mut cancelled = false;
+
+    egui::Window::new("Dimension")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let mut diameter = dialog.diameter;
+                ui.selectable_value(&mut diameter, false, "Radius");
+                ui.selectable_value(&mut diameter, true, "Diameter");
+                // Switching keeps the same curve size
+                if diameter != dialog.diameter {
+                    dialog.value *= if diameter { 2.0 } else { 0.5 };
+                    dialog.diameter = diameter;
+                }
+            });
+            let response = length_drag(ui, &mut dialog.value, unit, MIN_DIALOG_LENGTH);
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                confirmed = true;
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button("OK").clicked() {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if confirmed {
+        state.queue_action(AppAction::SketchAction(SketchAction::ConfirmDimension));
+    } else if cancelled || !open {
+        state.queue_action(AppAction::SketchAction(SketchAction::CancelDimension));
+    }
+}
+
+/// Get the display name of an extrude direction
+fn extrude_direction_name(direction: ExtrudeDirection) -> &'static str {
+    match direction {
+        ExtrudeDirection::Positive => "Along Normal",
+        ExtrudeDirection::Negative => "Against Normal",
+        ExtrudeDirection::Symmetric => "Symmetric",
+    }
+}
+
+/// Show the extrude dialog if it is open
+pub fn show_extrude_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    if state.cad.extrude_dialog.is_none() {
+        return;
+    }
+
+    let mut bodies: Vec<(Uuid, String)> = state
+        .cad
+        .data
+        .history
+        .bodies()
+        .values()
+        .map(|body| (body.id, body.name.clone()))
+        .collect();
+    bodies.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let profile_count = state
+        .cad
+        .extrude_dialog
+        .as_ref()
+        .and_then(|dialog| state.cad.data.history.get_sketch(dialog.sketch_id))
+        .and_then(|sketch| sketch.extract_profiles().ok())
+        .map_or(0, |profiles| profiles.len());
+
+    let unit = state.length_unit;
+    let Some(dialog) = state.cad.extrude_dialog.as_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut changed = false;
+    let mut confirmed = false;
+    let mut cancelled = false;
+
+    let title = if dialog.editing.is_some() {
+        "Edit Extrude"
+    } else {
+        "Extrude"
+    };
+    egui::Window::new(title)
+        .id(egui::Id::new("extrude_dialog"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("extrude_dialog_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    if profile_count > 1 {
+                        ui.label("Profiles:");
+                        ui.horizontal_wrapped(|ui| {
+                            for index in 0..profile_count {
+                                let mut selected = dialog.profiles.contains(&index);
+                                // The last checked profile stays checked
+                                let last = selected && dialog.profiles.len() == 1;
+                                if ui
+                                    .add_enabled(
+                                        !last,
+                                        egui::Checkbox::new(
+                                            &mut selected,
+                                            format!("{}", index + 1),
+                                        ),
+                                    )
+                                    .changed()
+                                {
+                                    if selected {
+                                        dialog.profiles.push(index);
+                                        dialog.profiles.sort_unstable();
+                                    } else {
+                                        dialog.profiles.retain(|&i| i != index);
+                                    }
+                                    changed = true;
+                                }
+                            }
+                        });
+                        ui.end_row();
+                    }
+
+                    ui.label("Distance:");
+                    changed |=
+                        length_drag(ui, &mut dialog.distance, unit, MIN_DIALOG_LENGTH).changed();
+                    ui.end_row();
+
+                    ui.label("Direction:");
+                    egui::ComboBox::from_id_salt("extrude_direction")
+                        .selected_text(extrude_direction_name(dialog.direction))
+                        .show_ui(ui, |ui| {
+                            for direction in [
+                                ExtrudeDirection::Positive,
+                                ExtrudeDirection::Negative,
+                                ExtrudeDirection::Symmetric,
+                            ] {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut dialog.direction,
+                                        direction,
+                                        extrude_direction_name(direction),
+                                    )
+                                    .changed();
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Draft:");
+                    let mut draft_degrees = dialog.draft_angle.to_degrees();
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut draft_degrees)
+                                .speed(0.1)
+                                .range(-44.9..=44.9)
+                                .suffix("°"),
+                        )
+                        .changed()
+                    {
+                        dialog.draft_angle = draft_degrees.to_radians();
+                        changed = true;
+                    }
+                    ui.end_row();
+
+                    ui.label("Operation:");
+                    egui::ComboBox::from_id_salt("extrude_boolean_op")
+                        .selected_text(boolean_op_name(dialog.boolean_op))
+                        .show_ui(ui, |ui| {
+                            for op in BOOLEAN_OPS {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut dialog.boolean_op,
+                                        op,
+                                        boolean_op_name(op),
+                                    )
+                                    .changed();
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Target body:");
+                    ui.add_enabled_ui(dialog.boolean_op != BooleanOp::New, |ui| {
+                        let target_text = dialog
+                            .target_body
+                            .and_then(|target| bodies.iter().find(|(id, _)| *id == target))
+                            .map(|(_, name)| name.as_str())
+                            .unwrap_or("(none)");
+                        egui::ComboBox::from_id_salt("extrude_target_body")
+                            .selected_text(target_text)
+                            .show_ui(ui, |ui| {
+                                for (body_id, name) in &bodies {
+                                    changed |= ui
+                                        .selectable_value(
+                                            &mut dialog.target_body,
+                                            Some(*body_id),
+                                            name,
+                                        )
+                                        .changed();
+                                }
+                            });
+                    });
+                    ui.end_row();
+                });
+
+            if let Some(error) = &dialog.preview_error {
+                ui.add_space(4.0);
+                ui.colored_label(egui::Color32::from_rgb(255, 120, 80), error);
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button("OK").clicked() {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if confirmed {
+        state.queue_action(AppAction::SketchAction(SketchAction::ConfirmExtrude));
+    } else if cancelled || !open {
+        state.queue_action(AppAction::SketchAction(SketchAction::CancelExtrude));
+    } else if changed {
+        state.queue_action(AppAction::SketchAction(SketchAction::UpdateExtrudePreview));
+    }
+}
+
+/// Show the revolve dialog if it is open
+pub fn show_revolve_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    let Some(sketch_id) = state.cad.revolve_dialog.as_ref().map(|d| d.sketch_id) else {
+        return;
+    };
+
+    // Sketch lines usable as axes, labelled by their end points
+    let mut lines: Vec<(Uuid, String)> = state
+        .cad
+        .get_sketch(sketch_id)
+        .map(|sketch| {
+            sketch
+                .entities_iter()
+                .filter_map(|entity| {
+                    let SketchEntity::Line { id, start, end } = entity else {
+                        return None;
+                    };
+                    let start = sketch.get_entity(*start)?.position()?;
+                    let end = sketch.get_entity(*end)?.position()?;
+                    Some((
+                        *id,
+                        format!(
+                            "Line ({:.3}, {:.3}) - ({:.3}, {:.3})",
+                            start.x, start.y, end.x, end.y
+                        ),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    lines.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let mut bodies: Vec<(Uuid, String)> = state
+        .cad
+        .data
+        .history
+        .bodies()
+        .values()
+        .map(|body| (body.id, body.name.clone()))
+        .collect();
+    bodies.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let ref_axes: Vec<(Uuid, String)> = state
+        .cad
+        .data
+        .ref_axes
+        .iter()
+        .map(|axis| (axis.id, axis.name.clone()))
+        .collect();
+
+    let Some(dialog) = state.cad.revolve_dialog.as_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut changed = false;
+    let mut confirmed = false;
+    let mut cancelled = false;
+
+    egui::Window::new("Revolve")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("revolve_dialog_grid")
+                .num_columns(2)
+                .spacing([8.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut dialog.name);
+                    ui.end_row();
+
+                    ui.label("Axis:");
+                    let axis_text = match dialog.axis {
+                        RevolveAxis::SketchLine(line_id) => lines
+                            .iter()
+                            .find(|(id, _)| *id == line_id)
+                            .map(|(_, label)| label.clone())
+                            .unwrap_or_else(|| "(missing line)".to_string()),
+                        RevolveAxis::RefAxis(axis_id) => ref_axes
+                            .iter()
+                            .find(|(id, _)| *id == axis_id)
+                            .map(|(_, name)| name.clone())
+                            .unwrap_or_else(|| "(missing axis)".to_string()),
+                        axis => axis.name().to_string(),
+                    };
+                    egui::ComboBox::from_id_salt("revolve_axis")
+                        .selected_text(axis_text)
+                        .show_ui(ui, |ui| {
+                            for axis in RevolveAxis::REFERENCE {
+                                changed |= ui
+                                    .selectable_value(&mut dialog.axis, axis, axis.name())
+                                    .changed();
+                            }
+                            if !ref_axes.is_empty() {
+                                ui.separator();
+                            }
+                            for (axis_id, name) in &ref_axes {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut dialog.axis,
+                                        RevolveAxis::RefAxis(*axis_id),
+                                        name,
+                                    )
+                                    .changed();
+                            }
+                            if !lines.is_empty() {
+                                ui.separator();
+                            }
+                            for (line_id, label) in &lines {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut dialog.axis,
+                                        RevolveAxis::SketchLine(*line_id),
+                                        label,
+                                    )
+                                    .changed();
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Angle:");
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut dialog.angle_degrees)
+                                .speed(1.0)
+                                .range(0.1..=360.0)
+                                .suffix("°"),
+                        )
+                        .changed();
+                    ui.end_row();
+
+                    ui.label("Operation:");
+                    egui::ComboBox::from_id_salt("revolve_boolean_op")
+                        .selected_text(boolean_op_name(dialog.boolean_op))
+                        .show_ui(ui, |ui| {
+                            for op in BOOLEAN_OPS {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut dialog.boolean_op,
+                                        op,
+                                        boolean_op_name(op),
+                                    )
+                                    .changed();
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Target body:");
+                    ui.add_enabled_ui(dialog.boolean_op != BooleanOp::New, |ui| {
+                        let target_text = dialog
+                            .target_body
+                            .and_then(|target| bodies.iter().find(|(id, _)| *id == target))
+                            .map(|(_, name)| name.as_str())
+                            .unwrap_or("(none)");
+                        egui::ComboBox::from_id_salt("revolve_target_body")
+                            .selected_text(target_text)
+                            .show_ui(ui, |ui| {
+                                for (body_id, name) in &bodies {
+                                    changed |= ui
+                                        .selectable_value(
+                                            &mut dialog.target_body,
+                                            Some(*body_id),
+                                            name,
+                                        )
+                                        .changed();
+                                }
+                            });
+                    });
+                    ui.end_row();
+                });
+
+            if let Some(error) = &dialog.preview_error {
+                ui.add_space(4.0);
+                ui.colored_label(egui::Color32::from_rgb(255, 120, 80), error);
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button("OK").clicked() {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if confirmed {
+        state.queue_action(AppAction::SketchAction(SketchAction::ConfirmRevolve));
+    } else if cancelled || !open {
+        state.queue_action(AppAction::SketchAction(SketchAction::CancelRevolve));
+    } else if changed {
+        state.queue_action(AppAction::SketchAction(SketchAction::UpdateRevolvePreview));
+    }
+}
+
+/// Show the shell dialog if it is open
+pub fn show_shell_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    if state.cad.shell_dialog.is_none() {
+        return;
+    }
+
+    let mut bodies: Vec<(Uuid, String)> = state
+        .cad
+        .data
+        .history
+        .bodies()
+        .values()
+        .map(|body| (body.id, body.name.clone()))
+        .collect();
+    bodies.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let unit = state.length_unit;
+    let Some(dialog) = state.cad.shell_dialog.as_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut body_changed = false;
+    let mut confirmed = false;
+    let mut cancelled = false;
+
+    egui::Window::new("Shell")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("shell_dialog_grid")
+                .num_columns(2)
+                .spacing([8.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut dialog.name);
+                    ui.end_row();
+
+                    ui.label("Body:");
+                    let body_text = dialog
+                        .body_id
+                        .and_then(|body| bodies.iter().find(|(id, _)| *id == body))
+                        .map(|(_, name)| name.as_str())
+                        .unwrap_or("(none)");
+                    egui::ComboBox::from_id_salt("shell_body")
+                        .selected_text(body_text)
+                        .show_ui(ui, |ui| {
+                            for (body_id, name) in &bodies {
+                                body_changed |= ui
+                                    .selectable_value(&mut dialog.body_id, Some(*body_id), name)
+                                    .changed();
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Thickness:");
+                    length_drag(ui, &mut dialog.thickness, unit, f32::NEG_INFINITY)
+                        .on_hover_text("Positive hollows inward, negative grows the walls outward");
+                    ui.end_row();
+                });
+
+            ui.add_space(4.0);
+            ui.label("Open faces:");
+            if dialog.faces.is_empty() {
+                ui.weak("No faces available");
+            }
+            egui::ScrollArea::vertical()
+                .max_height(160.0)
+                .show(ui, |ui| {
+                    for (index, face) in dialog.faces.iter().enumerate() {
+                        let mut removed = dialog.removed_faces.contains(&face.id);
+                        let label = format!(
+                            "Face {} (normal {:.2}, {:.2}, {:.2})",
+                            index + 1,
+                            face.normal.x,
+                            face.normal.y,
+                            face.normal.z
+                        );
+                        if ui.checkbox(&mut removed, label).changed() {
+                            if removed {
+                                dialog.removed_faces.push(face.id);
+                            } else {
+                                dialog.removed_faces.retain(|id| *id != face.id);
+                            }
+                        }
+                    }
+                });
+
+            if let Some(error) = &dialog.error {
+                ui.add_space(4.0);
+                ui.colored_label(egui::Color32::from_rgb(255, 120, 80), error);
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(dialog.body_id.is_some(), egui::Button::new("OK"))
+                    .clicked()
+                {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if confirmed {
+        state.queue_action(AppAction::SketchAction(SketchAction::ConfirmShell));
+    } else if cancelled || !open {
+        state.queue_action(AppAction::SketchAction(SketchAction::CancelShell));
+    } else if body_changed {
+        state.queue_action(AppAction::SketchAction(SketchAction::UpdateShellFaces));
+    }
+}
+
+/// Show the hole dialog if it is open
+pub fn show_hole_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    if state.cad.hole_dialog.is_none() {
+        return;
+    }
+
+    let mut bodies: Vec<(Uuid, String)> = state
+        .cad
+        .data
+        .history
+        .bodies()
+        .values()
+        .map(|body| (body.id, body.name.clone()))
+        .collect();
+    bodies.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let unit = state.length_unit;
+    let Some(dialog) = state.cad.hole_dialog.as_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut confirmed = false;
+    let mut cancelled = false;
+
+    egui::Window::new("Hole")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("hole_dialog_grid")
+                .num_columns(2)
+                .spacing([8.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut dialog.name);
+                    ui.end_row();
+
+                    ui.label("Body:");
+                    let body_text = dialog
+                        .body_id
+                        .and_then(|body| bodies.iter().find(|(id, _)| *id == body))
+                        .map(|(_, name)| name.as_str())
+                        .unwrap_or("(none)");
+                    egui::ComboBox::from_id_salt("hole_body")
+                        .selected_text(body_text)
+                        .show_ui(ui, |ui| {
+                            for (body_id, name) in &bodies {
+                                if ui
+                                    .selectable_value(&mut dialog.body_id, Some(*body_id), name)
+                                    .changed()
+                                {
+                                    dialog.position = None;
+                                }
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Position:");
+                    match dialog.position {
+                        Some(p) => ui.label(format!("({:.2}, {:.2}, {:.2})", p.x, p.y, p.z)),
+                        None => ui.weak("Click a face in the viewport"),
+                    };
+                    ui.end_row();
+
+                    ui.label("Size:");
+                    let size_text = dialog
+                        .size
+                        .and_then(|index| STANDARD_HOLE_SIZES.get(index))
+                        .map_or("Custom", |size| size.name);
+                    egui::ComboBox::from_id_salt("hole_size")
+                        .selected_text(size_text)
+                        .show_ui(ui, |ui| {
+                            for (index, size) in STANDARD_HOLE_SIZES.iter().enumerate() {
+                                if ui
+                                    .selectable_label(dialog.size == Some(index), size.name)
+                                    .clicked()
+                                {
+                                    dialog.apply_size(index);
+                                }
+                            }
+                            ui.selectable_value(&mut dialog.size, None, "Custom");
+                        });
+                    ui.end_row();
+
+                    ui.label("Type:");
+                    egui::ComboBox::from_id_salt("hole_kind")
+                        .selected_text(dialog.kind.name())
+                        .show_ui(ui, |ui| {
+                            for kind in dialog.kind_options() {
+                                if ui
+                                    .selectable_label(
+                                        dialog.kind.name() == kind.name(),
+                                        kind.name(),
+                                    )
+                                    .clicked()
+                                {
+                                    dialog.kind = kind;
+                                }
+                            }
+                        });
+                    ui.end_row();
+
+                    // Editing any dimension leaves the standard size
+                    let mut custom = false;
+                    ui.label("Diameter:");
+                    custom |=
+                        length_drag(ui, &mut dialog.diameter, unit, MIN_DIALOG_LENGTH).changed();
+                    ui.end_row();
+
+                    match &mut dialog.kind {
+                        HoleKind::Simple => {}
+                        HoleKind::Counterbore { diameter, depth } => {
+                            ui.label("Counterbore diameter:");
+                            custom |= length_drag(ui, diameter, unit, MIN_DIALOG_LENGTH).changed();
+                            ui.end_row();
+                            ui.label("Counterbore depth:");
+                            custom |= length_drag(ui, depth, unit, MIN_DIALOG_LENGTH).changed();
+                            ui.end_row();
+                        }
+                        HoleKind::Countersink { diameter, angle } => {
+                            ui.label("Countersink diameter:");
+                            custom |= length_drag(ui, diameter, unit, MIN_DIALOG_LENGTH).changed();
+                            ui.end_row();
+                            ui.label("Countersink angle:");
+                            custom |= ui.drag_angle(angle).changed();
+                            ui.end_row();
+                        }
+                    }
+                    if custom {
+                        dialog.size = None;
+                    }
+
+                    ui.label("Depth:");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut dialog.through_all, "Through all");
+                        ui.add_enabled_ui(!dialog.through_all, |ui| {
+                            length_drag(ui, &mut dialog.depth, unit, MIN_DIALOG_LENGTH);
+                        });
+                    });
+                    ui.end_row();
+                });
+
+            if let Some(error) = &dialog.error {
+                ui.add_space(4.0);
+                ui.colored_label(egui::Color32::from_rgb(255, 120, 80), error);
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(dialog.position.is_some(), egui::Button::new("OK"))
+                    .clicked()
+                {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if confirmed {
+        state.queue_action(AppAction::SketchAction(SketchAction::ConfirmHole));
+    } else if cancelled || !open {
+        state.queue_action(AppAction::SketchAction(SketchAction::CancelHole));
+    }
+}
+
+/// Show the part rename dialog if it is open
+pub fn show_rename_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    let Some(dialog) = state.rename_dialog.as_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut confirmed = false;
+    let mut cancelled = false;
+
+    egui::Window::new("Rename Part")
+        .id(egui::Id::new("rename_dialog"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let response = ui.text_edit_singleline(&mut dialog.name);
+            if dialog.needs_focus {
+                response.request_focus();
+                dialog.needs_focus = false;
+            }
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                confirmed = true;
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button("OK").clicked() {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    let name = dialog.name.trim().to_string();
+    if confirmed && !name.is_empty() {
+        let part_id = dialog.part_id;
+        state.rename_dialog = None;
+        state.queue_action(AppAction::RenamePart { part_id, name });
+    } else if cancelled || !open {
+        state.rename_dialog = None;
+    }
+}
+
+/// World axis names, indexed like [`crate::state::PatternDialogState::axis`]
+const AXIS_NAMES: [&str; 3] = ["X", "Y", "Z"];
+
+/// Show the part pattern dialog if it is open
+pub fn show_pattern_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    let Some(part_id) = state.pattern_dialog.as_ref().map(|d| d.part_id) else {
+        return;
+    };
+    let Some(part_name) = state.get_part(part_id).map(|p| p.name.clone()) else {
+        state.pattern_dialog = None;
+        return;
+    };
+    let unit = state.length_unit;
+    let Some(dialog) = state.pattern_dialog.as_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut confirmed = false;
+    let mut cancelled = false;
+
+    egui::Window::new(format!("Pattern {}", part_name))
+        .id(egui::Id::new("pattern_dialog"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut dialog.circular, false, "Linear");
+                ui.selectable_value(&mut dialog.circular, true, "Circular");
+            });
+            ui.add_space(4.0);
+
+            egui::Grid::new("pattern_dialog_grid")
+                .num_columns(2)
+                .spacing([8.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Count:");
+                    ui.add(egui::DragValue::new(&mut dialog.count).range(2..=64))
+                        .on_hover_text("Number of instances, including the original");
+                    ui.end_row();
+
+                    ui.label(if dialog.circular {
+                        "Axis:"
+                    } else {
+                        "Direction:"
+                    });
+                    ui.horizontal(|ui| {
+                        for (index, name) in AXIS_NAMES.iter().enumerate() {
+                            ui.selectable_value(&mut dialog.axis, index, *name);
+                        }
+                    });
+                    ui.end_row();
+
+                    if dialog.circular {
+                        ui.label("Angle:");
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut dialog.angle_step_degrees)
+                                    .speed(1.0)
+                                    .range(-360.0..=360.0)
+                                    .suffix("°"),
+                            );
+                            if ui
+                                .button("Full Circle")
+                                .on_hover_text("Space the instances evenly around the axis")
+                                .clicked()
+                            {
+                                dialog.angle_step_degrees = 360.0 / dialog.count as f32;
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Axis origin:");
+                        ui.horizontal(|ui| {
+                            for axis in 0..3 {
+                                length_drag(
+                                    ui,
+                                    &mut dialog.axis_origin[axis],
+                                    unit,
+                                    f32::NEG_INFINITY,
+                                );
+                            }
+                        });
+                        ui.end_row();
+                    } else {
+                        ui.label("Spacing:");
+                        length_drag(ui, &mut dialog.spacing, unit, f32::NEG_INFINITY);
+                        ui.end_row();
+                    }
+                });
+
+            ui.checkbox(
+                &mut dialog.attach_to_parent,
+                "Attach copies to the same parent",
+            );
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button("OK").clicked() {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if confirmed {
+        let action = AppAction::PatternPart {
+            part_id,
+            kind: dialog.kind(),
+            attach_to_parent: dialog.attach_to_parent,
+        };
+        state.pattern_dialog = None;
+        state.queue_action(action);
+    } else if cancelled || !open {
+        state.pattern_dialog = None;
+    }
+}
+
+/// Show the joint creation dialog if it is open
+pub fn show_create_joint_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    if state.create_joint_dialog.is_none() {
+        return;
+    }
+    let mut parts: Vec<(Uuid, String)> = state
+        .project
+        .parts()
+        .values()
+        .map(|part| (part.id, part.name.clone()))
+        .collect();
+    parts.sort_by(|a, b| a.1.cmp(&b.1));
+    let Some(dialog) = state.create_joint_dialog.as_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut confirmed = false;
+    let mut cancelled = false;
+
+    egui::Window::new("Create Joint")
+        .id(egui::Id::new("create_joint_dialog"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("create_joint_dialog_grid")
+                .num_columns(2)
+                .spacing([8.0, 4.0])
+                .show(ui, |ui| {
+                    for (label, salt, choice) in [
+                        ("Parent:", "create_joint_parent", &mut dialog.parent),
+                        ("Child:", "create_joint_child", &mut dialog.child),
+                    ] {
+                        ui.label(label);
+                        let selected = choice
+                            .and_then(|id| parts.iter().find(|(part_id, _)| *part_id == id))
+                            .map_or("Select part...", |(_, name)| name.as_str());
+                        egui::ComboBox::from_id_salt(salt)
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for (id, name) in &parts {
+                                    ui.selectable_value(choice, Some(*id), name);
+                                }
+                            });
+                        ui.end_row();
+                    }
+
+                    ui.label("Type:");
+                    egui::ComboBox::from_id_salt("create_joint_type")
+                        .selected_text(dialog.joint_type.display_name())
+                        .show_ui(ui, |ui| {
+                            for joint_type in JointType::all() {
+                                ui.selectable_value(
+                                    &mut dialog.joint_type,
+                                    *joint_type,
+                                    joint_type.display_name(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                });
+
+            ui.weak("The joint is placed at the child's current pose");
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let valid = dialog.parent.is_some()
+                    && dialog.child.is_some()
+                    && dialog.parent != dialog.child;
+                if ui.add_enabled(valid, egui::Button::new("Create")).clicked() {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if confirmed && let (Some(parent), Some(child)) = (dialog.parent, dialog.child) {
+        let action = AppAction::CreateJoint {
+            parent,
+            child,
+            joint_type: dialog.joint_type,
+        };
+        state.create_joint_dialog = None;
+        state.queue_action(action);
+    } else if cancelled || !open {
+        state.create_joint_dialog = None;
+    }
+}
+
+/// Show the turntable export dialog if it is open
+#[cfg(not(target_arch = "wasm32"))]
+pub fn show_turntable_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    let Some(dialog) = state.turntable_dialog.as_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut confirmed = false;
+    let mut cancelled = false;
+
+    egui::Window::new("Export Turntable")
+        .id(egui::Id::new("turntable_dialog"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("turntable_dialog_grid")
+                .num_columns(2)
+                .spacing([8.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Frames:");
+                    ui.add(egui::DragValue::new(&mut dialog.frames).range(2..=3600))
+                        .on_hover_text("Number of frames in one full revolution");
+                    ui.end_row();
+
+                    ui.label("Duration:");
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut dialog.duration_secs)
+                                .speed(0.1)
+                                .range(0.1..=120.0)
+                                .suffix(" s"),
+                        );
+                        ui.weak(format!("{:.1} fps", dialog.fps()));
+                    });
+                    ui.end_row();
+
+                    ui.label("Output:");
+                    ui.horizontal(|ui| {
+                        let label = dialog
+                            .output_dir
+                            .as_ref()
+                            .map(|dir| dir.display().to_string())
+                            .unwrap_or_else(|| "(none)".to_string());
+                        ui.label(label);
+                        if ui.button("Browse...").clicked()
+                            && let Some(dir) = rfd::FileDialog::new().pick_folder()
+                        {
+                            dialog.output_dir = Some(dir);
+                        }
+                    });
+                    ui.end_row();
+                });
+
+            ui.weak("Frames are rendered at the current viewport size");
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(dialog.output_dir.is_some(), egui::Button::new("Export"))
+                    .clicked()
+                {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if confirmed && let Some(output_dir) = dialog.output_dir.clone() {
+        let action = AppAction::ExportTurntable {
+            output_dir,
+            frames: dialog.frames,
+            duration_secs: dialog.duration_secs,
+        };
+        state.turntable_dialog = None;
+        state.queue_action(action);
+    } else if cancelled || !open {
+        state.turntable_dialog = None;
+    }
+}
+
+/// Ask whether a mesh is imported as a new visual part or as collision
+/// geometry of the selected link
+#[cfg(not(target_arch = "wasm32"))]
+pub fn show_import_mesh_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    let Some(dialog) = state.import_mesh_dialog.as_ref() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut action = None;
+    let mut cancelled = false;
+
+    egui::Window::new("Import Mesh")
+        .id(egui::Id::new("import_mesh_dialog"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let file = dialog
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default();
+            ui.label(format!("Import {} as:", file));
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui
+                    .button("Import as Visual")
+                    .on_hover_text("Add the mesh as a new part")
+                    .clicked()
+                {
+                    action = Some(AppAction::ImportMesh(dialog.path.clone()));
+                }
+                if ui
+                    .button("Import as Collision")
+                    .on_hover_text(format!(
+                        "Use the mesh as collision geometry of {}",
+                        dialog.link_name
+                    ))
+                    .clicked()
+                {
+                    action = Some(AppAction::ImportCollisionMesh {
+                        path: dialog.path.clone(),
+                        link_id: dialog.link_id,
+                    });
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if let Some(action) = action {
+        state.import_mesh_dialog = None;
+        state.queue_action(action);
+    } else if cancelled || !open {
+        state.import_mesh_dialog = None;
+    }
+}
+
+/// Show the issues found before URDF export, with automatic fixes
+#[cfg(not(target_arch = "wasm32"))]
+pub fn show_export_check_dialog(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    let Some(dialog) = state.export_check_dialog.as_ref() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut fix = false;
+    let mut export = false;
+    let mut cancelled = false;
+
+    egui::Window::new("Export Check")
+        .id(egui::Id::new("export_check_dialog"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            if dialog.issues.is_empty() {
+                ui.label("No issues found.");
+            }
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("export_check_grid")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for issue in &dialog.issues {
+                                let (icon, color) = match issue.severity {
+                                    rk_core::IssueSeverity::Error => {
+                                        ("✖", egui::Color32::from_rgb(255, 120, 80))
+                                    }
+                                    rk_core::IssueSeverity::Warning => {
+                                        ("⚠", ui.visuals().warn_fg_color)
+                                    }
+                                };
+                                ui.colored_label(color, format!("{} {}", icon, issue.subject));
+                                ui.label(&issue.message);
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            if dialog.has_errors() {
+                ui.add_space(4.0);
+                ui.weak("Errors must be fixed before the robot can be exported");
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(dialog.has_fixes(), egui::Button::new("Fix Automatically"))
+                    .on_hover_text("Reset broken axes, repair limits and recompute inertias")
+                    .clicked()
+                {
+                    fix = true;
+                }
+                let label = if dialog.issues.is_empty() {
+                    "Export"
+                } else {
+                    "Export Anyway"
+                };
+                if ui
+                    .add_enabled(!dialog.has_errors(), egui::Button::new(label))
+                    .clicked()
+                {
+                    export = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if fix {
+        state.queue_action(AppAction::FixExportIssues);
+    } else if export && let Some(dialog) = state.export_check_dialog.take() {
+        state.queue_action(AppAction::ExportUrdf {
+            path: dialog.path,
+            robot_name: dialog.robot_name,
+        });
+    } else if cancelled || !open {
+        state.export_check_dialog = None;
+    }
+}
@@ -1,9 +1,13 @@
 //! Dock layout and tab viewer
 
 use egui_dock::{DockState, NodeIndex, TabViewer};
+use serde::{Deserialize, Serialize};
 
 use crate::config::SharedConfig;
-use crate::panels::{JointListPanel, Panel, PartListPanel, PropertiesPanel, ViewportPanel};
+use crate::panels::{
+    FeatureTreePanel, JointListPanel, MaterialsPanel, Panel, PartListPanel, PropertiesPanel,
+    StatisticsPanel, ViewportPanel,
+};
 use crate::state::{SharedAppState, SharedViewportState};
 
 /// Panel types for the dock system
@@ -12,6 +16,9 @@ pub enum PanelType {
     PartList(PartListPanel),
     JointList(JointListPanel),
     Properties(PropertiesPanel),
+    FeatureTree(FeatureTreePanel),
+    Statistics(StatisticsPanel),
+    Materials(MaterialsPanel),
 }
 
 impl PanelType {
@@ -21,6 +28,73 @@ impl PanelType {
             PanelType::PartList(p) => p.name(),
             PanelType::JointList(p) => p.name(),
             PanelType::Properties(p) => p.name(),
+            PanelType::FeatureTree(p) => p.name(),
+            PanelType::Statistics(p) => p.name(),
+            PanelType::Materials(p) => p.name(),
+        }
+    }
+
+    /// Get the kind of this panel
+    pub fn kind(&self) -> PanelKind {
+        match self {
+            PanelType::Viewport(_) => PanelKind::Viewport,
+            PanelType::PartList(_) => PanelKind::PartList,
+            PanelType::JointList(_) => PanelKind::JointList,
+            PanelType::Properties(_) => PanelKind::Properties,
+            PanelType::FeatureTree(_) => PanelKind::FeatureTree,
+            PanelType::Statistics(_) => PanelKind::Statistics,
+            PanelType::Materials(_) => PanelKind::Materials,
+        }
+    }
+}
+
+/// Panel identity without UI state, used to persist the layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PanelKind {
+    Viewport,
+    PartList,
+    JointList,
+    Properties,
+    FeatureTree,
+    Statistics,
+    Materials,
+}
+
+impl PanelKind {
+    /// All panels, in View menu order
+    pub const ALL: [PanelKind; 7] = [
+        PanelKind::Viewport,
+        PanelKind::PartList,
+        PanelKind::JointList,
+        PanelKind::Properties,
+        PanelKind::FeatureTree,
+        PanelKind::Statistics,
+        PanelKind::Materials,
+    ];
+
+    /// Display name, matching the panel's tab title
+    pub fn label(self) -> &'static str {
+        match self {
+            PanelKind::Viewport => "3D Viewport",
+            PanelKind::PartList => "Parts",
+            PanelKind::JointList => "Joints",
+            PanelKind::Properties => "Properties",
+            PanelKind::FeatureTree => "Features",
+            PanelKind::Statistics => "Statistics",
+            PanelKind::Materials => "Materials",
+        }
+    }
+
+    /// Create a fresh panel of this kind
+    pub fn create(self) -> PanelType {
+        match self {
+            PanelKind::Viewport => PanelType::Viewport(ViewportPanel::new()),
+            PanelKind::PartList => PanelType::PartList(PartListPanel::new()),
+            PanelKind::JointList => PanelType::JointList(JointListPanel::new()),
+            PanelKind::Properties => PanelType::Properties(PropertiesPanel::new()),
+            PanelKind::FeatureTree => PanelType::FeatureTree(FeatureTreePanel::new()),
+            PanelKind::Statistics => PanelType::Statistics(StatisticsPanel::new()),
+            PanelKind::Materials => PanelType::Materials(MaterialsPanel::new()),
         }
     }
 }
@@ -59,6 +133,9 @@ impl TabViewer for UrdfTabViewer<'_> {
             }
             PanelType::PartList(panel) => panel.ui(ui, self.app_state),
             PanelType::JointList(panel) => panel.ui(ui, self.app_state),
+            PanelType::FeatureTree(panel) => panel.ui(ui, self.app_state),
+            PanelType::Statistics(panel) => panel.ui(ui, self.app_state),
+            PanelType::Materials(panel) => panel.ui(ui, self.app_state),
             PanelType::Properties(panel) => {
                 if let (Some(render_state), Some(viewport_state)) =
                     (self.render_state, self.viewport_state)
@@ -108,3 +185,44 @@ pub fn create_dock_layout() -> DockState<PanelType> {
 
     dock_state
 }
+
+/// Toggle a panel: close it if it is open, otherwise add it to the focused leaf
+pub fn toggle_panel(dock_state: &mut DockState<PanelType>, kind: PanelKind) {
+    match dock_state.find_tab_from(|tab| tab.kind() == kind) {
+        Some(location) => {
+            dock_state.remove_tab(location);
+        }
+        None => dock_state.push_to_focused_leaf(kind.create()),
+    }
+}
+
+/// Panels currently open in the layout
+pub fn open_panels(dock_state: &DockState<PanelType>) -> Vec<PanelKind> {
+    dock_state
+        .iter_all_tabs()
+        .map(|(_, tab)| tab.kind())
+        .collect()
+}
+
+/// Serialize the layout (splits, sizes and open panels) to RON
+pub fn save_dock_layout(dock_state: &DockState<PanelType>) -> Option<String> {
+    let layout = dock_state.map_tabs(|tab| tab.kind());
+    match ron::to_string(&layout) {
+        Ok(layout) => Some(layout),
+        Err(e) => {
+            tracing::warn!("Failed to serialize dock layout: {}", e);
+            None
+        }
+    }
+}
+
+/// Restore a layout saved by [`save_dock_layout`], with fresh panels
+pub fn load_dock_layout(layout: &str) -> Option<DockState<PanelType>> {
+    match ron::from_str::<DockState<PanelKind>>(layout) {
+        Ok(layout) => Some(layout.map_tabs(|kind| kind.create())),
+        Err(e) => {
+            tracing::warn!("Ignoring saved dock layout: {}", e);
+            None
+        }
+    }
+}
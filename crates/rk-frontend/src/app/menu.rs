@@ -1,9 +1,71 @@
 //! Menu bar rendering
 
-use crate::state::{AppAction, SharedAppState};
+use rk_core::{AlignEdge, AlignReference};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::state::ImportMeshDialogState;
+#[cfg(target_arch = "wasm32")]
+use crate::state::project_file;
+use crate::state::{AppAction, CreateJointDialogState, SharedAppState};
+
+use super::dock::PanelKind;
+
+/// Import a picked mesh file as a new part, or, while a link is selected,
+/// ask whether it is a visual part or collision geometry of that link
+#[cfg(not(target_arch = "wasm32"))]
+fn request_mesh_import(app_state: &SharedAppState, path: std::path::PathBuf) {
+    let mut state = app_state.lock();
+    let link = state
+        .selected_part
+        .and_then(|part_id| state.project.assembly.find_link_by_part(part_id))
+        .map(|link| (link.id, link.name.clone()));
+    match link {
+        Some((link_id, link_name)) => {
+            state.import_mesh_dialog = Some(ImportMeshDialogState {
+                path,
+                link_id,
+                link_name,
+            });
+        }
+        None => state.queue_action(AppAction::ImportMesh(path)),
+    }
+}
+
+/// Render a tab per open document, below the menu bar
+pub fn render_document_tabs(ctx: &egui::Context, app_state: &SharedAppState) {
+    let (titles, active) = {
+        let state = app_state.lock();
+        (state.document_titles(), state.active_document)
+    };
+
+    egui::TopBottomPanel::top("document_tabs").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            let mut action = None;
+            for (index, title) in titles.iter().enumerate() {
+                if ui.selectable_label(index == active, title).clicked() {
+                    action = Some(AppAction::SwitchDocument(index));
+                }
+                if ui.small_button("x").on_hover_text("Close tab").clicked() {
+                    action = Some(AppAction::CloseDocument(index));
+                }
+                ui.separator();
+            }
+            if ui.small_button("+").on_hover_text("New tab").clicked() {
+                action = Some(AppAction::NewDocument);
+            }
+            if let Some(action) = action {
+                app_state.lock().queue_action(action);
+            }
+        });
+    });
+}
 
 /// Render the menu bar and return any triggered action
-pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Option<MenuAction> {
+pub fn render_menu_bar(
+    ctx: &egui::Context,
+    app_state: &SharedAppState,
+    open_panels: &[PanelKind],
+) -> Option<MenuAction> {
     let mut menu_action = None;
 
     egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
@@ -13,6 +75,16 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
                     app_state.lock().queue_action(AppAction::NewProject);
                     ui.close();
                 }
+                if ui.button("New Tab").clicked() {
+                    app_state.lock().queue_action(AppAction::NewDocument);
+                    ui.close();
+                }
+                if ui.button("Close Tab").clicked() {
+                    let mut state = app_state.lock();
+                    let index = state.active_document;
+                    state.queue_action(AppAction::CloseDocument(index));
+                    ui.close();
+                }
                 #[cfg(not(target_arch = "wasm32"))]
                 {
                     if ui.button("Open Project...").clicked() {
@@ -46,7 +118,7 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
                                 .add_filter("STL files", &["stl", "STL"])
                                 .pick_file()
                             {
-                                app_state.lock().queue_action(AppAction::ImportMesh(path));
+                                request_mesh_import(app_state, path);
                             }
                             ui.close();
                         }
@@ -55,7 +127,7 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
                                 .add_filter("OBJ files", &["obj", "OBJ"])
                                 .pick_file()
                             {
-                                app_state.lock().queue_action(AppAction::ImportMesh(path));
+                                request_mesh_import(app_state, path);
                             }
                             ui.close();
                         }
@@ -64,7 +136,16 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
                                 .add_filter("DAE files", &["dae", "DAE"])
                                 .pick_file()
                             {
-                                app_state.lock().queue_action(AppAction::ImportMesh(path));
+                                request_mesh_import(app_state, path);
+                            }
+                            ui.close();
+                        }
+                        if ui.button("PLY...").clicked() {
+                            if let Some(path) = rfd::FileDialog::new()
+                                .add_filter("PLY files", &["ply", "PLY"])
+                                .pick_file()
+                            {
+                                request_mesh_import(app_state, path);
                             }
                             ui.close();
                         }
@@ -79,6 +160,41 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
                         }
                         ui.close();
                     }
+                    if ui
+                        .button("Merge Project...")
+                        .on_hover_text(
+                            "Add another project or URDF, attached to the selected part's link",
+                        )
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("RK Project or URDF", &["rk", "urdf", "xacro", "xml"])
+                            .add_filter("All files", &["*"])
+                            .pick_file()
+                        {
+                            let mut state = app_state.lock();
+                            let assembly = &state.project.assembly;
+                            let parent_link = state
+                                .selected_part
+                                .and_then(|id| assembly.find_link_by_part(id))
+                                .map(|link| link.id)
+                                .or_else(|| assembly.root_link());
+                            state.queue_action(AppAction::MergeProject { path, parent_link });
+                        }
+                        ui.close();
+                    }
+                    if ui.button("Load Joint State...").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("YAML", &["yaml", "yml"])
+                            .add_filter("All files", &["*"])
+                            .pick_file()
+                        {
+                            app_state
+                                .lock()
+                                .queue_action(AppAction::LoadJointState { path });
+                        }
+                        ui.close();
+                    }
                     if ui.button("Export URDF...").clicked() {
                         let default_name = app_state.lock().project.name.clone();
                         if let Some(path) = rfd::FileDialog::new()
@@ -97,13 +213,60 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
                                 .parent()
                                 .map(|p| p.to_path_buf())
                                 .unwrap_or_else(|| std::path::PathBuf::from("."));
-                            app_state.lock().queue_action(AppAction::ExportUrdf {
+                            app_state.lock().queue_action(AppAction::CheckExportUrdf {
                                 path: output_dir,
                                 robot_name,
                             });
                         }
                         ui.close();
                     }
+                    if ui.button("Export Merged STL...").clicked() {
+                        let default_name = app_state.lock().project.name.clone();
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("STL", &["stl"])
+                            .set_file_name(format!("{}.stl", default_name))
+                            .save_file()
+                        {
+                            app_state
+                                .lock()
+                                .queue_action(AppAction::ExportMergedStl(path));
+                        }
+                        ui.close();
+                    }
+                    if ui.button("Export COLLADA...").clicked() {
+                        let default_name = app_state.lock().project.name.clone();
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("COLLADA", &["dae"])
+                            .set_file_name(format!("{}.dae", default_name))
+                            .save_file()
+                        {
+                            app_state.lock().queue_action(AppAction::ExportDae(path));
+                        }
+                        ui.close();
+                    }
+                    if ui.button("Export Bill of Materials...").clicked() {
+                        let default_name = app_state.lock().project.name.clone();
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("CSV", &["csv"])
+                            .set_file_name(format!("{}_bom.csv", default_name))
+                            .save_file()
+                        {
+                            app_state.lock().queue_action(AppAction::ExportBom { path });
+                        }
+                        ui.close();
+                    }
+                    if ui.button("Export Turntable...").clicked() {
+                        app_state.lock().turntable_dialog = Some(Default::default());
+                        ui.close();
+                    }
+                    ui.menu_button("Mesh Export Unit", |ui| {
+                        let current = app_state.lock().stl_export_unit;
+                        for unit in rk_core::StlUnit::ALL {
+                            if ui.selectable_label(current == *unit, unit.name()).clicked() {
+                                menu_action = Some(MenuAction::SetMeshExportUnit(*unit));
+                            }
+                        }
+                    });
                     ui.separator();
                     if ui.button("Exit").clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
@@ -134,8 +297,7 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
                             // Serialize project to bytes
                             let data = {
                                 let state = app_state.lock();
-                                // No sync needed - parts are stored directly in project
-                                match state.project.to_bytes() {
+                                match project_file::to_bytes(&state.project, &state.cad.data) {
                                     Ok(data) => data,
                                     Err(e) => {
                                         tracing::error!("Failed to serialize project: {}", e);
@@ -195,9 +357,8 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
                             let (urdf_content, robot_name) = {
                                 let state = app_state.lock();
                                 let robot_name = state.project.name.clone();
-                                match rk_core::export_urdf_to_string(
-                                    &state.project.assembly,
-                                    state.project.parts(),
+                                match rk_core::export_project_urdf_to_string(
+                                    &state.project,
                                     &robot_name,
                                 ) {
                                     Ok(urdf) => (urdf, robot_name),
@@ -228,8 +389,64 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
             });
 
             ui.menu_button("Edit", |ui| {
+                let (has_selection, has_clipboard) = {
+                    let state = app_state.lock();
+                    (
+                        !state.selected_parts.is_empty(),
+                        state.part_clipboard.is_some(),
+                    )
+                };
+                if ui
+                    .add_enabled(has_selection, egui::Button::new("Copy Parts"))
+                    .clicked()
+                {
+                    app_state.lock().queue_action(AppAction::CopySelectedParts);
+                    ui.close();
+                }
+                if ui
+                    .add_enabled(has_clipboard, egui::Button::new("Paste Parts"))
+                    .on_hover_text("Paste into the active tab")
+                    .clicked()
+                {
+                    app_state.lock().queue_action(AppAction::PasteParts);
+                    ui.close();
+                }
+                ui.separator();
                 if ui.button("Delete Selected").clicked() {
-                    app_state.lock().queue_action(AppAction::DeleteSelectedPart);
+                    app_state
+                        .lock()
+                        .queue_action(AppAction::DeleteSelectedPart {
+                            reparent_children: true,
+                        });
+                    ui.close();
+                }
+                ui.menu_button("Align", |ui| render_align_menu(ui, app_state));
+                if ui
+                    .button("Create Joint...")
+                    .on_hover_text("Joint two parts where they are")
+                    .clicked()
+                {
+                    let mut state = app_state.lock();
+                    let mut selected = state.selected_parts.iter().copied();
+                    state.create_joint_dialog = Some(CreateJointDialogState {
+                        parent: selected.next(),
+                        child: selected.next(),
+                        ..Default::default()
+                    });
+                    ui.close();
+                }
+                ui.separator();
+                if ui
+                    .button("Recompute All Inertias")
+                    .on_hover_text(
+                        "Set every link's inertia and center of mass from its mesh \
+                         (links with open meshes are skipped)",
+                    )
+                    .clicked()
+                {
+                    app_state
+                        .lock()
+                        .queue_action(AppAction::RecomputeAllInertias);
                     ui.close();
                 }
                 ui.separator();
@@ -240,6 +457,23 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
             });
 
             ui.menu_button("View", |ui| {
+                if ui.button("Isolate Selected").clicked() {
+                    app_state.lock().queue_action(AppAction::IsolateSelection);
+                    ui.close();
+                }
+                if ui.button("Show All Parts").clicked() {
+                    app_state.lock().queue_action(AppAction::ShowAllParts);
+                    ui.close();
+                }
+                ui.separator();
+                ui.menu_button("Panels", |ui| {
+                    for kind in PanelKind::ALL {
+                        let mut open = open_panels.contains(&kind);
+                        if ui.checkbox(&mut open, kind.label()).clicked() {
+                            menu_action = Some(MenuAction::TogglePanel(kind));
+                        }
+                    }
+                });
                 if ui.button("Reset Layout").clicked() {
                     menu_action = Some(MenuAction::ResetLayout);
                     ui.close();
@@ -251,8 +485,69 @@ pub fn render_menu_bar(ctx: &egui::Context, app_state: &SharedAppState) -> Optio
     menu_action
 }
 
+/// Align and distribute commands for the multi-selection
+fn render_align_menu(ui: &mut egui::Ui, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    let selected_count = state.selected_parts.len();
+
+    ui.label("Align to:");
+    ui.radio_value(
+        &mut state.align_reference,
+        AlignReference::FirstSelected,
+        "First Selected",
+    );
+    ui.radio_value(
+        &mut state.align_reference,
+        AlignReference::SelectionBounds,
+        "Selection Bounds",
+    );
+    ui.separator();
+
+    let reference = state.align_reference;
+    let mut action = None;
+    ui.add_enabled_ui(selected_count >= 2, |ui| {
+        for (axis, axis_name) in AXIS_NAMES.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(*axis_name);
+                for edge in AlignEdge::ALL {
+                    if ui.button(edge.name()).clicked() {
+                        action = Some(AppAction::AlignSelectedParts {
+                            axis,
+                            edge: *edge,
+                            reference,
+                        });
+                    }
+                }
+            });
+        }
+    });
+    ui.separator();
+
+    ui.add_enabled_ui(selected_count >= 3, |ui| {
+        for (axis, axis_name) in AXIS_NAMES.iter().enumerate() {
+            if ui.button(format!("Distribute {}", axis_name)).clicked() {
+                action = Some(AppAction::DistributeSelectedParts { axis });
+            }
+        }
+    });
+
+    if selected_count < 2 {
+        ui.weak("Ctrl-click to select several parts");
+    }
+
+    if let Some(action) = action {
+        state.queue_action(action);
+        ui.close();
+    }
+}
+
+const AXIS_NAMES: [&str; 3] = ["X", "Y", "Z"];
+
 /// Actions triggered by the menu
 pub enum MenuAction {
     ResetLayout,
+    TogglePanel(PanelKind),
     OpenPreferences,
+    /// Choose the unit of exported meshes, remembered across sessions
+    SetMeshExportUnit(rk_core::StlUnit),
 }
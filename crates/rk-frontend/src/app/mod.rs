@@ -1,7 +1,9 @@
 //! Main application module
 
+mod dialogs;
 mod dock;
 mod menu;
+mod notifications;
 mod overlays;
 mod welcome;
 
@@ -10,15 +12,18 @@ use std::sync::Arc;
 use egui_dock::{DockArea, DockState, Style};
 use parking_lot::Mutex;
 
-use crate::actions::{ActionContext, dispatch_action};
-use crate::config::{SharedConfig, create_shared_config};
+use crate::actions::{ActionContext, dispatch_action, sync_cad_bodies};
+use crate::config::{SharedConfig, apply_theme, create_shared_config};
 use crate::panels::PreferencesPanel;
 use crate::state::{SharedAppState, SharedViewportState, ViewportState, create_shared_state};
 use crate::update::{SharedUpdateStatus, UpdateStatus, check_for_updates, create_update_status};
 use welcome::WelcomeDialog;
 
-pub use dock::{PanelType, UrdfTabViewer, create_dock_layout};
-pub use menu::{MenuAction, render_menu_bar};
+pub use dock::{
+    PanelKind, PanelType, UrdfTabViewer, create_dock_layout, load_dock_layout, open_panels,
+    save_dock_layout, toggle_panel,
+};
+pub use menu::{MenuAction, render_document_tabs, render_menu_bar};
 pub use overlays::update_overlays;
 
 /// Storage key for tracking first launch
@@ -40,6 +45,8 @@ pub struct UrdfEditorApp {
     preferences_panel: PreferencesPanel,
     /// Whether preferences window is open
     preferences_open: bool,
+    /// Dock layout as last written to the config
+    saved_layout: Option<String>,
 }
 
 impl UrdfEditorApp {
@@ -48,6 +55,9 @@ impl UrdfEditorApp {
         // Load configuration
         let config = create_shared_config();
 
+        // Apply the saved theme and font scale
+        apply_theme(&cc.egui_ctx, &config.read().config().ui);
+
         // Create viewport state if WGPU is available
         let viewport_state = cc.wgpu_render_state.as_ref().map(|render_state| {
             let device = Arc::new(render_state.device.clone());
@@ -55,15 +65,23 @@ impl UrdfEditorApp {
             let format = render_state.target_format;
 
             let mut vp_state = ViewportState::new(device, queue, format);
+            vp_state
+                .renderer
+                .detect_sample_counts(&render_state.adapter, &vp_state.device);
 
-            // Apply renderer config from saved settings
+            // Apply renderer config from saved settings, keeping the MSAA
+            // count the GPU actually rendered with
             {
-                let cfg = config.read();
+                let mut cfg = config.write();
                 vp_state.renderer.apply_config(
                     &cfg.config().renderer,
                     &vp_state.device,
                     &vp_state.queue,
                 );
+                let sample_count = vp_state.renderer.sample_count();
+                if cfg.config().renderer.viewport.msaa_sample_count != sample_count {
+                    cfg.config_mut().renderer.viewport.msaa_sample_count = sample_count;
+                }
             }
 
             Arc::new(Mutex::new(vp_state))
@@ -76,12 +94,28 @@ impl UrdfEditorApp {
             let mut state = app_state.lock();
             state.show_part_axes = cfg.config().editor.show_part_axes;
             state.show_joint_markers = cfg.config().editor.show_joint_markers;
+            state.show_center_of_mass = cfg.config().editor.show_center_of_mass;
             state.angle_display_mode = cfg.config().editor.angle_display_mode;
+            state.length_unit = cfg.config().editor.length_unit;
             state.stl_import_unit = cfg.config().editor.stl_import_unit;
+            state.stl_export_unit = cfg.config().editor.stl_export_unit;
+            state.export_precision = cfg.config().editor.export_precision;
+            state.primitive_quality = cfg.config().editor.primitive_quality;
+            state.cad_tessellation_tolerance = cfg.config().editor.cad_tessellation_tolerance;
+            state.snap_part_translation = cfg.config().editor.snap_part_translation;
+            state.part_snap_increment = cfg.config().editor.part_snap_increment;
+            state.nudge_step = cfg.config().editor.nudge_step;
+            state.sketch_auto_solve = cfg.config().editor.sketch_auto_solve;
+            state.sketch_snap_to_grid = cfg.config().editor.sketch_snap_to_grid;
+            state.sketch_grid_spacing = cfg.config().editor.sketch_grid_spacing;
         }
 
-        // Create dock layout
-        let dock_state = create_dock_layout();
+        // Restore the dock layout from the last session
+        let saved_layout = config.read().config().ui.dock_layout.clone();
+        let dock_state = saved_layout
+            .as_deref()
+            .and_then(load_dock_layout)
+            .unwrap_or_else(create_dock_layout);
 
         // Start update check in background
         let update_status = create_update_status();
@@ -103,6 +137,46 @@ impl UrdfEditorApp {
             config,
             preferences_panel: PreferencesPanel::new(),
             preferences_open: false,
+            saved_layout,
+        }
+    }
+
+    /// Write the dock layout to the config if it changed since the last save
+    fn persist_layout(&mut self) {
+        let layout = save_dock_layout(&self.dock_state);
+        if layout.is_none() || layout == self.saved_layout {
+            return;
+        }
+        let mut config = self.config.write();
+        config.config_mut().ui.dock_layout = layout.clone();
+        if let Err(e) = config.save() {
+            tracing::warn!("Failed to save dock layout: {}", e);
+        }
+        self.saved_layout = layout;
+    }
+
+    /// Save sketch grid settings changed from the sketch panel to the config
+    ///
+    /// Waits until the pointer is released, so dragging the spacing saves
+    /// once at the end instead of on every frame.
+    fn persist_sketch_grid(&mut self, ctx: &egui::Context) {
+        let (snap_to_grid, grid_spacing) = {
+            let state = self.app_state.lock();
+            (state.sketch_snap_to_grid, state.sketch_grid_spacing)
+        };
+        let mut config = self.config.write();
+        let editor = &config.config().editor;
+        if (editor.sketch_snap_to_grid == snap_to_grid
+            && editor.sketch_grid_spacing == grid_spacing)
+            || ctx.input(|i| i.pointer.any_down())
+        {
+            return;
+        }
+        let editor = &mut config.config_mut().editor;
+        editor.sketch_snap_to_grid = snap_to_grid;
+        editor.sketch_grid_spacing = grid_spacing;
+        if let Err(e) = config.save() {
+            tracing::warn!("Failed to save sketch grid settings: {}", e);
         }
     }
 
@@ -114,6 +188,15 @@ impl UrdfEditorApp {
         for action in actions {
             dispatch_action(action, &ctx);
         }
+
+        // Show bodies rebuilt by the actions, including after a project load
+        sync_cad_bodies(&ctx);
+
+        // Surface features that failed to rebuild
+        let mut state = self.app_state.lock();
+        for failure in std::mem::take(&mut state.cad.unreported_failures) {
+            state.notify_error(failure);
+        }
     }
 
     /// Show update notification banner
@@ -160,19 +243,47 @@ impl eframe::App for UrdfEditorApp {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         // Process pending actions
         self.process_actions();
+        self.persist_sketch_grid(ctx);
+
+        // Solve the sketch being edited once edits settle
+        {
+            let mut state = self.app_state.lock();
+            if state.sketch_auto_solve
+                && let Some(delay) = state.cad.auto_solve(ctx.input(|i| i.time))
+            {
+                ctx.request_repaint_after_secs(delay as f32);
+            }
+        }
 
         // Menu bar
-        if let Some(menu_action) = render_menu_bar(ctx, &self.app_state) {
+        let panels = open_panels(&self.dock_state);
+        let mut layout_changed = false;
+        if let Some(menu_action) = render_menu_bar(ctx, &self.app_state, &panels) {
             match menu_action {
                 MenuAction::ResetLayout => {
                     self.dock_state = create_dock_layout();
+                    layout_changed = true;
+                }
+                MenuAction::TogglePanel(kind) => {
+                    toggle_panel(&mut self.dock_state, kind);
+                    layout_changed = true;
                 }
                 MenuAction::OpenPreferences => {
                     self.preferences_open = true;
                 }
+                MenuAction::SetMeshExportUnit(unit) => {
+                    self.app_state.lock().stl_export_unit = unit;
+                    let mut config = self.config.write();
+                    config.config_mut().editor.stl_export_unit = unit;
+                    if let Err(e) = config.save() {
+                        tracing::warn!("Failed to save mesh export unit: {}", e);
+                    }
+                }
             }
         }
 
+        render_document_tabs(ctx, &self.app_state);
+
         // Update notification banner
         if !self.update_dismissed {
             self.show_update_banner(ctx);
@@ -193,9 +304,32 @@ impl eframe::App for UrdfEditorApp {
                 },
             );
 
+        // Splits, tab moves and closes all finish with a pointer release
+        if layout_changed || ctx.input(|i| i.pointer.any_released()) {
+            self.persist_layout();
+        }
+
         // Update overlays when selection changes
         update_overlays(&self.app_state, &self.viewport_state);
 
+        // Feature dialogs
+        dialogs::show_extrude_dialog(ctx, &self.app_state);
+        dialogs::show_revolve_dialog(ctx, &self.app_state);
+        dialogs::show_shell_dialog(ctx, &self.app_state);
+        dialogs::show_hole_dialog(ctx, &self.app_state);
+        dialogs::show_dimension_dialog(ctx, &self.app_state);
+        dialogs::show_rename_dialog(ctx, &self.app_state);
+        dialogs::show_pattern_dialog(ctx, &self.app_state);
+        dialogs::show_create_joint_dialog(ctx, &self.app_state);
+        #[cfg(not(target_arch = "wasm32"))]
+        dialogs::show_turntable_dialog(ctx, &self.app_state);
+        #[cfg(not(target_arch = "wasm32"))]
+        dialogs::show_export_check_dialog(ctx, &self.app_state);
+        #[cfg(not(target_arch = "wasm32"))]
+        dialogs::show_import_mesh_dialog(ctx, &self.app_state);
+
+        notifications::show_notifications(ctx, &self.app_state);
+
         // Welcome dialog (shown on first launch)
         self.welcome_dialog.show(ctx);
 
@@ -0,0 +1,46 @@
+//! Toast notifications in the bottom-right corner of the window
+
+use crate::state::{NotificationKind, SharedAppState};
+
+/// Draw the queued notifications, dropping the ones that timed out or were
+/// clicked
+pub fn show_notifications(ctx: &egui::Context, app_state: &SharedAppState) {
+    let mut state = app_state.lock();
+    let now = ctx.input(|i| i.time);
+    state.notifications.retain(|n| !n.expired(now));
+    if state.notifications.is_empty() {
+        return;
+    }
+
+    let mut dismissed = None;
+    egui::Area::new(egui::Id::new("notifications"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            for (index, notification) in state.notifications.iter_mut().enumerate() {
+                let shown_at = *notification.shown_at.get_or_insert(now);
+                let color = match notification.kind {
+                    NotificationKind::Success => egui::Color32::from_rgb(100, 200, 100),
+                    NotificationKind::Error => egui::Color32::from_rgb(255, 120, 80),
+                };
+                let response = egui::Frame::popup(ui.style())
+                    .show(ui, |ui| {
+                        ui.set_max_width(320.0);
+                        ui.colored_label(color, &notification.message);
+                    })
+                    .response
+                    .interact(egui::Sense::click())
+                    .on_hover_text("Click to dismiss");
+                if response.clicked() {
+                    dismissed = Some(index);
+                }
+
+                let remaining = shown_at + notification.duration() - now;
+                ctx.request_repaint_after_secs(remaining.max(0.0) as f32);
+            }
+        });
+
+    if let Some(index) = dismissed {
+        state.notifications.remove(index);
+    }
+}
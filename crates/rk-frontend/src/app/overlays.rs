@@ -1,6 +1,18 @@
 //! Overlay update logic
 
-use crate::state::{SharedAppState, SharedViewportState};
+use std::f32::consts::TAU;
+
+use glam::{Mat4, Quat, Vec2, Vec3, Vec4};
+
+use parking_lot::MutexGuard;
+use rk_cad::{CadData, SPLINE_SEGMENTS, Sketch, SketchEntity, sample_ellipse, sample_spline};
+use rk_core::{CollisionMesh, GeometryType, JointType};
+use rk_renderer::sub_renderers::sketch::flags;
+use rk_renderer::{JointAxisInstance, JointAxisKind, MarkerInstance, SketchRenderData};
+
+use crate::state::{
+    AppState, InProgressEntity, SharedAppState, SharedViewportState, SketchModeState,
+};
 
 /// Update overlays based on current selection
 pub fn update_overlays(app_state: &SharedAppState, viewport_state: &Option<SharedViewportState>) {
@@ -10,7 +22,71 @@ pub fn update_overlays(app_state: &SharedAppState, viewport_state: &Option<Share
 
     let state = app_state.lock();
 
-    // First check if a collision is selected (takes priority over part selection)
+    // Collision previews for every visible link
+    let (collision_shapes, collision_meshes) = collect_collision_shapes(&state);
+    viewport_state
+        .lock()
+        .update_collision_shapes(&collision_shapes, &collision_meshes);
+
+    // Joint axes and limit ranges
+    let joint_axes = if state.show_joint_markers {
+        collect_joint_axes(&state)
+    } else {
+        Vec::new()
+    };
+    viewport_state.lock().update_joint_axes(&joint_axes);
+
+    // Sketch being edited, then reference geometry
+    let mut sketches: Vec<SketchRenderData> = state
+        .cad
+        .editor_mode
+        .sketch()
+        .and_then(|mode| {
+            let sketch = state.cad.get_sketch(mode.active_sketch)?;
+            Some(sketch_to_render_data(sketch, mode))
+        })
+        .into_iter()
+        .collect();
+    sketches.extend(reference_render_data(&state.cad.data));
+    viewport_state.lock().update_sketches(sketches);
+
+    // Center of mass, following the links as joints move
+    let center_of_mass = state
+        .show_center_of_mass
+        .then(|| state.project.statistics().center_of_mass)
+        .flatten();
+
+    update_selection_overlays(state, viewport_state);
+    viewport_state
+        .lock()
+        .update_center_of_mass(&center_of_mass_markers(center_of_mass));
+}
+
+/// Show the gizmo and axes for the selected joint origin, collision or part
+fn update_selection_overlays(state: MutexGuard<AppState>, viewport_state: &SharedViewportState) {
+    // Exploded parts are drawn away from where the gizmo would edit them
+    if viewport_state.lock().is_exploded() {
+        drop(state);
+        viewport_state.lock().clear_overlays();
+        return;
+    }
+
+    // A joint origin being moved takes priority over any selection
+    if let Some(joint_id) = state.joint_origin_drag
+        && let Some(joint) = state.project.assembly.get_joint(joint_id)
+        && let Some(parent) = state.project.assembly.get_link(joint.parent_link)
+    {
+        let parent_world_transform = parent.world_transform;
+        let joint_origin = joint.origin.to_mat4();
+        drop(state);
+
+        let mut vp = viewport_state.lock();
+        vp.update_axes(&[]);
+        vp.show_gizmo_for_joint(joint_id, parent_world_transform, joint_origin);
+        return;
+    }
+
+    // Then check if a collision is selected (takes priority over part selection)
     if let Some((link_id, collision_index)) = state.selected_collision
         && let Some(link) = state.project.assembly.get_link(link_id)
         && let Some(collision) = link.collisions.get(collision_index)
@@ -21,8 +97,7 @@ pub fn update_overlays(app_state: &SharedAppState, viewport_state: &Option<Share
 
         let mut vp = viewport_state.lock();
         // Clear part-specific overlays but keep gizmo for collision
-        let queue = vp.queue.clone();
-        vp.renderer.update_axes(&queue, &[]);
+        vp.update_axes(&[]);
         vp.show_gizmo_for_collision(
             link_id,
             collision_index,
@@ -51,3 +126,302 @@ pub fn update_overlays(app_state: &SharedAppState, viewport_state: &Option<Share
     drop(state);
     viewport_state.lock().clear_overlays();
 }
+
+/// Radius of the center of mass marker (meters)
+const CENTER_OF_MASS_RADIUS: f32 = 0.02;
+/// Color of the center of mass marker
+const CENTER_OF_MASS_COLOR: [f32; 4] = [1.0, 0.2, 0.8, 1.0];
+/// Color of the center of mass projected onto the floor
+const CENTER_OF_MASS_FLOOR_COLOR: [f32; 4] = [0.6, 0.1, 0.5, 1.0];
+
+/// Markers for the center of mass and its projection onto the floor (z = 0)
+///
+/// The projection shows where the center of mass falls relative to the
+/// robot's support polygon.
+fn center_of_mass_markers(center_of_mass: Option<Vec3>) -> Vec<MarkerInstance> {
+    let Some(com) = center_of_mass else {
+        return Vec::new();
+    };
+    vec![
+        MarkerInstance::new(com, CENTER_OF_MASS_RADIUS, CENTER_OF_MASS_COLOR),
+        MarkerInstance::new(
+            Vec3::new(com.x, com.y, 0.0),
+            CENTER_OF_MASS_RADIUS * 0.5,
+            CENTER_OF_MASS_FLOOR_COLOR,
+        ),
+    ]
+}
+
+/// Imported collision mesh: world transform, mesh and whether it is selected
+type CollisionMeshRef<'a> = (Mat4, &'a CollisionMesh, bool);
+
+/// Collect world transform, geometry and selection state of all collisions on visible links
+///
+/// Imported collision meshes are returned apart from the primitive shapes.
+fn collect_collision_shapes(
+    state: &AppState,
+) -> (Vec<(Mat4, GeometryType, bool)>, Vec<CollisionMeshRef<'_>>) {
+    let mut shapes = Vec::new();
+    let mut meshes = Vec::new();
+    for link in state.project.assembly.links.values() {
+        let hidden = link
+            .part_id
+            .and_then(|part_id| state.get_part(part_id))
+            .is_some_and(|part| !part.visible);
+        if hidden {
+            continue;
+        }
+        for (index, collision) in link.collisions.iter().enumerate() {
+            let selected = state.selected_collision == Some((link.id, index));
+            let transform = link.world_transform * collision.origin.to_mat4();
+            match (&collision.mesh, &collision.geometry) {
+                (Some(mesh), GeometryType::Mesh { scale, .. }) => {
+                    let scale = scale.map_or(Vec3::ONE, Vec3::from);
+                    meshes.push((transform * Mat4::from_scale(scale), mesh, selected));
+                }
+                _ => shapes.push((transform, collision.geometry.clone(), selected)),
+            }
+        }
+    }
+    (shapes, meshes)
+}
+
+/// Arrow length of joint axis indicators (meters)
+const JOINT_AXIS_LENGTH: f32 = 0.15;
+
+/// Collect axis indicators for every movable joint whose child link is visible
+fn collect_joint_axes(state: &AppState) -> Vec<JointAxisInstance> {
+    let assembly = &state.project.assembly;
+    let mut axes = Vec::new();
+    for joint in assembly.joints.values() {
+        let kind = match joint.joint_type {
+            JointType::Revolute => {
+                let limits = joint.limits.unwrap_or_default();
+                JointAxisKind::Revolute {
+                    lower: limits.lower,
+                    upper: limits.upper,
+                }
+            }
+            JointType::Continuous => JointAxisKind::Continuous,
+            JointType::Prismatic => {
+                let limits = joint.limits.unwrap_or_default();
+                JointAxisKind::Prismatic {
+                    lower: limits.lower,
+                    upper: limits.upper,
+                }
+            }
+            JointType::Fixed | JointType::Floating | JointType::Planar | JointType::Spherical => {
+                continue;
+            }
+        };
+        let hidden = assembly
+            .get_link(joint.child_link)
+            .and_then(|link| link.part_id)
+            .and_then(|part_id| state.get_part(part_id))
+            .is_some_and(|part| !part.visible);
+        if hidden {
+            continue;
+        }
+        let Some(parent) = assembly.get_link(joint.parent_link) else {
+            continue;
+        };
+        axes.push(JointAxisInstance {
+            transform: parent.world_transform * joint.origin.to_mat4(),
+            axis: joint.axis,
+            kind,
+            scale: JOINT_AXIS_LENGTH,
+        });
+    }
+    axes
+}
+
+/// Color of reference axes and points
+const REFERENCE_COLOR: Vec4 = Vec4::new(1.0, 0.75, 0.2, 1.0);
+/// Length drawn on each side of a reference axis origin
+const REFERENCE_AXIS_HALF_LENGTH: f32 = 10.0;
+/// Dash length of reference axes
+const REFERENCE_AXIS_DASH: f32 = 0.25;
+
+/// Build renderer geometry for reference axes and points
+///
+/// Each item is drawn in its own frame: axes as a dashed line along the
+/// frame's X axis, points as a single point at the frame origin.
+fn reference_render_data(data: &CadData) -> Vec<SketchRenderData> {
+    let axes = data.ref_axes.iter().map(|axis| {
+        let rotation = Quat::from_rotation_arc(Vec3::X, axis.direction);
+        let mut render = SketchRenderData::new(
+            axis.id,
+            Mat4::from_rotation_translation(rotation, axis.origin),
+        );
+        render.add_polyline(
+            &[
+                Vec2::new(-REFERENCE_AXIS_HALF_LENGTH, 0.0),
+                Vec2::new(REFERENCE_AXIS_HALF_LENGTH, 0.0),
+            ],
+            REFERENCE_COLOR,
+            0,
+            Some(REFERENCE_AXIS_DASH),
+        );
+        render.add_point(Vec2::ZERO, REFERENCE_COLOR, 0);
+        render
+    });
+    let points = data.ref_points.iter().map(|point| {
+        let mut render = SketchRenderData::new(point.id, Mat4::from_translation(point.position));
+        render.add_point(Vec2::ZERO, REFERENCE_COLOR, 0);
+        render
+    });
+    axes.chain(points).collect()
+}
+
+/// Color of regular sketch geometry that is fully constrained
+const SKETCH_COLOR: Vec4 = Vec4::new(0.9, 0.9, 0.9, 1.0);
+/// Color of regular sketch geometry that can still move
+const UNDER_CONSTRAINED_COLOR: Vec4 = Vec4::new(0.3, 0.55, 1.0, 1.0);
+/// Color of construction geometry, dimmed further by the sketch shader
+const CONSTRUCTION_COLOR: Vec4 = Vec4::new(0.45, 0.65, 1.0, 1.0);
+/// Line segments used for full circles and ellipses
+const CURVE_SEGMENTS: u32 = 64;
+/// Construction dash length relative to the sketch grid spacing
+const DASH_FRACTION: f32 = 0.25;
+
+/// Build renderer geometry for a sketch in edit mode
+///
+/// Construction geometry is drawn dashed in its own color and flagged so the
+/// shader renders it semi-transparent. Once solved, entities that still have
+/// freedom are drawn blue; unsolved sketches use the regular color.
+fn sketch_to_render_data(sketch: &Sketch, mode: &SketchModeState) -> SketchRenderData {
+    let mut data = SketchRenderData::new(sketch.id, sketch.plane.transform());
+    data.is_active = true;
+    let point = |id| match sketch.get_entity(id) {
+        Some(SketchEntity::Point { position, .. }) => Some(*position),
+        _ => None,
+    };
+
+    for entity in sketch.entities_iter() {
+        let id = entity.id();
+        let construction = sketch.is_construction(id);
+        let mut entity_flags = 0;
+        if mode.selected_entities.contains(&id) {
+            entity_flags |= flags::SELECTED;
+        }
+        if mode.hovered_entity == Some(id) {
+            entity_flags |= flags::HOVERED;
+        }
+        let (color, dash) = if construction {
+            entity_flags |= flags::CONSTRUCTION;
+            (CONSTRUCTION_COLOR, Some(mode.grid_spacing * DASH_FRACTION))
+        } else if sketch.is_solved() && sketch.is_entity_free(id) {
+            (UNDER_CONSTRAINED_COLOR, None)
+        } else {
+            (SKETCH_COLOR, None)
+        };
+
+        let polyline: Vec<Vec2> = match entity {
+            SketchEntity::Point { position, .. } => {
+                data.add_point(*position, color, entity_flags);
+                continue;
+            }
+            SketchEntity::Line { start, end, .. } => {
+                [point(*start), point(*end)].into_iter().flatten().collect()
+            }
+            SketchEntity::Arc {
+                center,
+                start,
+                end,
+                radius,
+                ..
+            } => {
+                let (Some(c), Some(s), Some(e)) = (point(*center), point(*start), point(*end))
+                else {
+                    continue;
+                };
+                let a0 = (s - c).to_angle();
+                let mut sweep = (e - c).to_angle() - a0;
+                if sweep <= 0.0 {
+                    sweep += TAU;
+                }
+                let segments = ((sweep / TAU) * CURVE_SEGMENTS as f32).ceil().max(1.0) as u32;
+                (0..=segments)
+                    .map(|i| {
+                        c + Vec2::from_angle(a0 + sweep * i as f32 / segments as f32) * *radius
+                    })
+                    .collect()
+            }
+            SketchEntity::Circle { center, radius, .. } => {
+                let Some(c) = point(*center) else {
+                    continue;
+                };
+                (0..=CURVE_SEGMENTS)
+                    .map(|i| c + Vec2::from_angle(TAU * i as f32 / CURVE_SEGMENTS as f32) * *radius)
+                    .collect()
+            }
+            SketchEntity::Ellipse { .. } => sketch.ellipse_polyline(id).unwrap_or_default(),
+            SketchEntity::Spline { .. } => sketch.spline_polyline(id).unwrap_or_default(),
+        };
+        data.add_polyline(&polyline, color, entity_flags, dash);
+    }
+
+    // Rubber band from the last placed point of the line tool
+    if let Some(InProgressEntity::Line {
+        start_point,
+        preview_end,
+    }) = &mode.in_progress
+        && let Some(start) = point(*start_point)
+    {
+        data.add_polyline(&[start, *preview_end], SKETCH_COLOR, flags::HOVERED, None);
+    }
+
+    // Ellipse axis, then the full ellipse, following the cursor
+    if let Some(InProgressEntity::Ellipse {
+        center_point,
+        major_end,
+        preview_end,
+    }) = &mode.in_progress
+        && let Some(center) = point(*center_point)
+    {
+        let outline = match major_end {
+            None => vec![center, *preview_end],
+            Some(major_end) => {
+                let major = *major_end - center;
+                let minor = major
+                    .normalize_or_zero()
+                    .perp_dot(*preview_end - center)
+                    .abs();
+                sample_ellipse(
+                    center,
+                    major.length(),
+                    minor,
+                    major.to_angle(),
+                    CURVE_SEGMENTS,
+                )
+            }
+        };
+        data.add_polyline(&outline, SKETCH_COLOR, flags::HOVERED, None);
+    }
+
+    // Spline through the placed control points and the cursor
+    if let Some(InProgressEntity::Spline {
+        control_points,
+        preview_end,
+    }) = &mode.in_progress
+    {
+        let mut points: Vec<Vec2> = control_points.iter().filter_map(|id| point(*id)).collect();
+        points.push(*preview_end);
+        let curve = sample_spline(&points, false, SPLINE_SEGMENTS);
+        data.add_polyline(&curve, SKETCH_COLOR, flags::HOVERED, None);
+    }
+
+    // Lasso being dragged, closed back to its start
+    if let Some(&first) = mode.lasso.first() {
+        let mut path = mode.lasso.clone();
+        path.push(first);
+        data.add_polyline(
+            &path,
+            SKETCH_COLOR,
+            flags::HOVERED,
+            Some(mode.grid_spacing * DASH_FRACTION),
+        );
+    }
+
+    data
+}
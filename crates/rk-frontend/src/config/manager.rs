@@ -75,19 +75,44 @@ impl ConfigManager {
     }
 
     /// Load configuration from a file path
+    ///
+    /// Older configs are migrated to [`AppConfig::CURRENT_VERSION`] and
+    /// rewritten. A config that cannot be parsed is moved aside to a `.bak`
+    /// file so the defaults can be saved without losing it.
     #[cfg(not(target_arch = "wasm32"))]
     fn load_from_path(path: &PathBuf) -> Option<AppConfig> {
         let content = std::fs::read_to_string(path).ok()?;
-        match ron::from_str(&content) {
-            Ok(config) => {
-                tracing::info!("Loaded config from {:?}", path);
-                Some(config)
-            }
+        let mut config: AppConfig = match ron::from_str(&content) {
+            Ok(config) => config,
             Err(e) => {
                 tracing::warn!("Failed to parse config file: {}", e);
-                None
+                let backup = path.with_extension("rk.bak");
+                match std::fs::rename(path, &backup) {
+                    Ok(()) => tracing::warn!("Moved unreadable config to {:?}", backup),
+                    Err(e) => tracing::warn!("Failed to back up config file: {}", e),
+                }
+                return None;
+            }
+        };
+        tracing::info!("Loaded config from {:?}", path);
+
+        let version = config.version;
+        if config.migrate() {
+            tracing::info!(
+                "Migrated config from version {} to {}",
+                version,
+                AppConfig::CURRENT_VERSION
+            );
+            let mut manager = Self {
+                config: config.clone(),
+                config_path: path.clone(),
+                dirty: true,
+            };
+            if let Err(e) = manager.save() {
+                tracing::warn!("Failed to rewrite migrated config: {}", e);
             }
         }
+        Some(config)
     }
 
     #[cfg(target_arch = "wasm32")]
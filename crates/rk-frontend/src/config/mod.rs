@@ -7,11 +7,13 @@ mod manager;
 
 pub use manager::{ConfigError, ConfigManager, SharedConfig, create_shared_config};
 
-use rk_core::StlUnit;
-use rk_renderer::config::RendererConfig;
+use std::ops::RangeInclusive;
+
+use rk_core::{PrimitiveQuality, StlUnit};
+use rk_renderer::config::{GridConfig, RendererConfig, ViewportConfig};
 use serde::{Deserialize, Serialize};
 
-use crate::state::AngleDisplayMode;
+use crate::state::{AngleDisplayMode, LengthUnit};
 
 /// Editor preferences
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -20,10 +22,79 @@ pub struct EditorConfig {
     pub show_part_axes: bool,
     /// Show joint point markers
     pub show_joint_markers: bool,
+    /// Show the center of mass of the whole assembly
+    #[serde(default)]
+    pub show_center_of_mass: bool,
     /// Angle display mode for joint sliders
     pub angle_display_mode: AngleDisplayMode,
+    /// Unit lengths are shown and entered in
+    #[serde(default)]
+    pub length_unit: LengthUnit,
     /// Default unit for STL import
     pub stl_import_unit: StlUnit,
+    /// Unit meshes are written in on URDF export
+    #[serde(default = "default_stl_export_unit")]
+    pub stl_export_unit: StlUnit,
+    /// Significant digits numbers are written with on URDF export, or
+    /// `None` for full precision
+    #[serde(default = "default_export_precision")]
+    pub export_precision: Option<u32>,
+    /// Tessellation level for URDF cylinder, sphere and capsule geometry
+    #[serde(default)]
+    pub primitive_quality: PrimitiveQuality,
+    /// Tessellation tolerance for CAD bodies
+    #[serde(default = "default_tessellation_tolerance")]
+    pub cad_tessellation_tolerance: f32,
+    /// Snap gizmo part translations to a grid
+    #[serde(default)]
+    pub snap_part_translation: bool,
+    /// Grid increment for part translation snapping (meters)
+    #[serde(default = "default_part_snap_increment")]
+    pub part_snap_increment: f32,
+    /// Distance the arrow keys move the selected part (meters)
+    #[serde(default = "default_nudge_step")]
+    pub nudge_step: f32,
+    /// Solve sketches automatically shortly after each edit
+    #[serde(default = "default_sketch_auto_solve")]
+    pub sketch_auto_solve: bool,
+    /// Snap sketch drawing tools to the grid
+    #[serde(default = "default_sketch_snap_to_grid")]
+    pub sketch_snap_to_grid: bool,
+    /// Sketch grid spacing used for drawing and snapping
+    #[serde(default = "default_sketch_grid_spacing")]
+    pub sketch_grid_spacing: f32,
+}
+
+fn default_stl_export_unit() -> StlUnit {
+    StlUnit::Meters
+}
+
+fn default_export_precision() -> Option<u32> {
+    Some(rk_core::DEFAULT_PRECISION)
+}
+
+fn default_tessellation_tolerance() -> f32 {
+    0.01
+}
+
+fn default_part_snap_increment() -> f32 {
+    0.01
+}
+
+fn default_nudge_step() -> f32 {
+    0.01
+}
+
+fn default_sketch_auto_solve() -> bool {
+    true
+}
+
+fn default_sketch_snap_to_grid() -> bool {
+    true
+}
+
+fn default_sketch_grid_spacing() -> f32 {
+    1.0
 }
 
 impl Default for EditorConfig {
@@ -31,8 +102,20 @@ impl Default for EditorConfig {
         Self {
             show_part_axes: true,
             show_joint_markers: true,
+            show_center_of_mass: false,
             angle_display_mode: AngleDisplayMode::Degrees,
+            length_unit: LengthUnit::default(),
             stl_import_unit: StlUnit::Millimeters,
+            stl_export_unit: default_stl_export_unit(),
+            export_precision: default_export_precision(),
+            primitive_quality: PrimitiveQuality::default(),
+            cad_tessellation_tolerance: default_tessellation_tolerance(),
+            snap_part_translation: false,
+            part_snap_increment: default_part_snap_increment(),
+            nudge_step: default_nudge_step(),
+            sketch_auto_solve: default_sketch_auto_solve(),
+            sketch_snap_to_grid: default_sketch_snap_to_grid(),
+            sketch_grid_spacing: default_sketch_grid_spacing(),
         }
     }
 }
@@ -45,6 +128,32 @@ pub enum UiTheme {
     Light,
 }
 
+impl UiTheme {
+    /// Viewport background colors that suit this theme
+    pub fn background_preset(&self) -> ViewportConfig {
+        match self {
+            UiTheme::Dark => ViewportConfig::dark_background(),
+            UiTheme::Light => ViewportConfig::light_background(),
+        }
+    }
+
+    /// Grid line colors that suit this theme
+    pub fn grid_preset(&self) -> GridConfig {
+        match self {
+            UiTheme::Dark => GridConfig::dark_lines(),
+            UiTheme::Light => GridConfig::light_lines(),
+        }
+    }
+
+    /// Switch the renderer's background and grid colors to this theme
+    pub fn apply_to_renderer(&self, renderer: &mut RendererConfig) {
+        renderer
+            .viewport
+            .apply_background_preset(&self.background_preset());
+        renderer.grid.apply_color_preset(&self.grid_preset());
+    }
+}
+
 /// UI preferences
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct UiConfig {
@@ -52,6 +161,27 @@ pub struct UiConfig {
     pub theme: UiTheme,
     /// Font size multiplier
     pub font_size: f32,
+    /// Dock layout from the last session (RON), or `None` for the default
+    #[serde(default)]
+    pub dock_layout: Option<String>,
+}
+
+impl UiConfig {
+    /// Allowed font scale multipliers
+    pub const FONT_SCALE_RANGE: RangeInclusive<f32> = 0.5..=3.0;
+
+    /// Font scale clamped to [`Self::FONT_SCALE_RANGE`], so hand-edited
+    /// configs cannot make the UI unusable
+    pub fn font_scale(&self) -> f32 {
+        if self.font_size.is_finite() {
+            self.font_size.clamp(
+                *Self::FONT_SCALE_RANGE.start(),
+                *Self::FONT_SCALE_RANGE.end(),
+            )
+        } else {
+            1.0
+        }
+    }
 }
 
 impl Default for UiConfig {
@@ -59,10 +189,34 @@ impl Default for UiConfig {
         Self {
             theme: UiTheme::Dark,
             font_size: 1.0,
+            dock_layout: None,
         }
     }
 }
 
+/// Apply the UI theme and font scale to egui
+///
+/// Text sizes are scaled from egui's defaults, so calling this repeatedly
+/// does not compound the scale.
+pub fn apply_theme(ctx: &egui::Context, ui_config: &UiConfig) {
+    ctx.set_theme(match ui_config.theme {
+        UiTheme::Dark => egui::Theme::Dark,
+        UiTheme::Light => egui::Theme::Light,
+    });
+
+    let scale = ui_config.font_scale();
+    let defaults = egui::Style::default().text_styles;
+    ctx.all_styles_mut(|style| {
+        for (text_style, font) in style.text_styles.iter_mut() {
+            if let Some(default) = defaults.get(text_style) {
+                font.size = default.size * scale;
+            }
+        }
+    });
+    // Lay panels out again with the new text sizes
+    ctx.request_repaint();
+}
+
 /// Complete application configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct AppConfig {
@@ -91,4 +245,28 @@ impl AppConfig {
             ..Default::default()
         }
     }
+
+    /// Bring preferences saved by an older release up to [`Self::CURRENT_VERSION`]
+    ///
+    /// A config written by a newer release is still used, since losing a
+    /// few unknown settings is better than refusing to start. Returns
+    /// whether the version changed, so the caller can save the result.
+    pub fn migrate(&mut self) -> bool {
+        if self.version >= Self::CURRENT_VERSION {
+            if self.version > Self::CURRENT_VERSION {
+                tracing::warn!(
+                    "Config version {} is newer than {}, unknown settings are ignored",
+                    self.version,
+                    Self::CURRENT_VERSION
+                );
+            }
+            return false;
+        }
+        while self.version < Self::CURRENT_VERSION {
+            // Version 0 configs only lack the version field; every setting
+            // they contain kept its meaning in version 1
+            self.version += 1;
+        }
+        true
+    }
 }
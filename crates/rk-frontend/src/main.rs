@@ -30,7 +30,10 @@ fn main() -> eframe::Result<()> {
             power_preference: wgpu::PowerPreference::default(),
             device_descriptor: std::sync::Arc::new(|adapter| wgpu::DeviceDescriptor {
                 label: Some("rk device"),
-                required_features: wgpu::Features::empty(),
+                // Lets the viewport use MSAA counts beyond 1x and 4x where
+                // the GPU supports them
+                required_features: adapter.features()
+                    & wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
                 required_limits: adapter.limits(),
                 memory_hints: wgpu::MemoryHints::default(),
                 trace: wgpu::Trace::Off,
@@ -4,12 +4,13 @@
 //! allowing navigation, editing, and reordering.
 
 use egui::{CollapsingHeader, Ui};
+use glam::Vec3;
 use uuid::Uuid;
 
-use rk_cad::SketchPlane;
+use rk_cad::{RefAxis, RefPoint, SketchPlane, SolveDiagnostics, SolveStop};
 
 use crate::panels::Panel;
-use crate::state::{AppAction, SharedAppState, SketchAction};
+use crate::state::{AppAction, SharedAppState, SketchAction, SketchTool};
 
 /// Feature tree panel for CAD modeling
 pub struct FeatureTreePanel {
@@ -18,6 +19,8 @@ pub struct FeatureTreePanel {
     /// Items expanded in the tree
     #[allow(dead_code)]
     expanded: std::collections::HashSet<Uuid>,
+    /// Inline rename in progress
+    renaming: Option<RenameState>,
 }
 
 /// An item in the feature tree
@@ -25,14 +28,37 @@ pub struct FeatureTreePanel {
 enum TreeItem {
     Sketch(Uuid),
     Feature(Uuid),
+    Body(Uuid),
+    Reference(Uuid),
 }
 
+/// Inline rename of a feature or body
+struct RenameState {
+    item: TreeItem,
+    text: String,
+    /// Whether the text field still needs keyboard focus
+    needs_focus: bool,
+}
+
+/// Drag payload for reordering features
+#[derive(Debug, Clone, Copy)]
+struct FeatureDrag {
+    feature_id: Uuid,
+    /// Index of the feature in the history when the drag started
+    index: usize,
+}
+
+/// Drag payload for moving the rollback marker
+#[derive(Debug, Clone, Copy)]
+struct RollbackDrag;
+
 /// Snapshot of sketch data for rendering
 struct SketchInfo {
     id: Uuid,
     name: String,
     is_solved: bool,
     dof: u32,
+    diagnostics: Option<SolveDiagnostics>,
 }
 
 /// Snapshot of feature data for rendering
@@ -41,6 +67,44 @@ struct FeatureInfo {
     name: String,
     type_name: &'static str,
     is_suppressed: bool,
+    /// Whether the feature is after the rollback marker
+    is_rolled_back: bool,
+    /// Why the feature failed in the last rebuild
+    error: Option<String>,
+}
+
+/// Edit requested from the row of a reference axis or point
+enum ReferenceEdit {
+    /// The item was renamed inline
+    Rename(String),
+    /// An action to queue as is
+    Action(SketchAction),
+}
+
+/// World axes offered when adding a reference axis
+const REFERENCE_AXES: [(&str, Vec3); 3] = [
+    ("X Axis", Vec3::X),
+    ("Y Axis", Vec3::Y),
+    ("Z Axis", Vec3::Z),
+];
+
+/// World planes offered for mirroring bodies, by normal
+const MIRROR_PLANES: [(&str, Vec3); 3] = [
+    ("YZ Plane", Vec3::X),
+    ("XZ Plane", Vec3::Y),
+    ("XY Plane", Vec3::Z),
+];
+
+/// Snapshot of body data for rendering
+struct BodyInfo {
+    id: Uuid,
+    name: String,
+    /// Per-body tessellation tolerance, if overridden
+    tolerance_override: Option<f32>,
+    /// Color the body is drawn with
+    color: [f32; 4],
+    /// Whether the color was picked by the user
+    custom_color: bool,
 }
 
 impl FeatureTreePanel {
@@ -48,8 +112,148 @@ impl FeatureTreePanel {
         Self {
             selected: None,
             expanded: std::collections::HashSet::new(),
+            renaming: None,
+        }
+    }
+
+    /// Start renaming an item with its current name
+    fn start_rename(&mut self, item: TreeItem, name: &str) {
+        self.renaming = Some(RenameState {
+            item,
+            text: name.to_string(),
+            needs_focus: true,
+        });
+    }
+
+    /// Check if an item is being renamed
+    fn is_renaming(&self, item: TreeItem) -> bool {
+        self.renaming.as_ref().is_some_and(|r| r.item == item)
+    }
+
+    /// Show the inline rename field
+    ///
+    /// Returns the new name when editing is committed with Enter or by
+    /// clicking away. Escape cancels the rename.
+    fn rename_field(&mut self, ui: &mut Ui) -> Option<String> {
+        let rename = self.renaming.as_mut()?;
+        let response = ui.text_edit_singleline(&mut rename.text);
+        if rename.needs_focus {
+            response.request_focus();
+            rename.needs_focus = false;
+        }
+        if !response.lost_focus() {
+            return None;
+        }
+
+        let rename = self.renaming.take()?;
+        let name = rename.text.trim();
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) || name.is_empty() {
+            None
+        } else {
+            Some(name.to_string())
         }
     }
+
+    /// Show a reference axis row, returning the action for any edit
+    fn ref_axis_row(
+        &mut self,
+        ui: &mut Ui,
+        axis: &RefAxis,
+        is_sketch_mode: bool,
+    ) -> Option<AppAction> {
+        let mut edited = axis.clone();
+        let mut changed = false;
+        let edit = self.reference_row(ui, axis.id, &axis.name, is_sketch_mode, |ui| {
+            changed |= vec3_row(ui, "Origin:", &mut edited.origin);
+            changed |= vec3_row(ui, "Direction:", &mut edited.direction);
+        });
+        match edit {
+            Some(ReferenceEdit::Action(action)) => return Some(AppAction::SketchAction(action)),
+            Some(ReferenceEdit::Rename(name)) => {
+                edited.name = name;
+                changed = true;
+            }
+            None => {}
+        }
+        changed.then(|| AppAction::SketchAction(SketchAction::UpdateRefAxis { axis: edited }))
+    }
+
+    /// Show a reference point row, returning the action for any edit
+    fn ref_point_row(
+        &mut self,
+        ui: &mut Ui,
+        point: &RefPoint,
+        is_sketch_mode: bool,
+    ) -> Option<AppAction> {
+        let mut edited = point.clone();
+        let mut changed = false;
+        let edit = self.reference_row(ui, point.id, &point.name, is_sketch_mode, |ui| {
+            changed |= vec3_row(ui, "Position:", &mut edited.position);
+        });
+        match edit {
+            Some(ReferenceEdit::Action(action)) => return Some(AppAction::SketchAction(action)),
+            Some(ReferenceEdit::Rename(name)) => {
+                edited.name = name;
+                changed = true;
+            }
+            None => {}
+        }
+        changed.then(|| AppAction::SketchAction(SketchAction::UpdateRefPoint { point: edited }))
+    }
+
+    /// Show the selectable row of a reference item
+    ///
+    /// `details` draws the item's editable values below the row while it is
+    /// selected.
+    fn reference_row(
+        &mut self,
+        ui: &mut Ui,
+        id: Uuid,
+        name: &str,
+        is_sketch_mode: bool,
+        details: impl FnOnce(&mut Ui),
+    ) -> Option<ReferenceEdit> {
+        let item = TreeItem::Reference(id);
+        if self.is_renaming(item) {
+            return self.rename_field(ui).map(ReferenceEdit::Rename);
+        }
+
+        let is_selected = self.selected == Some(item);
+        let response = ui.selectable_label(is_selected, name);
+        if response.clicked() {
+            self.selected = Some(item);
+        }
+
+        let mut edit = None;
+        let mut rename = response.double_clicked();
+        response.context_menu(|ui| {
+            if ui.button("Rename").clicked() {
+                rename = true;
+                ui.close();
+            }
+            if ui
+                .add_enabled(is_sketch_mode, egui::Button::new("Project into Sketch"))
+                .on_disabled_hover_text("Edit a sketch to project into it")
+                .clicked()
+            {
+                edit = Some(ReferenceEdit::Action(SketchAction::ProjectReference { id }));
+                ui.close();
+            }
+            ui.separator();
+            if ui.button("Delete").clicked() {
+                edit = Some(ReferenceEdit::Action(SketchAction::DeleteReference { id }));
+                ui.close();
+            }
+        });
+        if rename {
+            self.start_rename(item, name);
+        }
+
+        if is_selected {
+            ui.indent(id, details);
+        }
+        edit
+    }
 }
 
 impl Default for FeatureTreePanel {
@@ -65,7 +269,19 @@ impl Panel for FeatureTreePanel {
 
     fn ui(&mut self, ui: &mut Ui, app_state: &SharedAppState) {
         // Collect data from state
-        let (has_sketches, is_sketch_mode, active_sketch, sketches, features) = {
+        let (
+            has_sketches,
+            is_sketch_mode,
+            active_sketch,
+            sketches,
+            features,
+            bodies,
+            ref_axes,
+            ref_points,
+            last_error,
+            global_tolerance,
+            face_sketch_pick,
+        ) = {
             let state = app_state.lock();
             let cad = &state.cad;
 
@@ -79,20 +295,51 @@ impl Panel for FeatureTreePanel {
                     name: s.name.clone(),
                     is_solved: s.is_solved(),
                     dof: s.degrees_of_freedom(),
+                    diagnostics: s.solve_diagnostics(),
                 })
                 .collect();
 
+            let effective_len = cad.data.history.effective_len();
             let features: Vec<FeatureInfo> = cad
                 .data
                 .history
-                .features()
-                .map(|f| FeatureInfo {
-                    id: f.id(),
-                    name: f.name().to_string(),
-                    type_name: f.type_name(),
-                    is_suppressed: f.is_suppressed(),
+                .entries()
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| FeatureInfo {
+                    id: entry.feature.id(),
+                    name: entry.feature.name().to_string(),
+                    type_name: entry.feature.type_name(),
+                    is_suppressed: entry.feature.is_suppressed(),
+                    is_rolled_back: index >= effective_len,
+                    error: entry.error.clone(),
+                })
+                .collect();
+
+            // Bodies in the order of the features that created them
+            let history = &cad.data.history;
+            let mut bodies: Vec<(usize, BodyInfo)> = history
+                .bodies()
+                .values()
+                .map(|b| {
+                    let order = b
+                        .source_feature
+                        .and_then(|id| history.index_of(id))
+                        .unwrap_or(usize::MAX);
+                    (
+                        order,
+                        BodyInfo {
+                            id: b.id,
+                            name: b.name.clone(),
+                            tolerance_override: b.tessellation_tolerance,
+                            color: b.display_color(),
+                            custom_color: b.color.is_some(),
+                        },
+                    )
                 })
                 .collect();
+            bodies.sort_by_key(|(order, _)| *order);
+            let bodies: Vec<BodyInfo> = bodies.into_iter().map(|(_, body)| body).collect();
 
             let has_sketches = !sketches.is_empty();
             let is_sketch_mode = cad.is_sketch_mode();
@@ -104,6 +351,12 @@ impl Panel for FeatureTreePanel {
                 active_sketch,
                 sketches,
                 features,
+                bodies,
+                cad.data.ref_axes.clone(),
+                cad.data.ref_points.clone(),
+                cad.last_error.clone(),
+                state.cad_tessellation_tolerance,
+                cad.face_sketch_pick,
             )
         };
 
@@ -121,23 +374,101 @@ impl Panel for FeatureTreePanel {
                     },
                 ));
             }
+            if ui
+                .add_enabled(
+                    !bodies.is_empty(),
+                    egui::Button::new("+ On Face").selected(face_sketch_pick),
+                )
+                .on_hover_text("Create a sketch on a face picked in the viewport")
+                .clicked()
+            {
+                app_state.lock().queue_action(AppAction::SketchAction(
+                    SketchAction::SetFaceSketchPick {
+                        active: !face_sketch_pick,
+                    },
+                ));
+            }
 
             ui.separator();
 
-            // New feature buttons (disabled when no sketches exist)
+            // New feature buttons (disabled when no sketches exist), applied
+            // to the selected sketch, then the one being edited
+            let feature_sketch = match self.selected {
+                Some(TreeItem::Sketch(id)) => Some(id),
+                _ => active_sketch.or_else(|| sketches.first().map(|s| s.id)),
+            };
             ui.add_enabled_ui(has_sketches, |ui| {
                 if ui
                     .button("Extrude")
                     .on_hover_text("Create extrude feature")
                     .clicked()
+                    && let Some(sketch_id) = feature_sketch
                 {
-                    // TODO: Open extrude dialog
+                    app_state.lock().queue_action(AppAction::SketchAction(
+                        SketchAction::OpenExtrudeDialog { sketch_id },
+                    ));
+                }
+                if ui
+                    .button("Revolve")
+                    .on_hover_text("Create revolve feature")
+                    .clicked()
+                    && let Some(sketch_id) = feature_sketch
+                {
+                    app_state.lock().queue_action(AppAction::SketchAction(
+                        SketchAction::OpenRevolveDialog { sketch_id },
+                    ));
+                }
+            });
+            ui.add_enabled_ui(!bodies.is_empty(), |ui| {
+                if ui
+                    .button("Shell")
+                    .on_hover_text("Hollow a body to a wall thickness")
+                    .clicked()
+                {
+                    let body_id = match self.selected {
+                        Some(TreeItem::Body(id)) => Some(id),
+                        _ => None,
+                    };
+                    app_state.lock().queue_action(AppAction::SketchAction(
+                        SketchAction::OpenShellDialog { body_id },
+                    ));
+                }
+                if ui
+                    .button("Hole")
+                    .on_hover_text("Drill a fastener hole into a body face")
+                    .clicked()
+                {
+                    let body_id = match self.selected {
+                        Some(TreeItem::Body(id)) => Some(id),
+                        _ => None,
+                    };
+                    app_state.lock().queue_action(AppAction::SketchAction(
+                        SketchAction::OpenHoleDialog { body_id },
+                    ));
                 }
             });
         });
 
         ui.separator();
 
+        if face_sketch_pick {
+            ui.weak("Click a face in the viewport to sketch on it (Esc to cancel)");
+            ui.separator();
+        }
+
+        // Error from the last rejected edit (e.g. an invalid reorder)
+        if let Some(error) = last_error {
+            ui.horizontal(|ui| {
+                ui.colored_label(egui::Color32::from_rgb(255, 120, 80), error);
+                if ui.small_button("x").on_hover_text("Dismiss").clicked() {
+                    app_state
+                        .lock()
+                        .queue_action(AppAction::SketchAction(SketchAction::ClearError));
+                }
+            });
+            ui.separator();
+        }
+
         // Feature tree
         egui::ScrollArea::vertical()
             .id_salt("feature_tree_scroll")
@@ -185,6 +516,18 @@ impl Panel for FeatureTreePanel {
                                         ));
                                         ui.close();
                                     }
+                                    if ui.button("Extrude...").clicked() {
+                                        app_state.lock().queue_action(AppAction::SketchAction(
+                                            SketchAction::OpenExtrudeDialog { sketch_id },
+                                        ));
+                                        ui.close();
+                                    }
+                                    if ui.button("Revolve...").clicked() {
+                                        app_state.lock().queue_action(AppAction::SketchAction(
+                                            SketchAction::OpenRevolveDialog { sketch_id },
+                                        ));
+                                        ui.close();
+                                    }
                                     if ui.button("Delete").clicked() {
                                         // TODO: Delete sketch
                                         ui.close();
@@ -201,7 +544,31 @@ impl Panel for FeatureTreePanel {
                         if features.is_empty() {
                             ui.weak("No features yet.");
                         } else {
-                            for feature in &features {
+                            let mut move_request = None;
+                            let mut rollback_request = None;
+                            let mut edit_request = None;
+                            let rollback_index = features
+                                .iter()
+                                .position(|f| f.is_rolled_back)
+                                .unwrap_or(features.len());
+                            for (index, feature) in features.iter().enumerate() {
+                                if index == rollback_index {
+                                    rollback_marker(ui);
+                                }
+
+                                let item = TreeItem::Feature(feature.id);
+                                if self.is_renaming(item) {
+                                    if let Some(name) = self.rename_field(ui) {
+                                        app_state.lock().queue_action(AppAction::SketchAction(
+                                            SketchAction::RenameFeature {
+                                                feature_id: feature.id,
+                                                name,
+                                            },
+                                        ));
+                                    }
+                                    continue;
+                                }
+
                                 let is_selected =
                                     self.selected == Some(TreeItem::Feature(feature.id));
                                 let is_suppressed = feature.is_suppressed;
@@ -211,17 +578,104 @@ impl Panel for FeatureTreePanel {
                                 } else {
                                     format!("  {} ({})", feature.name, feature.type_name)
                                 };
+                                // Features after the rollback marker are dimmed
+                                let label = if feature.is_rolled_back {
+                                    egui::RichText::new(label).weak()
+                                } else {
+                                    egui::RichText::new(label)
+                                };
 
-                                let response = ui.selectable_label(is_selected, label);
+                                // Features can be dragged onto another row to reorder them;
+                                // failed ones get a badge that opens them for fixing
+                                let row = ui
+                                    .horizontal(|ui| {
+                                        let row = ui.dnd_drag_source(
+                                            egui::Id::new(("feature_drag", feature.id)),
+                                            FeatureDrag {
+                                                feature_id: feature.id,
+                                                index,
+                                            },
+                                            |ui| ui.selectable_label(is_selected, label),
+                                        );
+                                        if let Some(error) = &feature.error
+                                            && failed_feature_badge(ui, error).clicked()
+                                        {
+                                            edit_request = Some(feature.id);
+                                        }
+                                        row
+                                    })
+                                    .inner;
+                                let response = row.inner;
+
+                                if let Some(drag) = row.response.dnd_hover_payload::<FeatureDrag>()
+                                    && drag.index != index
+                                {
+                                    // Show where the feature will land
+                                    let rect = row.response.rect;
+                                    let y = if drag.index < index {
+                                        rect.bottom()
+                                    } else {
+                                        rect.top()
+                                    };
+                                    ui.painter().hline(
+                                        rect.x_range(),
+                                        y,
+                                        ui.visuals().selection.stroke,
+                                    );
+                                }
+                                if let Some(drag) =
+                                    row.response.dnd_release_payload::<FeatureDrag>()
+                                    && drag.index != index
+                                {
+                                    move_request = Some((drag.feature_id, index));
+                                }
+
+                                // Dropping the rollback marker on the upper half of a
+                                // row places it before the feature, otherwise after
+                                let rect = row.response.rect;
+                                let marker_index = |ui: &Ui| {
+                                    let pointer_y = ui
+                                        .ctx()
+                                        .pointer_interact_pos()
+                                        .map_or(rect.bottom(), |pos| pos.y);
+                                    if pointer_y < rect.center().y {
+                                        index
+                                    } else {
+                                        index + 1
+                                    }
+                                };
+                                if row.response.dnd_hover_payload::<RollbackDrag>().is_some() {
+                                    let y = if marker_index(ui) == index {
+                                        rect.top()
+                                    } else {
+                                        rect.bottom()
+                                    };
+                                    ui.painter().hline(
+                                        rect.x_range(),
+                                        y,
+                                        ui.visuals().selection.stroke,
+                                    );
+                                }
+                                if row.response.dnd_release_payload::<RollbackDrag>().is_some() {
+                                    rollback_request = Some(marker_index(ui));
+                                }
 
                                 if response.clicked() {
-                                    self.selected = Some(TreeItem::Feature(feature.id));
+                                    self.selected = Some(item);
+                                }
+                                if response.double_clicked() {
+                                    self.start_rename(item, &feature.name);
                                 }
 
                                 // Context menu
+                                let mut rename = false;
                                 response.context_menu(|ui| {
                                     if ui.button("Edit").clicked() {
-                                        // TODO: Edit feature
+                                        edit_request = Some(feature.id);
+                                        ui.close();
+                                    }
+                                    if ui.button("Rename").clicked() {
+                                        rename = true;
                                         ui.close();
                                     }
                                     if ui
@@ -235,11 +689,189 @@ impl Panel for FeatureTreePanel {
                                         // TODO: Toggle suppression
                                         ui.close();
                                     }
+                                    ui.separator();
+                                    if ui.button("Roll Back to Here").clicked() {
+                                        rollback_request = Some(index + 1);
+                                        ui.close();
+                                    }
                                     if ui.button("Delete").clicked() {
                                         // TODO: Delete feature
                                         ui.close();
                                     }
                                 });
+                                if rename {
+                                    self.start_rename(item, &feature.name);
+                                }
+                            }
+                            if rollback_index == features.len() {
+                                rollback_marker(ui);
+                            }
+
+                            if let Some(index) = rollback_request
+                                && index != rollback_index
+                            {
+                                app_state.lock().queue_action(AppAction::SketchAction(
+                                    SketchAction::SetRollback { index },
+                                ));
+                            }
+
+                            if let Some(feature_id) = edit_request {
+                                app_state.lock().queue_action(AppAction::SketchAction(
+                                    SketchAction::EditFeature { feature_id },
+                                ));
+                            }
+
+                            if let Some((feature_id, new_index)) = move_request {
+                                app_state.lock().queue_action(AppAction::SketchAction(
+                                    SketchAction::MoveFeature {
+                                        feature_id,
+                                        new_index,
+                                    },
+                                ));
+                            }
+                        }
+                    });
+
+                // Bodies section
+                CollapsingHeader::new("Bodies")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        if bodies.is_empty() {
+                            ui.weak("No bodies yet.");
+                        }
+                        for body in &bodies {
+                            let item = TreeItem::Body(body.id);
+                            if self.is_renaming(item) {
+                                if let Some(name) = self.rename_field(ui) {
+                                    app_state.lock().queue_action(AppAction::SketchAction(
+                                        SketchAction::RenameBody {
+                                            body_id: body.id,
+                                            name,
+                                        },
+                                    ));
+                                }
+                                continue;
+                            }
+
+                            let is_selected = self.selected == Some(item);
+                            let response = ui
+                                .horizontal(|ui| {
+                                    let mut color = body.color;
+                                    if ui
+                                        .color_edit_button_rgba_unmultiplied(&mut color)
+                                        .on_hover_text("Body color")
+                                        .changed()
+                                    {
+                                        app_state.lock().queue_action(AppAction::SketchAction(
+                                            SketchAction::SetBodyColor {
+                                                body_id: body.id,
+                                                color: Some(color),
+                                            },
+                                        ));
+                                    }
+                                    ui.selectable_label(is_selected, &body.name)
+                                })
+                                .inner;
+                            if response.clicked() {
+                                self.selected = Some(item);
+                            }
+                            let mut rename = response.double_clicked();
+                            response.context_menu(|ui| {
+                                if ui.button("Rename").clicked() {
+                                    rename = true;
+                                    ui.close();
+                                }
+                                if body.custom_color && ui.button("Reset Color").clicked() {
+                                    app_state.lock().queue_action(AppAction::SketchAction(
+                                        SketchAction::SetBodyColor {
+                                            body_id: body.id,
+                                            color: None,
+                                        },
+                                    ));
+                                    ui.close();
+                                }
+                                if ui.button("Shell...").clicked() {
+                                    app_state.lock().queue_action(AppAction::SketchAction(
+                                        SketchAction::OpenShellDialog {
+                                            body_id: Some(body.id),
+                                        },
+                                    ));
+                                    ui.close();
+                                }
+                                if ui.button("Hole...").clicked() {
+                                    app_state.lock().queue_action(AppAction::SketchAction(
+                                        SketchAction::OpenHoleDialog {
+                                            body_id: Some(body.id),
+                                        },
+                                    ));
+                                    ui.close();
+                                }
+                                ui.menu_button("Mirror", |ui| {
+                                    for (label, plane_normal) in MIRROR_PLANES {
+                                        if ui.button(label).clicked() {
+                                            app_state.lock().queue_action(AppAction::SketchAction(
+                                                SketchAction::MirrorBody {
+                                                    body_id: body.id,
+                                                    plane_normal,
+                                                },
+                                            ));
+                                            ui.close();
+                                        }
+                                    }
+                                });
+                                ui.separator();
+                                if let Some(tolerance) =
+                                    tolerance_menu(ui, body.tolerance_override, global_tolerance)
+                                {
+                                    app_state.lock().queue_action(AppAction::SketchAction(
+                                        SketchAction::SetBodyTolerance {
+                                            body_id: body.id,
+                                            tolerance,
+                                        },
+                                    ));
+                                }
+                            });
+                            if rename {
+                                self.start_rename(item, &body.name);
+                            }
+                        }
+                    });
+
+                // Reference geometry section
+                CollapsingHeader::new("Reference")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        ui.menu_button("+ Add", |ui| {
+                            for (label, direction) in REFERENCE_AXES {
+                                if ui.button(label).clicked() {
+                                    app_state.lock().queue_action(AppAction::SketchAction(
+                                        SketchAction::AddRefAxis {
+                                            origin: Vec3::ZERO,
+                                            direction,
+                                        },
+                                    ));
+                                    ui.close();
+                                }
+                            }
+                            ui.separator();
+                            if ui.button("Point at Origin").clicked() {
+                                app_state.lock().queue_action(AppAction::SketchAction(
+                                    SketchAction::AddRefPoint {
+                                        position: Vec3::ZERO,
+                                    },
+                                ));
+                                ui.close();
+                            }
+                        });
+
+                        for axis in &ref_axes {
+                            if let Some(edited) = self.ref_axis_row(ui, axis, is_sketch_mode) {
+                                app_state.lock().queue_action(edited);
+                            }
+                        }
+                        for point in &ref_points {
+                            if let Some(edited) = self.ref_point_row(ui, point, is_sketch_mode) {
+                                app_state.lock().queue_action(edited);
                             }
                         }
                     });
@@ -248,11 +880,270 @@ impl Panel for FeatureTreePanel {
         // Exit sketch mode button (shown when in sketch mode)
         if is_sketch_mode {
             ui.separator();
-            if ui.button("Exit Sketch Mode").clicked() {
-                app_state
-                    .lock()
-                    .queue_action(AppAction::SketchAction(SketchAction::ExitSketchMode));
+            let (
+                has_selection,
+                current_tool,
+                project_construction,
+                snap_to_grid,
+                grid_spacing,
+                offset_distance,
+            ) = app_state
+                .lock()
+                .cad
+                .editor_mode
+                .sketch()
+                .map(|s| {
+                    (
+                        !s.selected_entities.is_empty(),
+                        s.current_tool,
+                        s.project_construction,
+                        s.snap_to_grid,
+                        s.grid_spacing,
+                        s.offset_distance,
+                    )
+                })
+                .unwrap_or_default();
+            ui.horizontal(|ui| {
+                for tool in [
+                    SketchTool::Select,
+                    SketchTool::Line,
+                    SketchTool::Spline,
+                    SketchTool::Ellipse,
+                    SketchTool::DimensionRadius,
+                    SketchTool::Mirror,
+                    SketchTool::Offset,
+                    SketchTool::Project,
+                ] {
+                    let label = ui
+                        .selectable_label(current_tool == tool, tool.name())
+                        .on_hover_text(match tool {
+                            SketchTool::Line => {
+                                "Click to chain lines, hold Ctrl to lock the angle, \
+                                 Shift+click for a tangent arc"
+                            }
+                            SketchTool::Spline => {
+                                "Click to place points, double-click or Enter to finish, \
+                                 click the first point to close"
+                            }
+                            SketchTool::Ellipse => {
+                                "Click the center, then the end of the major axis, then the \
+                                 minor radius"
+                            }
+                            SketchTool::DimensionRadius => "Click a circle or arc to dimension it",
+                            SketchTool::Mirror => {
+                                "Select entities first, then click the line to mirror them across"
+                            }
+                            SketchTool::Offset => {
+                                "Select a chain of lines and arcs, then enter the offset distance"
+                            }
+                            SketchTool::Project => {
+                                "Click body edges to project them into the sketch"
+                            }
+                            _ => "Click or drag a lasso to select sketch entities, Shift to add",
+                        });
+                    if label.clicked() && current_tool != tool {
+                        app_state
+                            .lock()
+                            .queue_action(AppAction::SketchAction(SketchAction::SetTool { tool }));
+                    }
+                }
+            });
+            if current_tool == SketchTool::Offset {
+                ui.horizontal(|ui| {
+                    let mut distance = offset_distance;
+                    if ui
+                        .add(egui::DragValue::new(&mut distance).speed(0.01))
+                        .on_hover_text("Offset distance, negative for the other side")
+                        .changed()
+                        && let Some(mode) = app_state.lock().cad.editor_mode.sketch_mut()
+                    {
+                        mode.offset_distance = distance;
+                    }
+                    if ui
+                        .add_enabled(has_selection && distance != 0.0, egui::Button::new("Apply"))
+                        .clicked()
+                    {
+                        app_state.lock().queue_action(AppAction::SketchAction(
+                            SketchAction::OffsetSelected { distance },
+                        ));
+                    }
+                });
+            }
+            if current_tool == SketchTool::Project {
+                let mut construction = project_construction;
+                if ui
+                    .checkbox(&mut construction, "Project as construction")
+                    .changed()
+                {
+                    app_state.lock().queue_action(AppAction::SketchAction(
+                        SketchAction::ToggleProjectConstruction,
+                    ));
+                }
+            }
+            ui.horizontal(|ui| {
+                let mut snap = snap_to_grid;
+                if ui.checkbox(&mut snap, "Snap to grid").changed() {
+                    app_state
+                        .lock()
+                        .queue_action(AppAction::SketchAction(SketchAction::ToggleSnap));
+                }
+                let mut spacing = grid_spacing;
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut spacing)
+                            .speed(0.01)
+                            .range(0.001..=1000.0),
+                    )
+                    .on_hover_text("Grid spacing")
+                    .changed()
+                {
+                    app_state.lock().queue_action(AppAction::SketchAction(
+                        SketchAction::SetGridSpacing { spacing },
+                    ));
+                }
+            });
+            let active = sketches.iter().find(|s| active_sketch == Some(s.id));
+            let status = active.map(|s| (s.is_solved, s.dof));
+            ui.horizontal(|ui| {
+                match status {
+                    Some((true, 0)) => {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(80, 200, 80),
+                            "✔ Fully constrained",
+                        );
+                    }
+                    Some((true, dof)) => {
+                        ui.label(format!("DOF: {dof}"))
+                            .on_hover_text("Blue geometry can still move");
+                    }
+                    _ => {
+                        ui.weak("Not solved");
+                    }
+                }
+                if ui.button("Solve").clicked() {
+                    app_state
+                        .lock()
+                        .queue_action(AppAction::SketchAction(SketchAction::SolveSketch));
+                }
+            });
+            if let Some(diagnostics) = active.and_then(|s| s.diagnostics) {
+                CollapsingHeader::new("Solver Info")
+                    .id_salt("sketch_solver_info")
+                    .show(ui, |ui| solver_info(ui, &diagnostics));
             }
+            ui.horizontal(|ui| {
+                if ui.button("Exit Sketch Mode").clicked() {
+                    app_state
+                        .lock()
+                        .queue_action(AppAction::SketchAction(SketchAction::ExitSketchMode));
+                }
+                if ui
+                    .add_enabled(has_selection, egui::Button::new("Construction"))
+                    .on_hover_text("Toggle construction geometry for the selection (Q)")
+                    .clicked()
+                {
+                    app_state
+                        .lock()
+                        .queue_action(AppAction::SketchAction(SketchAction::ToggleConstruction));
+                }
+            });
         }
     }
 }
+
+/// Edit a vector with one drag value per component
+///
+/// Returns whether any component changed.
+fn vec3_row(ui: &mut Ui, label: &str, value: &mut Vec3) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let mut changed = false;
+        for component in [&mut value.x, &mut value.y, &mut value.z] {
+            changed |= ui
+                .add(egui::DragValue::new(component).speed(0.01))
+                .changed();
+        }
+        changed
+    })
+    .inner
+}
+
+/// Draw the error badge of a feature that failed to rebuild
+fn failed_feature_badge(ui: &mut Ui, error: &str) -> egui::Response {
+    ui.add(
+        egui::Button::new(
+            egui::RichText::new("\u{26a0}").color(egui::Color32::from_rgb(255, 120, 80)),
+        )
+        .frame(false),
+    )
+    .on_hover_text(format!(
+        "{}\nClick to roll back and edit the feature",
+        error
+    ))
+}
+
+/// Draw the draggable rollback marker between feature rows
+fn rollback_marker(ui: &mut Ui) {
+    let color = ui.visuals().selection.stroke.color;
+    ui.dnd_drag_source(egui::Id::new("rollback_marker"), RollbackDrag, |ui| {
+        ui.colored_label(color, "── Rollback ──");
+    })
+    .response
+    .on_hover_text("Drag to roll back the feature history");
+}
+
+/// Tessellation tolerance controls for a body's context menu
+///
+/// Returns the new override when the user changed it.
+fn tolerance_menu(
+    ui: &mut Ui,
+    tolerance_override: Option<f32>,
+    global_tolerance: f32,
+) -> Option<Option<f32>> {
+    let mut overridden = tolerance_override.is_some();
+    let mut tolerance = tolerance_override.unwrap_or(global_tolerance);
+    let mut changed = ui
+        .checkbox(&mut overridden, "Custom Tessellation")
+        .on_hover_text(format!("Global tolerance: {}", global_tolerance))
+        .changed();
+    ui.add_enabled_ui(overridden, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Tolerance:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut tolerance)
+                        .speed(0.001)
+                        .range(0.0001..=1.0)
+                        .max_decimals(4),
+                )
+                .changed();
+        });
+    });
+    changed.then_some(overridden.then_some(tolerance))
+}
+
+/// Iterations and residuals of the last sketch solve, with why it stopped
+fn solver_info(ui: &mut Ui, diagnostics: &SolveDiagnostics) {
+    egui::Grid::new("sketch_solver_info_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Iterations:");
+            ui.label(diagnostics.iterations.to_string());
+            ui.end_row();
+            ui.label("Residual:");
+            ui.label(format!(
+                "{:.3e} (start {:.3e})",
+                diagnostics.residual, diagnostics.initial_residual
+            ));
+            ui.end_row();
+        });
+    let stop = match diagnostics.stop {
+        SolveStop::Converged => "Converged",
+        SolveStop::SingularJacobian => "Singular Jacobian, likely conflicting constraints",
+        SolveStop::IterationLimit if diagnostics.diverged() => {
+            "Diverged, the residual grew instead of shrinking"
+        }
+        SolveStop::IterationLimit => "Hit the iteration limit before converging",
+    };
+    ui.weak(stop);
+}
@@ -5,7 +5,7 @@ use egui::Ui;
 use rk_core::JointType;
 
 use crate::panels::Panel;
-use crate::state::{AngleDisplayMode, AppAction, SharedAppState};
+use crate::state::{AngleDisplayMode, AppAction, LengthUnit, SharedAppState};
 
 /// Joint list panel for controlling joint positions
 pub struct JointListPanel {
@@ -33,7 +33,9 @@ impl Panel for JointListPanel {
         let state = app_state.lock();
         let joints: Vec<_> = state.project.assembly.joints.values().cloned().collect();
         let joint_positions = state.project.assembly.joint_positions.clone();
+        let joint_dof_positions = state.project.assembly.joint_dof_positions.clone();
         let angle_mode = state.angle_display_mode;
+        let length_unit = state.length_unit;
         drop(state);
 
         if joints.is_empty() {
@@ -48,6 +50,20 @@ impl Panel for JointListPanel {
                     .lock()
                     .queue_action(AppAction::ResetAllJointPositions);
             }
+            if ui
+                .button("Go Home")
+                .on_hover_text("Move all joints to their home positions")
+                .clicked()
+            {
+                app_state.lock().queue_action(AppAction::GoHome);
+            }
+            if ui
+                .button("Set Home")
+                .on_hover_text("Store the current joint positions as the home pose")
+                .clicked()
+            {
+                app_state.lock().queue_action(AppAction::SetHomePose);
+            }
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 let mode_text = match angle_mode {
@@ -70,7 +86,15 @@ impl Panel for JointListPanel {
             .id_salt("joint_list_scroll")
             .show(ui, |ui| {
                 for joint in &joints {
-                    self.render_joint_control(ui, joint, &joint_positions, angle_mode, app_state);
+                    Self::render_joint_control(
+                        ui,
+                        joint,
+                        &joint_positions,
+                        &joint_dof_positions,
+                        angle_mode,
+                        length_unit,
+                        app_state,
+                    );
                 }
             });
     }
@@ -78,11 +102,12 @@ impl Panel for JointListPanel {
 
 impl JointListPanel {
     fn render_joint_control(
-        &self,
         ui: &mut Ui,
         joint: &rk_core::Joint,
         joint_positions: &std::collections::HashMap<uuid::Uuid, f32>,
+        joint_dof_positions: &std::collections::HashMap<uuid::Uuid, [f32; 6]>,
         angle_mode: AngleDisplayMode,
+        length_unit: LengthUnit,
         app_state: &SharedAppState,
     ) {
         let current_value_rad = joint_positions.get(&joint.id).copied().unwrap_or(0.0);
@@ -97,8 +122,10 @@ impl JointListPanel {
                     JointType::Prismatic => "[Prism]",
                     JointType::Floating => "[Float]",
                     JointType::Planar => "[Planar]",
+                    JointType::Spherical => "[Sph]",
                 };
                 ui.label(format!("{} {}", type_label, joint.name));
+                joint_issue_badge(ui, joint);
             });
 
             match joint.joint_type {
@@ -180,16 +207,19 @@ impl JointListPanel {
                         .map(|l| (l.lower, l.upper))
                         .unwrap_or((-1.0, 1.0));
 
-                    let mut value = current_value_rad; // For prismatic, this is meters
+                    // For prismatic joints the position is in meters
+                    let mut value = length_unit.from_meters(current_value_rad);
 
                     ui.horizontal(|ui| {
-                        let slider = egui::Slider::new(&mut value, lower..=upper).suffix(" m");
+                        let range = length_unit.from_meters(lower)..=length_unit.from_meters(upper);
+                        let slider =
+                            egui::Slider::new(&mut value, range).suffix(length_unit.suffix());
                         if ui.add(slider).changed() {
                             app_state
                                 .lock()
                                 .queue_action(AppAction::UpdateJointPosition {
                                     joint_id: joint.id,
-                                    position: value,
+                                    position: length_unit.to_meters(value),
                                 });
                         }
                         if ui.button("R").on_hover_text("Reset to 0").clicked() {
@@ -199,9 +229,54 @@ impl JointListPanel {
                         }
                     });
                 }
-                JointType::Floating | JointType::Planar => {
-                    // These require multi-DOF controls - show as not implemented
-                    ui.weak("(Multi-DOF control not implemented)");
+                JointType::Floating | JointType::Planar | JointType::Spherical => {
+                    // One slider per DOF: angles wrap at +/- 180 degrees,
+                    // translations start at +/- 1 m but aren't clamped
+                    let dofs = joint_dof_positions
+                        .get(&joint.id)
+                        .copied()
+                        .unwrap_or_default();
+                    for &dof in joint.joint_type.multi_dofs() {
+                        let current = dofs[dof.index()];
+                        ui.horizontal(|ui| {
+                            let new_value = if dof.is_angular() {
+                                let limit = angle_mode.from_radians(std::f32::consts::PI);
+                                let mut display_value = angle_mode.from_radians(current);
+                                let slider = egui::Slider::new(&mut display_value, -limit..=limit)
+                                    .suffix(angle_mode.suffix())
+                                    .text(dof.name());
+                                ui.add(slider)
+                                    .changed()
+                                    .then(|| angle_mode.to_radians(display_value))
+                            } else {
+                                let limit = length_unit.from_meters(1.0);
+                                let mut value = length_unit.from_meters(current);
+                                let slider = egui::Slider::new(&mut value, -limit..=limit)
+                                    .suffix(length_unit.suffix())
+                                    .clamping(egui::SliderClamping::Never)
+                                    .text(dof.name());
+                                ui.add(slider)
+                                    .changed()
+                                    .then(|| length_unit.to_meters(value))
+                            };
+                            if let Some(value) = new_value {
+                                app_state.lock().queue_action(AppAction::UpdateJointDof {
+                                    joint_id: joint.id,
+                                    dof,
+                                    value,
+                                });
+                            }
+                        });
+                    }
+                    if ui
+                        .button("R")
+                        .on_hover_text("Reset all DOFs to 0")
+                        .clicked()
+                    {
+                        app_state
+                            .lock()
+                            .queue_action(AppAction::ResetJointPosition { joint_id: joint.id });
+                    }
                 }
             }
 
@@ -209,3 +284,23 @@ impl JointListPanel {
         });
     }
 }
+
+/// Show a warning marker listing the joint's limit issues, if it has any
+pub(crate) fn joint_issue_badge(ui: &mut Ui, joint: &rk_core::Joint) {
+    let issues = joint.validate();
+    if issues.is_empty() {
+        return;
+    }
+
+    let color = if issues.iter().any(|issue| issue.is_error()) {
+        ui.visuals().error_fg_color
+    } else {
+        ui.visuals().warn_fg_color
+    };
+    let text = issues
+        .iter()
+        .map(|issue| issue.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    ui.colored_label(color, "\u{26a0}").on_hover_text(text);
+}
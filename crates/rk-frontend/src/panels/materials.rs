@@ -0,0 +1,174 @@
+//! Materials panel for managing the project's shared material library
+
+use egui::Ui;
+
+use rk_core::MaterialDef;
+
+use crate::panels::Panel;
+use crate::state::{AppAction, SharedAppState};
+
+/// Materials panel for creating, editing and assigning named materials
+pub struct MaterialsPanel {
+    /// Material being renamed: (current name, edited name)
+    renaming: Option<(String, String)>,
+}
+
+impl MaterialsPanel {
+    pub fn new() -> Self {
+        Self { renaming: None }
+    }
+}
+
+impl Default for MaterialsPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Panel for MaterialsPanel {
+    fn name(&self) -> &str {
+        "Materials"
+    }
+
+    fn ui(&mut self, ui: &mut Ui, app_state: &SharedAppState) {
+        let state = app_state.lock();
+        let materials = state.project.materials.clone();
+        let usage: Vec<usize> = materials
+            .iter()
+            .map(|material| {
+                state
+                    .project
+                    .parts_iter()
+                    .filter(|part| part.material_name.as_deref() == Some(&material.name))
+                    .count()
+            })
+            .collect();
+        let has_selection = !state.selected_parts.is_empty();
+        drop(state);
+
+        let mut actions = Vec::new();
+
+        ui.horizontal(|ui| {
+            if ui.button("+ New").clicked() {
+                actions.push(AppAction::AddMaterial);
+            }
+            if ui
+                .add_enabled(has_selection, egui::Button::new("Clear"))
+                .on_hover_text("Remove the material from the selected parts")
+                .clicked()
+            {
+                actions.push(AppAction::AssignMaterial { name: None });
+            }
+        });
+
+        ui.separator();
+
+        if materials.is_empty() {
+            ui.weak("No materials.\nAdd one to share a color between parts.");
+        }
+
+        egui::ScrollArea::vertical()
+            .id_salt("materials_scroll")
+            .show(ui, |ui| {
+                for (material, count) in materials.iter().zip(usage) {
+                    ui.push_id(&material.name, |ui| {
+                        self.material_row(ui, material, count, has_selection, &mut actions);
+                    });
+                }
+            });
+
+        if !actions.is_empty() {
+            let mut state = app_state.lock();
+            for action in actions {
+                state.queue_action(action);
+            }
+        }
+    }
+}
+
+impl MaterialsPanel {
+    fn material_row(
+        &mut self,
+        ui: &mut Ui,
+        material: &MaterialDef,
+        count: usize,
+        has_selection: bool,
+        actions: &mut Vec<AppAction>,
+    ) {
+        ui.horizontal(|ui| {
+            let mut color = egui::Rgba::from_rgba_unmultiplied(
+                material.color[0],
+                material.color[1],
+                material.color[2],
+                material.color[3],
+            );
+            if egui::color_picker::color_edit_button_rgba(
+                ui,
+                &mut color,
+                egui::color_picker::Alpha::OnlyBlend,
+            )
+            .changed()
+            {
+                actions.push(AppAction::UpdateMaterial {
+                    name: material.name.clone(),
+                    material: MaterialDef {
+                        color: color.to_rgba_unmultiplied(),
+                        ..material.clone()
+                    },
+                });
+            }
+
+            // Double-click the name to rename; Enter or clicking away applies it
+            let rename_id = ui.id().with("rename");
+            match &mut self.renaming {
+                Some((name, edited)) if *name == material.name => {
+                    let response = ui.add(egui::TextEdit::singleline(edited).id(rename_id));
+                    if response.lost_focus() {
+                        if !edited.is_empty() && *edited != material.name {
+                            actions.push(AppAction::UpdateMaterial {
+                                name: material.name.clone(),
+                                material: MaterialDef {
+                                    name: edited.clone(),
+                                    ..material.clone()
+                                },
+                            });
+                        }
+                        self.renaming = None;
+                    }
+                }
+                _ => {
+                    if ui
+                        .add(egui::Label::new(&material.name).sense(egui::Sense::click()))
+                        .on_hover_text("Double-click to rename")
+                        .double_clicked()
+                    {
+                        self.renaming = Some((material.name.clone(), material.name.clone()));
+                        ui.memory_mut(|memory| memory.request_focus(rename_id));
+                    }
+                }
+            }
+            ui.weak(format!("({})", count));
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .small_button("🗑")
+                    .on_hover_text("Delete material")
+                    .clicked()
+                {
+                    actions.push(AppAction::RemoveMaterial {
+                        name: material.name.clone(),
+                    });
+                }
+                if ui
+                    .add_enabled(has_selection, egui::Button::new("Assign"))
+                    .on_hover_text("Assign to the selected parts")
+                    .clicked()
+                {
+                    actions.push(AppAction::AssignMaterial {
+                        name: Some(material.name.clone()),
+                    });
+                }
+            });
+        });
+    }
+}
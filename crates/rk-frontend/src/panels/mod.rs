@@ -2,18 +2,25 @@
 
 mod feature_tree;
 mod joint_list;
+mod materials;
 mod part_list;
+mod part_menu;
 mod preferences;
 mod properties;
+mod statistics;
 mod viewport;
 
 pub use feature_tree::FeatureTreePanel;
 pub use joint_list::JointListPanel;
+pub use materials::MaterialsPanel;
 pub use part_list::PartListPanel;
 pub use preferences::PreferencesPanel;
 pub use properties::PropertiesPanel;
+pub use statistics::StatisticsPanel;
 pub use viewport::ViewportPanel;
 
+pub(crate) use properties::length_drag;
+
 use crate::config::SharedConfig;
 use crate::state::{SharedAppState, SharedViewportState};
 
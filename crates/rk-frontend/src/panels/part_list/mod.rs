@@ -7,7 +7,8 @@ use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 use crate::panels::Panel;
-use crate::state::{AppAction, SharedAppState};
+use crate::panels::part_menu::{apply_part_command, show_part_menu};
+use crate::state::{AppAction, PatternDialogState, SharedAppState};
 
 use toolbar::{render_unit_selector, show_tree_context_menu};
 use tree::{TreeAction, build_tree_structure, can_connect};
@@ -22,6 +23,21 @@ pub struct PartListPanel {
     editing_project_name: bool,
     /// Temporary buffer for editing project name
     project_name_buffer: String,
+    /// Parts hidden in the viewport (refreshed each frame)
+    hidden_parts: HashSet<Uuid>,
+    /// Parts whose link is cut off from the root link (refreshed each frame)
+    disconnected_parts: HashSet<Uuid>,
+    /// Parts in the current selection (refreshed each frame)
+    selected_parts: HashSet<Uuid>,
+    /// Part hovered here or in the viewport (refreshed each frame)
+    hovered_part: Option<Uuid>,
+    /// Row under the pointer this frame and the previous one
+    hovered_row: Option<Uuid>,
+    last_hovered_row: Option<Uuid>,
+    /// Primary selection seen last frame, to notice selections made elsewhere
+    last_selected: Option<Uuid>,
+    /// Row to scroll into view on the next frame it is drawn
+    scroll_to: Option<Uuid>,
 }
 
 impl PartListPanel {
@@ -31,6 +47,14 @@ impl PartListPanel {
             drop_target: None,
             editing_project_name: false,
             project_name_buffer: String::new(),
+            hidden_parts: HashSet::new(),
+            disconnected_parts: HashSet::new(),
+            selected_parts: HashSet::new(),
+            hovered_part: None,
+            hovered_row: None,
+            last_hovered_row: None,
+            last_selected: None,
+            scroll_to: None,
         }
     }
 
@@ -40,20 +64,43 @@ impl PartListPanel {
         ui: &mut egui::Ui,
         part_id: Uuid,
         label_text: &str,
-        is_selected: bool,
         has_parent: bool,
+        has_children: bool,
         actions: &mut Vec<TreeAction>,
     ) {
+        let is_selected = self.selected_parts.contains(&part_id);
         let is_being_dragged = self.dragging_part == Some(part_id);
         let is_drop_target = self.drop_target == Some(part_id);
+        let is_visible = !self.hidden_parts.contains(&part_id);
+
+        // Visibility toggle (eye icon)
+        let eye = if is_visible { "👁" } else { "◌" };
+        let hover = if is_visible { "Hide" } else { "Show" };
+        if ui
+            .add(egui::Button::new(eye).frame(false).small())
+            .on_hover_text(hover)
+            .clicked()
+        {
+            actions.push(TreeAction::SetVisible {
+                part_id,
+                visible: !is_visible,
+            });
+        }
 
-        // Visual style based on drag state
+        if self.disconnected_parts.contains(&part_id) {
+            ui.label(egui::RichText::new("⚠").color(ui.visuals().warn_fg_color))
+                .on_hover_text("Not connected to the root link; URDF export is blocked");
+        }
+
+        // Visual style based on drag and visibility state
         let text = if is_being_dragged {
             egui::RichText::new(label_text).italics().weak()
         } else if is_drop_target {
             egui::RichText::new(label_text)
                 .strong()
                 .color(egui::Color32::GREEN)
+        } else if !is_visible {
+            egui::RichText::new(label_text).weak()
         } else {
             egui::RichText::new(label_text)
         };
@@ -65,20 +112,37 @@ impl PartListPanel {
                 .sense(egui::Sense::click_and_drag()),
         );
 
-        // Draw selection background
+        // Draw selection background, or a fainter one for the hovered part
         if is_selected {
             ui.painter()
                 .rect_filled(response.rect, 2.0, ui.visuals().selection.bg_fill);
+        } else if self.hovered_part == Some(part_id) {
+            ui.painter().rect_filled(
+                response.rect,
+                2.0,
+                ui.visuals().selection.bg_fill.gamma_multiply(0.35),
+            );
+        }
+        if response.hovered() {
+            self.hovered_row = Some(part_id);
+        }
+        if self.scroll_to == Some(part_id) {
+            response.scroll_to_me(Some(egui::Align::Center));
+            self.scroll_to = None;
         }
 
         // Context menu
         response.context_menu(|ui| {
+            if let Some(command) = show_part_menu(ui, is_visible, has_children) {
+                actions.push(TreeAction::Menu(part_id, command));
+            }
+            ui.separator();
             if has_parent && ui.button("Disconnect").clicked() {
                 actions.push(TreeAction::Disconnect(part_id));
                 ui.close();
             }
-            if ui.button("Delete").clicked() {
-                actions.push(TreeAction::Delete(part_id));
+            if ui.button("Pattern...").clicked() {
+                actions.push(TreeAction::Pattern(part_id));
                 ui.close();
             }
         });
@@ -96,9 +160,17 @@ impl PartListPanel {
             self.drop_target = Some(part_id);
         }
 
-        // Selection on click
-        if response.clicked() {
+        // Selection on click (Ctrl/Cmd-click adds to the selection);
+        // double-click also frames the part in the viewport
+        if response.double_clicked() {
             actions.push(TreeAction::Select(part_id));
+            actions.push(TreeAction::Frame(part_id));
+        } else if response.clicked() {
+            if ui.input(|i| i.modifiers.command) {
+                actions.push(TreeAction::ToggleSelect(part_id));
+            } else {
+                actions.push(TreeAction::Select(part_id));
+            }
         }
     }
 
@@ -111,7 +183,6 @@ impl PartListPanel {
         part_names: &HashMap<Uuid, String>,
         children_map: &HashMap<Uuid, Vec<Uuid>>,
         parts_with_parent: &HashSet<Uuid>,
-        selected_id: Option<Uuid>,
         depth: usize,
         actions: &mut Vec<TreeAction>,
     ) {
@@ -121,7 +192,6 @@ impl PartListPanel {
         let children = children_map.get(&part_id);
         let has_children = children.is_some_and(|c| !c.is_empty());
         let has_parent = parts_with_parent.contains(&part_id);
-        let is_selected = selected_id == Some(part_id);
 
         ui.push_id(part_id, |ui| {
             let indent = depth as f32 * 16.0;
@@ -132,7 +202,7 @@ impl PartListPanel {
 
             ui.horizontal(|ui| {
                 ui.add_space(indent);
-                self.render_part_item(ui, part_id, &label_text, is_selected, has_parent, actions);
+                self.render_part_item(ui, part_id, &label_text, has_parent, has_children, actions);
             });
 
             // Render children
@@ -144,7 +214,6 @@ impl PartListPanel {
                         part_names,
                         children_map,
                         parts_with_parent,
-                        selected_id,
                         depth + 1,
                         actions,
                     );
@@ -159,16 +228,14 @@ impl PartListPanel {
         ui: &mut egui::Ui,
         part_id: Uuid,
         name: &str,
-        selected_id: Option<Uuid>,
         actions: &mut Vec<TreeAction>,
     ) {
-        let is_selected = selected_id == Some(part_id);
         let label_text = format!("○ {}", name);
 
         ui.push_id(part_id, |ui| {
             ui.horizontal(|ui| {
                 ui.add_space(16.0); // Indent under project root
-                self.render_part_item(ui, part_id, &label_text, is_selected, false, actions);
+                self.render_part_item(ui, part_id, &label_text, false, false, actions);
             });
         });
     }
@@ -256,7 +323,6 @@ impl Panel for PartListPanel {
 
         // Collect state data
         let state = app_state.lock();
-        let selected_id = state.selected_part;
         let project_name = state.project.name.clone();
 
         // Build tree structure from Assembly
@@ -271,11 +337,34 @@ impl Panel for PartListPanel {
             .map(|(id, p)| (*id, p.name.clone()))
             .collect();
 
+        self.hidden_parts = state
+            .project
+            .parts()
+            .values()
+            .filter(|p| !p.visible)
+            .map(|p| p.id)
+            .collect();
+        let assembly = &state.project.assembly;
+        self.disconnected_parts = assembly
+            .connectivity_report()
+            .problem_links()
+            .filter_map(|link_id| assembly.links.get(&link_id)?.part_id)
+            .collect();
+        self.selected_parts = state.selected_parts.iter().copied().collect();
+        self.hovered_part = state.hovered_part;
+
+        // Bring a part selected in the viewport into view
+        if state.selected_part != self.last_selected {
+            self.last_selected = state.selected_part;
+            self.scroll_to = state.selected_part;
+        }
+
         let is_empty = state.project.parts().is_empty();
         drop(state);
 
-        // Reset drop targets each frame
+        // Reset drop targets and row hover each frame
         self.drop_target = None;
+        self.hovered_row = None;
 
         // Collect actions during rendering
         let mut actions: Vec<TreeAction> = Vec::new();
@@ -294,7 +383,6 @@ impl Panel for PartListPanel {
                     &part_names,
                     &children_map,
                     &parts_with_parent,
-                    selected_id,
                     1,
                     &mut actions,
                 );
@@ -310,7 +398,7 @@ impl Panel for PartListPanel {
 
                 for part_id in &unconnected_parts {
                     if let Some(name) = part_names.get(part_id) {
-                        self.render_orphan_part(ui, *part_id, name, selected_id, &mut actions);
+                        self.render_orphan_part(ui, *part_id, name, &mut actions);
                     }
                 }
             }
@@ -377,19 +465,27 @@ impl Panel for PartListPanel {
             self.drop_target = None;
         }
 
+        // Share row hover with the viewport highlight, only on change so the
+        // viewport's own hover is not overwritten
+        if self.hovered_row != self.last_hovered_row {
+            self.last_hovered_row = self.hovered_row;
+            app_state.lock().hovered_part = self.hovered_row;
+        }
+
         // Process collected actions
         for action in actions {
             match action {
                 TreeAction::Select(id) => {
+                    // Already visible, so no need to scroll to it
+                    self.last_selected = Some(id);
                     app_state
                         .lock()
                         .queue_action(AppAction::SelectPart(Some(id)));
                 }
-                TreeAction::Delete(id) => {
+                TreeAction::ToggleSelect(id) => {
                     app_state
                         .lock()
-                        .queue_action(AppAction::SelectPart(Some(id)));
-                    app_state.lock().queue_action(AppAction::DeleteSelectedPart);
+                        .queue_action(AppAction::TogglePartSelection(id));
                 }
                 TreeAction::Disconnect(id) => {
                     app_state
@@ -402,6 +498,18 @@ impl Panel for PartListPanel {
                         .lock()
                         .queue_action(AppAction::ConnectParts { parent, child });
                 }
+                TreeAction::SetVisible { part_id, visible } => {
+                    app_state
+                        .lock()
+                        .queue_action(AppAction::SetPartVisibility { part_id, visible });
+                }
+                TreeAction::Frame(id) => {
+                    app_state.lock().queue_action(AppAction::FramePart(id));
+                }
+                TreeAction::Pattern(id) => {
+                    app_state.lock().pattern_dialog = Some(PatternDialogState::new(id));
+                }
+                TreeAction::Menu(id, command) => apply_part_command(app_state, id, command),
             }
         }
 
@@ -3,14 +3,19 @@
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+use crate::panels::part_menu::PartMenuCommand;
 use crate::state::AppState;
 
 /// Actions collected during tree rendering
 pub enum TreeAction {
     Select(Uuid),
-    Delete(Uuid),
+    ToggleSelect(Uuid),
+    Frame(Uuid),
     Disconnect(Uuid),
     Connect { parent: Uuid, child: Uuid },
+    SetVisible { part_id: Uuid, visible: bool },
+    Pattern(Uuid),
+    Menu(Uuid, PartMenuCommand),
 }
 
 /// Build tree structure from Assembly state
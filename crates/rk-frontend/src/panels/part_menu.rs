@@ -0,0 +1,113 @@
+//! Part context menu shared by the viewport and part list
+
+use uuid::Uuid;
+
+use crate::state::{AppAction, RenameDialogState, SharedAppState};
+
+/// Command chosen from the part context menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartMenuCommand {
+    Rename,
+    Duplicate,
+    /// Delete the part and its link, reattaching the link's children to its
+    /// parent or leaving them unconnected
+    Delete {
+        reparent_children: bool,
+    },
+    SetVisible(bool),
+    Isolate,
+    ShowAll,
+    Focus,
+    RecomputeInertia,
+}
+
+/// Draw the part menu entries, returning the command that was clicked
+///
+/// Parts with jointed children get a choice of what happens to them on
+/// delete.
+pub fn show_part_menu(
+    ui: &mut egui::Ui,
+    visible: bool,
+    has_children: bool,
+) -> Option<PartMenuCommand> {
+    let mut command = None;
+    let mut item = |ui: &mut egui::Ui, label: &str, cmd: PartMenuCommand| {
+        if ui.button(label).clicked() {
+            command = Some(cmd);
+            ui.close();
+        }
+    };
+
+    item(ui, "Rename...", PartMenuCommand::Rename);
+    item(ui, "Duplicate", PartMenuCommand::Duplicate);
+    ui.separator();
+    if visible {
+        item(ui, "Hide", PartMenuCommand::SetVisible(false));
+    } else {
+        item(ui, "Show", PartMenuCommand::SetVisible(true));
+    }
+    item(ui, "Isolate", PartMenuCommand::Isolate);
+    item(ui, "Show All", PartMenuCommand::ShowAll);
+    item(ui, "Focus", PartMenuCommand::Focus);
+    ui.separator();
+    item(ui, "Recompute Inertia", PartMenuCommand::RecomputeInertia);
+    ui.separator();
+    if has_children {
+        ui.menu_button("Delete", |ui| {
+            item(
+                ui,
+                "Reattach Children to Parent",
+                PartMenuCommand::Delete {
+                    reparent_children: true,
+                },
+            );
+            item(
+                ui,
+                "Disconnect Children",
+                PartMenuCommand::Delete {
+                    reparent_children: false,
+                },
+            );
+        });
+    } else {
+        item(
+            ui,
+            "Delete",
+            PartMenuCommand::Delete {
+                reparent_children: true,
+            },
+        );
+    }
+
+    command
+}
+
+/// Queue the actions for a part menu command
+pub fn apply_part_command(app_state: &SharedAppState, part_id: Uuid, command: PartMenuCommand) {
+    let mut state = app_state.lock();
+    match command {
+        PartMenuCommand::Rename => {
+            if let Some(part) = state.get_part(part_id) {
+                let dialog = RenameDialogState::new(part_id, part.name.clone());
+                state.rename_dialog = Some(dialog);
+            }
+        }
+        PartMenuCommand::Duplicate => state.queue_action(AppAction::DuplicatePart(part_id)),
+        PartMenuCommand::Delete { reparent_children } => {
+            state.queue_action(AppAction::SelectPart(Some(part_id)));
+            state.queue_action(AppAction::DeleteSelectedPart { reparent_children });
+        }
+        PartMenuCommand::SetVisible(visible) => {
+            state.queue_action(AppAction::SetPartVisibility { part_id, visible });
+        }
+        PartMenuCommand::Isolate => {
+            state.queue_action(AppAction::SelectPart(Some(part_id)));
+            state.queue_action(AppAction::IsolateSelection);
+        }
+        PartMenuCommand::ShowAll => state.queue_action(AppAction::ShowAllParts),
+        PartMenuCommand::Focus => state.queue_action(AppAction::FramePart(part_id)),
+        PartMenuCommand::RecomputeInertia => {
+            state.queue_action(AppAction::RecomputeInertia(part_id));
+        }
+    }
+}
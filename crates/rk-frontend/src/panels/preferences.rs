@@ -1,10 +1,11 @@
 //! Preferences window for application settings
 
-use rk_core::StlUnit;
-use rk_renderer::config::RendererConfig;
+use rk_core::{PrimitiveQuality, StlUnit};
+use rk_renderer::config::{BackgroundMode, MiddleMouseAction, RendererConfig};
+use rk_renderer::constants::viewport::GUARANTEED_SAMPLE_COUNTS;
 
-use crate::config::{EditorConfig, SharedConfig, UiConfig, UiTheme};
-use crate::state::{AngleDisplayMode, SharedAppState, SharedViewportState};
+use crate::config::{EditorConfig, SharedConfig, UiConfig, UiTheme, apply_theme};
+use crate::state::{AngleDisplayMode, LengthUnit, SharedAppState, SharedViewportState};
 
 /// Current tab in the preferences window
 #[derive(Default, Clone, Copy, PartialEq, Eq)]
@@ -74,7 +75,7 @@ impl PreferencesPanel {
                         self.editor_tab(ui, config, app_state);
                     }
                     PreferencesTab::Interface => {
-                        self.interface_tab(ui, config);
+                        self.interface_tab(ui, config, viewport_state);
                     }
                 });
 
@@ -97,14 +98,28 @@ impl PreferencesPanel {
                                 &queue,
                             );
                         }
+                        apply_theme(ui.ctx(), &config.read().config().ui);
                         // Apply defaults to app state
                         {
                             let cfg = config.read();
                             let mut state = app_state.lock();
                             state.show_part_axes = cfg.config().editor.show_part_axes;
                             state.show_joint_markers = cfg.config().editor.show_joint_markers;
+                            state.show_center_of_mass = cfg.config().editor.show_center_of_mass;
                             state.angle_display_mode = cfg.config().editor.angle_display_mode;
+                            state.length_unit = cfg.config().editor.length_unit;
                             state.stl_import_unit = cfg.config().editor.stl_import_unit;
+                            state.stl_export_unit = cfg.config().editor.stl_export_unit;
+                            state.export_precision = cfg.config().editor.export_precision;
+                            state.primitive_quality = cfg.config().editor.primitive_quality;
+                            state.cad_tessellation_tolerance =
+                                cfg.config().editor.cad_tessellation_tolerance;
+                            state.snap_part_translation = cfg.config().editor.snap_part_translation;
+                            state.part_snap_increment = cfg.config().editor.part_snap_increment;
+                            state.nudge_step = cfg.config().editor.nudge_step;
+                            state.sketch_auto_solve = cfg.config().editor.sketch_auto_solve;
+                            state.sketch_snap_to_grid = cfg.config().editor.sketch_snap_to_grid;
+                            state.sketch_grid_spacing = cfg.config().editor.sketch_grid_spacing;
                         }
                     }
 
@@ -134,10 +149,21 @@ impl PreferencesPanel {
         ui.collapsing("Grid", |ui| {
             changed |= ui.checkbox(&mut grid.enabled, "Show Grid").changed();
             changed |= ui
-                .add(egui::Slider::new(&mut grid.size, 1.0..=100.0).text("Size"))
+                .checkbox(&mut grid.infinite, "Infinite Grid")
+                .on_hover_text("Shader grid that follows the camera and fades with distance")
                 .changed();
             changed |= ui
-                .add(egui::Slider::new(&mut grid.spacing, 0.1..=10.0).text("Spacing"))
+                .add(egui::Slider::new(&mut grid.size, 1.0..=100.0).text("Extent"))
+                .changed();
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut grid.spacing, 0.001..=10.0)
+                        .logarithmic(true)
+                        .text("Spacing"),
+                )
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(&mut grid.major_every, 1..=20).text("Major Every"))
                 .changed();
 
             ui.horizontal(|ui| {
@@ -156,56 +182,181 @@ impl PreferencesPanel {
                     changed = true;
                 }
             });
+
+            ui.horizontal(|ui| {
+                ui.label("Major Line Color:");
+                let mut color = [
+                    (grid.major_line_color[0] * 255.0) as u8,
+                    (grid.major_line_color[1] * 255.0) as u8,
+                    (grid.major_line_color[2] * 255.0) as u8,
+                ];
+                if ui.color_edit_button_srgb(&mut color).changed() {
+                    grid.major_line_color = [
+                        color[0] as f32 / 255.0,
+                        color[1] as f32 / 255.0,
+                        color[2] as f32 / 255.0,
+                    ];
+                    changed = true;
+                }
+            });
         });
 
         // Viewport settings
         let mut viewport = renderer_cfg.viewport.clone();
+        let theme = cfg.config().ui.theme;
         ui.collapsing("Viewport", |ui| {
             ui.horizontal(|ui| {
-                ui.label("Background Color:");
-                let mut color = egui::Color32::from_rgba_unmultiplied(
-                    (viewport.background_color[0] * 255.0) as u8,
-                    (viewport.background_color[1] * 255.0) as u8,
-                    (viewport.background_color[2] * 255.0) as u8,
-                    (viewport.background_color[3] * 255.0) as u8,
-                );
-                if ui.color_edit_button_srgba(&mut color).changed() {
-                    viewport.background_color = [
-                        color.r() as f32 / 255.0,
-                        color.g() as f32 / 255.0,
-                        color.b() as f32 / 255.0,
-                        color.a() as f32 / 255.0,
-                    ];
-                    changed = true;
-                }
+                ui.label("Background:");
+                egui::ComboBox::from_id_salt("background_mode")
+                    .selected_text(viewport.background_mode.name())
+                    .show_ui(ui, |ui| {
+                        for mode in BackgroundMode::ALL {
+                            changed |= ui
+                                .selectable_value(&mut viewport.background_mode, *mode, mode.name())
+                                .changed();
+                        }
+                    });
             });
 
+            let mut color_row = |ui: &mut egui::Ui, label: &str, rgba: &mut [f32; 4]| {
+                ui.horizontal(|ui| {
+                    ui.label(label);
+                    let mut color = egui::Color32::from_rgba_unmultiplied(
+                        (rgba[0] * 255.0) as u8,
+                        (rgba[1] * 255.0) as u8,
+                        (rgba[2] * 255.0) as u8,
+                        (rgba[3] * 255.0) as u8,
+                    );
+                    if ui.color_edit_button_srgba(&mut color).changed() {
+                        *rgba = [
+                            color.r() as f32 / 255.0,
+                            color.g() as f32 / 255.0,
+                            color.b() as f32 / 255.0,
+                            color.a() as f32 / 255.0,
+                        ];
+                        changed = true;
+                    }
+                });
+            };
+            match viewport.background_mode {
+                BackgroundMode::Solid => {
+                    color_row(ui, "Background Color:", &mut viewport.background_color);
+                }
+                BackgroundMode::Gradient => {
+                    color_row(ui, "Top Color:", &mut viewport.gradient_top_color);
+                    color_row(ui, "Bottom Color:", &mut viewport.gradient_bottom_color);
+                }
+            }
+
+            if ui
+                .button("Use Theme Colors")
+                .on_hover_text("Reset the background colors to suit the current UI theme")
+                .clicked()
+            {
+                viewport.apply_background_preset(&theme.background_preset());
+                changed = true;
+            }
+
+            // Only offer the counts the GPU can render with
+            let sample_counts = viewport_state.as_ref().map_or_else(
+                || GUARANTEED_SAMPLE_COUNTS.to_vec(),
+                |vp| vp.lock().renderer.supported_sample_counts().to_vec(),
+            );
             ui.horizontal(|ui| {
                 ui.label("Anti-aliasing:");
                 egui::ComboBox::from_id_salt("msaa")
                     .selected_text(format!("{}x MSAA", viewport.msaa_sample_count))
                     .show_ui(ui, |ui| {
-                        if ui
-                            .selectable_value(&mut viewport.msaa_sample_count, 1, "Off")
-                            .changed()
-                        {
-                            changed = true;
-                        }
-                        if ui
-                            .selectable_value(&mut viewport.msaa_sample_count, 2, "2x")
-                            .changed()
-                        {
-                            changed = true;
-                        }
-                        if ui
-                            .selectable_value(&mut viewport.msaa_sample_count, 4, "4x (default)")
-                            .changed()
-                        {
-                            changed = true;
+                        for count in sample_counts {
+                            let label = match count {
+                                1 => "Off".to_string(),
+                                4 => "4x (default)".to_string(),
+                                _ => format!("{}x", count),
+                            };
+                            changed |= ui
+                                .selectable_value(&mut viewport.msaa_sample_count, count, label)
+                                .changed();
                         }
                     });
             });
-            ui.label("(MSAA changes require restart)");
+        });
+
+        // Floor settings
+        let mut floor = renderer_cfg.floor.clone();
+        ui.collapsing("Floor", |ui| {
+            changed |= ui.checkbox(&mut floor.enabled, "Show Floor").changed();
+            changed |= ui
+                .checkbox(&mut floor.receive_shadows, "Receive Shadows")
+                .changed();
+
+            ui.horizontal(|ui| {
+                ui.label("Height:");
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut floor.height)
+                            .speed(0.001)
+                            .suffix(" m"),
+                    )
+                    .changed();
+            });
+
+            changed |= ui
+                .add(egui::Slider::new(&mut floor.size, 1.0..=100.0).text("Size (m)"))
+                .changed();
+
+            ui.horizontal(|ui| {
+                ui.label("Color:");
+                let mut color = [
+                    (floor.color[0] * 255.0) as u8,
+                    (floor.color[1] * 255.0) as u8,
+                    (floor.color[2] * 255.0) as u8,
+                ];
+                if ui.color_edit_button_srgb(&mut color).changed() {
+                    floor.color = [
+                        color[0] as f32 / 255.0,
+                        color[1] as f32 / 255.0,
+                        color[2] as f32 / 255.0,
+                    ];
+                    changed = true;
+                }
+            });
+        });
+
+        // Mesh edge settings
+        let mut edges = renderer_cfg.edges.clone();
+        ui.collapsing("Edges", |ui| {
+            changed |= ui
+                .checkbox(&mut edges.enabled, "Show Edges")
+                .on_hover_text("Outline creases, open borders and silhouettes of meshes")
+                .changed();
+            ui.add_enabled_ui(edges.enabled, |ui| {
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut edges.crease_angle, 1.0..=90.0)
+                            .suffix("°")
+                            .text("Crease Angle"),
+                    )
+                    .on_hover_text("Draw edges where adjacent faces meet at a sharper angle")
+                    .changed();
+
+                ui.horizontal(|ui| {
+                    ui.label("Color:");
+                    let mut color = [
+                        (edges.color[0] * 255.0) as u8,
+                        (edges.color[1] * 255.0) as u8,
+                        (edges.color[2] * 255.0) as u8,
+                    ];
+                    if ui.color_edit_button_srgb(&mut color).changed() {
+                        edges.color = [
+                            color[0] as f32 / 255.0,
+                            color[1] as f32 / 255.0,
+                            color[2] as f32 / 255.0,
+                            edges.color[3],
+                        ];
+                        changed = true;
+                    }
+                });
+            });
         });
 
         // Shadow settings
@@ -244,7 +395,6 @@ impl PreferencesPanel {
                         }
                     });
             });
-            ui.label("(Shadow quality changes require restart)");
 
             changed |= ui
                 .add(egui::Slider::new(&mut shadow.bias, 0.0..=0.02).text("Bias"))
@@ -267,6 +417,30 @@ impl PreferencesPanel {
                 .add(egui::Slider::new(&mut lighting.ambient_strength, 0.0..=1.0).text("Ambient"))
                 .changed();
 
+            // Light direction as azimuth/elevation (degrees)
+            let [x, y, z] = lighting.direction;
+            let mut azimuth = y.atan2(x).to_degrees();
+            let mut elevation = z.atan2((x * x + y * y).sqrt()).to_degrees();
+            let azimuth_changed = ui
+                .add(
+                    egui::Slider::new(&mut azimuth, -180.0..=180.0)
+                        .text("Azimuth")
+                        .suffix("°"),
+                )
+                .changed();
+            let elevation_changed = ui
+                .add(
+                    egui::Slider::new(&mut elevation, -89.0..=89.0)
+                        .text("Elevation")
+                        .suffix("°"),
+                )
+                .changed();
+            if azimuth_changed || elevation_changed {
+                let (az, el) = (azimuth.to_radians(), elevation.to_radians());
+                lighting.direction = [el.cos() * az.cos(), el.cos() * az.sin(), el.sin()];
+                changed = true;
+            }
+
             ui.horizontal(|ui| {
                 ui.label("Light Color:");
                 let mut color = [
@@ -326,6 +500,19 @@ impl PreferencesPanel {
                         .text("Orbit Sensitivity"),
                 )
                 .changed();
+            changed |= ui.checkbox(&mut camera.invert_y, "Invert Y").changed();
+            ui.horizontal(|ui| {
+                ui.label("Middle Mouse:");
+                egui::ComboBox::from_id_salt("middle_mouse")
+                    .selected_text(camera.middle_mouse.name())
+                    .show_ui(ui, |ui| {
+                        for action in MiddleMouseAction::ALL {
+                            changed |= ui
+                                .selectable_value(&mut camera.middle_mouse, *action, action.name())
+                                .changed();
+                        }
+                    });
+            });
         });
 
         // Gizmo settings
@@ -337,15 +524,36 @@ impl PreferencesPanel {
                 .changed();
         });
 
+        // Fast orbit settings
+        let mut fast_orbit = renderer_cfg.fast_orbit.clone();
+        ui.collapsing("Fast Orbit", |ui| {
+            changed |= ui
+                .checkbox(&mut fast_orbit.enabled, "Enable Fast Orbit")
+                .on_hover_text("Draw large parts as bounding boxes while the camera moves")
+                .changed();
+            ui.add_enabled_ui(fast_orbit.enabled, |ui| {
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut fast_orbit.triangle_threshold, 1_000..=1_000_000)
+                            .logarithmic(true)
+                            .text("Triangle Threshold"),
+                    )
+                    .changed();
+            });
+        });
+
         // Apply changes to config and renderer
         if changed {
             let new_config = RendererConfig {
                 grid,
                 viewport,
+                floor,
+                edges,
                 shadow,
                 lighting,
                 camera,
                 gizmo,
+                fast_orbit,
             };
             cfg.config_mut().renderer = new_config.clone();
 
@@ -355,9 +563,10 @@ impl PreferencesPanel {
                 let device = vp_lock.device.clone();
                 let queue = vp_lock.queue.clone();
                 drop(vp_lock);
-                vp.lock()
-                    .renderer
-                    .apply_config(&new_config, &device, &queue);
+                let mut vp = vp.lock();
+                vp.renderer.apply_config(&new_config, &device, &queue);
+                // Show the count actually in use if the GPU refused the request
+                cfg.config_mut().renderer.viewport.msaa_sample_count = vp.renderer.sample_count();
             }
         }
     }
@@ -369,13 +578,32 @@ impl PreferencesPanel {
 
         let mut show_part_axes = editor_cfg.show_part_axes;
         let mut show_joint_markers = editor_cfg.show_joint_markers;
+        let mut show_center_of_mass = editor_cfg.show_center_of_mass;
         let mut angle_display_mode = editor_cfg.angle_display_mode;
+        let mut length_unit = editor_cfg.length_unit;
         let mut stl_import_unit = editor_cfg.stl_import_unit;
+        let mut stl_export_unit = editor_cfg.stl_export_unit;
+        let mut round_export = editor_cfg.export_precision.is_some();
+        let mut export_digits = editor_cfg
+            .export_precision
+            .unwrap_or(rk_core::DEFAULT_PRECISION);
+        let mut primitive_quality = editor_cfg.primitive_quality;
+        let mut cad_tessellation_tolerance = editor_cfg.cad_tessellation_tolerance;
+        let mut snap_part_translation = editor_cfg.snap_part_translation;
+        let mut part_snap_increment = editor_cfg.part_snap_increment;
+        let mut nudge_step = editor_cfg.nudge_step;
+        let mut sketch_auto_solve = editor_cfg.sketch_auto_solve;
+        let mut sketch_snap_to_grid = editor_cfg.sketch_snap_to_grid;
+        let mut sketch_grid_spacing = editor_cfg.sketch_grid_spacing;
 
         changed |= ui.checkbox(&mut show_part_axes, "Show Part Axes").changed();
         changed |= ui
             .checkbox(&mut show_joint_markers, "Show Joint Markers")
             .changed();
+        changed |= ui
+            .checkbox(&mut show_center_of_mass, "Show Center of Mass")
+            .on_hover_text("Mark the combined center of mass and its projection on the floor")
+            .changed();
 
         ui.horizontal(|ui| {
             ui.label("Angle Display:");
@@ -408,6 +636,21 @@ impl PreferencesPanel {
                 });
         });
 
+        ui.horizontal(|ui| {
+            ui.label("Length Unit:");
+            egui::ComboBox::from_id_salt("length_unit")
+                .selected_text(length_unit.name())
+                .show_ui(ui, |ui| {
+                    for unit in LengthUnit::ALL {
+                        changed |= ui
+                            .selectable_value(&mut length_unit, *unit, unit.name())
+                            .changed();
+                    }
+                })
+                .response
+                .on_hover_text("Unit lengths are shown and entered in; files always use meters");
+        });
+
         ui.horizontal(|ui| {
             ui.label("STL Import Unit:");
             egui::ComboBox::from_id_salt("stl_unit")
@@ -445,30 +688,178 @@ impl PreferencesPanel {
                 });
         });
 
+        ui.horizontal(|ui| {
+            ui.label("STL Export Unit:");
+            egui::ComboBox::from_id_salt("stl_export_unit")
+                .selected_text(stl_export_unit.name())
+                .show_ui(ui, |ui| {
+                    for unit in StlUnit::ALL {
+                        changed |= ui
+                            .selectable_value(&mut stl_export_unit, *unit, unit.name())
+                            .changed();
+                    }
+                })
+                .response
+                .on_hover_text("Exported URDFs scale the meshes back to meters");
+        });
+
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut round_export, "Round Exported Numbers")
+                .on_hover_text(
+                    "Write URDF numbers with this many significant digits, \
+                     snapping near-zero values and near right angles",
+                )
+                .changed();
+            ui.add_enabled_ui(round_export, |ui| {
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut export_digits)
+                            .range(1..=9)
+                            .suffix(" digits"),
+                    )
+                    .changed();
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("URDF Primitive Quality:");
+            egui::ComboBox::from_id_salt("primitive_quality")
+                .selected_text(primitive_quality.name())
+                .show_ui(ui, |ui| {
+                    for quality in PrimitiveQuality::ALL {
+                        changed |= ui
+                            .selectable_value(&mut primitive_quality, *quality, quality.name())
+                            .changed();
+                    }
+                })
+                .response
+                .on_hover_text("Tessellation of imported cylinder, sphere and capsule geometry");
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("CAD Tessellation Tolerance:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut cad_tessellation_tolerance)
+                        .speed(0.001)
+                        .range(0.0001..=1.0)
+                        .max_decimals(4),
+                )
+                .on_hover_text("Smaller values give smoother curved bodies at a higher cost")
+                .changed();
+        });
+
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut snap_part_translation, "Snap Part Translation")
+                .on_hover_text(
+                    "Snap parts moved with the gizmo to a grid relative to their parent link",
+                )
+                .changed();
+            ui.add_enabled_ui(snap_part_translation, |ui| {
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut part_snap_increment)
+                            .speed(0.001)
+                            .range(0.0001..=1.0)
+                            .max_decimals(4)
+                            .suffix(" m"),
+                    )
+                    .changed();
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Arrow Key Nudge:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut nudge_step)
+                        .speed(0.001)
+                        .range(0.0001..=1.0)
+                        .max_decimals(4)
+                        .suffix(" m"),
+                )
+                .on_hover_text(
+                    "Distance the arrow keys and Page Up/Down move the selected part; \
+                     Shift moves 10x further, Ctrl 10x less",
+                )
+                .changed();
+        });
+
+        changed |= ui
+            .checkbox(&mut sketch_auto_solve, "Auto-Solve Sketches")
+            .on_hover_text("Solve the sketch being edited shortly after each change")
+            .changed();
+
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut sketch_snap_to_grid, "Snap Sketches to Grid")
+                .changed();
+            ui.label("Spacing:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut sketch_grid_spacing)
+                        .speed(0.01)
+                        .range(0.001..=1000.0),
+                )
+                .on_hover_text("Grid spacing for new sketch sessions, in sketch units")
+                .changed();
+        });
+
         if changed {
             cfg.config_mut().editor = EditorConfig {
                 show_part_axes,
                 show_joint_markers,
+                show_center_of_mass,
                 angle_display_mode,
+                length_unit,
                 stl_import_unit,
+                stl_export_unit,
+                export_precision: round_export.then_some(export_digits),
+                primitive_quality,
+                cad_tessellation_tolerance,
+                snap_part_translation,
+                part_snap_increment,
+                nudge_step,
+                sketch_auto_solve,
+                sketch_snap_to_grid,
+                sketch_grid_spacing,
             };
 
             // Apply to app state immediately
             let mut state = app_state.lock();
             state.show_part_axes = show_part_axes;
             state.show_joint_markers = show_joint_markers;
+            state.show_center_of_mass = show_center_of_mass;
             state.angle_display_mode = angle_display_mode;
+            state.length_unit = length_unit;
             state.stl_import_unit = stl_import_unit;
+            state.stl_export_unit = stl_export_unit;
+            state.export_precision = round_export.then_some(export_digits);
+            state.primitive_quality = primitive_quality;
+            state.cad_tessellation_tolerance = cad_tessellation_tolerance;
+            state.snap_part_translation = snap_part_translation;
+            state.part_snap_increment = part_snap_increment;
+            state.nudge_step = nudge_step;
+            state.sketch_auto_solve = sketch_auto_solve;
+            state.sketch_snap_to_grid = sketch_snap_to_grid;
+            state.sketch_grid_spacing = sketch_grid_spacing;
         }
     }
 
-    fn interface_tab(&mut self, ui: &mut egui::Ui, config: &SharedConfig) {
+    fn interface_tab(
+        &mut self,
+        ui: &mut egui::Ui,
+        config: &SharedConfig,
+        viewport_state: &Option<SharedViewportState>,
+    ) {
         let mut cfg = config.write();
         let ui_cfg = cfg.config_mut().ui.clone();
         let mut changed = false;
 
         let mut theme = ui_cfg.theme;
-        let mut font_size = ui_cfg.font_size;
+        let mut font_size = ui_cfg.font_scale();
 
         ui.horizontal(|ui| {
             ui.label("Theme:");
@@ -494,13 +885,29 @@ impl PreferencesPanel {
         });
 
         changed |= ui
-            .add(egui::Slider::new(&mut font_size, 0.8..=1.5).text("Font Scale"))
+            .add(egui::Slider::new(&mut font_size, UiConfig::FONT_SCALE_RANGE).text("Font Scale"))
             .changed();
 
         if changed {
-            cfg.config_mut().ui = UiConfig { theme, font_size };
+            if theme != ui_cfg.theme {
+                theme.apply_to_renderer(&mut cfg.config_mut().renderer);
+                if let Some(vp) = viewport_state {
+                    let renderer_cfg = &cfg.config().renderer;
+                    let mut vp = vp.lock();
+                    let device = vp.device.clone();
+                    let queue = vp.queue.clone();
+                    vp.renderer
+                        .apply_viewport_config(&renderer_cfg.viewport, &device, &queue);
+                    vp.renderer
+                        .apply_grid_config(&renderer_cfg.grid, &device, &queue);
+                }
+            }
+            cfg.config_mut().ui = UiConfig {
+                theme,
+                font_size,
+                ..ui_cfg
+            };
+            apply_theme(ui.ctx(), &cfg.config().ui);
         }
-
-        ui.label("(Theme and font changes require restart)");
     }
 }
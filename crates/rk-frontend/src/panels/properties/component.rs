@@ -5,7 +5,7 @@ use glam::Mat4;
 use rk_core::{CollisionElement, Joint, Part};
 use uuid::Uuid;
 
-use crate::state::AppAction;
+use crate::state::{AngleDisplayMode, AppAction, LengthUnit};
 
 /// Information about a child joint (for display in properties panel)
 #[derive(Debug, Clone)]
@@ -26,12 +26,24 @@ pub struct PropertyContext<'a> {
     pub parent_world_transform: Option<Mat4>,
     /// The link's UUID (if any)
     pub link_id: Option<Uuid>,
+    /// The link's world transform (identity if the part has no link)
+    pub link_world_transform: Mat4,
     /// Copy of the link's collisions for display (modifications via actions)
     pub collisions: Vec<CollisionElement>,
     /// Currently selected collision index (if any)
     pub selected_collision_index: Option<usize>,
     /// Joints connecting this part to its children
     pub child_joints: Vec<ChildJointInfo>,
+    /// Joint points on other parts this part can snap onto, with display labels
+    pub joint_point_targets: Vec<(Uuid, String)>,
+    /// Joint whose origin is being placed in the viewport, if any
+    pub joint_origin_pick: Option<Uuid>,
+    /// Joint whose origin the viewport gizmo moves, if any
+    pub joint_origin_drag: Option<Uuid>,
+    /// Unit angles are shown in
+    pub angle_mode: AngleDisplayMode,
+    /// Unit lengths are shown in
+    pub length_unit: LengthUnit,
     /// Queue for actions to be processed
     pub pending_actions: &'a mut Vec<AppAction>,
 }
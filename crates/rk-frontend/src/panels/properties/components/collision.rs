@@ -1,12 +1,13 @@
 //! Collision component - collision element editing
 
-use egui::{DragValue, Ui};
+use egui::Ui;
 
-use rk_core::{GeometryType, Pose};
+use glam::{EulerRot, Mat4, Quat, Vec3};
+use rk_core::{CollisionElement, CollisionSource, GeometryType, Part, Pose};
 
-use crate::panels::properties::helpers::{rotation_row, vector3_row};
+use crate::panels::properties::helpers::{length_drag, length_row, rotation_row};
 use crate::panels::properties::{PropertyComponent, PropertyContext};
-use crate::state::AppAction;
+use crate::state::{AppAction, LengthUnit};
 
 /// Collision component for editing collision elements
 pub struct CollisionComponent {
@@ -43,19 +44,48 @@ impl PropertyComponent for CollisionComponent {
 
         let mut changed = false;
 
-        // Add collision button
+        // Add collision menu
         ui.horizontal(|ui| {
             ui.label(format!("{} collision(s)", ctx.collisions.len()));
-            if ui.button("+ Add").clicked() {
-                // Add a default box collision
-                ctx.pending_actions.push(AppAction::AddCollision {
-                    link_id,
-                    geometry: GeometryType::Box {
-                        size: [0.1, 0.1, 0.1],
-                    },
-                });
-                changed = true;
-            }
+            ui.menu_button("+ Add", |ui| {
+                for (label, geometry) in default_primitives() {
+                    if ui.button(label).clicked() {
+                        ctx.pending_actions
+                            .push(AppAction::AddCollision { link_id, geometry });
+                        // Select the new collision so it can be edited right away
+                        ctx.pending_actions.push(AppAction::SelectCollision(Some((
+                            link_id,
+                            ctx.collisions.len(),
+                        ))));
+                        changed = true;
+                        ui.close();
+                    }
+                }
+            });
+            let has_mesh = !ctx.part.indices.is_empty();
+            ui.add_enabled_ui(has_mesh, |ui| {
+                ui.menu_button("Copy Visual", |ui| {
+                    for source in CollisionSource::ALL {
+                        let hover = match source {
+                            CollisionSource::Exact => "Collide with the visual mesh itself",
+                            CollisionSource::ConvexHull => {
+                                "Collide with the convex hull of the visual mesh"
+                            }
+                            CollisionSource::Simplified => {
+                                "Collide with a decimated copy of the visual mesh"
+                            }
+                        };
+                        if ui.button(source.name()).on_hover_text(hover).clicked() {
+                            ctx.pending_actions
+                                .push(AppAction::CopyVisualToCollision { link_id, source });
+                            changed = true;
+                            ui.close();
+                        }
+                    }
+                })
+                .response
+                .on_hover_text("Replace the collisions with the visual mesh");
+            });
         });
 
         ui.add_space(4.0);
@@ -88,7 +118,7 @@ impl PropertyComponent for CollisionComponent {
                 ui.indent(format!("collision_{}", index), |ui| {
                     // Origin position
                     let mut pos = collision.origin.xyz;
-                    if vector3_row(ui, "Position", &mut pos, 0.01) {
+                    if length_row(ui, "Position", &mut pos, ctx.length_unit) {
                         let origin = Pose::new(pos, collision.origin.rpy);
                         ctx.pending_actions.push(AppAction::UpdateCollisionOrigin {
                             link_id,
@@ -121,7 +151,9 @@ impl PropertyComponent for CollisionComponent {
 
                     // Geometry type selector and parameters
                     ui.add_space(4.0);
-                    if let Some(new_geometry) = render_geometry_editor(ui, &collision.geometry) {
+                    if let Some(new_geometry) =
+                        render_geometry_editor(ui, collision, ctx.length_unit)
+                    {
                         ctx.pending_actions
                             .push(AppAction::UpdateCollisionGeometry {
                                 link_id,
@@ -131,6 +163,39 @@ impl PropertyComponent for CollisionComponent {
                         changed = true;
                     }
 
+                    // Fit primitive to the part's mesh
+                    ui.add_space(4.0);
+                    let mut fitted = None;
+                    ui.horizontal(|ui| {
+                        let has_mesh = !ctx.part.vertices.is_empty();
+                        if ui
+                            .add_enabled(has_mesh, egui::Button::new("Fit Box to Mesh"))
+                            .clicked()
+                        {
+                            fitted = fit_box(ctx.part, ctx.link_world_transform);
+                        }
+                        if ui
+                            .add_enabled(has_mesh, egui::Button::new("Fit Sphere to Mesh"))
+                            .clicked()
+                        {
+                            fitted = fit_sphere(ctx.part, ctx.link_world_transform);
+                        }
+                    });
+                    if let Some((geometry, origin)) = fitted {
+                        ctx.pending_actions
+                            .push(AppAction::UpdateCollisionGeometry {
+                                link_id,
+                                index,
+                                geometry,
+                            });
+                        ctx.pending_actions.push(AppAction::UpdateCollisionOrigin {
+                            link_id,
+                            index,
+                            origin,
+                        });
+                        changed = true;
+                    }
+
                     // Remove button
                     ui.add_space(4.0);
                     if ui.button("Remove").clicked() {
@@ -151,8 +216,76 @@ impl PropertyComponent for CollisionComponent {
     }
 }
 
+/// Primitive types offered by the add menu, with their default sizes
+fn default_primitives() -> [(&'static str, GeometryType); 4] {
+    [
+        (
+            "Box",
+            GeometryType::Box {
+                size: [0.1, 0.1, 0.1],
+            },
+        ),
+        ("Sphere", GeometryType::Sphere { radius: 0.05 }),
+        (
+            "Cylinder",
+            GeometryType::Cylinder {
+                radius: 0.05,
+                length: 0.1,
+            },
+        ),
+        (
+            "Capsule",
+            GeometryType::Capsule {
+                radius: 0.05,
+                length: 0.1,
+            },
+        ),
+    ]
+}
+
+/// Smallest primitive dimension allowed when editing or fitting (meters)
+const MIN_PRIMITIVE_SIZE: f32 = 0.001;
+
+/// Scale, rotation and bounding box center of the part's mesh in the link frame
+fn mesh_in_link_frame(part: &Part, link_world_transform: Mat4) -> Option<(Vec3, Quat, Vec3)> {
+    if part.vertices.is_empty() {
+        return None;
+    }
+    let local = link_world_transform.inverse() * part.origin_transform;
+    let (scale, rotation, _) = local.to_scale_rotation_translation();
+    Some((scale.abs(), rotation, local.transform_point3(part.center())))
+}
+
+/// Fit a box to the part's bounding box
+fn fit_box(part: &Part, link_world_transform: Mat4) -> Option<(GeometryType, Pose)> {
+    let (scale, rotation, center) = mesh_in_link_frame(part, link_world_transform)?;
+    let size = (part.size() * scale).max(Vec3::splat(MIN_PRIMITIVE_SIZE));
+    let (roll, pitch, yaw) = rotation.to_euler(EulerRot::XYZ);
+    Some((
+        GeometryType::Box {
+            size: size.to_array(),
+        },
+        Pose::new(center.to_array(), [roll, pitch, yaw]),
+    ))
+}
+
+/// Fit a sphere enclosing all of the part's vertices
+fn fit_sphere(part: &Part, link_world_transform: Mat4) -> Option<(GeometryType, Pose)> {
+    let (scale, _, center) = mesh_in_link_frame(part, link_world_transform)?;
+    let radius = (part.bounding_radius() * scale.max_element()).max(MIN_PRIMITIVE_SIZE);
+    Some((
+        GeometryType::Sphere { radius },
+        Pose::new(center.to_array(), [0.0; 3]),
+    ))
+}
+
 /// Render geometry editor and return new geometry if changed
-fn render_geometry_editor(ui: &mut Ui, geometry: &GeometryType) -> Option<GeometryType> {
+fn render_geometry_editor(
+    ui: &mut Ui,
+    collision: &CollisionElement,
+    unit: LengthUnit,
+) -> Option<GeometryType> {
+    let geometry = &collision.geometry;
     let mut changed = false;
     let mut new_geometry = geometry.clone();
 
@@ -219,61 +352,43 @@ fn render_geometry_editor(ui: &mut Ui, geometry: &GeometryType) -> Option<Geomet
                 ui.label("Size:");
             });
             ui.horizontal(|ui| {
-                ui.label("X");
-                if ui.add(DragValue::new(&mut size[0]).speed(0.01)).changed() {
-                    changed = true;
-                }
-                ui.label("Y");
-                if ui.add(DragValue::new(&mut size[1]).speed(0.01)).changed() {
-                    changed = true;
-                }
-                ui.label("Z");
-                if ui.add(DragValue::new(&mut size[2]).speed(0.01)).changed() {
-                    changed = true;
+                for (name, value) in ["X", "Y", "Z"].into_iter().zip(size.iter_mut()) {
+                    ui.label(name);
+                    changed |= length_drag(ui, value, unit, MIN_PRIMITIVE_SIZE).changed();
                 }
             });
         }
-        GeometryType::Cylinder { radius, length } => {
+        GeometryType::Cylinder { radius, length } | GeometryType::Capsule { radius, length } => {
             ui.horizontal(|ui| {
                 ui.label("Radius:");
-                if ui.add(DragValue::new(radius).speed(0.01)).changed() {
-                    changed = true;
-                }
+                changed |= length_drag(ui, radius, unit, MIN_PRIMITIVE_SIZE).changed();
             });
             ui.horizontal(|ui| {
                 ui.label("Length:");
-                if ui.add(DragValue::new(length).speed(0.01)).changed() {
-                    changed = true;
-                }
+                changed |= length_drag(ui, length, unit, MIN_PRIMITIVE_SIZE).changed();
             });
         }
         GeometryType::Sphere { radius } => {
             ui.horizontal(|ui| {
                 ui.label("Radius:");
-                if ui.add(DragValue::new(radius).speed(0.01)).changed() {
-                    changed = true;
-                }
+                changed |= length_drag(ui, radius, unit, MIN_PRIMITIVE_SIZE).changed();
             });
         }
-        GeometryType::Capsule { radius, length } => {
-            ui.horizontal(|ui| {
-                ui.label("Radius:");
-                if ui.add(DragValue::new(radius).speed(0.01)).changed() {
-                    changed = true;
+        GeometryType::Mesh { path, .. } => {
+            ui.horizontal(|ui| match &collision.mesh {
+                Some(mesh) => {
+                    ui.label("Imported:");
+                    ui.weak(format!(
+                        "{} ({} triangles)",
+                        mesh.name,
+                        mesh.triangle_count()
+                    ));
                 }
-            });
-            ui.horizontal(|ui| {
-                ui.label("Length:");
-                if ui.add(DragValue::new(length).speed(0.01)).changed() {
-                    changed = true;
+                None => {
+                    ui.label("Path:");
+                    ui.weak(path.as_deref().unwrap_or("(none)"));
                 }
             });
-        }
-        GeometryType::Mesh { path, .. } => {
-            ui.horizontal(|ui| {
-                ui.label("Path:");
-                ui.weak(path.as_deref().unwrap_or("(none)"));
-            });
             ui.weak("Mesh geometry cannot be edited");
         }
     }
@@ -3,11 +3,15 @@
 use egui::{DragValue, Ui};
 use glam::Vec3;
 
-use rk_core::{JointLimits, JointType, Pose};
+use rk_core::{
+    Joint, JointDynamics, JointLimits, JointSafetyController, JointTransmission, JointType, Pose,
+};
+use uuid::Uuid;
 
-use crate::panels::properties::helpers::{rotation_row, vector3_row};
+use crate::panels::joint_list::joint_issue_badge;
+use crate::panels::properties::helpers::{length_row, rotation_row, vector3_row};
 use crate::panels::properties::{PropertyComponent, PropertyContext};
-use crate::state::AppAction;
+use crate::state::{AngleDisplayMode, AppAction, LengthUnit};
 
 /// Joint component for editing joints to child parts
 pub struct JointComponent {
@@ -60,6 +64,7 @@ impl PropertyComponent for JointComponent {
                     self.expanded_index = if is_expanded { None } else { Some(index) };
                 }
                 ui.weak(format!("[{}]", info.joint.joint_type.display_name()));
+                joint_issue_badge(ui, &info.joint);
             });
 
             // Show details when expanded
@@ -89,9 +94,38 @@ impl PropertyComponent for JointComponent {
 
                     ui.add_space(4.0);
 
+                    // Place the origin by clicking a vertex or face in the viewport
+                    let picking = ctx.joint_origin_pick == Some(info.joint_id);
+                    if ui
+                        .selectable_label(picking, "⌖ Pick Origin")
+                        .on_hover_text("Click a vertex or face center in the viewport")
+                        .clicked()
+                    {
+                        ctx.pending_actions.push(AppAction::SetJointOriginPick(
+                            (!picking).then_some(info.joint_id),
+                        ));
+                    }
+
+                    // Move the origin with the viewport gizmo
+                    let dragging = ctx.joint_origin_drag == Some(info.joint_id);
+                    let hint = if info.joint.joint_type == JointType::Prismatic {
+                        "Drag the gizmo to move the origin along the joint axis"
+                    } else {
+                        "Drag the gizmo to move or rotate the origin"
+                    };
+                    if ui
+                        .selectable_label(dragging, "✥ Drag Origin")
+                        .on_hover_text(hint)
+                        .clicked()
+                    {
+                        ctx.pending_actions.push(AppAction::SetJointOriginDrag(
+                            (!dragging).then_some(info.joint_id),
+                        ));
+                    }
+
                     // Origin position
                     let mut pos = info.joint.origin.xyz;
-                    if vector3_row(ui, "Position", &mut pos, 0.01) {
+                    if length_row(ui, "Position", &mut pos, ctx.length_unit) {
                         let origin = Pose::new(pos, info.joint.origin.rpy);
                         ctx.pending_actions.push(AppAction::UpdateJointOrigin {
                             joint_id: info.joint_id,
@@ -138,6 +172,39 @@ impl PropertyComponent for JointComponent {
                             });
                             changed = true;
                         }
+
+                        // Position in the home pose
+                        let is_prismatic = info.joint.joint_type == JointType::Prismatic;
+                        let unit = ctx.length_unit;
+                        let mut home = if is_prismatic {
+                            unit.from_meters(info.joint.default_value)
+                        } else {
+                            info.joint.default_value.to_degrees()
+                        };
+                        let (speed, suffix) = if is_prismatic {
+                            (unit.from_meters(0.01), unit.suffix())
+                        } else {
+                            (1.0, "°")
+                        };
+                        ui.horizontal(|ui| {
+                            ui.label("Home:");
+                            if ui
+                                .add(DragValue::new(&mut home).speed(speed).suffix(suffix))
+                                .on_hover_text("Position of the joint in the home pose")
+                                .changed()
+                            {
+                                let value = if is_prismatic {
+                                    unit.to_meters(home)
+                                } else {
+                                    home.to_radians()
+                                };
+                                ctx.pending_actions.push(AppAction::UpdateJointDefault {
+                                    joint_id: info.joint_id,
+                                    value,
+                                });
+                                changed = true;
+                            }
+                        });
                     }
 
                     // Limits (for revolute/prismatic)
@@ -158,15 +225,24 @@ impl PropertyComponent for JointComponent {
                         let mut effort = limits.effort;
                         let mut velocity = limits.velocity;
 
-                        // Convert to degrees for revolute joints
+                        // Convert to degrees for revolute joints and to the
+                        // length unit for prismatic ones
                         let is_revolute = info.joint.joint_type == JointType::Revolute;
+                        let unit = ctx.length_unit;
                         if is_revolute {
                             lower = lower.to_degrees();
                             upper = upper.to_degrees();
+                        } else {
+                            lower = unit.from_meters(lower);
+                            upper = unit.from_meters(upper);
                         }
 
-                        let suffix = if is_revolute { "°" } else { " m" };
-                        let speed = if is_revolute { 1.0 } else { 0.01 };
+                        let suffix = if is_revolute { "°" } else { unit.suffix() };
+                        let speed = if is_revolute {
+                            1.0
+                        } else {
+                            unit.from_meters(0.01)
+                        };
 
                         let mut limits_changed = false;
 
@@ -187,28 +263,34 @@ impl PropertyComponent for JointComponent {
                             }
                         });
 
-                        ui.horizontal(|ui| {
-                            ui.label("Effort:");
-                            if ui
-                                .add(DragValue::new(&mut effort).speed(1.0).range(0.0..=10000.0))
-                                .changed()
-                            {
-                                limits_changed = true;
-                            }
-                            ui.label("Velocity:");
-                            if ui
-                                .add(DragValue::new(&mut velocity).speed(0.1).range(0.0..=100.0))
-                                .changed()
-                            {
-                                limits_changed = true;
-                            }
-                        });
+                        if effort_velocity_ui(
+                            ui,
+                            &mut effort,
+                            &mut velocity,
+                            info.joint.joint_type,
+                            ctx.angle_mode,
+                            unit,
+                        ) {
+                            limits_changed = true;
+                        }
+
+                        for issue in info.joint.validate() {
+                            let color = if issue.is_error() {
+                                ui.visuals().error_fg_color
+                            } else {
+                                ui.visuals().warn_fg_color
+                            };
+                            ui.colored_label(color, issue.to_string());
+                        }
 
                         if limits_changed {
-                            // Convert back to radians for revolute joints
+                            // Convert back to radians or meters
                             if is_revolute {
                                 lower = lower.to_radians();
                                 upper = upper.to_radians();
+                            } else {
+                                lower = unit.to_meters(lower);
+                                upper = unit.to_meters(upper);
                             }
                             ctx.pending_actions.push(AppAction::UpdateJointLimits {
                                 joint_id: info.joint_id,
@@ -221,6 +303,30 @@ impl PropertyComponent for JointComponent {
                             });
                             changed = true;
                         }
+
+                        ui.add_space(4.0);
+                        if safety_controller_ui(
+                            ui,
+                            ctx.pending_actions,
+                            info.joint_id,
+                            &info.joint,
+                            &limits,
+                            unit,
+                        ) {
+                            changed = true;
+                        }
+                    }
+
+                    // Dynamics and transmission (for actuated joints)
+                    if info.joint.joint_type.has_axis() {
+                        ui.add_space(4.0);
+                        if dynamics_ui(ui, ctx.pending_actions, info.joint_id, &info.joint) {
+                            changed = true;
+                        }
+                        ui.add_space(4.0);
+                        if transmission_ui(ui, ctx.pending_actions, info.joint_id, &info.joint) {
+                            changed = true;
+                        }
                     }
                 });
             }
@@ -229,3 +335,301 @@ impl PropertyComponent for JointComponent {
         changed
     }
 }
+
+/// Effort and velocity limit fields in the units of the joint's motion
+///
+/// Prismatic joints take a force and a linear velocity in the length unit,
+/// revolute and continuous joints a torque and an angular velocity in the
+/// angle unit. `effort` and `velocity` stay in SI units and non-negative.
+/// Returns true if either value was changed.
+fn effort_velocity_ui(
+    ui: &mut Ui,
+    effort: &mut f32,
+    velocity: &mut f32,
+    joint_type: JointType,
+    angle_mode: AngleDisplayMode,
+    unit: LengthUnit,
+) -> bool {
+    let is_prismatic = joint_type == JointType::Prismatic;
+    let (effort_suffix, effort_hint) = if is_prismatic {
+        (" N", "Maximum force the joint can exert")
+    } else {
+        (" N·m", "Maximum torque the joint can exert")
+    };
+    let (mut display, velocity_suffix, velocity_speed) = if is_prismatic {
+        (
+            unit.from_meters(*velocity),
+            format!("{}/s", unit.suffix()),
+            unit.from_meters(0.01),
+        )
+    } else {
+        (
+            angle_mode.from_radians(*velocity),
+            format!("{}/s", angle_mode.suffix()),
+            angle_mode.from_radians(1f32.to_radians()),
+        )
+    };
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Effort:");
+        changed |= ui
+            .add(
+                DragValue::new(effort)
+                    .speed(1.0)
+                    .range(0.0..=f32::MAX)
+                    .suffix(effort_suffix),
+            )
+            .on_hover_text(effort_hint)
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Velocity:");
+        let velocity_changed = ui
+            .add(
+                DragValue::new(&mut display)
+                    .speed(velocity_speed)
+                    .range(0.0..=f32::MAX)
+                    .suffix(velocity_suffix),
+            )
+            .on_hover_text(if is_prismatic {
+                "Maximum linear speed of the joint"
+            } else {
+                "Maximum angular speed of the joint"
+            })
+            .changed();
+        if velocity_changed {
+            *velocity = if is_prismatic {
+                unit.to_meters(display)
+            } else {
+                angle_mode.to_radians(display)
+            };
+            changed = true;
+        }
+    });
+    changed
+}
+
+/// Safety controller (soft limits) editor, shown under the joint limits
+fn safety_controller_ui(
+    ui: &mut Ui,
+    pending_actions: &mut Vec<AppAction>,
+    joint_id: Uuid,
+    joint: &Joint,
+    limits: &JointLimits,
+    unit: LengthUnit,
+) -> bool {
+    let mut enabled = joint.safety_controller.is_some();
+    if ui.checkbox(&mut enabled, "Safety controller").changed() {
+        pending_actions.push(AppAction::UpdateJointSafetyController {
+            joint_id,
+            safety_controller: enabled.then(|| JointSafetyController::from_limits(limits)),
+        });
+        return true;
+    }
+
+    let Some(mut sc) = joint.safety_controller else {
+        return false;
+    };
+
+    let is_revolute = joint.joint_type == JointType::Revolute;
+    let (suffix, speed) = if is_revolute {
+        ("°", 1.0)
+    } else {
+        (unit.suffix(), unit.from_meters(0.01))
+    };
+    if is_revolute {
+        sc.soft_lower_limit = sc.soft_lower_limit.to_degrees();
+        sc.soft_upper_limit = sc.soft_upper_limit.to_degrees();
+    } else {
+        sc.soft_lower_limit = unit.from_meters(sc.soft_lower_limit);
+        sc.soft_upper_limit = unit.from_meters(sc.soft_upper_limit);
+    }
+
+    let mut sc_changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Soft lower:");
+        sc_changed |= ui
+            .add(
+                DragValue::new(&mut sc.soft_lower_limit)
+                    .speed(speed)
+                    .suffix(suffix),
+            )
+            .changed();
+        ui.label("Soft upper:");
+        sc_changed |= ui
+            .add(
+                DragValue::new(&mut sc.soft_upper_limit)
+                    .speed(speed)
+                    .suffix(suffix),
+            )
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("k_position:");
+        sc_changed |= ui
+            .add(
+                DragValue::new(&mut sc.k_position)
+                    .speed(1.0)
+                    .range(0.0..=10000.0),
+            )
+            .changed();
+        ui.label("k_velocity:");
+        sc_changed |= ui
+            .add(
+                DragValue::new(&mut sc.k_velocity)
+                    .speed(0.1)
+                    .range(0.0..=10000.0),
+            )
+            .changed();
+    });
+
+    if !sc_changed {
+        return false;
+    }
+
+    if is_revolute {
+        sc.soft_lower_limit = sc.soft_lower_limit.to_radians();
+        sc.soft_upper_limit = sc.soft_upper_limit.to_radians();
+    } else {
+        sc.soft_lower_limit = unit.to_meters(sc.soft_lower_limit);
+        sc.soft_upper_limit = unit.to_meters(sc.soft_upper_limit);
+    }
+    pending_actions.push(AppAction::UpdateJointSafetyController {
+        joint_id,
+        safety_controller: Some(sc),
+    });
+    true
+}
+
+/// Damping and friction editor for actuated joints
+fn dynamics_ui(
+    ui: &mut Ui,
+    pending_actions: &mut Vec<AppAction>,
+    joint_id: Uuid,
+    joint: &Joint,
+) -> bool {
+    let mut enabled = joint.dynamics.is_some();
+    if ui
+        .checkbox(&mut enabled, "Dynamics")
+        .on_hover_text("Damping and friction for simulators such as Gazebo and MuJoCo")
+        .changed()
+    {
+        pending_actions.push(AppAction::UpdateJointDynamics {
+            joint_id,
+            dynamics: enabled.then(JointDynamics::default_damped),
+        });
+        return true;
+    }
+
+    let Some(mut dynamics) = joint.dynamics else {
+        return false;
+    };
+
+    let (damping_suffix, friction_suffix) = if joint.joint_type == JointType::Prismatic {
+        (" N·s/m", " N")
+    } else {
+        (" N·m·s/rad", " N·m")
+    };
+    let mut dynamics_changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Damping:");
+        dynamics_changed |= ui
+            .add(
+                DragValue::new(&mut dynamics.damping)
+                    .speed(0.01)
+                    .range(0.0..=f32::MAX)
+                    .suffix(damping_suffix),
+            )
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Friction:");
+        dynamics_changed |= ui
+            .add(
+                DragValue::new(&mut dynamics.friction)
+                    .speed(0.01)
+                    .range(0.0..=f32::MAX)
+                    .suffix(friction_suffix),
+            )
+            .changed();
+    });
+
+    if dynamics_changed {
+        pending_actions.push(AppAction::UpdateJointDynamics {
+            joint_id,
+            dynamics: Some(dynamics),
+        });
+    }
+    dynamics_changed
+}
+
+/// ros_control transmission editor for actuated joints
+fn transmission_ui(
+    ui: &mut Ui,
+    pending_actions: &mut Vec<AppAction>,
+    joint_id: Uuid,
+    joint: &Joint,
+) -> bool {
+    let mut enabled = joint.transmission.is_some();
+    if ui.checkbox(&mut enabled, "Transmission").changed() {
+        pending_actions.push(AppAction::UpdateJointTransmission {
+            joint_id,
+            transmission: enabled.then(|| JointTransmission::simple(&joint.name)),
+        });
+        return true;
+    }
+
+    let Some(mut transmission) = joint.transmission.clone() else {
+        return false;
+    };
+
+    let mut transmission_changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Interface:");
+        let current = transmission
+            .hardware_interface
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        egui::ComboBox::from_id_salt(("transmission_interface", joint_id))
+            .selected_text(current)
+            .show_ui(ui, |ui| {
+                for interface in JointTransmission::HARDWARE_INTERFACES {
+                    let label = interface.rsplit('/').next().unwrap_or(interface);
+                    if ui
+                        .selectable_label(transmission.hardware_interface == interface, label)
+                        .clicked()
+                    {
+                        transmission.hardware_interface = interface.to_string();
+                        transmission_changed = true;
+                    }
+                }
+            });
+    });
+    ui.horizontal(|ui| {
+        ui.label("Actuator:");
+        transmission_changed |= ui
+            .text_edit_singleline(&mut transmission.actuator_name)
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Reduction:");
+        transmission_changed |= ui
+            .add(
+                DragValue::new(&mut transmission.mechanical_reduction)
+                    .speed(0.1)
+                    .range(0.001..=100000.0),
+            )
+            .changed();
+    });
+
+    if transmission_changed {
+        pending_actions.push(AppAction::UpdateJointTransmission {
+            joint_id,
+            transmission: Some(transmission),
+        });
+    }
+    transmission_changed
+}
@@ -0,0 +1,118 @@
+//! Joint point component - connection points other parts snap onto
+
+use egui::Ui;
+use glam::Vec3;
+
+use rk_core::{JointPoint, JointType};
+
+use crate::panels::properties::helpers::{length_row, vector3_row};
+use crate::panels::properties::{PropertyComponent, PropertyContext};
+use crate::state::AppAction;
+
+/// Joint point component for editing a part's connection points
+pub struct JointPointComponent;
+
+impl JointPointComponent {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for JointPointComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PropertyComponent for JointPointComponent {
+    fn name(&self) -> &str {
+        "Joint Points"
+    }
+
+    fn default_open(&self) -> bool {
+        false
+    }
+
+    fn ui(&mut self, ui: &mut Ui, ctx: &mut PropertyContext) -> bool {
+        let mut changed = false;
+        let mut remove = None;
+        let part = &mut ctx.part;
+
+        for (index, point) in part.joint_points.iter_mut().enumerate() {
+            ui.push_id(point.id, |ui| {
+                ui.horizontal(|ui| {
+                    changed |= ui.text_edit_singleline(&mut point.name).changed();
+                    if ui.small_button("🗑").on_hover_text("Remove").clicked() {
+                        remove = Some(index);
+                    }
+                });
+
+                let mut position = point.position.to_array();
+                if length_row(ui, "Position", &mut position, ctx.length_unit) {
+                    point.position = Vec3::from_array(position);
+                    changed = true;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Type:");
+                    egui::ComboBox::from_id_salt("joint_point_type")
+                        .selected_text(point.joint_type.display_name())
+                        .show_ui(ui, |ui| {
+                            for joint_type in JointType::all() {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut point.joint_type,
+                                        *joint_type,
+                                        joint_type.display_name(),
+                                    )
+                                    .changed();
+                            }
+                        });
+                });
+
+                if point.joint_type.has_axis() {
+                    let mut axis = point.axis.to_array();
+                    if vector3_row(ui, "Axis", &mut axis, 0.1) {
+                        point.axis = Vec3::from_array(axis).normalize_or(Vec3::Z);
+                        changed = true;
+                    }
+                }
+
+                // Snapping moves this part so the point lands on the target
+                ui.horizontal(|ui| {
+                    ui.label("Snap to:");
+                    egui::ComboBox::from_id_salt("joint_point_snap")
+                        .selected_text("Select point...")
+                        .show_ui(ui, |ui| {
+                            if ctx.joint_point_targets.is_empty() {
+                                ui.weak("No joint points on other parts");
+                            }
+                            for (target, label) in &ctx.joint_point_targets {
+                                if ui.selectable_label(false, label).clicked() {
+                                    ctx.pending_actions.push(AppAction::SnapJointPoints {
+                                        parent_point: *target,
+                                        child_point: point.id,
+                                    });
+                                }
+                            }
+                        });
+                });
+            });
+            ui.separator();
+        }
+
+        if let Some(index) = remove {
+            part.joint_points.remove(index);
+            changed = true;
+        }
+
+        if ui.button("Add Joint Point").clicked() {
+            let name = format!("point_{}", part.joint_points.len() + 1);
+            let center = part.center();
+            part.add_joint_point(JointPoint::new(name, part.id, center));
+            changed = true;
+        }
+
+        changed
+    }
+}
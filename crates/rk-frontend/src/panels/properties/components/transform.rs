@@ -1,20 +1,91 @@
 //! Transform component - position, rotation, and scale editing
 
-use egui::Ui;
+use egui::{DragValue, Ui};
 use glam::{EulerRot, Mat4, Quat, Vec3};
 
-use crate::panels::properties::helpers::{rotation_row, vector3_row};
+use crate::panels::properties::helpers::{angle_row, length_drag, length_row, vector3_row};
 use crate::panels::properties::{PropertyComponent, PropertyContext};
+use crate::state::{AngleDisplayMode, LengthUnit};
+
+/// Axis names for the nudge buttons, as (translation, rotation)
+const NUDGE_AXES: [(&str, &str); 3] = [("X", "Roll"), ("Y", "Pitch"), ("Z", "Yaw")];
 
 /// Transform component (position, rotation, scale)
 pub struct TransformComponent {
-    /// Whether to show local coordinates instead of world coordinates
+    /// Whether to show coordinates relative to the parent link instead of world coordinates
     show_local: bool,
+    /// Distance moved by one nudge (meters)
+    nudge_distance: f32,
+    /// Angle rotated by one nudge (radians)
+    nudge_angle: f32,
 }
 
 impl TransformComponent {
     pub fn new() -> Self {
-        Self { show_local: false }
+        Self {
+            show_local: false,
+            nudge_distance: 0.01,
+            nudge_angle: 15f32.to_radians(),
+        }
+    }
+
+    /// Show the relative nudge controls
+    ///
+    /// Returns the translation and rotation to apply, expressed in the
+    /// displayed coordinate frame.
+    fn nudge_ui(
+        &mut self,
+        ui: &mut Ui,
+        angle_mode: AngleDisplayMode,
+        length_unit: LengthUnit,
+    ) -> Option<(Vec3, Quat)> {
+        let mut nudge = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Move by");
+            length_drag(ui, &mut self.nudge_distance, length_unit, 0.0);
+        });
+        ui.horizontal(|ui| {
+            for (axis, (name, _)) in NUDGE_AXES.iter().enumerate() {
+                for sign in [-1.0, 1.0] {
+                    let label = format!("{}{}", if sign < 0.0 { "-" } else { "+" }, name);
+                    if ui.small_button(label).clicked() {
+                        let delta = Vec3::AXES[axis] * sign * self.nudge_distance;
+                        nudge = Some((delta, Quat::IDENTITY));
+                    }
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Rotate by");
+            let mut angle = angle_mode.from_radians(self.nudge_angle);
+            if ui
+                .add(
+                    DragValue::new(&mut angle)
+                        .speed(angle_mode.from_radians(1f32.to_radians()))
+                        .range(0.0..=angle_mode.from_radians(std::f32::consts::PI))
+                        .suffix(angle_mode.suffix()),
+                )
+                .changed()
+            {
+                self.nudge_angle = angle_mode.to_radians(angle);
+            }
+        });
+        ui.horizontal(|ui| {
+            for (axis, (_, name)) in NUDGE_AXES.iter().enumerate() {
+                for sign in [-1.0, 1.0] {
+                    let label = format!("{}{}", if sign < 0.0 { "-" } else { "+" }, name);
+                    if ui.small_button(label).clicked() {
+                        let rotation =
+                            Quat::from_axis_angle(Vec3::AXES[axis], sign * self.nudge_angle);
+                        nudge = Some((Vec3::ZERO, rotation));
+                    }
+                }
+            }
+        });
+
+        nudge
     }
 }
 
@@ -30,109 +101,68 @@ impl PropertyComponent for TransformComponent {
     }
 
     fn ui(&mut self, ui: &mut Ui, ctx: &mut PropertyContext) -> bool {
-        let part = &mut ctx.part;
         let parent_transform = ctx.parent_world_transform;
 
-        // Extract position, rotation, and scale from the transform matrix (world coordinates)
-        let (scale, rotation, translation) = part.origin_transform.to_scale_rotation_translation();
-        let euler = rotation.to_euler(EulerRot::XYZ);
-
-        let mut pos = [translation.x, translation.y, translation.z];
-        let mut rot_deg = [
-            euler.0.to_degrees(),
-            euler.1.to_degrees(),
-            euler.2.to_degrees(),
-        ];
-        let mut scl = [scale.x, scale.y, scale.z];
-
-        // Local transform variables
-        let mut local_pos = [0.0f32; 3];
-        let mut local_rot_deg = [0.0f32; 3];
-        let mut local_scl = [1.0f32; 3];
-
-        // Calculate local transform if parent exists
-        if let Some(parent) = parent_transform {
-            let parent_inverse = parent.inverse();
-            let local = parent_inverse * part.origin_transform;
-            let (local_scale, local_rotation, local_translation) =
-                local.to_scale_rotation_translation();
-            let local_euler = local_rotation.to_euler(EulerRot::XYZ);
-
-            local_pos = [
-                local_translation.x,
-                local_translation.y,
-                local_translation.z,
-            ];
-            local_rot_deg = [
-                local_euler.0.to_degrees(),
-                local_euler.1.to_degrees(),
-                local_euler.2.to_degrees(),
-            ];
-            local_scl = [local_scale.x, local_scale.y, local_scale.z];
-        }
-
-        // Show toggle switch only if parent exists
+        // Choose the coordinate frame, only offering the parent frame if there is a parent
         if parent_transform.is_some() {
-            ui.checkbox(&mut self.show_local, "Local");
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.show_local, false, "World")
+                    .on_hover_text("Values are relative to the world origin");
+                ui.selectable_value(&mut self.show_local, true, "Parent")
+                    .on_hover_text("Values are relative to the parent link");
+            });
             ui.add_space(4.0);
         }
-
-        let (pos_changed, rot_changed, scale_changed);
-        let (local_pos_changed, local_rot_changed, local_scale_changed);
-
-        if self.show_local && parent_transform.is_some() {
-            // Show local coordinates
-            local_pos_changed = vector3_row(ui, "Position", &mut local_pos, 0.01);
-            local_rot_changed = rotation_row(ui, "Rotation", &mut local_rot_deg, 1.0);
-            local_scale_changed = vector3_row(ui, "Scale", &mut local_scl, 0.01);
-            pos_changed = false;
-            rot_changed = false;
-            scale_changed = false;
-        } else {
-            // Show world coordinates
-            pos_changed = vector3_row(ui, "Position", &mut pos, 0.01);
-            rot_changed = rotation_row(ui, "Rotation", &mut rot_deg, 1.0);
-            scale_changed = vector3_row(ui, "Scale", &mut scl, 0.01);
-            local_pos_changed = false;
-            local_rot_changed = false;
-            local_scale_changed = false;
+        let frame = parent_transform
+            .filter(|_| self.show_local)
+            .unwrap_or(Mat4::IDENTITY);
+
+        // Decompose the transform in the displayed frame (world = frame * local)
+        let local = frame.inverse() * ctx.part.origin_transform;
+        let (scale, rotation, translation) = local.to_scale_rotation_translation();
+        let (rx, ry, rz) = rotation.to_euler(EulerRot::XYZ);
+
+        let mut pos = translation.to_array();
+        let mut rot = [rx, ry, rz];
+        let mut scl = scale.to_array();
+
+        let mut changed = length_row(ui, "Position", &mut pos, ctx.length_unit);
+        changed |= angle_row(ui, "Rotation", &mut rot, ctx.angle_mode);
+        changed |= vector3_row(ui, "Scale", &mut scl, 0.01);
+
+        if changed {
+            let new_local = Mat4::from_scale_rotation_translation(
+                Vec3::from_array(scl),
+                Quat::from_euler(EulerRot::XYZ, rot[0], rot[1], rot[2]),
+                Vec3::from_array(pos),
+            );
+            ctx.part.origin_transform = frame * new_local;
         }
 
-        // Update transform if changed
-        if local_pos_changed || local_rot_changed || local_scale_changed {
-            // Local transform was edited - compute new world transform
-            let parent = parent_transform.unwrap();
-            let new_local_rotation = Quat::from_euler(
-                EulerRot::XYZ,
-                local_rot_deg[0].to_radians(),
-                local_rot_deg[1].to_radians(),
-                local_rot_deg[2].to_radians(),
-            );
-            let new_local_translation = Vec3::new(local_pos[0], local_pos[1], local_pos[2]);
-            let new_local_scale = Vec3::new(local_scl[0], local_scl[1], local_scl[2]);
-            let new_local_transform = Mat4::from_scale_rotation_translation(
-                new_local_scale,
-                new_local_rotation,
-                new_local_translation,
-            );
-            // world = parent * local
-            part.origin_transform = parent * new_local_transform;
-            true
-        } else if pos_changed || rot_changed || scale_changed {
-            // World transform was edited
-            let new_rotation = Quat::from_euler(
-                EulerRot::XYZ,
-                rot_deg[0].to_radians(),
-                rot_deg[1].to_radians(),
-                rot_deg[2].to_radians(),
-            );
-            let new_translation = Vec3::new(pos[0], pos[1], pos[2]);
-            let new_scale = Vec3::new(scl[0], scl[1], scl[2]);
-            part.origin_transform =
-                Mat4::from_scale_rotation_translation(new_scale, new_rotation, new_translation);
-            true
-        } else {
-            false
+        // Relative nudges along and about the displayed frame's axes
+        ui.add_space(4.0);
+        if let Some((delta, rotation)) = self.nudge_ui(ui, ctx.angle_mode, ctx.length_unit) {
+            ctx.part.origin_transform =
+                nudge_transform(ctx.part.origin_transform, frame, delta, rotation);
+            changed = true;
         }
+
+        changed
     }
 }
+
+/// Move and rotate a world transform by a delta expressed in `frame`
+///
+/// The rotation turns the transform about its own origin, so nudging the
+/// rotation never moves the part.
+fn nudge_transform(transform: Mat4, frame: Mat4, delta: Vec3, rotation: Quat) -> Mat4 {
+    let (_, frame_rotation, _) = frame.to_scale_rotation_translation();
+    let origin = transform.w_axis.truncate();
+    let world_rotation = frame_rotation * rotation * frame_rotation.inverse();
+    let world_delta = frame_rotation * delta;
+
+    Mat4::from_translation(origin + world_delta)
+        * Mat4::from_quat(world_rotation)
+        * Mat4::from_translation(-origin)
+        * transform
+}
@@ -1,10 +1,10 @@
-//! Visual component - color and material editing
+//! Visual component - color and PBR material editing
 
 use egui::Ui;
 
 use crate::panels::properties::{PropertyComponent, PropertyContext};
 
-/// Visual properties component (color, material)
+/// Visual properties component (color, metallic/roughness, material)
 pub struct VisualComponent;
 
 impl VisualComponent {
@@ -46,6 +46,77 @@ impl PropertyComponent for VisualComponent {
                 ];
                 changed = true;
             }
+            if !part.vertex_colors.is_empty() {
+                ui.weak("(vertex colors)").on_hover_text(
+                    "The mesh has per-vertex colors, which the viewport shows instead of this color",
+                );
+            }
+        });
+
+        // Viewport-only color, e.g. to tell apart links sharing a material
+        ui.horizontal(|ui| {
+            ui.label("Display:");
+            match &mut part.color_override {
+                Some(override_color) => {
+                    let mut color = egui::Rgba::from_rgba_unmultiplied(
+                        override_color[0],
+                        override_color[1],
+                        override_color[2],
+                        override_color[3],
+                    );
+                    if egui::color_picker::color_edit_button_rgba(
+                        ui,
+                        &mut color,
+                        egui::color_picker::Alpha::OnlyBlend,
+                    )
+                    .changed()
+                    {
+                        *override_color = color.to_rgba_unmultiplied();
+                        changed = true;
+                    }
+                    if ui
+                        .button("Reset to material")
+                        .on_hover_text("Show the material color again")
+                        .clicked()
+                    {
+                        part.color_override = None;
+                        changed = true;
+                    }
+                }
+                None => {
+                    if ui
+                        .button("Override")
+                        .on_hover_text(
+                            "Show this part in a different color while editing; \
+                             the exported color is unchanged",
+                        )
+                        .clicked()
+                    {
+                        part.color_override = Some(part.color);
+                        changed = true;
+                    }
+                }
+            }
+        });
+
+        // PBR factors
+        ui.horizontal(|ui| {
+            ui.label("Metallic:");
+            if ui
+                .add(egui::Slider::new(&mut part.metallic, 0.0..=1.0).fixed_decimals(2))
+                .changed()
+            {
+                changed = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Roughness:");
+            if ui
+                .add(egui::Slider::new(&mut part.roughness, 0.0..=1.0).fixed_decimals(2))
+                .changed()
+            {
+                changed = true;
+            }
         });
 
         // Material name
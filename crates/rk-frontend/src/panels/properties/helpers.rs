@@ -1,6 +1,8 @@
 //! Common UI helper functions for property components
 
-use egui::{DragValue, Ui};
+use egui::{DragValue, Response, Ui};
+
+use crate::state::{AngleDisplayMode, LengthUnit};
 
 /// Render a labeled XYZ vector3 row with drag values
 /// Returns true if any value was changed
@@ -68,6 +70,65 @@ pub fn rotation_row(ui: &mut Ui, label: &str, rot_deg: &mut [f32; 3], speed: f32
     .inner
 }
 
+/// Render a rotation row in the given angle unit, with values stored in radians
+/// Returns true if any value was changed
+pub fn angle_row(ui: &mut Ui, label: &str, radians: &mut [f32; 3], mode: AngleDisplayMode) -> bool {
+    let speed = mode.from_radians(1f32.to_radians());
+    let mut display = radians.map(|r| mode.from_radians(r));
+    ui.horizontal(|ui| {
+        ui.label(label);
+    });
+    let changed = ui
+        .horizontal(|ui| {
+            let mut changed = false;
+            for (name, value) in ["X", "Y", "Z"].into_iter().zip(&mut display) {
+                ui.label(name);
+                changed |= ui
+                    .add(DragValue::new(value).speed(speed).suffix(mode.suffix()))
+                    .changed();
+            }
+            changed
+        })
+        .inner;
+    if changed {
+        *radians = display.map(|v| mode.to_radians(v));
+    }
+    changed
+}
+
+/// Render a labeled XYZ row in the given length unit, with values stored in meters
+/// Returns true if any value was changed
+pub fn length_row(ui: &mut Ui, label: &str, meters: &mut [f32; 3], unit: LengthUnit) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(label);
+    });
+    ui.horizontal(|ui| {
+        let mut changed = false;
+        for (name, value) in ["X", "Y", "Z"].into_iter().zip(meters.iter_mut()) {
+            ui.label(name);
+            changed |= length_drag(ui, value, unit, f32::NEG_INFINITY).changed();
+        }
+        changed
+    })
+    .inner
+}
+
+/// Render a drag value in the given length unit for a value stored in meters,
+/// kept at or above `min` meters
+pub fn length_drag(ui: &mut Ui, meters: &mut f32, unit: LengthUnit, min: f32) -> Response {
+    let mut display = unit.from_meters(*meters);
+    let response = ui.add(
+        DragValue::new(&mut display)
+            .speed(unit.from_meters(0.01))
+            .range(unit.from_meters(min)..=f32::MAX)
+            .suffix(unit.suffix()),
+    );
+    if response.changed() {
+        *meters = unit.to_meters(display);
+    }
+    response
+}
+
 /// Render rotation row (read-only) with degree suffix
 #[allow(dead_code)]
 pub fn rotation_readonly_row(ui: &mut Ui, label: &str, rot_deg: &[f32; 3]) {
@@ -5,12 +5,16 @@ mod components;
 mod helpers;
 
 pub use component::{ChildJointInfo, PropertyComponent, PropertyContext};
+pub(crate) use helpers::length_drag;
 
 use components::{
-    CollisionComponent, GeometryComponent, JointComponent, PhysicalComponent, TransformComponent,
-    VisualComponent,
+    CollisionComponent, GeometryComponent, JointComponent, JointPointComponent, PhysicalComponent,
+    TransformComponent, VisualComponent,
 };
 
+use glam::Mat4;
+use uuid::Uuid;
+
 use crate::config::SharedConfig;
 use crate::panels::Panel;
 use crate::state::{AppAction, SharedAppState, SharedViewportState};
@@ -23,6 +27,7 @@ pub struct PropertiesPanel {
     geometry: GeometryComponent,
     collision: CollisionComponent,
     joint: JointComponent,
+    joint_point: JointPointComponent,
 }
 
 impl PropertiesPanel {
@@ -34,6 +39,7 @@ impl PropertiesPanel {
             geometry: GeometryComponent::new(),
             collision: CollisionComponent::new(),
             joint: JointComponent::new(),
+            joint_point: JointPointComponent::new(),
         }
     }
 }
@@ -74,45 +80,47 @@ impl Panel for PropertiesPanel {
         };
 
         // Find link info for this part
-        let (link_id, parent_world_transform, collisions, child_joints) = state
-            .project
-            .assembly
-            .find_link_by_part(selected_id)
-            .map(|link| {
-                let parent_transform = state
-                    .project
-                    .assembly
-                    .get_parent_link(link.id)
-                    .map(|parent| parent.world_transform);
-
-                // Collect child joint info
-                let children = state.project.assembly.get_children(link.id);
-                let child_joints: Vec<ChildJointInfo> = children
-                    .iter()
-                    .filter_map(|(joint_id, child_link_id)| {
-                        let joint = state.project.assembly.get_joint(*joint_id)?.clone();
-                        let child_link = state.project.assembly.get_link(*child_link_id)?;
-                        let child_part_name = child_link
-                            .part_id
-                            .and_then(|pid| state.project.get_part(pid))
-                            .map(|p| p.name.clone())
-                            .unwrap_or_else(|| child_link.name.clone());
-                        Some(ChildJointInfo {
-                            joint_id: *joint_id,
-                            joint,
-                            child_part_name,
+        let (link_id, link_world_transform, parent_world_transform, collisions, child_joints) =
+            state
+                .project
+                .assembly
+                .find_link_by_part(selected_id)
+                .map(|link| {
+                    let parent_transform = state
+                        .project
+                        .assembly
+                        .get_parent_link(link.id)
+                        .map(|parent| parent.world_transform);
+
+                    // Collect child joint info
+                    let children = state.project.assembly.get_children(link.id);
+                    let child_joints: Vec<ChildJointInfo> = children
+                        .iter()
+                        .filter_map(|(joint_id, child_link_id)| {
+                            let joint = state.project.assembly.get_joint(*joint_id)?.clone();
+                            let child_link = state.project.assembly.get_link(*child_link_id)?;
+                            let child_part_name = child_link
+                                .part_id
+                                .and_then(|pid| state.project.get_part(pid))
+                                .map(|p| p.name.clone())
+                                .unwrap_or_else(|| child_link.name.clone());
+                            Some(ChildJointInfo {
+                                joint_id: *joint_id,
+                                joint,
+                                child_part_name,
+                            })
                         })
-                    })
-                    .collect();
-
-                (
-                    Some(link.id),
-                    parent_transform,
-                    link.collisions.clone(),
-                    child_joints,
-                )
-            })
-            .unwrap_or((None, None, Vec::new(), Vec::new()));
+                        .collect();
+
+                    (
+                        Some(link.id),
+                        link.world_transform,
+                        parent_transform,
+                        link.collisions.clone(),
+                        child_joints,
+                    )
+                })
+                .unwrap_or((None, Mat4::IDENTITY, None, Vec::new(), Vec::new()));
 
         // Get selected collision index if the link matches
         let selected_collision_index = state.selected_collision.and_then(|(sel_link_id, index)| {
@@ -123,6 +131,21 @@ impl Panel for PropertiesPanel {
             }
         });
 
+        let angle_mode = state.angle_display_mode;
+        let length_unit = state.length_unit;
+        let joint_origin_pick = state.joint_origin_pick;
+        let joint_origin_drag = state.joint_origin_drag;
+        let joint_point_targets: Vec<(Uuid, String)> = state
+            .project
+            .parts_iter()
+            .filter(|part| part.id != selected_id)
+            .flat_map(|part| {
+                part.joint_points
+                    .iter()
+                    .map(move |point| (point.id, format!("{}: {}", part.name, point.name)))
+            })
+            .collect();
+
         let Some(part) = state.get_part_mut(selected_id) else {
             ui.weak("Selected part not found");
             return;
@@ -147,9 +170,15 @@ impl Panel for PropertiesPanel {
             part,
             parent_world_transform,
             link_id,
+            link_world_transform,
             collisions,
             selected_collision_index,
             child_joints,
+            joint_point_targets,
+            joint_origin_pick,
+            joint_origin_drag,
+            angle_mode,
+            length_unit,
             pending_actions: &mut pending_actions,
         };
 
@@ -157,10 +186,11 @@ impl Panel for PropertiesPanel {
         // Track if transform was changed
         let transform_changed = render_component(ui, &mut self.transform, &mut ctx);
         render_component(ui, &mut self.physical, &mut ctx);
-        render_component(ui, &mut self.visual, &mut ctx);
+        let visual_changed = render_component(ui, &mut self.visual, &mut ctx);
         render_component(ui, &mut self.geometry, &mut ctx);
         render_component(ui, &mut self.collision, &mut ctx);
         render_component(ui, &mut self.joint, &mut ctx);
+        render_component(ui, &mut self.joint_point, &mut ctx);
 
         // If transform changed, update the renderer
         let new_transform = if transform_changed {
@@ -169,6 +199,17 @@ impl Panel for PropertiesPanel {
             None
         };
 
+        // If visual properties changed, push color and material to the renderer
+        let new_material = if visual_changed {
+            Some((
+                ctx.part.display_color(),
+                ctx.part.metallic,
+                ctx.part.roughness,
+            ))
+        } else {
+            None
+        };
+
         // Queue any pending actions from components
         for action in pending_actions {
             state.queue_action(action);
@@ -182,6 +223,13 @@ impl Panel for PropertiesPanel {
                 .lock()
                 .update_part_transform(selected_id, transform);
         }
+
+        // Update renderer with new material
+        if let Some((color, metallic, roughness)) = new_material {
+            let mut vp = viewport_state.lock();
+            vp.update_part_color(selected_id, color);
+            vp.update_part_material(selected_id, metallic, roughness);
+        }
     }
 }
 
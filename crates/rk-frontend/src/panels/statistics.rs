@@ -0,0 +1,117 @@
+//! Statistics panel with aggregate counts, mass and extent of the robot
+
+use egui::Ui;
+
+use crate::panels::Panel;
+use crate::state::SharedAppState;
+
+/// Statistics panel for sanity checks before export
+pub struct StatisticsPanel {
+    // Panel has no persistent state - stats are recomputed every frame
+}
+
+impl StatisticsPanel {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for StatisticsPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Panel for StatisticsPanel {
+    fn name(&self) -> &str {
+        "Statistics"
+    }
+
+    fn ui(&mut self, ui: &mut Ui, app_state: &SharedAppState) {
+        let state = app_state.lock();
+        let stats = state.project.statistics();
+        // Heaviest link, to spot a mass that would destabilize simulation
+        let heaviest = state
+            .project
+            .assembly
+            .links
+            .values()
+            .max_by(|a, b| a.inertial.mass.total_cmp(&b.inertial.mass))
+            .map(|link| (link.name.clone(), link.inertial.mass));
+        let unit = state.length_unit;
+        let massless = state
+            .project
+            .assembly
+            .links
+            .values()
+            .filter(|link| link.part_id.is_some() && link.inertial.mass <= 0.0)
+            .count();
+        drop(state);
+
+        egui::Grid::new("statistics_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Links:");
+                ui.label(stats.link_count.to_string());
+                ui.end_row();
+
+                ui.label("Joints:");
+                ui.label(stats.joint_count.to_string());
+                ui.end_row();
+
+                ui.label("Parts:");
+                ui.label(stats.part_count.to_string());
+                ui.end_row();
+
+                ui.label("Triangles:");
+                ui.label(stats.triangle_count.to_string());
+                ui.end_row();
+
+                ui.label("Total mass:");
+                ui.label(format!("{:.3} kg", stats.total_mass));
+                ui.end_row();
+
+                if let Some((name, mass)) = heaviest {
+                    ui.label("Heaviest link:");
+                    ui.label(format!("{} ({:.3} kg)", name, mass));
+                    ui.end_row();
+                }
+
+                ui.label("Center of mass:");
+                match stats.center_of_mass {
+                    Some(com) => ui.label(format!(
+                        "({}, {}, {})",
+                        unit.format(com.x),
+                        unit.format(com.y),
+                        unit.format(com.z)
+                    )),
+                    None => ui.weak("-"),
+                };
+                ui.end_row();
+
+                ui.label("Size:");
+                match stats.bounds {
+                    Some((min, max)) => {
+                        let size = max - min;
+                        ui.label(format!(
+                            "{} x {} x {}",
+                            unit.format(size.x),
+                            unit.format(size.y),
+                            unit.format(size.z)
+                        ))
+                    }
+                    None => ui.weak("-"),
+                };
+                ui.end_row();
+            });
+
+        if massless > 0 {
+            ui.add_space(4.0);
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("⚠ {} link(s) with geometry have no mass", massless),
+            );
+        }
+    }
+}
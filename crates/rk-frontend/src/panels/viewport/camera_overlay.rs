@@ -1,8 +1,10 @@
 //! Camera settings overlay for the 3D viewport
 
 use glam::Vec3;
+use rk_renderer::config::MiddleMouseAction;
 use rk_renderer::{GizmoMode, GizmoSpace};
 
+use crate::config::SharedConfig;
 use crate::state::SharedViewportState;
 
 /// Render camera settings overlay in the top-right corner (Unity-style)
@@ -10,6 +12,7 @@ pub fn render_camera_settings(
     ui: &mut egui::Ui,
     rect: egui::Rect,
     viewport_state: &SharedViewportState,
+    config: &SharedConfig,
     show_camera_settings: &mut bool,
 ) {
     let panel_width = 180.0;
@@ -88,51 +91,171 @@ pub fn render_camera_settings(
                         }
                     });
 
-                    // Near plane
+                    // Clip planes, fitted to the content unless set by hand
+                    let mut auto_clip = vp.renderer.auto_clip_planes();
+                    if ui
+                        .checkbox(&mut auto_clip, "Auto clip planes")
+                        .on_hover_text("Fit near and far tightly around the robot")
+                        .changed()
+                    {
+                        let mut camera = config.read().config().renderer.camera.clone();
+                        camera.auto_clip_planes = auto_clip;
+                        vp.renderer.apply_camera_config(&camera);
+                        config.write().config_mut().renderer.camera = camera;
+                    }
+
+                    ui.add_enabled_ui(!auto_clip, |ui| {
+                        // Near plane
+                        ui.horizontal(|ui| {
+                            ui.label("Near");
+                            ui.add_space(ui.available_width() - 100.0);
+                            let mut near = vp.renderer.camera().near;
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut near)
+                                        .speed(0.01)
+                                        .range(0.001..=100.0)
+                                        .suffix(" m"),
+                                )
+                                .changed()
+                            {
+                                vp.renderer.camera_mut().set_near(near);
+                                config.write().config_mut().renderer.camera.near_plane =
+                                    vp.renderer.camera().near;
+                            }
+                        });
+
+                        // Far plane
+                        ui.horizontal(|ui| {
+                            ui.label("Far");
+                            ui.add_space(ui.available_width() - 100.0);
+                            let mut far = vp.renderer.camera().far;
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut far)
+                                        .speed(100.0)
+                                        .range(10.0..=1000000.0)
+                                        .suffix(" m"),
+                                )
+                                .changed()
+                            {
+                                vp.renderer.camera_mut().set_far(far);
+                                config.write().config_mut().renderer.camera.far_plane =
+                                    vp.renderer.camera().far;
+                            }
+                        });
+                    });
+
+                    ui.separator();
+
+                    // Camera distance info
                     ui.horizontal(|ui| {
-                        ui.label("Near");
+                        ui.label("Distance");
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.label(format!("{:.2} m", vp.renderer.camera().distance));
+                        });
+                    });
+
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        ui.strong("Navigation");
+                    });
+
+                    let mut camera = config.read().config().renderer.camera.clone();
+                    let mut camera_changed = false;
+
+                    for (label, value, range) in [
+                        ("Orbit", &mut camera.orbit_sensitivity, 0.001..=0.02),
+                        ("Pan", &mut camera.pan_sensitivity, 0.0005..=0.01),
+                        ("Zoom", &mut camera.zoom_sensitivity, 0.01..=0.5),
+                    ] {
+                        ui.horizontal(|ui| {
+                            ui.label(label);
+                            ui.add_space(ui.available_width() - 100.0);
+                            camera_changed |= ui
+                                .add(egui::Slider::new(value, range).show_value(false))
+                                .changed();
+                        });
+                    }
+
+                    camera_changed |= ui.checkbox(&mut camera.invert_y, "Invert Y").changed();
+
+                    egui::ComboBox::from_id_salt("overlay_middle_mouse")
+                        .selected_text(camera.middle_mouse.name())
+                        .width(panel_width - 16.0)
+                        .show_ui(ui, |ui| {
+                            for action in MiddleMouseAction::ALL {
+                                camera_changed |= ui
+                                    .selectable_value(
+                                        &mut camera.middle_mouse,
+                                        *action,
+                                        action.name(),
+                                    )
+                                    .changed();
+                            }
+                        })
+                        .response
+                        .on_hover_text("Middle-mouse drag");
+
+                    if camera_changed {
+                        config.write().config_mut().renderer.camera = camera;
+                    }
+
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        ui.strong("Grid");
+                    });
+
+                    let mut grid = vp.renderer.grid_config().clone();
+                    let mut grid_changed = false;
+
+                    // Minor line spacing
+                    ui.horizontal(|ui| {
+                        ui.label("Spacing");
                         ui.add_space(ui.available_width() - 100.0);
-                        let mut near = vp.renderer.camera().near;
-                        if ui
+                        grid_changed |= ui
                             .add(
-                                egui::DragValue::new(&mut near)
-                                    .speed(0.01)
+                                egui::DragValue::new(&mut grid.spacing)
+                                    .speed(0.001)
                                     .range(0.001..=100.0)
                                     .suffix(" m"),
                             )
-                            .changed()
-                        {
-                            vp.renderer.camera_mut().set_near(near);
-                        }
+                            .changed();
                     });
 
-                    // Far plane
+                    // Major line ratio
                     ui.horizontal(|ui| {
-                        ui.label("Far");
+                        ui.label("Major every");
                         ui.add_space(ui.available_width() - 100.0);
-                        let mut far = vp.renderer.camera().far;
-                        if ui
+                        grid_changed |= ui
+                            .add(egui::DragValue::new(&mut grid.major_every).range(1..=100))
+                            .changed();
+                    });
+
+                    // Extent
+                    ui.horizontal(|ui| {
+                        ui.label("Extent");
+                        ui.add_space(ui.available_width() - 100.0);
+                        grid_changed |= ui
                             .add(
-                                egui::DragValue::new(&mut far)
-                                    .speed(100.0)
-                                    .range(10.0..=1000000.0)
+                                egui::DragValue::new(&mut grid.size)
+                                    .speed(0.1)
+                                    .range(0.1..=1000.0)
                                     .suffix(" m"),
                             )
-                            .changed()
-                        {
-                            vp.renderer.camera_mut().set_far(far);
-                        }
+                            .changed();
                     });
 
-                    ui.separator();
+                    grid_changed |= ui.checkbox(&mut grid.infinite, "Infinite").changed();
 
-                    // Camera distance info
-                    ui.horizontal(|ui| {
-                        ui.label("Distance");
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.label(format!("{:.2} m", vp.renderer.camera().distance));
-                        });
-                    });
+                    if grid_changed {
+                        let device = vp.device.clone();
+                        let queue = vp.queue.clone();
+                        vp.renderer.apply_grid_config(&grid, &device, &queue);
+                        config.write().config_mut().renderer.grid = grid;
+                    }
                 });
         });
 }
@@ -208,9 +331,33 @@ pub fn render_gizmo_toggle(
         });
 }
 
+/// Radius of the clickable axis ends of the axes indicator
+const AXIS_HANDLE_RADIUS: f32 = 7.0;
+/// Radius of the clickable corner dots of the axes indicator
+const CORNER_HANDLE_RADIUS: f32 = 4.0;
+
+/// Clickable view direction on the axes indicator
+struct ViewHandle {
+    direction: Vec3,
+    name: &'static str,
+    position: egui::Pos2,
+    depth: f32,
+    radius: f32,
+    color: egui::Color32,
+    /// Negative axis ends are drawn hollow
+    filled: bool,
+}
+
 /// Render axes indicator in the bottom-right corner
-pub fn render_axes_indicator(ui: &mut egui::Ui, rect: egui::Rect, yaw: f32, pitch: f32) {
-    let painter = ui.painter();
+///
+/// Axis ends and the corner dots between them are clickable; returns the
+/// direction to view the target from when one is clicked.
+pub fn render_axes_indicator(
+    ui: &mut egui::Ui,
+    rect: egui::Rect,
+    yaw: f32,
+    pitch: f32,
+) -> Option<Vec3> {
     let axes_center = rect.right_bottom() - egui::vec2(50.0, 50.0);
     let axis_len = 30.0;
 
@@ -236,13 +383,90 @@ pub fn render_axes_indicator(ui: &mut egui::Ui, rect: egui::Rect, yaw: f32, pitc
     let (y_dir, y_depth) = project_axis(Vec3::Y);
     let (z_dir, z_depth) = project_axis(Vec3::Z);
 
+    let x_color = egui::Color32::from_rgb(255, 68, 68);
+    let y_color = egui::Color32::from_rgb(68, 255, 68);
+    let z_color = egui::Color32::from_rgb(68, 68, 255);
     let mut axes = [
-        (x_depth, x_dir, "X", egui::Color32::from_rgb(255, 68, 68)),
-        (y_depth, y_dir, "Y", egui::Color32::from_rgb(68, 255, 68)),
-        (z_depth, z_dir, "Z", egui::Color32::from_rgb(68, 68, 255)),
+        (x_depth, x_dir, "X", x_color),
+        (y_depth, y_dir, "Y", y_color),
+        (z_depth, z_dir, "Z", z_color),
     ];
     axes.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
 
+    // Axis ends, then the eight isometric corners
+    let axis_views = [
+        (Vec3::X, "Front", x_color, true),
+        (Vec3::NEG_X, "Back", x_color, false),
+        (Vec3::Y, "Left", y_color, true),
+        (Vec3::NEG_Y, "Right", y_color, false),
+        (Vec3::Z, "Top", z_color, true),
+        (Vec3::NEG_Z, "Bottom", z_color, false),
+    ];
+    let mut handles: Vec<ViewHandle> = axis_views
+        .into_iter()
+        .map(|(direction, name, color, filled)| {
+            let (offset, depth) = project_axis(direction);
+            ViewHandle {
+                direction,
+                name,
+                position: axes_center + offset,
+                depth,
+                radius: AXIS_HANDLE_RADIUS,
+                color,
+                filled,
+            }
+        })
+        .collect();
+    for corner in 0..8 {
+        let sign = |bit: i32| if corner & bit == 0 { 1.0 } else { -1.0 };
+        let direction = Vec3::new(sign(1), sign(2), sign(4));
+        let (offset, depth) = project_axis(direction.normalize());
+        handles.push(ViewHandle {
+            direction,
+            name: "Isometric",
+            position: axes_center + offset,
+            depth,
+            radius: CORNER_HANDLE_RADIUS,
+            color: egui::Color32::GRAY,
+            filled: true,
+        });
+    }
+
+    let extent = axis_len + AXIS_HANDLE_RADIUS + 4.0;
+    let response = ui.interact(
+        egui::Rect::from_center_size(axes_center, egui::Vec2::splat(extent * 2.0)),
+        ui.id().with("axes_indicator"),
+        egui::Sense::click(),
+    );
+
+    // Front-most handle under the pointer
+    let hovered = response.hover_pos().and_then(|pointer| {
+        handles
+            .iter()
+            .filter(|handle| handle.position.distance(pointer) <= handle.radius + 2.0)
+            .min_by(|a, b| a.depth.total_cmp(&b.depth))
+            .map(|handle| (handle.direction, handle.name))
+    });
+
+    // Handles facing away sit behind the axis lines
+    let painter = ui.painter();
+    handles.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+    let (back, front): (Vec<_>, Vec<_>) = handles.iter().partition(|handle| handle.depth > 0.0);
+    let draw_handle = |handle: &ViewHandle| {
+        let is_hovered = hovered.is_some_and(|(direction, _)| direction == handle.direction);
+        let stroke = if is_hovered {
+            egui::Stroke::new(2.0, egui::Color32::WHITE)
+        } else {
+            egui::Stroke::new(1.0, handle.color)
+        };
+        if handle.filled {
+            painter.circle(handle.position, handle.radius, handle.color, stroke);
+        } else {
+            painter.circle_stroke(handle.position, handle.radius, stroke);
+        }
+    };
+
+    back.into_iter().for_each(draw_handle);
     for (_depth, dir, label, color) in axes {
         painter.line_segment(
             [axes_center, axes_center + dir],
@@ -258,4 +482,8 @@ pub fn render_axes_indicator(ui: &mut egui::Ui, rect: egui::Rect, yaw: f32, pitc
             color,
         );
     }
+    front.into_iter().for_each(draw_handle);
+
+    let (direction, name) = hovered?;
+    response.on_hover_text(name).clicked().then_some(direction)
 }
@@ -1,23 +1,50 @@
 //! 3D Viewport panel
 
 mod camera_overlay;
+mod pick_overlay;
+mod section;
+mod sketch_input;
 
-use glam::Vec3;
-use rk_renderer::{GizmoAxis, GizmoMode, GizmoSpace};
+use glam::{Mat4, Vec2, Vec3};
+use rk_core::JointType;
+use rk_renderer::config::MiddleMouseAction;
+use rk_renderer::{GizmoAxis, GizmoMode, GizmoSpace, RenderMode};
+use uuid::Uuid;
 
 use crate::config::SharedConfig;
 use crate::panels::Panel;
+use crate::panels::part_menu::{apply_part_command, show_part_menu};
 use crate::state::{
-    AppAction, GizmoTransform, PickablePartData, SharedAppState, SharedViewportState, pick_object,
+    AppAction, AppState, GizmoDragStart, GizmoInteraction, GizmoTransform, MeasuredElement,
+    PickElement, PickResult, SharedAppState, SharedViewportState, SketchAction, SketchTool,
+    SnapPoint, ViewportState, parts_in_rect, pick_body_element, pick_object, pick_snap_point,
 };
 
 use camera_overlay::{render_axes_indicator, render_camera_settings, render_gizmo_toggle};
+use section::SectionView;
+
+/// Minimum time in seconds between hover picks
+const HOVER_PICK_INTERVAL: f64 = 0.05;
 
 /// 3D viewport panel
 pub struct ViewportPanel {
     last_size: egui::Vec2,
     hovered_axis: GizmoAxis,
     show_camera_settings: bool,
+    /// Pointer position of the last hover pick
+    last_hover_pos: Option<egui::Vec2>,
+    /// Time of the last hover pick
+    last_hover_time: f64,
+    /// Part the open context menu acts on
+    context_part: Option<Uuid>,
+    /// Last joint origin snap candidate, with the pointer position and view it was picked for
+    joint_snap: Option<(egui::Vec2, Mat4, Option<SnapPoint>)>,
+    /// Exploded view factor (0 = assembled, 1 = fully exploded)
+    explode: f32,
+    /// Section plane settings
+    section: SectionView,
+    /// Start of the selection rectangle being dragged, relative to the viewport
+    marquee_start: Option<egui::Vec2>,
 }
 
 impl ViewportPanel {
@@ -26,6 +53,138 @@ impl ViewportPanel {
             last_size: egui::Vec2::ZERO,
             hovered_axis: GizmoAxis::None,
             show_camera_settings: false,
+            last_hover_pos: None,
+            last_hover_time: 0.0,
+            context_part: None,
+            joint_snap: None,
+            explode: 0.0,
+            section: SectionView::new(),
+            marquee_start: None,
+        }
+    }
+
+    /// Update the hovered part when the pointer moved, at most every
+    /// [`HOVER_PICK_INTERVAL`]
+    fn update_hover(
+        &mut self,
+        ui: &egui::Ui,
+        hover_pos: Option<egui::Vec2>,
+        enabled: bool,
+        viewport: &mut ViewportState,
+        size: egui::Vec2,
+        app_state: &SharedAppState,
+    ) {
+        let hover_pos = hover_pos.filter(|_| enabled);
+        if hover_pos == self.last_hover_pos {
+            return;
+        }
+        let now = ui.input(|i| i.time);
+        let wait = HOVER_PICK_INTERVAL - (now - self.last_hover_time);
+        if hover_pos.is_some() && wait > 0.0 {
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_secs_f64(wait));
+            return;
+        }
+        self.last_hover_pos = hover_pos;
+        self.last_hover_time = now;
+
+        let camera = viewport.renderer.camera();
+        let mut app = app_state.lock();
+        let hovered = hover_pos.and_then(|pos| {
+            let parts = viewport
+                .pick_cache
+                .pickable_parts(app.project.parts().values());
+            let section = viewport.renderer.section_plane();
+            pick_object(camera, pos.x, pos.y, size.x, size.y, &parts, section).map(|(id, _)| id)
+        });
+        app.hovered_part = hovered;
+    }
+}
+
+impl ViewportPanel {
+    /// Find the vertex or face center under the pointer for placing a joint
+    /// origin, reusing the last result while the pointer and view are unchanged
+    fn joint_snap_point(
+        &mut self,
+        pos: egui::Vec2,
+        viewport: &mut ViewportState,
+        size: egui::Vec2,
+        app_state: &SharedAppState,
+    ) -> Option<SnapPoint> {
+        let camera = viewport.renderer.camera();
+        let view = camera.view_matrix();
+        if let Some((last_pos, last_view, snap)) = self.joint_snap
+            && last_pos == pos
+            && last_view == view
+        {
+            return snap;
+        }
+
+        let app = app_state.lock();
+        let parts = viewport
+            .pick_cache
+            .pickable_parts(app.project.parts().values());
+        let section = viewport.renderer.section_plane();
+        let snap = pick_snap_point(camera, pos.x, pos.y, size.x, size.y, &parts, section);
+        self.joint_snap = Some((pos, view, snap));
+        snap
+    }
+}
+
+impl ViewportPanel {
+    /// Select the parts overlapping a rectangle dragged with the primary button
+    ///
+    /// Holding Ctrl/Cmd when the drag ends adds them to the selection;
+    /// Escape cancels the drag.
+    fn update_marquee(
+        &mut self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        can_start: bool,
+        viewport: &ViewportState,
+        size: egui::Vec2,
+        app_state: &SharedAppState,
+    ) {
+        if can_start && response.drag_started_by(egui::PointerButton::Primary) {
+            self.marquee_start = ui
+                .input(|i| i.pointer.press_origin())
+                .map(|p| p - response.rect.min);
+        }
+        let Some(start) = self.marquee_start else {
+            return;
+        };
+        let current = response
+            .interact_pointer_pos()
+            .map_or(start, |p| p - response.rect.min);
+        let rect = egui::Rect::from_two_pos(start.to_pos2(), current.to_pos2());
+
+        if response.drag_stopped_by(egui::PointerButton::Primary) {
+            self.marquee_start = None;
+            let part_ids = parts_in_rect(
+                viewport.renderer.camera(),
+                Vec2::new(rect.min.x, rect.min.y),
+                Vec2::new(rect.max.x, rect.max.y),
+                size.x,
+                size.y,
+                app_state.lock().project.parts().values(),
+            );
+            let extend = ui.input(|i| i.modifiers.command);
+            app_state
+                .lock()
+                .queue_action(AppAction::SelectParts { part_ids, extend });
+        } else if !response.dragged_by(egui::PointerButton::Primary)
+            || ui.input(|i| i.key_pressed(egui::Key::Escape))
+        {
+            self.marquee_start = None;
+        } else {
+            let color = ui.visuals().selection.bg_fill;
+            ui.painter_at(response.rect).rect(
+                rect.translate(response.rect.min.to_vec2()),
+                0.0,
+                color.gamma_multiply(0.2),
+                egui::Stroke::new(1.0, color),
+                egui::StrokeKind::Inside,
+            );
         }
     }
 }
@@ -72,6 +231,7 @@ impl Panel for ViewportPanel {
         config: &SharedConfig,
     ) {
         // Toolbar
+        let mut explode_changed = false;
         ui.horizontal(|ui| {
             ui.label("View:");
             if ui.button("Top").clicked() {
@@ -97,6 +257,7 @@ impl Panel for ViewportPanel {
             let mut show_grid = state.renderer.show_grid();
             let mut show_axes = state.renderer.show_axes();
             let mut show_markers = state.renderer.show_markers();
+            let mut render_mode = state.renderer.render_mode();
             if ui.checkbox(&mut show_grid, "Grid").changed() {
                 state.renderer.set_show_grid(show_grid);
             }
@@ -106,8 +267,59 @@ impl Panel for ViewportPanel {
             if ui.checkbox(&mut show_markers, "Markers").changed() {
                 state.renderer.set_show_markers(show_markers);
             }
+            egui::ComboBox::from_id_salt("render_mode")
+                .selected_text(render_mode.name())
+                .show_ui(ui, |ui| {
+                    for mode in RenderMode::ALL {
+                        ui.selectable_value(&mut render_mode, mode, mode.name());
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Show visual meshes, collision shapes, or collisions through dimmed visuals",
+                );
+            if render_mode != state.renderer.render_mode() {
+                state.renderer.set_render_mode(render_mode);
+            }
+            let measuring = state.angle_measure.active;
+            if ui
+                .selectable_label(measuring, "∠ Angle")
+                .on_hover_text("Measure the angle between two faces or edges of CAD bodies")
+                .clicked()
+            {
+                state.angle_measure.set_active(!measuring);
+            }
+            let floor = state.renderer.floor_config().clone();
+            drop(state);
+
+            ui.separator();
+            ui.label("Explode:");
+            explode_changed = ui
+                .add(egui::Slider::new(&mut self.explode, 0.0..=1.0).show_value(false))
+                .on_hover_text(
+                    "Spread parts apart for viewing; the design is not changed and parts \
+                     can't be picked or moved while spread",
+                )
+                .changed();
+
+            ui.separator();
+            let length_unit = app_state.lock().length_unit;
+            self.section.ui(ui, viewport_state, length_unit);
+
+            if floor.enabled {
+                ui.separator();
+                render_floor_clearance(ui, app_state, floor.height);
+            }
         });
 
+        // Parts can move while exploded, so offsets follow them every frame
+        if self.explode > 0.0 || explode_changed {
+            let app = app_state.lock();
+            viewport_state
+                .lock()
+                .set_explode(self.explode, app.project.parts());
+        }
+
         // Main viewport area
         let available_size = ui.available_size();
         let width = available_size.x as u32;
@@ -118,11 +330,29 @@ impl Panel for ViewportPanel {
         }
 
         // Ensure texture and render
+        let hovered_part = app_state.lock().hovered_part;
         let texture_id = {
             let mut state = viewport_state.lock();
+            if state.renderer.hovered_part() != hovered_part {
+                state.set_hovered_part(hovered_part);
+            }
             let mut egui_renderer = render_state.renderer.write();
+            let dt = ui.input(|i| i.stable_dt);
+            if state.renderer.camera_mut().update_transition(dt) {
+                ui.ctx().request_repaint();
+            }
             let tex_id = state.ensure_texture(width, height, &mut egui_renderer);
-            state.render();
+            // Large parts are drawn as boxes while the camera moves; the
+            // extra repaint brings the full meshes back once it stops
+            let camera_moving = state.camera_moved();
+            state.renderer.set_camera_moving(camera_moving);
+            if camera_moving {
+                ui.ctx().request_repaint();
+            }
+            // Keep showing the last frame while nothing changed
+            if state.needs_render() {
+                state.render();
+            }
             tex_id
         };
 
@@ -144,6 +374,17 @@ impl Panel for ViewportPanel {
 
         // Gizmo interaction (left mouse button)
         let mut gizmo_delta: Option<GizmoTransform> = None;
+        // Drag that ended this frame, committed once its last delta is applied
+        let mut finished_drag: Option<GizmoDragStart> = None;
+
+        if local_mouse.is_none() {
+            self.update_hover(ui, None, false, &mut vp_state, available_size, app_state);
+        }
+
+        // Whether a primary drag may start a selection rectangle
+        let mut marquee_allowed = false;
+        // Parts are not where they are drawn while exploded, so nothing is picked
+        let exploded = vp_state.is_exploded();
 
         if let Some(pos) = local_mouse {
             // Check for gizmo hover
@@ -168,6 +409,20 @@ impl Panel for ViewportPanel {
                     available_size.x,
                     available_size.y,
                 );
+                if vp_state.is_dragging_gizmo() {
+                    vp_state.gizmo.drag_start =
+                        gizmo_drag_start(&vp_state.gizmo, &app_state.lock());
+                }
+            }
+
+            // Escape cancels the drag, restoring the target
+            if vp_state.is_dragging_gizmo() && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                vp_state.end_gizmo_drag();
+                if let Some(start) = vp_state.gizmo.drag_start.take() {
+                    app_state
+                        .lock()
+                        .queue_action(AppAction::RevertGizmoDrag(start));
+                }
             }
 
             // Update drag
@@ -179,50 +434,303 @@ impl Panel for ViewportPanel {
             // End drag
             if response.drag_stopped_by(egui::PointerButton::Primary) {
                 vp_state.end_gizmo_drag();
+                finished_drag = vp_state.gizmo.drag_start.take();
             }
 
-            // Object picking on click (only if not interacting with gizmo)
-            if response.clicked_by(egui::PointerButton::Primary)
+            // Sketch drawing tools take over primary clicks
+            let sketch_drawing = self.hovered_axis == GizmoAxis::None
+                && !vp_state.is_dragging_gizmo()
+                && sketch_input::handle_sketch_input(
+                    ui,
+                    &response,
+                    vp_state.renderer.camera_mut(),
+                    pos,
+                    available_size,
+                    app_state,
+                );
+
+            // Hover highlight, paused while dragging or drawing
+            let hover_enabled = !sketch_drawing
+                && !exploded
                 && self.hovered_axis == GizmoAxis::None
-            {
-                // Gather pickable part data from app_state
-                let pickable_parts: Vec<PickablePartData> = {
-                    let app = app_state.lock();
-                    app.project
-                        .parts()
-                        .values()
-                        .map(|part| PickablePartData {
-                            id: part.id,
-                            vertices: part.vertices.clone(),
-                            indices: part.indices.clone(),
-                            transform: part.origin_transform,
-                            bbox_min: part.bbox_min,
-                            bbox_max: part.bbox_max,
-                        })
-                        .collect()
+                && !vp_state.is_dragging_gizmo()
+                && !ui.input(|i| i.pointer.any_down());
+            self.update_hover(
+                ui,
+                response.hover_pos().map(|p| p - response.rect.min),
+                hover_enabled,
+                &mut vp_state,
+                available_size,
+                app_state,
+            );
+
+            // Placing a joint origin takes over primary clicks
+            let joint_pick = app_state.lock().joint_origin_pick;
+            if let Some(joint_id) = joint_pick {
+                let snap = if self.hovered_axis == GizmoAxis::None
+                    && !vp_state.is_dragging_gizmo()
+                    && !exploded
+                {
+                    self.joint_snap_point(pos, &mut vp_state, available_size, app_state)
+                } else {
+                    None
                 };
+                pick_overlay::render_snap_crosshair(
+                    ui,
+                    response.rect,
+                    vp_state.renderer.camera(),
+                    snap.as_ref(),
+                );
 
-                // Perform picking
-                let camera = vp_state.renderer.camera();
-                let hit = pick_object(
-                    camera,
+                let mut app = app_state.lock();
+                if response.clicked_by(egui::PointerButton::Primary)
+                    && let Some(snap) = snap
+                {
+                    app.queue_action(AppAction::SnapJointOrigin {
+                        joint_id,
+                        position: snap.position,
+                    });
+                    app.queue_action(AppAction::SetJointOriginPick(None));
+                } else if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    app.queue_action(AppAction::SetJointOriginPick(None));
+                }
+            }
+
+            // Face and edge picking for dialogs that select parts of a body,
+            // and for projecting edges into a sketch
+            let measuring = vp_state.angle_measure.active;
+            if measuring && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                vp_state.angle_measure.set_active(false);
+            }
+            let (pick_bodies, projecting) = {
+                let mut app = app_state.lock();
+                let tolerance = app.cad_tessellation_tolerance;
+                let body_ids = if measuring {
+                    app.cad.rendered_bodies.clone()
+                } else {
+                    app.cad.element_pick_bodies()
+                };
+                let projecting = app
+                    .cad
+                    .editor_mode
+                    .sketch()
+                    .is_some_and(|mode| mode.current_tool == SketchTool::Project);
+                if app.cad.face_sketch_pick && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    app.queue_action(AppAction::SketchAction(SketchAction::SetFaceSketchPick {
+                        active: false,
+                    }));
+                }
+                (app.cad.pickable_bodies(&body_ids, tolerance), projecting)
+            };
+            let element_hit = if self.hovered_axis == GizmoAxis::None
+                && !vp_state.is_dragging_gizmo()
+                && !exploded
+            {
+                pick_body_element(
+                    vp_state.renderer.camera(),
                     pos.x,
                     pos.y,
                     available_size.x,
                     available_size.y,
-                    &pickable_parts,
+                    &pick_bodies,
+                    vp_state.renderer.section_plane(),
+                )
+            } else {
+                None
+            };
+            if let Some(hit) = &element_hit {
+                pick_overlay::render_pick_highlight(
+                    ui,
+                    response.rect,
+                    vp_state.renderer.camera(),
+                    &pick_bodies,
+                    hit,
+                );
+            }
+            if vp_state.angle_measure.active {
+                let angle_mode = app_state.lock().angle_display_mode;
+                pick_overlay::render_angle_measurement(
+                    ui,
+                    response.rect,
+                    vp_state.renderer.camera(),
+                    &vp_state.angle_measure,
+                    angle_mode,
                 );
+            }
+
+            // Clicking a face or edge while measuring adds it to the angle
+            if measuring && response.clicked_by(egui::PointerButton::Primary) {
+                if let Some(PickResult {
+                    body_id,
+                    element: Some(element),
+                    ..
+                }) = element_hit
+                    && let Some(body) = pick_bodies.iter().find(|body| body.id == body_id)
+                    && let Some(measured) = MeasuredElement::from_pick(&body.mesh, element)
+                {
+                    vp_state.angle_measure.pick(measured);
+                }
+            }
+            // Clicking an edge while projecting copies it into the sketch
+            else if projecting && response.clicked_by(egui::PointerButton::Primary) {
+                if let Some(PickResult {
+                    body_id,
+                    element: Some(PickElement::Edge(edge_id)),
+                    ..
+                }) = element_hit
+                {
+                    app_state.lock().queue_action(AppAction::SketchAction(
+                        SketchAction::ProjectEdge { body_id, edge_id },
+                    ));
+                }
+            }
+            // Clicking a face starts a sketch on it, toggles it in the shell
+            // dialog, or places the hole dialog's hole at the clicked point
+            else if response.clicked_by(egui::PointerButton::Primary)
+                && let Some(PickResult {
+                    body_id,
+                    element: Some(PickElement::Face(face_id)),
+                    distance,
+                }) = element_hit
+            {
+                let mut app = app_state.lock();
+                let action = if app.cad.face_sketch_pick {
+                    SketchAction::CreateSketchOnFace { body_id, face_id }
+                } else if app.cad.shell_dialog.is_some() {
+                    SketchAction::ToggleShellFace { face_id }
+                } else {
+                    let (ray_origin, ray_dir) = vp_state.renderer.camera().screen_to_ray(
+                        pos.x,
+                        pos.y,
+                        available_size.x,
+                        available_size.y,
+                    );
+                    SketchAction::PickHolePosition {
+                        face_id,
+                        position: ray_origin + ray_dir * distance,
+                    }
+                };
+                app.queue_action(AppAction::SketchAction(action));
+            }
+            // Object picking on click (only if not interacting with gizmo)
+            else if response.clicked_by(egui::PointerButton::Primary)
+                && self.hovered_axis == GizmoAxis::None
+                && !sketch_drawing
+                && joint_pick.is_none()
+                && !measuring
+                && !exploded
+            {
+                // Perform picking
+                let hit = {
+                    let app = app_state.lock();
+                    let pickable_parts = vp_state
+                        .pick_cache
+                        .pickable_parts(app.project.parts().values());
+                    pick_object(
+                        vp_state.renderer.camera(),
+                        pos.x,
+                        pos.y,
+                        available_size.x,
+                        available_size.y,
+                        &pickable_parts,
+                        vp_state.renderer.section_plane(),
+                    )
+                };
 
-                // Queue selection action
+                // Queue selection action (Ctrl/Cmd-click adds to the selection)
                 let selected_id = hit.map(|(id, _)| id);
-                app_state
-                    .lock()
-                    .queue_action(AppAction::SelectPart(selected_id));
+                let action = match selected_id {
+                    Some(id) if ui.input(|i| i.modifiers.command) => {
+                        AppAction::TogglePartSelection(id)
+                    }
+                    _ => AppAction::SelectPart(selected_id),
+                };
+                app_state.lock().queue_action(action);
+            }
+
+            // Left-drag on empty space selects the parts inside a rectangle
+            marquee_allowed = self.hovered_axis == GizmoAxis::None
+                && !vp_state.is_dragging_gizmo()
+                && !sketch_drawing
+                && joint_pick.is_none()
+                && !measuring
+                && !exploded
+                && pick_bodies.is_empty();
+
+            // Right-click opens the part menu for the part under the cursor,
+            // falling back to the current selection
+            if response.secondary_clicked() && !sketch_drawing {
+                let app = app_state.lock();
+                let pickable_parts = vp_state
+                    .pick_cache
+                    .pickable_parts(app.project.parts().values());
+                let hit = if exploded {
+                    None
+                } else {
+                    pick_object(
+                        vp_state.renderer.camera(),
+                        pos.x,
+                        pos.y,
+                        available_size.x,
+                        available_size.y,
+                        &pickable_parts,
+                        vp_state.renderer.section_plane(),
+                    )
+                };
+                self.context_part = hit.map(|(id, _)| id).or(app.selected_part);
             }
         }
 
-        // Apply gizmo transform to collision element
+        self.update_marquee(
+            ui,
+            &response,
+            marquee_allowed,
+            &vp_state,
+            available_size,
+            app_state,
+        );
+
+        // Apply gizmo transform to a joint origin
         if let Some(transform) = gizmo_delta
+            && let Some(joint_id) = vp_state.gizmo.editing_joint
+        {
+            let parent_world_transform = vp_state.gizmo.link_world_transform;
+            let mut app = app_state.lock();
+            if let Some(joint) = app.project.assembly.get_joint(joint_id) {
+                let mut origin = joint.origin;
+                match transform {
+                    GizmoTransform::Translation(delta) => {
+                        // The origin is expressed in the parent link's frame
+                        let mut local_delta =
+                            parent_world_transform.inverse().transform_vector3(delta);
+                        // Prismatic joints only slide along their own axis
+                        if joint.joint_type == JointType::Prismatic {
+                            let axis = (origin.to_quat() * joint.axis).normalize_or_zero();
+                            local_delta = axis * local_delta.dot(axis);
+                        }
+                        origin.xyz[0] += local_delta.x;
+                        origin.xyz[1] += local_delta.y;
+                        origin.xyz[2] += local_delta.z;
+                    }
+                    GizmoTransform::Rotation(rotation) => {
+                        // Bring the world rotation into the parent link's frame
+                        let (_, parent_rotation, _) =
+                            parent_world_transform.to_scale_rotation_translation();
+                        let local_rotation = parent_rotation.inverse() * rotation * parent_rotation;
+                        let (x, y, z) =
+                            (local_rotation * origin.to_quat()).to_euler(glam::EulerRot::XYZ);
+                        origin.rpy = [x, y, z];
+                    }
+                    GizmoTransform::Scale(_) => {
+                        // Joint origins don't support scaling - ignore
+                    }
+                }
+                // Re-poses the child links once applied
+                app.queue_action(AppAction::DragJointOrigin { joint_id, origin });
+            }
+        }
+        // Apply gizmo transform to collision element
+        else if let Some(transform) = gizmo_delta
             && let Some((link_id, collision_index)) = vp_state.gizmo.editing_collision
         {
             let link_world_transform = vp_state.gizmo.link_world_transform;
@@ -265,7 +773,6 @@ impl Panel for ViewportPanel {
                 }
             }
 
-            app.modified = true;
             drop(app);
 
             // Re-lock viewport state for rest of handling
@@ -276,39 +783,63 @@ impl Panel for ViewportPanel {
             && let Some(part_id) = vp_state.gizmo.part_id
         {
             let queue = vp_state.queue.clone();
+            let snap_residual = vp_state.gizmo.snap_residual;
             drop(vp_state);
 
             let mut app = app_state.lock();
 
             match transform {
                 GizmoTransform::Translation(delta) => {
+                    // Snap in the parent link's frame so children line up with their parent
+                    let snap_frame = app.snap_part_translation.then(|| {
+                        let assembly = &app.project.assembly;
+                        assembly
+                            .find_link_by_part(part_id)
+                            .and_then(|link| assembly.get_parent_link(link.id))
+                            .map(|parent| parent.world_transform)
+                            .unwrap_or(Mat4::IDENTITY)
+                    });
+                    let increment = app.part_snap_increment;
+
                     // Moving the whole part - update part transform
-                    let new_transform = if let Some(part) = app.get_part_mut(part_id) {
+                    let new_transform = if let Some(part) = app.project.get_part_mut(part_id) {
                         let (scale, rotation, translation) =
                             part.origin_transform.to_scale_rotation_translation();
-                        let new_translation = translation + delta;
+                        let target = translation + delta + snap_residual;
+                        let new_translation = match snap_frame {
+                            Some(frame) => snap_to_grid(target, frame, increment),
+                            None => target,
+                        };
                         part.origin_transform = glam::Mat4::from_scale_rotation_translation(
                             scale,
                             rotation,
                             new_translation,
                         );
-                        Some(part.origin_transform)
+                        Some((part.origin_transform, target, new_translation - translation))
                     } else {
                         None
                     };
                     drop(app);
 
                     // Update mesh renderer transform
-                    if let Some(transform) = new_transform {
+                    if let Some((transform, target, moved)) = new_transform {
                         let mut vp = viewport_state.lock();
                         vp.renderer
                             .update_part_transform(&queue, part_id, transform);
+                        if snap_frame.is_some() {
+                            // Keep the gizmo on the snapped part and carry the remainder
+                            vp.gizmo.snap_residual = target - transform.w_axis.truncate();
+                            vp.gizmo.gizmo_position += moved - delta;
+                            let (position, scale) = (vp.gizmo.gizmo_position, vp.gizmo.gizmo_scale);
+                            let rotation = vp.renderer.gizmo_object_rotation();
+                            vp.show_gizmo(position, scale, rotation);
+                        }
                         drop(vp);
                     }
                 }
                 GizmoTransform::Rotation(rotation) => {
                     // Rotating the whole part
-                    let new_transform = if let Some(part) = app.get_part_mut(part_id) {
+                    let new_transform = if let Some(part) = app.project.get_part_mut(part_id) {
                         let (scale, old_rotation, translation) =
                             part.origin_transform.to_scale_rotation_translation();
                         let new_rotation = rotation * old_rotation;
@@ -333,7 +864,7 @@ impl Panel for ViewportPanel {
                 }
                 GizmoTransform::Scale(scale_delta) => {
                     // Scaling the whole part
-                    let new_transform = if let Some(part) = app.get_part_mut(part_id) {
+                    let new_transform = if let Some(part) = app.project.get_part_mut(part_id) {
                         let (old_scale, rotation, translation) =
                             part.origin_transform.to_scale_rotation_translation();
                         let new_scale = old_scale * scale_delta;
@@ -362,28 +893,40 @@ impl Panel for ViewportPanel {
             vp_state = viewport_state.lock();
         }
 
-        // Get camera sensitivity from config
-        let (orbit_sens, pan_sens, zoom_sens) = {
+        // Commit a finished drag as a single change
+        if let Some(start) = finished_drag {
+            app_state
+                .lock()
+                .queue_action(AppAction::CommitGizmoDrag(start));
+        }
+
+        // Get camera navigation settings from config
+        let (orbit_sens, pan_sens, zoom_sens, invert_y, middle_mouse) = {
             let cfg = config.read();
             let cam = &cfg.config().renderer.camera;
             (
                 cam.orbit_sensitivity,
                 cam.pan_sensitivity,
                 cam.zoom_sensitivity,
+                cam.invert_y,
+                cam.middle_mouse,
             )
         };
+        let y_sign = if invert_y { -1.0 } else { 1.0 };
 
         // Middle mouse button for orbit/pan (only if not dragging gizmo)
         if !vp_state.is_dragging_gizmo() && response.dragged_by(egui::PointerButton::Middle) {
-            let delta = response.drag_delta();
-            if ui.input(|i| i.modifiers.shift) {
-                // Pan
+            let delta = response.drag_delta() * egui::vec2(1.0, y_sign);
+            let pan = match middle_mouse {
+                MiddleMouseAction::Orbit => ui.input(|i| i.modifiers.shift),
+                MiddleMouseAction::Pan => !ui.input(|i| i.modifiers.shift),
+            };
+            if pan {
                 vp_state
                     .renderer
                     .camera_mut()
                     .pan_with_sensitivity(delta.x, delta.y, pan_sens);
             } else {
-                // Orbit
                 vp_state
                     .renderer
                     .camera_mut()
@@ -393,7 +936,7 @@ impl Panel for ViewportPanel {
 
         // Right mouse button for orbit as well
         if !vp_state.is_dragging_gizmo() && response.dragged_by(egui::PointerButton::Secondary) {
-            let delta = response.drag_delta();
+            let delta = response.drag_delta() * egui::vec2(1.0, y_sign);
             vp_state
                 .renderer
                 .camera_mut()
@@ -436,8 +979,70 @@ impl Panel for ViewportPanel {
             });
         }
 
+        // Arrow keys and Page Up/Down nudge the selected part
+        if response.hovered()
+            && !ui.ctx().wants_keyboard_input()
+            && !vp_state.is_dragging_gizmo()
+            && let Some(direction) = ui.input(nudge_direction)
+        {
+            let mut app = app_state.lock();
+            if !app.cad.editor_mode.is_sketch()
+                && let Some(part) = app.selected_part.and_then(|id| app.get_part(id))
+            {
+                let scale = ui.input(|i| {
+                    if i.modifiers.shift {
+                        10.0
+                    } else if i.modifiers.command {
+                        0.1
+                    } else {
+                        1.0
+                    }
+                });
+                let transform = nudge_transform(
+                    part.origin_transform,
+                    direction * app.nudge_step * scale,
+                    vp_state.renderer.gizmo_space(),
+                );
+                let action = AppAction::UpdatePartTransform {
+                    part_id: part.id,
+                    transform,
+                };
+                app.queue_action(action);
+            }
+        }
+
+        // Sketch shortcuts: copy/paste (Ctrl+C / Ctrl+V), construction toggle (Q);
+        // outside a sketch, copy/paste applies to the selected parts
+        if response.hovered() && !ui.ctx().wants_keyboard_input() {
+            if app_state.lock().cad.editor_mode.is_sketch() {
+                handle_sketch_clipboard(ui, app_state);
+                if ui.input(|i| i.key_pressed(egui::Key::Q)) {
+                    app_state
+                        .lock()
+                        .queue_action(AppAction::SketchAction(SketchAction::ToggleConstruction));
+                }
+            } else {
+                handle_part_clipboard(ui, app_state);
+            }
+        }
+
         // Context menu
+        let context_part = self.context_part.and_then(|id| {
+            let app = app_state.lock();
+            let assembly = &app.project.assembly;
+            let has_children = assembly
+                .find_link_by_part(id)
+                .is_some_and(|link| !assembly.get_children(link.id).is_empty());
+            app.get_part(id)
+                .map(|part| (id, part.visible, has_children))
+        });
         response.context_menu(|ui| {
+            if let Some((part_id, visible, has_children)) = context_part {
+                if let Some(command) = show_part_menu(ui, visible, has_children) {
+                    apply_part_command(app_state, part_id, command);
+                }
+                ui.separator();
+            }
             if ui.button("Reset View").clicked() {
                 vp_state.renderer.camera_mut().fit_all(Vec3::ZERO, 2.0);
                 ui.close();
@@ -462,8 +1067,14 @@ impl Panel for ViewportPanel {
         let pitch = vp_state.renderer.camera().pitch;
         drop(vp_state);
 
-        // Draw axes indicator overlay
-        render_axes_indicator(ui, response.rect, yaw, pitch);
+        // Draw axes indicator overlay; clicking it turns the camera
+        if let Some(direction) = render_axes_indicator(ui, response.rect, yaw, pitch) {
+            viewport_state
+                .lock()
+                .renderer
+                .camera_mut()
+                .animate_to_direction(direction);
+        }
 
         // Draw gizmo mode toggle overlay (top-left)
         render_gizmo_toggle(ui, response.rect, viewport_state);
@@ -473,9 +1084,169 @@ impl Panel for ViewportPanel {
             ui,
             response.rect,
             viewport_state,
+            config,
             &mut self.show_camera_settings,
         );
 
         self.last_size = available_size;
     }
 }
+
+/// Forward copy and paste events to the active sketch
+///
+/// Copied entities are also written to the system clipboard as JSON, so they
+/// can be pasted into another editor instance. Pasted text that is not sketch
+/// data falls back to the in-app clipboard.
+fn handle_sketch_clipboard(ui: &egui::Ui, app_state: &SharedAppState) {
+    let (copy, paste) = ui.input(|i| {
+        let mut copy = false;
+        let mut paste = None;
+        for event in &i.events {
+            match event {
+                egui::Event::Copy => copy = true,
+                egui::Event::Paste(text) => paste = Some(text.clone()),
+                _ => {}
+            }
+        }
+        (copy, paste)
+    });
+
+    if copy {
+        app_state
+            .lock()
+            .queue_action(AppAction::SketchAction(SketchAction::CopySelected));
+        let app = app_state.lock();
+        if let Some(sketch_state) = app.cad.editor_mode.sketch()
+            && let Some(sketch) = app.cad.get_sketch(sketch_state.active_sketch)
+        {
+            let clipboard = sketch.copy_entities(&sketch_state.selected_entities);
+            if !clipboard.is_empty()
+                && let Ok(json) = serde_json::to_string(&clipboard)
+            {
+                ui.ctx().copy_text(json);
+            }
+        }
+    }
+
+    if let Some(text) = paste {
+        let clipboard = serde_json::from_str(&text).ok();
+        app_state
+            .lock()
+            .queue_action(AppAction::SketchAction(SketchAction::Paste { clipboard }));
+    }
+}
+
+/// Forward copy and paste events to the part clipboard, which is shared
+/// between open documents
+fn handle_part_clipboard(ui: &egui::Ui, app_state: &SharedAppState) {
+    let (copy, paste) = ui.input(|i| {
+        i.events
+            .iter()
+            .fold((false, false), |(copy, paste), event| {
+                (
+                    copy || matches!(event, egui::Event::Copy),
+                    paste || matches!(event, egui::Event::Paste(_)),
+                )
+            })
+    });
+    let mut app = app_state.lock();
+    if copy {
+        app.queue_action(AppAction::CopySelectedParts);
+    }
+    if paste {
+        app.queue_action(AppAction::PasteParts);
+    }
+}
+
+/// Snap a world-space point to a grid of `increment` aligned with `frame`
+fn snap_to_grid(point: Vec3, frame: Mat4, increment: f32) -> Vec3 {
+    let local = frame.inverse().transform_point3(point);
+    frame.transform_point3((local / increment).round() * increment)
+}
+
+/// Penetration depth below which the robot is considered to stand on the floor
+const FLOOR_CONTACT_TOLERANCE: f32 = 1e-4;
+
+/// Show how far the lowest collision geometry is above (or below) the floor
+fn render_floor_clearance(ui: &mut egui::Ui, app_state: &SharedAppState, floor_height: f32) {
+    let state = app_state.lock();
+    let assembly = &state.project.assembly;
+    let Some((link_id, lowest)) = assembly.lowest_collision_point(state.project.parts()) else {
+        return;
+    };
+    let link_name = assembly
+        .get_link(link_id)
+        .map(|link| link.name.as_str())
+        .unwrap_or_default();
+
+    let clearance_mm = (lowest - floor_height) * 1000.0;
+    if lowest < floor_height - FLOOR_CONTACT_TOLERANCE {
+        ui.colored_label(
+            ui.visuals().error_fg_color,
+            format!("\u{26a0} Floor penetration: {:.1} mm", -clearance_mm),
+        )
+        .on_hover_text(format!("'{link_name}' reaches below the floor"));
+    } else {
+        ui.label(format!("Floor clearance: {clearance_mm:.1} mm"))
+            .on_hover_text(format!("Lowest collision geometry: '{link_name}'"));
+    }
+}
+
+/// Capture the value of the gizmo's target as a drag starts
+fn gizmo_drag_start(gizmo: &GizmoInteraction, app: &AppState) -> Option<GizmoDragStart> {
+    if let Some(joint_id) = gizmo.editing_joint {
+        let joint = app.project.assembly.get_joint(joint_id)?;
+        return Some(GizmoDragStart::Joint {
+            joint_id,
+            origin: joint.origin,
+        });
+    }
+    if let Some((link_id, index)) = gizmo.editing_collision {
+        let collision = app
+            .project
+            .assembly
+            .get_link(link_id)?
+            .collisions
+            .get(index)?;
+        return Some(GizmoDragStart::Collision {
+            link_id,
+            index,
+            origin: collision.origin,
+        });
+    }
+    let part_id = gizmo.part_id?;
+    Some(GizmoDragStart::Part {
+        part_id,
+        transform: app.get_part(part_id)?.origin_transform,
+    })
+}
+
+/// Unit direction of the arrow or Page Up/Down key pressed this frame
+///
+/// Left/Right move along X, Up/Down along Y and Page Up/Down along Z.
+fn nudge_direction(input: &egui::InputState) -> Option<Vec3> {
+    [
+        (egui::Key::ArrowRight, Vec3::X),
+        (egui::Key::ArrowLeft, Vec3::NEG_X),
+        (egui::Key::ArrowUp, Vec3::Y),
+        (egui::Key::ArrowDown, Vec3::NEG_Y),
+        (egui::Key::PageUp, Vec3::Z),
+        (egui::Key::PageDown, Vec3::NEG_Z),
+    ]
+    .into_iter()
+    .find(|(key, _)| input.key_pressed(*key))
+    .map(|(_, direction)| direction)
+}
+
+/// Move a part transform by `delta`, given along the part's own axes in
+/// local gizmo space and along the world axes otherwise
+fn nudge_transform(transform: Mat4, delta: Vec3, space: GizmoSpace) -> Mat4 {
+    let delta = match space {
+        GizmoSpace::Global => delta,
+        GizmoSpace::Local => {
+            let (_, rotation, _) = transform.to_scale_rotation_translation();
+            rotation * delta
+        }
+    };
+    Mat4::from_translation(delta) * transform
+}
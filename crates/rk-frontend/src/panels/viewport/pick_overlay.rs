@@ -0,0 +1,207 @@
+//! Highlight for the face or edge under the cursor, and snap point previews
+
+use glam::{Quat, Vec3};
+use rk_renderer::Camera;
+
+use crate::state::{
+    AngleDisplayMode, AngleMeasurement, PickElement, PickResult, PickableBodyData, SnapKind,
+    SnapPoint,
+};
+
+/// Fill color for a hovered face
+const FACE_HIGHLIGHT: egui::Color32 = egui::Color32::from_rgba_premultiplied(60, 110, 40, 90);
+
+/// Stroke color for a hovered edge
+const EDGE_HIGHLIGHT: egui::Color32 = egui::Color32::from_rgb(255, 200, 60);
+
+/// Color of the snap point crosshair
+const SNAP_HIGHLIGHT: egui::Color32 = egui::Color32::from_rgb(80, 220, 255);
+
+/// Half-size of the snap point crosshair in pixels
+const SNAP_CROSSHAIR_SIZE: f32 = 10.0;
+
+/// Color of the angle measurement arc and label
+const MEASURE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 140, 220);
+
+/// Radius of the angle measurement arc, relative to its distance from the camera
+const MEASURE_ARC_SCALE: f32 = 0.08;
+
+/// Number of segments of the angle measurement arc
+const MEASURE_ARC_SEGMENTS: usize = 32;
+
+/// Paint the hovered face or edge over the rendered viewport
+pub fn render_pick_highlight(
+    ui: &egui::Ui,
+    rect: egui::Rect,
+    camera: &Camera,
+    bodies: &[PickableBodyData],
+    hit: &PickResult,
+) {
+    let Some(element) = hit.element else {
+        return;
+    };
+    let Some(body) = bodies.iter().find(|body| body.id == hit.body_id) else {
+        return;
+    };
+
+    let mesh = &body.mesh;
+    let painter = ui.painter_at(rect);
+    let to_screen = |point: [f32; 3]| {
+        camera
+            .world_to_screen(Vec3::from(point), rect.width(), rect.height())
+            .map(|p| rect.min + egui::vec2(p.x, p.y))
+    };
+
+    match element {
+        PickElement::Face(face_id) => {
+            let mut shape = egui::Mesh::default();
+            for (triangle, chunk) in mesh.indices.chunks_exact(3).enumerate() {
+                if mesh.face_of_triangle(triangle) != Some(face_id) {
+                    continue;
+                }
+                let corners: Option<Vec<egui::Pos2>> = chunk
+                    .iter()
+                    .map(|&index| to_screen(mesh.vertices[index as usize]))
+                    .collect();
+                let Some(corners) = corners else {
+                    continue;
+                };
+
+                let base = shape.vertices.len() as u32;
+                for corner in corners {
+                    shape.colored_vertex(corner, FACE_HIGHLIGHT);
+                }
+                shape.add_triangle(base, base + 1, base + 2);
+            }
+            painter.add(shape);
+        }
+        PickElement::Edge(edge_id) => {
+            let Some(edge) = mesh.edges.iter().find(|edge| edge.id == edge_id) else {
+                return;
+            };
+            let points: Vec<egui::Pos2> =
+                edge.points.iter().filter_map(|p| to_screen(*p)).collect();
+            painter.add(egui::Shape::line(
+                points,
+                egui::Stroke::new(3.0, EDGE_HIGHLIGHT),
+            ));
+        }
+    }
+}
+
+/// Paint a crosshair at the snap candidate, with a hint for placing the point
+pub fn render_snap_crosshair(
+    ui: &egui::Ui,
+    rect: egui::Rect,
+    camera: &Camera,
+    snap: Option<&SnapPoint>,
+) {
+    let painter = ui.painter_at(rect);
+    let hint = match snap.map(|snap| snap.kind) {
+        Some(SnapKind::Vertex) => "Vertex",
+        Some(SnapKind::FaceCenter) => "Face center",
+        None => "Click a vertex or face to place the joint origin (Esc to cancel)",
+    };
+    painter.text(
+        rect.center_bottom() - egui::vec2(0.0, 12.0),
+        egui::Align2::CENTER_BOTTOM,
+        hint,
+        egui::FontId::proportional(13.0),
+        SNAP_HIGHLIGHT,
+    );
+
+    let Some(center) = snap
+        .and_then(|snap| camera.world_to_screen(snap.position, rect.width(), rect.height()))
+        .map(|p| rect.min + egui::vec2(p.x, p.y))
+    else {
+        return;
+    };
+    let stroke = egui::Stroke::new(2.0, SNAP_HIGHLIGHT);
+    let size = SNAP_CROSSHAIR_SIZE;
+    painter.line_segment(
+        [
+            center - egui::vec2(size, 0.0),
+            center + egui::vec2(size, 0.0),
+        ],
+        stroke,
+    );
+    painter.line_segment(
+        [
+            center - egui::vec2(0.0, size),
+            center + egui::vec2(0.0, size),
+        ],
+        stroke,
+    );
+    painter.circle_stroke(center, size * 0.5, stroke);
+}
+
+/// Paint the measured angle as an arc between its arms, with a hint while
+/// picks are missing
+pub fn render_angle_measurement(
+    ui: &egui::Ui,
+    rect: egui::Rect,
+    camera: &Camera,
+    measure: &AngleMeasurement,
+    angle_mode: AngleDisplayMode,
+) {
+    let painter = ui.painter_at(rect);
+    let Some(result) = measure.result() else {
+        let hint = if measure.pick_count() == 0 {
+            "Click a face or edge to measure from (Esc to stop)"
+        } else {
+            "Click a second face or edge"
+        };
+        painter.text(
+            rect.center_bottom() - egui::vec2(0.0, 12.0),
+            egui::Align2::CENTER_BOTTOM,
+            hint,
+            egui::FontId::proportional(13.0),
+            MEASURE_COLOR,
+        );
+        return;
+    };
+
+    let to_screen = |point: Vec3| {
+        camera
+            .world_to_screen(point, rect.width(), rect.height())
+            .map(|p| rect.min + egui::vec2(p.x, p.y))
+    };
+    let radius = camera.position.distance(result.vertex) * MEASURE_ARC_SCALE;
+    let (arm_a, arm_b) = result.arms;
+    let stroke = egui::Stroke::new(2.0, MEASURE_COLOR);
+
+    // Arms, drawn a little past the arc
+    for arm in [arm_a, arm_b] {
+        if let (Some(start), Some(end)) = (
+            to_screen(result.vertex),
+            to_screen(result.vertex + arm * radius * 1.5),
+        ) {
+            painter.line_segment([start, end], stroke);
+        }
+    }
+
+    // Arc, swept from the first arm to the second
+    let sweep = Quat::from_rotation_arc(arm_a, arm_b);
+    let points: Vec<egui::Pos2> = (0..=MEASURE_ARC_SEGMENTS)
+        .filter_map(|i| {
+            let t = i as f32 / MEASURE_ARC_SEGMENTS as f32;
+            to_screen(result.vertex + Quat::IDENTITY.slerp(sweep, t) * arm_a * radius)
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, stroke));
+
+    let middle = Quat::IDENTITY.slerp(sweep, 0.5) * arm_a;
+    if let Some(label_pos) = to_screen(result.vertex + middle * radius * 1.3) {
+        painter.text(
+            label_pos,
+            egui::Align2::CENTER_CENTER,
+            format!(
+                "{:.2}{}",
+                angle_mode.from_radians(result.angle),
+                angle_mode.suffix()
+            ),
+            egui::FontId::proportional(14.0),
+            MEASURE_COLOR,
+        );
+    }
+}
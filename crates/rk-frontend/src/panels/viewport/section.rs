@@ -0,0 +1,114 @@
+//! Section plane controls for looking inside parts
+
+use glam::Vec3;
+use rk_cad::SketchPlane;
+use rk_renderer::Plane;
+
+use crate::panels::length_drag;
+use crate::state::{LengthUnit, SharedViewportState};
+
+/// Reference plane the section is parallel to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SectionOrientation {
+    Xy,
+    Xz,
+    Yz,
+}
+
+impl SectionOrientation {
+    const ALL: [SectionOrientation; 3] = [Self::Xy, Self::Xz, Self::Yz];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Xy => "XY",
+            Self::Xz => "XZ",
+            Self::Yz => "YZ",
+        }
+    }
+
+    fn normal(&self) -> Vec3 {
+        match self {
+            Self::Xy => SketchPlane::xy().normal,
+            Self::Xz => SketchPlane::xz().normal,
+            Self::Yz => SketchPlane::yz().normal,
+        }
+    }
+}
+
+/// Section plane settings shown in the viewport toolbar
+pub struct SectionView {
+    enabled: bool,
+    orientation: SectionOrientation,
+    /// Distance of the plane from the origin along its normal (meters)
+    offset: f32,
+    /// Cut away the negative side instead of the positive one
+    flipped: bool,
+}
+
+impl SectionView {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            orientation: SectionOrientation::Xz,
+            offset: 0.0,
+            flipped: false,
+        }
+    }
+
+    /// Plane passed to the renderer, if the section is on
+    fn plane(&self) -> Option<Plane> {
+        self.enabled.then(|| {
+            let normal = self.orientation.normal();
+            let cut_side = if self.flipped { -normal } else { normal };
+            Plane::from_point_normal(normal * self.offset, cut_side)
+        })
+    }
+
+    /// Toolbar controls; updates the renderer when anything changes
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        viewport_state: &SharedViewportState,
+        unit: LengthUnit,
+    ) {
+        let mut changed = ui
+            .checkbox(&mut self.enabled, "Section")
+            .on_hover_text("Cut away parts on one side of a plane to look inside")
+            .changed();
+
+        ui.add_enabled_ui(self.enabled, |ui| {
+            egui::ComboBox::from_id_salt("section_orientation")
+                .width(48.0)
+                .selected_text(self.orientation.name())
+                .show_ui(ui, |ui| {
+                    for orientation in SectionOrientation::ALL {
+                        changed |= ui
+                            .selectable_value(
+                                &mut self.orientation,
+                                orientation,
+                                orientation.name(),
+                            )
+                            .changed();
+                    }
+                });
+            changed |= length_drag(ui, &mut self.offset, unit, f32::NEG_INFINITY)
+                .on_hover_text("Plane offset from the origin along its normal")
+                .changed();
+            if ui
+                .selectable_label(self.flipped, "⇅")
+                .on_hover_text("Cut away the other side")
+                .clicked()
+            {
+                self.flipped = !self.flipped;
+                changed = true;
+            }
+        });
+
+        if changed {
+            viewport_state
+                .lock()
+                .renderer
+                .set_section_plane(self.plane());
+        }
+    }
+}
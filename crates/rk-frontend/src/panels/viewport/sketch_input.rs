@@ -0,0 +1,433 @@
+//! Mouse input for sketch selection and drawing tools
+
+use std::f32::consts::TAU;
+
+use glam::{Vec2, Vec3};
+use rk_cad::{Sketch, SketchEntity};
+use rk_renderer::Camera;
+use uuid::Uuid;
+
+use crate::state::{AppAction, InProgressEntity, SharedAppState, SketchAction, SketchTool};
+
+/// Screen distance within which a click picks sketch geometry
+const PICK_RADIUS_PX: f32 = 8.0;
+
+/// Cosine between the view ray and the sketch normal below which the plane
+/// counts as edge-on (about 3°), too oblique to place points precisely
+const EDGE_ON_DOT: f32 = 0.05;
+
+/// Handle sketch tool input while a sketch is being edited
+///
+/// The cursor is projected onto the sketch plane and, for the line and
+/// spline tools, snapped to the grid (or, with Ctrl held while drawing a
+/// line, to the nearest angle step). The line tool snaps to existing points
+/// first. Pick and snap distances are [`PICK_RADIUS_PX`] on screen,
+/// converted to sketch units at the cursor, so they hold at any zoom.
+/// Returns `true` when a sketch tool handled the input, in which case clicks
+/// must not fall through to part picking.
+///
+/// When the sketch plane is edge-on or behind the camera, a hint is shown
+/// instead and a click turns the camera to face the plane.
+pub(super) fn handle_sketch_input(
+    ui: &egui::Ui,
+    response: &egui::Response,
+    camera: &mut Camera,
+    pos: egui::Vec2,
+    size: egui::Vec2,
+    app_state: &SharedAppState,
+) -> bool {
+    let mut state = app_state.lock();
+    let Some(mode) = state.cad.editor_mode.sketch() else {
+        return false;
+    };
+    let tool = mode.current_tool;
+    if !matches!(
+        tool,
+        SketchTool::Select
+            | SketchTool::Offset
+            | SketchTool::Line
+            | SketchTool::Spline
+            | SketchTool::Ellipse
+            | SketchTool::DimensionRadius
+            | SketchTool::Mirror
+    ) {
+        return false;
+    }
+    let Some(sketch) = state.cad.get_sketch(mode.active_sketch) else {
+        return false;
+    };
+
+    let plane = &sketch.plane;
+    let to_sketch = |x: f32, y: f32| {
+        let (ray_origin, ray_dir) = camera.screen_to_ray(x, y, size.x, size.y);
+        ray_plane_intersection(ray_origin, ray_dir, plane.origin, plane.normal)
+            .map(|hit| plane.to_local(hit))
+    };
+    let clicked = response.clicked_by(egui::PointerButton::Primary);
+    let (_, view_dir) = camera.screen_to_ray(pos.x, pos.y, size.x, size.y);
+    let facing = view_dir.dot(plane.normal);
+    let cursor = if facing.abs() < EDGE_ON_DOT {
+        None
+    } else {
+        to_sketch(pos.x, pos.y)
+    };
+    let Some(cursor) = cursor else {
+        show_edge_on_hint(ui, response.rect);
+        if clicked {
+            let side = if facing > 0.0 {
+                -plane.normal
+            } else {
+                plane.normal
+            };
+            camera.animate_to_direction(side);
+        }
+        return true;
+    };
+    let tolerance = to_sketch(pos.x + PICK_RADIUS_PX, pos.y).map_or(0.0, |p| p.distance(cursor));
+
+    // The offset tool selects the chain to offset like the select tool
+    if matches!(tool, SketchTool::Select | SketchTool::Offset) {
+        // Dragging draws a lasso that selects the entities centered inside
+        // it once released, Shift adds them to the selection
+        let lassoed = (response.drag_stopped_by(egui::PointerButton::Primary)
+            && mode.lasso.len() >= 3)
+            .then(|| entities_in_lasso(sketch, &mode.lasso));
+        let hovered = if mode.lasso.is_empty() {
+            pick_entity(sketch, cursor, tolerance, |_| true)
+        } else {
+            None
+        };
+        if let Some(mode) = state.cad.editor_mode.sketch_mut() {
+            mode.hovered_entity = hovered;
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                mode.lasso.clear();
+            } else if response.drag_started_by(egui::PointerButton::Primary) {
+                mode.lasso = vec![cursor];
+            } else if response.dragged_by(egui::PointerButton::Primary) && !mode.lasso.is_empty() {
+                // Skip points closer than half the pick radius to keep the path short
+                if mode
+                    .lasso
+                    .last()
+                    .is_none_or(|last| last.distance(cursor) > tolerance / 2.0)
+                {
+                    mode.lasso.push(cursor);
+                }
+            } else {
+                mode.lasso.clear();
+            }
+        }
+        if let Some(entities) = lassoed {
+            let extend = ui.input(|i| i.modifiers.shift);
+            state.queue_action(AppAction::SketchAction(SketchAction::SelectEntities {
+                entities,
+                extend,
+            }));
+        }
+
+        // Click picks the nearest entity, Shift+click adds to the selection
+        if clicked {
+            let toggle = ui.input(|i| i.modifiers.shift);
+            state.queue_action(AppAction::SketchAction(SketchAction::SelectEntity {
+                entity: hovered,
+                toggle,
+            }));
+        }
+        return true;
+    }
+
+    if tool == SketchTool::DimensionRadius {
+        // A single click on a circle or arc dimensions it directly
+        let hovered = pick_entity(sketch, cursor, tolerance, |entity| {
+            matches!(
+                entity,
+                SketchEntity::Circle { .. } | SketchEntity::Arc { .. }
+            )
+        });
+        if let Some(mode) = state.cad.editor_mode.sketch_mut() {
+            mode.hovered_entity = hovered;
+        }
+        if clicked && let Some(entity) = hovered {
+            state.queue_action(AppAction::SketchAction(SketchAction::OpenDimensionDialog {
+                entity,
+            }));
+        }
+        return true;
+    }
+
+    if tool == SketchTool::Mirror {
+        // Clicking a line outside the selection mirrors the selection across it
+        let hovered = pick_entity(sketch, cursor, tolerance, |entity| {
+            matches!(entity, SketchEntity::Line { .. })
+                && !mode.selected_entities.contains(&entity.id())
+        });
+        if let Some(mode) = state.cad.editor_mode.sketch_mut() {
+            mode.hovered_entity = hovered;
+        }
+        if clicked && let Some(axis) = hovered {
+            state.queue_action(AppAction::SketchAction(SketchAction::MirrorSelected {
+                axis,
+            }));
+            state.queue_action(AppAction::SketchAction(SketchAction::SetTool {
+                tool: SketchTool::Select,
+            }));
+        }
+        return true;
+    }
+
+    if tool == SketchTool::Ellipse {
+        let position = mode.snap_point(cursor);
+        if let Some(mode) = state.cad.editor_mode.sketch_mut()
+            && let Some(InProgressEntity::Ellipse { preview_end, .. }) = &mut mode.in_progress
+        {
+            *preview_end = position;
+        }
+
+        // Center, then major axis end, then minor radius; Escape discards
+        let action = if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            Some(SketchAction::CancelDrawing)
+        } else if clicked {
+            Some(SketchAction::EllipseToolClick { position })
+        } else {
+            None
+        };
+        if let Some(action) = action {
+            state.queue_action(AppAction::SketchAction(action));
+        }
+        return true;
+    }
+
+    if tool == SketchTool::Spline {
+        let position = mode.snap_point(cursor);
+        // Clicking the first control point again closes the spline
+        let closes = match &mode.in_progress {
+            Some(InProgressEntity::Spline { control_points, .. }) if control_points.len() >= 3 => {
+                sketch
+                    .get_entity(control_points[0])
+                    .and_then(|first| first.position())
+                    .is_some_and(|first| first.distance(cursor) <= tolerance)
+            }
+            _ => false,
+        };
+        if let Some(mode) = state.cad.editor_mode.sketch_mut()
+            && let Some(InProgressEntity::Spline { preview_end, .. }) = &mut mode.in_progress
+        {
+            *preview_end = position;
+        }
+
+        // Double-click or Enter finishes an open spline, Escape discards it
+        let action = if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            Some(SketchAction::CancelDrawing)
+        } else if ui.input(|i| i.key_pressed(egui::Key::Enter))
+            || response.double_clicked_by(egui::PointerButton::Primary)
+        {
+            Some(SketchAction::FinishSpline { closed: false })
+        } else if clicked && closes {
+            Some(SketchAction::FinishSpline { closed: true })
+        } else if clicked {
+            Some(SketchAction::SplineToolClick { position })
+        } else {
+            None
+        };
+        if let Some(action) = action {
+            state.queue_action(AppAction::SketchAction(action));
+        }
+        return true;
+    }
+
+    // Ctrl locks the line to the nearest angle step, overriding the grid
+    let chain_point = match mode.in_progress {
+        Some(InProgressEntity::Line { start_point, .. }) => Some(start_point),
+        _ => None,
+    };
+    let chain_start = chain_point
+        .and_then(|id| sketch.get_entity(id))
+        .and_then(|point| point.position());
+    // Existing points within the pick radius win over grid and angle snaps
+    let point = pick_entity(sketch, cursor, tolerance, |entity| {
+        matches!(entity, SketchEntity::Point { .. }) && Some(entity.id()) != chain_point
+    });
+    let position = match (
+        point.and_then(|id| sketch.get_entity(id)?.position()),
+        chain_start,
+    ) {
+        (Some(snapped), _) => snapped,
+        (None, Some(start)) if ui.input(|i| i.modifiers.command) => mode.snap_angle(start, cursor),
+        (None, _) => mode.snap_point(cursor),
+    };
+    if let Some(mode) = state.cad.editor_mode.sketch_mut() {
+        mode.hovered_entity = point;
+        if let Some(InProgressEntity::Line { preview_end, .. }) = &mut mode.in_progress {
+            *preview_end = position;
+        }
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            mode.cancel_drawing();
+        }
+    }
+
+    // Holding Shift turns the next segment into an arc tangent to the previous one
+    if clicked {
+        let tangent_arc = ui.input(|i| i.modifiers.shift);
+        state.queue_action(AppAction::SketchAction(SketchAction::LineToolClick {
+            position,
+            point,
+            tangent_arc,
+        }));
+    }
+
+    true
+}
+
+/// Tell the user why clicks are not reaching an edge-on sketch plane
+fn show_edge_on_hint(ui: &egui::Ui, rect: egui::Rect) {
+    ui.painter().text(
+        rect.center_top() + egui::vec2(0.0, 40.0),
+        egui::Align2::CENTER_TOP,
+        "Sketch plane is edge-on: click to face it",
+        egui::FontId::proportional(14.0),
+        egui::Color32::from_rgb(255, 200, 80),
+    );
+}
+
+/// Find the entity accepted by `filter` closest to `position`, within `tolerance`
+fn pick_entity(
+    sketch: &Sketch,
+    position: Vec2,
+    tolerance: f32,
+    filter: impl Fn(&SketchEntity) -> bool,
+) -> Option<Uuid> {
+    sketch
+        .entities_iter()
+        .filter(|entity| filter(entity))
+        .filter_map(|entity| {
+            let distance = entity_distance(sketch, entity, position)?;
+            (distance <= tolerance).then_some((entity.id(), distance))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, _)| id)
+}
+
+/// Entities whose center lies inside a closed lasso polygon
+fn entities_in_lasso(sketch: &Sketch, lasso: &[Vec2]) -> Vec<Uuid> {
+    sketch
+        .entities_iter()
+        .filter(|entity| {
+            entity_center(sketch, entity).is_some_and(|center| point_in_polygon(center, lasso))
+        })
+        .map(|entity| entity.id())
+        .collect()
+}
+
+/// Representative center of an entity in sketch coordinates
+///
+/// Lines use their midpoint and arcs the middle of their sweep, so both sit
+/// on the geometry; circles and ellipses use their center point and splines
+/// the mean of their control points. Returns `None` for entities that
+/// reference missing points.
+fn entity_center(sketch: &Sketch, entity: &SketchEntity) -> Option<Vec2> {
+    let point = |id| match sketch.get_entity(id) {
+        Some(SketchEntity::Point { position, .. }) => Some(*position),
+        _ => None,
+    };
+    match entity {
+        SketchEntity::Point { position, .. } => Some(*position),
+        SketchEntity::Line { start, end, .. } => Some((point(*start)? + point(*end)?) / 2.0),
+        SketchEntity::Arc {
+            center,
+            start,
+            end,
+            radius,
+            ..
+        } => {
+            let c = point(*center)?;
+            let (s, e) = (point(*start)? - c, point(*end)? - c);
+            let sweep = s.angle_to(e).rem_euclid(TAU);
+            Some(c + Vec2::from_angle(s.to_angle() + sweep / 2.0) * *radius)
+        }
+        SketchEntity::Circle { center, .. } | SketchEntity::Ellipse { center, .. } => {
+            point(*center)
+        }
+        SketchEntity::Spline { control_points, .. } => {
+            let points: Vec<Vec2> = control_points.iter().filter_map(|id| point(*id)).collect();
+            (!points.is_empty()).then(|| points.iter().sum::<Vec2>() / points.len() as f32)
+        }
+    }
+}
+
+/// Even-odd test of a point against a closed polygon
+fn point_in_polygon(point: Vec2, polygon: &[Vec2]) -> bool {
+    let mut inside = false;
+    let mut previous = polygon.last().copied().unwrap_or(point);
+    for &current in polygon {
+        if (current.y > point.y) != (previous.y > point.y)
+            && point.x
+                < current.x
+                    + (point.y - current.y) * (previous.x - current.x) / (previous.y - current.y)
+        {
+            inside = !inside;
+        }
+        previous = current;
+    }
+    inside
+}
+
+/// Distance from `position` to an entity in sketch coordinates
+///
+/// Ellipses and splines are measured against their tessellated curve.
+/// Returns `None` for entities that reference missing points.
+fn entity_distance(sketch: &Sketch, entity: &SketchEntity, position: Vec2) -> Option<f32> {
+    let point = |id| match sketch.get_entity(id) {
+        Some(SketchEntity::Point { position, .. }) => Some(*position),
+        _ => None,
+    };
+    match entity {
+        SketchEntity::Point { position: p, .. } => Some(p.distance(position)),
+        SketchEntity::Line { start, end, .. } => {
+            Some(segment_distance(position, point(*start)?, point(*end)?))
+        }
+        SketchEntity::Circle { center, radius, .. } => {
+            Some((point(*center)?.distance(position) - radius).abs())
+        }
+        SketchEntity::Arc { id, .. } => sketch.arc_distance(*id, position),
+        SketchEntity::Ellipse { id, .. } => {
+            polyline_distance(position, &sketch.ellipse_polyline(*id)?)
+        }
+        SketchEntity::Spline { id, .. } => {
+            polyline_distance(position, &sketch.spline_polyline(*id)?)
+        }
+    }
+}
+
+/// Distance from a point to the closest segment of a polyline
+fn polyline_distance(point: Vec2, polyline: &[Vec2]) -> Option<f32> {
+    polyline
+        .windows(2)
+        .map(|pair| segment_distance(point, pair[0], pair[1]))
+        .min_by(f32::total_cmp)
+}
+
+/// Distance from a point to a segment
+fn segment_distance(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let length_sq = segment.length_squared();
+    let t = if length_sq > 0.0 {
+        ((point - start).dot(segment) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    point.distance(start + segment * t)
+}
+
+/// Intersect a ray with a plane, if the ray hits it in front of the origin
+fn ray_plane_intersection(
+    ray_origin: Vec3,
+    ray_dir: Vec3,
+    plane_origin: Vec3,
+    plane_normal: Vec3,
+) -> Option<Vec3> {
+    let denom = ray_dir.dot(plane_normal);
+    if denom.abs() < 1e-6 {
+        return None;
+    }
+    let t = (plane_origin - ray_origin).dot(plane_normal) / denom;
+    (t >= 0.0).then(|| ray_origin + ray_dir * t)
+}
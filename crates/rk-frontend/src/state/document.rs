@@ -0,0 +1,41 @@
+//! Open documents
+//!
+//! Several projects can be open at once, one per tab. The active one lives
+//! in the [`AppState`](super::AppState) fields the panels read; the others
+//! wait in a [`Document`] until they are switched to.
+
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use rk_core::Project;
+
+use super::CadState;
+
+/// Per-document part of the application state
+#[derive(Default)]
+pub struct Document {
+    /// Project (parts, assembly, materials)
+    pub project: Project,
+    /// CAD state (sketches, features, editor mode)
+    pub cad: CadState,
+    /// Active selected part
+    pub selected_part: Option<Uuid>,
+    /// All selected parts
+    pub selected_parts: Vec<Uuid>,
+    /// Selected collision element (link_id, collision_index)
+    pub selected_collision: Option<(Uuid, usize)>,
+    /// Project file path
+    pub project_path: Option<PathBuf>,
+    /// Has unsaved changes
+    pub modified: bool,
+}
+
+/// Tab title of a document: the file name, or the project name while unsaved
+pub fn document_title(project: &Project, path: Option<&Path>, modified: bool) -> String {
+    let name = path
+        .and_then(|path| path.file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| project.name.clone());
+    if modified { format!("{}*", name) } else { name }
+}
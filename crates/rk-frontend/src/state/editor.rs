@@ -1,5 +1,12 @@
 //! Editor state types
 
+use std::path::PathBuf;
+
+use glam::Vec3;
+use uuid::Uuid;
+
+use rk_core::{ExportIssue, IssueSeverity, JointType, PatternKind};
+
 /// Editor tool mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum EditorTool {
@@ -36,3 +43,161 @@ impl PrimitiveType {
         }
     }
 }
+
+/// State of the part rename dialog
+#[derive(Debug, Clone)]
+pub struct RenameDialogState {
+    /// Part being renamed
+    pub part_id: Uuid,
+    /// Name being edited
+    pub name: String,
+    /// Whether the text field still needs keyboard focus
+    pub needs_focus: bool,
+}
+
+impl RenameDialogState {
+    /// Create a dialog for renaming a part, starting from its current name
+    pub fn new(part_id: Uuid, name: String) -> Self {
+        Self {
+            part_id,
+            name,
+            needs_focus: true,
+        }
+    }
+}
+
+/// State of the part pattern dialog
+#[derive(Debug, Clone)]
+pub struct PatternDialogState {
+    /// Part to copy
+    pub part_id: Uuid,
+    /// Circular instead of linear pattern
+    pub circular: bool,
+    /// Number of instances, including the original
+    pub count: usize,
+    /// World axis index (0 = X, 1 = Y, 2 = Z) for the direction or rotation axis
+    pub axis: usize,
+    /// Distance between instances for linear patterns
+    pub spacing: f32,
+    /// Angle between instances in degrees for circular patterns
+    pub angle_step_degrees: f32,
+    /// Point the rotation axis passes through for circular patterns
+    pub axis_origin: Vec3,
+    /// Joint each copy to the original's parent link
+    pub attach_to_parent: bool,
+}
+
+impl PatternDialogState {
+    /// Create a dialog for patterning a part
+    pub fn new(part_id: Uuid) -> Self {
+        Self {
+            part_id,
+            circular: false,
+            count: 2,
+            axis: 0,
+            spacing: 0.1,
+            angle_step_degrees: 180.0,
+            axis_origin: Vec3::ZERO,
+            attach_to_parent: true,
+        }
+    }
+
+    /// Get the selected world axis
+    pub fn axis_vector(&self) -> Vec3 {
+        match self.axis {
+            0 => Vec3::X,
+            1 => Vec3::Y,
+            _ => Vec3::Z,
+        }
+    }
+
+    /// Build the pattern described by the dialog
+    pub fn kind(&self) -> PatternKind {
+        if self.circular {
+            PatternKind::Circular {
+                axis_origin: self.axis_origin,
+                axis: self.axis_vector(),
+                angle_step: self.angle_step_degrees.to_radians(),
+                count: self.count,
+            }
+        } else {
+            PatternKind::Linear {
+                direction: self.axis_vector(),
+                spacing: self.spacing,
+                count: self.count,
+            }
+        }
+    }
+}
+
+/// State of the dialog joining two parts where they are
+#[derive(Debug, Clone, Default)]
+pub struct CreateJointDialogState {
+    /// Part of the parent link
+    pub parent: Option<Uuid>,
+    /// Part of the child link
+    pub child: Option<Uuid>,
+    pub joint_type: JointType,
+}
+
+/// State of the turntable export dialog
+#[derive(Debug, Clone)]
+pub struct TurntableDialogState {
+    /// Directory the PNG sequence is written to
+    pub output_dir: Option<PathBuf>,
+    /// Number of frames in one full revolution
+    pub frames: u32,
+    /// Playback length of one revolution in seconds
+    pub duration_secs: f32,
+}
+
+impl Default for TurntableDialogState {
+    fn default() -> Self {
+        Self {
+            output_dir: None,
+            frames: 120,
+            duration_secs: 4.0,
+        }
+    }
+}
+
+/// State of the dialog asking how a mesh is imported while a link is selected
+#[derive(Debug, Clone)]
+pub struct ImportMeshDialogState {
+    /// Mesh file picked for import
+    pub path: PathBuf,
+    /// Link the mesh would become collision geometry of
+    pub link_id: Uuid,
+    pub link_name: String,
+}
+
+/// State of the pre-export check dialog
+#[derive(Debug, Clone)]
+pub struct ExportCheckDialogState {
+    /// URDF export destination, as picked in the file dialog
+    pub path: PathBuf,
+    pub robot_name: String,
+    /// Issues found in the last check
+    pub issues: Vec<ExportIssue>,
+}
+
+impl ExportCheckDialogState {
+    /// Whether any issue would make the export fail
+    pub fn has_errors(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.severity == IssueSeverity::Error)
+    }
+
+    /// Whether any issue can be fixed automatically
+    pub fn has_fixes(&self) -> bool {
+        self.issues.iter().any(|issue| issue.fix.is_some())
+    }
+}
+
+impl TurntableDialogState {
+    /// Playback frame rate implied by the frame count and duration
+    pub fn fps(&self) -> f32 {
+        self.frames as f32 / self.duration_secs.max(0.01)
+    }
+}
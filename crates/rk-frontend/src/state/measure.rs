@@ -0,0 +1,143 @@
+//! Angle measurement between faces and edges of CAD bodies
+
+use glam::Vec3;
+use rk_cad::TessellatedMesh;
+
+use super::PickElement;
+
+/// Face or edge picked for an angle measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeasuredElement {
+    /// Face, measured by its normal
+    Face { center: Vec3, normal: Vec3 },
+    /// Edge, measured by the direction from its first to its last point
+    Edge { start: Vec3, end: Vec3 },
+}
+
+impl MeasuredElement {
+    /// Measure a picked face or edge of a tessellated body
+    ///
+    /// Returns `None` for faces without area and for closed edges, which
+    /// have no direction.
+    pub fn from_pick(mesh: &TessellatedMesh, element: PickElement) -> Option<Self> {
+        match element {
+            PickElement::Face(face_id) => {
+                let mut area_normal = Vec3::ZERO;
+                let mut vertex_normal = Vec3::ZERO;
+                let mut center = Vec3::ZERO;
+                let mut weight = 0.0;
+                for (triangle, chunk) in mesh.indices.chunks_exact(3).enumerate() {
+                    if mesh.face_of_triangle(triangle) != Some(face_id) {
+                        continue;
+                    }
+                    let [v0, v1, v2] =
+                        [0, 1, 2].map(|i| Vec3::from(mesh.vertices[chunk[i] as usize]));
+                    let normal = (v1 - v0).cross(v2 - v0);
+                    area_normal += normal;
+                    center += (v0 + v1 + v2) / 3.0 * normal.length();
+                    weight += normal.length();
+                    for &index in chunk {
+                        if let Some(n) = mesh.normals.get(index as usize) {
+                            vertex_normal += Vec3::from(*n);
+                        }
+                    }
+                }
+                let mut normal = area_normal.try_normalize()?;
+                // Winding may be inconsistent; the shading normals are not
+                if normal.dot(vertex_normal) < 0.0 {
+                    normal = -normal;
+                }
+                Some(MeasuredElement::Face {
+                    center: center / weight,
+                    normal,
+                })
+            }
+            PickElement::Edge(edge_id) => {
+                let edge = mesh.edges.iter().find(|edge| edge.id == edge_id)?;
+                let start = Vec3::from(*edge.points.first()?);
+                let end = Vec3::from(*edge.points.last()?);
+                (start.distance(end) > f32::EPSILON).then_some(MeasuredElement::Edge { start, end })
+            }
+        }
+    }
+
+    /// Point the element is measured from when paired with another element
+    fn reference(&self) -> Vec3 {
+        match *self {
+            MeasuredElement::Face { center, .. } => center,
+            MeasuredElement::Edge { start, end } => (start + end) / 2.0,
+        }
+    }
+
+    /// Anchor point and unit direction of the element's arm of the angle
+    ///
+    /// Edges point away from their end nearest `toward`, so two edges
+    /// meeting at a corner measure the angle of the corner.
+    fn arm(&self, toward: Vec3) -> (Vec3, Vec3) {
+        match *self {
+            MeasuredElement::Face { center, normal } => (center, normal),
+            MeasuredElement::Edge { start, end } => {
+                let (near, far) = if start.distance(toward) <= end.distance(toward) {
+                    (start, end)
+                } else {
+                    (end, start)
+                };
+                (near, (far - near).normalize())
+            }
+        }
+    }
+}
+
+/// Angle between two measured elements
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AngleResult {
+    /// Point the angle is drawn at
+    pub vertex: Vec3,
+    /// Unit directions of the first and second arm
+    pub arms: (Vec3, Vec3),
+    /// Angle between the arms (radians, 0 to π)
+    pub angle: f32,
+}
+
+/// Angle measurement tool: collects two picks and reports their angle
+#[derive(Debug, Default)]
+pub struct AngleMeasurement {
+    /// Whether clicks on faces and edges are measuring
+    pub active: bool,
+    picks: Vec<MeasuredElement>,
+}
+
+impl AngleMeasurement {
+    /// Turn the tool on or off, dropping any picks
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+        self.picks.clear();
+    }
+
+    /// Add a pick; a third pick starts a new measurement
+    pub fn pick(&mut self, element: MeasuredElement) {
+        if self.picks.len() == 2 {
+            self.picks.clear();
+        }
+        self.picks.push(element);
+    }
+
+    /// Number of elements picked for the current measurement
+    pub fn pick_count(&self) -> usize {
+        self.picks.len()
+    }
+
+    /// Angle between the two picks, once both are made
+    pub fn result(&self) -> Option<AngleResult> {
+        let [first, second] = self.picks.as_slice() else {
+            return None;
+        };
+        let (anchor_a, arm_a) = first.arm(second.reference());
+        let (anchor_b, arm_b) = second.arm(first.reference());
+        Some(AngleResult {
+            vertex: (anchor_a + anchor_b) / 2.0,
+            arms: (arm_a, arm_b),
+            angle: arm_a.angle_between(arm_b),
+        })
+    }
+}
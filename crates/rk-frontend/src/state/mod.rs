@@ -1,16 +1,29 @@
 //! Application state module
 
+mod document;
 mod editor;
+mod measure;
+mod notification;
+pub mod project_file;
 mod sketch_mode;
 mod viewport;
 
-pub use editor::{EditorTool, PrimitiveType};
+pub use document::Document;
+pub use editor::{
+    CreateJointDialogState, EditorTool, ExportCheckDialogState, ImportMeshDialogState,
+    PatternDialogState, PrimitiveType, RenameDialogState, TurntableDialogState,
+};
+pub use measure::{AngleMeasurement, AngleResult, MeasuredElement};
+pub use notification::{Notification, NotificationKind};
 pub use sketch_mode::{
-    CadState, EditorMode, InProgressEntity, SketchAction, SketchModeState, SketchTool,
+    CadState, DimensionDialogState, EditorMode, ExtrudeDialogState, HoleDialogState,
+    InProgressEntity, RevolveAxis, RevolveDialogState, ShellDialogState, SketchAction,
+    SketchModeState, SketchTool,
 };
 pub use viewport::{
-    GizmoInteraction, GizmoTransform, PickablePartData, SharedViewportState, ViewportState,
-    pick_object,
+    GizmoDragStart, GizmoInteraction, GizmoTransform, PickCache, PickElement, PickResult,
+    PickableBodyData, PickablePartData, SharedViewportState, SnapKind, SnapPoint, ViewportState,
+    parts_in_rect, pick_body_element, pick_object, pick_snap_point,
 };
 
 use std::path::PathBuf;
@@ -18,27 +31,70 @@ use std::sync::Arc;
 
 use glam::Mat4;
 use parking_lot::Mutex;
+use rk_cad::CadData;
 use uuid::Uuid;
 
-use rk_core::{GeometryType, JointLimits, JointType, Part, Pose, Project, StlUnit};
+use document::document_title;
+use rk_core::{
+    AlignEdge, AlignReference, CollisionSource, GeometryType, JointDof, JointDynamics, JointLimits,
+    JointSafetyController, JointTransmission, JointType, MaterialDef, Part, PatternKind, Pose,
+    PrimitiveQuality, Project, StlUnit,
+};
 
 /// Actions that can be performed on the app state
 #[derive(Debug, Clone)]
 pub enum AppAction {
     // File actions (path-based, native only)
-    /// Import a mesh file (STL, OBJ, DAE)
+    /// Import a mesh file (STL, OBJ, DAE, PLY)
     ImportMesh(PathBuf),
+    /// Import a mesh file as collision geometry of an existing link
+    ImportCollisionMesh { path: PathBuf, link_id: Uuid },
     /// Import a URDF file
     ImportUrdf(PathBuf),
     /// Save project
     SaveProject(Option<PathBuf>),
     /// Load project
     LoadProject(PathBuf),
+    /// Merge a project or URDF file into the current project, attaching
+    /// its root links to `parent_link` by fixed joints
+    MergeProject {
+        path: PathBuf,
+        parent_link: Option<Uuid>,
+    },
+    /// Check the assembly before URDF export; exports right away if there
+    /// are no issues, otherwise shows them in the export check dialog
+    CheckExportUrdf { path: PathBuf, robot_name: String },
+    /// Apply the automatic fixes offered in the export check dialog
+    FixExportIssues,
     /// Export URDF with path and robot name
     ExportUrdf { path: PathBuf, robot_name: String },
+    /// Export the posed robot as a single STL mesh
+    ExportMergedStl(PathBuf),
+    /// Export the posed robot as a COLLADA scene with one node per part
+    ExportDae(PathBuf),
+    /// Export a CSV bill of materials with identical parts counted together
+    ExportBom { path: PathBuf },
+    /// Orbit the camera once around the model and write a numbered PNG sequence
+    ExportTurntable {
+        output_dir: PathBuf,
+        frames: u32,
+        duration_secs: f32,
+    },
     /// New project
     NewProject,
 
+    // Document actions
+    /// Open a new empty project in its own tab
+    NewDocument,
+    /// Make the document at an index the active one
+    SwitchDocument(usize),
+    /// Close the document at an index
+    CloseDocument(usize),
+    /// Copy the selected parts so they can be pasted into any open document
+    CopySelectedParts,
+    /// Paste copied parts into the active document
+    PasteParts,
+
     // File actions (bytes-based, for WASM)
     /// Import a mesh from bytes (STL format only for now)
     ImportMeshBytes { name: String, data: Vec<u8> },
@@ -55,24 +111,99 @@ pub enum AppAction {
     CreateEmpty { name: Option<String> },
     /// Select a part
     SelectPart(Option<Uuid>),
-    /// Delete selected part
-    DeleteSelectedPart,
+    /// Add a part to the selection, or remove it if already selected
+    TogglePartSelection(Uuid),
+    /// Select several parts, adding them to the current selection if `extend`
+    SelectParts { part_ids: Vec<Uuid>, extend: bool },
+    /// Point the camera at a part so it fills the view
+    FramePart(Uuid),
+    /// Delete the selected part and its link; the link's children are
+    /// reattached to its parent with `reparent_children`, else disconnected
+    DeleteSelectedPart { reparent_children: bool },
+    /// Rename a part
+    RenamePart { part_id: Uuid, name: String },
+    /// Copy a part in place and select the copy
+    DuplicatePart(Uuid),
+    /// Recompute a part's inertia from its mass and bounding box
+    RecomputeInertia(Uuid),
+    /// Recompute the inertia and center of mass of every link from its mesh
+    RecomputeAllInertias,
     /// Update part transform
     UpdatePartTransform { part_id: Uuid, transform: Mat4 },
+    /// Show or hide a part in the viewport
+    SetPartVisibility { part_id: Uuid, visible: bool },
+    /// Hide every part except the selected one
+    IsolateSelection,
+    /// Make all parts visible again
+    ShowAllParts,
+    /// Create copies of a part arranged in a linear or circular pattern
+    PatternPart {
+        part_id: Uuid,
+        kind: PatternKind,
+        /// Joint each copy to the original's parent link
+        attach_to_parent: bool,
+    },
+    /// Align the selected parts' bounding boxes along an axis (0 = X, 1 = Y, 2 = Z)
+    AlignSelectedParts {
+        axis: usize,
+        edge: AlignEdge,
+        reference: AlignReference,
+    },
+    /// Space the selected parts' bounding box centers evenly along an axis
+    DistributeSelectedParts { axis: usize },
+
+    // Gizmo actions
+    /// Finish a gizmo drag, keeping its result
+    CommitGizmoDrag(GizmoDragStart),
+    /// Cancel a gizmo drag, restoring the target's value from before it
+    RevertGizmoDrag(GizmoDragStart),
+
+    // Material actions
+    /// Add a new material to the library
+    AddMaterial,
+    /// Replace a library material, updating every part using it
+    UpdateMaterial { name: String, material: MaterialDef },
+    /// Remove a material from the library
+    RemoveMaterial { name: String },
+    /// Assign a library material to the selected parts, or clear it with `None`
+    AssignMaterial { name: Option<String> },
 
     // Assembly actions
     /// Connect two parts
     ConnectParts { parent: Uuid, child: Uuid },
     /// Disconnect a part from its parent
     DisconnectPart { child: Uuid },
+    /// Move the child point's part onto the parent point and joint them there
+    SnapJointPoints {
+        parent_point: Uuid,
+        child_point: Uuid,
+    },
+    /// Joint the child part's link to the parent part's link where it is
+    CreateJoint {
+        parent: Uuid,
+        child: Uuid,
+        joint_type: JointType,
+    },
 
     // Joint position actions
     /// Update a joint position (value in radians for revolute, meters for prismatic)
     UpdateJointPosition { joint_id: Uuid, position: f32 },
+    /// Update one DOF of a planar, floating or spherical joint (radians or meters)
+    UpdateJointDof {
+        joint_id: Uuid,
+        dof: JointDof,
+        value: f32,
+    },
     /// Reset a joint position to 0
     ResetJointPosition { joint_id: Uuid },
     /// Reset all joint positions to 0
     ResetAllJointPositions,
+    /// Move all joints to their default values (the home pose)
+    GoHome,
+    /// Store the current joint positions as the home pose
+    SetHomePose,
+    /// Load joint positions from a joint state YAML file (matched by joint name)
+    LoadJointState { path: PathBuf },
 
     // Joint configuration actions
     /// Update joint type
@@ -82,6 +213,17 @@ pub enum AppAction {
     },
     /// Update joint origin (position/rotation relative to parent)
     UpdateJointOrigin { joint_id: Uuid, origin: Pose },
+    /// Start placing a joint origin by clicking in the viewport, or stop with `None`
+    SetJointOriginPick(Option<Uuid>),
+    /// Start moving a joint origin with the viewport gizmo, or stop with `None`
+    SetJointOriginDrag(Option<Uuid>),
+    /// Move a joint origin to a world-space point, keeping its orientation
+    SnapJointOrigin {
+        joint_id: Uuid,
+        position: glam::Vec3,
+    },
+    /// Move a joint origin during a gizmo drag, committed by `CommitGizmoDrag`
+    DragJointOrigin { joint_id: Uuid, origin: Pose },
     /// Update joint axis (for revolute/prismatic/continuous)
     UpdateJointAxis { joint_id: Uuid, axis: glam::Vec3 },
     /// Update joint limits
@@ -89,6 +231,23 @@ pub enum AppAction {
         joint_id: Uuid,
         limits: Option<JointLimits>,
     },
+    /// Update a joint's position in the home pose
+    UpdateJointDefault { joint_id: Uuid, value: f32 },
+    /// Update joint damping and friction
+    UpdateJointDynamics {
+        joint_id: Uuid,
+        dynamics: Option<JointDynamics>,
+    },
+    /// Update joint safety controller (soft limits)
+    UpdateJointSafetyController {
+        joint_id: Uuid,
+        safety_controller: Option<JointSafetyController>,
+    },
+    /// Update joint ros_control transmission
+    UpdateJointTransmission {
+        joint_id: Uuid,
+        transmission: Option<JointTransmission>,
+    },
 
     // Collision actions
     /// Select a collision element (link_id, collision_index)
@@ -98,6 +257,11 @@ pub enum AppAction {
         link_id: Uuid,
         geometry: GeometryType,
     },
+    /// Replace a link's collisions with one derived from its visual mesh
+    CopyVisualToCollision {
+        link_id: Uuid,
+        source: CollisionSource,
+    },
     /// Remove a collision element from a link
     RemoveCollision { link_id: Uuid, index: usize },
     /// Update collision origin (position/rotation)
@@ -126,6 +290,17 @@ pub enum AngleDisplayMode {
     Radians,
 }
 
+/// Unit lengths are shown and entered in
+///
+/// Lengths are always stored in meters; this only affects the UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum LengthUnit {
+    Millimeters,
+    Centimeters,
+    #[default]
+    Meters,
+}
+
 /// Application state
 pub struct AppState {
     /// Current project (contains parts, assembly, materials)
@@ -134,6 +309,8 @@ pub struct AppState {
     pub cad: CadState,
     /// Currently selected part
     pub selected_part: Option<Uuid>,
+    /// All selected parts in the order they were picked (includes `selected_part`)
+    pub selected_parts: Vec<Uuid>,
     /// Currently selected collision element (link_id, collision_index)
     pub selected_collision: Option<(Uuid, usize)>,
     /// Hovered part
@@ -152,10 +329,61 @@ pub struct AppState {
     pub show_part_axes: bool,
     /// Show joint markers
     pub show_joint_markers: bool,
+    /// Show the center of mass of the whole assembly
+    pub show_center_of_mass: bool,
     /// Global unit setting for STL import and other operations
     pub stl_import_unit: StlUnit,
+    /// Unit meshes are written in on URDF export
+    pub stl_export_unit: StlUnit,
+    /// Significant digits of exported URDF numbers (`None` for full precision)
+    pub export_precision: Option<u32>,
+    /// Tessellation level for URDF primitive geometry on import
+    pub primitive_quality: PrimitiveQuality,
     /// Angle display mode for joint sliders
     pub angle_display_mode: AngleDisplayMode,
+    /// Unit lengths are shown and entered in
+    pub length_unit: LengthUnit,
+    /// Joint whose origin is being placed by clicking in the viewport
+    pub joint_origin_pick: Option<Uuid>,
+    /// Joint whose origin the viewport gizmo is attached to
+    pub joint_origin_drag: Option<Uuid>,
+    /// Part rename dialog, if open
+    pub rename_dialog: Option<RenameDialogState>,
+    /// Part pattern dialog, if open
+    pub pattern_dialog: Option<PatternDialogState>,
+    /// Joint creation dialog, if open
+    pub create_joint_dialog: Option<CreateJointDialogState>,
+    /// Turntable export dialog, if open
+    pub turntable_dialog: Option<TurntableDialogState>,
+    /// Pre-export check dialog, if open
+    pub export_check_dialog: Option<ExportCheckDialogState>,
+    /// Visual or collision mesh import dialog, if open
+    pub import_mesh_dialog: Option<ImportMeshDialogState>,
+    /// Tessellation tolerance for CAD bodies without their own override
+    pub cad_tessellation_tolerance: f32,
+    /// Snap gizmo part translations to a grid
+    pub snap_part_translation: bool,
+    /// Grid increment for part translation snapping (meters)
+    pub part_snap_increment: f32,
+    /// Distance the arrow keys move the selected part (meters)
+    pub nudge_step: f32,
+    /// Solve the sketch being edited automatically after each edit
+    pub sketch_auto_solve: bool,
+    /// Grid snapping for sketches entered from now on
+    pub sketch_snap_to_grid: bool,
+    /// Grid spacing for sketches entered from now on
+    pub sketch_grid_spacing: f32,
+    /// Reference used by the align commands
+    pub align_reference: AlignReference,
+    /// Open documents; the entry at `active_document` is empty while its
+    /// contents live in the fields above
+    documents: Vec<Document>,
+    /// Index of the active document
+    pub active_document: usize,
+    /// Parts copied from any document, with the materials they use
+    pub part_clipboard: Option<Project>,
+    /// Toasts waiting to be shown or on screen
+    pub notifications: Vec<Notification>,
 }
 
 impl Default for AppState {
@@ -164,6 +392,7 @@ impl Default for AppState {
             project: Project::default(),
             cad: CadState::default(),
             selected_part: None,
+            selected_parts: Vec::new(),
             selected_collision: None,
             hovered_part: None,
             current_tool: EditorTool::default(),
@@ -173,8 +402,33 @@ impl Default for AppState {
             pending_actions: Vec::new(),
             show_part_axes: true,
             show_joint_markers: true,
+            show_center_of_mass: false,
             stl_import_unit: StlUnit::Millimeters,
+            stl_export_unit: StlUnit::Meters,
+            export_precision: Some(rk_core::DEFAULT_PRECISION),
+            primitive_quality: PrimitiveQuality::default(),
             angle_display_mode: AngleDisplayMode::default(),
+            length_unit: LengthUnit::default(),
+            joint_origin_pick: None,
+            joint_origin_drag: None,
+            rename_dialog: None,
+            pattern_dialog: None,
+            create_joint_dialog: None,
+            turntable_dialog: None,
+            export_check_dialog: None,
+            import_mesh_dialog: None,
+            cad_tessellation_tolerance: 0.01,
+            snap_part_translation: false,
+            part_snap_increment: 0.01,
+            nudge_step: 0.01,
+            sketch_auto_solve: true,
+            sketch_snap_to_grid: true,
+            sketch_grid_spacing: 1.0,
+            align_reference: AlignReference::default(),
+            documents: vec![Document::default()],
+            active_document: 0,
+            part_clipboard: None,
+            notifications: Vec::new(),
         }
     }
 }
@@ -213,12 +467,78 @@ impl AngleDisplayMode {
     }
 }
 
+impl LengthUnit {
+    /// All units, for selection lists
+    pub const ALL: &'static [LengthUnit] = &[
+        LengthUnit::Millimeters,
+        LengthUnit::Centimeters,
+        LengthUnit::Meters,
+    ];
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            LengthUnit::Millimeters => "Millimeters",
+            LengthUnit::Centimeters => "Centimeters",
+            LengthUnit::Meters => "Meters",
+        }
+    }
+
+    /// Display units per meter
+    fn per_meter(&self) -> f32 {
+        match self {
+            LengthUnit::Millimeters => 1000.0,
+            LengthUnit::Centimeters => 100.0,
+            LengthUnit::Meters => 1.0,
+        }
+    }
+
+    /// Convert meters to display value
+    pub fn from_meters(&self, meters: f32) -> f32 {
+        meters * self.per_meter()
+    }
+
+    /// Convert display value to meters
+    pub fn to_meters(&self, value: f32) -> f32 {
+        value / self.per_meter()
+    }
+
+    /// Get the suffix for display
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            LengthUnit::Millimeters => " mm",
+            LengthUnit::Centimeters => " cm",
+            LengthUnit::Meters => " m",
+        }
+    }
+
+    /// Format a length in meters to the millimeter, with the unit suffix
+    pub fn format(&self, meters: f32) -> String {
+        let decimals = match self {
+            LengthUnit::Millimeters => 0,
+            LengthUnit::Centimeters => 1,
+            LengthUnit::Meters => 3,
+        };
+        format!("{:.*}{}", decimals, self.from_meters(meters), self.suffix())
+    }
+}
+
 impl AppState {
     /// Create a new app state
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Enter sketch editing mode with the preferred grid settings
+    pub fn enter_sketch_mode(&mut self, sketch_id: Uuid) {
+        self.cad.enter_sketch_mode(sketch_id);
+        let (snap_to_grid, grid_spacing) = (self.sketch_snap_to_grid, self.sketch_grid_spacing);
+        if let Some(mode) = self.cad.editor_mode.sketch_mut() {
+            mode.snap_to_grid = snap_to_grid;
+            mode.grid_spacing = grid_spacing;
+        }
+    }
+
     /// Add a part (delegates to project)
     pub fn add_part(&mut self, part: Part) {
         self.project.add_part(part);
@@ -239,12 +559,59 @@ impl AppState {
     /// Remove a part (delegates to project)
     pub fn remove_part(&mut self, id: Uuid) -> Option<Part> {
         self.modified = true;
+        self.selected_parts.retain(|selected| *selected != id);
         self.project.remove_part(id)
     }
 
     /// Select a part
     pub fn select_part(&mut self, id: Option<Uuid>) {
+        if id != self.selected_part {
+            self.joint_origin_drag = None;
+        }
         self.selected_part = id;
+        self.selected_parts = id.into_iter().collect();
+    }
+
+    /// Add a part to the selection, or remove it if already selected
+    ///
+    /// The most recently added part becomes the active `selected_part`.
+    pub fn toggle_part_selection(&mut self, id: Uuid) {
+        if let Some(index) = self.selected_parts.iter().position(|s| *s == id) {
+            self.selected_parts.remove(index);
+            self.selected_part = self.selected_parts.last().copied();
+        } else {
+            self.selected_parts.push(id);
+            self.selected_part = Some(id);
+        }
+    }
+
+    /// Select several parts at once
+    ///
+    /// With `extend`, the parts are added to the current selection. The last
+    /// new part becomes the active `selected_part`; an empty list keeps the
+    /// selection when extending and clears it otherwise.
+    pub fn select_parts(&mut self, ids: &[Uuid], extend: bool) {
+        if !extend {
+            self.select_part(None);
+        }
+        for &id in ids {
+            if !self.selected_parts.contains(&id) {
+                self.selected_parts.push(id);
+                self.selected_part = Some(id);
+            }
+        }
+    }
+
+    /// Show a toast confirming that something worked
+    pub fn notify_success(&mut self, message: impl Into<String>) {
+        self.notifications
+            .push(Notification::new(NotificationKind::Success, message));
+    }
+
+    /// Show a toast reporting a failure
+    pub fn notify_error(&mut self, message: impl Into<String>) {
+        self.notifications
+            .push(Notification::new(NotificationKind::Error, message));
     }
 
     /// Queue an action
@@ -262,20 +629,125 @@ impl AppState {
         self.project = Project::default();
         self.cad = CadState::default();
         self.selected_part = None;
+        self.selected_parts.clear();
         self.selected_collision = None;
+        self.joint_origin_drag = None;
         self.project_path = None;
         self.modified = false;
     }
 
-    /// Load a project
-    pub fn load_project(&mut self, project: Project, path: PathBuf) {
+    /// Load a project, rebuilding CAD bodies from its feature history
+    pub fn load_project(&mut self, project: Project, cad: CadData, path: Option<PathBuf>) {
+        self.cad = CadState::default();
+        self.cad.data = cad;
+        self.cad.rebuild();
         self.project = project;
-        self.cad = CadState::default(); // TODO: Load CAD data from project
-        self.project_path = Some(path);
+        self.project_path = path;
         self.selected_part = None;
+        self.selected_parts.clear();
         self.selected_collision = None;
+        self.joint_origin_drag = None;
         self.modified = false;
     }
+
+    /// Titles of the open documents, in tab order
+    pub fn document_titles(&self) -> Vec<String> {
+        self.documents
+            .iter()
+            .enumerate()
+            .map(|(index, document)| {
+                if index == self.active_document {
+                    document_title(&self.project, self.project_path.as_deref(), self.modified)
+                } else {
+                    document_title(
+                        &document.project,
+                        document.project_path.as_deref(),
+                        document.modified,
+                    )
+                }
+            })
+            .collect()
+    }
+
+    /// Open a new empty project in its own tab and make it active
+    pub fn open_document(&mut self) {
+        self.documents.push(Document::default());
+        self.switch_document(self.documents.len() - 1);
+    }
+
+    /// Make the document at `index` the active one
+    ///
+    /// Returns whether the active document changed.
+    pub fn switch_document(&mut self, index: usize) -> bool {
+        if index == self.active_document || index >= self.documents.len() {
+            return false;
+        }
+        let current = self.take_document();
+        self.documents[self.active_document] = current;
+        let next = std::mem::take(&mut self.documents[index]);
+        self.restore_document(next);
+        self.active_document = index;
+        true
+    }
+
+    /// Close the document at `index`
+    ///
+    /// Closing the last document leaves a new empty project. Returns whether
+    /// the active document changed.
+    pub fn close_document(&mut self, index: usize) -> bool {
+        if index >= self.documents.len() {
+            return false;
+        }
+        if self.documents.len() == 1 {
+            self.new_project();
+            return true;
+        }
+        let closing_active = index == self.active_document;
+        if closing_active {
+            let next = if index + 1 < self.documents.len() {
+                index + 1
+            } else {
+                index - 1
+            };
+            self.switch_document(next);
+        }
+        self.documents.remove(index);
+        if self.active_document > index {
+            self.active_document -= 1;
+        }
+        closing_active
+    }
+
+    /// Move the active document out of the state, clearing what refers to it
+    fn take_document(&mut self) -> Document {
+        self.hovered_part = None;
+        self.joint_origin_pick = None;
+        self.joint_origin_drag = None;
+        self.rename_dialog = None;
+        self.pattern_dialog = None;
+        self.create_joint_dialog = None;
+        self.import_mesh_dialog = None;
+        Document {
+            project: std::mem::take(&mut self.project),
+            cad: std::mem::take(&mut self.cad),
+            selected_part: self.selected_part.take(),
+            selected_parts: std::mem::take(&mut self.selected_parts),
+            selected_collision: self.selected_collision.take(),
+            project_path: self.project_path.take(),
+            modified: std::mem::take(&mut self.modified),
+        }
+    }
+
+    /// Make a document the active one
+    fn restore_document(&mut self, document: Document) {
+        self.project = document.project;
+        self.cad = document.cad;
+        self.selected_part = document.selected_part;
+        self.selected_parts = document.selected_parts;
+        self.selected_collision = document.selected_collision;
+        self.project_path = document.project_path;
+        self.modified = document.modified;
+    }
 }
 
 pub type SharedAppState = Arc<Mutex<AppState>>;
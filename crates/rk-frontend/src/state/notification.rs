@@ -0,0 +1,42 @@
+//! Toast notifications for user-facing results of actions
+
+/// Kind of notification, which sets its color and how long it stays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    Success,
+    Error,
+}
+
+/// A message shown in the corner of the window until it times out or is
+/// clicked away
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub message: String,
+    /// Time (seconds) the notification was first drawn
+    pub shown_at: Option<f64>,
+}
+
+impl Notification {
+    pub fn new(kind: NotificationKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            shown_at: None,
+        }
+    }
+
+    /// Seconds the notification stays on screen; errors stay longer
+    pub fn duration(&self) -> f64 {
+        match self.kind {
+            NotificationKind::Success => 4.0,
+            NotificationKind::Error => 8.0,
+        }
+    }
+
+    /// Whether the notification has been on screen for its full duration
+    pub fn expired(&self, now: f64) -> bool {
+        self.shown_at
+            .is_some_and(|shown_at| now - shown_at >= self.duration())
+    }
+}
@@ -0,0 +1,60 @@
+//! Editor project files
+//!
+//! rk-core does not know about CAD data, so the editor saves the core
+//! [`Project`] and the CAD history side by side. Plain project files, as
+//! written before CAD history was saved, still load with an empty history.
+
+use std::path::Path;
+
+use rk_cad::CadData;
+use rk_core::{Project, ProjectError};
+use serde::{Deserialize, Serialize};
+
+/// Project file written by the editor
+#[derive(Serialize)]
+struct ProjectFileRef<'a> {
+    project: &'a Project,
+    cad: &'a CadData,
+}
+
+/// Project file read by the editor
+#[derive(Deserialize)]
+struct ProjectFile {
+    project: Project,
+    #[serde(default)]
+    cad: CadData,
+}
+
+/// Serialize a project and its CAD data
+pub fn to_bytes(project: &Project, cad: &CadData) -> Result<Vec<u8>, ProjectError> {
+    let file = ProjectFileRef { project, cad };
+    let content = ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default())
+        .map_err(|e| ProjectError::Serialize(e.to_string()))?;
+    Ok(content.into_bytes())
+}
+
+/// Save a project and its CAD data to a file
+pub fn save(project: &Project, cad: &CadData, path: impl AsRef<Path>) -> Result<(), ProjectError> {
+    let content = to_bytes(project, cad)?;
+    std::fs::write(path, content).map_err(|e| ProjectError::Io(e.to_string()))
+}
+
+/// Load a project and its CAD data from a file
+pub fn load(path: impl AsRef<Path>) -> Result<(Project, CadData), ProjectError> {
+    let data = std::fs::read(path).map_err(|e| ProjectError::Io(e.to_string()))?;
+    load_from_bytes(&data)
+}
+
+/// Load a project and its CAD data from bytes
+pub fn load_from_bytes(data: &[u8]) -> Result<(Project, CadData), ProjectError> {
+    let content =
+        std::str::from_utf8(data).map_err(|e| ProjectError::Deserialize(e.to_string()))?;
+    match ron::from_str::<ProjectFile>(content) {
+        Ok(ProjectFile { mut project, cad }) => {
+            project.migrate()?;
+            Ok((project, cad))
+        }
+        // Not wrapped: a plain project file without CAD data
+        Err(_) => Project::load_from_bytes(data).map(|project| (project, CadData::default())),
+    }
+}
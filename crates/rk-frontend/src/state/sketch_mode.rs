@@ -1,9 +1,23 @@
 //! Sketch mode state types
 
-use glam::Vec2;
+use std::collections::HashSet;
+
+use glam::{Vec2, Vec3};
 use uuid::Uuid;
 
-use rk_cad::{CadData, Sketch, SketchConstraint, SketchEntity, SketchPlane};
+use rk_cad::{
+    Axis3D, BooleanOp, CadData, ExtrudeDirection, FaceInfo, Feature, FeatureError, FeatureResult,
+    HoleDepth, HoleKind, RefAxis, RefPoint, STANDARD_HOLE_SIZES, Sketch, SketchClipboard,
+    SketchConstraint, SketchEntity, SketchPlane, default_kernel,
+};
+
+use super::PickableBodyData;
+
+/// Seconds a sketch must go unchanged before it is solved automatically
+pub const AUTO_SOLVE_DELAY: f64 = 0.15;
+
+/// Angle increment lines snap to while the angle lock is held (radians)
+pub const ANGLE_SNAP_STEP: f32 = std::f32::consts::PI / 12.0;
 
 /// Tool for sketch editing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -19,6 +33,16 @@ pub enum SketchTool {
     Arc,
     /// Draw a rectangle
     Rectangle,
+    /// Draw a spline through clicked points
+    Spline,
+    /// Draw an ellipse from its center and two axis ends
+    Ellipse,
+    /// Mirror the selection across a line
+    Mirror,
+    /// Offset the selected chain of curves
+    Offset,
+    /// Project body edges onto the sketch plane
+    Project,
     /// Add coincident constraint
     ConstrainCoincident,
     /// Add horizontal constraint
@@ -46,6 +70,11 @@ impl SketchTool {
             SketchTool::Circle => "Circle",
             SketchTool::Arc => "Arc",
             SketchTool::Rectangle => "Rectangle",
+            SketchTool::Spline => "Spline",
+            SketchTool::Ellipse => "Ellipse",
+            SketchTool::Mirror => "Mirror",
+            SketchTool::Offset => "Offset",
+            SketchTool::Project => "Project",
             SketchTool::ConstrainCoincident => "Coincident",
             SketchTool::ConstrainHorizontal => "Horizontal",
             SketchTool::ConstrainVertical => "Vertical",
@@ -61,7 +90,12 @@ impl SketchTool {
     pub fn is_drawing(&self) -> bool {
         matches!(
             self,
-            SketchTool::Line | SketchTool::Circle | SketchTool::Arc | SketchTool::Rectangle
+            SketchTool::Line
+                | SketchTool::Circle
+                | SketchTool::Arc
+                | SketchTool::Rectangle
+                | SketchTool::Spline
+                | SketchTool::Ellipse
         )
     }
 
@@ -105,6 +139,18 @@ pub enum InProgressEntity {
         corner1: Vec2,
         preview_corner2: Vec2,
     },
+    /// Ellipse with center, and major axis end once placed (awaiting the
+    /// major axis end, then the minor radius)
+    Ellipse {
+        center_point: Uuid,
+        major_end: Option<Vec2>,
+        preview_end: Vec2,
+    },
+    /// Spline through the points placed so far (awaiting more points)
+    Spline {
+        control_points: Vec<Uuid>,
+        preview_end: Vec2,
+    },
 }
 
 /// Sketch editing mode state
@@ -120,10 +166,20 @@ pub struct SketchModeState {
     pub selected_entities: Vec<Uuid>,
     /// Hovered entity
     pub hovered_entity: Option<Uuid>,
+    /// Lasso being dragged with the select tool (sketch coordinates)
+    pub lasso: Vec<Vec2>,
     /// Snap to grid
     pub snap_to_grid: bool,
     /// Grid spacing for snapping
     pub grid_spacing: f32,
+    /// Whether projected edges become construction geometry
+    pub project_construction: bool,
+    /// Distance entered for the offset tool
+    pub offset_distance: f32,
+    /// Sketch revision after the last automatic solve
+    pub solved_revision: Option<u64>,
+    /// Sketch revision waiting to be solved, with the time it was first seen
+    pub pending_solve: Option<(u64, f64)>,
 }
 
 impl Default for SketchModeState {
@@ -134,8 +190,13 @@ impl Default for SketchModeState {
             in_progress: None,
             selected_entities: Vec::new(),
             hovered_entity: None,
+            lasso: Vec::new(),
             snap_to_grid: true,
             grid_spacing: 1.0,
+            project_construction: true,
+            offset_distance: 0.1,
+            solved_revision: None,
+            pending_solve: None,
         }
     }
 }
@@ -191,6 +252,18 @@ impl SketchModeState {
             point
         }
     }
+
+    /// Snap a line end so the line from `start` runs at a multiple of
+    /// [`ANGLE_SNAP_STEP`], keeping the cursor's distance from `start`
+    pub fn snap_angle(&self, start: Vec2, point: Vec2) -> Vec2 {
+        let offset = point - start;
+        let length = offset.length();
+        if length <= f32::EPSILON {
+            return start;
+        }
+        let angle = (offset.y.atan2(offset.x) / ANGLE_SNAP_STEP).round() * ANGLE_SNAP_STEP;
+        start + Vec2::from_angle(angle) * length
+    }
 }
 
 /// Editor mode (3D assembly or 2D sketch)
@@ -226,11 +299,422 @@ impl EditorMode {
     }
 }
 
+/// Axis a sketch profile is revolved around
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RevolveAxis {
+    /// A line entity of the sketch
+    SketchLine(Uuid),
+    /// A reference axis of the CAD data
+    RefAxis(Uuid),
+    /// The sketch plane's X axis through its origin
+    #[default]
+    SketchX,
+    /// The sketch plane's Y axis through its origin
+    SketchY,
+    /// World X axis
+    WorldX,
+    /// World Y axis
+    WorldY,
+    /// World Z axis
+    WorldZ,
+}
+
+impl RevolveAxis {
+    /// Reference axes that do not depend on sketch entities
+    pub const REFERENCE: [RevolveAxis; 5] = [
+        RevolveAxis::SketchX,
+        RevolveAxis::SketchY,
+        RevolveAxis::WorldX,
+        RevolveAxis::WorldY,
+        RevolveAxis::WorldZ,
+    ];
+
+    /// Get the display name of a reference axis
+    pub fn name(&self) -> &'static str {
+        match self {
+            RevolveAxis::SketchLine(_) => "Sketch Line",
+            RevolveAxis::RefAxis(_) => "Reference Axis",
+            RevolveAxis::SketchX => "Sketch X",
+            RevolveAxis::SketchY => "Sketch Y",
+            RevolveAxis::WorldX => "World X",
+            RevolveAxis::WorldY => "World Y",
+            RevolveAxis::WorldZ => "World Z",
+        }
+    }
+
+    /// Resolve the axis in world space
+    ///
+    /// Returns `None` if the sketch line or reference axis no longer exists,
+    /// or the line has zero length.
+    pub fn resolve(&self, sketch: &Sketch, ref_axes: &[RefAxis]) -> Option<Axis3D> {
+        let plane = &sketch.plane;
+        match self {
+            RevolveAxis::SketchLine(line_id) => {
+                let SketchEntity::Line { start, end, .. } = sketch.get_entity(*line_id)? else {
+                    return None;
+                };
+                let start = plane.to_world(sketch.get_entity(*start)?.position()?);
+                let end = plane.to_world(sketch.get_entity(*end)?.position()?);
+                if start.distance(end) < f32::EPSILON {
+                    return None;
+                }
+                Some(Axis3D::new(start, end - start))
+            }
+            RevolveAxis::RefAxis(axis_id) => ref_axes
+                .iter()
+                .find(|axis| axis.id == *axis_id)
+                .map(RefAxis::axis),
+            RevolveAxis::SketchX => Some(Axis3D::new(plane.origin, plane.x_axis)),
+            RevolveAxis::SketchY => Some(Axis3D::new(plane.origin, plane.y_axis())),
+            RevolveAxis::WorldX => Some(Axis3D::x()),
+            RevolveAxis::WorldY => Some(Axis3D::y()),
+            RevolveAxis::WorldZ => Some(Axis3D::z()),
+        }
+    }
+}
+
+/// State of the revolve feature dialog
+#[derive(Debug, Clone)]
+pub struct RevolveDialogState {
+    /// Sketch whose profile is revolved
+    pub sketch_id: Uuid,
+    /// Name of the feature to create
+    pub name: String,
+    /// Axis of revolution
+    pub axis: RevolveAxis,
+    /// Revolution angle in degrees
+    pub angle_degrees: f32,
+    /// Boolean operation with the target body
+    pub boolean_op: BooleanOp,
+    /// Target body for join/cut/intersect
+    pub target_body: Option<Uuid>,
+    /// Renderer mesh showing the live preview
+    pub preview_part: Option<Uuid>,
+    /// Error from the last preview attempt
+    pub preview_error: Option<String>,
+}
+
+impl RevolveDialogState {
+    /// Create a dialog for revolving a sketch with a full 360 degree turn
+    pub fn new(sketch_id: Uuid, name: impl Into<String>) -> Self {
+        Self {
+            sketch_id,
+            name: name.into(),
+            axis: RevolveAxis::default(),
+            angle_degrees: 360.0,
+            boolean_op: BooleanOp::New,
+            target_body: None,
+            preview_part: None,
+            preview_error: None,
+        }
+    }
+
+    /// Build the revolve feature described by the dialog
+    pub fn build_feature(&self, sketch: &Sketch, ref_axes: &[RefAxis]) -> FeatureResult<Feature> {
+        let axis = self.axis.resolve(sketch, ref_axes).ok_or_else(|| {
+            FeatureError::InvalidFeature("Revolve axis is not a valid line".into())
+        })?;
+        let mut feature = Feature::revolve(
+            self.name.clone(),
+            self.sketch_id,
+            axis,
+            self.angle_degrees.to_radians(),
+        );
+        if let Feature::Revolve {
+            boolean_op,
+            target_body,
+            ..
+        } = &mut feature
+        {
+            *boolean_op = self.boolean_op;
+            *target_body = match self.boolean_op {
+                BooleanOp::New => None,
+                _ => self.target_body,
+            };
+        }
+        Ok(feature)
+    }
+}
+
+/// State of the extrude feature dialog
+#[derive(Debug, Clone)]
+pub struct ExtrudeDialogState {
+    /// Sketch whose profile is extruded
+    pub sketch_id: Uuid,
+    /// Name of the feature to create
+    pub name: String,
+    /// Indices of the sketch profiles to extrude
+    pub profiles: Vec<usize>,
+    /// Extrusion distance
+    pub distance: f32,
+    /// Direction relative to the sketch normal
+    pub direction: ExtrudeDirection,
+    /// Boolean operation with the target body
+    pub boolean_op: BooleanOp,
+    /// Target body for join/cut/intersect
+    pub target_body: Option<Uuid>,
+    /// Draft angle in radians, tapering the side walls
+    pub draft_angle: f32,
+    /// Whether the feature is suppressed
+    pub suppressed: bool,
+    /// Renderer mesh showing the live preview
+    pub preview_part: Option<Uuid>,
+    /// Error from the last preview attempt
+    pub preview_error: Option<String>,
+    /// Feature being edited, replaced on confirm instead of adding a new one
+    pub editing: Option<Uuid>,
+    /// Rollback position from before the edit, restored on cancel
+    pub previous_rollback: Option<usize>,
+}
+
+impl ExtrudeDialogState {
+    /// Create a dialog for extruding a sketch along its normal
+    pub fn new(sketch_id: Uuid, name: impl Into<String>) -> Self {
+        Self {
+            sketch_id,
+            name: name.into(),
+            profiles: vec![0],
+            distance: 1.0,
+            direction: ExtrudeDirection::default(),
+            boolean_op: BooleanOp::New,
+            target_body: None,
+            draft_angle: 0.0,
+            suppressed: false,
+            preview_part: None,
+            preview_error: None,
+            editing: None,
+            previous_rollback: None,
+        }
+    }
+
+    /// Create a dialog for editing an existing extrude feature
+    pub fn edit(feature: &Feature) -> Option<Self> {
+        let Feature::Extrude {
+            id,
+            name,
+            sketch_id,
+            profiles,
+            distance,
+            direction,
+            boolean_op,
+            target_body,
+            draft_angle,
+            suppressed,
+        } = feature
+        else {
+            return None;
+        };
+        Some(Self {
+            profiles: profiles.clone().unwrap_or_else(|| vec![0]),
+            distance: *distance,
+            direction: *direction,
+            boolean_op: *boolean_op,
+            target_body: *target_body,
+            draft_angle: *draft_angle,
+            suppressed: *suppressed,
+            editing: Some(*id),
+            ..Self::new(*sketch_id, name.clone())
+        })
+    }
+
+    /// Build the extrude feature described by the dialog
+    pub fn build_feature(&self) -> Feature {
+        let mut feature = Feature::extrude(
+            self.name.clone(),
+            self.sketch_id,
+            self.distance,
+            self.direction,
+        );
+        if let Feature::Extrude {
+            profiles,
+            boolean_op,
+            target_body,
+            draft_angle,
+            ..
+        } = &mut feature
+        {
+            *profiles = Some(self.profiles.clone());
+            *draft_angle = self.draft_angle;
+            *boolean_op = self.boolean_op;
+            *target_body = match self.boolean_op {
+                BooleanOp::New => None,
+                _ => self.target_body,
+            };
+        }
+        feature.set_suppressed(self.suppressed);
+        feature
+    }
+}
+
+/// State of the radius/diameter dimension dialog
+#[derive(Debug, Clone)]
+pub struct DimensionDialogState {
+    /// Circle or arc being dimensioned
+    pub entity: Uuid,
+    /// Dimension value, a diameter if `diameter` is set
+    pub value: f32,
+    /// Dimension the diameter instead of the radius
+    pub diameter: bool,
+}
+
+/// State of the shell feature dialog
+#[derive(Debug, Clone)]
+pub struct ShellDialogState {
+    /// Name of the feature to create
+    pub name: String,
+    /// Body to hollow
+    pub body_id: Option<Uuid>,
+    /// Wall thickness (positive = inward, negative = outward)
+    pub thickness: f32,
+    /// Faces of the selected body
+    pub faces: Vec<FaceInfo>,
+    /// Faces to remove, leaving the shell open
+    pub removed_faces: Vec<Uuid>,
+    /// Error from face enumeration or validation
+    pub error: Option<String>,
+}
+
+impl ShellDialogState {
+    /// Create a dialog for shelling a body
+    pub fn new(name: impl Into<String>, body_id: Option<Uuid>) -> Self {
+        Self {
+            name: name.into(),
+            body_id,
+            thickness: 1.0,
+            faces: Vec::new(),
+            removed_faces: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Build the shell feature described by the dialog
+    pub fn build_feature(&self) -> FeatureResult<Feature> {
+        let body_id = self
+            .body_id
+            .ok_or_else(|| FeatureError::InvalidFeature("No body selected".into()))?;
+        Ok(Feature::shell(
+            self.name.clone(),
+            body_id,
+            self.thickness,
+            self.removed_faces.clone(),
+        ))
+    }
+}
+
+/// State of the hole feature dialog
+#[derive(Debug, Clone)]
+pub struct HoleDialogState {
+    /// Name of the feature to create
+    pub name: String,
+    /// Body to drill
+    pub body_id: Option<Uuid>,
+    /// Point on a face where the hole starts, once picked in the viewport
+    pub position: Option<Vec3>,
+    /// Direction into the material, opposite the picked face's normal
+    pub direction: Vec3,
+    /// Selected entry of [`STANDARD_HOLE_SIZES`], or `None` for custom sizes
+    pub size: Option<usize>,
+    /// Hole diameter
+    pub diameter: f32,
+    /// Shape of the hole entry
+    pub kind: HoleKind,
+    /// Cut through the whole body instead of to `depth`
+    pub through_all: bool,
+    /// Depth of a blind hole
+    pub depth: f32,
+    /// Error from picking or validation
+    pub error: Option<String>,
+}
+
+impl HoleDialogState {
+    /// Create a dialog for an M3 through hole in a body
+    pub fn new(name: impl Into<String>, body_id: Option<Uuid>) -> Self {
+        let mut dialog = Self {
+            name: name.into(),
+            body_id,
+            position: None,
+            direction: Vec3::NEG_Z,
+            size: None,
+            diameter: 0.0,
+            kind: HoleKind::Simple,
+            through_all: true,
+            depth: 0.01,
+            error: None,
+        };
+        dialog.apply_size(2);
+        dialog
+    }
+
+    /// Take the diameter and entry dimensions from a standard size
+    pub fn apply_size(&mut self, index: usize) {
+        let Some(size) = STANDARD_HOLE_SIZES.get(index) else {
+            return;
+        };
+        self.size = Some(index);
+        self.diameter = size.clearance_diameter;
+        self.kind = match self.kind {
+            HoleKind::Simple => HoleKind::Simple,
+            HoleKind::Counterbore { .. } => size.counterbore(),
+            HoleKind::Countersink { .. } => size.countersink(),
+        };
+    }
+
+    /// Entry shapes to choose from, sized for the selected standard size or
+    /// scaled from the diameter for custom holes
+    pub fn kind_options(&self) -> [HoleKind; 3] {
+        match self.size.and_then(|index| STANDARD_HOLE_SIZES.get(index)) {
+            Some(size) => [HoleKind::Simple, size.counterbore(), size.countersink()],
+            None => [
+                HoleKind::Simple,
+                HoleKind::Counterbore {
+                    diameter: self.diameter * 1.8,
+                    depth: self.diameter,
+                },
+                HoleKind::Countersink {
+                    diameter: self.diameter * 2.0,
+                    angle: 90f32.to_radians(),
+                },
+            ],
+        }
+    }
+
+    /// Build the hole feature described by the dialog
+    pub fn build_feature(&self) -> FeatureResult<Feature> {
+        let body_id = self
+            .body_id
+            .ok_or_else(|| FeatureError::InvalidFeature("No body selected".into()))?;
+        let position = self.position.ok_or_else(|| {
+            FeatureError::InvalidFeature("Click a face of the body to place the hole".into())
+        })?;
+        let depth = if self.through_all {
+            HoleDepth::ThroughAll
+        } else {
+            HoleDepth::Blind(self.depth)
+        };
+        let mut feature = Feature::hole(
+            self.name.clone(),
+            body_id,
+            position,
+            self.direction,
+            self.diameter,
+            depth,
+        );
+        if let Feature::Hole { kind, .. } = &mut feature {
+            *kind = self.kind;
+        }
+        Ok(feature)
+    }
+}
+
 /// Actions related to sketch mode
 #[derive(Debug, Clone)]
 pub enum SketchAction {
     /// Create a new sketch on a plane
     CreateSketch { plane: SketchPlane },
+    /// Start or stop waiting for a face click to sketch on
+    SetFaceSketchPick { active: bool },
+    /// Create a new sketch on the plane of a body face and start editing it
+    CreateSketchOnFace { body_id: Uuid, face_id: Uuid },
     /// Enter sketch editing mode
     EditSketch { sketch_id: Uuid },
     /// Exit sketch editing mode
@@ -239,8 +723,49 @@ pub enum SketchAction {
     SetTool { tool: SketchTool },
     /// Add an entity to the sketch
     AddEntity { entity: SketchEntity },
+    /// Place the next point of the line tool, as a tangent arc if `tangent_arc`
+    ///
+    /// `point` is an existing point the cursor snapped to, reused so the
+    /// line connects to it instead of ending on a new point.
+    LineToolClick {
+        position: Vec2,
+        point: Option<Uuid>,
+        tangent_arc: bool,
+    },
+    /// Place the next control point of the spline being drawn
+    SplineToolClick { position: Vec2 },
+    /// Create the spline being drawn, optionally closing it
+    FinishSpline { closed: bool },
+    /// Place the center, major axis end or minor radius of the ellipse tool
+    EllipseToolClick { position: Vec2 },
+    /// Discard the spline or ellipse being drawn and the points placed for it
+    CancelDrawing,
+    /// Select an entity, or clear the selection with `None`
+    ///
+    /// With `toggle`, the entity is added to or removed from the selection
+    /// instead of replacing it.
+    SelectEntity { entity: Option<Uuid>, toggle: bool },
+    /// Select the entities inside a lasso, adding them to the selection with
+    /// `extend`
+    SelectEntities { entities: Vec<Uuid>, extend: bool },
+    /// Open the dimension dialog for a circle or arc, pre-filled with its radius
+    OpenDimensionDialog { entity: Uuid },
+    /// Add the dimension from the dialog and close it
+    ConfirmDimension,
+    /// Close the dimension dialog without adding a dimension
+    CancelDimension,
     /// Delete selected entities
     DeleteSelected,
+    /// Toggle the construction flag of the selected entities
+    ToggleConstruction,
+    /// Mirror selected entities across a line, constraining the copies symmetric
+    MirrorSelected { axis: Uuid },
+    /// Offset the selected chain of lines and arcs by a signed distance
+    OffsetSelected { distance: f32 },
+    /// Copy selected entities to the sketch clipboard
+    CopySelected,
+    /// Paste into the active sketch, from `clipboard` or else the sketch clipboard
+    Paste { clipboard: Option<SketchClipboard> },
     /// Add a constraint
     AddConstraint { constraint: SketchConstraint },
     /// Delete a constraint
@@ -249,8 +774,82 @@ pub enum SketchAction {
     SolveSketch,
     /// Toggle grid snapping
     ToggleSnap,
+    /// Toggle whether projected edges become construction geometry
+    ToggleProjectConstruction,
+    /// Project an edge of a body onto the active sketch
+    ProjectEdge { body_id: Uuid, edge_id: Uuid },
     /// Set grid spacing
     SetGridSpacing { spacing: f32 },
+    /// Open the extrude dialog for a sketch
+    OpenExtrudeDialog { sketch_id: Uuid },
+    /// Recompute the extrude preview after a dialog change
+    UpdateExtrudePreview,
+    /// Create the extrude feature and close the dialog
+    ConfirmExtrude,
+    /// Close the extrude dialog without creating a feature
+    CancelExtrude,
+    /// Open the revolve dialog for a sketch
+    OpenRevolveDialog { sketch_id: Uuid },
+    /// Recompute the revolve preview after a dialog change
+    UpdateRevolvePreview,
+    /// Create the revolve feature and close the dialog
+    ConfirmRevolve,
+    /// Close the revolve dialog without creating a feature
+    CancelRevolve,
+    /// Open the shell dialog, optionally for a specific body
+    OpenShellDialog { body_id: Option<Uuid> },
+    /// Refresh the face list after the shell dialog's body changed
+    UpdateShellFaces,
+    /// Open the hole dialog, optionally for a specific body
+    OpenHoleDialog { body_id: Option<Uuid> },
+    /// Place the hole dialog's hole at a point on a face of its body
+    PickHolePosition { face_id: Uuid, position: Vec3 },
+    /// Create the hole feature and close the dialog
+    ConfirmHole,
+    /// Close the hole dialog without creating a feature
+    CancelHole,
+    /// Mirror a body across a world plane through the origin, joining the copy
+    MirrorBody { body_id: Uuid, plane_normal: Vec3 },
+    /// Toggle whether a face is left open by the shell dialog
+    ToggleShellFace { face_id: Uuid },
+    /// Create the shell feature and close the dialog
+    ConfirmShell,
+    /// Close the shell dialog without creating a feature
+    CancelShell,
+    /// Move a feature to a new position in the history
+    MoveFeature { feature_id: Uuid, new_index: usize },
+    /// Rename a feature in the history
+    RenameFeature { feature_id: Uuid, name: String },
+    /// Rename a body produced by a feature
+    RenameBody { body_id: Uuid, name: String },
+    /// Override a body's tessellation tolerance, or use the global one if `None`
+    SetBodyTolerance {
+        body_id: Uuid,
+        tolerance: Option<f32>,
+    },
+    /// Set a body's display color, or use its palette color if `None`
+    SetBodyColor {
+        body_id: Uuid,
+        color: Option<[f32; 4]>,
+    },
+    /// Add a reference axis through `origin` along `direction`
+    AddRefAxis { origin: Vec3, direction: Vec3 },
+    /// Add a reference point at `position`
+    AddRefPoint { position: Vec3 },
+    /// Replace a reference axis with an edited copy
+    UpdateRefAxis { axis: RefAxis },
+    /// Replace a reference point with an edited copy
+    UpdateRefPoint { point: RefPoint },
+    /// Delete a reference axis or point
+    DeleteReference { id: Uuid },
+    /// Project a reference axis or point into the active sketch
+    ProjectReference { id: Uuid },
+    /// Move the rollback marker so only the first `index` features are built
+    SetRollback { index: usize },
+    /// Roll back to just before a feature and open its dialog for editing
+    EditFeature { feature_id: Uuid },
+    /// Dismiss the last history edit error
+    ClearError,
 }
 
 /// Extended CAD state for the application
@@ -260,6 +859,32 @@ pub struct CadState {
     pub data: CadData,
     /// Current editor mode
     pub editor_mode: EditorMode,
+    /// Open extrude dialog, if any
+    pub extrude_dialog: Option<ExtrudeDialogState>,
+    /// Open revolve dialog, if any
+    pub revolve_dialog: Option<RevolveDialogState>,
+    /// Open shell dialog, if any
+    pub shell_dialog: Option<ShellDialogState>,
+    /// Open hole dialog, if any
+    pub hole_dialog: Option<HoleDialogState>,
+    /// Open sketch dimension dialog, if any
+    pub dimension_dialog: Option<DimensionDialogState>,
+    /// Error from the last rejected history edit, shown in the feature tree
+    pub last_error: Option<String>,
+    /// Entities copied from a sketch
+    pub sketch_clipboard: Option<SketchClipboard>,
+    /// Pastes since the last copy, so repeated pastes do not stack
+    pub paste_count: u32,
+    /// Bodies changed since they were last sent to the viewport
+    pub bodies_dirty: bool,
+    /// Bodies whose geometry changed in rebuilds since the last sync
+    pub changed_bodies: HashSet<Uuid>,
+    /// Bodies currently shown in the viewport
+    pub rendered_bodies: Vec<Uuid>,
+    /// Waiting for a face click to start a new sketch on
+    pub face_sketch_pick: bool,
+    /// Rebuild failures not yet shown to the user
+    pub unreported_failures: Vec<String>,
 }
 
 impl CadState {
@@ -306,4 +931,113 @@ impl CadState {
     pub fn is_sketch_mode(&self) -> bool {
         self.editor_mode.is_sketch()
     }
+
+    /// Solve the active sketch once it has gone unchanged for
+    /// [`AUTO_SOLVE_DELAY`] seconds
+    ///
+    /// `now` is the current time in seconds. Returns the number of seconds
+    /// until the pending solve is due, or `None` if nothing is pending.
+    pub fn auto_solve(&mut self, now: f64) -> Option<f64> {
+        let EditorMode::Sketch(mode) = &mut self.editor_mode else {
+            return None;
+        };
+        let sketch = self.data.history.get_sketch_mut(mode.active_sketch)?;
+        let revision = sketch.revision();
+        if mode.solved_revision == Some(revision) {
+            return None;
+        }
+
+        match mode.pending_solve {
+            Some((pending, since)) if pending == revision => {
+                let due = since + AUTO_SOLVE_DELAY;
+                if now < due {
+                    return Some(due - now);
+                }
+                sketch.solve();
+                mode.solved_revision = Some(sketch.revision());
+                mode.pending_solve = None;
+                None
+            }
+            _ => {
+                mode.pending_solve = Some((revision, now));
+                Some(AUTO_SOLVE_DELAY)
+            }
+        }
+    }
+
+    /// Get the body whose faces the open dialog selects in the viewport
+    pub fn element_pick_body(&self) -> Option<Uuid> {
+        self.shell_dialog
+            .as_ref()
+            .and_then(|dialog| dialog.body_id)
+            .or_else(|| self.hole_dialog.as_ref().and_then(|dialog| dialog.body_id))
+    }
+
+    /// Get the bodies whose faces and edges can be picked in the viewport
+    ///
+    /// While projecting edges into a sketch or picking a face to sketch on,
+    /// every displayed body can be picked; otherwise only the open dialog's
+    /// body.
+    pub fn element_pick_bodies(&self) -> Vec<Uuid> {
+        let projecting = self
+            .editor_mode
+            .sketch()
+            .is_some_and(|mode| mode.current_tool == SketchTool::Project);
+        if projecting || self.face_sketch_pick {
+            self.rendered_bodies.clone()
+        } else {
+            self.element_pick_body().into_iter().collect()
+        }
+    }
+
+    /// Tessellate bodies for face and edge picking
+    pub fn pickable_bodies(
+        &mut self,
+        body_ids: &[Uuid],
+        default_tolerance: f32,
+    ) -> Vec<PickableBodyData> {
+        let kernel = default_kernel();
+        body_ids
+            .iter()
+            .filter_map(|&id| {
+                let body = self.data.history.get_body_mut(id)?;
+                let mesh = body.get_mesh(kernel.as_ref(), default_tolerance)?;
+                Some(PickableBodyData {
+                    id,
+                    mesh: mesh.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Rebuild all bodies from the feature history
+    pub fn rebuild(&mut self) {
+        let kernel = default_kernel();
+        if !kernel.is_available() {
+            tracing::warn!("No CAD kernel available, bodies are not rebuilt");
+            return;
+        }
+        let failing: HashSet<Uuid> = self
+            .data
+            .history
+            .failed_features()
+            .map(|(feature, _)| feature.id())
+            .collect();
+        match self.data.history.rebuild(kernel.as_ref()) {
+            Ok(changes) => self.changed_bodies.extend(changes.all()),
+            Err(e) => {
+                tracing::warn!("Rebuild failed: {}", e);
+                self.unreported_failures
+                    .push(format!("Rebuild failed: {}", e));
+            }
+        }
+        // Only report features that were not already failing
+        for (feature, error) in self.data.history.failed_features() {
+            if !failing.contains(&feature.id()) {
+                self.unreported_failures
+                    .push(format!("{} failed: {}", feature.name(), error));
+            }
+        }
+        self.bodies_dirty = true;
+    }
 }
@@ -1,13 +1,21 @@
 //! Viewport rendering state
 
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use glam::{Mat4, Quat, Vec3};
+use glam::{Mat4, Quat, Vec2, Vec3};
 use parking_lot::Mutex;
 use uuid::Uuid;
 
-use rk_core::Part;
-use rk_renderer::{GizmoAxis, GizmoMode, Renderer, axis::AxisInstance};
+use rk_cad::TessellatedMesh;
+use rk_core::{Bvh, CollisionMesh, GeometryType, Part, Pose};
+use rk_renderer::constants::collision as collision_colors;
+use rk_renderer::{
+    BoundingBox, GizmoAxis, GizmoMode, JointAxisInstance, MarkerInstance, Plane, Renderer,
+    SketchRenderData, axis::AxisInstance,
+};
+
+use super::AngleMeasurement;
 
 /// Render texture for viewport
 struct RenderTexture {
@@ -27,6 +35,27 @@ pub enum GizmoTransform {
     Scale(Vec3),
 }
 
+/// Value of the gizmo's target when a drag started
+///
+/// A drag updates its target live; it is committed against this value when
+/// it ends, or restores it when cancelled.
+#[derive(Debug, Clone, Copy)]
+pub enum GizmoDragStart {
+    Part {
+        part_id: Uuid,
+        transform: Mat4,
+    },
+    Collision {
+        link_id: Uuid,
+        index: usize,
+        origin: Pose,
+    },
+    Joint {
+        joint_id: Uuid,
+        origin: Pose,
+    },
+}
+
 /// Gizmo interaction state
 #[derive(Default)]
 pub struct GizmoInteraction {
@@ -38,10 +67,16 @@ pub struct GizmoInteraction {
     pub part_id: Option<Uuid>,
     /// Collision being edited: (link_id, collision_index)
     pub editing_collision: Option<(Uuid, usize)>,
-    /// Link world transform for collision editing
+    /// Joint whose origin is being edited
+    pub editing_joint: Option<Uuid>,
+    /// Link world transform for collision editing, or the parent link's for joint editing
     pub link_world_transform: Mat4,
     pub gizmo_position: Vec3,
     pub gizmo_scale: f32,
+    /// Translation not yet applied to the part while grid snapping is active
+    pub snap_residual: Vec3,
+    /// Target value captured when the current drag started
+    pub drag_start: Option<GizmoDragStart>,
 }
 
 /// Viewport rendering state
@@ -51,6 +86,48 @@ pub struct ViewportState {
     pub queue: Arc<wgpu::Queue>,
     render_texture: Option<RenderTexture>,
     pub gizmo: GizmoInteraction,
+    /// Triangle hierarchies of part meshes for picking
+    pub pick_cache: PickCache,
+    /// Camera (view, projection) the texture was last rendered with
+    rendered_camera: Option<(Mat4, Mat4)>,
+    overlays: OverlayCache,
+    /// Angle measurement between picked faces and edges
+    pub angle_measure: AngleMeasurement,
+    /// Whether parts are drawn spread apart from where they are
+    exploded: bool,
+}
+
+/// Collision preview: world transform, geometry and whether it is selected
+type CollisionShape = (Mat4, GeometryType, bool);
+
+/// Imported collision mesh preview: world transform, mesh ID and whether it
+/// is selected
+type CollisionMeshShape = (Mat4, Uuid, bool);
+
+/// Overlay data last sent to the renderer
+///
+/// Overlays are refreshed every frame; passing unchanged data on would mark
+/// the renderer dirty and redraw the viewport for nothing.
+#[derive(Default)]
+struct OverlayCache {
+    axes: Option<Vec<AxisInstance>>,
+    markers: Option<Vec<MarkerInstance>>,
+    selected_markers: Option<Vec<MarkerInstance>>,
+    joint_axes: Option<Vec<JointAxisInstance>>,
+    sketches: Option<Vec<SketchRenderData>>,
+    /// Collision shapes and meshes, and the camera position they were sorted for
+    collision_shapes: Option<(Vec<CollisionShape>, Vec<CollisionMeshShape>, Vec3)>,
+    /// Gizmo (position, scale, rotation), `None` while hidden
+    gizmo: Option<Option<(Vec3, f32, Quat)>>,
+}
+
+/// Store `value` in `slot`, returning whether it differs from the last one
+fn replace_changed<T: PartialEq>(slot: &mut Option<T>, value: T) -> bool {
+    if slot.as_ref() == Some(&value) {
+        return false;
+    }
+    *slot = Some(value);
+    true
 }
 
 impl ViewportState {
@@ -67,6 +144,11 @@ impl ViewportState {
             queue,
             render_texture: None,
             gizmo: GizmoInteraction::default(),
+            pick_cache: PickCache::default(),
+            rendered_camera: None,
+            overlays: OverlayCache::default(),
+            angle_measure: AngleMeasurement::default(),
+            exploded: false,
         }
     }
 
@@ -132,6 +214,25 @@ impl ViewportState {
         self.render_texture.as_ref().unwrap().egui_texture_id
     }
 
+    /// Whether the texture is out of date
+    ///
+    /// Every renderer change marks its scene dirty; camera moves are
+    /// detected by comparing against the camera of the last render.
+    pub fn needs_render(&self) -> bool {
+        self.renderer.scene().is_dirty() || self.rendered_camera != Some(self.camera_matrices())
+    }
+
+    /// Whether the camera moved since the last rendered frame
+    pub fn camera_moved(&self) -> bool {
+        self.rendered_camera
+            .is_some_and(|rendered| rendered != self.camera_matrices())
+    }
+
+    fn camera_matrices(&self) -> (Mat4, Mat4) {
+        let camera = self.renderer.camera();
+        (camera.view_matrix(), camera.projection_matrix())
+    }
+
     /// Render the 3D scene to the texture
     pub fn render(&mut self) {
         let Some(ref rt) = self.render_texture else {
@@ -144,16 +245,123 @@ impl ViewportState {
                 label: Some("Viewport Render Encoder"),
             });
 
+        self.renderer.fit_clip_planes();
+        self.renderer.prepare_edges(&self.device);
         self.renderer.render(&mut encoder, &rt.view, &self.queue);
 
         self.queue.submit(std::iter::once(encoder.finish()));
+
+        self.rendered_camera = Some(self.camera_matrices());
+        self.renderer.scene_mut().mark_clean();
+    }
+
+    /// Render the scene offscreen at the viewport size and read it back as RGBA8
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn capture_frame(&mut self) -> Option<(u32, u32, Vec<u8>)> {
+        let (width, height) = self
+            .render_texture
+            .as_ref()
+            .map(|rt| (rt.width, rt.height))?;
+        let format = self.renderer.format();
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Capture Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Rows in the readback buffer must be padded to the copy alignment
+        let row_bytes = width * 4;
+        let padded_row_bytes = row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Capture Readback Buffer"),
+            size: (padded_row_bytes * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Capture Encoder"),
+            });
+        self.renderer.prepare_edges(&self.device);
+        self.renderer.render(&mut encoder, &view, &self.queue);
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: Some(height),
+                },
+            },
+            size,
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::PollType::wait_indefinitely()).ok()?;
+        receiver.recv().ok()?.ok()?;
+
+        let bgra = matches!(
+            format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+        let mut pixels = Vec::with_capacity((row_bytes * height) as usize);
+        for row in slice.get_mapped_range().chunks(padded_row_bytes as usize) {
+            for pixel in row[..row_bytes as usize].chunks_exact(4) {
+                if bgra {
+                    pixels.extend_from_slice(&[pixel[2], pixel[1], pixel[0], 255]);
+                } else {
+                    pixels.extend_from_slice(&[pixel[0], pixel[1], pixel[2], 255]);
+                }
+            }
+        }
+        buffer.unmap();
+
+        Some((width, height, pixels))
     }
 
     /// Add a part to the viewport
     pub fn add_part(&mut self, part: &Part) -> Uuid {
+        self.pick_cache.invalidate(part.id);
         self.renderer.add_part(&self.device, part)
     }
 
+    /// Add a copy of a part, sharing the source part's GPU mesh when possible
+    pub fn add_part_instance(&mut self, source_id: Uuid, part: &Part) -> Uuid {
+        match self
+            .renderer
+            .add_part_instance(&self.device, source_id, part)
+        {
+            Some(id) => id,
+            None => self.renderer.add_part(&self.device, part),
+        }
+    }
+
     /// Update a part's transform
     pub fn update_part_transform(&mut self, part_id: Uuid, transform: Mat4) {
         self.renderer
@@ -165,18 +373,85 @@ impl ViewportState {
         self.renderer.update_part_color(&self.queue, part_id, color);
     }
 
-    /// Set selected part
+    /// Update a part's PBR material factors
+    pub fn update_part_material(&mut self, part_id: Uuid, metallic: f32, roughness: f32) {
+        self.renderer
+            .update_part_material(&self.queue, part_id, metallic, roughness);
+    }
+
+    /// Show or hide a part
+    pub fn set_part_visible(&mut self, part_id: Uuid, visible: bool) {
+        self.renderer.set_part_visible(part_id, visible);
+    }
+
+    /// Draw parts spread apart for an exploded view
+    ///
+    /// Each visible part moves away from the center of all visible parts by
+    /// `factor` times its distance from it; 0 draws the assembly as is.
+    pub fn set_explode(&mut self, factor: f32, parts: &HashMap<Uuid, Part>) {
+        self.exploded = factor > 0.0;
+        let centers: Vec<(Uuid, Vec3)> = parts
+            .values()
+            .filter(|part| factor > 0.0 && part.visible)
+            .map(|part| {
+                let (min, max) = part.world_bounds();
+                (part.id, (min + max) / 2.0)
+            })
+            .collect();
+        let (min, max) = parts
+            .values()
+            .filter(|part| part.visible)
+            .map(|part| part.world_bounds())
+            .fold(
+                (Vec3::INFINITY, Vec3::NEG_INFINITY),
+                |(lo, hi), (min, max)| (lo.min(min), hi.max(max)),
+            );
+        let center = (min + max) / 2.0;
+
+        let offsets: HashMap<Uuid, Vec3> = centers
+            .into_iter()
+            .map(|(id, part_center)| (id, (part_center - center) * factor))
+            .collect();
+        self.renderer.set_explode_offsets(&self.queue, &offsets);
+    }
+
+    /// Whether parts are drawn exploded, away from their picking geometry
+    pub fn is_exploded(&self) -> bool {
+        self.exploded
+    }
+
+    /// Highlight colliding parts (all others are cleared)
+    pub fn set_colliding_parts(&mut self, part_ids: &HashSet<Uuid>) {
+        self.renderer.set_colliding_parts(&self.queue, part_ids);
+    }
+
+    /// Set selected part, clearing any multi-selection highlight
     pub fn set_selected_part(&mut self, part_id: Option<Uuid>) {
-        self.renderer.set_selected_part(&self.queue, part_id);
+        let selected: HashSet<Uuid> = part_id.into_iter().collect();
+        self.renderer
+            .set_selected_parts(&self.queue, part_id, &selected);
+    }
+
+    /// Highlight the part under the cursor
+    pub fn set_hovered_part(&mut self, part_id: Option<Uuid>) {
+        self.renderer.set_hovered_part(&self.queue, part_id);
+    }
+
+    /// Set the multi-selection highlight, with `primary` as the active part
+    pub fn set_selected_parts(&mut self, primary: Option<Uuid>, part_ids: &HashSet<Uuid>) {
+        self.renderer
+            .set_selected_parts(&self.queue, primary, part_ids);
     }
 
     /// Remove a part
     pub fn remove_part(&mut self, part_id: Uuid) {
+        self.pick_cache.invalidate(part_id);
         self.renderer.remove_part(part_id);
     }
 
     /// Clear all parts
     pub fn clear_parts(&mut self) {
+        self.pick_cache.clear();
         self.renderer.clear_parts();
     }
 
@@ -187,15 +462,114 @@ impl ViewportState {
             scale: 0.3,
             _pad: [0.0; 3],
         };
-        self.renderer.update_axes(&self.queue, &[instance]);
+        self.update_axes(&[instance]);
+    }
+
+    /// Replace the displayed axes
+    pub fn update_axes(&mut self, axes: &[AxisInstance]) {
+        if replace_changed(&mut self.overlays.axes, axes.to_vec()) {
+            self.renderer.update_axes(&self.queue, axes);
+        }
     }
 
     /// Clear axes and markers
     pub fn clear_overlays(&mut self) {
-        self.renderer.update_axes(&self.queue, &[]);
-        self.renderer.update_markers(&self.queue, &[]);
-        self.renderer.update_selected_markers(&self.queue, &[]);
-        self.renderer.hide_gizmo();
+        self.update_axes(&[]);
+        if replace_changed(&mut self.overlays.markers, Vec::new()) {
+            self.renderer.update_markers(&self.queue, &[]);
+        }
+        self.hide_gizmo();
+    }
+
+    /// Replace the center of mass markers
+    ///
+    /// They are drawn over the robot, which usually hides the center of mass.
+    pub fn update_center_of_mass(&mut self, markers: &[MarkerInstance]) {
+        if replace_changed(&mut self.overlays.selected_markers, markers.to_vec()) {
+            self.renderer.update_selected_markers(&self.queue, markers);
+        }
+    }
+
+    /// Replace the joint axis indicators
+    pub fn update_joint_axes(&mut self, axes: &[JointAxisInstance]) {
+        if replace_changed(&mut self.overlays.joint_axes, axes.to_vec()) {
+            self.renderer.update_joint_axes(&self.queue, axes);
+        }
+    }
+
+    /// Replace the displayed sketches
+    pub fn update_sketches(&mut self, sketches: Vec<SketchRenderData>) {
+        if replace_changed(&mut self.overlays.sketches, sketches.clone()) {
+            self.renderer.set_sketches(&self.device, sketches);
+        }
+    }
+
+    /// Show the gizmo at a position, turned to `rotation` for local space
+    pub fn show_gizmo(&mut self, position: Vec3, scale: f32, rotation: Quat) {
+        if replace_changed(&mut self.overlays.gizmo, Some((position, scale, rotation))) {
+            self.renderer
+                .set_gizmo_object_rotation(&self.queue, rotation);
+            self.renderer.show_gizmo(&self.queue, position, scale);
+        }
+    }
+
+    /// Rebuild collision shape previews
+    ///
+    /// Each entry is a collision's world transform, its geometry and whether it is selected.
+    /// Imported collision meshes are passed in `meshes`; mesh collisions that
+    /// reuse the visual mesh are not previewed.
+    pub fn update_collision_shapes(
+        &mut self,
+        shapes: &[(Mat4, GeometryType, bool)],
+        meshes: &[(Mat4, &CollisionMesh, bool)],
+    ) {
+        let camera_position = self.renderer.camera().position;
+        let mesh_shapes: Vec<CollisionMeshShape> = meshes
+            .iter()
+            .map(|(transform, mesh, selected)| (*transform, mesh.id, *selected))
+            .collect();
+        if !replace_changed(
+            &mut self.overlays.collision_shapes,
+            (shapes.to_vec(), mesh_shapes, camera_position),
+        ) {
+            return;
+        }
+        let collisions = self.renderer.collision_renderer_mut();
+        collisions.clear();
+        // Mesh data never changes under an ID, so each is uploaded once
+        collisions.retain_meshes(|id| meshes.iter().any(|(_, mesh, _)| mesh.id == id));
+        for (transform, mesh, selected) in meshes {
+            if !collisions.has_mesh(mesh.id) {
+                collisions.upload_mesh(&self.device, mesh.id, &mesh.vertices, &mesh.indices);
+            }
+            let color = if *selected {
+                collision_colors::SELECTED_COLOR
+            } else {
+                collision_colors::DEFAULT_COLOR
+            };
+            collisions.add_mesh(mesh.id, *transform, color);
+        }
+        for (transform, geometry, selected) in shapes {
+            let color = if *selected {
+                collision_colors::SELECTED_COLOR
+            } else {
+                collision_colors::DEFAULT_COLOR
+            };
+            match geometry {
+                GeometryType::Box { size } => collisions.add_box(*transform, *size, color),
+                GeometryType::Sphere { radius } => {
+                    collisions.add_sphere(*transform, *radius, color)
+                }
+                GeometryType::Cylinder { radius, length } => {
+                    collisions.add_cylinder(*transform, *radius, *length, color)
+                }
+                GeometryType::Capsule { radius, length } => {
+                    collisions.add_capsule(*transform, *radius, *length, color)
+                }
+                GeometryType::Mesh { .. } => {}
+            }
+        }
+        collisions.upload(&self.queue, camera_position);
     }
 
     /// Show gizmo for a part
@@ -219,19 +593,21 @@ impl ViewportState {
         self.gizmo.gizmo_position = world_center;
         self.gizmo.gizmo_scale = scale;
         self.gizmo.part_id = Some(part.id);
+        self.gizmo.editing_joint = None;
         self.gizmo.part_start_transform = part.origin_transform;
 
         // Set object rotation for local coordinate space
-        self.renderer
-            .set_gizmo_object_rotation(&self.queue, rotation);
-        self.renderer.show_gizmo(&self.queue, world_center, scale);
+        self.show_gizmo(world_center, scale, rotation);
     }
 
     /// Hide gizmo
     pub fn hide_gizmo(&mut self) {
-        self.renderer.hide_gizmo();
+        if replace_changed(&mut self.overlays.gizmo, None) {
+            self.renderer.hide_gizmo();
+        }
         self.gizmo.part_id = None;
         self.gizmo.editing_collision = None;
+        self.gizmo.editing_joint = None;
     }
 
     /// Show gizmo for a collision element
@@ -262,13 +638,41 @@ impl ViewportState {
         self.gizmo.gizmo_scale = scale;
         self.gizmo.part_id = None;
         self.gizmo.editing_collision = Some((link_id, collision_index));
+        self.gizmo.editing_joint = None;
         self.gizmo.link_world_transform = link_world_transform;
         self.gizmo.part_start_transform = collision_origin;
 
         // Set object rotation for local coordinate space
-        self.renderer
-            .set_gizmo_object_rotation(&self.queue, rotation);
-        self.renderer.show_gizmo(&self.queue, translation, scale);
+        self.show_gizmo(translation, scale, rotation);
+    }
+
+    /// Show gizmo at a joint origin
+    ///
+    /// # Arguments
+    /// * `joint_id` - The joint whose origin is edited
+    /// * `parent_world_transform` - World transform of the joint's parent link
+    /// * `joint_origin` - Joint origin relative to the parent link (from Pose)
+    pub fn show_gizmo_for_joint(
+        &mut self,
+        joint_id: Uuid,
+        parent_world_transform: Mat4,
+        joint_origin: Mat4,
+    ) {
+        let world_transform = parent_world_transform * joint_origin;
+        let (_, rotation, translation) = world_transform.to_scale_rotation_translation();
+
+        // Use fixed scale - shader handles distance-based scaling
+        let scale = 1.0;
+
+        self.gizmo.gizmo_position = translation;
+        self.gizmo.gizmo_scale = scale;
+        self.gizmo.part_id = None;
+        self.gizmo.editing_collision = None;
+        self.gizmo.editing_joint = Some(joint_id);
+        self.gizmo.link_world_transform = parent_world_transform;
+        self.gizmo.part_start_transform = joint_origin;
+
+        self.show_gizmo(translation, scale, rotation);
     }
 
     /// Check if currently editing a collision element
@@ -284,7 +688,10 @@ impl ViewportState {
         width: f32,
         height: f32,
     ) -> GizmoAxis {
-        if self.gizmo.part_id.is_none() && self.gizmo.editing_collision.is_none() {
+        if self.gizmo.part_id.is_none()
+            && self.gizmo.editing_collision.is_none()
+            && self.gizmo.editing_joint.is_none()
+        {
             return GizmoAxis::None;
         }
 
@@ -319,6 +726,7 @@ impl ViewportState {
             .screen_to_ray(screen_x, screen_y, width, height);
 
         let mode = self.renderer.gizmo_mode();
+        self.gizmo.snap_residual = Vec3::ZERO;
 
         match mode {
             GizmoMode::Translate | GizmoMode::Scale => {
@@ -408,11 +816,8 @@ impl ViewportState {
             self.gizmo.drag_start_pos = current_point;
 
             // Update gizmo visual
-            self.renderer.show_gizmo(
-                &self.queue,
-                self.gizmo.gizmo_position,
-                self.gizmo.gizmo_scale,
-            );
+            let rotation = self.renderer.gizmo_object_rotation();
+            self.show_gizmo(self.gizmo.gizmo_position, self.gizmo.gizmo_scale, rotation);
 
             return Some(GizmoTransform::Translation(projected_delta));
         }
@@ -582,7 +987,8 @@ fn ray_plane_intersection(
 }
 
 /// Ray-AABB (Axis-Aligned Bounding Box) intersection test
-/// Returns the distance to intersection if hit, None otherwise
+/// Returns the distance at which the ray enters the box (0 if it starts
+/// inside), or None if it misses
 fn ray_aabb_intersection(
     ray_origin: Vec3,
     ray_dir: Vec3,
@@ -605,7 +1011,7 @@ fn ray_aabb_intersection(
         return None;
     }
 
-    Some(if tmin < 0.0 { tmax } else { tmin })
+    Some(tmin.max(0.0))
 }
 
 /// Ray-triangle intersection using Möller–Trumbore algorithm
@@ -624,7 +1030,8 @@ fn ray_triangle_intersection(
     let h = ray_dir.cross(edge2);
     let a = edge1.dot(h);
 
-    if a.abs() < EPSILON {
+    // Relative to the edge lengths, so small triangles still count
+    if a.abs() <= EPSILON * edge1.length() * edge2.length() * ray_dir.length() {
         return None; // Ray is parallel to triangle
     }
 
@@ -649,17 +1056,133 @@ fn ray_triangle_intersection(
 }
 
 /// Data needed for picking a single part
-pub struct PickablePartData {
+pub struct PickablePartData<'a> {
     pub id: Uuid,
-    pub vertices: Vec<[f32; 3]>,
-    pub indices: Vec<u32>,
+    pub vertices: &'a [[f32; 3]],
+    pub indices: &'a [u32],
     pub transform: Mat4,
-    pub bbox_min: [f32; 3],
-    pub bbox_max: [f32; 3],
+    /// World-space bounding box as (min, max)
+    pub world_bounds: (Vec3, Vec3),
+    /// Hidden parts are skipped by picking
+    pub visible: bool,
+    /// Hierarchy over the mesh triangles in part space; without one every
+    /// triangle is tested
+    pub bvh: Option<Arc<Bvh>>,
+}
+
+impl<'a> PickablePartData<'a> {
+    /// Borrow the picking data of a part
+    pub fn from_part(part: &'a Part) -> Self {
+        Self {
+            id: part.id,
+            vertices: &part.vertices,
+            indices: &part.indices,
+            transform: part.origin_transform,
+            world_bounds: part.world_bounds(),
+            visible: part.visible,
+            bvh: None,
+        }
+    }
+}
+
+/// Triangle hierarchies of part meshes, built on first pick
+///
+/// Entries are dropped when a part's mesh is uploaded again or removed, so
+/// the next pick rebuilds them from the new geometry.
+#[derive(Default)]
+pub struct PickCache {
+    bvhs: HashMap<Uuid, Arc<Bvh>>,
+}
+
+impl PickCache {
+    /// Picking data for `parts`, building missing hierarchies
+    pub fn pickable_parts<'a>(
+        &mut self,
+        parts: impl IntoIterator<Item = &'a Part>,
+    ) -> Vec<PickablePartData<'a>> {
+        let bvhs = &mut self.bvhs;
+        parts
+            .into_iter()
+            .map(|part| {
+                let triangles = part.indices.len() / 3;
+                let bvh = match bvhs.get(&part.id) {
+                    // Geometry replaced without a re-upload; rebuild
+                    Some(bvh) if bvh.len() == triangles => bvh.clone(),
+                    _ => {
+                        let bvh = Arc::new(Bvh::from_triangles(&part.vertices, &part.indices));
+                        bvhs.insert(part.id, bvh.clone());
+                        bvh
+                    }
+                };
+                PickablePartData {
+                    bvh: Some(bvh),
+                    ..PickablePartData::from_part(part)
+                }
+            })
+            .collect()
+    }
+
+    /// Drop the hierarchy of a part
+    pub fn invalidate(&mut self, part_id: Uuid) {
+        self.bvhs.remove(&part_id);
+    }
+
+    /// Drop all hierarchies
+    pub fn clear(&mut self) {
+        self.bvhs.clear();
+    }
+}
+
+/// Find the visible parts whose projected bounding box overlaps a screen rectangle
+///
+/// Each part's world bounding box corners are projected with the camera and
+/// the screen-space box around them is tested against `rect_min`..`rect_max`.
+/// Corners behind the camera are ignored; parts entirely behind it are never
+/// selected.
+pub fn parts_in_rect<'a>(
+    camera: &rk_renderer::Camera,
+    rect_min: Vec2,
+    rect_max: Vec2,
+    width: f32,
+    height: f32,
+    parts: impl IntoIterator<Item = &'a Part>,
+) -> Vec<Uuid> {
+    parts
+        .into_iter()
+        .filter(|part| part.visible)
+        .filter(|part| {
+            let (min, max) = part.world_bounds();
+            let mut projected = BoundingBox::new(min, max)
+                .corners()
+                .into_iter()
+                .filter_map(|corner| camera.world_to_screen(corner, width, height))
+                .peekable();
+            if projected.peek().is_none() {
+                return false;
+            }
+            let (screen_min, screen_max) = projected.fold(
+                (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+                |(lo, hi), p| (lo.min(p), hi.max(p)),
+            );
+            screen_min.cmple(rect_max).all() && screen_max.cmpge(rect_min).all()
+        })
+        .map(|part| part.id)
+        .collect()
+}
+
+/// Whether a point is drawn with the section plane, which cuts away
+/// everything on its positive side
+fn kept_by_section(section: Option<Plane>, point: Vec3) -> bool {
+    section.is_none_or(|plane| plane.distance_to_point(point) <= 0.0)
 }
 
 /// Pick the closest object at the given screen position
 /// Returns the UUID of the hit object and the distance, if any
+///
+/// Bounding boxes are tested first; triangles are then tested nearest box
+/// first, stopping once a box starts beyond the closest hit. This keeps the
+/// test cheap enough to run on every hover update. Hits cut away by the
+/// `section` plane are skipped.
 pub fn pick_object(
     camera: &rk_renderer::Camera,
     screen_x: f32,
@@ -667,64 +1190,346 @@ pub fn pick_object(
     width: f32,
     height: f32,
     parts: &[PickablePartData],
+    section: Option<Plane>,
 ) -> Option<(Uuid, f32)> {
     let (ray_origin, ray_dir) = camera.screen_to_ray(screen_x, screen_y, width, height);
+    pick_triangle(ray_origin, ray_dir, parts, section).map(|(part, _, t)| (part.id, t))
+}
 
-    let mut closest_hit: Option<(Uuid, f32)> = None;
+/// Find the closest triangle hit by a ray, as (part, triangle index, distance)
+fn pick_triangle<'p, 'a>(
+    ray_origin: Vec3,
+    ray_dir: Vec3,
+    parts: &'p [PickablePartData<'a>],
+    section: Option<Plane>,
+) -> Option<(&'p PickablePartData<'a>, usize, f32)> {
+    let mut candidates: Vec<(f32, &PickablePartData)> = parts
+        .iter()
+        .filter(|p| p.visible)
+        .filter_map(|part| {
+            let (world_min, world_max) = part.world_bounds;
+            ray_aabb_intersection(ray_origin, ray_dir, world_min, world_max).map(|t| (t, part))
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut closest_hit: Option<(&PickablePartData, usize, f32)> = None;
+    for (entry, part) in candidates {
+        // No triangle in this box can be closer than where the ray enters it
+        if closest_hit.is_some_and(|(_, _, t)| entry > t) {
+            break;
+        }
+
+        if let Some(bvh) = &part.bvh {
+            if let Some((triangle, t)) = pick_part_bvh(ray_origin, ray_dir, part, bvh, section)
+                && closest_hit.is_none_or(|(_, _, current_t)| t < current_t)
+            {
+                closest_hit = Some((part, triangle, t));
+            }
+            continue;
+        }
 
-    for part in parts {
         let transform = part.transform;
+        for (triangle, chunk) in part.indices.chunks_exact(3).enumerate() {
+            let v0 = transform.transform_point3(Vec3::from(part.vertices[chunk[0] as usize]));
+            let v1 = transform.transform_point3(Vec3::from(part.vertices[chunk[1] as usize]));
+            let v2 = transform.transform_point3(Vec3::from(part.vertices[chunk[2] as usize]));
+
+            if let Some(t) = ray_triangle_intersection(ray_origin, ray_dir, v0, v1, v2)
+                && kept_by_section(section, ray_origin + ray_dir * t)
+                && closest_hit.is_none_or(|(_, _, current_t)| t < current_t)
+            {
+                closest_hit = Some((part, triangle, t));
+            }
+        }
+    }
 
-        // Transform bounding box to world space (approximate with corners)
-        let bbox_min = Vec3::from(part.bbox_min);
-        let bbox_max = Vec3::from(part.bbox_max);
-
-        // Transform all 8 corners of the bounding box
-        let corners = [
-            transform.transform_point3(Vec3::new(bbox_min.x, bbox_min.y, bbox_min.z)),
-            transform.transform_point3(Vec3::new(bbox_max.x, bbox_min.y, bbox_min.z)),
-            transform.transform_point3(Vec3::new(bbox_min.x, bbox_max.y, bbox_min.z)),
-            transform.transform_point3(Vec3::new(bbox_max.x, bbox_max.y, bbox_min.z)),
-            transform.transform_point3(Vec3::new(bbox_min.x, bbox_min.y, bbox_max.z)),
-            transform.transform_point3(Vec3::new(bbox_max.x, bbox_min.y, bbox_max.z)),
-            transform.transform_point3(Vec3::new(bbox_min.x, bbox_max.y, bbox_max.z)),
-            transform.transform_point3(Vec3::new(bbox_max.x, bbox_max.y, bbox_max.z)),
-        ];
-
-        // Compute world-space AABB from transformed corners
-        let mut world_min = corners[0];
-        let mut world_max = corners[0];
-        for corner in &corners[1..] {
-            world_min = world_min.min(*corner);
-            world_max = world_max.max(*corner);
-        }
-
-        // First check AABB for early rejection
-        if ray_aabb_intersection(ray_origin, ray_dir, world_min, world_max).is_none() {
+    closest_hit
+}
+
+/// Find the closest triangle of a part hit by a ray using its hierarchy
+///
+/// The ray is moved into part space instead of transforming every vertex;
+/// as the direction is not renormalized, the returned distance is still
+/// measured along the world ray.
+fn pick_part_bvh(
+    ray_origin: Vec3,
+    ray_dir: Vec3,
+    part: &PickablePartData,
+    bvh: &Bvh,
+    section: Option<Plane>,
+) -> Option<(usize, f32)> {
+    // A flattened part has no area to hit. The determinant is compared to
+    // the axis lengths so that small but uniform scales still count.
+    let axis_lengths = [
+        part.transform.x_axis,
+        part.transform.y_axis,
+        part.transform.z_axis,
+    ]
+    .map(|axis| axis.truncate().length());
+    if part.transform.determinant().abs()
+        <= f32::EPSILON * axis_lengths.into_iter().product::<f32>()
+    {
+        return None;
+    }
+    let inverse = part.transform.inverse();
+    let origin = inverse.transform_point3(ray_origin);
+    let dir = inverse.transform_vector3(ray_dir);
+    bvh.ray_cast(origin, dir, |triangle| {
+        let [v0, v1, v2] =
+            [0, 1, 2].map(|i| Vec3::from(part.vertices[part.indices[triangle * 3 + i] as usize]));
+        ray_triangle_intersection(origin, dir, v0, v1, v2)
+            .filter(|&t| kept_by_section(section, ray_origin + ray_dir * t))
+    })
+}
+
+/// Screen distance in pixels within which a vertex is snapped to
+const VERTEX_SNAP_RADIUS: f32 = 10.0;
+
+/// Feature of a part mesh that a snap point lies on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapKind {
+    Vertex,
+    FaceCenter,
+}
+
+/// Point on a part mesh to snap to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapPoint {
+    pub part_id: Uuid,
+    /// World-space position
+    pub position: Vec3,
+    pub kind: SnapKind,
+}
+
+/// Pick a vertex or face center of the part under the cursor
+///
+/// Corners of the hit triangle within [`VERTEX_SNAP_RADIUS`] pixels of the
+/// cursor win; otherwise the snap point is the center of the planar face
+/// the triangle belongs to.
+pub fn pick_snap_point(
+    camera: &rk_renderer::Camera,
+    screen_x: f32,
+    screen_y: f32,
+    width: f32,
+    height: f32,
+    parts: &[PickablePartData],
+    section: Option<Plane>,
+) -> Option<SnapPoint> {
+    let (ray_origin, ray_dir) = camera.screen_to_ray(screen_x, screen_y, width, height);
+    let (part, triangle, _) = pick_triangle(ray_origin, ray_dir, parts, section)?;
+
+    let cursor = Vec2::new(screen_x, screen_y);
+    let vertex = part.indices[triangle * 3..triangle * 3 + 3]
+        .iter()
+        .map(|&index| {
+            part.transform
+                .transform_point3(Vec3::from(part.vertices[index as usize]))
+        })
+        .filter_map(|position| {
+            let screen = camera.world_to_screen(position, width, height)?;
+            Some((screen.distance(cursor), position))
+        })
+        .filter(|(pixels, _)| *pixels <= VERTEX_SNAP_RADIUS)
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+
+    let (position, kind) = match vertex {
+        Some((_, position)) => (position, SnapKind::Vertex),
+        None => {
+            let center = planar_face_center(part.vertices, part.indices, triangle);
+            (
+                part.transform.transform_point3(center),
+                SnapKind::FaceCenter,
+            )
+        }
+    };
+    Some(SnapPoint {
+        part_id: part.id,
+        position,
+        kind,
+    })
+}
+
+/// Area-weighted center of the planar face containing a triangle
+///
+/// The face is grown from the triangle across neighbors that share a vertex
+/// position and lie in the same plane, so separate coplanar faces stay apart.
+fn planar_face_center(vertices: &[[f32; 3]], indices: &[u32], triangle: usize) -> Vec3 {
+    let corners = |t: usize| [0, 1, 2].map(|i| Vec3::from(vertices[indices[t * 3 + i] as usize]));
+    let [a, b, c] = corners(triangle);
+    let normal = (b - a).cross(c - a).normalize_or_zero();
+    if normal == Vec3::ZERO {
+        return (a + b + c) / 3.0;
+    }
+    let tolerance = 1e-3 * (b - a).length().max((c - a).length()).max((c - b).length());
+
+    // Triangles in the hit plane, keyed by the exact positions of their vertices
+    let count = indices.len() / 3;
+    let mut coplanar = vec![false; count];
+    let mut by_vertex: HashMap<[u32; 3], Vec<usize>> = HashMap::new();
+    for t in 0..count {
+        let [p0, p1, p2] = corners(t);
+        let n = (p1 - p0).cross(p2 - p0).normalize_or_zero();
+        if n.dot(normal) < 1.0 - 1e-4 || (p0 - a).dot(normal).abs() > tolerance {
             continue;
         }
+        coplanar[t] = true;
+        for i in 0..3 {
+            let key = vertices[indices[t * 3 + i] as usize].map(f32::to_bits);
+            by_vertex.entry(key).or_default().push(t);
+        }
+    }
 
-        // Test each triangle
-        for chunk in part.indices.chunks(3) {
-            if chunk.len() != 3 {
-                continue;
+    let mut visited = vec![false; count];
+    let mut stack = vec![triangle];
+    visited[triangle] = true;
+    let (mut weighted, mut area) = (Vec3::ZERO, 0.0);
+    while let Some(t) = stack.pop() {
+        let [p0, p1, p2] = corners(t);
+        let triangle_area = (p1 - p0).cross(p2 - p0).length() * 0.5;
+        weighted += (p0 + p1 + p2) / 3.0 * triangle_area;
+        area += triangle_area;
+
+        for i in 0..3 {
+            let key = vertices[indices[t * 3 + i] as usize].map(f32::to_bits);
+            for &next in by_vertex.get(&key).into_iter().flatten() {
+                if coplanar[next] && !visited[next] {
+                    visited[next] = true;
+                    stack.push(next);
+                }
             }
+        }
+    }
 
-            let v0 = transform.transform_point3(Vec3::from(part.vertices[chunk[0] as usize]));
-            let v1 = transform.transform_point3(Vec3::from(part.vertices[chunk[1] as usize]));
-            let v2 = transform.transform_point3(Vec3::from(part.vertices[chunk[2] as usize]));
+    if area > 0.0 {
+        weighted / area
+    } else {
+        (a + b + c) / 3.0
+    }
+}
+
+/// Screen distance in pixels within which an edge is picked
+const EDGE_PICK_RADIUS: f32 = 6.0;
+
+/// Relative depth slack so edges on the hit surface are not treated as hidden
+const EDGE_DEPTH_TOLERANCE: f32 = 0.01;
+
+/// Part of a CAD body hit by picking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickElement {
+    Face(Uuid),
+    Edge(Uuid),
+}
+
+/// Result of picking a CAD body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickResult {
+    pub body_id: Uuid,
+    /// Face or edge under the cursor, if the kernel tagged the mesh
+    pub element: Option<PickElement>,
+    pub distance: f32,
+}
+
+/// Data needed for picking faces and edges of a CAD body
+pub struct PickableBodyData {
+    pub id: Uuid,
+    /// Tessellation in world space
+    pub mesh: TessellatedMesh,
+}
 
-            if let Some(t) = ray_triangle_intersection(ray_origin, ray_dir, v0, v1, v2) {
-                match closest_hit {
-                    None => closest_hit = Some((part.id, t)),
-                    Some((_, current_t)) if t < current_t => closest_hit = Some((part.id, t)),
-                    _ => {}
+/// Pick the face or edge of a CAD body at the given screen position
+///
+/// Edges within [`EDGE_PICK_RADIUS`] pixels of the cursor take priority over
+/// faces, unless they are hidden behind the surface under the cursor. Faces
+/// and edges cut away by the `section` plane are skipped.
+pub fn pick_body_element(
+    camera: &rk_renderer::Camera,
+    screen_x: f32,
+    screen_y: f32,
+    width: f32,
+    height: f32,
+    bodies: &[PickableBodyData],
+    section: Option<Plane>,
+) -> Option<PickResult> {
+    let (ray_origin, ray_dir) = camera.screen_to_ray(screen_x, screen_y, width, height);
+
+    // Nearest triangle along the ray
+    let mut closest_face: Option<PickResult> = None;
+    for body in bodies {
+        let mesh = &body.mesh;
+        for (triangle, chunk) in mesh.indices.chunks_exact(3).enumerate() {
+            let v0 = Vec3::from(mesh.vertices[chunk[0] as usize]);
+            let v1 = Vec3::from(mesh.vertices[chunk[1] as usize]);
+            let v2 = Vec3::from(mesh.vertices[chunk[2] as usize]);
+
+            if let Some(t) = ray_triangle_intersection(ray_origin, ray_dir, v0, v1, v2)
+                && kept_by_section(section, ray_origin + ray_dir * t)
+                && closest_face.is_none_or(|hit| t < hit.distance)
+            {
+                closest_face = Some(PickResult {
+                    body_id: body.id,
+                    element: mesh.face_of_triangle(triangle).map(PickElement::Face),
+                    distance: t,
+                });
+            }
+        }
+    }
+
+    // Nearest visible edge on screen
+    let cursor = Vec2::new(screen_x, screen_y);
+    let max_depth = closest_face.map_or(f32::INFINITY, |hit| {
+        hit.distance * (1.0 + EDGE_DEPTH_TOLERANCE)
+    });
+    let mut closest_edge: Option<(f32, PickResult)> = None;
+    for body in bodies {
+        for edge in &body.mesh.edges {
+            for segment in edge.points.windows(2) {
+                let start = Vec3::from(segment[0]);
+                let end = Vec3::from(segment[1]);
+                let (Some(screen_start), Some(screen_end)) = (
+                    camera.world_to_screen(start, width, height),
+                    camera.world_to_screen(end, width, height),
+                ) else {
+                    continue;
+                };
+
+                let (pixels, t) = point_segment_distance(cursor, screen_start, screen_end);
+                let point = start.lerp(end, t);
+                let depth = (point - ray_origin).length();
+                if pixels > EDGE_PICK_RADIUS
+                    || depth > max_depth
+                    || !kept_by_section(section, point)
+                {
+                    continue;
+                }
+
+                if closest_edge.is_none_or(|(best, _)| pixels < best) {
+                    closest_edge = Some((
+                        pixels,
+                        PickResult {
+                            body_id: body.id,
+                            element: Some(PickElement::Edge(edge.id)),
+                            distance: depth,
+                        },
+                    ));
                 }
             }
         }
     }
 
-    closest_hit
+    closest_edge.map(|(_, hit)| hit).or(closest_face)
+}
+
+/// Distance from a point to a 2D segment, with the parameter of the closest point
+fn point_segment_distance(point: Vec2, start: Vec2, end: Vec2) -> (f32, f32) {
+    let segment = end - start;
+    let length_sq = segment.length_squared();
+    let t = if length_sq > 0.0 {
+        ((point - start).dot(segment) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (point.distance(start + segment * t), t)
 }
 
 pub type SharedViewportState = Arc<Mutex<ViewportState>>;
@@ -0,0 +1,81 @@
+//! Render a spinning cube offscreen with the embeddable renderer
+//!
+//! Writes one PNG per frame into the directory given as the first argument
+//! (default `spinning_cube`):
+//!
+//! ```text
+//! cargo run -p rk-renderer --example spinning_cube -- out
+//! ```
+
+mod common;
+
+use std::path::PathBuf;
+
+use glam::{Mat4, Vec3};
+use rk_core::Part;
+use rk_renderer::{Renderer, RendererConfig};
+
+use common::{FORMAT, HEIGHT, Offscreen, WIDTH};
+
+const FRAMES: u32 = 36;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let output_dir = std::env::args()
+        .nth(1)
+        .map_or_else(|| PathBuf::from("spinning_cube"), PathBuf::from);
+    std::fs::create_dir_all(&output_dir)?;
+
+    let target = Offscreen::new()?;
+    let mut renderer = Renderer::with_config(
+        &target.device,
+        &target.queue,
+        FORMAT,
+        WIDTH,
+        HEIGHT,
+        &RendererConfig::default(),
+    );
+    let cube = cube_part();
+    let id = renderer.add_part(&target.device, &cube);
+    renderer
+        .camera_mut()
+        .fit_all(Vec3::ZERO, cube.bounding_radius());
+
+    let step = std::f32::consts::TAU / FRAMES as f32;
+    for frame in 0..FRAMES {
+        let angle = step * frame as f32;
+        let transform = Mat4::from_rotation_z(angle) * Mat4::from_rotation_x(angle * 0.5);
+        renderer.update_part_transform(&target.queue, id, transform);
+
+        let path = output_dir.join(format!("frame_{:04}.png", frame));
+        target.render_to_png(&renderer, &path)?;
+    }
+
+    println!("Wrote {} frames to {}", FRAMES, output_dir.display());
+    Ok(())
+}
+
+/// A unit cube centered on the origin, with one normal per triangle
+fn cube_part() -> Part {
+    let mut part = Part::new("Cube");
+    let faces = [
+        (Vec3::X, Vec3::Y, Vec3::Z),
+        (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+        (Vec3::Y, Vec3::Z, Vec3::X),
+        (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+        (Vec3::Z, Vec3::X, Vec3::Y),
+        (Vec3::NEG_Z, Vec3::Y, Vec3::X),
+    ];
+    for (normal, u, v) in faces {
+        let base = part.vertices.len() as u32;
+        for (su, sv) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            part.vertices
+                .push(((normal + u * su + v * sv) * 0.5).to_array());
+        }
+        part.indices
+            .extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+        part.normals.extend([normal.to_array(); 2]);
+    }
+    part.color = [0.2, 0.5, 0.9, 1.0];
+    part.calculate_bounding_box();
+    part
+}
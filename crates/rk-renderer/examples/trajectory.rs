@@ -0,0 +1,66 @@
+//! Draw an end-effector path with the trajectory sub-renderer
+//!
+//! Registers a [`TrajectoryRenderer`] through the custom sub-renderer
+//! extension point, then writes one frame as a PNG to the path given as the
+//! first argument (default `trajectory.png`):
+//!
+//! ```text
+//! cargo run -p rk-renderer --example trajectory -- trajectory.png
+//! ```
+
+mod common;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use glam::Vec3;
+use rk_renderer::{RenderContext, Renderer, RendererConfig, TrajectoryRenderer};
+
+use common::{FORMAT, HEIGHT, Offscreen, WIDTH};
+
+/// Points along a rising helix around the Z axis
+fn helix(turns: f32, radius: f32, height: f32, samples: usize) -> Vec<Vec3> {
+    (0..samples)
+        .map(|i| {
+            let t = i as f32 / (samples - 1) as f32;
+            let angle = t * turns * std::f32::consts::TAU;
+            Vec3::new(radius * angle.cos(), radius * angle.sin(), t * height)
+        })
+        .collect()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .map_or_else(|| PathBuf::from("trajectory.png"), PathBuf::from);
+
+    let target = Offscreen::new()?;
+    let mut renderer = Renderer::with_config(
+        &target.device,
+        &target.queue,
+        FORMAT,
+        WIDTH,
+        HEIGHT,
+        &RendererConfig::default(),
+    );
+    renderer.camera_mut().fit_all(Vec3::new(0.0, 0.0, 0.5), 1.0);
+
+    // The context must share the device and formats the renderer draws with
+    let ctx = RenderContext::new(
+        Arc::new(target.device.clone()),
+        Arc::new(target.queue.clone()),
+        FORMAT,
+        WIDTH,
+        HEIGHT,
+    );
+    let mut trajectory = TrajectoryRenderer::new();
+    trajectory.set_points(helix(3.0, 0.5, 1.0, 48));
+    trajectory.set_point_radius(Some(0.015));
+    renderer.register_sub_renderer(&ctx, Box::new(trajectory));
+
+    renderer.prepare_sub_renderers(&ctx);
+    target.render_to_png(&renderer, &path)?;
+
+    println!("Wrote {}", path.display());
+    Ok(())
+}
@@ -3,6 +3,8 @@
 use bytemuck::{Pod, Zeroable};
 use glam::{Mat4, Vec3};
 
+use crate::constants::camera::{MAX_PITCH_DEGREES, MIN_PITCH_DEGREES, VIEW_TRANSITION_SECONDS};
+
 /// Camera uniform buffer data
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
@@ -15,6 +17,9 @@ pub struct CameraUniform {
     pub proj: [[f32; 4]; 4],
     /// Eye position in world space (w = 1.0).
     pub eye: [f32; 4],
+    /// Section plane (normal, distance); fragments on its positive side are
+    /// discarded. All zeros disables clipping.
+    pub clip_plane: [f32; 4],
 }
 
 impl Default for CameraUniform {
@@ -25,6 +30,7 @@ impl Default for CameraUniform {
             view: identity,
             proj: identity,
             eye: [0.0, 0.0, 0.0, 1.0],
+            clip_plane: [0.0; 4],
         }
     }
 }
@@ -51,6 +57,17 @@ pub struct Camera {
     pub pitch: f32,
     /// Distance from target.
     pub distance: f32,
+    /// Animated change of view direction in progress
+    transition: Option<ViewTransition>,
+}
+
+/// Interpolation between two orbit orientations
+#[derive(Debug, Clone, Copy)]
+struct ViewTransition {
+    from: (f32, f32),
+    to: (f32, f32),
+    /// Progress from 0 to 1
+    t: f32,
 }
 
 impl Camera {
@@ -78,6 +95,7 @@ impl Camera {
             yaw,
             pitch,
             distance,
+            transition: None,
         }
     }
 
@@ -88,6 +106,7 @@ impl Camera {
 
     /// Orbit the camera around the target
     pub fn orbit(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        self.transition = None;
         self.yaw += delta_yaw;
         self.pitch =
             (self.pitch + delta_pitch).clamp(-89.0_f32.to_radians(), 89.0_f32.to_radians());
@@ -152,6 +171,7 @@ impl Camera {
 
     /// Set to top view
     pub fn set_top_view(&mut self) {
+        self.transition = None;
         self.yaw = 0.0;
         self.pitch = 89.0_f32.to_radians();
         self.update_position_from_orbit();
@@ -159,6 +179,7 @@ impl Camera {
 
     /// Set to front view
     pub fn set_front_view(&mut self) {
+        self.transition = None;
         self.yaw = 0.0;
         self.pitch = 0.0;
         self.update_position_from_orbit();
@@ -166,11 +187,65 @@ impl Camera {
 
     /// Set to side view
     pub fn set_side_view(&mut self) {
+        self.transition = None;
         self.yaw = 90.0_f32.to_radians();
         self.pitch = 0.0;
         self.update_position_from_orbit();
     }
 
+    /// Orbit angles (yaw, pitch) that place the camera on the given side of the target
+    pub fn orbit_angles_for_direction(direction: Vec3) -> (f32, f32) {
+        let direction = direction.normalize();
+        let pitch = direction.z.asin().clamp(
+            MIN_PITCH_DEGREES.to_radians(),
+            MAX_PITCH_DEGREES.to_radians(),
+        );
+        // Straight up or down keeps yaw 0, matching the top view
+        let yaw = if direction.truncate().length_squared() > 1e-6 {
+            direction.y.atan2(direction.x)
+        } else {
+            0.0
+        };
+        (yaw, pitch)
+    }
+
+    /// Smoothly turn the camera to look at the target from the given direction
+    ///
+    /// Call [`Self::update_transition`] every frame to advance the animation.
+    pub fn animate_to_direction(&mut self, direction: Vec3) {
+        let (yaw, pitch) = Self::orbit_angles_for_direction(direction);
+        // Turn the short way around
+        let delta = (yaw - self.yaw + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+            - std::f32::consts::PI;
+        self.transition = Some(ViewTransition {
+            from: (self.yaw, self.pitch),
+            to: (self.yaw + delta, pitch),
+            t: 0.0,
+        });
+    }
+
+    /// Advance a running view animation by `dt` seconds
+    ///
+    /// Returns whether the animation is still running.
+    pub fn update_transition(&mut self, dt: f32) -> bool {
+        let Some(transition) = &mut self.transition else {
+            return false;
+        };
+        transition.t = (transition.t + dt / VIEW_TRANSITION_SECONDS).min(1.0);
+        let ViewTransition { from, to, t } = *transition;
+
+        // Ease in and out
+        let s = t * t * (3.0 - 2.0 * t);
+        self.yaw = from.0 + (to.0 - from.0) * s;
+        self.pitch = from.1 + (to.1 - from.1) * s;
+        self.update_position_from_orbit();
+
+        if t >= 1.0 {
+            self.transition = None;
+        }
+        self.transition.is_some()
+    }
+
     /// Get view matrix
     pub fn view_matrix(&self) -> Mat4 {
         Mat4::look_at_rh(self.position, self.target, self.up)
@@ -192,6 +267,7 @@ impl Camera {
             view: view.to_cols_array_2d(),
             proj: proj.to_cols_array_2d(),
             eye: [self.position.x, self.position.y, self.position.z, 1.0],
+            clip_plane: [0.0; 4],
         }
     }
 
@@ -229,4 +305,63 @@ impl Camera {
 
         (ray_origin, ray_direction)
     }
+
+    /// Convert a world point to screen coordinates
+    ///
+    /// Returns `None` if the point is behind the camera.
+    pub fn world_to_screen(
+        &self,
+        point: Vec3,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Option<glam::Vec2> {
+        let clip = self.projection_matrix() * self.view_matrix() * point.extend(1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+
+        let ndc = clip.truncate() / clip.w;
+        Some(glam::Vec2::new(
+            (ndc.x + 1.0) * 0.5 * screen_width,
+            (1.0 - ndc.y) * 0.5 * screen_height,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Direction from the target to the camera
+    fn view_direction(camera: &Camera) -> Vec3 {
+        (camera.position - camera.target).normalize()
+    }
+
+    #[test]
+    fn test_view_transition() {
+        let mut camera = Camera::new(1.0);
+        camera.animate_to_direction(Vec3::Y);
+
+        // Halfway through, the camera is between the two views
+        assert!(camera.update_transition(VIEW_TRANSITION_SECONDS / 2.0));
+        let halfway = camera.yaw;
+        assert!(halfway > 0.0 && halfway < std::f32::consts::FRAC_PI_2);
+
+        // A new target takes over from where the camera is
+        camera.animate_to_direction(Vec3::X);
+        assert!(camera.update_transition(1e-4));
+        assert!((camera.yaw - halfway).abs() < 1e-2);
+
+        let mut frames = 0;
+        while camera.update_transition(1.0 / 60.0) {
+            frames += 1;
+            assert!(frames < 100, "view transition never finished");
+        }
+        assert!(view_direction(&camera).abs_diff_eq(Vec3::X, 1e-5));
+
+        // Orbiting by hand cancels a running transition
+        camera.animate_to_direction(Vec3::NEG_Y);
+        camera.orbit(0.1, 0.0);
+        assert!(!camera.update_transition(1.0 / 60.0));
+    }
 }
@@ -7,15 +7,23 @@ use serde::{Deserialize, Serialize};
 
 /// Grid rendering configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct GridConfig {
     /// Whether the grid is enabled
     pub enabled: bool,
-    /// Grid extent (half-size in each direction)
+    /// Grid extent (half-size in each direction); for the infinite grid this
+    /// is the minimum fade-out distance
     pub size: f32,
-    /// Grid line spacing
+    /// Minor grid line spacing
     pub spacing: f32,
+    /// Number of minor cells per major line (values below 2 disable major lines)
+    pub major_every: u32,
+    /// Draw a shader-based grid that follows the camera and fades with distance
+    pub infinite: bool,
     /// Grid line color (RGB)
     pub line_color: [f32; 3],
+    /// Major grid line color (RGB)
+    pub major_line_color: [f32; 3],
     /// X-axis color (RGB)
     pub x_axis_color: [f32; 3],
     /// Y-axis color (RGB)
@@ -28,31 +36,170 @@ impl Default for GridConfig {
             enabled: true,
             size: 10.0,
             spacing: 1.0,
+            major_every: 10,
+            infinite: false,
             line_color: [0.3, 0.3, 0.3],
+            major_line_color: [0.45, 0.45, 0.45],
             x_axis_color: [0.8, 0.2, 0.2],
             y_axis_color: [0.2, 0.8, 0.2],
         }
     }
 }
 
+impl GridConfig {
+    /// Line colors matching a dark UI theme
+    pub fn dark_lines() -> Self {
+        Self::default()
+    }
+
+    /// Line colors matching a light UI theme
+    pub fn light_lines() -> Self {
+        Self {
+            line_color: [0.62, 0.63, 0.66],
+            major_line_color: [0.46, 0.47, 0.5],
+            x_axis_color: [0.75, 0.15, 0.15],
+            y_axis_color: [0.1, 0.6, 0.1],
+            ..Self::default()
+        }
+    }
+
+    /// Copy the line colors of `preset`, keeping spacing and other settings
+    pub fn apply_color_preset(&mut self, preset: &GridConfig) {
+        self.line_color = preset.line_color;
+        self.major_line_color = preset.major_line_color;
+        self.x_axis_color = preset.x_axis_color;
+        self.y_axis_color = preset.y_axis_color;
+    }
+}
+
+/// How the viewport background is filled
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum BackgroundMode {
+    /// Flat background color
+    #[default]
+    Solid,
+    /// Vertical blend from the top color to the bottom color
+    Gradient,
+}
+
+impl BackgroundMode {
+    /// All modes, in display order
+    pub const ALL: &'static [BackgroundMode] = &[BackgroundMode::Solid, BackgroundMode::Gradient];
+
+    /// Get the display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackgroundMode::Solid => "Solid",
+            BackgroundMode::Gradient => "Gradient",
+        }
+    }
+}
+
 /// Viewport rendering configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct ViewportConfig {
-    /// Background clear color (RGBA)
+    /// Background clear color (RGBA), used by the solid mode
     pub background_color: [f32; 4],
+    /// Background fill mode
+    pub background_mode: BackgroundMode,
+    /// Gradient color at the top of the viewport (RGBA)
+    pub gradient_top_color: [f32; 4],
+    /// Gradient color at the bottom of the viewport (RGBA)
+    pub gradient_bottom_color: [f32; 4],
     /// MSAA sample count (1 = disabled, 2, 4, 8)
     pub msaa_sample_count: u32,
 }
 
+impl ViewportConfig {
+    /// Background colors matching a dark UI theme
+    pub fn dark_background() -> Self {
+        Self::default()
+    }
+
+    /// Background colors matching a light UI theme
+    pub fn light_background() -> Self {
+        Self {
+            background_color: [0.82, 0.83, 0.86, 1.0],
+            gradient_top_color: [0.93, 0.94, 0.96, 1.0],
+            gradient_bottom_color: [0.70, 0.72, 0.76, 1.0],
+            ..Self::default()
+        }
+    }
+
+    /// Copy the background colors of `preset`, keeping the mode and other settings
+    pub fn apply_background_preset(&mut self, preset: &ViewportConfig) {
+        self.background_color = preset.background_color;
+        self.gradient_top_color = preset.gradient_top_color;
+        self.gradient_bottom_color = preset.gradient_bottom_color;
+    }
+}
+
 impl Default for ViewportConfig {
     fn default() -> Self {
         Self {
             background_color: [0.15, 0.15, 0.18, 1.0],
+            background_mode: BackgroundMode::Solid,
+            gradient_top_color: [0.30, 0.32, 0.38, 1.0],
+            gradient_bottom_color: [0.08, 0.08, 0.10, 1.0],
             msaa_sample_count: 4,
         }
     }
 }
 
+/// Floor plane configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FloorConfig {
+    /// Whether the floor is drawn
+    pub enabled: bool,
+    /// Floor height along world Z (meters)
+    pub height: f32,
+    /// Edge length of the square floor (meters)
+    pub size: f32,
+    /// Floor color (RGB)
+    pub color: [f32; 3],
+    /// Whether parts cast shadows onto the floor
+    pub receive_shadows: bool,
+}
+
+impl Default for FloorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            height: 0.0,
+            size: 20.0,
+            color: [0.55, 0.55, 0.58],
+            receive_shadows: true,
+        }
+    }
+}
+
+/// Feature edge configuration
+///
+/// Edges are drawn as lines over the shaded meshes along creases, open
+/// borders and silhouettes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EdgeConfig {
+    /// Whether edges are drawn
+    pub enabled: bool,
+    /// Minimum angle between adjacent face normals for a crease (degrees)
+    pub crease_angle: f32,
+    /// Edge line color (RGBA)
+    pub color: [f32; 4],
+}
+
+impl Default for EdgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            crease_angle: 30.0,
+            color: [0.05, 0.05, 0.06, 1.0],
+        }
+    }
+}
+
 /// Shadow mapping configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ShadowConfig {
@@ -107,8 +254,33 @@ impl Default for LightingConfig {
     }
 }
 
+/// What a plain middle-mouse drag does in the viewport (Shift gives the other)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum MiddleMouseAction {
+    /// Orbit, Shift to pan
+    #[default]
+    Orbit,
+    /// Pan, Shift to orbit
+    Pan,
+}
+
+impl MiddleMouseAction {
+    /// All mappings, in display order
+    pub const ALL: &'static [MiddleMouseAction] =
+        &[MiddleMouseAction::Orbit, MiddleMouseAction::Pan];
+
+    /// Get the display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            MiddleMouseAction::Orbit => "Orbit (Shift: Pan)",
+            MiddleMouseAction::Pan => "Pan (Shift: Orbit)",
+        }
+    }
+}
+
 /// Camera default configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct CameraConfig {
     /// Field of view in degrees
     pub fov_degrees: f32,
@@ -116,12 +288,19 @@ pub struct CameraConfig {
     pub near_plane: f32,
     /// Far clipping plane distance
     pub far_plane: f32,
+    /// Derive the clip planes from the content bounds instead of using
+    /// `near_plane` and `far_plane`
+    pub auto_clip_planes: bool,
     /// Pan sensitivity multiplier
     pub pan_sensitivity: f32,
     /// Zoom sensitivity multiplier
     pub zoom_sensitivity: f32,
     /// Orbit sensitivity multiplier
     pub orbit_sensitivity: f32,
+    /// Invert vertical mouse movement when orbiting and panning
+    pub invert_y: bool,
+    /// Middle-mouse drag mapping
+    pub middle_mouse: MiddleMouseAction,
 }
 
 impl Default for CameraConfig {
@@ -130,9 +309,12 @@ impl Default for CameraConfig {
             fov_degrees: 40.0,
             near_plane: 0.1,
             far_plane: 100000.0,
+            auto_clip_planes: true,
             pan_sensitivity: 0.002,
             zoom_sensitivity: 0.1,
             orbit_sensitivity: 0.005,
+            invert_y: false,
+            middle_mouse: MiddleMouseAction::Orbit,
         }
     }
 }
@@ -164,6 +346,28 @@ impl Default for GizmoConfig {
     }
 }
 
+/// Fast orbit configuration
+///
+/// While the camera is moving, parts above the triangle threshold are drawn
+/// as their bounding boxes to keep large scenes responsive.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FastOrbitConfig {
+    /// Whether proxies are drawn while the camera moves
+    pub enabled: bool,
+    /// Parts with more triangles than this are replaced by their bounding box
+    pub triangle_threshold: u32,
+}
+
+impl Default for FastOrbitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            triangle_threshold: 50_000,
+        }
+    }
+}
+
 /// Complete renderer configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct RendererConfig {
@@ -173,6 +377,9 @@ pub struct RendererConfig {
     /// Viewport settings
     #[serde(default)]
     pub viewport: ViewportConfig,
+    /// Floor plane settings
+    #[serde(default)]
+    pub floor: FloorConfig,
     /// Shadow settings
     #[serde(default)]
     pub shadow: ShadowConfig,
@@ -185,6 +392,12 @@ pub struct RendererConfig {
     /// Gizmo settings
     #[serde(default)]
     pub gizmo: GizmoConfig,
+    /// Fast orbit settings
+    #[serde(default)]
+    pub fast_orbit: FastOrbitConfig,
+    /// Feature edge settings
+    #[serde(default)]
+    pub edges: EdgeConfig,
 }
 
 impl RendererConfig {
@@ -9,6 +9,8 @@ pub mod grid {
     pub const DEFAULT_SIZE: f32 = 10.0;
     /// Default grid line spacing
     pub const DEFAULT_SPACING: f32 = 1.0;
+    /// Upper bound on lines generated per direction for the fixed-size grid
+    pub const MAX_LINES_PER_AXIS: i32 = 2000;
     /// Grid line color (gray)
     pub const LINE_COLOR: [f32; 3] = [0.3, 0.3, 0.3];
     /// X-axis color (red)
@@ -71,6 +73,26 @@ pub mod marker {
     pub const RINGS: u32 = 12;
 }
 
+/// Joint axis visualization constants
+pub mod joint_axis {
+    /// Maximum number of line vertices for all joint axes combined
+    pub const MAX_VERTICES: u32 = 16384;
+    /// Line segments used for a full circle of rotation
+    pub const CIRCLE_SEGMENTS: u32 = 48;
+    /// Arrowhead length as a fraction of the arrow length
+    pub const HEAD_LENGTH: f32 = 0.2;
+    /// Arrowhead half-width as a fraction of the arrow length
+    pub const HEAD_WIDTH: f32 = 0.06;
+    /// Range arc radius as a fraction of the arrow length
+    pub const ARC_RADIUS: f32 = 0.5;
+    /// Revolute joint color (orange)
+    pub const REVOLUTE_COLOR: [f32; 3] = [1.0, 0.6, 0.1];
+    /// Continuous joint color (yellow)
+    pub const CONTINUOUS_COLOR: [f32; 3] = [0.95, 0.9, 0.2];
+    /// Prismatic joint color (cyan)
+    pub const PRISMATIC_COLOR: [f32; 3] = [0.2, 0.8, 1.0];
+}
+
 /// Instance buffer limits
 pub mod instances {
     /// Maximum number of axis instances
@@ -91,6 +113,17 @@ pub mod collision {
     pub const DEFAULT_COLOR: [f32; 4] = [0.0, 0.8, 0.8, 0.3];
     /// Selected collision color (semi-transparent yellow)
     pub const SELECTED_COLOR: [f32; 4] = [1.0, 0.8, 0.0, 0.5];
+    /// Opacity of visual meshes behind collision shapes in X-ray mode
+    pub const XRAY_VISUAL_OPACITY: f32 = 0.25;
+}
+
+/// Feature edge constants
+pub mod edges {
+    /// Faces whose normals have a larger dot product count as coplanar; the
+    /// edges between them are never drawn
+    pub const COPLANAR_DOT: f32 = 0.9999;
+    /// Fraction of the distance to the camera edges are pulled forward by
+    pub const DEPTH_PULL: f32 = 0.002;
 }
 
 /// Camera default parameters
@@ -111,6 +144,12 @@ pub mod camera {
     pub const MIN_PITCH_DEGREES: f32 = -89.0;
     /// Maximum pitch angle in degrees
     pub const MAX_PITCH_DEGREES: f32 = 89.0;
+    /// Depth margin around the content with auto clip planes, relative to
+    /// its bounding radius (keeps the grid and floor around it visible)
+    pub const AUTO_CLIP_MARGIN: f32 = 0.5;
+    /// Smallest near/far ratio with auto clip planes, bounding depth precision
+    /// loss when the camera is inside the content
+    pub const AUTO_CLIP_MIN_NEAR_RATIO: f32 = 1e-4;
     /// Pan sensitivity multiplier
     pub const PAN_SCALE: f32 = 0.002;
     /// Zoom sensitivity multiplier
@@ -121,6 +160,8 @@ pub mod camera {
     pub const MAX_DISTANCE: f32 = 10000.0;
     /// Fit-all radius multiplier
     pub const FIT_ALL_MULTIPLIER: f32 = 2.5;
+    /// Duration of animated view changes in seconds
+    pub const VIEW_TRANSITION_SECONDS: f32 = 0.3;
 }
 
 /// Viewport rendering constants
@@ -135,6 +176,9 @@ pub mod viewport {
 
     /// MSAA sample count for anti-aliasing (1 = disabled, 4 = 4x MSAA)
     pub const SAMPLE_COUNT: u32 = 4;
+
+    /// MSAA sample counts WebGPU supports for every renderable format
+    pub const GUARANTEED_SAMPLE_COUNTS: [u32; 2] = [1, 4];
 }
 
 /// Shadow mapping constants
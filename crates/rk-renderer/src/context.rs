@@ -111,6 +111,13 @@ impl RenderContext {
         self.sample_count
     }
 
+    /// Sets the MSAA sample count, to follow [`Renderer::sample_count`].
+    ///
+    /// [`Renderer::sample_count`]: crate::Renderer::sample_count
+    pub fn set_sample_count(&mut self, sample_count: u32) {
+        self.sample_count = sample_count;
+    }
+
     /// Returns the camera bind group layout.
     pub fn camera_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
         &self.camera_bind_group_layout
@@ -12,6 +12,35 @@
 //! - [`scene::Scene`] - Scene management for renderable objects
 //! - [`resources::MeshManager`] - GPU mesh resource management
 //!
+//! # Embedding
+//!
+//! The [`Renderer`] draws into any texture view, so another application can
+//! host it with its own device and window:
+//!
+//! ```no_run
+//! use glam::Mat4;
+//! use rk_core::Part;
+//! use rk_renderer::{Renderer, RendererConfig};
+//!
+//! fn draw(device: &wgpu::Device, queue: &wgpu::Queue, view: &wgpu::TextureView) {
+//!     let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+//!     let config = RendererConfig::default();
+//!     let mut renderer = Renderer::with_config(device, queue, format, 800, 600, &config);
+//!
+//!     let part = Part::new("body");
+//!     let id = renderer.add_part(device, &part);
+//!     renderer.update_part_transform(queue, id, Mat4::from_rotation_z(0.5));
+//!     renderer.camera_mut().fit_all(glam::Vec3::ZERO, 1.0);
+//!
+//!     let mut encoder = device.create_command_encoder(&Default::default());
+//!     renderer.prepare_edges(device);
+//!     renderer.render(&mut encoder, view, queue);
+//!     queue.submit([encoder.finish()]);
+//! }
+//! ```
+//!
+//! See `examples/spinning_cube.rs` for a complete offscreen program.
+//!
 //! # Module Structure
 //!
 //! ```text
@@ -56,7 +85,8 @@ pub mod gizmo {
 }
 pub mod grid {
     //! Grid renderer (re-exported from sub_renderers)
-    pub use crate::sub_renderers::grid_legacy::*;
+    #[allow(deprecated)]
+    pub use crate::sub_renderers::{GridRenderer, GridSubRenderer};
 }
 pub mod marker {
     //! Marker renderer (re-exported from sub_renderers)
@@ -76,11 +106,13 @@ pub use plugin::{RendererPlugin, RendererRegistry};
 pub use renderer::*;
 pub use resources::MeshData as ResourceMeshData;
 pub use resources::{GpuMesh, MeshHandle, MeshManager};
-pub use scene::{BoundingBox, Frustum, RenderLayer, RenderObject, Scene};
+pub use scene::{BoundingBox, Frustum, Plane, RenderLayer, RenderObject, Scene};
+#[allow(deprecated)]
+pub use sub_renderers::GridRenderer;
 pub use sub_renderers::{
-    AxisInstance, AxisRenderer, GizmoAxis, GizmoMode, GizmoRenderer, GizmoSpace, GridRenderer,
-    GridSubRenderer, MarkerInstance, MarkerRenderer, MeshRenderer, SketchRenderData,
-    SketchRenderer, SketchVertex,
+    AxisInstance, AxisRenderer, GizmoAxis, GizmoMode, GizmoRenderer, GizmoSpace, GridSubRenderer,
+    JointAxisInstance, JointAxisKind, MarkerInstance, MarkerRenderer, MeshRenderer,
+    SketchRenderData, SketchRenderer, SketchVertex, TrajectoryRenderer, TrajectoryVertex,
 };
 pub use traits::{PassType, SubRenderer};
 pub use vertex::MeshVertex;
@@ -124,7 +124,7 @@ impl<'a> PipelineConfig<'a> {
     }
 
     /// Build the render pipeline.
-    pub fn build(self, device: &wgpu::Device) -> wgpu::RenderPipeline {
+    pub fn build(self, device: &wgpu::Device) -> MsaaPipeline {
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some(&format!("{} Shader", self.label)),
             source: wgpu::ShaderSource::Wgsl(self.shader_source.into()),
@@ -136,25 +136,18 @@ impl<'a> PipelineConfig<'a> {
             push_constant_ranges: &[],
         });
 
-        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some(&format!("{} Pipeline", self.label)),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some(self.vs_entry_point),
-                buffers: &self.vertex_layouts,
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
+        let descriptor = PipelineDescriptor {
+            label: format!("{} Pipeline", self.label),
+            shader,
+            layout: pipeline_layout,
+            vs_entry_point: self.vs_entry_point.to_string(),
+            fs_entry_point: self.fs_entry_point.to_string(),
+            vertex_layouts: self.vertex_layouts.iter().map(VertexLayout::from).collect(),
+            target: wgpu::ColorTargetState {
+                format: self.format,
+                blend: self.blend,
+                write_mask: wgpu::ColorWrites::ALL,
             },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some(self.fs_entry_point),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: self.format,
-                    blend: self.blend,
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
             primitive: wgpu::PrimitiveState {
                 topology: self.topology,
                 cull_mode: self.cull_mode,
@@ -167,8 +160,86 @@ impl<'a> PipelineConfig<'a> {
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
+        };
+        MsaaPipeline::new(device, descriptor, self.sample_count)
+    }
+}
+
+/// Vertex buffer layout that owns its attributes
+#[derive(Debug, Clone)]
+pub struct VertexLayout {
+    /// Bytes between consecutive elements
+    pub array_stride: wgpu::BufferAddress,
+    /// Whether the buffer advances per vertex or per instance
+    pub step_mode: wgpu::VertexStepMode,
+    /// Attributes read from each element
+    pub attributes: Vec<wgpu::VertexAttribute>,
+}
+
+impl From<&wgpu::VertexBufferLayout<'_>> for VertexLayout {
+    fn from(layout: &wgpu::VertexBufferLayout<'_>) -> Self {
+        Self {
+            array_stride: layout.array_stride,
+            step_mode: layout.step_mode,
+            attributes: layout.attributes.to_vec(),
+        }
+    }
+}
+
+/// Everything needed to create a render pipeline except its sample count
+#[derive(Debug, Clone)]
+pub struct PipelineDescriptor {
+    /// Pipeline label for debugging
+    pub label: String,
+    /// Shader module holding both entry points
+    pub shader: wgpu::ShaderModule,
+    /// Pipeline layout
+    pub layout: wgpu::PipelineLayout,
+    /// Vertex shader entry point
+    pub vs_entry_point: String,
+    /// Fragment shader entry point
+    pub fs_entry_point: String,
+    /// Vertex buffer layouts
+    pub vertex_layouts: Vec<VertexLayout>,
+    /// Single color target
+    pub target: wgpu::ColorTargetState,
+    /// Primitive state
+    pub primitive: wgpu::PrimitiveState,
+    /// Depth state
+    pub depth_stencil: Option<wgpu::DepthStencilState>,
+}
+
+impl PipelineDescriptor {
+    fn create(&self, device: &wgpu::Device, sample_count: u32) -> wgpu::RenderPipeline {
+        let buffers: Vec<wgpu::VertexBufferLayout> = self
+            .vertex_layouts
+            .iter()
+            .map(|layout| wgpu::VertexBufferLayout {
+                array_stride: layout.array_stride,
+                step_mode: layout.step_mode,
+                attributes: &layout.attributes,
+            })
+            .collect();
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(&self.label),
+            layout: Some(&self.layout),
+            vertex: wgpu::VertexState {
+                module: &self.shader,
+                entry_point: Some(&self.vs_entry_point),
+                buffers: &buffers,
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &self.shader,
+                entry_point: Some(&self.fs_entry_point),
+                targets: &[Some(self.target.clone())],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: self.primitive,
+            depth_stencil: self.depth_stencil.clone(),
             multisample: wgpu::MultisampleState {
-                count: self.sample_count,
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
@@ -178,6 +249,49 @@ impl<'a> PipelineConfig<'a> {
     }
 }
 
+/// Render pipeline drawing into the multisampled viewport targets
+///
+/// Keeps its descriptor so it can be recreated when the MSAA sample count
+/// changes. Dereferences to the current [`wgpu::RenderPipeline`].
+#[derive(Debug)]
+pub struct MsaaPipeline {
+    pipeline: wgpu::RenderPipeline,
+    descriptor: PipelineDescriptor,
+    sample_count: u32,
+}
+
+impl MsaaPipeline {
+    /// Create the pipeline with the given sample count
+    pub fn new(device: &wgpu::Device, descriptor: PipelineDescriptor, sample_count: u32) -> Self {
+        Self {
+            pipeline: descriptor.create(device, sample_count),
+            descriptor,
+            sample_count,
+        }
+    }
+
+    /// Sample count the pipeline currently renders with
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Recreate the pipeline for another sample count; no-op if unchanged
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        if sample_count != self.sample_count {
+            self.pipeline = self.descriptor.create(device, sample_count);
+            self.sample_count = sample_count;
+        }
+    }
+}
+
+impl std::ops::Deref for MsaaPipeline {
+    type Target = wgpu::RenderPipeline;
+
+    fn deref(&self) -> &wgpu::RenderPipeline {
+        &self.pipeline
+    }
+}
+
 /// Create a camera bind group from the layout and buffer.
 ///
 /// This is a common operation used by all sub-renderers.
@@ -10,10 +10,11 @@ use crate::traits::SubRenderer;
 /// Registry for managing sub-renderers.
 ///
 /// The registry maintains a collection of sub-renderers and handles
-/// their lifecycle (initialization, rendering, cleanup).
+/// their lifecycle (initialization, rendering, cleanup). Sub-renderers are
+/// kept in [`priorities`](crate::sub_renderers::priorities) order, lowest
+/// first; ties keep their registration order.
 pub struct RendererRegistry {
     sub_renderers: Vec<Box<dyn SubRenderer>>,
-    sorted: bool,
 }
 
 impl RendererRegistry {
@@ -21,17 +22,20 @@ impl RendererRegistry {
     pub fn new() -> Self {
         Self {
             sub_renderers: Vec::new(),
-            sorted: true,
         }
     }
 
-    /// Registers a new sub-renderer.
+    /// Registers a new sub-renderer at its priority position.
     ///
-    /// The sub-renderer will be initialized on the next frame if a render
-    /// context is available.
-    pub fn register<R: SubRenderer + 'static>(&mut self, renderer: R) {
-        self.sub_renderers.push(Box::new(renderer));
-        self.sorted = false;
+    /// The registry does not initialize it; call
+    /// [`SubRenderer::on_init`] first, or use
+    /// [`Renderer::register_sub_renderer`](crate::Renderer::register_sub_renderer).
+    pub fn register(&mut self, renderer: Box<dyn SubRenderer>) {
+        let priority = renderer.priority();
+        let index = self
+            .sub_renderers
+            .partition_point(|r| r.priority() <= priority);
+        self.sub_renderers.insert(index, renderer);
     }
 
     /// Unregisters a sub-renderer by name.
@@ -88,17 +92,8 @@ impl RendererRegistry {
         self.sub_renderers.iter_mut()
     }
 
-    /// Sorts sub-renderers by priority if needed.
-    fn ensure_sorted(&mut self) {
-        if !self.sorted {
-            self.sub_renderers.sort_by_key(|r| r.priority());
-            self.sorted = true;
-        }
-    }
-
     /// Initializes all sub-renderers with the given context.
     pub fn init_all(&mut self, ctx: &RenderContext) {
-        self.ensure_sorted();
         for renderer in &mut self.sub_renderers {
             renderer.on_init(ctx);
         }
@@ -111,9 +106,15 @@ impl RendererRegistry {
         }
     }
 
+    /// Notifies all sub-renderers of a new MSAA sample count.
+    pub fn sample_count_changed_all(&mut self, ctx: &RenderContext) {
+        for renderer in &mut self.sub_renderers {
+            renderer.on_sample_count_changed(ctx);
+        }
+    }
+
     /// Prepares all sub-renderers for rendering.
     pub fn prepare_all(&mut self, ctx: &RenderContext, scene: &Scene) {
-        self.ensure_sorted();
         for renderer in &mut self.sub_renderers {
             if renderer.is_enabled() {
                 renderer.prepare(ctx, scene);
@@ -213,11 +214,9 @@ mod tests {
     fn test_registry_ordering() {
         let mut registry = RendererRegistry::new();
 
-        registry.register(TestRenderer::new("third", 300));
-        registry.register(TestRenderer::new("first", 100));
-        registry.register(TestRenderer::new("second", 200));
-
-        registry.ensure_sorted();
+        registry.register(Box::new(TestRenderer::new("third", 300)));
+        registry.register(Box::new(TestRenderer::new("first", 100)));
+        registry.register(Box::new(TestRenderer::new("second", 200)));
 
         let names: Vec<&str> = registry.iter().map(|r| r.name()).collect();
         assert_eq!(names, vec!["first", "second", "third"]);
@@ -227,11 +226,23 @@ mod tests {
     fn test_registry_unregister() {
         let mut registry = RendererRegistry::new();
 
-        registry.register(TestRenderer::new("test", 100));
+        registry.register(Box::new(TestRenderer::new("test", 100)));
         assert!(registry.contains("test"));
 
         let removed = registry.unregister("test");
         assert!(removed.is_some());
         assert!(!registry.contains("test"));
     }
+
+    #[test]
+    fn test_registry_equal_priority_keeps_registration_order() {
+        let mut registry = RendererRegistry::new();
+
+        registry.register(Box::new(TestRenderer::new("early", 100)));
+        registry.register(Box::new(TestRenderer::new("late", 100)));
+        registry.register(Box::new(TestRenderer::new("background", 0)));
+
+        let names: Vec<&str> = registry.iter().map(|r| r.name()).collect();
+        assert_eq!(names, vec!["background", "early", "late"]);
+    }
 }
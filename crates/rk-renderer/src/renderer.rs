@@ -12,7 +12,7 @@
 //! - [`MeshManager`]: Handles GPU mesh resources
 //! - [`RendererRegistry`]: Manages sub-renderer plugins
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use glam::{Mat4, Vec3};
 use uuid::Uuid;
@@ -20,21 +20,27 @@ use wgpu::util::DeviceExt;
 
 use rk_core::Part;
 
-use crate::camera::Camera;
+use crate::camera::{Camera, CameraUniform};
 use crate::config::{
-    CameraConfig, GizmoConfig, GridConfig, LightingConfig, RendererConfig, ShadowConfig,
-    ViewportConfig,
+    CameraConfig, EdgeConfig, FastOrbitConfig, FloorConfig, GizmoConfig, GridConfig,
+    LightingConfig, RendererConfig, ShadowConfig, ViewportConfig,
 };
+use crate::constants::camera::{AUTO_CLIP_MARGIN, AUTO_CLIP_MIN_NEAR_RATIO};
+use crate::constants::collision::XRAY_VISUAL_OPACITY;
 use crate::constants::shadow::{SHADOW_MAP_FORMAT, SHADOW_MAP_SIZE};
-use crate::constants::viewport::{CLEAR_COLOR, SAMPLE_COUNT};
+use crate::constants::viewport::{CLEAR_COLOR, GUARANTEED_SAMPLE_COUNTS, SAMPLE_COUNT};
+use crate::context::RenderContext;
 use crate::light::DirectionalLight;
 use crate::plugin::RendererRegistry;
 use crate::resources::MeshManager;
-use crate::scene::Scene;
+use crate::scene::{BoundingBox, Plane, Scene};
 use crate::sub_renderers::{
-    AxisInstance, AxisRenderer, CollisionRenderer, GizmoAxis, GizmoMode, GizmoRenderer, GizmoSpace,
-    GridRenderer, MarkerInstance, MarkerRenderer, MeshData, MeshRenderer,
+    AxisInstance, AxisRenderer, BackgroundSubRenderer, CollisionRenderer, EdgeMesh, EdgeRenderer,
+    FloorRenderer, GizmoAxis, GizmoMode, GizmoRenderer, GizmoSpace, GridSubRenderer,
+    JointAxisInstance, JointAxisRenderer, MarkerInstance, MarkerRenderer, MeshData, MeshRenderer,
+    SketchRenderData, SketchRenderer,
 };
+use crate::traits::{ConfigurableSubRenderer, SubRenderer};
 
 /// Mesh entry with bind group
 pub struct MeshEntry {
@@ -42,25 +48,142 @@ pub struct MeshEntry {
     pub data: MeshData,
     /// Bind group for instance-specific uniforms.
     pub bind_group: wgpu::BindGroup,
+    /// Whether the mesh is drawn.
+    pub visible: bool,
+    /// Model transform before the exploded-view offset.
+    pub transform: Mat4,
+    /// Exploded-view translation applied on top of `transform`.
+    pub explode_offset: Vec3,
+    /// Bounding box drawn instead of the mesh during fast orbit.
+    pub proxy: MeshData,
+    /// Number of triangles in the mesh.
+    pub triangle_count: u32,
+    /// Bounding box before the transform.
+    pub bounds: BoundingBox,
+    /// Crease and silhouette edge candidates, shared with part copies and
+    /// built by [`Renderer::prepare_edges`] when first needed.
+    pub edges: Option<EdgeMesh>,
+}
+
+impl MeshEntry {
+    fn new(
+        device: &wgpu::Device,
+        data: MeshData,
+        bind_group: wgpu::BindGroup,
+        part: &Part,
+    ) -> Self {
+        let transform = Mat4::from_cols_array_2d(&data.instance.model);
+        let proxy = data.box_proxy(device, Vec3::from(part.bbox_min), Vec3::from(part.bbox_max));
+        Self {
+            triangle_count: data.index_count / 3,
+            data,
+            bind_group,
+            visible: part.visible,
+            transform,
+            explode_offset: Vec3::ZERO,
+            proxy,
+            bounds: BoundingBox::new(Vec3::from(part.bbox_min), Vec3::from(part.bbox_max)),
+            edges: None,
+        }
+    }
+
+    fn world_transform(&self) -> Mat4 {
+        Mat4::from_translation(self.explode_offset) * self.transform
+    }
+
+    fn write_transform(&mut self, queue: &wgpu::Queue) {
+        self.data.update_transform(queue, self.world_transform());
+    }
+}
+
+/// Which of visual meshes and collision shapes the viewport draws
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Visual meshes with collision shapes on top
+    #[default]
+    Combined,
+    /// Visual meshes only
+    VisualOnly,
+    /// Collision shapes only, for tuning collision geometry
+    CollisionOnly,
+    /// Collision shapes over dimmed visual meshes that do not hide them
+    XRay,
+}
+
+impl RenderMode {
+    /// All render modes, in menu order
+    pub const ALL: [RenderMode; 4] = [
+        RenderMode::Combined,
+        RenderMode::VisualOnly,
+        RenderMode::CollisionOnly,
+        RenderMode::XRay,
+    ];
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            RenderMode::Combined => "Visual + Collision",
+            RenderMode::VisualOnly => "Visual Only",
+            RenderMode::CollisionOnly => "Collision Only",
+            RenderMode::XRay => "X-Ray",
+        }
+    }
+
+    /// Whether visual meshes are drawn
+    pub fn shows_visuals(self) -> bool {
+        self != RenderMode::CollisionOnly
+    }
+
+    /// Whether collision shapes are drawn
+    pub fn shows_collisions(self) -> bool {
+        self != RenderMode::VisualOnly
+    }
 }
 
 /// Main renderer combining all sub-renderers.
 ///
-/// The renderer provides both a legacy API for backward compatibility
-/// and access to new architectural components for advanced use cases.
+/// The renderer does not own a window or surface, so it can be embedded in
+/// any application that has a wgpu device: create it with [`Renderer::new`]
+/// or [`Renderer::with_config`], and call [`Renderer::render`] with the
+/// texture view to draw into.
+///
+/// # Objects
+///
+/// Objects are [`Part`] meshes keyed by their `Uuid`: [`add_part`] uploads
+/// one, [`update_part_transform`] and [`update_part_color`] change it, and
+/// [`remove_part`] drops it again.
+///
+/// # Redrawing
 ///
-/// # Legacy API
+/// Every method that changes what is drawn marks the [`scene`] dirty, so a
+/// caller can skip rendering while [`Scene::is_dirty`] is false and the
+/// camera has not moved, and call [`Scene::mark_clean`] after rendering.
+/// Changes made through [`camera_mut`] are not tracked.
 ///
-/// Methods like [`add_part`], [`update_part_transform`], etc. work the same
-/// as before for existing code.
+/// # Custom sub-renderers
 ///
-/// # New Architecture
+/// Sub-renderers registered with [`register_sub_renderer`] draw after the
+/// built-in geometry and before the gizmo. Call [`prepare_sub_renderers`]
+/// once per frame before rendering so they see the current camera.
 ///
-/// For advanced use cases, access the new components:
+/// For advanced use cases, access the underlying components:
 /// - [`registry`]/[`registry_mut`]: Access the plugin system
 /// - [`scene`]/[`scene_mut`]: Access the scene graph
 /// - [`mesh_manager`]/[`mesh_manager_mut`]: Access mesh resources
-/// - [`context`]: Access the render context
+///
+/// [`add_part`]: Renderer::add_part
+/// [`update_part_transform`]: Renderer::update_part_transform
+/// [`update_part_color`]: Renderer::update_part_color
+/// [`remove_part`]: Renderer::remove_part
+/// [`register_sub_renderer`]: Renderer::register_sub_renderer
+/// [`prepare_sub_renderers`]: Renderer::prepare_sub_renderers
+/// [`registry`]: Renderer::registry
+/// [`registry_mut`]: Renderer::registry_mut
+/// [`scene`]: Renderer::scene
+/// [`camera_mut`]: Renderer::camera_mut
+/// [`scene_mut`]: Renderer::scene_mut
+/// [`mesh_manager`]: Renderer::mesh_manager
+/// [`mesh_manager_mut`]: Renderer::mesh_manager_mut
 pub struct Renderer {
     // New architectural components (optional during migration)
     // These will be fully integrated in future versions
@@ -90,32 +213,60 @@ pub struct Renderer {
     light_bind_group: wgpu::BindGroup,
     /// Bind group for shadow pass (light uniform only)
     shadow_light_bind_group: wgpu::BindGroup,
+    /// Light uniform with shadows disabled, for a floor that does not receive them
+    unshadowed_light_buffer: wgpu::Buffer,
+    /// Main pass bind group using `unshadowed_light_buffer`
+    unshadowed_light_bind_group: wgpu::BindGroup,
 
     // Sub-renderers (legacy - will migrate to registry)
-    grid_renderer: GridRenderer,
+    background_renderer: BackgroundSubRenderer,
+    grid_renderer: GridSubRenderer,
+    floor_renderer: FloorRenderer,
     mesh_renderer: MeshRenderer,
+    edge_renderer: EdgeRenderer,
     axis_renderer: AxisRenderer,
     marker_renderer: MarkerRenderer,
+    joint_axis_renderer: JointAxisRenderer,
+    sketch_renderer: SketchRenderer,
     gizmo_renderer: GizmoRenderer,
     collision_renderer: CollisionRenderer,
 
     // Data - UUID-keyed storage for O(1) lookup and removal
     meshes: HashMap<Uuid, MeshEntry>,
     selected_part: Option<Uuid>,
+    hovered_part: Option<Uuid>,
 
     // Display options
     show_grid: bool,
     show_axes: bool,
     show_markers: bool,
     show_gizmo: bool,
+    render_mode: RenderMode,
+    /// Section plane cutting away part meshes on its positive side
+    section_plane: Option<Plane>,
+    /// Whether the camera is being moved, for fast orbit
+    camera_moving: bool,
+    fast_orbit: FastOrbitConfig,
+    /// Whether clip planes follow the content bounds
+    auto_clip_planes: bool,
+    /// Content bounds the clip planes were fitted to, until the scene changes
+    content_bounds: Option<BoundingBox>,
 
     // Configurable rendering settings
     clear_color: wgpu::Color,
     shadow_map_size: u32,
+    /// MSAA sample count of the main pass
+    sample_count: u32,
+    /// Sample counts both the color and depth formats can render with
+    supported_sample_counts: Vec<u32>,
 
     format: wgpu::TextureFormat,
     width: u32,
     height: u32,
+    /// Viewport size last forwarded to custom sub-renderers
+    sub_renderer_size: (u32, u32),
+    /// Sample count last forwarded to custom sub-renderers
+    sub_renderer_sample_count: u32,
 }
 
 impl Renderer {
@@ -152,8 +303,9 @@ impl Renderer {
                 }],
             });
 
-        let (depth_texture, depth_view) = Self::create_depth_texture(device, width, height);
-        let msaa_result = Self::create_msaa_texture(device, format, width, height);
+        let (depth_texture, depth_view) =
+            Self::create_depth_texture(device, width, height, SAMPLE_COUNT);
+        let msaa_result = Self::create_msaa_texture(device, format, width, height, SAMPLE_COUNT);
         let (msaa_texture, msaa_view) = match msaa_result {
             Some((tex, view)) => (Some(tex), Some(view)),
             None => (None, None),
@@ -172,7 +324,11 @@ impl Renderer {
         let (shadow_texture, shadow_view) = Self::create_shadow_texture(device, SHADOW_MAP_SIZE);
         let shadow_sampler = Self::create_shadow_sampler(device);
 
-        let grid_renderer = GridRenderer::new(
+        let mut background_renderer = BackgroundSubRenderer::new();
+        background_renderer.init(device, format, depth_format);
+
+        let mut grid_renderer = GridSubRenderer::new();
+        grid_renderer.init(
             device,
             format,
             depth_format,
@@ -197,6 +353,30 @@ impl Renderer {
             &shadow_sampler,
         );
 
+        let unshadowed_light_buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Unshadowed Light Buffer"),
+                contents: bytemuck::cast_slice(&[light_uniform]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+        let unshadowed_light_bind_group = Self::create_light_bind_group(
+            device,
+            mesh_renderer.light_bind_group_layout(),
+            &unshadowed_light_buffer,
+            &shadow_view,
+            &shadow_sampler,
+        );
+
+        let floor_renderer = FloorRenderer::new(device, &mesh_renderer);
+        let edge_renderer = EdgeRenderer::new(
+            device,
+            format,
+            depth_format,
+            &camera_bind_group_layout,
+            &camera_buffer,
+            mesh_renderer.instance_bind_group_layout(),
+        );
+
         // Shadow pass bind group (light uniform only, for shadow.wgsl group 0)
         let shadow_light_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -238,6 +418,23 @@ impl Renderer {
             &camera_buffer,
         );
 
+        let joint_axis_renderer = JointAxisRenderer::new(
+            device,
+            format,
+            depth_format,
+            &camera_bind_group_layout,
+            &camera_buffer,
+        );
+
+        let mut sketch_renderer = SketchRenderer::new();
+        sketch_renderer.init(
+            device,
+            format,
+            depth_format,
+            &camera_bind_group_layout,
+            &camera_buffer,
+        );
+
         let gizmo_renderer = GizmoRenderer::new(
             device,
             format,
@@ -282,27 +479,59 @@ impl Renderer {
             shadow_sampler,
             light_bind_group,
             shadow_light_bind_group,
+            unshadowed_light_buffer,
+            unshadowed_light_bind_group,
 
+            background_renderer,
             grid_renderer,
+            floor_renderer,
             mesh_renderer,
+            edge_renderer,
             axis_renderer,
             marker_renderer,
+            joint_axis_renderer,
+            sketch_renderer,
             gizmo_renderer,
             collision_renderer,
             meshes: HashMap::new(),
             selected_part: None,
+            hovered_part: None,
             show_grid: true,
             show_axes: true,
             show_markers: true,
             show_gizmo: true,
+            render_mode: RenderMode::default(),
+            section_plane: None,
+            camera_moving: false,
+            fast_orbit: FastOrbitConfig::default(),
+            auto_clip_planes: false,
+            content_bounds: None,
             clear_color: CLEAR_COLOR,
             shadow_map_size: SHADOW_MAP_SIZE,
+            sample_count: SAMPLE_COUNT,
+            supported_sample_counts: GUARANTEED_SAMPLE_COUNTS.to_vec(),
             format,
             width,
             height,
+            sub_renderer_size: (width, height),
+            sub_renderer_sample_count: SAMPLE_COUNT,
         }
     }
 
+    /// Creates a new renderer and applies `config` to it.
+    pub fn with_config(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        config: &RendererConfig,
+    ) -> Self {
+        let mut renderer = Self::new(device, format, width, height);
+        renderer.apply_config(config, device, queue);
+        renderer
+    }
+
     // ========== Camera accessors ==========
 
     /// Get a reference to the camera.
@@ -315,6 +544,13 @@ impl Renderer {
         &mut self.camera
     }
 
+    /// Replace the camera, keeping the aspect ratio of the current viewport.
+    pub fn set_camera(&mut self, mut camera: Camera) {
+        self.scene.mark_dirty();
+        camera.update_aspect(self.width as f32 / self.height.max(1) as f32);
+        self.camera = camera;
+    }
+
     // ========== Light accessors ==========
 
     /// Get a reference to the directional light.
@@ -324,28 +560,33 @@ impl Renderer {
 
     /// Get a mutable reference to the directional light.
     pub fn light_mut(&mut self) -> &mut DirectionalLight {
+        self.scene.mark_dirty();
         &mut self.light
     }
 
     /// Set light direction (convenience method).
     pub fn set_light_direction(&mut self, direction: Vec3) {
+        self.scene.mark_dirty();
         self.light.set_direction(direction);
     }
 
     /// Set light color and intensity (convenience method).
     pub fn set_light_color(&mut self, color: Vec3, intensity: f32) {
+        self.scene.mark_dirty();
         self.light.color = color;
         self.light.intensity = intensity;
     }
 
     /// Set ambient lighting (convenience method).
     pub fn set_ambient(&mut self, color: Vec3, strength: f32) {
+        self.scene.mark_dirty();
         self.light.ambient_color = color;
         self.light.ambient_strength = strength;
     }
 
     /// Enable or disable shadows.
     pub fn set_shadows_enabled(&mut self, enabled: bool) {
+        self.scene.mark_dirty();
         self.light.shadows_enabled = enabled;
     }
 
@@ -363,6 +604,7 @@ impl Renderer {
 
     /// Set whether the grid is visible.
     pub fn set_show_grid(&mut self, show: bool) {
+        self.scene.mark_dirty();
         self.show_grid = show;
     }
 
@@ -373,6 +615,7 @@ impl Renderer {
 
     /// Set whether axes are visible.
     pub fn set_show_axes(&mut self, show: bool) {
+        self.scene.mark_dirty();
         self.show_axes = show;
     }
 
@@ -383,9 +626,35 @@ impl Renderer {
 
     /// Set whether markers are visible.
     pub fn set_show_markers(&mut self, show: bool) {
+        self.scene.mark_dirty();
         self.show_markers = show;
     }
 
+    /// Get which of visual meshes and collision shapes are drawn.
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    /// Set which of visual meshes and collision shapes are drawn.
+    ///
+    /// Also shows or hides the collision renderer to match.
+    pub fn set_render_mode(&mut self, mode: RenderMode) {
+        self.scene.mark_dirty();
+        self.render_mode = mode;
+        self.collision_renderer.set_visible(mode.shows_collisions());
+    }
+
+    /// Get the section plane, if clipping is on.
+    pub fn section_plane(&self) -> Option<Plane> {
+        self.section_plane
+    }
+
+    /// Clip part meshes on the positive side of a plane, or stop clipping.
+    pub fn set_section_plane(&mut self, plane: Option<Plane>) {
+        self.scene.mark_dirty();
+        self.section_plane = plane;
+    }
+
     /// Get whether the gizmo rendering is enabled.
     pub fn is_gizmo_enabled(&self) -> bool {
         self.show_gizmo
@@ -393,6 +662,7 @@ impl Renderer {
 
     /// Set whether the gizmo rendering is enabled.
     pub fn set_gizmo_enabled(&mut self, enabled: bool) {
+        self.scene.mark_dirty();
         self.show_gizmo = enabled;
     }
 
@@ -412,6 +682,7 @@ impl Renderer {
         device: &wgpu::Device,
         width: u32,
         height: u32,
+        sample_count: u32,
     ) -> (wgpu::Texture, wgpu::TextureView) {
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Depth Texture"),
@@ -421,7 +692,7 @@ impl Renderer {
                 depth_or_array_layers: 1,
             },
             mip_level_count: 1,
-            sample_count: SAMPLE_COUNT,
+            sample_count,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Depth32Float,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
@@ -436,8 +707,9 @@ impl Renderer {
         format: wgpu::TextureFormat,
         width: u32,
         height: u32,
+        sample_count: u32,
     ) -> Option<(wgpu::Texture, wgpu::TextureView)> {
-        if SAMPLE_COUNT <= 1 {
+        if sample_count <= 1 {
             return None;
         }
         let texture = device.create_texture(&wgpu::TextureDescriptor {
@@ -448,7 +720,7 @@ impl Renderer {
                 depth_or_array_layers: 1,
             },
             mip_level_count: 1,
-            sample_count: SAMPLE_COUNT,
+            sample_count,
             dimension: wgpu::TextureDimension::D2,
             format,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -524,18 +796,31 @@ impl Renderer {
 
     /// Resizes the renderer's textures for a new viewport size.
     pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.scene.mark_dirty();
         if width == 0 || height == 0 {
             return;
         }
         self.width = width;
         self.height = height;
         self.camera.update_aspect(width as f32 / height as f32);
-        let (depth_texture, depth_view) = Self::create_depth_texture(device, width, height);
+        self.recreate_targets(device);
+    }
+
+    /// Recreate the depth and MSAA color textures at the current size and
+    /// sample count
+    fn recreate_targets(&mut self, device: &wgpu::Device) {
+        let (depth_texture, depth_view) =
+            Self::create_depth_texture(device, self.width, self.height, self.sample_count);
         self.depth_texture = depth_texture;
         self.depth_view = depth_view;
 
-        // Recreate MSAA texture
-        let msaa_result = Self::create_msaa_texture(device, self.format, width, height);
+        let msaa_result = Self::create_msaa_texture(
+            device,
+            self.format,
+            self.width,
+            self.height,
+            self.sample_count,
+        );
         let (msaa_texture, msaa_view) = match msaa_result {
             Some((tex, view)) => (Some(tex), Some(view)),
             None => (None, None),
@@ -544,13 +829,91 @@ impl Renderer {
         self.msaa_view = msaa_view;
     }
 
+    /// Look up which MSAA sample counts the adapter supports for the color
+    /// and depth formats
+    ///
+    /// Counts beyond the ones WebGPU guarantees are only usable when the
+    /// device was created with
+    /// [`TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`](wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES).
+    /// Until this is called only the guaranteed counts are used.
+    pub fn detect_sample_counts(&mut self, adapter: &wgpu::Adapter, device: &wgpu::Device) {
+        if !device
+            .features()
+            .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        {
+            self.supported_sample_counts = GUARANTEED_SAMPLE_COUNTS.to_vec();
+            return;
+        }
+        let color = adapter.get_texture_format_features(self.format).flags;
+        let depth = adapter
+            .get_texture_format_features(wgpu::TextureFormat::Depth32Float)
+            .flags;
+        self.supported_sample_counts = [1, 2, 4, 8]
+            .into_iter()
+            .filter(|&count| {
+                count == 1
+                    || (color.sample_count_supported(count) && depth.sample_count_supported(count))
+            })
+            .collect();
+    }
+
+    /// MSAA sample counts [`set_sample_count`](Self::set_sample_count) accepts
+    pub fn supported_sample_counts(&self) -> &[u32] {
+        &self.supported_sample_counts
+    }
+
+    /// Switch the main pass to another MSAA sample count
+    ///
+    /// Recreates the depth and MSAA targets and every built-in pipeline
+    /// drawing into them; custom sub-renderers follow on the next
+    /// [`prepare_sub_renderers`](Self::prepare_sub_renderers). Counts the
+    /// formats cannot render with fall back to 4, which WebGPU always
+    /// supports; [`sample_count`](Self::sample_count) reports the count in
+    /// use.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        let sample_count = if self.supported_sample_counts.contains(&sample_count) {
+            sample_count
+        } else {
+            4
+        };
+        if sample_count == self.sample_count {
+            return;
+        }
+        self.scene.mark_dirty();
+        self.sample_count = sample_count;
+        self.recreate_targets(device);
+
+        self.background_renderer
+            .set_sample_count(device, sample_count);
+        self.grid_renderer.set_sample_count(device, sample_count);
+        self.mesh_renderer.set_sample_count(device, sample_count);
+        self.edge_renderer.set_sample_count(device, sample_count);
+        self.axis_renderer.set_sample_count(device, sample_count);
+        self.marker_renderer.set_sample_count(device, sample_count);
+        self.joint_axis_renderer
+            .set_sample_count(device, sample_count);
+        self.sketch_renderer.set_sample_count(device, sample_count);
+        self.gizmo_renderer.set_sample_count(device, sample_count);
+        self.collision_renderer
+            .set_sample_count(device, sample_count);
+    }
+
     /// Returns the texture format used by the renderer.
     pub fn format(&self) -> wgpu::TextureFormat {
         self.format
     }
 
+    /// Camera uniform including the section plane.
+    fn camera_uniform(&self) -> CameraUniform {
+        let mut uniform = self.camera.uniform();
+        if let Some(plane) = self.section_plane {
+            uniform.clip_plane = plane.normal.extend(plane.distance).to_array();
+        }
+        uniform
+    }
+
     fn update_camera(&self, queue: &wgpu::Queue) {
-        let camera_uniform = self.camera.uniform();
+        let camera_uniform = self.camera_uniform();
         queue.write_buffer(
             &self.camera_buffer,
             0,
@@ -561,43 +924,121 @@ impl Renderer {
     fn update_light(&self, queue: &wgpu::Queue) {
         // Use camera target as scene center for shadow projection
         let scene_center = self.camera.target;
-        let light_uniform = self.light.uniform(scene_center);
+        let mut light_uniform = self.light.uniform(scene_center);
         queue.write_buffer(
             &self.light_buffer,
             0,
             bytemuck::cast_slice(&[light_uniform]),
         );
+
+        light_uniform.shadow_params[3] = 0.0;
+        queue.write_buffer(
+            &self.unshadowed_light_buffer,
+            0,
+            bytemuck::cast_slice(&[light_uniform]),
+        );
     }
 
     /// Add a part to the renderer.
     ///
     /// Returns the part's UUID for reference.
     pub fn add_part(&mut self, device: &wgpu::Device, part: &Part) -> Uuid {
+        self.scene.mark_dirty();
         tracing::info!("Renderer::add_part called for '{}'", part.name);
         let data = MeshData::from_part(device, part);
         let bind_group = self.mesh_renderer.create_instance_bind_group(device, &data);
 
-        self.meshes.insert(part.id, MeshEntry { data, bind_group });
+        let mut entry = MeshEntry::new(device, data, bind_group, part);
+        entry.edges = Some(EdgeMesh::from_part(part));
+        self.meshes.insert(part.id, entry);
         tracing::info!("Renderer now has {} meshes", self.meshes.len());
         part.id
     }
 
+    /// Add a part that shares the GPU geometry of an already added part.
+    ///
+    /// Used for copies of a part, which only differ in transform. Returns
+    /// `None` if `source_id` has not been added.
+    pub fn add_part_instance(
+        &mut self,
+        device: &wgpu::Device,
+        source_id: Uuid,
+        part: &Part,
+    ) -> Option<Uuid> {
+        self.scene.mark_dirty();
+        let source = self.meshes.get(&source_id)?;
+        let data = source.data.instance_of(device, part);
+        let edges = source.edges.clone();
+        let bind_group = self.mesh_renderer.create_instance_bind_group(device, &data);
+
+        let mut entry = MeshEntry::new(device, data, bind_group, part);
+        entry.edges = edges;
+        self.meshes.insert(part.id, entry);
+        Some(part.id)
+    }
+
     /// Update a part's transform.
     pub fn update_part_transform(&mut self, queue: &wgpu::Queue, part_id: Uuid, transform: Mat4) {
+        self.scene.mark_dirty();
         if let Some(entry) = self.meshes.get_mut(&part_id) {
-            entry.data.update_transform(queue, transform);
+            entry.transform = transform;
+            entry.write_transform(queue);
+        }
+    }
+
+    /// Set the exploded-view translation of each part.
+    ///
+    /// Only affects drawing; parts missing from `offsets` go back to their
+    /// assembled position.
+    pub fn set_explode_offsets(&mut self, queue: &wgpu::Queue, offsets: &HashMap<Uuid, Vec3>) {
+        for (id, entry) in &mut self.meshes {
+            let offset = offsets.get(id).copied().unwrap_or(Vec3::ZERO);
+            if entry.explode_offset != offset {
+                entry.explode_offset = offset;
+                entry.write_transform(queue);
+                self.scene.mark_dirty();
+            }
         }
     }
 
     /// Update a part's color.
     pub fn update_part_color(&mut self, queue: &wgpu::Queue, part_id: Uuid, color: [f32; 4]) {
+        self.scene.mark_dirty();
         if let Some(entry) = self.meshes.get_mut(&part_id) {
             entry.data.update_color(queue, color);
         }
     }
 
+    /// Update a part's PBR material factors.
+    pub fn update_part_material(
+        &mut self,
+        queue: &wgpu::Queue,
+        part_id: Uuid,
+        metallic: f32,
+        roughness: f32,
+    ) {
+        self.scene.mark_dirty();
+        if let Some(entry) = self.meshes.get_mut(&part_id) {
+            entry.data.update_material(queue, metallic, roughness);
+        }
+    }
+
+    /// Show or hide a part.
+    pub fn set_part_visible(&mut self, part_id: Uuid, visible: bool) {
+        self.scene.mark_dirty();
+        if let Some(entry) = self.meshes.get_mut(&part_id) {
+            entry.visible = visible;
+        }
+    }
+
+    /// Check whether a part is visible.
+    pub fn is_part_visible(&self, part_id: Uuid) -> bool {
+        self.meshes.get(&part_id).is_some_and(|entry| entry.visible)
+    }
+
     /// Set selected part.
     pub fn set_selected_part(&mut self, queue: &wgpu::Queue, part_id: Option<Uuid>) {
+        self.scene.mark_dirty();
         // Deselect previous
         if let Some(prev_id) = self.selected_part
             && let Some(entry) = self.meshes.get_mut(&prev_id)
@@ -607,6 +1048,7 @@ impl Renderer {
 
         // Select new
         self.selected_part = part_id;
+        self.scene.set_selected(part_id);
         if let Some(id) = part_id
             && let Some(entry) = self.meshes.get_mut(&id)
         {
@@ -614,6 +1056,53 @@ impl Renderer {
         }
     }
 
+    /// Highlight every part in `part_ids` as selected, with `primary` as the active part.
+    pub fn set_selected_parts(
+        &mut self,
+        queue: &wgpu::Queue,
+        primary: Option<Uuid>,
+        part_ids: &HashSet<Uuid>,
+    ) {
+        self.scene.mark_dirty();
+        for (id, entry) in &mut self.meshes {
+            entry.data.set_selected(queue, part_ids.contains(id));
+        }
+        self.selected_part = primary;
+        self.scene.set_selected(primary);
+    }
+
+    /// Highlight the given parts as colliding and clear the highlight on all others.
+    pub fn set_colliding_parts(&mut self, queue: &wgpu::Queue, part_ids: &HashSet<Uuid>) {
+        self.scene.mark_dirty();
+        for (id, entry) in &mut self.meshes {
+            entry.data.set_colliding(queue, part_ids.contains(id));
+        }
+    }
+
+    /// Highlight the part under the cursor, distinct from the selection tint.
+    pub fn set_hovered_part(&mut self, queue: &wgpu::Queue, part_id: Option<Uuid>) {
+        self.scene.mark_dirty();
+        if part_id == self.hovered_part {
+            return;
+        }
+        if let Some(prev_id) = self.hovered_part
+            && let Some(entry) = self.meshes.get_mut(&prev_id)
+        {
+            entry.data.set_hovered(queue, false);
+        }
+        self.hovered_part = part_id;
+        if let Some(id) = part_id
+            && let Some(entry) = self.meshes.get_mut(&id)
+        {
+            entry.data.set_hovered(queue, true);
+        }
+    }
+
+    /// Get the currently hovered part ID.
+    pub fn hovered_part(&self) -> Option<Uuid> {
+        self.hovered_part
+    }
+
     /// Get the currently selected part ID.
     pub fn selected_part(&self) -> Option<Uuid> {
         self.selected_part
@@ -621,16 +1110,24 @@ impl Renderer {
 
     /// Remove a part - O(1) operation with UUID-based storage.
     pub fn remove_part(&mut self, part_id: Uuid) {
+        self.scene.mark_dirty();
         self.meshes.remove(&part_id);
         if self.selected_part == Some(part_id) {
             self.selected_part = None;
+            self.scene.set_selected(None);
+        }
+        if self.hovered_part == Some(part_id) {
+            self.hovered_part = None;
         }
     }
 
     /// Clear all parts.
     pub fn clear_parts(&mut self) {
+        self.scene.mark_dirty();
         self.meshes.clear();
         self.selected_part = None;
+        self.scene.set_selected(None);
+        self.hovered_part = None;
     }
 
     /// Check if a part exists.
@@ -645,32 +1142,51 @@ impl Renderer {
 
     /// Update axis display
     pub fn update_axes(&mut self, queue: &wgpu::Queue, instances: &[AxisInstance]) {
+        self.scene.mark_dirty();
         self.axis_renderer.update_instances(queue, instances);
     }
 
     /// Update marker display
     pub fn update_markers(&mut self, queue: &wgpu::Queue, instances: &[MarkerInstance]) {
+        self.scene.mark_dirty();
         self.marker_renderer.update_instances(queue, instances);
     }
 
     /// Update selected marker display (rendered on top)
     pub fn update_selected_markers(&mut self, queue: &wgpu::Queue, instances: &[MarkerInstance]) {
+        self.scene.mark_dirty();
         self.marker_renderer
             .update_selected_instances(queue, instances);
     }
 
+    /// Update joint axis and limit display
+    pub fn update_joint_axes(&mut self, queue: &wgpu::Queue, axes: &[JointAxisInstance]) {
+        self.scene.mark_dirty();
+        self.joint_axis_renderer.update(queue, axes);
+    }
+
+    /// Replace the displayed sketches
+    pub fn set_sketches(&mut self, device: &wgpu::Device, sketches: Vec<SketchRenderData>) {
+        self.scene.mark_dirty();
+        self.sketch_renderer.set_sketches(sketches);
+        self.sketch_renderer.upload(device);
+    }
+
     /// Show gizmo at position
     pub fn show_gizmo(&mut self, queue: &wgpu::Queue, position: glam::Vec3, scale: f32) {
+        self.scene.mark_dirty();
         self.gizmo_renderer.show(queue, position, scale);
     }
 
     /// Hide gizmo
     pub fn hide_gizmo(&mut self) {
+        self.scene.mark_dirty();
         self.gizmo_renderer.hide();
     }
 
     /// Set gizmo highlighted axis
     pub fn set_gizmo_highlight(&mut self, queue: &wgpu::Queue, axis: GizmoAxis) {
+        self.scene.mark_dirty();
         self.gizmo_renderer.set_highlighted(queue, axis);
     }
 
@@ -692,6 +1208,7 @@ impl Renderer {
 
     /// Set gizmo mode
     pub fn set_gizmo_mode(&mut self, mode: GizmoMode) {
+        self.scene.mark_dirty();
         self.gizmo_renderer.set_mode(mode);
     }
 
@@ -702,6 +1219,7 @@ impl Renderer {
 
     /// Set gizmo coordinate space
     pub fn set_gizmo_space(&mut self, queue: &wgpu::Queue, space: GizmoSpace) {
+        self.scene.mark_dirty();
         self.gizmo_renderer.set_space(queue, space);
     }
 
@@ -712,6 +1230,7 @@ impl Renderer {
 
     /// Set object rotation for local coordinate space
     pub fn set_gizmo_object_rotation(&mut self, queue: &wgpu::Queue, rotation: glam::Quat) {
+        self.scene.mark_dirty();
         self.gizmo_renderer.set_object_rotation(queue, rotation);
     }
 
@@ -729,6 +1248,7 @@ impl Renderer {
 
     /// Get mutable reference to collision renderer
     pub fn collision_renderer_mut(&mut self) -> &mut CollisionRenderer {
+        self.scene.mark_dirty();
         &mut self.collision_renderer
     }
 
@@ -737,6 +1257,27 @@ impl Renderer {
         &self.collision_renderer
     }
 
+    /// Meshes that get crease and silhouette edges this frame.
+    fn edged_meshes(&self) -> impl Iterator<Item = &MeshEntry> {
+        let shown = self.edge_renderer.config().enabled && self.render_mode != RenderMode::XRay;
+        self.meshes.values().filter(move |e| {
+            shown
+                && e.visible
+                && self.render_mode.shows_visuals()
+                && std::ptr::eq(self.draw_data(e), &e.data)
+        })
+    }
+
+    /// Extract and upload the edges of meshes about to be drawn with them.
+    ///
+    /// Edges are built on first use, so call this before [`render`](Self::render)
+    /// while edges are enabled.
+    pub fn prepare_edges(&self, device: &wgpu::Device) {
+        for edges in self.edged_meshes().filter_map(|e| e.edges.as_ref()) {
+            edges.prepare(device);
+        }
+    }
+
     /// Render the scene.
     pub fn render(
         &self,
@@ -774,10 +1315,14 @@ impl Renderer {
                 1.0,
             );
 
-            for entry in self.meshes.values() {
+            let shadow_casters = self
+                .meshes
+                .values()
+                .filter(|e| e.visible && self.render_mode.shows_visuals());
+            for entry in shadow_casters {
                 self.mesh_renderer.render_shadow(
                     &mut shadow_pass,
-                    &entry.data,
+                    self.draw_data(entry),
                     &entry.bind_group,
                     &self.shadow_light_bind_group,
                 );
@@ -825,34 +1370,78 @@ impl Renderer {
             occlusion_query_set: None,
         });
 
+        // Gradient background (solid backgrounds come from the clear color)
+        self.background_renderer.draw(&mut render_pass);
+
+        // Floor is drawn before the grid so a grid at floor height stays visible
+        let floor_light_bind_group = if self.floor_renderer.config().receive_shadows {
+            &self.light_bind_group
+        } else {
+            &self.unshadowed_light_bind_group
+        };
+        self.floor_renderer.render(
+            &mut render_pass,
+            &self.mesh_renderer,
+            floor_light_bind_group,
+        );
+
         // Render grid
         if self.show_grid {
-            self.grid_renderer.render(&mut render_pass);
+            self.grid_renderer.draw(&mut render_pass);
         }
 
         // Render meshes with lighting and shadows
-        for entry in self.meshes.values() {
-            self.mesh_renderer.render(
-                &mut render_pass,
-                &entry.data,
-                &entry.bind_group,
-                &self.light_bind_group,
-            );
+        let visible_meshes = self
+            .meshes
+            .values()
+            .filter(|e| e.visible && self.render_mode.shows_visuals());
+        for entry in visible_meshes {
+            if self.render_mode == RenderMode::XRay {
+                self.mesh_renderer.render_xray(
+                    &mut render_pass,
+                    self.draw_data(entry),
+                    &entry.bind_group,
+                    &self.light_bind_group,
+                    XRAY_VISUAL_OPACITY,
+                );
+            } else {
+                self.mesh_renderer.render(
+                    &mut render_pass,
+                    self.draw_data(entry),
+                    &entry.bind_group,
+                    &self.light_bind_group,
+                );
+            }
         }
 
+        // Crease and silhouette edges over the shaded meshes
+        for entry in self.edged_meshes() {
+            if let Some(edges) = &entry.edges {
+                self.edge_renderer
+                    .render(&mut render_pass, edges, &entry.bind_group);
+            }
+        }
+
+        // Sketch curves and points on their planes
+        self.sketch_renderer.draw(&mut render_pass);
+
         // Render axes
         if self.show_axes {
             self.axis_renderer.render(&mut render_pass);
         }
 
-        // Render markers
+        // Render markers and joint axes
         if self.show_markers {
             self.marker_renderer.render(&mut render_pass);
+            self.joint_axis_renderer.render(&mut render_pass);
         }
 
         // Render collision shapes (semi-transparent, after markers)
         self.collision_renderer.render(&mut render_pass);
 
+        // Custom sub-renderers registered by the embedding application
+        self.registry.render_all(&mut render_pass, &self.scene);
+
         // Render gizmo (always on top)
         if self.show_gizmo {
             self.gizmo_renderer.render(&mut render_pass);
@@ -889,6 +1478,7 @@ impl Renderer {
 
     /// Get a mutable reference to the mesh manager.
     pub fn mesh_manager_mut(&mut self) -> &mut MeshManager {
+        self.scene.mark_dirty();
         &mut self.mesh_manager
     }
 
@@ -902,45 +1492,114 @@ impl Renderer {
 
     /// Get a mutable reference to the renderer registry.
     pub fn registry_mut(&mut self) -> &mut RendererRegistry {
+        self.scene.mark_dirty();
         &mut self.registry
     }
 
+    /// Initialize a custom sub-renderer and add it to the registry.
+    ///
+    /// `ctx` must share the device and formats the renderer draws with.
+    /// The sub-renderer is drawn at its [`SubRenderer::priority`] relative
+    /// to other registered sub-renderers.
+    pub fn register_sub_renderer(
+        &mut self,
+        ctx: &RenderContext,
+        mut renderer: Box<dyn SubRenderer>,
+    ) {
+        self.scene.mark_dirty();
+        renderer.on_init(ctx);
+        self.registry.register(renderer);
+    }
+
+    /// Prepare custom sub-renderers for the next frame.
+    ///
+    /// Forwards any resize and sample count change since the last call, then
+    /// copies the renderer's camera into `ctx` so sub-renderers binding
+    /// [`RenderContext::camera_bind_group`] see the same view. Keep `ctx`'s
+    /// sample count in step with [`sample_count`](Self::sample_count) through
+    /// [`RenderContext::set_sample_count`].
+    pub fn prepare_sub_renderers(&mut self, ctx: &RenderContext) {
+        self.scene.mark_dirty();
+        let size = (self.width, self.height);
+        if self.sub_renderer_size != size {
+            self.registry.resize_all(ctx, size.0, size.1);
+            self.sub_renderer_size = size;
+        }
+        if self.sub_renderer_sample_count != ctx.sample_count() {
+            self.registry.sample_count_changed_all(ctx);
+            self.sub_renderer_sample_count = ctx.sample_count();
+        }
+        ctx.update_camera(&self.camera_uniform());
+        self.registry.prepare_all(ctx, &self.scene);
+    }
+
     // ========== Configuration Methods ==========
 
     /// Apply a full renderer configuration.
     ///
-    /// This updates all renderer settings from the provided config.
-    /// Note: Some settings like MSAA require a restart to take effect.
+    /// This updates all renderer settings from the provided config, taking
+    /// effect from the next frame.
     pub fn apply_config(
         &mut self,
         config: &RendererConfig,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
     ) {
-        self.apply_grid_config(&config.grid, device);
-        self.apply_viewport_config(&config.viewport);
+        self.scene.mark_dirty();
+        self.apply_grid_config(&config.grid, device, queue);
+        self.apply_viewport_config(&config.viewport, device, queue);
+        self.apply_floor_config(&config.floor, queue);
+        self.apply_edge_config(&config.edges, queue);
         self.apply_shadow_config(&config.shadow, device);
         self.apply_lighting_config(&config.lighting);
         self.apply_camera_config(&config.camera);
         self.apply_gizmo_config(&config.gizmo, queue);
+        self.apply_fast_orbit_config(&config.fast_orbit);
     }
 
     /// Apply grid configuration.
-    pub fn apply_grid_config(&mut self, config: &GridConfig, device: &wgpu::Device) {
+    pub fn apply_grid_config(
+        &mut self,
+        config: &GridConfig,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        self.scene.mark_dirty();
         self.show_grid = config.enabled;
-        // Rebuild grid with new parameters
-        self.grid_renderer.rebuild(
-            device,
-            config.size,
-            config.spacing,
-            config.line_color,
-            config.x_axis_color,
-            config.y_axis_color,
-        );
+        self.grid_renderer.set_config(config.clone());
+        self.grid_renderer.upload(device, queue);
+    }
+
+    /// Get the current grid configuration.
+    pub fn grid_config(&self) -> &GridConfig {
+        self.grid_renderer.config()
+    }
+
+    /// Apply floor plane configuration.
+    pub fn apply_floor_config(&mut self, config: &FloorConfig, queue: &wgpu::Queue) {
+        self.scene.mark_dirty();
+        self.floor_renderer.set_config(queue, config);
+    }
+
+    /// Get the current floor configuration.
+    pub fn floor_config(&self) -> &FloorConfig {
+        self.floor_renderer.config()
+    }
+
+    /// Apply mesh edge configuration.
+    pub fn apply_edge_config(&mut self, config: &EdgeConfig, queue: &wgpu::Queue) {
+        self.scene.mark_dirty();
+        self.edge_renderer.set_config(queue, config);
+    }
+
+    /// Get the current mesh edge configuration.
+    pub fn edge_config(&self) -> &EdgeConfig {
+        self.edge_renderer.config()
     }
 
     /// Apply shadow configuration.
     pub fn apply_shadow_config(&mut self, config: &ShadowConfig, device: &wgpu::Device) {
+        self.scene.mark_dirty();
         self.light.shadows_enabled = config.enabled;
         self.light.shadow_bias = config.bias;
         self.light.shadow_normal_bias = config.normal_bias;
@@ -970,10 +1629,18 @@ impl Renderer {
             &self.shadow_view,
             &self.shadow_sampler,
         );
+        self.unshadowed_light_bind_group = Self::create_light_bind_group(
+            device,
+            self.mesh_renderer.light_bind_group_layout(),
+            &self.unshadowed_light_buffer,
+            &self.shadow_view,
+            &self.shadow_sampler,
+        );
     }
 
     /// Apply lighting configuration.
     pub fn apply_lighting_config(&mut self, config: &LightingConfig) {
+        self.scene.mark_dirty();
         self.light.set_direction(Vec3::from_array(config.direction));
         self.light.color = Vec3::from_array(config.color);
         self.light.intensity = config.intensity;
@@ -983,14 +1650,58 @@ impl Renderer {
 
     /// Apply camera configuration.
     pub fn apply_camera_config(&mut self, config: &CameraConfig) {
+        self.scene.mark_dirty();
         self.camera.set_fov_degrees(config.fov_degrees);
         self.camera.set_near(config.near_plane);
         self.camera.set_far(config.far_plane);
+        self.auto_clip_planes = config.auto_clip_planes;
+        self.content_bounds = None;
         // Note: sensitivity values are used by the frontend, not stored here
     }
 
+    /// Get whether clip planes follow the content bounds.
+    pub fn auto_clip_planes(&self) -> bool {
+        self.auto_clip_planes
+    }
+
+    /// Bounds of the visible part meshes and scene objects.
+    pub fn content_bounds(&self) -> Option<BoundingBox> {
+        self.meshes
+            .values()
+            .filter(|entry| entry.visible)
+            .map(|entry| entry.bounds.transform(&entry.world_transform()))
+            .chain(self.scene.compute_bounds())
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// Fit the camera's clip planes tightly around the content, if auto
+    /// clip planes are on.
+    ///
+    /// Call once per frame before rendering. The content bounds are only
+    /// recomputed after the scene changed; with no content the planes are
+    /// left alone.
+    pub fn fit_clip_planes(&mut self) {
+        if !self.auto_clip_planes {
+            return;
+        }
+        if self.scene.is_dirty() || self.content_bounds.is_none() {
+            self.content_bounds = self.content_bounds();
+        }
+        let Some(bounds) = self.content_bounds else {
+            return;
+        };
+
+        let forward = (self.camera.target - self.camera.position).normalize_or(Vec3::NEG_Z);
+        let (nearest, farthest) = bounds.depth_range(self.camera.position, forward);
+        let margin = bounds.radius() * AUTO_CLIP_MARGIN;
+        let far = (farthest + margin).max(f32::EPSILON);
+        self.camera.far = far;
+        self.camera.near = (nearest - margin).max(far * AUTO_CLIP_MIN_NEAR_RATIO);
+    }
+
     /// Apply gizmo configuration.
     pub fn apply_gizmo_config(&mut self, config: &GizmoConfig, queue: &wgpu::Queue) {
+        self.scene.mark_dirty();
         self.show_gizmo = config.enabled;
         // Apply axis colors from config
         self.gizmo_renderer.set_axis_colors(
@@ -1002,10 +1713,48 @@ impl Renderer {
         // Note: gizmo scale is applied per-instance when showing the gizmo
     }
 
+    /// Apply fast orbit configuration.
+    pub fn apply_fast_orbit_config(&mut self, config: &FastOrbitConfig) {
+        self.scene.mark_dirty();
+        self.fast_orbit = config.clone();
+    }
+
+    /// Set whether the camera is being moved.
+    ///
+    /// With fast orbit enabled, parts above the triangle threshold are drawn
+    /// as bounding boxes while the camera moves. Call this with `false` once
+    /// movement stops so the full meshes are drawn again.
+    pub fn set_camera_moving(&mut self, moving: bool) {
+        if self.camera_moving != moving {
+            self.camera_moving = moving;
+            if self.fast_orbit.enabled {
+                self.scene.mark_dirty();
+            }
+        }
+    }
+
+    /// Geometry to draw for a mesh entry, its bounding box during fast orbit
+    fn draw_data<'a>(&self, entry: &'a MeshEntry) -> &'a MeshData {
+        if self.fast_orbit.enabled
+            && self.camera_moving
+            && entry.triangle_count > self.fast_orbit.triangle_threshold
+        {
+            &entry.proxy
+        } else {
+            &entry.data
+        }
+    }
+
     /// Apply viewport configuration.
     ///
-    /// Note: MSAA changes require renderer recreation and are not applied here.
-    pub fn apply_viewport_config(&mut self, config: &ViewportConfig) {
+    /// An MSAA change recreates the render targets and pipelines.
+    pub fn apply_viewport_config(
+        &mut self,
+        config: &ViewportConfig,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        self.scene.mark_dirty();
         // Apply background color
         self.clear_color = wgpu::Color {
             r: config.background_color[0] as f64,
@@ -1013,11 +1762,19 @@ impl Renderer {
             b: config.background_color[2] as f64,
             a: config.background_color[3] as f64,
         };
-        // Note: MSAA changes require recreation of pipelines and textures
+        self.background_renderer.set_config(config.clone());
+        self.background_renderer.upload(queue);
+        self.set_sample_count(device, config.msaa_sample_count);
     }
 
     /// Get the current MSAA sample count.
     pub fn sample_count(&self) -> u32 {
-        SAMPLE_COUNT
+        self.sample_count
+    }
+}
+
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        self.registry.destroy_all();
     }
 }
@@ -103,7 +103,13 @@ impl BoundingBox {
     /// Note: This returns an axis-aligned bounding box that contains
     /// the transformed corners, which may be larger than optimal.
     pub fn transform(&self, transform: &Mat4) -> BoundingBox {
-        let corners = [
+        let transformed_corners = self.corners().map(|c| transform.transform_point3(c));
+        BoundingBox::from_points(transformed_corners)
+    }
+
+    /// Returns the eight corners of the bounding box.
+    pub fn corners(&self) -> [Vec3; 8] {
+        [
             Vec3::new(self.min.x, self.min.y, self.min.z),
             Vec3::new(self.max.x, self.min.y, self.min.z),
             Vec3::new(self.min.x, self.max.y, self.min.z),
@@ -112,10 +118,18 @@ impl BoundingBox {
             Vec3::new(self.max.x, self.min.y, self.max.z),
             Vec3::new(self.min.x, self.max.y, self.max.z),
             Vec3::new(self.max.x, self.max.y, self.max.z),
-        ];
+        ]
+    }
 
-        let transformed_corners = corners.map(|c| transform.transform_point3(c));
-        BoundingBox::from_points(transformed_corners)
+    /// Returns the nearest and farthest depth of the corners along a view
+    /// direction from `eye`.
+    pub fn depth_range(&self, eye: Vec3, forward: Vec3) -> (f32, f32) {
+        self.corners()
+            .map(|corner| (corner - eye).dot(forward))
+            .into_iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(near, far), depth| {
+                (near.min(depth), far.max(depth))
+            })
     }
 
     /// Returns true if the bounding box is valid (non-empty).
@@ -265,6 +279,14 @@ impl Frustum {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_bounding_box_depth_range() {
+        let bbox = BoundingBox::new(Vec3::splat(-1.0), Vec3::splat(1.0));
+        let (near, far) = bbox.depth_range(Vec3::new(0.0, 0.0, 5.0), Vec3::NEG_Z);
+        assert!((near - 4.0).abs() < 1e-6);
+        assert!((far - 6.0).abs() < 1e-6);
+    }
+
     #[test]
     fn test_bounding_box_center() {
         let bbox = BoundingBox::new(Vec3::new(-1.0, -2.0, -3.0), Vec3::new(1.0, 2.0, 3.0));
@@ -0,0 +1,34 @@
+// Viewport background shader
+//
+// Draws a single fullscreen triangle and blends the gradient colors by
+// vertical screen position. Drawn first with depth testing disabled.
+
+struct BackgroundUniform {
+    top_color: vec4<f32>,
+    bottom_color: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> background: BackgroundUniform;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // 0 at the bottom edge of the viewport, 1 at the top
+    @location(0) height: f32,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // (0,0), (2,0), (0,2) covers the whole [0,1] square
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * 2.0 - 1.0, 1.0, 1.0);
+    out.height = uv.y;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return mix(background.bottom_color, background.top_color, clamp(in.height, 0.0, 1.0));
+}
@@ -0,0 +1,84 @@
+// Feature edge shader: creases, open borders and silhouettes over meshes
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+    eye: vec4<f32>,
+    clip_plane: vec4<f32>,  // xyz = normal, w = distance; zero disables
+};
+
+// Leading field of the mesh instance uniform
+struct InstanceUniform {
+    model: mat4x4<f32>,
+};
+
+struct EdgeUniform {
+    color: vec4<f32>,
+    params: vec4<f32>,  // x = cosine of the crease angle, y = depth pull
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var<uniform> instance: InstanceUniform;
+
+@group(2) @binding(0)
+var<uniform> edge: EdgeUniform;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal_a: vec3<f32>,
+    @location(2) normal_b: vec3<f32>,  // zero on open borders
+    @location(3) midpoint: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_pos: vec3<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    let world_pos = (instance.model * vec4<f32>(in.position, 1.0)).xyz;
+    let to_eye = camera.eye.xyz - world_pos;
+
+    // Cofactor matrix: the inverse-transpose scaled by the determinant, which
+    // cancels out in the product of the two facing tests below
+    let m0 = instance.model[0].xyz;
+    let m1 = instance.model[1].xyz;
+    let m2 = instance.model[2].xyz;
+    let normal_matrix = mat3x3<f32>(cross(m1, m2), cross(m2, m0), cross(m0, m1));
+
+    // Facing is tested from the edge midpoint so both ends agree
+    let midpoint = (instance.model * vec4<f32>(in.midpoint, 1.0)).xyz;
+    let mid_to_eye = camera.eye.xyz - midpoint;
+
+    let border = dot(in.normal_b, in.normal_b) == 0.0;
+    let crease = dot(in.normal_a, in.normal_b) < edge.params.x;
+    let silhouette = dot(normal_matrix * in.normal_a, mid_to_eye)
+        * dot(normal_matrix * in.normal_b, mid_to_eye) < 0.0;
+
+    // Pull toward the camera so edges win the depth test against their faces
+    let pulled = world_pos + to_eye * edge.params.y;
+    out.clip_position = camera.view_proj * vec4<f32>(pulled, 1.0);
+    out.world_pos = world_pos;
+
+    // Edges between smoothly joined front or back faces fall outside the clip volume
+    if (!(border || crease || silhouette)) {
+        out.clip_position = vec4<f32>(0.0, 0.0, 2.0, 1.0);
+    }
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Section plane cuts away everything on its positive side
+    if (dot(camera.clip_plane.xyz, in.world_pos) + camera.clip_plane.w > 0.0) {
+        discard;
+    }
+    return edge.color;
+}
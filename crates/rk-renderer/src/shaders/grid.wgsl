@@ -1,4 +1,4 @@
-// Grid shader
+// Grid shader (fixed-size line grid)
 
 struct CameraUniform {
     view_proj: mat4x4<f32>,
@@ -7,9 +7,20 @@ struct CameraUniform {
     eye: vec4<f32>,
 };
 
+struct GridUniform {
+    minor_color: vec4<f32>,
+    major_color: vec4<f32>,
+    x_axis_color: vec4<f32>,
+    y_axis_color: vec4<f32>,
+    params: vec4<f32>,  // x = spacing, y = major every, z = fade start, w = fade end
+};
+
 @group(0) @binding(0)
 var<uniform> camera: CameraUniform;
 
+@group(1) @binding(0)
+var<uniform> grid: GridUniform;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) color: vec3<f32>,
@@ -34,6 +45,6 @@ fn vs_main(in: VertexInput) -> VertexOutput {
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Fade out based on distance from camera
     let dist = length(in.world_pos - camera.eye.xyz);
-    let fade = 1.0 - smoothstep(5.0, 15.0, dist);
+    let fade = 1.0 - smoothstep(grid.params.z, grid.params.w, dist);
     return vec4<f32>(in.color, fade * 0.8);
 }
@@ -0,0 +1,99 @@
+// Infinite grid shader
+//
+// Draws a ground-plane quad centered under the camera and computes grid lines
+// procedurally, anti-aliased with screen-space derivatives.
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+    eye: vec4<f32>,
+};
+
+struct GridUniform {
+    minor_color: vec4<f32>,
+    major_color: vec4<f32>,
+    x_axis_color: vec4<f32>,
+    y_axis_color: vec4<f32>,
+    params: vec4<f32>,  // x = spacing, y = major every, z = fade start, w = fade end
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var<uniform> grid: GridUniform;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_pos: vec3<f32>,
+};
+
+// Fade distance grows with camera height so the grid stays visible when zoomed out
+fn fade_end() -> f32 {
+    return max(grid.params.w, abs(camera.eye.z) * 8.0);
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, 1.0),
+    );
+
+    let xy = camera.eye.xy + corners[index] * fade_end();
+    let world_pos = vec3<f32>(xy, 0.0);
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(world_pos, 1.0);
+    out.world_pos = world_pos;
+    return out;
+}
+
+// Coverage of the nearest grid line for coordinates measured in cell units
+fn line_coverage(coord: vec2<f32>, deriv: vec2<f32>) -> f32 {
+    let dist = abs(fract(coord - 0.5) - 0.5) / max(deriv, vec2<f32>(1e-6));
+    return 1.0 - min(min(dist.x, dist.y), 1.0);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let spacing = max(grid.params.x, 1e-6);
+    let major_every = grid.params.y;
+    let has_major = major_every >= 2.0;
+
+    // Derivatives are computed up front to keep control flow uniform
+    let minor_coord = in.world_pos.xy / spacing;
+    let minor_deriv = fwidth(minor_coord);
+    let major_coord = minor_coord / max(major_every, 1.0);
+    let major_deriv = fwidth(major_coord);
+    let axis_deriv = fwidth(in.world_pos.xy);
+
+    // Minor lines disappear once they get denser than a couple of pixels
+    let minor_density = max(minor_deriv.x, minor_deriv.y);
+    let minor = line_coverage(minor_coord, minor_deriv) * (1.0 - smoothstep(0.3, 0.6, minor_density));
+    let major = select(0.0, line_coverage(major_coord, major_deriv), has_major);
+
+    var color = grid.minor_color.rgb;
+    var alpha = minor;
+    color = select(color, grid.major_color.rgb, major > alpha);
+    alpha = max(alpha, major);
+
+    // X axis runs along y = 0, Y axis along x = 0
+    let x_axis = 1.0 - min(abs(in.world_pos.y) / max(axis_deriv.y, 1e-6), 1.0);
+    let y_axis = 1.0 - min(abs(in.world_pos.x) / max(axis_deriv.x, 1e-6), 1.0);
+    color = select(color, grid.x_axis_color.rgb, x_axis > 0.0 && x_axis >= y_axis);
+    color = select(color, grid.y_axis_color.rgb, y_axis > 0.0 && y_axis > x_axis);
+    alpha = max(alpha, max(x_axis, y_axis));
+
+    // Fade out based on distance from camera
+    let dist = length(in.world_pos - camera.eye.xyz);
+    let end = fade_end();
+    let fade = 1.0 - smoothstep(end * grid.params.z / max(grid.params.w, 1e-6), end, dist);
+
+    return vec4<f32>(color, alpha * fade * 0.8);
+}
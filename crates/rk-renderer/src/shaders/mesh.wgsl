@@ -1,19 +1,20 @@
-// Mesh shader with Phong lighting and shadow mapping
+// Mesh shader with metallic/roughness PBR lighting and shadow mapping
 
 struct CameraUniform {
     view_proj: mat4x4<f32>,
     view: mat4x4<f32>,
     proj: mat4x4<f32>,
     eye: vec4<f32>,
+    clip_plane: vec4<f32>,  // xyz = normal, w = distance; zero disables
 };
 
 struct InstanceUniform {
     model: mat4x4<f32>,
     color: vec4<f32>,
-    selected: u32,
-    _padding1: u32,
-    _padding2: u32,
-    _padding3: u32,
+    highlight: u32,  // bit 0 = selected, bit 1 = hovered
+    metallic: f32,
+    roughness: f32,
+    colliding: u32,
 };
 
 struct LightUniform {
@@ -69,8 +70,8 @@ fn vs_main(in: VertexInput) -> VertexOutput {
     );
     out.world_normal = normalize(normal_matrix * in.normal);
 
-    // Use instance color if set, otherwise vertex color
-    out.color = instance.color;
+    // Use the vertex color if the mesh has one (negative alpha marks "none")
+    out.color = select(instance.color, in.color, in.color.a >= 0.0);
 
     // Transform position to light space for shadow mapping
     out.light_space_pos = light.light_view_proj * world_pos;
@@ -129,8 +130,46 @@ fn calculate_shadow(light_space_pos: vec4<f32>, normal: vec3<f32>, light_dir: ve
     return select(1.0, shadow, shadows_enabled && in_bounds);
 }
 
+const PI: f32 = 3.14159265359;
+
+// Reflectance at normal incidence for dielectrics
+const DIELECTRIC_F0: vec3<f32> = vec3<f32>(0.04, 0.04, 0.04);
+
+// Scale applied to direct light so default materials stay close to the previous Phong look
+const DIRECT_LIGHT_SCALE: f32 = 0.6;
+
+// GGX / Trowbridge-Reitz normal distribution
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let a = roughness * roughness;
+    let a2 = a * a;
+    let denom = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / max(PI * denom * denom, 1e-6);
+}
+
+// Schlick-GGX geometry term for a single direction
+fn geometry_schlick_ggx(n_dot_x: f32, roughness: f32) -> f32 {
+    let r = roughness + 1.0;
+    let k = (r * r) / 8.0;
+    return n_dot_x / (n_dot_x * (1.0 - k) + k);
+}
+
+// Smith geometry term combining view and light shadowing-masking
+fn geometry_smith(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    return geometry_schlick_ggx(n_dot_v, roughness) * geometry_schlick_ggx(n_dot_l, roughness);
+}
+
+// Schlick Fresnel approximation
+fn fresnel_schlick(cos_theta: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (vec3<f32>(1.0) - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Section plane cuts away everything on its positive side
+    if (dot(camera.clip_plane.xyz, in.world_pos) + camera.clip_plane.w > 0.0) {
+        discard;
+    }
+
     let light_dir = normalize(light.direction.xyz);
     let view_dir = normalize(camera.eye.xyz - in.world_pos);
     let normal = normalize(in.world_normal);
@@ -138,27 +177,49 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Calculate shadow factor
     let shadow = calculate_shadow(in.light_space_pos, normal, light_dir);
 
-    // Ambient lighting (always visible, not affected by shadow)
-    let ambient = light.ambient.rgb * light.ambient.a;
+    let albedo = in.color.rgb;
+    let metallic = clamp(instance.metallic, 0.0, 1.0);
+    // Avoid a singular highlight at zero roughness
+    let roughness = clamp(instance.roughness, 0.04, 1.0);
+    let f0 = mix(DIELECTRIC_F0, albedo, metallic);
 
-    // Diffuse lighting
-    let diff = max(dot(normal, light_dir), 0.0);
-    let diffuse = diff * light.color_intensity.rgb * light.color_intensity.a * 0.6;
-
-    // Specular lighting (Blinn-Phong)
     let halfway_dir = normalize(light_dir + view_dir);
-    let spec = pow(max(dot(normal, halfway_dir), 0.0), 32.0);
-    let specular = spec * light.color_intensity.rgb * 0.3;
+    let n_dot_l = max(dot(normal, light_dir), 0.0);
+    let n_dot_v = max(dot(normal, view_dir), 1e-4);
+    let n_dot_h = max(dot(normal, halfway_dir), 0.0);
+    let v_dot_h = max(dot(view_dir, halfway_dir), 0.0);
+
+    // Cook-Torrance specular BRDF
+    let d = distribution_ggx(n_dot_h, roughness);
+    let g = geometry_smith(n_dot_v, n_dot_l, roughness);
+    let f = fresnel_schlick(v_dot_h, f0);
+    let specular = d * g * f / max(4.0 * n_dot_v * n_dot_l, 1e-4);
 
-    // Combine: ambient is always visible, diffuse and specular are shadowed
-    let lighting = ambient + (diffuse + specular) * shadow;
+    // Energy not reflected specularly is diffused; metals have no diffuse term
+    let k_diffuse = (vec3<f32>(1.0) - f) * (1.0 - metallic);
+    let diffuse = k_diffuse * albedo;
 
-    var color = in.color.rgb * lighting;
+    let radiance = light.color_intensity.rgb * light.color_intensity.a * DIRECT_LIGHT_SCALE;
+    let direct = (diffuse + specular * PI) * radiance * n_dot_l;
+
+    // Ambient lighting (always visible, not affected by shadow)
+    let ambient = light.ambient.rgb * light.ambient.a * mix(albedo, f0, metallic);
+
+    // Combine: ambient is always visible, direct light is shadowed
+    var color = ambient + direct * shadow;
 
     // Selection highlight
-    if (instance.selected == 1u) {
+    if ((instance.highlight & 1u) != 0u) {
         // Add orange tint for selected objects
         color = mix(color, vec3<f32>(1.0, 0.6, 0.2), 0.3);
+    } else if ((instance.highlight & 2u) != 0u) {
+        // Subtle brightening for the part under the cursor
+        color = mix(color, vec3<f32>(1.0, 1.0, 1.0), 0.12);
+    }
+
+    // Self-collision highlight
+    if (instance.colliding == 1u) {
+        color = mix(color, vec3<f32>(1.0, 0.1, 0.1), 0.6);
     }
 
     return vec4<f32>(color, in.color.a);
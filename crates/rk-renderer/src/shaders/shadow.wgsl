@@ -11,10 +11,10 @@ struct LightUniform {
 struct InstanceUniform {
     model: mat4x4<f32>,
     color: vec4<f32>,
-    selected: u32,
-    _padding1: u32,
-    _padding2: u32,
-    _padding3: u32,
+    highlight: u32,  // bit 0 = selected, bit 1 = hovered
+    metallic: f32,
+    roughness: f32,
+    colliding: u32,
 };
 
 @group(0) @binding(0)
@@ -6,12 +6,12 @@ use wgpu::util::DeviceExt;
 
 use crate::constants::instances;
 use crate::instanced::InstanceBuffer;
-use crate::pipeline::{PipelineConfig, create_camera_bind_group};
+use crate::pipeline::{MsaaPipeline, PipelineConfig, create_camera_bind_group};
 use crate::vertex::{PositionColorVertex, mat4_instance_attributes};
 
 /// Axis instance data - passed as vertex instance
 #[repr(C)]
-#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
 pub struct AxisInstance {
     /// Transformation matrix for this axis instance.
     pub transform: [[f32; 4]; 4],
@@ -33,7 +33,7 @@ impl Default for AxisInstance {
 
 /// Axis renderer for coordinate frame visualization
 pub struct AxisRenderer {
-    pipeline: wgpu::RenderPipeline,
+    pipeline: MsaaPipeline,
     vertex_buffer: wgpu::Buffer,
     vertex_count: u32,
     instances: InstanceBuffer<AxisInstance>,
@@ -117,6 +117,11 @@ impl AxisRenderer {
         self.update_instances(queue, &[instance]);
     }
 
+    /// Recreate the pipeline for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.pipeline.set_sample_count(device, sample_count);
+    }
+
     /// Renders all axis instances.
     pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         if self.instances.is_empty() {
@@ -0,0 +1,216 @@
+//! Background sub-renderer implementing the SubRenderer trait.
+//!
+//! The solid background is produced by the main pass clear color; this
+//! renderer only draws when [`ViewportConfig::background_mode`] is
+//! [`BackgroundMode::Gradient`], filling the viewport with a fullscreen
+//! triangle before anything else is drawn.
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use crate::config::{BackgroundMode, ViewportConfig};
+use crate::context::RenderContext;
+use crate::pipeline::{MsaaPipeline, PipelineConfig};
+use crate::scene::Scene;
+use crate::traits::{ConfigurableSubRenderer, SubRenderer};
+
+/// Background uniform buffer data sent to GPU (32 bytes)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct BackgroundUniform {
+    top_color: [f32; 4],
+    bottom_color: [f32; 4],
+}
+
+impl BackgroundUniform {
+    fn from_config(config: &ViewportConfig) -> Self {
+        Self {
+            top_color: config.gradient_top_color,
+            bottom_color: config.gradient_bottom_color,
+        }
+    }
+}
+
+/// GPU resources created on initialization.
+struct BackgroundGpu {
+    pipeline: MsaaPipeline,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+}
+
+/// Background sub-renderer for gradient viewport backgrounds.
+pub struct BackgroundSubRenderer {
+    enabled: bool,
+    config: ViewportConfig,
+    /// Set when the config changed and the uniform buffer needs updating
+    dirty: bool,
+    gpu: Option<BackgroundGpu>,
+}
+
+impl BackgroundSubRenderer {
+    /// Creates a new background sub-renderer.
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            config: ViewportConfig::default(),
+            dirty: false,
+            gpu: None,
+        }
+    }
+
+    /// Creates GPU resources without a [`RenderContext`].
+    ///
+    /// Used by the main [`Renderer`](crate::Renderer).
+    pub fn init(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+    ) {
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Background Uniform Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let pipeline = PipelineConfig::new(
+            "Background",
+            include_str!("../shaders/background.wgsl"),
+            format,
+            depth_format,
+            &[&uniform_bind_group_layout],
+        )
+        .with_blend(wgpu::BlendState::REPLACE)
+        .without_depth_test()
+        .build(device);
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Background Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[BackgroundUniform::from_config(&self.config)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Background Uniform Bind Group"),
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        self.gpu = Some(BackgroundGpu {
+            pipeline,
+            uniform_buffer,
+            uniform_bind_group,
+        });
+        self.dirty = false;
+    }
+
+    /// Recreate the pipeline for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        if let Some(gpu) = &mut self.gpu {
+            gpu.pipeline.set_sample_count(device, sample_count);
+        }
+    }
+
+    /// Update the uniform buffer if the configuration changed since the last upload.
+    pub fn upload(&mut self, queue: &wgpu::Queue) {
+        if !self.dirty {
+            return;
+        }
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+
+        queue.write_buffer(
+            &gpu.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[BackgroundUniform::from_config(&self.config)]),
+        );
+        self.dirty = false;
+    }
+
+    /// Record draw commands for the background (no-op in solid mode).
+    pub fn draw<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>) {
+        if self.config.background_mode != BackgroundMode::Gradient {
+            return;
+        }
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+
+        pass.set_pipeline(&gpu.pipeline);
+        pass.set_bind_group(0, &gpu.uniform_bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+impl Default for BackgroundSubRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubRenderer for BackgroundSubRenderer {
+    fn name(&self) -> &str {
+        "background"
+    }
+
+    fn priority(&self) -> i32 {
+        super::priorities::BACKGROUND
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn on_init(&mut self, ctx: &RenderContext) {
+        self.init(ctx.device(), ctx.surface_format(), ctx.depth_format());
+        self.set_sample_count(ctx.device(), ctx.sample_count());
+    }
+
+    fn on_sample_count_changed(&mut self, ctx: &RenderContext) {
+        self.set_sample_count(ctx.device(), ctx.sample_count());
+    }
+
+    fn on_resize(&mut self, _ctx: &RenderContext, _width: u32, _height: u32) {
+        // The fullscreen triangle is resolution independent
+    }
+
+    fn prepare(&mut self, ctx: &RenderContext, _scene: &Scene) {
+        self.upload(ctx.queue());
+    }
+
+    fn render<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>, _scene: &Scene) {
+        self.draw(pass);
+    }
+}
+
+impl ConfigurableSubRenderer for BackgroundSubRenderer {
+    type Config = ViewportConfig;
+
+    fn config(&self) -> &ViewportConfig {
+        &self.config
+    }
+
+    fn set_config(&mut self, config: ViewportConfig) {
+        if config != self.config {
+            self.config = config;
+            self.dirty = true;
+        }
+    }
+}
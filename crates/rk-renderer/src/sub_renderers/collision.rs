@@ -1,11 +1,15 @@
 //! Collision shape visualization renderer
 
+use std::collections::HashMap;
+use std::ops::Range;
+
 use bytemuck::{Pod, Zeroable};
 use glam::{Mat4, Vec3};
+use uuid::Uuid;
 use wgpu::util::DeviceExt;
 
 use crate::constants::{collision as constants, instances};
-use crate::pipeline::{PipelineConfig, create_camera_bind_group};
+use crate::pipeline::{MsaaPipeline, PipelineConfig, create_camera_bind_group};
 
 /// Vertex with position and normal for collision geometry
 #[repr(C)]
@@ -114,8 +118,7 @@ impl Default for CollisionInstance {
 }
 
 /// Geometry type for collision shapes
-#[derive(Debug, Clone, Copy, PartialEq)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CollisionGeometry {
     /// Box collision shape
     Box,
@@ -127,9 +130,28 @@ pub enum CollisionGeometry {
     Capsule,
 }
 
+impl CollisionGeometry {
+    /// All geometry types, in buffer order
+    pub const ALL: [CollisionGeometry; 4] = [
+        CollisionGeometry::Box,
+        CollisionGeometry::Sphere,
+        CollisionGeometry::Cylinder,
+        CollisionGeometry::Capsule,
+    ];
+}
+
+/// GPU buffers of an imported collision mesh and its instances
+struct CollisionMeshBuffers {
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    index_count: u32,
+    instance_buffer: wgpu::Buffer,
+    instances: Vec<CollisionInstance>,
+}
+
 /// Collision renderer for visualizing collision shapes
 pub struct CollisionRenderer {
-    pipeline: wgpu::RenderPipeline,
+    pipeline: MsaaPipeline,
     bind_group: wgpu::BindGroup,
 
     // Box geometry
@@ -160,6 +182,12 @@ pub struct CollisionRenderer {
     capsule_instance_buffer: wgpu::Buffer,
     capsule_instances: Vec<CollisionInstance>,
 
+    /// Back-to-front draw order as runs of consecutive instances of one geometry
+    draw_runs: Vec<(CollisionGeometry, Range<u32>)>,
+
+    /// Imported collision meshes by ID
+    meshes: HashMap<Uuid, CollisionMeshBuffers>,
+
     visible: bool,
 }
 
@@ -175,7 +203,9 @@ impl CollisionRenderer {
         let bind_group =
             create_camera_bind_group(device, camera_bind_group_layout, camera_buffer, "Collision");
 
-        // Create pipeline with alpha blending (default in PipelineConfig)
+        // Create pipeline with alpha blending (default in PipelineConfig).
+        // Depth writes are disabled so overlapping shapes blend instead of
+        // occluding each other; instances are sorted back to front on upload.
         let pipeline = PipelineConfig::new(
             "Collision",
             include_str!("../shaders/collision.wgsl"),
@@ -185,6 +215,7 @@ impl CollisionRenderer {
         )
         .with_vertex_layouts(vec![CollisionVertex::layout(), CollisionInstance::layout()])
         .with_cull_mode(None) // Double-sided
+        .with_depth_write(false)
         .build(device);
 
         // Generate geometries
@@ -235,10 +266,17 @@ impl CollisionRenderer {
             capsule_index_count: capsule_indices.len() as u32,
             capsule_instance_buffer,
             capsule_instances: Vec::new(),
+            draw_runs: Vec::new(),
+            meshes: HashMap::new(),
             visible: true,
         }
     }
 
+    /// Recreate the pipeline for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.pipeline.set_sample_count(device, sample_count);
+    }
+
     /// Set visibility
     pub fn set_visible(&mut self, visible: bool) {
         self.visible = visible;
@@ -255,20 +293,67 @@ impl CollisionRenderer {
         self.sphere_instances.clear();
         self.cylinder_instances.clear();
         self.capsule_instances.clear();
+        self.draw_runs.clear();
+        for mesh in self.meshes.values_mut() {
+            mesh.instances.clear();
+        }
+    }
+
+    /// Check whether a collision mesh's buffers have been uploaded
+    pub fn has_mesh(&self, id: Uuid) -> bool {
+        self.meshes.contains_key(&id)
+    }
+
+    /// Upload a collision mesh so instances of it can be added
+    ///
+    /// Meshes are kept until dropped with [`Self::retain_meshes`].
+    pub fn upload_mesh(
+        &mut self,
+        device: &wgpu::Device,
+        id: Uuid,
+        positions: &[[f32; 3]],
+        indices: &[u32],
+    ) {
+        let vertices = mesh_vertices(positions, indices);
+        self.meshes.insert(
+            id,
+            CollisionMeshBuffers {
+                vertex_buffer: create_vertex_buffer(device, "Mesh", &vertices),
+                index_buffer: create_index_buffer(device, "Mesh", indices),
+                index_count: indices.len() as u32,
+                instance_buffer: create_instance_buffer(device, "Mesh"),
+                instances: Vec::new(),
+            },
+        );
+    }
+
+    /// Drop the buffers of collision meshes for which `keep` returns false
+    pub fn retain_meshes(&mut self, keep: impl Fn(Uuid) -> bool) {
+        self.meshes.retain(|id, _| keep(*id));
+    }
+
+    /// Add an instance of an uploaded collision mesh
+    pub fn add_mesh(&mut self, id: Uuid, transform: Mat4, color: [f32; 4]) {
+        if let Some(mesh) = self.meshes.get_mut(&id) {
+            push_instance(
+                &mut mesh.instances,
+                CollisionInstance::new(transform, color),
+            );
+        }
     }
 
     /// Add a box collision instance
     pub fn add_box(&mut self, transform: Mat4, size: [f32; 3], color: [f32; 4]) {
         let scale = Mat4::from_scale(Vec3::from_array(size));
         let instance = CollisionInstance::new(transform * scale, color);
-        self.box_instances.push(instance);
+        push_instance(&mut self.box_instances, instance);
     }
 
     /// Add a sphere collision instance
     pub fn add_sphere(&mut self, transform: Mat4, radius: f32, color: [f32; 4]) {
         let scale = Mat4::from_scale(Vec3::splat(radius));
         let instance = CollisionInstance::new(transform * scale, color);
-        self.sphere_instances.push(instance);
+        push_instance(&mut self.sphere_instances, instance);
     }
 
     /// Add a cylinder collision instance
@@ -276,7 +361,7 @@ impl CollisionRenderer {
         // Cylinder is along Z axis, scale appropriately
         let scale = Mat4::from_scale(Vec3::new(radius, radius, length));
         let instance = CollisionInstance::new(transform * scale, color);
-        self.cylinder_instances.push(instance);
+        push_instance(&mut self.cylinder_instances, instance);
     }
 
     /// Add a capsule collision instance
@@ -284,38 +369,43 @@ impl CollisionRenderer {
         // Capsule is along Z axis
         let scale = Mat4::from_scale(Vec3::new(radius, radius, length + 2.0 * radius));
         let instance = CollisionInstance::new(transform * scale, color);
-        self.capsule_instances.push(instance);
-    }
-
-    /// Upload instances to GPU
-    pub fn upload(&self, queue: &wgpu::Queue) {
-        if !self.box_instances.is_empty() {
-            queue.write_buffer(
-                &self.box_instance_buffer,
-                0,
-                bytemuck::cast_slice(&self.box_instances),
-            );
+        push_instance(&mut self.capsule_instances, instance);
+    }
+
+    /// Sort instances back to front from the camera and upload them to GPU
+    ///
+    /// Sorting keeps the blending of overlapping semi-transparent shapes
+    /// independent of the order they were added in.
+    pub fn upload(&mut self, queue: &wgpu::Queue, camera_position: Vec3) {
+        let mut order = Vec::new();
+        for geometry in CollisionGeometry::ALL {
+            let instances = self.instances_mut(geometry);
+            sort_back_to_front(instances, camera_position);
+            order.extend(instances.iter().enumerate().map(|(index, instance)| {
+                (
+                    instance_distance(instance, camera_position),
+                    geometry,
+                    index as u32,
+                )
+            }));
         }
-        if !self.sphere_instances.is_empty() {
-            queue.write_buffer(
-                &self.sphere_instance_buffer,
-                0,
-                bytemuck::cast_slice(&self.sphere_instances),
-            );
+        self.draw_runs = build_draw_runs(order);
+
+        for geometry in CollisionGeometry::ALL {
+            let instances = self.instances(geometry);
+            if !instances.is_empty() {
+                let (_, _, _, instance_buffer) = self.buffers(geometry);
+                queue.write_buffer(instance_buffer, 0, bytemuck::cast_slice(instances));
+            }
         }
-        if !self.cylinder_instances.is_empty() {
-            queue.write_buffer(
-                &self.cylinder_instance_buffer,
-                0,
-                bytemuck::cast_slice(&self.cylinder_instances),
-            );
-        }
-        if !self.capsule_instances.is_empty() {
-            queue.write_buffer(
-                &self.capsule_instance_buffer,
-                0,
-                bytemuck::cast_slice(&self.capsule_instances),
-            );
+        for mesh in self.meshes.values() {
+            if !mesh.instances.is_empty() {
+                queue.write_buffer(
+                    &mesh.instance_buffer,
+                    0,
+                    bytemuck::cast_slice(&mesh.instances),
+                );
+            }
         }
     }
 
@@ -328,64 +418,141 @@ impl CollisionRenderer {
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &self.bind_group, &[]);
 
-        // Render boxes
-        if !self.box_instances.is_empty() {
-            render_pass.set_vertex_buffer(0, self.box_vertex_buffer.slice(..));
-            render_pass.set_vertex_buffer(1, self.box_instance_buffer.slice(..));
-            render_pass
-                .set_index_buffer(self.box_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-            render_pass.draw_indexed(
-                0..self.box_index_count,
-                0,
-                0..self.box_instances.len() as u32,
-            );
+        for (geometry, instances) in &self.draw_runs {
+            let (vertex_buffer, index_buffer, index_count, instance_buffer) =
+                self.buffers(*geometry);
+            render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+            render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..index_count, 0, instances.clone());
         }
 
-        // Render spheres
-        if !self.sphere_instances.is_empty() {
-            render_pass.set_vertex_buffer(0, self.sphere_vertex_buffer.slice(..));
-            render_pass.set_vertex_buffer(1, self.sphere_instance_buffer.slice(..));
-            render_pass.set_index_buffer(
-                self.sphere_index_buffer.slice(..),
-                wgpu::IndexFormat::Uint32,
-            );
-            render_pass.draw_indexed(
-                0..self.sphere_index_count,
-                0,
-                0..self.sphere_instances.len() as u32,
-            );
+        // Meshes usually enclose a whole link, so they are drawn over the
+        // primitives rather than sorted with them
+        for mesh in self.meshes.values() {
+            if mesh.instances.is_empty() {
+                continue;
+            }
+            render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, mesh.instance_buffer.slice(..));
+            render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..mesh.index_count, 0, 0..mesh.instances.len() as u32);
         }
+    }
 
-        // Render cylinders
-        if !self.cylinder_instances.is_empty() {
-            render_pass.set_vertex_buffer(0, self.cylinder_vertex_buffer.slice(..));
-            render_pass.set_vertex_buffer(1, self.cylinder_instance_buffer.slice(..));
-            render_pass.set_index_buffer(
-                self.cylinder_index_buffer.slice(..),
-                wgpu::IndexFormat::Uint32,
-            );
-            render_pass.draw_indexed(
-                0..self.cylinder_index_count,
-                0,
-                0..self.cylinder_instances.len() as u32,
-            );
+    fn instances(&self, geometry: CollisionGeometry) -> &[CollisionInstance] {
+        match geometry {
+            CollisionGeometry::Box => &self.box_instances,
+            CollisionGeometry::Sphere => &self.sphere_instances,
+            CollisionGeometry::Cylinder => &self.cylinder_instances,
+            CollisionGeometry::Capsule => &self.capsule_instances,
         }
+    }
 
-        // Render capsules
-        if !self.capsule_instances.is_empty() {
-            render_pass.set_vertex_buffer(0, self.capsule_vertex_buffer.slice(..));
-            render_pass.set_vertex_buffer(1, self.capsule_instance_buffer.slice(..));
-            render_pass.set_index_buffer(
-                self.capsule_index_buffer.slice(..),
-                wgpu::IndexFormat::Uint32,
-            );
-            render_pass.draw_indexed(
-                0..self.capsule_index_count,
-                0,
-                0..self.capsule_instances.len() as u32,
-            );
+    fn instances_mut(&mut self, geometry: CollisionGeometry) -> &mut Vec<CollisionInstance> {
+        match geometry {
+            CollisionGeometry::Box => &mut self.box_instances,
+            CollisionGeometry::Sphere => &mut self.sphere_instances,
+            CollisionGeometry::Cylinder => &mut self.cylinder_instances,
+            CollisionGeometry::Capsule => &mut self.capsule_instances,
         }
     }
+
+    /// Vertex buffer, index buffer, index count and instance buffer of a geometry
+    fn buffers(
+        &self,
+        geometry: CollisionGeometry,
+    ) -> (&wgpu::Buffer, &wgpu::Buffer, u32, &wgpu::Buffer) {
+        match geometry {
+            CollisionGeometry::Box => (
+                &self.box_vertex_buffer,
+                &self.box_index_buffer,
+                self.box_index_count,
+                &self.box_instance_buffer,
+            ),
+            CollisionGeometry::Sphere => (
+                &self.sphere_vertex_buffer,
+                &self.sphere_index_buffer,
+                self.sphere_index_count,
+                &self.sphere_instance_buffer,
+            ),
+            CollisionGeometry::Cylinder => (
+                &self.cylinder_vertex_buffer,
+                &self.cylinder_index_buffer,
+                self.cylinder_index_count,
+                &self.cylinder_instance_buffer,
+            ),
+            CollisionGeometry::Capsule => (
+                &self.capsule_vertex_buffer,
+                &self.capsule_index_buffer,
+                self.capsule_index_count,
+                &self.capsule_instance_buffer,
+            ),
+        }
+    }
+}
+
+/// Distance from the camera to an instance's origin
+fn instance_distance(instance: &CollisionInstance, camera_position: Vec3) -> f32 {
+    Vec3::from_slice(&instance.model[3][..3]).distance(camera_position)
+}
+
+/// Sort instances farthest first
+fn sort_back_to_front(instances: &mut [CollisionInstance], camera_position: Vec3) {
+    instances.sort_by(|a, b| {
+        instance_distance(b, camera_position).total_cmp(&instance_distance(a, camera_position))
+    });
+}
+
+/// Merge instances of all geometries into a single back-to-front draw order
+///
+/// Each entry of `order` is (distance, geometry, index within that geometry's
+/// sorted instance list). Consecutive instances of the same geometry are
+/// merged into one instanced draw.
+fn build_draw_runs(
+    mut order: Vec<(f32, CollisionGeometry, u32)>,
+) -> Vec<(CollisionGeometry, Range<u32>)> {
+    // Stable sort keeps per-geometry indices ascending for equal distances
+    order.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut runs: Vec<(CollisionGeometry, Range<u32>)> = Vec::new();
+    for (_, geometry, index) in order {
+        if let Some((last_geometry, range)) = runs.last_mut()
+            && *last_geometry == geometry
+            && range.end == index
+        {
+            range.end += 1;
+        } else {
+            runs.push((geometry, index..index + 1));
+        }
+    }
+    runs
+}
+
+/// Vertices of an indexed triangle mesh with area-weighted vertex normals
+fn mesh_vertices(positions: &[[f32; 3]], indices: &[u32]) -> Vec<CollisionVertex> {
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i] as usize]));
+        let normal = (b - a).cross(c - a);
+        for &index in triangle {
+            normals[index as usize] += normal;
+        }
+    }
+    positions
+        .iter()
+        .zip(normals)
+        .map(|(position, normal)| {
+            CollisionVertex::new(Vec3::from(*position), normal.normalize_or_zero())
+        })
+        .collect()
+}
+
+/// Push an instance, dropping it if the GPU instance buffer is full
+fn push_instance(instances: &mut Vec<CollisionInstance>, instance: CollisionInstance) {
+    if instances.len() < instances::MAX_COLLISIONS as usize {
+        instances.push(instance);
+    }
 }
 
 fn create_vertex_buffer(
@@ -740,3 +907,59 @@ fn generate_capsule(segments: u32, half_rings: u32) -> (Vec<CollisionVertex>, Ve
 
     (vertices, indices)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance_at(x: f32) -> CollisionInstance {
+        CollisionInstance::new(
+            Mat4::from_translation(Vec3::new(x, 0.0, 0.0)),
+            constants::DEFAULT_COLOR,
+        )
+    }
+
+    #[test]
+    fn test_sort_back_to_front() {
+        let mut instances = vec![instance_at(1.0), instance_at(5.0), instance_at(3.0)];
+        sort_back_to_front(&mut instances, Vec3::ZERO);
+        let xs: Vec<f32> = instances.iter().map(|i| i.model[3][0]).collect();
+        assert_eq!(xs, vec![5.0, 3.0, 1.0]);
+    }
+
+    #[test]
+    fn test_mesh_vertex_normals() {
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ];
+        // Two triangles sharing the edge from vertex 0 to 2, facing +Z and -X
+        let vertices = mesh_vertices(&positions, &[0, 1, 2, 0, 3, 2]);
+        assert_eq!(vertices[1].normal, [0.0, 0.0, 1.0]);
+        assert_eq!(vertices[3].normal, [-1.0, 0.0, 0.0]);
+        let shared = Vec3::from(vertices[0].normal);
+        assert!(shared.abs_diff_eq(Vec3::new(-1.0, 0.0, 1.0).normalize(), 1e-6));
+    }
+
+    #[test]
+    fn test_draw_runs_interleave_geometries() {
+        let order = vec![
+            (5.0, CollisionGeometry::Box, 0),
+            (4.0, CollisionGeometry::Box, 1),
+            (1.0, CollisionGeometry::Box, 2),
+            (3.0, CollisionGeometry::Sphere, 0),
+            (2.0, CollisionGeometry::Sphere, 1),
+        ];
+        let runs = build_draw_runs(order);
+        assert_eq!(
+            runs,
+            vec![
+                (CollisionGeometry::Box, 0..2),
+                (CollisionGeometry::Sphere, 0..2),
+                (CollisionGeometry::Box, 2..3),
+            ]
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Feature edge renderer
+//!
+//! Draws lines over the shaded meshes along crease edges, where the normals
+//! of the two adjacent faces differ by more than the configured angle, along
+//! open borders, and along the silhouette seen from the camera. Candidate
+//! edges are extracted once per mesh together with the normals of both
+//! adjacent faces; the shader picks the ones to draw each frame, so the
+//! crease angle can change without rebuilding anything. Extraction waits
+//! until a mesh is first drawn with edges on.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use bytemuck::{Pod, Zeroable};
+use glam::Vec3;
+use wgpu::util::DeviceExt;
+
+use rk_core::Part;
+
+use crate::config::EdgeConfig;
+use crate::constants::edges as constants;
+use crate::pipeline::{MsaaPipeline, PipelineConfig, create_camera_bind_group};
+
+/// Vertex of a candidate edge with the normals of the faces it joins
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct EdgeVertex {
+    /// Position in mesh space
+    pub position: [f32; 3],
+    /// Normal of the first adjacent face
+    pub normal_a: [f32; 3],
+    /// Normal of the second adjacent face, zero on open borders
+    pub normal_b: [f32; 3],
+    /// Midpoint of the edge in mesh space, so both ends agree on the silhouette
+    pub midpoint: [f32; 3],
+}
+
+impl EdgeVertex {
+    /// Vertex attributes for the shader
+    pub const ATTRIBUTES: &'static [wgpu::VertexAttribute] = &wgpu::vertex_attr_array![
+        0 => Float32x3,
+        1 => Float32x3,
+        2 => Float32x3,
+        3 => Float32x3,
+    ];
+
+    /// Returns the vertex buffer layout
+    pub fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as u64,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// Candidate edges of a triangle mesh, as line list vertices
+///
+/// Vertices at the same position are welded first, so meshes split per face
+/// for flat shading still find their neighbors. Edges between coplanar faces
+/// are left out since they are never drawn; edges with more than two faces
+/// are treated like open borders and always drawn.
+pub fn extract_edges(vertices: &[[f32; 3]], indices: &[u32]) -> Vec<EdgeVertex> {
+    let mut welded: HashMap<[u32; 3], u32> = HashMap::new();
+    let mut weld = |index: u32| {
+        let position = vertices[index as usize];
+        let next = welded.len() as u32;
+        *welded.entry(position.map(f32::to_bits)).or_insert(next)
+    };
+
+    // Face normals per edge, keyed by welded endpoints (lower first)
+    let mut edges: HashMap<(u32, u32), (u32, u32, Vec<Vec3>)> = HashMap::new();
+    for triangle in indices.chunks_exact(3) {
+        if triangle.iter().any(|&i| i as usize >= vertices.len()) {
+            continue;
+        }
+        let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(vertices[triangle[i] as usize]));
+        let Some(normal) = (b - a).cross(c - a).try_normalize() else {
+            continue;
+        };
+        for (start, end) in [(0, 1), (1, 2), (2, 0)] {
+            let (start, end) = (triangle[start], triangle[end]);
+            let (weld_start, weld_end) = (weld(start), weld(end));
+            if weld_start == weld_end {
+                continue;
+            }
+            let key = (weld_start.min(weld_end), weld_start.max(weld_end));
+            edges
+                .entry(key)
+                .or_insert_with(|| (start, end, Vec::new()))
+                .2
+                .push(normal);
+        }
+    }
+
+    let mut result = Vec::new();
+    for (start, end, normals) in edges.into_values() {
+        let (normal_a, normal_b) = match normals[..] {
+            [a, b] if a.dot(b) > constants::COPLANAR_DOT => continue,
+            [a, b] => (a, b),
+            _ => (normals[0], Vec3::ZERO),
+        };
+        let midpoint =
+            (Vec3::from(vertices[start as usize]) + Vec3::from(vertices[end as usize])) * 0.5;
+        for index in [start, end] {
+            result.push(EdgeVertex {
+                position: vertices[index as usize],
+                normal_a: normal_a.to_array(),
+                normal_b: normal_b.to_array(),
+                midpoint: midpoint.to_array(),
+            });
+        }
+    }
+    result
+}
+
+/// GPU line list of a mesh's candidate edges, built on first use
+///
+/// Keeps the mesh geometry until [`prepare`](Self::prepare) extracts and
+/// uploads the edges. Cloning shares both, for part copies drawn from the
+/// same geometry.
+#[derive(Debug, Clone)]
+pub struct EdgeMesh(Arc<EdgeMeshInner>);
+
+/// Mesh vertices and indices
+type Geometry = (Vec<[f32; 3]>, Vec<u32>);
+
+#[derive(Debug)]
+struct EdgeMeshInner {
+    /// Mesh geometry, dropped once the edges are extracted
+    geometry: Mutex<Option<Geometry>>,
+    /// Line list and its vertex count; `None` if the mesh has no edges
+    uploaded: OnceLock<Option<(wgpu::Buffer, u32)>>,
+}
+
+impl EdgeMesh {
+    /// Edges of a part, extracted when first prepared
+    pub fn from_part(part: &Part) -> Self {
+        Self(Arc::new(EdgeMeshInner {
+            geometry: Mutex::new(Some((part.vertices.clone(), part.indices.clone()))),
+            uploaded: OnceLock::new(),
+        }))
+    }
+
+    /// Extract and upload the edges if not done yet
+    pub fn prepare(&self, device: &wgpu::Device) {
+        self.0.uploaded.get_or_init(|| {
+            let (vertices, indices) = self.0.geometry.lock().ok()?.take()?;
+            let vertices = extract_edges(&vertices, &indices);
+            if vertices.is_empty() {
+                return None;
+            }
+            let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Edge Vertex Buffer"),
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+            Some((vertex_buffer, vertices.len() as u32))
+        });
+    }
+}
+
+/// Edge color and crease threshold
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct EdgeUniform {
+    color: [f32; 4],
+    /// x = cosine of the crease angle, y = depth pull
+    params: [f32; 4],
+}
+
+impl EdgeUniform {
+    fn from_config(config: &EdgeConfig) -> Self {
+        Self {
+            color: config.color,
+            params: [
+                config.crease_angle.to_radians().cos(),
+                constants::DEPTH_PULL,
+                0.0,
+                0.0,
+            ],
+        }
+    }
+}
+
+/// Feature edge renderer
+pub struct EdgeRenderer {
+    pipeline: MsaaPipeline,
+    camera_bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    config: EdgeConfig,
+}
+
+impl EdgeRenderer {
+    /// Creates a new edge renderer.
+    ///
+    /// `instance_bind_group_layout` is the mesh renderer's, so edges are
+    /// placed with the instance bind group of their mesh.
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        camera_buffer: &wgpu::Buffer,
+        instance_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Self {
+        let config = EdgeConfig::default();
+        let camera_bind_group =
+            create_camera_bind_group(device, camera_bind_group_layout, camera_buffer, "Edge");
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Edge Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[EdgeUniform::from_config(&config)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Edge Uniform Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Edge Uniform Bind Group"),
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let pipeline = PipelineConfig::new(
+            "Edge",
+            include_str!("../shaders/edges.wgsl"),
+            format,
+            depth_format,
+            &[
+                camera_bind_group_layout,
+                instance_bind_group_layout,
+                &uniform_bind_group_layout,
+            ],
+        )
+        .with_vertex_layouts(vec![EdgeVertex::layout()])
+        .with_topology(wgpu::PrimitiveTopology::LineList)
+        .with_depth(false, wgpu::CompareFunction::LessEqual)
+        .build(device);
+
+        Self {
+            pipeline,
+            camera_bind_group,
+            uniform_buffer,
+            uniform_bind_group,
+            config,
+        }
+    }
+
+    /// Get the current edge configuration.
+    pub fn config(&self) -> &EdgeConfig {
+        &self.config
+    }
+
+    /// Update whether edges are drawn, their color and crease angle.
+    pub fn set_config(&mut self, queue: &wgpu::Queue, config: &EdgeConfig) {
+        self.config = config.clone();
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[EdgeUniform::from_config(config)]),
+        );
+    }
+
+    /// Recreate the pipeline for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.pipeline.set_sample_count(device, sample_count);
+    }
+
+    /// Renders the edges of one mesh, placed by its instance bind group.
+    pub fn render<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        edges: &'a EdgeMesh,
+        instance_bind_group: &'a wgpu::BindGroup,
+    ) {
+        let Some(Some((vertex_buffer, vertex_count))) = edges.0.uploaded.get() else {
+            return;
+        };
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        render_pass.set_bind_group(1, instance_bind_group, &[]);
+        render_pass.set_bind_group(2, &self.uniform_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        render_pass.draw(0..*vertex_count, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_edges() {
+        // Two triangles folded 90° along the X axis, split per face
+        let vertices = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ];
+        let indices = [0, 1, 2, 3, 5, 4];
+        let edges = extract_edges(&vertices, &indices);
+        assert_eq!(edges.len(), 10);
+
+        let shared: Vec<&EdgeVertex> = edges.iter().filter(|v| v.normal_b != [0.0; 3]).collect();
+        assert_eq!(shared.len(), 2);
+        let (a, b) = (
+            Vec3::from(shared[0].normal_a),
+            Vec3::from(shared[0].normal_b),
+        );
+        assert!(a.dot(b).abs() < 1e-6);
+        assert!(shared.iter().all(|v| v.midpoint == [0.5, 0.0, 0.0]));
+
+        // Coplanar neighbors leave no edge between them
+        let quad = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let edges = extract_edges(&quad, &[0, 1, 2, 0, 2, 3]);
+        assert_eq!(edges.len(), 8);
+        assert!(edges.iter().all(|v| v.normal_b == [0.0; 3]));
+    }
+}
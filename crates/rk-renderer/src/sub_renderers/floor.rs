@@ -0,0 +1,105 @@
+//! Floor plane renderer
+//!
+//! Draws a square ground plane at a configurable height through the mesh
+//! pipeline, so it is lit like the parts and can receive their shadows. The
+//! floor never casts shadows itself.
+
+use glam::{Mat4, Vec3};
+
+use crate::config::FloorConfig;
+use crate::sub_renderers::mesh::{
+    INSTANCE_COLOR, MeshData, MeshInstance, MeshRenderer, MeshVertex,
+};
+
+/// Distance the floor is lowered so the grid at the same height stays visible
+const GRID_CLEARANCE: f32 = 1e-3;
+
+/// Floor plane renderer
+pub struct FloorRenderer {
+    config: FloorConfig,
+    mesh: MeshData,
+    bind_group: wgpu::BindGroup,
+}
+
+impl FloorRenderer {
+    /// Creates a new floor renderer.
+    pub fn new(device: &wgpu::Device, mesh_renderer: &MeshRenderer) -> Self {
+        let config = FloorConfig::default();
+        let vertices = unit_quad_vertices();
+        let mesh = MeshData::from_vertices(device, &vertices, floor_instance(&config));
+        let bind_group = mesh_renderer.create_instance_bind_group(device, &mesh);
+
+        Self {
+            config,
+            mesh,
+            bind_group,
+        }
+    }
+
+    /// Get the current floor configuration.
+    pub fn config(&self) -> &FloorConfig {
+        &self.config
+    }
+
+    /// Update the floor placement and color.
+    pub fn set_config(&mut self, queue: &wgpu::Queue, config: &FloorConfig) {
+        if *config == self.config {
+            return;
+        }
+        self.config = config.clone();
+        self.mesh.instance = floor_instance(config);
+        queue.write_buffer(
+            &self.mesh.instance_buffer,
+            0,
+            bytemuck::cast_slice(&[self.mesh.instance]),
+        );
+    }
+
+    /// Renders the floor if it is enabled.
+    ///
+    /// `light_bind_group` decides whether shadows are applied.
+    pub fn render<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        mesh_renderer: &'a MeshRenderer,
+        light_bind_group: &'a wgpu::BindGroup,
+    ) {
+        if !self.config.enabled {
+            return;
+        }
+        mesh_renderer.render(render_pass, &self.mesh, &self.bind_group, light_bind_group);
+    }
+}
+
+/// Instance data placing the unit quad at the configured height and size
+fn floor_instance(config: &FloorConfig) -> MeshInstance {
+    let model = Mat4::from_translation(Vec3::new(0.0, 0.0, config.height - GRID_CLEARANCE))
+        * Mat4::from_scale(Vec3::new(config.size, config.size, 1.0));
+    let [r, g, b] = config.color;
+    MeshInstance {
+        model: model.to_cols_array_2d(),
+        color: [r, g, b, 1.0],
+        roughness: 0.9,
+        ..MeshInstance::default()
+    }
+}
+
+/// A 1x1 quad in the XY plane facing +Z
+fn unit_quad_vertices() -> Vec<MeshVertex> {
+    let corners = [
+        [-0.5, -0.5, 0.0],
+        [0.5, -0.5, 0.0],
+        [0.5, 0.5, 0.0],
+        [-0.5, -0.5, 0.0],
+        [0.5, 0.5, 0.0],
+        [-0.5, 0.5, 0.0],
+    ];
+    corners
+        .into_iter()
+        .map(|position| MeshVertex {
+            position,
+            normal: [0.0, 0.0, 1.0],
+            color: INSTANCE_COLOR,
+        })
+        .collect()
+}
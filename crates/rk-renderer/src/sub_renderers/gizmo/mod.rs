@@ -15,6 +15,7 @@ use wgpu::util::DeviceExt;
 
 use crate::constants::gizmo as constants;
 use crate::constants::viewport::SAMPLE_COUNT;
+use crate::pipeline::{MsaaPipeline, PipelineDescriptor, VertexLayout};
 use geometry::{generate_rotation_gizmo, generate_scale_gizmo, generate_translation_gizmo};
 
 /// Gizmo mode
@@ -130,7 +131,7 @@ impl Default for GizmoInstance {
 
 /// Gizmo renderer
 pub struct GizmoRenderer {
-    pipeline: wgpu::RenderPipeline,
+    pipeline: MsaaPipeline,
     // Translation gizmo buffers
     translate_vertex_buffer: wgpu::Buffer,
     translate_index_buffer: wgpu::Buffer,
@@ -226,80 +227,73 @@ impl GizmoRenderer {
             push_constant_ranges: &[],
         });
 
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Gizmo Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[
-                    // Vertex buffer
-                    wgpu::VertexBufferLayout {
-                        array_stride: std::mem::size_of::<GizmoVertex>() as u64,
-                        step_mode: wgpu::VertexStepMode::Vertex,
-                        attributes: &[
-                            wgpu::VertexAttribute {
-                                offset: 0,
-                                shader_location: 0,
-                                format: wgpu::VertexFormat::Float32x3,
-                            },
-                            wgpu::VertexAttribute {
-                                offset: 12,
-                                shader_location: 1,
-                                format: wgpu::VertexFormat::Float32x4,
-                            },
-                            wgpu::VertexAttribute {
-                                offset: 28,
-                                shader_location: 2,
-                                format: wgpu::VertexFormat::Uint32,
-                            },
-                        ],
-                    },
-                    // Instance buffer
-                    wgpu::VertexBufferLayout {
-                        array_stride: std::mem::size_of::<GizmoInstance>() as u64,
-                        step_mode: wgpu::VertexStepMode::Instance,
-                        attributes: &[
-                            wgpu::VertexAttribute {
-                                offset: 0,
-                                shader_location: 3,
-                                format: wgpu::VertexFormat::Float32x4,
-                            },
-                            wgpu::VertexAttribute {
-                                offset: 16,
-                                shader_location: 4,
-                                format: wgpu::VertexFormat::Float32x4,
-                            },
-                            wgpu::VertexAttribute {
-                                offset: 32,
-                                shader_location: 5,
-                                format: wgpu::VertexFormat::Float32x4,
-                            },
-                            wgpu::VertexAttribute {
-                                offset: 48,
-                                shader_location: 6,
-                                format: wgpu::VertexFormat::Float32x4,
-                            },
-                            wgpu::VertexAttribute {
-                                offset: 64,
-                                shader_location: 7,
-                                format: wgpu::VertexFormat::Float32x4,
-                            },
-                        ],
-                    },
-                ],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
+        let descriptor = PipelineDescriptor {
+            label: "Gizmo Pipeline".to_string(),
+            shader,
+            layout: pipeline_layout,
+            vs_entry_point: "vs_main".to_string(),
+            fs_entry_point: "fs_main".to_string(),
+            vertex_layouts: vec![
+                // Vertex buffer
+                VertexLayout {
+                    array_stride: std::mem::size_of::<GizmoVertex>() as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: vec![
+                        wgpu::VertexAttribute {
+                            offset: 0,
+                            shader_location: 0,
+                            format: wgpu::VertexFormat::Float32x3,
+                        },
+                        wgpu::VertexAttribute {
+                            offset: 12,
+                            shader_location: 1,
+                            format: wgpu::VertexFormat::Float32x4,
+                        },
+                        wgpu::VertexAttribute {
+                            offset: 28,
+                            shader_location: 2,
+                            format: wgpu::VertexFormat::Uint32,
+                        },
+                    ],
+                },
+                // Instance buffer
+                VertexLayout {
+                    array_stride: std::mem::size_of::<GizmoInstance>() as u64,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: vec![
+                        wgpu::VertexAttribute {
+                            offset: 0,
+                            shader_location: 3,
+                            format: wgpu::VertexFormat::Float32x4,
+                        },
+                        wgpu::VertexAttribute {
+                            offset: 16,
+                            shader_location: 4,
+                            format: wgpu::VertexFormat::Float32x4,
+                        },
+                        wgpu::VertexAttribute {
+                            offset: 32,
+                            shader_location: 5,
+                            format: wgpu::VertexFormat::Float32x4,
+                        },
+                        wgpu::VertexAttribute {
+                            offset: 48,
+                            shader_location: 6,
+                            format: wgpu::VertexFormat::Float32x4,
+                        },
+                        wgpu::VertexAttribute {
+                            offset: 64,
+                            shader_location: 7,
+                            format: wgpu::VertexFormat::Float32x4,
+                        },
+                    ],
+                },
+            ],
+            target: wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
             },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
             primitive: wgpu::PrimitiveState {
                 topology: wgpu::PrimitiveTopology::TriangleList,
                 cull_mode: None,
@@ -312,14 +306,8 @@ impl GizmoRenderer {
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
-            multisample: wgpu::MultisampleState {
-                count: SAMPLE_COUNT,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-            cache: None,
-        });
+        };
+        let pipeline = MsaaPipeline::new(device, descriptor, SAMPLE_COUNT);
 
         // Translation gizmo geometry
         let (translate_vertices, translate_indices) = generate_translation_gizmo();
@@ -508,6 +496,11 @@ impl GizmoRenderer {
         );
     }
 
+    /// Recreate the pipeline for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.pipeline.set_sample_count(device, sample_count);
+    }
+
     /// Renders the gizmo.
     pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         if !self.visible {
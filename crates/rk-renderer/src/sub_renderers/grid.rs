@@ -1,20 +1,72 @@
 //! Grid sub-renderer implementing the SubRenderer trait.
+//!
+//! Supports two modes selected by [`GridConfig::infinite`]:
+//! - a fixed-size line grid with minor/major lines, and
+//! - an infinite shader-based grid that follows the camera and fades with distance.
 
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use crate::config::GridConfig;
 use crate::constants::grid as constants;
 use crate::context::RenderContext;
-use crate::pipeline::PipelineConfig;
+use crate::pipeline::{MsaaPipeline, PipelineConfig, create_camera_bind_group};
 use crate::scene::Scene;
-use crate::traits::SubRenderer;
+use crate::traits::{ConfigurableSubRenderer, SubRenderer};
 use crate::vertex::PositionColorVertex;
 
+/// Grid uniform buffer data sent to GPU (80 bytes)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct GridUniform {
+    minor_color: [f32; 4],
+    major_color: [f32; 4],
+    x_axis_color: [f32; 4],
+    y_axis_color: [f32; 4],
+    /// x = spacing, y = major every, z = fade start, w = fade end
+    params: [f32; 4],
+}
+
+impl GridUniform {
+    fn from_config(config: &GridConfig) -> Self {
+        let rgba = |c: [f32; 3]| [c[0], c[1], c[2], 1.0];
+        Self {
+            minor_color: rgba(config.line_color),
+            major_color: rgba(config.major_line_color),
+            x_axis_color: rgba(config.x_axis_color),
+            y_axis_color: rgba(config.y_axis_color),
+            params: [
+                config.spacing,
+                config.major_every as f32,
+                config.size * 0.5,
+                config.size * 1.5,
+            ],
+        }
+    }
+}
+
+/// GPU resources created on initialization.
+struct GridGpu {
+    line_pipeline: MsaaPipeline,
+    infinite_pipeline: MsaaPipeline,
+    camera_bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    vertex_buffer: wgpu::Buffer,
+    vertex_count: u32,
+}
+
+/// Former name of the grid renderer, which now also draws the infinite grid.
+#[deprecated(note = "use GridSubRenderer")]
+pub type GridRenderer = GridSubRenderer;
+
 /// Grid sub-renderer for ground reference plane.
 pub struct GridSubRenderer {
     enabled: bool,
-    initialized: bool,
-    pipeline: Option<wgpu::RenderPipeline>,
-    vertex_buffer: Option<wgpu::Buffer>,
-    camera_bind_group: Option<wgpu::BindGroup>,
-    vertex_count: u32,
+    config: GridConfig,
+    /// Set when the config changed and GPU buffers need to be rebuilt
+    dirty: bool,
+    gpu: Option<GridGpu>,
 }
 
 impl GridSubRenderer {
@@ -22,11 +74,143 @@ impl GridSubRenderer {
     pub fn new() -> Self {
         Self {
             enabled: true,
-            initialized: false,
-            pipeline: None,
-            vertex_buffer: None,
-            camera_bind_group: None,
-            vertex_count: 0,
+            config: GridConfig::default(),
+            dirty: false,
+            gpu: None,
+        }
+    }
+
+    /// Creates GPU resources without a [`RenderContext`].
+    ///
+    /// Used by the main [`Renderer`](crate::Renderer), which owns its camera
+    /// buffer directly.
+    pub fn init(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        camera_buffer: &wgpu::Buffer,
+    ) {
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Grid Uniform Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+        let layouts = [camera_bind_group_layout, &uniform_bind_group_layout];
+
+        let line_pipeline = PipelineConfig::new(
+            "Grid",
+            include_str!("../shaders/grid.wgsl"),
+            format,
+            depth_format,
+            &layouts,
+        )
+        .with_vertex_layouts(vec![PositionColorVertex::layout()])
+        .with_topology(wgpu::PrimitiveTopology::LineList)
+        .build(device);
+
+        // The infinite grid is mostly transparent, so it must not occlude
+        // geometry drawn after it.
+        let infinite_pipeline = PipelineConfig::new(
+            "Infinite Grid",
+            include_str!("../shaders/grid_infinite.wgsl"),
+            format,
+            depth_format,
+            &layouts,
+        )
+        .with_depth_write(false)
+        .build(device);
+
+        let camera_bind_group =
+            create_camera_bind_group(device, camera_bind_group_layout, camera_buffer, "Grid");
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Grid Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[GridUniform::from_config(&self.config)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Grid Uniform Bind Group"),
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let (vertex_buffer, vertex_count) = create_vertex_buffer(device, &self.config);
+
+        self.gpu = Some(GridGpu {
+            line_pipeline,
+            infinite_pipeline,
+            camera_bind_group,
+            uniform_buffer,
+            uniform_bind_group,
+            vertex_buffer,
+            vertex_count,
+        });
+        self.dirty = false;
+    }
+
+    /// Recreate the pipelines for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        if let Some(gpu) = &mut self.gpu {
+            gpu.line_pipeline.set_sample_count(device, sample_count);
+            gpu.infinite_pipeline.set_sample_count(device, sample_count);
+        }
+    }
+
+    /// Rebuild GPU buffers if the configuration changed since the last upload.
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        if !self.dirty {
+            return;
+        }
+        let Some(gpu) = self.gpu.as_mut() else {
+            return;
+        };
+
+        queue.write_buffer(
+            &gpu.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[GridUniform::from_config(&self.config)]),
+        );
+
+        if !self.config.infinite {
+            let (vertex_buffer, vertex_count) = create_vertex_buffer(device, &self.config);
+            gpu.vertex_buffer = vertex_buffer;
+            gpu.vertex_count = vertex_count;
+        }
+
+        self.dirty = false;
+    }
+
+    /// Record draw commands for the grid.
+    pub fn draw<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>) {
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+
+        pass.set_bind_group(0, &gpu.camera_bind_group, &[]);
+        pass.set_bind_group(1, &gpu.uniform_bind_group, &[]);
+
+        if self.config.infinite {
+            pass.set_pipeline(&gpu.infinite_pipeline);
+            pass.draw(0..6, 0..1);
+        } else {
+            pass.set_pipeline(&gpu.line_pipeline);
+            pass.set_vertex_buffer(0, gpu.vertex_buffer.slice(..));
+            pass.draw(0..gpu.vertex_count, 0..1);
         }
     }
 }
@@ -55,81 +239,90 @@ impl SubRenderer for GridSubRenderer {
     }
 
     fn on_init(&mut self, ctx: &RenderContext) {
-        let pipeline = PipelineConfig::new(
-            "Grid",
-            include_str!("../shaders/grid.wgsl"),
+        self.init(
+            ctx.device(),
             ctx.surface_format(),
             ctx.depth_format(),
-            &[ctx.camera_bind_group_layout()],
-        )
-        .with_vertex_layouts(vec![PositionColorVertex::layout()])
-        .with_topology(wgpu::PrimitiveTopology::LineList)
-        .build(ctx.device());
-
-        // Generate grid vertices
-        let vertices = generate_grid_vertices(constants::DEFAULT_SIZE, constants::DEFAULT_SPACING);
-        self.vertex_count = vertices.len() as u32;
-
-        let vertex_buffer = ctx.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Grid Vertex Buffer"),
-            contents: bytemuck::cast_slice(&vertices),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
-
-        // Create bind group referencing the shared camera buffer
-        let camera_bind_group = ctx.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Grid Camera Bind Group"),
-            layout: ctx.camera_bind_group_layout(),
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: ctx.camera_buffer().as_entire_binding(),
-            }],
-        });
+            ctx.camera_bind_group_layout(),
+            ctx.camera_buffer(),
+        );
+        self.set_sample_count(ctx.device(), ctx.sample_count());
+    }
 
-        self.pipeline = Some(pipeline);
-        self.vertex_buffer = Some(vertex_buffer);
-        self.camera_bind_group = Some(camera_bind_group);
-        self.initialized = true;
+    fn on_sample_count_changed(&mut self, ctx: &RenderContext) {
+        self.set_sample_count(ctx.device(), ctx.sample_count());
     }
 
     fn on_resize(&mut self, _ctx: &RenderContext, _width: u32, _height: u32) {
         // Grid doesn't need to respond to resize
     }
 
-    fn prepare(&mut self, _ctx: &RenderContext, _scene: &Scene) {
-        // Grid doesn't need per-frame preparation
+    fn prepare(&mut self, ctx: &RenderContext, _scene: &Scene) {
+        self.upload(ctx.device(), ctx.queue());
     }
 
     fn render<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>, _scene: &Scene) {
-        if !self.initialized {
-            return;
-        }
+        self.draw(pass);
+    }
+}
 
-        let pipeline = self.pipeline.as_ref().unwrap();
-        let vertex_buffer = self.vertex_buffer.as_ref().unwrap();
-        let camera_bind_group = self.camera_bind_group.as_ref().unwrap();
+impl ConfigurableSubRenderer for GridSubRenderer {
+    type Config = GridConfig;
 
-        pass.set_pipeline(pipeline);
-        pass.set_bind_group(0, camera_bind_group, &[]);
-        pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-        pass.draw(0..self.vertex_count, 0..1);
+    fn config(&self) -> &GridConfig {
+        &self.config
+    }
+
+    fn set_config(&mut self, config: GridConfig) {
+        if config != self.config {
+            self.enabled = config.enabled;
+            self.config = config;
+            self.dirty = true;
+        }
     }
 }
 
+fn create_vertex_buffer(device: &wgpu::Device, config: &GridConfig) -> (wgpu::Buffer, u32) {
+    let vertices = generate_grid_vertices(config);
+    let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Grid Vertex Buffer"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+    (buffer, vertices.len() as u32)
+}
+
 /// Generate grid line vertices
-fn generate_grid_vertices(size: f32, spacing: f32) -> Vec<PositionColorVertex> {
+fn generate_grid_vertices(config: &GridConfig) -> Vec<PositionColorVertex> {
     let mut vertices = Vec::new();
-    let half_size = size;
-    let num_lines = (size / spacing) as i32;
+    let half_size = config.size;
+    let spacing = config.spacing.max(f32::EPSILON);
+    let mut num_lines = (half_size / spacing) as i32;
+    if num_lines > constants::MAX_LINES_PER_AXIS {
+        tracing::warn!(
+            "Grid spacing {} too fine for extent {}; limiting to {} lines per axis",
+            spacing,
+            half_size,
+            constants::MAX_LINES_PER_AXIS
+        );
+        num_lines = constants::MAX_LINES_PER_AXIS;
+    }
+    let major_every = config.major_every as i32;
+
+    let line_color = |i: i32, axis_color: [f32; 3]| {
+        if i == 0 {
+            axis_color
+        } else if major_every >= 2 && i % major_every == 0 {
+            config.major_line_color
+        } else {
+            config.line_color
+        }
+    };
 
     // Lines parallel to X axis
     for i in -num_lines..=num_lines {
         let y = i as f32 * spacing;
-        let color = if i == 0 {
-            constants::X_AXIS_COLOR
-        } else {
-            constants::LINE_COLOR
-        };
+        let color = line_color(i, config.x_axis_color);
 
         // Start point
         vertices.push(PositionColorVertex {
@@ -146,11 +339,7 @@ fn generate_grid_vertices(size: f32, spacing: f32) -> Vec<PositionColorVertex> {
     // Lines parallel to Y axis
     for i in -num_lines..=num_lines {
         let x = i as f32 * spacing;
-        let color = if i == 0 {
-            constants::Y_AXIS_COLOR
-        } else {
-            constants::LINE_COLOR
-        };
+        let color = line_color(i, config.y_axis_color);
 
         // Start point
         vertices.push(PositionColorVertex {
@@ -166,3 +355,46 @@ fn generate_grid_vertices(size: f32, spacing: f32) -> Vec<PositionColorVertex> {
 
     vertices
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_major_lines_use_major_color() {
+        let config = GridConfig {
+            size: 1.0,
+            spacing: 0.1,
+            major_every: 5,
+            ..Default::default()
+        };
+        let vertices = generate_grid_vertices(&config);
+
+        // 21 lines per direction, 2 vertices each
+        assert_eq!(vertices.len(), 2 * 21 * 2);
+
+        let color_at_y = |y: f32| {
+            vertices
+                .iter()
+                .find(|v| v.position[0] < 0.0 && (v.position[1] - y).abs() < 1e-5)
+                .map(|v| v.color)
+                .unwrap()
+        };
+        assert_eq!(color_at_y(0.0), config.x_axis_color);
+        assert_eq!(color_at_y(0.5), config.major_line_color);
+        assert_eq!(color_at_y(-0.5), config.major_line_color);
+        assert_eq!(color_at_y(0.3), config.line_color);
+    }
+
+    #[test]
+    fn test_line_count_is_capped() {
+        let config = GridConfig {
+            size: 100.0,
+            spacing: 0.001,
+            ..Default::default()
+        };
+        let vertices = generate_grid_vertices(&config);
+        let max_lines = (2 * constants::MAX_LINES_PER_AXIS + 1) as usize;
+        assert_eq!(vertices.len(), 2 * max_lines * 2);
+    }
+}
@@ -0,0 +1,283 @@
+//! Joint axis and limit renderer
+//!
+//! Draws each movable joint's axis as an arrow at the joint origin, colored
+//! by joint type. Revolute joints get an arc spanning their limit range,
+//! continuous joints a full circle, and prismatic joints a bracketed line
+//! from the lower to the upper limit. Lines are drawn without depth testing
+//! since joint origins usually sit inside the link meshes.
+
+use std::f32::consts::TAU;
+
+use glam::{Mat4, Vec3};
+
+use crate::constants::joint_axis as constants;
+use crate::pipeline::{MsaaPipeline, PipelineConfig, create_camera_bind_group};
+use crate::vertex::PositionColorVertex;
+
+/// Motion range shown for a joint axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JointAxisKind {
+    /// Rotation between two angles (radians)
+    Revolute {
+        /// Lower limit (radians)
+        lower: f32,
+        /// Upper limit (radians)
+        upper: f32,
+    },
+    /// Unlimited rotation
+    Continuous,
+    /// Translation between two offsets along the axis (meters)
+    Prismatic {
+        /// Lower limit (meters)
+        lower: f32,
+        /// Upper limit (meters)
+        upper: f32,
+    },
+}
+
+impl JointAxisKind {
+    /// Display color for this kind of joint
+    pub fn color(&self) -> [f32; 3] {
+        match self {
+            JointAxisKind::Revolute { .. } => constants::REVOLUTE_COLOR,
+            JointAxisKind::Continuous => constants::CONTINUOUS_COLOR,
+            JointAxisKind::Prismatic { .. } => constants::PRISMATIC_COLOR,
+        }
+    }
+}
+
+/// A joint axis to visualize
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointAxisInstance {
+    /// World transform of the joint frame
+    pub transform: Mat4,
+    /// Joint axis in the joint frame
+    pub axis: Vec3,
+    /// Joint type and limits
+    pub kind: JointAxisKind,
+    /// Arrow length in world units
+    pub scale: f32,
+}
+
+/// Joint axis renderer
+pub struct JointAxisRenderer {
+    pipeline: MsaaPipeline,
+    bind_group: wgpu::BindGroup,
+    vertex_buffer: wgpu::Buffer,
+    vertex_count: u32,
+}
+
+impl JointAxisRenderer {
+    /// Creates a new joint axis renderer.
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        camera_buffer: &wgpu::Buffer,
+    ) -> Self {
+        let bind_group = create_camera_bind_group(
+            device,
+            camera_bind_group_layout,
+            camera_buffer,
+            "Joint Axis",
+        );
+
+        let pipeline = PipelineConfig::new(
+            "Joint Axis",
+            include_str!("../shaders/joint_axis.wgsl"),
+            format,
+            depth_format,
+            &[camera_bind_group_layout],
+        )
+        .with_vertex_layouts(vec![PositionColorVertex::layout()])
+        .with_topology(wgpu::PrimitiveTopology::LineList)
+        .without_depth_test()
+        .build(device);
+
+        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Joint Axis Vertex Buffer"),
+            size: constants::MAX_VERTICES as u64
+                * std::mem::size_of::<PositionColorVertex>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            bind_group,
+            vertex_buffer,
+            vertex_count: 0,
+        }
+    }
+
+    /// Replace the displayed joint axes
+    pub fn update(&mut self, queue: &wgpu::Queue, axes: &[JointAxisInstance]) {
+        let mut vertices: Vec<PositionColorVertex> =
+            axes.iter().flat_map(generate_joint_axis_vertices).collect();
+
+        if vertices.len() > constants::MAX_VERTICES as usize {
+            tracing::warn!(
+                "Joint axis vertex count {} exceeds maximum {}, truncating",
+                vertices.len(),
+                constants::MAX_VERTICES
+            );
+            // Keep whole line segments
+            vertices.truncate(constants::MAX_VERTICES as usize & !1);
+        }
+
+        self.vertex_count = vertices.len() as u32;
+        if !vertices.is_empty() {
+            queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+        }
+    }
+
+    /// Recreate the pipeline for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.pipeline.set_sample_count(device, sample_count);
+    }
+
+    /// Renders all joint axes.
+    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.vertex_count == 0 {
+            return;
+        }
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.draw(0..self.vertex_count, 0..1);
+    }
+}
+
+/// Generate world-space line-list vertices for one joint axis
+fn generate_joint_axis_vertices(instance: &JointAxisInstance) -> Vec<PositionColorVertex> {
+    let axis = instance.axis.normalize_or(Vec3::Z);
+    let u = axis.any_orthonormal_vector();
+    let v = axis.cross(u);
+    let length = instance.scale;
+    let color = instance.kind.color();
+    let range_color = color.map(|c| c * 0.7);
+
+    let mut lines: Vec<(Vec3, Vec3, [f32; 3])> = Vec::new();
+
+    // Arrow along the axis
+    let tip = axis * length;
+    let head_base = tip - axis * length * constants::HEAD_LENGTH;
+    lines.push((Vec3::ZERO, tip, color));
+    for side in [u, -u, v, -v] {
+        lines.push((
+            tip,
+            head_base + side * length * constants::HEAD_WIDTH,
+            color,
+        ));
+    }
+
+    // Limit range
+    let radius = length * constants::ARC_RADIUS;
+    let on_circle = |angle: f32| (u * angle.cos() + v * angle.sin()) * radius;
+    let mut arc = |lower: f32, upper: f32| {
+        let step = TAU / constants::CIRCLE_SEGMENTS as f32;
+        let segments = ((upper - lower) / step).ceil().max(1.0) as u32;
+        for i in 0..segments {
+            let a0 = lower + (upper - lower) * i as f32 / segments as f32;
+            let a1 = lower + (upper - lower) * (i + 1) as f32 / segments as f32;
+            lines.push((on_circle(a0), on_circle(a1), range_color));
+        }
+    };
+    match instance.kind {
+        JointAxisKind::Revolute { lower, upper } => {
+            let (lower, upper) = (lower.min(upper), lower.max(upper));
+            arc(lower, upper.min(lower + TAU));
+            lines.push((Vec3::ZERO, on_circle(lower), range_color));
+            lines.push((Vec3::ZERO, on_circle(upper), range_color));
+        }
+        JointAxisKind::Continuous => arc(0.0, TAU),
+        JointAxisKind::Prismatic { lower, upper } => {
+            // Offset to the side so the range does not overlap the arrow
+            let offset = u * length * constants::HEAD_WIDTH * 2.0;
+            let tick = u * length * constants::HEAD_WIDTH;
+            let start = offset + axis * lower;
+            let end = offset + axis * upper;
+            lines.push((start, end, range_color));
+            lines.push((start - tick, start + tick, range_color));
+            lines.push((end - tick, end + tick, range_color));
+        }
+    }
+
+    lines
+        .into_iter()
+        .flat_map(|(a, b, color)| {
+            [a, b].map(|p| PositionColorVertex {
+                position: instance.transform.transform_point3(p).to_array(),
+                color,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(kind: JointAxisKind) -> JointAxisInstance {
+        JointAxisInstance {
+            transform: Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0)),
+            axis: Vec3::Z,
+            kind,
+            scale: 1.0,
+        }
+    }
+
+    fn position(vertex: &PositionColorVertex) -> Vec3 {
+        Vec3::from_array(vertex.position)
+    }
+
+    #[test]
+    fn test_arrow_starts_at_joint_origin() {
+        let vertices = generate_joint_axis_vertices(&instance(JointAxisKind::Continuous));
+
+        assert_eq!(vertices.len() % 2, 0);
+        assert_eq!(position(&vertices[0]), Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(position(&vertices[1]), Vec3::new(1.0, 2.0, 4.0));
+        assert_eq!(vertices[0].color, constants::CONTINUOUS_COLOR);
+    }
+
+    #[test]
+    fn test_revolute_arc_spans_limits() {
+        let vertices = generate_joint_axis_vertices(&instance(JointAxisKind::Revolute {
+            lower: -1.0,
+            upper: 1.0,
+        }));
+        let origin = Vec3::new(1.0, 2.0, 3.0);
+
+        // All range vertices lie in the plane of rotation at the arc radius or the origin
+        for vertex in &vertices[10..] {
+            let offset = position(vertex) - origin;
+            assert!(offset.z.abs() < 1e-5);
+            let r = offset.length();
+            assert!(r < 1e-5 || (r - constants::ARC_RADIUS).abs() < 1e-5);
+        }
+
+        // The two radial lines close the arc and are 2 rad apart
+        let n = vertices.len();
+        let lower = position(&vertices[n - 3]) - origin;
+        let upper = position(&vertices[n - 1]) - origin;
+        assert!((lower.angle_between(upper) - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_prismatic_range_follows_limits() {
+        let vertices = generate_joint_axis_vertices(&instance(JointAxisKind::Prismatic {
+            lower: -0.2,
+            upper: 0.5,
+        }));
+
+        // Arrow (5 lines) followed by the range line and two end ticks
+        assert_eq!(vertices.len(), 16);
+        let start = position(&vertices[10]);
+        let end = position(&vertices[11]);
+        assert!((start.z - 2.8).abs() < 1e-5);
+        assert!((end.z - 3.5).abs() < 1e-5);
+    }
+}
@@ -6,12 +6,12 @@ use wgpu::util::DeviceExt;
 
 use crate::constants::{instances, marker as constants};
 use crate::instanced::InstanceBuffer;
-use crate::pipeline::{PipelineConfig, create_camera_bind_group};
+use crate::pipeline::{MsaaPipeline, PipelineConfig, create_camera_bind_group};
 use crate::vertex::PositionVertex;
 
 /// Marker instance data - passed as vertex instance
 #[repr(C)]
-#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
 pub struct MarkerInstance {
     /// Marker center position in world space.
     pub position: [f32; 3],
@@ -44,9 +44,9 @@ impl Default for MarkerInstance {
 
 /// Marker renderer for joint points
 pub struct MarkerRenderer {
-    pipeline: wgpu::RenderPipeline,
+    pipeline: MsaaPipeline,
     /// Pipeline for selected markers (always on top, no depth test)
-    selected_pipeline: wgpu::RenderPipeline,
+    selected_pipeline: MsaaPipeline,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     index_count: u32,
@@ -175,6 +175,13 @@ impl MarkerRenderer {
         self.selected_instances.clear();
     }
 
+    /// Recreate the pipelines for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.pipeline.set_sample_count(device, sample_count);
+        self.selected_pipeline
+            .set_sample_count(device, sample_count);
+    }
+
     /// Renders all marker instances.
     pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         // Render normal markers first (with depth test)
@@ -200,7 +207,7 @@ impl MarkerRenderer {
 }
 
 /// Generate a unit sphere mesh
-fn generate_sphere(segments: u32, rings: u32) -> (Vec<PositionVertex>, Vec<u32>) {
+pub(super) fn generate_sphere(segments: u32, rings: u32) -> (Vec<PositionVertex>, Vec<u32>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
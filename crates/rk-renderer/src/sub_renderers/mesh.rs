@@ -1,13 +1,13 @@
 //! STL mesh renderer with shadow mapping support
 
 use bytemuck::{Pod, Zeroable};
-use glam::Mat4;
+use glam::{Mat4, Vec3};
 use wgpu::util::DeviceExt;
 
-use rk_core::Part;
+use rk_core::{DEFAULT_ROUGHNESS, Part};
 
 use crate::constants::viewport::SAMPLE_COUNT;
-use crate::pipeline::create_camera_bind_group;
+use crate::pipeline::{MsaaPipeline, PipelineDescriptor, VertexLayout, create_camera_bind_group};
 
 /// Vertex for mesh rendering
 #[repr(C)]
@@ -51,7 +51,20 @@ impl MeshVertex {
     }
 }
 
-/// Mesh instance transform
+/// Vertex color marking a vertex that takes the instance color instead
+///
+/// Real colors have alpha in 0..=1, so a negative alpha never collides with one.
+pub(crate) const INSTANCE_COLOR: [f32; 4] = [0.0, 0.0, 0.0, -1.0];
+
+/// Mesh instance highlight flags.
+pub mod highlight {
+    /// Part is selected.
+    pub const SELECTED: u32 = 1;
+    /// Part is under the cursor.
+    pub const HOVERED: u32 = 2;
+}
+
+/// Mesh instance transform and material
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 pub struct MeshInstance {
@@ -59,10 +72,14 @@ pub struct MeshInstance {
     pub model: [[f32; 4]; 4],
     /// Instance color (RGBA).
     pub color: [f32; 4],
-    /// Selection state (0 = unselected, 1 = selected).
-    pub selected: u32,
-    /// Padding for alignment.
-    pub _pad: [u32; 3],
+    /// Highlight flags (see [`highlight`]).
+    pub highlight: u32,
+    /// PBR metallic factor (0.0 - 1.0).
+    pub metallic: f32,
+    /// PBR roughness factor (0.0 - 1.0).
+    pub roughness: f32,
+    /// Self-collision state (0 = clear, 1 = colliding).
+    pub colliding: u32,
 }
 
 impl Default for MeshInstance {
@@ -70,8 +87,10 @@ impl Default for MeshInstance {
         Self {
             model: Mat4::IDENTITY.to_cols_array_2d(),
             color: [0.7, 0.7, 0.7, 1.0],
-            selected: 0,
-            _pad: [0; 3],
+            highlight: 0,
+            metallic: 0.0,
+            roughness: DEFAULT_ROUGHNESS,
+            colliding: 0,
         }
     }
 }
@@ -84,7 +103,7 @@ pub struct MeshData {
     pub index_buffer: wgpu::Buffer,
     /// Number of indices.
     pub index_count: u32,
-    /// Instance data (transform, color, selection).
+    /// Instance data (transform, color, selection, material).
     pub instance: MeshInstance,
     /// GPU buffer for instance data.
     pub instance_buffer: wgpu::Buffer,
@@ -102,8 +121,9 @@ impl MeshData {
             part.bbox_max
         );
 
-        // Build vertices with normals
+        // Build vertices with normals; parts without vertex colors use the instance color
         let mut vertices = Vec::new();
+        let has_vertex_colors = part.vertex_colors.len() == part.vertices.len();
 
         for (i, chunk) in part.indices.chunks(3).enumerate() {
             if chunk.len() != 3 {
@@ -121,22 +141,42 @@ impl MeshData {
                 vertices.push(MeshVertex {
                     position: pos,
                     normal,
-                    color: part.color,
+                    color: if has_vertex_colors {
+                        part.vertex_colors[idx as usize]
+                    } else {
+                        INSTANCE_COLOR
+                    },
                 });
             }
         }
 
-        let indices: Vec<u32> = (0..vertices.len() as u32).collect();
+        tracing::info!("MeshData created: {} GPU vertices", vertices.len());
 
-        tracing::info!(
-            "MeshData created: {} GPU vertices, {} indices",
-            vertices.len(),
-            indices.len()
-        );
+        let instance = MeshInstance {
+            model: part.origin_transform.to_cols_array_2d(),
+            color: part.display_color(),
+            highlight: 0,
+            metallic: part.metallic,
+            roughness: part.roughness,
+            colliding: 0,
+        };
+
+        Self::from_vertices(device, &vertices, instance)
+    }
+
+    /// Create mesh data from a non-indexed triangle list
+    ///
+    /// Vertices whose color alpha is negative take the instance color.
+    pub fn from_vertices(
+        device: &wgpu::Device,
+        vertices: &[MeshVertex],
+        instance: MeshInstance,
+    ) -> Self {
+        let indices: Vec<u32> = (0..vertices.len() as u32).collect();
 
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Mesh Vertex Buffer"),
-            contents: bytemuck::cast_slice(&vertices),
+            contents: bytemuck::cast_slice(vertices),
             usage: wgpu::BufferUsages::VERTEX,
         });
 
@@ -146,11 +186,32 @@ impl MeshData {
             usage: wgpu::BufferUsages::INDEX,
         });
 
+        let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mesh Instance Buffer"),
+            contents: bytemuck::cast_slice(&[instance]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            index_count: indices.len() as u32,
+            instance,
+            instance_buffer,
+        }
+    }
+
+    /// Create mesh data for another part that reuses this mesh's geometry buffers
+    ///
+    /// Only the instance data (transform, color, material) is taken from `part`.
+    pub fn instance_of(&self, device: &wgpu::Device, part: &Part) -> Self {
         let instance = MeshInstance {
             model: part.origin_transform.to_cols_array_2d(),
-            color: part.color,
-            selected: 0,
-            _pad: [0; 3],
+            color: part.display_color(),
+            highlight: 0,
+            metallic: part.metallic,
+            roughness: part.roughness,
+            colliding: 0,
         };
 
         let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -160,14 +221,59 @@ impl MeshData {
         });
 
         Self {
-            vertex_buffer,
-            index_buffer,
-            index_count: indices.len() as u32,
+            vertex_buffer: self.vertex_buffer.clone(),
+            index_buffer: self.index_buffer.clone(),
+            index_count: self.index_count,
             instance,
             instance_buffer,
         }
     }
 
+    /// Create a box spanning `min`..`max` that is drawn with this mesh's instance data
+    ///
+    /// The box shares the instance buffer, so it follows the transform,
+    /// color and highlight of this mesh. Its `instance` copy is not kept up
+    /// to date; update the original mesh instead.
+    pub fn box_proxy(&self, device: &wgpu::Device, min: Vec3, max: Vec3) -> Self {
+        let corner = |x: bool, y: bool, z: bool| {
+            Vec3::new(
+                if x { max.x } else { min.x },
+                if y { max.y } else { min.y },
+                if z { max.z } else { min.z },
+            )
+        };
+        let mut vertices = Vec::with_capacity(36);
+        for axis in 0..3 {
+            for positive in [false, true] {
+                let mut normal = Vec3::ZERO;
+                normal[axis] = if positive { 1.0 } else { -1.0 };
+                // Corners of the face in counter-clockwise order seen from outside
+                let quad =
+                    [(false, false), (true, false), (true, true), (false, true)].map(|(u, v)| {
+                        let (u, v) = if positive { (u, v) } else { (v, u) };
+                        match axis {
+                            0 => corner(positive, u, v),
+                            1 => corner(v, positive, u),
+                            _ => corner(u, v, positive),
+                        }
+                    });
+                for i in [0, 1, 2, 0, 2, 3] {
+                    vertices.push(MeshVertex {
+                        position: quad[i].to_array(),
+                        normal: normal.to_array(),
+                        color: INSTANCE_COLOR,
+                    });
+                }
+            }
+        }
+
+        let proxy = Self::from_vertices(device, &vertices, self.instance);
+        Self {
+            instance_buffer: self.instance_buffer.clone(),
+            ..proxy
+        }
+    }
+
     /// Update instance transform
     pub fn update_transform(&mut self, queue: &wgpu::Queue, transform: Mat4) {
         self.instance.model = transform.to_cols_array_2d();
@@ -188,9 +294,51 @@ impl MeshData {
         );
     }
 
+    /// Update instance PBR material factors
+    pub fn update_material(&mut self, queue: &wgpu::Queue, metallic: f32, roughness: f32) {
+        self.instance.metallic = metallic.clamp(0.0, 1.0);
+        self.instance.roughness = roughness.clamp(0.0, 1.0);
+        queue.write_buffer(
+            &self.instance_buffer,
+            0,
+            bytemuck::cast_slice(&[self.instance]),
+        );
+    }
+
+    /// Set self-collision state
+    pub fn set_colliding(&mut self, queue: &wgpu::Queue, colliding: bool) {
+        let colliding = if colliding { 1 } else { 0 };
+        if self.instance.colliding == colliding {
+            return;
+        }
+        self.instance.colliding = colliding;
+        queue.write_buffer(
+            &self.instance_buffer,
+            0,
+            bytemuck::cast_slice(&[self.instance]),
+        );
+    }
+
     /// Set selected state
     pub fn set_selected(&mut self, queue: &wgpu::Queue, selected: bool) {
-        self.instance.selected = if selected { 1 } else { 0 };
+        self.set_highlight(queue, highlight::SELECTED, selected);
+    }
+
+    /// Set hovered state
+    pub fn set_hovered(&mut self, queue: &wgpu::Queue, hovered: bool) {
+        self.set_highlight(queue, highlight::HOVERED, hovered);
+    }
+
+    fn set_highlight(&mut self, queue: &wgpu::Queue, flag: u32, enabled: bool) {
+        let highlight = if enabled {
+            self.instance.highlight | flag
+        } else {
+            self.instance.highlight & !flag
+        };
+        if highlight == self.instance.highlight {
+            return;
+        }
+        self.instance.highlight = highlight;
         queue.write_buffer(
             &self.instance_buffer,
             0,
@@ -201,7 +349,8 @@ impl MeshData {
 
 /// Mesh renderer with shadow mapping support
 pub struct MeshRenderer {
-    pipeline: wgpu::RenderPipeline,
+    pipeline: MsaaPipeline,
+    xray_pipeline: MsaaPipeline,
     shadow_pipeline: wgpu::RenderPipeline,
     camera_bind_group: wgpu::BindGroup,
     instance_bind_group_layout: wgpu::BindGroupLayout,
@@ -294,45 +443,52 @@ impl MeshRenderer {
             push_constant_ranges: &[],
         });
 
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Mesh Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[MeshVertex::layout()],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
+        let main_pipeline = |label: &str, blend, depth_write_enabled| {
+            let descriptor = PipelineDescriptor {
+                label: label.to_string(),
+                shader: shader.clone(),
+                layout: pipeline_layout.clone(),
+                vs_entry_point: "vs_main".to_string(),
+                fs_entry_point: "fs_main".to_string(),
+                vertex_layouts: vec![VertexLayout::from(&MeshVertex::layout())],
+                target: wgpu::ColorTargetState {
                     format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    blend: Some(blend),
                     write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                cull_mode: None, // Disable culling to show both sides
-                ..Default::default()
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: depth_format,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState {
-                count: SAMPLE_COUNT,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    cull_mode: None, // Disable culling to show both sides
+                    ..Default::default()
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: depth_format,
+                    depth_write_enabled,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+            };
+            MsaaPipeline::new(device, descriptor, SAMPLE_COUNT)
+        };
+
+        let pipeline = main_pipeline("Mesh Pipeline", wgpu::BlendState::ALPHA_BLENDING, true);
+
+        // X-ray pipeline - blends meshes at the pass blend constant and leaves
+        // depth alone, so collision shapes inside them stay visible
+        let dimmed = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::Constant,
+            dst_factor: wgpu::BlendFactor::OneMinusConstant,
+            operation: wgpu::BlendOperation::Add,
+        };
+        let xray_pipeline = main_pipeline(
+            "Mesh X-Ray Pipeline",
+            wgpu::BlendState {
+                color: dimmed,
+                alpha: dimmed,
             },
-            multiview: None,
-            cache: None,
-        });
+            false,
+        );
 
         // Shadow pipeline - uses light uniform at group 0, instance at group 1
         // (different from main pipeline which has camera at group 0)
@@ -396,6 +552,7 @@ impl MeshRenderer {
 
         Self {
             pipeline,
+            xray_pipeline,
             shadow_pipeline,
             camera_bind_group,
             instance_bind_group_layout,
@@ -429,6 +586,14 @@ impl MeshRenderer {
         })
     }
 
+    /// Recreate the main and x-ray pipelines for a new MSAA sample count
+    ///
+    /// The shadow pass renders into its own single-sampled map.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        self.pipeline.set_sample_count(device, sample_count);
+        self.xray_pipeline.set_sample_count(device, sample_count);
+    }
+
     /// Render mesh to shadow map (depth-only pass)
     pub fn render_shadow<'a>(
         &'a self,
@@ -461,4 +626,29 @@ impl MeshRenderer {
         render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
         render_pass.draw_indexed(0..mesh.index_count, 0, 0..1);
     }
+
+    /// Render mesh dimmed to `opacity` without writing depth, for X-ray view
+    pub fn render_xray<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        mesh: &'a MeshData,
+        instance_bind_group: &'a wgpu::BindGroup,
+        light_bind_group: &'a wgpu::BindGroup,
+        opacity: f32,
+    ) {
+        let opacity = opacity as f64;
+        render_pass.set_blend_constant(wgpu::Color {
+            r: opacity,
+            g: opacity,
+            b: opacity,
+            a: opacity,
+        });
+        render_pass.set_pipeline(&self.xray_pipeline);
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        render_pass.set_bind_group(1, instance_bind_group, &[]);
+        render_pass.set_bind_group(2, light_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+    }
 }
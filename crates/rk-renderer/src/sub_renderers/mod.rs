@@ -3,38 +3,52 @@
 //! This module contains all rendering components organized by functionality:
 //!
 //! ## New Architecture (SubRenderer trait)
-//! - [`GridSubRenderer`]: Grid sub-renderer implementing the new trait
+//! - [`BackgroundSubRenderer`]: Gradient viewport background
+//! - [`GridSubRenderer`]: Ground grid (line grid or infinite shader grid)
 //! - [`SketchRenderer`]: 2D sketch visualization on 3D planes
+//! - [`TrajectoryRenderer`]: Polyline through 3D points, e.g. end-effector paths
 //!
 //! ## Legacy Renderers (being migrated)
-//! - [`grid_legacy::GridRenderer`]: Legacy grid implementation
 //! - [`mesh::MeshRenderer`]: 3D geometry rendering
+//! - [`edges::EdgeRenderer`]: Crease and silhouette lines over meshes
+//! - [`floor::FloorRenderer`]: Ground plane drawn through the mesh pipeline
 //! - [`axis::AxisRenderer`]: Coordinate frame indicators
 //! - [`marker::MarkerRenderer`]: Joint point visualization
+//! - [`joint_axis::JointAxisRenderer`]: Joint axis and limit visualization
 //! - [`gizmo::GizmoRenderer`]: Transform manipulation tool
 //! - [`collision::CollisionRenderer`]: Collision shape visualization
 
 // New trait-based implementations
+mod background;
 mod grid;
 pub mod sketch;
+pub mod trajectory;
 
 // Legacy implementations (to be migrated to SubRenderer trait)
 pub mod axis;
 pub mod collision;
+pub mod edges;
+pub mod floor;
 pub mod gizmo;
-pub mod grid_legacy;
+pub mod joint_axis;
 pub mod marker;
 pub mod mesh;
 
 // Re-exports for new architecture
+pub use background::BackgroundSubRenderer;
+#[allow(deprecated)]
+pub use grid::GridRenderer;
 pub use grid::GridSubRenderer;
 pub use sketch::{SketchRenderData, SketchRenderer, SketchVertex};
+pub use trajectory::{TrajectoryRenderer, TrajectoryVertex};
 
 // Re-exports for legacy code
 pub use axis::{AxisInstance, AxisRenderer};
 pub use collision::{CollisionInstance, CollisionRenderer};
+pub use edges::{EdgeMesh, EdgeRenderer};
+pub use floor::FloorRenderer;
 pub use gizmo::{GizmoAxis, GizmoMode, GizmoRenderer, GizmoSpace};
-pub use grid_legacy::GridRenderer;
+pub use joint_axis::{JointAxisInstance, JointAxisKind, JointAxisRenderer};
 pub use marker::{MarkerInstance, MarkerRenderer};
 pub use mesh::{MeshData, MeshRenderer, MeshVertex};
 
@@ -43,6 +57,8 @@ pub use mesh::{MeshData, MeshRenderer, MeshVertex};
 /// Lower values are rendered first (background), higher values are rendered
 /// on top. Use these constants when implementing custom sub-renderers.
 pub mod priorities {
+    /// Background gradient is rendered before everything else
+    pub const BACKGROUND: i32 = -100;
     /// Grid is rendered first (background)
     pub const GRID: i32 = 0;
     /// Sketches are rendered after grid, before meshes
@@ -51,6 +67,8 @@ pub mod priorities {
     pub const MESH: i32 = 100;
     /// Axes are rendered on top of meshes
     pub const AXIS: i32 = 200;
+    /// Trajectories are rendered over axes, just below markers
+    pub const TRAJECTORY: i32 = 250;
     /// Markers are rendered on top of axes
     pub const MARKER: i32 = 300;
     /// Gizmo is always on top
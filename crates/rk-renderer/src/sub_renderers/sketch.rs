@@ -7,14 +7,16 @@ use glam::{Mat4, Vec2, Vec3, Vec4};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use wgpu::util::DeviceExt;
+
 use crate::context::RenderContext;
-use crate::pipeline::PipelineConfig;
+use crate::pipeline::{MsaaPipeline, PipelineConfig, create_camera_bind_group};
 use crate::scene::Scene;
 use crate::traits::SubRenderer;
 
 /// Vertex for sketch rendering.
 #[repr(C)]
-#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct SketchVertex {
     /// Position in sketch space (z = 0 for 2D sketches).
     pub position: [f32; 3],
@@ -86,7 +88,7 @@ struct SketchUniform {
 }
 
 /// Data for a single sketch to be rendered.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SketchRenderData {
     /// Sketch ID.
     pub id: Uuid,
@@ -189,6 +191,37 @@ impl SketchRenderData {
         }
     }
 
+    /// Add a polyline, optionally broken into dashes of the given length.
+    ///
+    /// Dashes and gaps have equal length and continue across vertices, so
+    /// curves made of many short segments are dashed evenly.
+    pub fn add_polyline(&mut self, points: &[Vec2], color: Vec4, flags: u32, dash: Option<f32>) {
+        let Some(dash) = dash.filter(|d| *d > 0.0) else {
+            for pair in points.windows(2) {
+                self.add_line(pair[0], pair[1], color, flags);
+            }
+            return;
+        };
+
+        // Distance along the pattern; even multiples of `dash` start a dash
+        let mut phase = 0.0f32;
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let length = a.distance(b);
+            let mut t = 0.0;
+            while t < length {
+                let in_dash = ((phase / dash) as u32).is_multiple_of(2);
+                let step = (dash - phase % dash).min(length - t);
+                if in_dash {
+                    let dir = (b - a) / length;
+                    self.add_line(a + dir * t, a + dir * (t + step), color, flags);
+                }
+                t += step;
+                phase += step;
+            }
+        }
+    }
+
     /// Clear all geometry.
     pub fn clear(&mut self) {
         self.line_vertices.clear();
@@ -200,16 +233,18 @@ impl SketchRenderData {
 pub struct SketchRenderer {
     enabled: bool,
     initialized: bool,
-    line_pipeline: Option<wgpu::RenderPipeline>,
-    point_pipeline: Option<wgpu::RenderPipeline>,
+    line_pipeline: Option<MsaaPipeline>,
+    point_pipeline: Option<MsaaPipeline>,
     camera_bind_group: Option<wgpu::BindGroup>,
     sketch_bind_group_layout: Option<wgpu::BindGroupLayout>,
 
     /// Per-sketch GPU resources.
     sketch_resources: HashMap<Uuid, SketchGpuResources>,
 
-    /// Sketch data to render (updated each frame).
+    /// Sketch data to render.
     pending_sketches: Vec<SketchRenderData>,
+    /// Set when the sketch data changed and GPU buffers need to be rebuilt
+    dirty: bool,
 }
 
 #[allow(dead_code)]
@@ -240,112 +275,113 @@ impl SketchRenderer {
             sketch_bind_group_layout: None,
             sketch_resources: HashMap::new(),
             pending_sketches: Vec::new(),
+            dirty: false,
         }
     }
 
     /// Set sketch data to render.
     pub fn set_sketches(&mut self, sketches: Vec<SketchRenderData>) {
-        self.pending_sketches = sketches;
+        if sketches != self.pending_sketches {
+            self.pending_sketches = sketches;
+            self.dirty = true;
+        }
     }
 
     /// Add a single sketch to render.
     pub fn add_sketch(&mut self, sketch: SketchRenderData) {
         self.pending_sketches.push(sketch);
+        self.dirty = true;
     }
 
     /// Clear all sketches.
     pub fn clear_sketches(&mut self) {
-        self.pending_sketches.clear();
-    }
-}
-
-impl SubRenderer for SketchRenderer {
-    fn name(&self) -> &str {
-        "sketch"
-    }
-
-    fn priority(&self) -> i32 {
-        super::priorities::SKETCH
-    }
-
-    fn is_enabled(&self) -> bool {
-        self.enabled
+        if !self.pending_sketches.is_empty() {
+            self.pending_sketches.clear();
+            self.dirty = true;
+        }
     }
 
-    fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+    /// Recreate the pipelines for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        for pipeline in [&mut self.line_pipeline, &mut self.point_pipeline]
+            .into_iter()
+            .flatten()
+        {
+            pipeline.set_sample_count(device, sample_count);
+        }
     }
 
-    fn on_init(&mut self, ctx: &RenderContext) {
+    /// Creates GPU resources without a [`RenderContext`].
+    ///
+    /// Used by the main [`Renderer`](crate::Renderer), which owns its camera
+    /// buffer directly.
+    pub fn init(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        camera_buffer: &wgpu::Buffer,
+    ) {
         // Create sketch uniform bind group layout
         let sketch_bind_group_layout =
-            ctx.device()
-                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                    label: Some("Sketch Bind Group Layout"),
-                    entries: &[wgpu::BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Uniform,
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    }],
-                });
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Sketch Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
 
         // Create line pipeline
         let line_pipeline = PipelineConfig::new(
             "Sketch Lines",
             include_str!("../shaders/sketch.wgsl"),
-            ctx.surface_format(),
-            ctx.depth_format(),
-            &[ctx.camera_bind_group_layout(), &sketch_bind_group_layout],
+            format,
+            depth_format,
+            &[camera_bind_group_layout, &sketch_bind_group_layout],
         )
         .with_vertex_layouts(vec![SketchVertex::layout()])
         .with_topology(wgpu::PrimitiveTopology::LineList)
         .with_blend(wgpu::BlendState::ALPHA_BLENDING)
         .with_depth_write(false)
-        .build(ctx.device());
+        .build(device);
 
         // Create point pipeline (using same shader but different topology)
         let point_pipeline = PipelineConfig::new(
             "Sketch Points",
             include_str!("../shaders/sketch.wgsl"),
-            ctx.surface_format(),
-            ctx.depth_format(),
-            &[ctx.camera_bind_group_layout(), &sketch_bind_group_layout],
+            format,
+            depth_format,
+            &[camera_bind_group_layout, &sketch_bind_group_layout],
         )
         .with_vertex_layouts(vec![SketchVertex::layout()])
         .with_topology(wgpu::PrimitiveTopology::PointList)
         .with_blend(wgpu::BlendState::ALPHA_BLENDING)
         .with_depth_write(false)
         .with_entry_point("vs_point", "fs_point")
-        .build(ctx.device());
-
-        // Create camera bind group
-        let camera_bind_group = ctx.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Sketch Camera Bind Group"),
-            layout: ctx.camera_bind_group_layout(),
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: ctx.camera_buffer().as_entire_binding(),
-            }],
-        });
+        .build(device);
+
+        let camera_bind_group =
+            create_camera_bind_group(device, camera_bind_group_layout, camera_buffer, "Sketch");
 
         self.line_pipeline = Some(line_pipeline);
         self.point_pipeline = Some(point_pipeline);
         self.camera_bind_group = Some(camera_bind_group);
         self.sketch_bind_group_layout = Some(sketch_bind_group_layout);
         self.initialized = true;
+        self.dirty = true;
     }
 
-    fn on_resize(&mut self, _ctx: &RenderContext, _width: u32, _height: u32) {
-        // Sketch renderer doesn't need to respond to resize
-    }
-
-    fn prepare(&mut self, ctx: &RenderContext, _scene: &Scene) {
-        if !self.initialized {
+    /// Rebuild GPU buffers if the sketch data changed since the last upload.
+    pub fn upload(&mut self, device: &wgpu::Device) {
+        if !self.initialized || !self.dirty {
             return;
         }
 
@@ -358,60 +394,56 @@ impl SubRenderer for SketchRenderer {
                 plane_color: [0.5, 0.5, 0.5, 0.2],
             };
 
-            // Create or update resources
-            let needs_update = !self.sketch_resources.contains_key(&sketch_data.id);
             let line_count = sketch_data.line_vertices.len() as u32;
             let point_count = sketch_data.point_vertices.len() as u32;
 
-            if needs_update || line_count > 0 || point_count > 0 {
-                // Create buffers
-                let line_buffer = ctx.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some("Sketch Line Buffer"),
-                    contents: if sketch_data.line_vertices.is_empty() {
-                        &[0u8; std::mem::size_of::<SketchVertex>()]
-                    } else {
-                        bytemuck::cast_slice(&sketch_data.line_vertices)
-                    },
-                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                });
-
-                let point_buffer = ctx.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some("Sketch Point Buffer"),
-                    contents: if sketch_data.point_vertices.is_empty() {
-                        &[0u8; std::mem::size_of::<SketchVertex>()]
-                    } else {
-                        bytemuck::cast_slice(&sketch_data.point_vertices)
-                    },
-                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                });
-
-                let uniform_buffer = ctx.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some("Sketch Uniform Buffer"),
-                    contents: bytemuck::bytes_of(&uniform),
-                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-                });
-
-                let bind_group = ctx.create_bind_group(&wgpu::BindGroupDescriptor {
-                    label: Some("Sketch Bind Group"),
-                    layout,
-                    entries: &[wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: uniform_buffer.as_entire_binding(),
-                    }],
-                });
-
-                self.sketch_resources.insert(
-                    sketch_data.id,
-                    SketchGpuResources {
-                        line_buffer,
-                        point_buffer,
-                        uniform_buffer,
-                        bind_group,
-                        line_count,
-                        point_count,
-                    },
-                );
-            }
+            // Create buffers
+            let line_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Sketch Line Buffer"),
+                contents: if sketch_data.line_vertices.is_empty() {
+                    &[0u8; std::mem::size_of::<SketchVertex>()]
+                } else {
+                    bytemuck::cast_slice(&sketch_data.line_vertices)
+                },
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            });
+
+            let point_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Sketch Point Buffer"),
+                contents: if sketch_data.point_vertices.is_empty() {
+                    &[0u8; std::mem::size_of::<SketchVertex>()]
+                } else {
+                    bytemuck::cast_slice(&sketch_data.point_vertices)
+                },
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            });
+
+            let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Sketch Uniform Buffer"),
+                contents: bytemuck::bytes_of(&uniform),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Sketch Bind Group"),
+                layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                }],
+            });
+
+            self.sketch_resources.insert(
+                sketch_data.id,
+                SketchGpuResources {
+                    line_buffer,
+                    point_buffer,
+                    uniform_buffer,
+                    bind_group,
+                    line_count,
+                    point_count,
+                },
+            );
         }
 
         // Remove resources for sketches no longer being rendered
@@ -419,9 +451,11 @@ impl SubRenderer for SketchRenderer {
             self.pending_sketches.iter().map(|s| s.id).collect();
         self.sketch_resources
             .retain(|id, _| active_ids.contains(id));
+        self.dirty = false;
     }
 
-    fn render<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>, _scene: &Scene) {
+    /// Record draw commands for all sketches.
+    pub fn draw<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>) {
         if !self.initialized || self.pending_sketches.is_empty() {
             return;
         }
@@ -458,3 +492,92 @@ impl SubRenderer for SketchRenderer {
         }
     }
 }
+
+impl SubRenderer for SketchRenderer {
+    fn name(&self) -> &str {
+        "sketch"
+    }
+
+    fn priority(&self) -> i32 {
+        super::priorities::SKETCH
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn on_init(&mut self, ctx: &RenderContext) {
+        self.init(
+            ctx.device(),
+            ctx.surface_format(),
+            ctx.depth_format(),
+            ctx.camera_bind_group_layout(),
+            ctx.camera_buffer(),
+        );
+        self.set_sample_count(ctx.device(), ctx.sample_count());
+    }
+
+    fn on_sample_count_changed(&mut self, ctx: &RenderContext) {
+        self.set_sample_count(ctx.device(), ctx.sample_count());
+    }
+
+    fn on_resize(&mut self, _ctx: &RenderContext, _width: u32, _height: u32) {
+        // Sketch renderer doesn't need to respond to resize
+    }
+
+    fn prepare(&mut self, ctx: &RenderContext, _scene: &Scene) {
+        self.upload(ctx.device());
+    }
+
+    fn render<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>, _scene: &Scene) {
+        self.draw(pass);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dashed_polyline_continues_across_vertices() {
+        let mut data = SketchRenderData::default();
+        let points = [Vec2::ZERO, Vec2::new(1.5, 0.0), Vec2::new(1.5, 1.5)];
+
+        data.add_polyline(&points, Vec4::ONE, flags::CONSTRUCTION, Some(0.5));
+
+        // Dashes at 0-0.5, 1-1.5 and, after the corner, 2-2.5 along the path
+        let segments: Vec<(Vec3, Vec3)> = data
+            .line_vertices
+            .chunks(2)
+            .map(|v| (Vec3::from(v[0].position), Vec3::from(v[1].position)))
+            .collect();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(
+            segments[1],
+            (Vec3::new(1.0, 0.0, 0.0), Vec3::new(1.5, 0.0, 0.0))
+        );
+        assert_eq!(
+            segments[2],
+            (Vec3::new(1.5, 0.5, 0.0), Vec3::new(1.5, 1.0, 0.0))
+        );
+        assert!(
+            data.line_vertices
+                .iter()
+                .all(|v| v.flags == flags::CONSTRUCTION)
+        );
+    }
+
+    #[test]
+    fn test_solid_polyline() {
+        let mut data = SketchRenderData::default();
+        let points = [Vec2::ZERO, Vec2::X, Vec2::ONE];
+
+        data.add_polyline(&points, Vec4::ONE, 0, None);
+
+        assert_eq!(data.line_vertices.len(), 4);
+    }
+}
@@ -0,0 +1,393 @@
+//! Trajectory sub-renderer for visualizing 3D paths.
+//!
+//! Draws a polyline through a list of points, for example an end-effector
+//! path swept by a joint, with optional spheres at each point that reuse the
+//! marker geometry and shader.
+
+use glam::Vec3;
+use wgpu::util::DeviceExt;
+
+use super::marker::{MarkerInstance, generate_sphere};
+use crate::constants::marker as marker_constants;
+use crate::context::RenderContext;
+use crate::pipeline::{MsaaPipeline, PipelineConfig, create_camera_bind_group};
+use crate::scene::Scene;
+use crate::traits::SubRenderer;
+use crate::vertex::PositionVertex;
+
+/// Vertex for trajectory lines.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TrajectoryVertex {
+    /// Position in world space.
+    pub position: [f32; 3],
+    /// Line color (RGBA).
+    pub color: [f32; 4],
+}
+
+impl TrajectoryVertex {
+    /// Vertex attributes for the shader.
+    pub const ATTRIBUTES: &'static [wgpu::VertexAttribute] = &[
+        wgpu::VertexAttribute {
+            offset: 0,
+            shader_location: 0,
+            format: wgpu::VertexFormat::Float32x3,
+        },
+        wgpu::VertexAttribute {
+            offset: std::mem::size_of::<[f32; 3]>() as u64,
+            shader_location: 1,
+            format: wgpu::VertexFormat::Float32x4,
+        },
+    ];
+
+    /// Returns the vertex buffer layout.
+    pub fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as u64,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// GPU resources created on initialization.
+struct TrajectoryGpu {
+    line_pipeline: MsaaPipeline,
+    sphere_pipeline: MsaaPipeline,
+    camera_bind_group: wgpu::BindGroup,
+    sphere_vertex_buffer: wgpu::Buffer,
+    sphere_index_buffer: wgpu::Buffer,
+    sphere_index_count: u32,
+    /// Line strip vertices, `None` while there are fewer than two points
+    line_buffer: Option<(wgpu::Buffer, u32)>,
+    /// Sphere instances, `None` while spheres are off or there are no points
+    instance_buffer: Option<(wgpu::Buffer, u32)>,
+}
+
+/// Trajectory sub-renderer drawing a line strip through 3D points.
+pub struct TrajectoryRenderer {
+    enabled: bool,
+    points: Vec<Vec3>,
+    color: [f32; 4],
+    /// Radius of the spheres drawn at each point, or `None` for no spheres
+    point_radius: Option<f32>,
+    /// Set when the points or style changed and GPU buffers need to be rebuilt
+    dirty: bool,
+    gpu: Option<TrajectoryGpu>,
+}
+
+impl TrajectoryRenderer {
+    /// Default line color (orange).
+    pub const DEFAULT_COLOR: [f32; 4] = [1.0, 0.6, 0.1, 1.0];
+
+    /// Creates a new, empty trajectory renderer.
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            points: Vec::new(),
+            color: Self::DEFAULT_COLOR,
+            point_radius: None,
+            dirty: false,
+            gpu: None,
+        }
+    }
+
+    /// Returns the trajectory points.
+    pub fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    /// Replaces the trajectory points.
+    pub fn set_points(&mut self, points: Vec<Vec3>) {
+        self.points = points;
+        self.dirty = true;
+    }
+
+    /// Returns the line and sphere color.
+    pub fn color(&self) -> [f32; 4] {
+        self.color
+    }
+
+    /// Sets the line and sphere color.
+    pub fn set_color(&mut self, color: [f32; 4]) {
+        if color != self.color {
+            self.color = color;
+            self.dirty = true;
+        }
+    }
+
+    /// Returns the radius of the per-point spheres, if they are shown.
+    pub fn point_radius(&self) -> Option<f32> {
+        self.point_radius
+    }
+
+    /// Shows a sphere of `radius` at each point, or hides them with `None`.
+    pub fn set_point_radius(&mut self, radius: Option<f32>) {
+        if radius != self.point_radius {
+            self.point_radius = radius;
+            self.dirty = true;
+        }
+    }
+
+    /// Recreate the pipelines for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        if let Some(gpu) = &mut self.gpu {
+            gpu.line_pipeline.set_sample_count(device, sample_count);
+            gpu.sphere_pipeline.set_sample_count(device, sample_count);
+        }
+    }
+
+    /// Creates GPU resources without a [`RenderContext`].
+    pub fn init(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        camera_buffer: &wgpu::Buffer,
+    ) {
+        let layouts = [camera_bind_group_layout];
+
+        let line_pipeline = PipelineConfig::new(
+            "Trajectory",
+            include_str!("../shaders/trajectory.wgsl"),
+            format,
+            depth_format,
+            &layouts,
+        )
+        .with_vertex_layouts(vec![TrajectoryVertex::layout()])
+        .with_topology(wgpu::PrimitiveTopology::LineStrip)
+        .build(device);
+
+        let sphere_pipeline = PipelineConfig::new(
+            "Trajectory Point",
+            include_str!("../shaders/marker.wgsl"),
+            format,
+            depth_format,
+            &layouts,
+        )
+        .with_vertex_layouts(vec![PositionVertex::layout(), marker_instance_layout()])
+        .with_cull_mode(Some(wgpu::Face::Back))
+        .build(device);
+
+        let camera_bind_group = create_camera_bind_group(
+            device,
+            camera_bind_group_layout,
+            camera_buffer,
+            "Trajectory",
+        );
+
+        let (vertices, indices) =
+            generate_sphere(marker_constants::SEGMENTS, marker_constants::RINGS);
+        let sphere_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Trajectory Sphere Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let sphere_index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Trajectory Sphere Index Buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        self.gpu = Some(TrajectoryGpu {
+            line_pipeline,
+            sphere_pipeline,
+            camera_bind_group,
+            sphere_vertex_buffer,
+            sphere_index_buffer,
+            sphere_index_count: indices.len() as u32,
+            line_buffer: None,
+            instance_buffer: None,
+        });
+        // Upload any points set before initialization
+        self.dirty = true;
+    }
+
+    /// Rebuild GPU buffers if the points or style changed since the last upload.
+    pub fn upload(&mut self, device: &wgpu::Device) {
+        if !self.dirty {
+            return;
+        }
+        let Some(gpu) = self.gpu.as_mut() else {
+            return;
+        };
+
+        let vertices = line_vertices(&self.points, self.color);
+        gpu.line_buffer = (vertices.len() >= 2).then(|| {
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Trajectory Vertex Buffer"),
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+            (buffer, vertices.len() as u32)
+        });
+
+        let instances = point_instances(&self.points, self.point_radius, self.color);
+        gpu.instance_buffer = (!instances.is_empty()).then(|| {
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Trajectory Point Instance Buffer"),
+                contents: bytemuck::cast_slice(&instances),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+            (buffer, instances.len() as u32)
+        });
+
+        self.dirty = false;
+    }
+
+    /// Record draw commands for the trajectory.
+    pub fn draw<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>) {
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+
+        if let Some((buffer, count)) = &gpu.line_buffer {
+            pass.set_pipeline(&gpu.line_pipeline);
+            pass.set_bind_group(0, &gpu.camera_bind_group, &[]);
+            pass.set_vertex_buffer(0, buffer.slice(..));
+            pass.draw(0..*count, 0..1);
+        }
+
+        if let Some((buffer, count)) = &gpu.instance_buffer {
+            pass.set_pipeline(&gpu.sphere_pipeline);
+            pass.set_bind_group(0, &gpu.camera_bind_group, &[]);
+            pass.set_vertex_buffer(0, gpu.sphere_vertex_buffer.slice(..));
+            pass.set_vertex_buffer(1, buffer.slice(..));
+            pass.set_index_buffer(gpu.sphere_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            pass.draw_indexed(0..gpu.sphere_index_count, 0, 0..*count);
+        }
+    }
+}
+
+impl Default for TrajectoryRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubRenderer for TrajectoryRenderer {
+    fn name(&self) -> &str {
+        "trajectory"
+    }
+
+    fn priority(&self) -> i32 {
+        super::priorities::TRAJECTORY
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn on_init(&mut self, ctx: &RenderContext) {
+        self.init(
+            ctx.device(),
+            ctx.surface_format(),
+            ctx.depth_format(),
+            ctx.camera_bind_group_layout(),
+            ctx.camera_buffer(),
+        );
+        self.set_sample_count(ctx.device(), ctx.sample_count());
+    }
+
+    fn on_sample_count_changed(&mut self, ctx: &RenderContext) {
+        self.set_sample_count(ctx.device(), ctx.sample_count());
+    }
+
+    fn on_resize(&mut self, _ctx: &RenderContext, _width: u32, _height: u32) {
+        // Lines are drawn in world space and do not depend on the viewport size
+    }
+
+    fn prepare(&mut self, ctx: &RenderContext, _scene: &Scene) {
+        self.upload(ctx.device());
+    }
+
+    fn render<'a>(&'a self, pass: &mut wgpu::RenderPass<'a>, _scene: &Scene) {
+        self.draw(pass);
+    }
+}
+
+/// Instance layout matching `marker.wgsl`: position+radius, then color.
+fn marker_instance_layout() -> wgpu::VertexBufferLayout<'static> {
+    wgpu::VertexBufferLayout {
+        array_stride: std::mem::size_of::<MarkerInstance>() as u64,
+        step_mode: wgpu::VertexStepMode::Instance,
+        attributes: &[
+            wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 1,
+                format: wgpu::VertexFormat::Float32x4,
+            },
+            wgpu::VertexAttribute {
+                offset: 16,
+                shader_location: 2,
+                format: wgpu::VertexFormat::Float32x4,
+            },
+        ],
+    }
+}
+
+/// Line strip vertices through `points`.
+fn line_vertices(points: &[Vec3], color: [f32; 4]) -> Vec<TrajectoryVertex> {
+    points
+        .iter()
+        .map(|point| TrajectoryVertex {
+            position: point.to_array(),
+            color,
+        })
+        .collect()
+}
+
+/// Sphere instances at each point, if spheres are enabled.
+fn point_instances(points: &[Vec3], radius: Option<f32>, color: [f32; 4]) -> Vec<MarkerInstance> {
+    let Some(radius) = radius.filter(|r| *r > 0.0) else {
+        return Vec::new();
+    };
+    points
+        .iter()
+        .map(|point| MarkerInstance::new(*point, radius, color))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_vertices_follow_points() {
+        let points = [Vec3::ZERO, Vec3::X, Vec3::new(1.0, 1.0, 0.5)];
+        let vertices = line_vertices(&points, [1.0, 0.0, 0.0, 1.0]);
+
+        assert_eq!(vertices.len(), 3);
+        assert_eq!(vertices[2].position, [1.0, 1.0, 0.5]);
+        assert!(vertices.iter().all(|v| v.color == [1.0, 0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_point_spheres_are_optional() {
+        let points = [Vec3::ZERO, Vec3::Y];
+        let color = TrajectoryRenderer::DEFAULT_COLOR;
+
+        assert!(point_instances(&points, None, color).is_empty());
+        assert!(point_instances(&points, Some(0.0), color).is_empty());
+
+        let spheres = point_instances(&points, Some(0.01), color);
+        assert_eq!(spheres.len(), 2);
+        assert_eq!(spheres[1].position, [0.0, 1.0, 0.0]);
+        assert_eq!(spheres[1].radius, 0.01);
+    }
+
+    #[test]
+    fn test_set_points_marks_dirty() {
+        let mut renderer = TrajectoryRenderer::new();
+        assert!(!renderer.dirty);
+
+        renderer.set_points(vec![Vec3::ZERO, Vec3::Z]);
+        assert!(renderer.dirty);
+        assert_eq!(renderer.points().len(), 2);
+    }
+}
@@ -38,6 +38,12 @@ pub trait SubRenderer: Send + Sync {
     /// Called when the viewport is resized.
     fn on_resize(&mut self, ctx: &RenderContext, width: u32, height: u32);
 
+    /// Called when the MSAA sample count of the viewport changes.
+    ///
+    /// Pipelines drawing into the main pass must be recreated with
+    /// [`RenderContext::sample_count`].
+    fn on_sample_count_changed(&mut self, _ctx: &RenderContext) {}
+
     /// Prepare data for rendering.
     ///
     /// Called once per frame before the render pass. Use this to update